@@ -472,11 +472,13 @@ impl<'a, R: ModuleResolver + ?Sized> MoveConverter<'a, R> {
     }
 
     pub fn try_into_events(&self, events: &[ContractEvent]) -> Result<Vec<Event>> {
+        let values = self.inner.view_values(
+            events
+                .iter()
+                .map(|event| (event.type_tag(), event.event_data())),
+        )?;
         let mut ret = vec![];
-        for event in events {
-            let data = self
-                .inner
-                .view_value(event.type_tag(), event.event_data())?;
+        for (event, data) in events.iter().zip(values) {
             ret.push((event, MoveValue::try_from(data)?.json()?).into());
         }
         Ok(ret)
@@ -486,11 +488,13 @@ impl<'a, R: ModuleResolver + ?Sized> MoveConverter<'a, R> {
         &self,
         events: &[EventWithVersion],
     ) -> Result<Vec<VersionedEvent>> {
+        let values = self.inner.view_values(
+            events
+                .iter()
+                .map(|event| (event.event.type_tag(), event.event.event_data())),
+        )?;
         let mut ret = vec![];
-        for event in events {
-            let data = self
-                .inner
-                .view_value(event.event.type_tag(), event.event.event_data())?;
+        for (event, data) in events.iter().zip(values) {
             ret.push((event, MoveValue::try_from(data)?.json()?).into());
         }
         Ok(ret)
@@ -157,6 +157,9 @@ crate::gas_schedule::macros::define_gas_parameters!(
         [algebra_ark_h2c_bls12381g2_xmd_sha256_sswu_per_msg_byte: InternalGasPerByte, { 8.. => "algebra.ark_h2c_bls12381g2_xmd_sha256_sswu_per_msg_byte" }, 176],
         // BLS12-381 algebra gas parameters end.
 
+        [algebra_destroy: InternalGas, { 13.. => "algebra.destroy" }, 38],
+        [algebra_per_retained_element: InternalGasPerArg, { 13.. => "algebra.per_retained_element" }, 2],
+
         [bls12381_base: InternalGas, "bls12381.base", 551],
 
         [bls12381_per_pubkey_deserialize: InternalGasPerArg, "bls12381.per_pubkey_deserialize", 400684],
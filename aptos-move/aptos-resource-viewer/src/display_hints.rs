@@ -0,0 +1,453 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Display hints render well-known framework types (e.g. coins, strings and fixed point numbers)
+//! in a friendlier form than their raw Move representation, in both `Display` and JSON output.
+
+use aptos_types::{account_address::AccountAddress, utility_coin::APTOS_COIN_TYPE};
+use move_binary_format::file_format::Ability;
+use move_core_types::{
+    ident_str,
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
+use move_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue};
+use serde::{
+    ser::{SerializeMap, SerializeSeq},
+    Serialize, Serializer,
+};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// The number of decimals of the utility coin
+const APTOS_COIN_DECIMALS: u8 = 8;
+
+/// How the values of a struct type are rendered.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisplayHint {
+    /// `0x1::coin::Coin<T>`: a decimal amount, if the decimals of `T` are registered.
+    CoinAmount,
+    /// `0x1::fixed_point32::FixedPoint32`: a decimal number.
+    FixedPoint32,
+    /// `0x1::fixed_point64::FixedPoint64`: a decimal number.
+    FixedPoint64,
+    /// `0x1::object::Object<T>`: the canonical address of the object.
+    ObjectAddress,
+    /// `0x1::option::Option<T>`: the element, if there is one.
+    Option,
+    /// `0x1::string::String`: the UTF-8 string.
+    Utf8String,
+}
+
+/// A registry of the display hints for struct types, and of the decimals of coin types.
+#[derive(Clone, Debug, Default)]
+pub struct DisplayHintRegistry {
+    hints: BTreeMap<(ModuleId, Identifier), DisplayHint>,
+    coin_decimals: BTreeMap<TypeTag, u8>,
+}
+
+impl DisplayHintRegistry {
+    /// Creates an empty registry (i.e. all values are rendered as is).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with hints for the common framework types and the utility coin.
+    pub fn with_framework_hints() -> Self {
+        let framework_hints: [(&IdentStr, &IdentStr, DisplayHint); 6] = [
+            (
+                ident_str!("coin"),
+                ident_str!("Coin"),
+                DisplayHint::CoinAmount,
+            ),
+            (
+                ident_str!("fixed_point32"),
+                ident_str!("FixedPoint32"),
+                DisplayHint::FixedPoint32,
+            ),
+            (
+                ident_str!("fixed_point64"),
+                ident_str!("FixedPoint64"),
+                DisplayHint::FixedPoint64,
+            ),
+            (
+                ident_str!("object"),
+                ident_str!("Object"),
+                DisplayHint::ObjectAddress,
+            ),
+            (
+                ident_str!("option"),
+                ident_str!("Option"),
+                DisplayHint::Option,
+            ),
+            (
+                ident_str!("string"),
+                ident_str!("String"),
+                DisplayHint::Utf8String,
+            ),
+        ];
+
+        let mut registry = Self::new();
+        for (module, name, hint) in framework_hints {
+            registry.register(
+                ModuleId::new(AccountAddress::ONE, module.to_owned()),
+                name.to_owned(),
+                hint,
+            );
+        }
+        registry.register_coin_decimals(APTOS_COIN_TYPE.clone(), APTOS_COIN_DECIMALS);
+        registry
+    }
+
+    /// Registers the display hint for the given struct type (overriding any existing hint).
+    pub fn register(&mut self, module: ModuleId, name: Identifier, hint: DisplayHint) {
+        self.hints.insert((module, name), hint);
+    }
+
+    /// Registers the number of decimals of the given coin type, used to render coin amounts.
+    pub fn register_coin_decimals(&mut self, coin_type: TypeTag, decimals: u8) {
+        self.coin_decimals.insert(coin_type, decimals);
+    }
+
+    /// Returns the display hint registered for the given struct type (if any).
+    pub fn get_hint(&self, struct_tag: &StructTag) -> Option<DisplayHint> {
+        self.hints
+            .get(&(struct_tag.module_id(), struct_tag.name.clone()))
+            .copied()
+    }
+
+    /// Returns a view of the value that renders it using the registered hints.
+    pub fn view_value<'a>(&'a self, value: &'a AnnotatedMoveValue) -> HintedMoveValue<'a> {
+        HintedMoveValue {
+            registry: self,
+            value,
+        }
+    }
+
+    /// Returns a view of the struct that renders it using the registered hints.
+    pub fn view_struct<'a>(&'a self, value: &'a AnnotatedMoveStruct) -> HintedMoveStruct<'a> {
+        HintedMoveStruct {
+            registry: self,
+            value,
+        }
+    }
+
+    /// Renders the struct using its display hint. Returns None if the struct has no hint, or if
+    /// its fields don't have the expected shape (in which case it's rendered as is).
+    fn render_struct<'a>(&self, value: &'a AnnotatedMoveStruct) -> Option<RenderedValue<'a>> {
+        let hint = self.get_hint(&value.type_)?;
+        Some(match (hint, value.value.as_slice()) {
+            (DisplayHint::CoinAmount, [(_, AnnotatedMoveValue::U64(amount))]) => {
+                let decimals = self.coin_decimals.get(value.type_.type_params.first()?)?;
+                RenderedValue::Decimal(format_decimal_amount(*amount, *decimals))
+            },
+            (DisplayHint::FixedPoint32, [(_, AnnotatedMoveValue::U64(raw_value))]) => {
+                RenderedValue::Decimal(format_fixed_point(*raw_value as u128, 32))
+            },
+            (DisplayHint::FixedPoint64, [(_, AnnotatedMoveValue::U128(raw_value))]) => {
+                RenderedValue::Decimal(format_fixed_point(*raw_value, 64))
+            },
+            (DisplayHint::ObjectAddress, [(_, AnnotatedMoveValue::Address(address))]) => {
+                RenderedValue::Address(*address)
+            },
+            (DisplayHint::Option, [(_, AnnotatedMoveValue::Vector(_, elements))]) => {
+                match elements.as_slice() {
+                    [] => RenderedValue::None,
+                    [element] => RenderedValue::Some(Cow::Borrowed(element)),
+                    _ => return None,
+                }
+            },
+            (DisplayHint::Option, [(_, AnnotatedMoveValue::Bytes(bytes))]) => {
+                match bytes.as_slice() {
+                    [] => RenderedValue::None,
+                    [byte] => RenderedValue::Some(Cow::Owned(AnnotatedMoveValue::U8(*byte))),
+                    _ => return None,
+                }
+            },
+            (DisplayHint::Utf8String, [(_, AnnotatedMoveValue::Bytes(bytes))]) => {
+                RenderedValue::String(std::str::from_utf8(bytes).ok()?)
+            },
+            _ => return None,
+        })
+    }
+
+    fn fmt_value(
+        &self,
+        f: &mut Formatter,
+        value: &AnnotatedMoveValue,
+        indent: u64,
+    ) -> std::fmt::Result {
+        match value {
+            AnnotatedMoveValue::Vector(_, elements) => {
+                writeln!(f, "[")?;
+                for element in elements {
+                    write_indent(f, indent + 4)?;
+                    self.fmt_value(f, element, indent + 4)?;
+                    writeln!(f, ",")?;
+                }
+                write_indent(f, indent)?;
+                write!(f, "]")
+            },
+            AnnotatedMoveValue::Struct(value) => self.fmt_struct(f, value, indent),
+            _ => write!(f, "{}", value),
+        }
+    }
+
+    fn fmt_struct(
+        &self,
+        f: &mut Formatter,
+        value: &AnnotatedMoveStruct,
+        indent: u64,
+    ) -> std::fmt::Result {
+        match self.render_struct(value) {
+            Some(RenderedValue::Address(address)) => write!(f, "{}", address.to_standard_string()),
+            Some(RenderedValue::Decimal(decimal)) => write!(f, "{}", decimal),
+            Some(RenderedValue::None) => write!(f, "none"),
+            Some(RenderedValue::Some(element)) => {
+                write!(f, "some(")?;
+                self.fmt_value(f, &element, indent)?;
+                write!(f, ")")
+            },
+            Some(RenderedValue::String(string)) => write!(f, "{:?}", string),
+            None => {
+                for ability in value.abilities {
+                    match ability {
+                        Ability::Copy => write!(f, "copy ")?,
+                        Ability::Drop => write!(f, "drop ")?,
+                        Ability::Store => write!(f, "store ")?,
+                        Ability::Key => write!(f, "key ")?,
+                    }
+                }
+                writeln!(f, "{} {{", value.type_)?;
+                for (field_name, field_value) in &value.value {
+                    write_indent(f, indent + 4)?;
+                    write!(f, "{}: ", field_name)?;
+                    self.fmt_value(f, field_value, indent + 4)?;
+                    writeln!(f)?;
+                }
+                write_indent(f, indent)?;
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+/// A struct value rendered using its display hint.
+enum RenderedValue<'a> {
+    Address(AccountAddress),
+    Decimal(String),
+    None,
+    Some(Cow<'a, AnnotatedMoveValue>),
+    String(&'a str),
+}
+
+/// A Move value that is rendered using the hints of a `DisplayHintRegistry`.
+pub struct HintedMoveValue<'a> {
+    registry: &'a DisplayHintRegistry,
+    value: &'a AnnotatedMoveValue,
+}
+
+/// A Move struct that is rendered using the hints of a `DisplayHintRegistry`.
+pub struct HintedMoveStruct<'a> {
+    registry: &'a DisplayHintRegistry,
+    value: &'a AnnotatedMoveStruct,
+}
+
+impl Display for HintedMoveValue<'_> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        self.registry.fmt_value(f, self.value, 0)
+    }
+}
+
+impl Display for HintedMoveStruct<'_> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        self.registry.fmt_struct(f, self.value, 0)
+    }
+}
+
+impl Serialize for HintedMoveValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.value {
+            AnnotatedMoveValue::Vector(_, elements) => {
+                let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                for element in elements {
+                    seq.serialize_element(&self.registry.view_value(element))?;
+                }
+                seq.end()
+            },
+            AnnotatedMoveValue::Struct(value) => {
+                self.registry.view_struct(value).serialize(serializer)
+            },
+            _ => self.value.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for HintedMoveStruct<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.registry.render_struct(self.value) {
+            Some(RenderedValue::Address(address)) => {
+                serializer.serialize_str(&address.to_standard_string())
+            },
+            Some(RenderedValue::Decimal(decimal)) => serializer.serialize_str(&decimal),
+            Some(RenderedValue::None) => serializer.serialize_none(),
+            Some(RenderedValue::Some(element)) => {
+                serializer.serialize_some(&self.registry.view_value(&element))
+            },
+            Some(RenderedValue::String(string)) => serializer.serialize_str(string),
+            None => {
+                let mut map = serializer.serialize_map(Some(self.value.value.len()))?;
+                for (field_name, field_value) in &self.value.value {
+                    map.serialize_entry(field_name, &self.registry.view_value(field_value))?;
+                }
+                map.end()
+            },
+        }
+    }
+}
+
+fn write_indent(f: &mut Formatter, indent: u64) -> std::fmt::Result {
+    for _ in 0..indent {
+        write!(f, " ")?;
+    }
+    Ok(())
+}
+
+/// Formats `amount / 10^decimals` as a decimal number (keeping all fractional digits).
+fn format_decimal_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", amount, width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    format!("{}.{}", integer, fraction)
+}
+
+/// Formats `raw_value / 2^fractional_bits` exactly as a decimal number.
+fn format_fixed_point(raw_value: u128, fractional_bits: u32) -> String {
+    let mask = (1u128 << fractional_bits) - 1;
+    let integer = raw_value >> fractional_bits;
+    let mut fraction = raw_value & mask;
+    if fraction == 0 {
+        return integer.to_string();
+    }
+
+    // Each step shifts out one decimal digit (the fraction is < 2^64, so this can't overflow)
+    let mut digits = String::new();
+    while fraction != 0 {
+        fraction *= 10;
+        digits.push(char::from(b'0' + (fraction >> fractional_bits) as u8));
+        fraction &= mask;
+    }
+    format!("{}.{}", integer, digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::AbilitySet;
+
+    fn framework_struct(
+        module: &str,
+        name: &str,
+        type_params: Vec<TypeTag>,
+        fields: Vec<(&str, AnnotatedMoveValue)>,
+    ) -> AnnotatedMoveValue {
+        AnnotatedMoveValue::Struct(AnnotatedMoveStruct {
+            abilities: AbilitySet::EMPTY,
+            type_: StructTag {
+                address: AccountAddress::ONE,
+                module: Identifier::new(module).unwrap(),
+                name: Identifier::new(name).unwrap(),
+                type_params,
+            },
+            value: fields
+                .into_iter()
+                .map(|(name, value)| (Identifier::new(name).unwrap(), value))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn test_number_formatting() {
+        assert_eq!(format_decimal_amount(150_000_000, 8), "1.50000000");
+        assert_eq!(format_decimal_amount(42, 8), "0.00000042");
+        assert_eq!(format_decimal_amount(42, 0), "42");
+        assert_eq!(format_fixed_point(3 << 31, 32), "1.5");
+        assert_eq!(format_fixed_point(5 << 32, 32), "5");
+        assert_eq!(
+            format_fixed_point(1, 64),
+            "0.0000000000000000000542101086242752217003726400434970855712890625"
+        );
+    }
+
+    #[test]
+    fn test_framework_hints() {
+        let registry = DisplayHintRegistry::with_framework_hints();
+        let object_address = AccountAddress::from_hex_literal("0xabc").unwrap();
+        let coin = framework_struct(
+            "coin",
+            "Coin",
+            vec![APTOS_COIN_TYPE.clone()],
+            vec![("value", AnnotatedMoveValue::U64(250_000_000))],
+        );
+        let name = framework_struct(
+            "string",
+            "String",
+            vec![],
+            vec![("bytes", AnnotatedMoveValue::Bytes(b"cedra".to_vec()))],
+        );
+        let owner = framework_struct(
+            "object",
+            "Object",
+            vec![],
+            vec![("inner", AnnotatedMoveValue::Address(object_address))],
+        );
+        let rate = framework_struct(
+            "fixed_point32",
+            "FixedPoint32",
+            vec![],
+            vec![("value", AnnotatedMoveValue::U64(1 << 30))],
+        );
+        let maybe = framework_struct(
+            "option",
+            "Option",
+            vec![TypeTag::U64],
+            vec![("vec", AnnotatedMoveValue::Vector(TypeTag::U64, vec![]))],
+        );
+        let fields = vec![
+            ("coin", coin),
+            ("name", name),
+            ("owner", owner),
+            ("rate", rate),
+            ("maybe", maybe),
+        ];
+        let value = framework_struct("test", "Resource", vec![], fields);
+
+        assert_eq!(
+            serde_json::to_value(registry.view_value(&value)).unwrap(),
+            serde_json::json!({
+                "coin": "2.50000000",
+                "name": "cedra",
+                "owner": object_address.to_standard_string(),
+                "rate": "0.25",
+                "maybe": null,
+            })
+        );
+        assert_eq!(
+            registry.view_value(&value).to_string(),
+            format!(
+                "0x1::test::Resource {{\n    coin: 2.50000000\n    name: \"cedra\"\n    owner: {}\n    rate: 0.25\n    maybe: none\n}}",
+                object_address.to_standard_string()
+            )
+        );
+
+        // Without hints, values are rendered as is
+        let registry = DisplayHintRegistry::new();
+        assert_eq!(registry.view_value(&value).to_string(), value.to_string());
+    }
+}
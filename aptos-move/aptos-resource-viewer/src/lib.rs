@@ -4,10 +4,15 @@
 
 use anyhow::{bail, Result};
 use aptos_types::{
-    access_path::AccessPath, account_address::AccountAddress, account_state::AccountState,
+    access_path::{AccessPath, Path},
+    account_address::AccountAddress,
+    account_state::AccountState,
     contract_event::ContractEvent,
+    event::EventKey,
+};
+use move_core_types::{
+    identifier::Identifier, language_storage::StructTag, resolver::ModuleResolver,
 };
-use move_core_types::{language_storage::StructTag, resolver::ModuleResolver};
 use move_resource_viewer::MoveValueAnnotator;
 pub use move_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue};
 use std::{
@@ -15,12 +20,36 @@ use std::{
     fmt::{Display, Formatter},
 };
 
+pub mod display_hints;
+
 /// A wrapper around `MoveValueAnnotator` that adds a few aptos-specific functionalities.
 pub struct AptosValueAnnotator<'a, T>(MoveValueAnnotator<'a, T>);
 
 #[derive(Debug)]
 pub struct AnnotatedAccountStateBlob(BTreeMap<StructTag, AnnotatedMoveStruct>);
 
+/// The resources that differ between two states of an account, with annotated values.
+#[derive(Debug, Default)]
+pub struct AccountStateDiff {
+    pub added: BTreeMap<StructTag, AnnotatedMoveStruct>,
+    pub removed: BTreeMap<StructTag, AnnotatedMoveStruct>,
+    pub changed: BTreeMap<StructTag, ChangedResource>,
+}
+
+#[derive(Debug)]
+pub struct ChangedResource {
+    pub before: AnnotatedMoveStruct,
+    pub after: AnnotatedMoveStruct,
+}
+
+/// The location of an event handle within the resources of an account, e.g.
+/// `0x1::account::Account/coin_register_events`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventHandlePath {
+    pub resource: StructTag,
+    pub field_path: Vec<Identifier>,
+}
+
 impl<'a, T: ModuleResolver> AptosValueAnnotator<'a, T> {
     pub fn new(storage: &'a T) -> Self {
         Self(MoveValueAnnotator::new(storage))
@@ -30,6 +59,21 @@ impl<'a, T: ModuleResolver> AptosValueAnnotator<'a, T> {
         self.0.view_resource(tag, blob)
     }
 
+    /// Annotates every member of a resource group. The group is stored as a single state value,
+    /// the BCS-encoded map from the struct tag of each member to the member's BCS-encoded value.
+    pub fn view_resource_group(
+        &self,
+        blob: &[u8],
+    ) -> Result<BTreeMap<StructTag, AnnotatedMoveStruct>> {
+        decode_resource_group(blob)?
+            .into_iter()
+            .map(|(tag, member_blob)| {
+                let member = self.view_resource(&tag, &member_blob)?;
+                Ok((tag, member))
+            })
+            .collect()
+    }
+
     pub fn view_access_path(
         &self,
         access_path: AccessPath,
@@ -45,6 +89,46 @@ impl<'a, T: ModuleResolver> AptosValueAnnotator<'a, T> {
         self.0.view_value(event.type_tag(), event.event_data())
     }
 
+    /// Annotates a batch of events. Modules and types are resolved once for the whole batch,
+    /// which is much cheaper than calling `view_contract_event` for each event.
+    pub fn view_contract_events(
+        &self,
+        events: &[ContractEvent],
+    ) -> Result<Vec<AnnotatedMoveValue>> {
+        self.0.view_values(
+            events
+                .iter()
+                .map(|event| (event.type_tag(), event.event_data())),
+        )
+    }
+
+    /// Returns the paths of all event handles stored in the resources of the account, keyed by
+    /// the event key (i.e. creation number and address) of the handle. Handles stored in members
+    /// of a resource group are reported under the tag of the member.
+    pub fn view_event_handles(
+        &self,
+        state: &AccountState,
+    ) -> Result<BTreeMap<EventKey, EventHandlePath>> {
+        let mut output = BTreeMap::new();
+        for (tag, blob) in resource_blobs(state)? {
+            let resource = self.view_resource(&tag, &blob)?;
+            let mut field_path = vec![];
+            collect_event_handles(&resource, &tag, &mut field_path, &mut output);
+        }
+        Ok(output)
+    }
+
+    /// Resolves the event handle that emitted the given (V1) event to a human-readable path,
+    /// if the handle is stored in the resources of the given account.
+    pub fn view_event_handle_path(
+        &self,
+        state: &AccountState,
+        event: &ContractEvent,
+    ) -> Result<Option<EventHandlePath>> {
+        let key = event.v1()?.key();
+        Ok(self.view_event_handles(state)?.remove(key))
+    }
+
     pub fn view_account_state(&self, state: &AccountState) -> Result<AnnotatedAccountStateBlob> {
         let mut output = BTreeMap::new();
         for (k, v) in state.iter() {
@@ -60,6 +144,123 @@ impl<'a, T: ModuleResolver> AptosValueAnnotator<'a, T> {
         }
         Ok(AnnotatedAccountStateBlob(output))
     }
+
+    /// Returns the resources added, removed and changed between the old and the new state of
+    /// an account (e.g. before and after a transaction). Members of resource groups are diffed
+    /// individually, and modules are ignored. Only the resources that differ are annotated.
+    pub fn diff_account_state(
+        &self,
+        old: &AccountState,
+        new: &AccountState,
+    ) -> Result<AccountStateDiff> {
+        let mut old_resources = resource_blobs(old)?;
+        let mut diff = AccountStateDiff::default();
+        for (tag, new_blob) in resource_blobs(new)? {
+            match old_resources.remove(&tag) {
+                None => {
+                    let value = self.view_resource(&tag, &new_blob)?;
+                    diff.added.insert(tag, value);
+                },
+                Some(old_blob) if old_blob != new_blob => {
+                    let before = self.view_resource(&tag, &old_blob)?;
+                    let after = self.view_resource(&tag, &new_blob)?;
+                    diff.changed.insert(tag, ChangedResource { before, after });
+                },
+                Some(_) => (),
+            }
+        }
+        for (tag, old_blob) in old_resources {
+            let value = self.view_resource(&tag, &old_blob)?;
+            diff.removed.insert(tag, value);
+        }
+        Ok(diff)
+    }
+}
+
+/// Returns the BCS-encoded resources of the account (with resource groups flattened).
+fn resource_blobs(state: &AccountState) -> Result<BTreeMap<StructTag, Vec<u8>>> {
+    let mut output = BTreeMap::new();
+    for (k, v) in state.iter() {
+        match Path::try_from(k)? {
+            Path::Resource(tag) => {
+                output.insert(tag, v.clone());
+            },
+            Path::ResourceGroup(_) => {
+                output.extend(decode_resource_group(v)?);
+            },
+            Path::Code(_) => (),
+        }
+    }
+    Ok(output)
+}
+
+/// Decodes a resource group into the BCS-encoded values of its members.
+fn decode_resource_group(blob: &[u8]) -> Result<BTreeMap<StructTag, Vec<u8>>> {
+    Ok(bcs::from_bytes(blob)?)
+}
+
+fn collect_event_handles(
+    value: &AnnotatedMoveStruct,
+    resource: &StructTag,
+    field_path: &mut Vec<Identifier>,
+    output: &mut BTreeMap<EventKey, EventHandlePath>,
+) {
+    if let Some(key) = event_key_of_handle(value) {
+        output.insert(key, EventHandlePath {
+            resource: resource.clone(),
+            field_path: field_path.clone(),
+        });
+        return;
+    }
+    for (name, field) in &value.value {
+        if let AnnotatedMoveValue::Struct(inner) = field {
+            field_path.push(name.clone());
+            collect_event_handles(inner, resource, field_path, output);
+            field_path.pop();
+        }
+    }
+}
+
+/// Extracts the event key out of a `0x1::event::EventHandle<T>`.
+fn event_key_of_handle(value: &AnnotatedMoveStruct) -> Option<EventKey> {
+    fn field<'a>(value: &'a AnnotatedMoveStruct, name: &str) -> Option<&'a AnnotatedMoveValue> {
+        value
+            .value
+            .iter()
+            .find(|(field_name, _)| field_name.as_str() == name)
+            .map(|(_, field_value)| field_value)
+    }
+
+    let tag = &value.type_;
+    if tag.address != AccountAddress::ONE
+        || tag.module.as_str() != "event"
+        || tag.name.as_str() != "EventHandle"
+    {
+        return None;
+    }
+    let id = match field(value, "guid")? {
+        AnnotatedMoveValue::Struct(guid) => match field(guid, "id")? {
+            AnnotatedMoveValue::Struct(id) => id,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match (field(id, "creation_num")?, field(id, "addr")?) {
+        (AnnotatedMoveValue::U64(creation_num), AnnotatedMoveValue::Address(addr)) => {
+            Some(EventKey::new(*creation_num, *addr))
+        },
+        _ => None,
+    }
+}
+
+impl Display for EventHandlePath {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.resource)?;
+        for field in &self.field_path {
+            write!(f, "/{}", field)?;
+        }
+        Ok(())
+    }
 }
 
 impl Display for AnnotatedAccountStateBlob {
@@ -72,3 +273,298 @@ impl Display for AnnotatedAccountStateBlob {
         writeln!(f, "}}")
     }
 }
+
+impl Display for AccountStateDiff {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for (tag, value) in &self.added {
+            writeln!(f, "+ {}: {}", tag, value)?;
+        }
+        for (tag, value) in &self.removed {
+            writeln!(f, "- {}: {}", tag, value)?;
+        }
+        for (tag, resource) in &self.changed {
+            writeln!(f, "~ {}:", tag)?;
+            writeln!(f, "  before: {}", resource.before)?;
+            writeln!(f, "  after: {}", resource.after)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        account_config::{AccountResource, CoinStoreResource, ObjectGroupResource},
+        event::EventHandle,
+    };
+    use bytes::Bytes;
+    use move_binary_format::{access::ModuleAccess, file_format::AbilitySet};
+    use move_core_types::{
+        language_storage::ModuleId, metadata::Metadata, move_resource::MoveStructType,
+    };
+
+    /// Resolves the modules of the head framework release.
+    struct FrameworkModules(BTreeMap<ModuleId, Bytes>);
+
+    impl FrameworkModules {
+        fn new() -> Self {
+            let bundle = aptos_cached_packages::head_release_bundle();
+            Self(
+                bundle
+                    .code_and_compiled_modules()
+                    .into_iter()
+                    .map(|(code, module)| (module.self_id(), Bytes::copy_from_slice(code)))
+                    .collect(),
+            )
+        }
+    }
+
+    impl ModuleResolver for FrameworkModules {
+        type Error = anyhow::Error;
+
+        fn get_module_metadata(&self, _module_id: &ModuleId) -> Vec<Metadata> {
+            vec![]
+        }
+
+        fn get_module(&self, id: &ModuleId) -> Result<Option<Bytes>> {
+            Ok(self.0.get(id).cloned())
+        }
+    }
+
+    fn account_resource(sequence_number: u64) -> Vec<u8> {
+        bcs::to_bytes(&AccountResource::new(
+            sequence_number,
+            vec![0; 32],
+            EventHandle::random(0),
+            EventHandle::random(0),
+        ))
+        .unwrap()
+    }
+
+    fn coin_store_resource(coin: u64) -> Vec<u8> {
+        bcs::to_bytes(&CoinStoreResource::new(
+            coin,
+            false,
+            EventHandle::random(0),
+            EventHandle::random(0),
+        ))
+        .unwrap()
+    }
+
+    fn resource_group(members: Vec<(StructTag, Vec<u8>)>) -> Vec<u8> {
+        bcs::to_bytes(&members.into_iter().collect::<BTreeMap<_, _>>()).unwrap()
+    }
+
+    fn struct_tag(address: AccountAddress, module: &str, name: &str) -> StructTag {
+        StructTag {
+            address,
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params: vec![],
+        }
+    }
+
+    fn annotated_struct(
+        tag: StructTag,
+        fields: Vec<(&str, AnnotatedMoveValue)>,
+    ) -> AnnotatedMoveStruct {
+        AnnotatedMoveStruct {
+            abilities: AbilitySet::EMPTY,
+            type_: tag,
+            value: fields
+                .into_iter()
+                .map(|(name, value)| (Identifier::new(name).unwrap(), value))
+                .collect(),
+        }
+    }
+
+    fn event_handle(creation_num: u64, addr: AccountAddress) -> AnnotatedMoveValue {
+        let id = annotated_struct(struct_tag(AccountAddress::ONE, "guid", "ID"), vec![
+            ("creation_num", AnnotatedMoveValue::U64(creation_num)),
+            ("addr", AnnotatedMoveValue::Address(addr)),
+        ]);
+        let guid = annotated_struct(struct_tag(AccountAddress::ONE, "guid", "GUID"), vec![(
+            "id",
+            AnnotatedMoveValue::Struct(id),
+        )]);
+        AnnotatedMoveValue::Struct(annotated_struct(
+            struct_tag(AccountAddress::ONE, "event", "EventHandle"),
+            vec![
+                ("counter", AnnotatedMoveValue::U64(0)),
+                ("guid", AnnotatedMoveValue::Struct(guid)),
+            ],
+        ))
+    }
+
+    #[test]
+    fn test_resource_blobs_flattens_resource_groups() {
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let resource = struct_tag(address, "test", "Resource");
+        let group = struct_tag(address, "test", "Group");
+        let member_a = struct_tag(address, "test", "MemberA");
+        let member_b = struct_tag(address, "test", "MemberB");
+        let members: BTreeMap<StructTag, Vec<u8>> = [
+            (member_a.clone(), vec![1]),
+            (member_b.clone(), vec![2, 3]),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut state = AccountState::new(address, BTreeMap::new());
+        state.insert(AccessPath::resource_path_vec(resource.clone()).unwrap(), vec![
+            4,
+        ]);
+        state.insert(
+            AccessPath::resource_group_path_vec(group.clone()),
+            bcs::to_bytes(&members).unwrap(),
+        );
+
+        let blobs = resource_blobs(&state).unwrap();
+        assert_eq!(blobs.len(), 3);
+        assert_eq!(blobs.get(&resource), Some(&vec![4]));
+        assert_eq!(blobs.get(&member_a), Some(&vec![1]));
+        assert_eq!(blobs.get(&member_b), Some(&vec![2, 3]));
+        // The group itself is never passed on as a resource
+        assert!(!blobs.contains_key(&group));
+
+        // A group that is not a map of members is an error rather than a bogus resource
+        state.insert(AccessPath::resource_group_path_vec(group), vec![0xff]);
+        assert!(resource_blobs(&state).is_err());
+    }
+
+    #[test]
+    fn test_diff_account_state() {
+        let modules = FrameworkModules::new();
+        let annotator = AptosValueAnnotator::new(&modules);
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let account_tag = AccountResource::struct_tag();
+        let coin_store_tag = CoinStoreResource::struct_tag();
+        let group_path = AccessPath::resource_group_path_vec(ObjectGroupResource::struct_tag());
+
+        let mut old = AccountState::new(address, BTreeMap::new());
+        old.insert(
+            AccessPath::resource_path_vec(account_tag.clone()).unwrap(),
+            account_resource(1),
+        );
+        old.insert(
+            AccessPath::resource_path_vec(coin_store_tag.clone()).unwrap(),
+            coin_store_resource(100),
+        );
+
+        // Identical states don't differ
+        let diff = annotator.diff_account_state(&old, &old).unwrap();
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty());
+        assert_eq!(diff.to_string(), "");
+
+        // The account resource changes, the coin store moves into a resource group (so it is
+        // diffed as a group member), and a module is published (which is ignored)
+        let mut new = AccountState::new(address, BTreeMap::new());
+        new.insert(
+            AccessPath::resource_path_vec(account_tag.clone()).unwrap(),
+            account_resource(2),
+        );
+        new.insert(
+            group_path,
+            resource_group(vec![(coin_store_tag.clone(), coin_store_resource(100))]),
+        );
+        new.insert(
+            AccessPath::code_path_vec(ModuleId::new(address, Identifier::new("test").unwrap())),
+            vec![0xff],
+        );
+        let diff = annotator.diff_account_state(&old, &new).unwrap();
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.keys().collect::<Vec<_>>(), vec![&account_tag]);
+        let changed = &diff.changed[&account_tag];
+        assert_eq!(changed.before.type_, account_tag);
+        assert!(changed.before.to_string().contains("sequence_number: 1"));
+        assert!(changed.after.to_string().contains("sequence_number: 2"));
+
+        // Resources only in one of the states are added or removed
+        let mut removed = old.clone();
+        removed.remove(&AccessPath::resource_path_vec(coin_store_tag.clone()).unwrap());
+        let diff = annotator.diff_account_state(&old, &removed).unwrap();
+        assert!(diff.added.is_empty() && diff.changed.is_empty());
+        assert_eq!(
+            diff.removed.keys().collect::<Vec<_>>(),
+            vec![&coin_store_tag]
+        );
+        let output = diff.to_string();
+        assert!(output.starts_with(&format!("- {}: ", coin_store_tag)));
+
+        let diff = annotator.diff_account_state(&removed, &old).unwrap();
+        assert!(diff.removed.is_empty() && diff.changed.is_empty());
+        assert_eq!(diff.added.keys().collect::<Vec<_>>(), vec![&coin_store_tag]);
+        let output = diff.to_string();
+        assert!(output.starts_with(&format!("+ {}: ", coin_store_tag)));
+    }
+
+    #[test]
+    fn test_view_resource_group() {
+        let modules = FrameworkModules::new();
+        let annotator = AptosValueAnnotator::new(&modules);
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let account_tag = AccountResource::struct_tag();
+        let coin_store_tag = CoinStoreResource::struct_tag();
+        let group_path = AccessPath::resource_group_path_vec(ObjectGroupResource::struct_tag());
+
+        let mut state = AccountState::new(address, BTreeMap::new());
+        state.insert(
+            group_path.clone(),
+            resource_group(vec![
+                (account_tag.clone(), account_resource(5)),
+                (coin_store_tag.clone(), coin_store_resource(100)),
+            ]),
+        );
+
+        // Every member of the group is annotated under its own tag
+        let group = annotator
+            .view_resource_group(state.get(&group_path).unwrap())
+            .unwrap();
+        assert_eq!(group.keys().collect::<Vec<_>>(), vec![
+            &account_tag,
+            &coin_store_tag
+        ]);
+        assert_eq!(group[&account_tag].type_, account_tag);
+        assert!(group[&account_tag]
+            .to_string()
+            .contains("sequence_number: 5"));
+        assert_eq!(group[&coin_store_tag].type_, coin_store_tag);
+
+        // The members match the flattened resources of the account
+        let blobs = resource_blobs(&state).unwrap();
+        assert_eq!(
+            blobs.keys().collect::<Vec<_>>(),
+            group.keys().collect::<Vec<_>>()
+        );
+
+        // A blob that is not a map of members is an error
+        assert!(annotator.view_resource_group(&[0xff]).is_err());
+    }
+
+    #[test]
+    fn test_collect_event_handles() {
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let tag = struct_tag(address, "test", "Member");
+        let nested = annotated_struct(struct_tag(address, "test", "Events"), vec![
+            ("deposit_events", event_handle(3, address)),
+            ("value", AnnotatedMoveValue::U64(7)),
+        ]);
+        let resource = annotated_struct(tag.clone(), vec![
+            ("withdraw_events", event_handle(2, address)),
+            ("events", AnnotatedMoveValue::Struct(nested)),
+        ]);
+
+        let mut output = BTreeMap::new();
+        collect_event_handles(&resource, &tag, &mut vec![], &mut output);
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[&EventKey::new(2, address)].to_string(),
+            format!("{}/withdraw_events", tag)
+        );
+        assert_eq!(
+            output[&EventKey::new(3, address)].to_string(),
+            format!("{}/events/deposit_events", tag)
+        );
+    }
+}
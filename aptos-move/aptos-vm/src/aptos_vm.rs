@@ -107,6 +107,7 @@ static NUM_EXECUTION_SHARD: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static PARANOID_TYPE_CHECKS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
+static BLOCK_EXECUTOR_CONTENTION_STATS: OnceCell<bool> = OnceCell::new();
 static TIMED_FEATURE_OVERRIDE: OnceCell<TimedFeatureOverride> = OnceCell::new();
 
 // TODO: Don't expose this in AptosVM, and use only in BlockAptosVM!
@@ -314,6 +315,20 @@ impl AptosVM {
         }
     }
 
+    /// Enables per-key contention stats in parallel execution when invoked the first time.
+    pub fn set_block_executor_contention_stats() {
+        // Only the first call succeeds, due to OnceCell semantics.
+        BLOCK_EXECUTOR_CONTENTION_STATS.set(true).ok();
+    }
+
+    /// Get whether parallel execution should record per-key contention stats
+    pub fn get_block_executor_contention_stats() -> bool {
+        match BLOCK_EXECUTOR_CONTENTION_STATS.get() {
+            Some(value) => *value,
+            None => false,
+        }
+    }
+
     /// Returns the internal gas schedule if it has been loaded, or an error if it hasn't.
     #[cfg(any(test, feature = "testing"))]
     pub fn gas_params(&self) -> Result<&AptosGasParameters, VMStatus> {
@@ -727,6 +742,10 @@ impl AptosVM {
                 },
             };
 
+            if self.gas_feature_version >= 13 {
+                session.clear_algebra_elements();
+            }
+
             self.resolve_pending_code_publish(
                 &mut session,
                 gas_meter,
@@ -992,6 +1011,10 @@ impl AptosVM {
             payload,
         )?;
 
+        if self.gas_feature_version >= 13 {
+            session.clear_algebra_elements();
+        }
+
         // Resolve any pending module publishes in case the multisig transaction is deploying
         // modules.
         self.resolve_pending_code_publish(session, gas_meter, new_published_modules_loaded)?;
@@ -2067,6 +2090,7 @@ impl VMExecutor for AptosVM {
             BlockExecutorConfig {
                 local: BlockExecutorLocalConfig {
                     concurrency_level: Self::get_concurrency_level(),
+                    record_contention_stats: Self::get_block_executor_contention_stats(),
                 },
                 onchain: onchain_config,
             },
@@ -11,6 +11,7 @@ use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
 use aptos_framework::natives::{
     aggregator_natives::{AggregatorChangeSet, AggregatorChangeV1, NativeAggregatorContext},
     code::{NativeCodeContext, PublishRequest},
+    cryptography::algebra::AlgebraContext,
     event::NativeEventContext,
 };
 use aptos_table_natives::{NativeTableContext, TableChangeSet};
@@ -213,6 +214,14 @@ impl<'r, 'l> SessionExt<'r, 'l> {
         ctx.requested_module_bundle.take()
     }
 
+    /// Drops all the elements of algebraic structures created so far. Elements cannot be
+    /// stored, so none of them are reachable once the function that created them returns.
+    pub fn clear_algebra_elements(&mut self) {
+        self.get_native_extensions()
+            .get_mut::<AlgebraContext>()
+            .clear();
+    }
+
     fn populate_v0_resource_group_change_set(
         change_set: &mut BTreeMap<StateKey, MoveStorageOp<BytesWithResourceLayout>>,
         state_key: StateKey,
@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 
 use crate::{
+    aptos_vm::AptosVM,
     block_executor::BlockAptosVM,
     sharded_block_executor::{
         aggr_overridden_state_view::{AggregatorOverriddenStateView, TOTAL_SUPPLY_AGGR_BASE_VAL},
@@ -142,7 +143,10 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                     &signature_verified_transactions,
                     aggr_overridden_state_view.as_ref(),
                     BlockExecutorConfig {
-                        local: BlockExecutorLocalConfig { concurrency_level },
+                        local: BlockExecutorLocalConfig {
+                            concurrency_level,
+                            record_contention_stats: AptosVM::get_block_executor_contention_stats(),
+                        },
                         onchain: onchain_config,
                     },
                     cross_shard_commit_sender,
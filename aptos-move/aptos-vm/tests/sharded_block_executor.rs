@@ -185,6 +185,25 @@ fn test_partitioner_v2_connected_component_sharded_block_executor_with_random_tr
     }
 }
 
+#[test]
+fn test_partitioner_v2_connected_component_sharded_block_executor_with_pruned_dependent_edges() {
+    for merge_discard in [false, true] {
+        let num_shards = 7;
+        let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(4));
+        let sharded_block_executor = ShardedBlockExecutor::new(client);
+        let partitioner = PartitionerV2Config::default()
+            .partition_last_round(merge_discard)
+            .prune_redundant_dependent_edges(true)
+            .pre_partitioner_config(Box::<ConnectedComponentPartitionerConfig>::default())
+            .build();
+        test_utils::sharded_block_executor_with_random_transfers(
+            partitioner,
+            sharded_block_executor,
+            4,
+        )
+    }
+}
+
 mod test_utils {
     use aptos_block_partitioner::BlockPartitioner;
     use aptos_language_e2e_tests::{
@@ -158,6 +158,13 @@ impl<V: TransactionWrite> DataRead<V> {
             },
         }
     }
+
+    /// Deletions are recorded in the multi-versioned data-structure without a value,
+    /// so the read is represented by a sentinel deletion at the given version.
+    pub(crate) fn from_deletion(version: Version) -> Self {
+        let sentinel_deletion = Arc::<V>::new(TransactionWrite::from_state_value(None));
+        DataRead::Versioned(version, sentinel_deletion, None)
+    }
 }
 
 /// Additional state regarding groups that may be provided to the VM during transaction
@@ -555,6 +562,10 @@ impl<T: Transaction> CapturedReads<T> {
                         DataReadComparison::Contains
                     )
                 },
+                Ok(Deleted(version)) => matches!(
+                    DataRead::from_deletion(version).contains(r),
+                    DataReadComparison::Contains
+                ),
                 Ok(Resolved(value)) => matches!(
                     DataRead::Resolved(value).contains(r),
                     DataReadComparison::Contains
@@ -1,12 +1,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_logger::debug;
 use aptos_metrics_core::{
     exponential_buckets, register_histogram, register_histogram_vec, register_int_counter,
     register_int_counter_vec, Histogram, HistogramVec, IntCounter, IntCounterVec,
 };
+use aptos_mvhashmap::contention_stats::KeyContentionStats;
 use aptos_types::fee_statement::FeeStatement;
 use once_cell::sync::Lazy;
+use std::{collections::HashMap, fmt::Debug};
+
+/// Number of most contended keys logged after each block, when contention stats are enabled.
+const NUM_LOGGED_CONTENDED_KEYS: usize = 10;
 
 pub struct GasType;
 
@@ -211,6 +217,66 @@ pub static BLOCK_COMMITTED_TXNS: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of conflicts observed in parallel execution, recorded when contention stats are enabled.
+pub static CONTENTION_CONFLICTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_contention_conflicts",
+        "Number of write-write conflicts and read dependencies observed in parallel execution",
+        &["kind"]
+    )
+    .unwrap()
+});
+
+/// Number of keys with at least one conflict in a block, recorded when contention stats are enabled.
+pub static BLOCK_CONTENDED_KEYS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_execution_block_contended_keys",
+        "Number of keys with at least one conflict per block in parallel execution",
+        output_buckets(),
+    )
+    .unwrap()
+});
+
+/// Number of conflicts at the most contended key of a block.
+pub static BLOCK_MAX_KEY_CONFLICTS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_execution_block_max_key_conflicts",
+        "Number of conflicts at the most contended key per block in parallel execution",
+        output_buckets(),
+    )
+    .unwrap()
+});
+
+pub(crate) fn update_contention_stats_counters<K: Debug>(
+    contention_stats: HashMap<K, KeyContentionStats>,
+) {
+    let mut keys: Vec<_> = contention_stats.into_iter().collect();
+    keys.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total_conflicts()));
+
+    let (write_write_conflicts, read_dependencies) =
+        keys.iter().fold((0, 0), |(write_write, read), (_, stats)| {
+            (
+                write_write + stats.write_write_conflicts,
+                read + stats.read_dependencies,
+            )
+        });
+    CONTENTION_CONFLICTS
+        .with_label_values(&["write_write"])
+        .inc_by(write_write_conflicts);
+    CONTENTION_CONFLICTS
+        .with_label_values(&["read_dependency"])
+        .inc_by(read_dependencies);
+    BLOCK_CONTENDED_KEYS.observe(keys.len() as f64);
+    BLOCK_MAX_KEY_CONFLICTS
+        .observe(keys.first().map_or(0, |(_, stats)| stats.total_conflicts()) as f64);
+
+    // Keys are not metric labels (the cardinality is unbounded), so the hottest ones are logged.
+    if !keys.is_empty() {
+        keys.truncate(NUM_LOGGED_CONTENDED_KEYS);
+        debug!("Most contended keys in the block: {:?}", keys);
+    }
+}
+
 fn observe_gas(counter: &Lazy<HistogramVec>, mode_str: &str, fee_statement: &FeeStatement) {
     counter
         .with_label_values(&[mode_str, GasType::TOTAL_GAS])
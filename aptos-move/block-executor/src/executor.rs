@@ -51,7 +51,7 @@ use rand::{thread_rng, Rng};
 use rayon::ThreadPool;
 use std::{
     cell::RefCell,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     marker::{PhantomData, Sync},
     sync::{atomic::AtomicU32, Arc},
 };
@@ -159,9 +159,15 @@ where
                 if prev_modified_keys.remove(&k).is_none() {
                     updates_outside = true;
                 }
-                versioned_cache
-                    .data()
-                    .write(k, idx_to_execute, incarnation, v);
+                if v.0.is_deletion() {
+                    versioned_cache
+                        .data()
+                        .delete(k, idx_to_execute, incarnation);
+                } else {
+                    versioned_cache
+                        .data()
+                        .write(k, idx_to_execute, incarnation, v);
+                }
             }
 
             for (k, v) in output.module_write_set().into_iter() {
@@ -1063,7 +1069,11 @@ where
             "Must use sequential execution"
         );
 
-        let versioned_cache = MVHashMap::new();
+        // Every transaction touches at least one key, so pre-size the map accordingly.
+        let mut versioned_cache = MVHashMap::with_capacity_hint(signature_verified_block.len());
+        if self.config.local.record_contention_stats {
+            versioned_cache.enable_contention_stats();
+        }
         let start_shared_counter = gen_id_start_value(false);
         let shared_counter = AtomicU32::new(start_shared_counter);
 
@@ -1117,9 +1127,15 @@ where
             }
         });
         drop(timer);
+        if self.config.local.record_contention_stats {
+            counters::update_contention_stats_counters(versioned_cache.contention_stats());
+        }
+        let (_block_limit_processor, maybe_error) = shared_commit_state.into_inner();
+        if cfg!(debug_assertions) && maybe_error.is_none() {
+            Self::check_final_writes(&versioned_cache, scheduler.commit_state().0);
+        }
         // Explicit async drops.
         DEFAULT_DROPPER.schedule_drop((last_input_output, scheduler, versioned_cache));
-        let (_block_limit_processor, maybe_error) = shared_commit_state.into_inner();
 
         // TODO add block end info to output.
         // block_limit_processor.is_block_limit_reached();
@@ -1130,6 +1146,43 @@ where
         }
     }
 
+    /// Checks that the final writes of the first num_committed transactions can be read
+    /// from the multi-version map, and that the final members of every resource group
+    /// match the contents committed while finalizing the group outputs.
+    fn check_final_writes(
+        versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
+        num_committed: TxnIndex,
+    ) {
+        if let Err((key, err)) = versioned_cache.data().final_writes(num_committed) {
+            panic!("Final write of {:?} is not available: {:?}", key, err);
+        }
+        let group_writes = versioned_cache
+            .group_data()
+            .final_writes(num_committed)
+            .unwrap_or_else(|(key, err)| {
+                panic!("Final write of group {:?} is not available: {:?}", key, err)
+            });
+        for (key, final_write) in group_writes {
+            let committed_tags: BTreeSet<T::Tag> = versioned_cache
+                .group_data()
+                .get_last_committed_group(&key)
+                .expect("Committed group must exist")
+                .into_iter()
+                .map(|(tag, _)| tag)
+                .collect();
+            let final_tags: BTreeSet<T::Tag> = final_write
+                .members
+                .into_iter()
+                .map(|(tag, _)| tag)
+                .collect();
+            assert_eq!(
+                committed_tags, final_tags,
+                "Final members of group {:?} do not match the committed group",
+                key
+            );
+        }
+    }
+
     fn apply_output_sequential(
         unsync_map: &UnsyncMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
         output: &E::Output,
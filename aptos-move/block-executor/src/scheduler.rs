@@ -387,7 +387,6 @@ impl Scheduler {
         None
     }
 
-    #[cfg(test)]
     /// Return the TxnIndex and Wave of current commit index
     pub fn commit_state(&self) -> (TxnIndex, u32) {
         let commit_state = self.commit_state.dereference();
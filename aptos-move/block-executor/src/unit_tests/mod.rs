@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    counters::{BLOCK_CONTENDED_KEYS, CONTENTION_CONFLICTS},
     executor::BlockExecutor,
     proptest_types::{
         baseline::BaselineOutput,
@@ -274,6 +275,54 @@ fn one_writes_all_barrier() {
     run_and_assert(transactions)
 }
 
+#[test]
+fn contention_stats_counters() {
+    let key = KeyType(random::<[u8; 32]>(), false);
+    let num_txns = 20;
+    // Every transaction writes the same key, so all writes but the first one conflict.
+    let transactions: Vec<_> = (0..num_txns)
+        .map(|_| {
+            MockTransaction::from_behavior(MockIncarnation::<KeyType<[u8; 32]>, MockEvent>::new(
+                vec![key],                        // reads
+                vec![(key, random_value(false))], // writes
+                vec![],
+                vec![],
+                1, // gas
+            ))
+        })
+        .collect();
+
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap(),
+    );
+    let mut config = BlockExecutorConfig::new_no_block_limit(4);
+    config.local.record_contention_stats = true;
+
+    let write_write_conflicts = CONTENTION_CONFLICTS.with_label_values(&["write_write"]);
+    let conflicts_before = write_write_conflicts.get();
+    let blocks_before = BLOCK_CONTENDED_KEYS.get_sample_count();
+
+    let output = BlockExecutor::<
+        MockTransaction<KeyType<[u8; 32]>, MockEvent>,
+        MockTask<KeyType<[u8; 32]>, MockEvent>,
+        DeltaDataView<KeyType<[u8; 32]>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<[u8; 32]>, MockEvent>, usize>,
+        ExecutableTestType,
+    >::new(config, executor_thread_pool, None)
+    .execute_transactions_parallel((), &transactions, &data_view);
+    BaselineOutput::generate(&transactions, None).assert_output(&output);
+
+    // The counters are global, so other tests may only add to them.
+    assert!(write_write_conflicts.get() - conflicts_before >= num_txns - 1);
+    assert!(BLOCK_CONTENDED_KEYS.get_sample_count() > blocks_before);
+}
+
 #[test]
 fn early_aborts() {
     let mut transactions = vec![];
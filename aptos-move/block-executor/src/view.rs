@@ -625,6 +625,26 @@ impl<'a, T: Transaction, X: Executable> ResourceState<T> for ParallelState<'a, T
 
                     return ReadResult::from_data_read(data_read);
                 },
+                Ok(Deleted(version)) => {
+                    // The value is known to be deleted, no need to read from storage.
+                    let data_read = DataRead::from_deletion(version)
+                        .downcast(target_kind)
+                        .expect("Downcast from Versioned must succeed");
+
+                    if self
+                        .captured_reads
+                        .borrow_mut()
+                        .capture_read(key.clone(), None, data_read.clone())
+                        .is_err()
+                    {
+                        // Inconsistency in recorded reads.
+                        return ReadResult::HaltSpeculativeExecution(
+                            "Inconsistency in reads (must be due to speculation)".to_string(),
+                        );
+                    }
+
+                    return ReadResult::from_data_read(data_read);
+                },
                 Err(Uninitialized) | Err(Unresolved(_)) => {
                     // The underlying assumption here for not recording anything about the read is
                     // that the caller is expected to initialize the contents and serve the reads
@@ -0,0 +1,48 @@
+module 0xbeef::algebra {
+    use std::option;
+    use std::vector;
+    use aptos_std::bls12381_algebra::{FormatFrLsb, FormatG1Compr, Fr, G1};
+    use aptos_std::crypto_algebra::{Self, Element};
+
+    /// Deserialize `bytes` as a compressed G1 point and, if it is valid, check that it survives a
+    /// serialization round trip and that adding it to itself doubles it.
+    public entry fun deserialize_serialize_add(bytes: vector<u8>) {
+        let maybe_point = crypto_algebra::deserialize<G1, FormatG1Compr>(&bytes);
+        if (option::is_some(&maybe_point)) {
+            check_serialize_add(&option::extract(&mut maybe_point));
+        }
+    }
+
+    /// Same as `deserialize_serialize_add`, for the valid point `k * generator`.
+    public entry fun scalar_mul_serialize_add(k: u64) {
+        let point = crypto_algebra::scalar_mul(
+            &crypto_algebra::one<G1>(),
+            &crypto_algebra::from_u64<Fr>(k),
+        );
+        check_serialize_add(&point);
+    }
+
+    /// Store `num_elements` scalars, destroy the one at index `destroyed` and store a new scalar
+    /// in its slot, then add the scalar at index `used` to the new one. Aborts with
+    /// `E_ELEMENT_DESTROYED` iff `used == destroyed`.
+    public entry fun use_after_destroy(num_elements: u64, destroyed: u64, used: u64) {
+        let elements = vector[];
+        let i = 0;
+        while (i < num_elements) {
+            vector::push_back(&mut elements, crypto_algebra::from_u64<Fr>(i));
+            i = i + 1;
+        };
+        crypto_algebra::destroy(*vector::borrow(&elements, destroyed));
+        let new_element = crypto_algebra::from_u64<Fr>(num_elements);
+        let sum = crypto_algebra::add(vector::borrow(&elements, used), &new_element);
+        let _ = crypto_algebra::serialize<Fr, FormatFrLsb>(&sum);
+    }
+
+    fun check_serialize_add(point: &Element<G1>) {
+        let bytes = crypto_algebra::serialize<G1, FormatG1Compr>(point);
+        let deserialized = crypto_algebra::deserialize<G1, FormatG1Compr>(&bytes);
+        assert!(crypto_algebra::eq(option::borrow(&deserialized), point), 1);
+        let sum = crypto_algebra::add(point, point);
+        assert!(crypto_algebra::eq(&sum, &crypto_algebra::double(point)), 2);
+    }
+}
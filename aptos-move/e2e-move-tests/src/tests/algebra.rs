@@ -0,0 +1,79 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_abort, assert_success, tests::common, MoveHarness};
+use aptos_language_e2e_tests::account::Account;
+use aptos_types::account_address::AccountAddress;
+use proptest::prelude::*;
+
+/// Equivalent to `std::error::invalid_state(6)` in Move.
+const E_ELEMENT_DESTROYED: u64 = 0x03_0006;
+
+fn setup() -> (MoveHarness, Account) {
+    let mut h = MoveHarness::new();
+    let acc = h.new_account_at(AccountAddress::from_hex_literal("0xbeef").unwrap());
+    assert_success!(h.publish_package(&acc, &common::test_dir_path("algebra.data/pack")));
+    (h, acc)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        // Cases are expensive, few cases is enough.
+        cases: 10,
+        .. ProptestConfig::default()
+    })]
+
+    #[test]
+    fn fuzz_deserialize_serialize_add(
+        bytes in prop::collection::vec(any::<u8>(), 0..100),
+        k in any::<u64>(),
+    ) {
+        let (mut h, acc) = setup();
+
+        // Arbitrary bytes are almost never a valid point, and must then be rejected cleanly.
+        let result = h.run_entry_function(
+            &acc,
+            str::parse("0xbeef::algebra::deserialize_serialize_add").unwrap(),
+            vec![],
+            vec![bcs::to_bytes(&bytes).unwrap()],
+        );
+        assert_success!(result);
+
+        let result = h.run_entry_function(
+            &acc,
+            str::parse("0xbeef::algebra::scalar_mul_serialize_add").unwrap(),
+            vec![],
+            vec![bcs::to_bytes(&k).unwrap()],
+        );
+        assert_success!(result);
+    }
+
+    #[test]
+    fn fuzz_use_after_destroy(
+        num_elements in 1..10u64,
+        destroyed in any::<prop::sample::Index>(),
+        used in any::<prop::sample::Index>(),
+    ) {
+        let (mut h, acc) = setup();
+        let destroyed = destroyed.index(num_elements as usize) as u64;
+        let used = used.index(num_elements as usize) as u64;
+
+        // The handle of the destroyed element is stale even though its slot got reused, while
+        // the handles of the other elements stay valid.
+        let result = h.run_entry_function(
+            &acc,
+            str::parse("0xbeef::algebra::use_after_destroy").unwrap(),
+            vec![],
+            vec![
+                bcs::to_bytes(&num_elements).unwrap(),
+                bcs::to_bytes(&destroyed).unwrap(),
+                bcs::to_bytes(&used).unwrap(),
+            ],
+        );
+        if used == destroyed {
+            assert_abort!(result, E_ELEMENT_DESTROYED);
+        } else {
+            assert_success!(result);
+        }
+    }
+}
@@ -482,6 +482,7 @@ impl FakeExecutor {
             BlockExecutorConfig {
                 local: BlockExecutorLocalConfig {
                     concurrency_level: usize::min(4, num_cpus::get()),
+                    record_contention_stats: false,
                 },
                 onchain: onchain_config,
             },
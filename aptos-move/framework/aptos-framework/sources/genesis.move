@@ -16,6 +16,7 @@ module aptos_framework::genesis {
     use aptos_framework::consensus_config;
     use aptos_framework::execution_config;
     use aptos_framework::create_signer::create_signer;
+    use aptos_framework::delegation_pool;
     use aptos_framework::gas_schedule;
     use aptos_framework::reconfiguration;
     use aptos_framework::stake;
@@ -62,6 +63,20 @@ module aptos_framework::genesis {
         join_during_genesis: bool,
     }
 
+    struct DelegationConfiguration has copy, drop {
+        delegator_address: address,
+        amount: u64,
+    }
+
+    struct DelegationPoolConfiguration has copy, drop {
+        owner_address: address,
+        operator_address: address,
+        /// In hundredths of a percent, i.e. 10000 is 100%.
+        operator_commission_percentage: u64,
+        delegation_pool_creation_seed: vector<u8>,
+        delegations: vector<DelegationConfiguration>,
+    }
+
     /// Genesis step 1: Initialize aptos framework account and core modules on chain.
     fun initialize(
         gas_schedule: vector<u8>,
@@ -375,6 +390,38 @@ module aptos_framework::genesis {
         stake::join_validator_set_internal(operator, pool_address);
     }
 
+    /// Creates the given delegation pools, and adds their initial delegations. The owner, operator and delegator
+    /// accounts are created if they don't exist yet. Every delegator, whether or not it already exists, is minted
+    /// the amount it delegates on top of its balance, so the delegations are part of the total supply.
+    /// This must be called before the validators are initialized, as that destroys the ability to mint coins.
+    fun create_delegation_pools(
+        aptos_framework: &signer,
+        delegation_pools: vector<DelegationPoolConfiguration>,
+    ) {
+        vector::for_each_ref(&delegation_pools, |pool_config| {
+            let pool_config: &DelegationPoolConfiguration = pool_config;
+            let owner = &create_account(aptos_framework, pool_config.owner_address, 0);
+            delegation_pool::initialize_delegation_pool(
+                owner,
+                pool_config.operator_commission_percentage,
+                pool_config.delegation_pool_creation_seed,
+            );
+            let pool_address = delegation_pool::get_owned_pool_address(pool_config.owner_address);
+
+            if (pool_config.operator_address != pool_config.owner_address) {
+                create_account(aptos_framework, pool_config.operator_address, 0);
+                delegation_pool::set_operator(owner, pool_config.operator_address);
+            };
+
+            vector::for_each_ref(&pool_config.delegations, |delegation| {
+                let delegation: &DelegationConfiguration = delegation;
+                let delegator = &create_account(aptos_framework, delegation.delegator_address, 0);
+                aptos_coin::mint(aptos_framework, delegation.delegator_address, delegation.amount);
+                delegation_pool::add_stake(delegator, pool_address, delegation.amount);
+            });
+        });
+    }
+
     /// The last step of genesis.
     fun set_genesis_end(aptos_framework: &signer) {
         chain_status::set_genesis_end(aptos_framework);
@@ -511,4 +558,59 @@ module aptos_framework::genesis {
         create_account(aptos_framework, addr0, 23456);
         assert!(coin::balance<AptosCoin>(addr0) == 12345, 2);
     }
+
+    #[test(aptos_framework = @0x1)]
+    fun test_create_delegation_pools(aptos_framework: &signer) {
+        initialize(
+            x"000000000000000000", // empty gas schedule
+            4u8, // TESTING chain ID
+            0,
+            x"12",
+            x"13",
+            1,
+            0,
+            1000000_00000000, // maximum stake
+            1,
+            true,
+            1,
+            1,
+            30,
+        );
+        initialize_aptos_coin(aptos_framework);
+        std::features::change_feature_flags(
+            aptos_framework,
+            vector[std::features::get_delegation_pools_feature()],
+            vector[],
+        );
+
+        let owner = @0x121341;
+        let operator = @0x121342;
+        let delegator0 = @0x121343;
+        let delegator1 = @0x121344;
+        // An account that already exists keeps its balance.
+        create_account(aptos_framework, delegator1, 5_00000000);
+        let pool_configs = vector[
+            DelegationPoolConfiguration {
+                owner_address: owner,
+                operator_address: operator,
+                operator_commission_percentage: 1000,
+                delegation_pool_creation_seed: x"01",
+                delegations: vector[
+                    DelegationConfiguration { delegator_address: delegator0, amount: 20_00000000 },
+                    DelegationConfiguration { delegator_address: delegator1, amount: 30_00000000 },
+                ],
+            },
+        ];
+
+        create_delegation_pools(aptos_framework, pool_configs);
+        let pool_address = delegation_pool::get_owned_pool_address(owner);
+        assert!(stake::get_operator(pool_address) == operator, 0);
+        assert!(delegation_pool::operator_commission_percentage(pool_address) == 1000, 1);
+        let (active, _, _) = delegation_pool::get_stake(pool_address, delegator0);
+        assert!(active == 20_00000000, 2);
+        let (active, _, _) = delegation_pool::get_stake(pool_address, delegator1);
+        assert!(active == 30_00000000, 3);
+        assert!(coin::balance<AptosCoin>(delegator0) == 0, 4);
+        assert!(coin::balance<AptosCoin>(delegator1) == 5_00000000, 5);
+    }
 }
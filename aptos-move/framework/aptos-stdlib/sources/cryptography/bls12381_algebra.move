@@ -627,7 +627,7 @@ module aptos_std::bls12381_algebra {
     }
 
     #[test_only]
-    use aptos_std::crypto_algebra::{zero, one, from_u64, eq, deserialize, serialize, neg, add, sub, mul, div, inv, rand_insecure, sqr, order, scalar_mul, multi_scalar_mul, double, hash_to, upcast, enable_cryptography_algebra_natives, pairing, multi_pairing, downcast, Element};
+    use aptos_std::crypto_algebra::{zero, one, from_u64, eq, deserialize, serialize, neg, add, add_batch, sub, mul, mul_batch, div, inv, rand_insecure, sqr, order, scalar_mul, multi_scalar_mul, double, hash_to, upcast, enable_cryptography_algebra_natives, pairing, multi_pairing, downcast, destroy, Element};
 
     #[test_only]
     const FR_VAL_0_SERIALIZED_LSB: vector<u8> = x"0000000000000000000000000000000000000000000000000000000000000000";
@@ -698,6 +698,57 @@ module aptos_std::bls12381_algebra {
         // Squaring.
         let val_x = rand_insecure<Fr>();
         assert!(eq(&mul(&val_x, &val_x), &sqr(&val_x)), 1);
+
+        // Batched addition and multiplication.
+        let sums = add_batch(&vector[val_minus_7, val_7], &vector[val_9, val_2]);
+        assert!(std::vector::length(&sums) == 2, 1);
+        assert!(eq(&val_2, std::vector::borrow(&sums, 0)), 1);
+        assert!(eq(&from_u64<Fr>(9), std::vector::borrow(&sums, 1)), 1);
+        let products = mul_batch(&vector[val_7, val_x], &vector[val_9, val_x]);
+        assert!(std::vector::length(&products) == 2, 1);
+        assert!(eq(&val_63, std::vector::borrow(&products, 0)), 1);
+        assert!(eq(&sqr(&val_x), std::vector::borrow(&products, 1)), 1);
+        assert!(std::vector::is_empty(&add_batch<Fr>(&vector[], &vector[])), 1);
+    }
+
+    #[test(fx = @std)]
+    #[expected_failure(abort_code = 0x010002, location = aptos_std::crypto_algebra)]
+    fun test_add_batch_should_abort_when_sizes_mismatch(fx: signer) {
+        enable_cryptography_algebra_natives(&fx);
+        let x = vector[rand_insecure<G1>()];
+        let y = vector[rand_insecure<G1>(), rand_insecure<G1>()];
+        add_batch(&x, &y);
+    }
+
+    #[test(fx = @std)]
+    #[expected_failure(abort_code = 0x010002, location = aptos_std::crypto_algebra)]
+    fun test_mul_batch_should_abort_when_sizes_mismatch(fx: signer) {
+        enable_cryptography_algebra_natives(&fx);
+        let x = vector[rand_insecure<Fr>(), rand_insecure<Fr>()];
+        let y = vector[rand_insecure<Fr>()];
+        mul_batch(&x, &y);
+    }
+
+    #[test(fx = @std)]
+    fun test_destroy(fx: signer) {
+        enable_cryptography_algebra_natives(&fx);
+
+        // Destroying an element keeps the other elements usable.
+        let val_7 = from_u64<Fr>(7);
+        let val_9 = from_u64<Fr>(9);
+        let val_16 = add(&val_7, &val_9);
+        destroy(val_7);
+        assert!(eq(&from_u64<Fr>(25), &add(&val_9, &val_16)), 1);
+    }
+
+    #[test(fx = @std)]
+    #[expected_failure(abort_code = 0x030006, location = aptos_std::crypto_algebra)]
+    fun test_use_after_destroy(fx: signer) {
+        enable_cryptography_algebra_natives(&fx);
+        let val_7 = from_u64<Fr>(7);
+        let val_7_copy = val_7;
+        destroy(val_7);
+        neg(&val_7_copy);
     }
 
     #[test(fx = @std)]
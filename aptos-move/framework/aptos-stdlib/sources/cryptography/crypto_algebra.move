@@ -42,6 +42,7 @@ module aptos_std::crypto_algebra {
     const E_NOT_IMPLEMENTED: u64 = 1;
     const E_NON_EQUAL_LENGTHS: u64 = 2;
     const E_TOO_MUCH_MEMORY_USED: u64 = 3;
+    const E_ELEMENT_DESTROYED: u64 = 6;
 
     /// This struct represents an element of a structure `S`.
     struct Element<phantom S> has copy, drop {
@@ -98,6 +99,16 @@ module aptos_std::crypto_algebra {
         }
     }
 
+    /// Compute `x[i] + y[i]` for every `i`, where `x[]` and `y[]` are `n` elements of a structure `S`.
+    /// Cheaper than calling `add()` `n` times, as all the additions are done in a single native call.
+    ///
+    /// Abort with code `std::error::invalid_argument(E_NON_EQUAL_LENGTHS)` if the sizes of `x` and `y` do not match.
+    public fun add_batch<S>(x: &vector<Element<S>>, y: &vector<Element<S>>): vector<Element<S>> {
+        abort_unless_cryptography_algebra_natives_enabled();
+        let handles = add_batch_internal<S>(handles_from_elements(x), handles_from_elements(y));
+        elements_from_handles(handles)
+    }
+
     /// Compute `x - y` for elements `x` and `y` of a structure `S`.
     public fun sub<S>(x: &Element<S>, y: &Element<S>): Element<S> {
         abort_unless_cryptography_algebra_natives_enabled();
@@ -114,6 +125,16 @@ module aptos_std::crypto_algebra {
         }
     }
 
+    /// Compute `x[i] * y[i]` for every `i`, where `x[]` and `y[]` are `n` elements of a structure `S`.
+    /// Cheaper than calling `mul()` `n` times, as all the multiplications are done in a single native call.
+    ///
+    /// Abort with code `std::error::invalid_argument(E_NON_EQUAL_LENGTHS)` if the sizes of `x` and `y` do not match.
+    public fun mul_batch<S>(x: &vector<Element<S>>, y: &vector<Element<S>>): vector<Element<S>> {
+        abort_unless_cryptography_algebra_natives_enabled();
+        let handles = mul_batch_internal<S>(handles_from_elements(x), handles_from_elements(y));
+        elements_from_handles(handles)
+    }
+
     /// Try computing `x / y` for elements `x` and `y` of a structure `S`.
     /// Return none if `y` does not have a multiplicative inverse in the structure `S`
     /// (e.g., when `S` is a field, and `y` is zero).
@@ -251,6 +272,15 @@ module aptos_std::crypto_algebra {
         }
     }
 
+    /// Release the memory held by element `x`, which makes room for new elements (see `E_TOO_MUCH_MEMORY_USED`)
+    /// and lowers the cost of creating them. All elements are released automatically once the transaction payload returns.
+    ///
+    /// NOTE: `x` is copyable, so any copy of it that is used (or destroyed) afterwards aborts with `E_ELEMENT_DESTROYED`.
+    public fun destroy<S>(x: Element<S>) {
+        abort_unless_cryptography_algebra_natives_enabled();
+        destroy_internal<S>(x.handle)
+    }
+
     /// Hash an arbitrary-length byte array `msg` into structure `S` with a domain separation tag `dst`
     /// using the given hash-to-structure suite `H`.
     ///
@@ -303,13 +333,32 @@ module aptos_std::crypto_algebra {
         element_handles
     }
 
+    fun elements_from_handles<S>(handles: vector<u64>): vector<Element<S>> {
+        let num_handles = std::vector::length(&handles);
+        let elements = std::vector::empty();
+        let i = 0;
+        while ({
+            spec {
+                invariant len(elements) == i;
+                invariant forall k in 0..i: elements[k].handle == handles[k];
+            };
+            i < num_handles
+        }) {
+            std::vector::push_back(&mut elements, Element<S> { handle: *std::vector::borrow(&handles, i) });
+            i = i + 1;
+        };
+        elements
+    }
+
     //
     // (Private functions end here.)
     // Native functions begin.
     //
 
     native fun add_internal<S>(handle_1: u64, handle_2: u64): u64;
+    native fun add_batch_internal<S>(handles_1: vector<u64>, handles_2: vector<u64>): vector<u64>;
     native fun deserialize_internal<S, F>(bytes: &vector<u8>): (bool, u64);
+    native fun destroy_internal<S>(handle: u64);
     native fun div_internal<F>(handle_1: u64, handle_2: u64): (bool, u64);
     native fun double_internal<G>(element_handle: u64): u64;
     native fun downcast_internal<L,S>(handle: u64): (bool, u64);
@@ -320,6 +369,7 @@ module aptos_std::crypto_algebra {
     #[test_only]
     native fun rand_insecure_internal<S>(): u64;
     native fun mul_internal<F>(handle_1: u64, handle_2: u64): u64;
+    native fun mul_batch_internal<F>(handles_1: vector<u64>, handles_2: vector<u64>): vector<u64>;
     native fun multi_pairing_internal<G1,G2,Gt>(g1_handles: vector<u64>, g2_handles: vector<u64>): u64;
     native fun multi_scalar_mul_internal<G, S>(element_handles: vector<u64>, scalar_handles: vector<u64>): u64;
     native fun neg_internal<F>(handle: u64): u64;
@@ -5,14 +5,27 @@ spec aptos_std::crypto_algebra {
         ensures forall i in 0..len(elements): result[i] == elements[i].handle;
     }
 
+    spec elements_from_handles<S>(handles: vector<u64>): vector<Element<S>> {
+        aborts_if false;
+        ensures forall i in 0..len(handles): result[i].handle == handles[i];
+    }
+
     spec add_internal<S>(handle_1: u64, handle_2: u64): u64 {
         pragma opaque;
     }
 
+    spec add_batch_internal<S>(handles_1: vector<u64>, handles_2: vector<u64>): vector<u64> {
+        pragma opaque;
+    }
+
     spec deserialize_internal<S, F>(bytes: &vector<u8>): (bool, u64) {
         pragma opaque;
     }
 
+    spec destroy_internal<S>(handle: u64) {
+        pragma opaque;
+    }
+
     spec div_internal<F>(handle_1: u64, handle_2: u64): (bool, u64) {
         pragma opaque;
     }
@@ -45,6 +58,10 @@ spec aptos_std::crypto_algebra {
         pragma opaque;
     }
 
+    spec mul_batch_internal<F>(handles_1: vector<u64>, handles_2: vector<u64>): vector<u64> {
+        pragma opaque;
+    }
+
     spec multi_pairing_internal<G1,G2,Gt>(g1_handles: vector<u64>, g2_handles: vector<u64>): u64 {
         pragma opaque;
     }
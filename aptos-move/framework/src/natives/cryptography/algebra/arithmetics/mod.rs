@@ -15,8 +15,8 @@ macro_rules! ark_binary_op_internal {
     ($context:expr, $args:ident, $ark_typ:ty, $ark_func:ident, $gas:expr) => {{
         let handle_2 = aptos_native_interface::safely_pop_arg!($args, u64) as usize;
         let handle_1 = aptos_native_interface::safely_pop_arg!($args, u64) as usize;
-        safe_borrow_element!($context, handle_1, $ark_typ, element_1_ptr, element_1);
-        safe_borrow_element!($context, handle_2, $ark_typ, element_2_ptr, element_2);
+        safe_borrow_element!($context, handle_1, $ark_typ, ptr_1, element_1);
+        safe_borrow_element!($context, handle_2, $ark_typ, ptr_2, element_2);
         $context.charge($gas)?;
         let new_element = element_1.$ark_func(element_2);
         let new_handle = store_element!($context, new_element)?;
@@ -24,6 +24,31 @@ macro_rules! ark_binary_op_internal {
     }};
 }
 
+/// Applies a binary operation to every pair of elements of two equally-sized handle vectors
+/// in a single native call, charging the gas of the operation once per pair.
+#[macro_export]
+macro_rules! ark_binary_batch_op_internal {
+    ($context:expr, $args:ident, $ark_typ:ty, $ark_func:ident, $gas:expr) => {{
+        let handles_2 = aptos_native_interface::safely_pop_arg!($args, Vec<u64>);
+        let handles_1 = aptos_native_interface::safely_pop_arg!($args, Vec<u64>);
+        if handles_1.len() != handles_2.len() {
+            return Err(SafeNativeError::Abort {
+                abort_code: MOVE_ABORT_CODE_INPUT_VECTOR_SIZES_NOT_MATCHING,
+            });
+        }
+        $context.charge($gas.per::<Arg>() * NumArgs::from(handles_1.len() as u64))?;
+        let mut new_handles = Vec::with_capacity(handles_1.len());
+        for (handle_1, handle_2) in handles_1.into_iter().zip(handles_2) {
+            safe_borrow_element!($context, handle_1 as usize, $ark_typ, ptr_1, element_1);
+            safe_borrow_element!($context, handle_2 as usize, $ark_typ, ptr_2, element_2);
+            let new_element = element_1.$ark_func(element_2);
+            let new_handle = store_element!($context, new_element)?;
+            new_handles.push(new_handle as u64);
+        }
+        Ok(smallvec![Value::vector_u64(new_handles)])
+    }};
+}
+
 #[macro_export]
 macro_rules! ark_unary_op_internal {
     ($context:expr, $args:ident, $ark_typ:ty, $ark_func:ident, $gas:expr) => {{
@@ -0,0 +1,25 @@
+// Copyright © Aptos Foundation
+
+use crate::natives::cryptography::algebra::AlgebraContext;
+use aptos_gas_schedule::gas_params::natives::aptos_framework::*;
+use aptos_native_interface::{safely_pop_arg, SafeNativeContext, SafeNativeResult};
+use move_vm_types::{loaded_data::runtime_types::Type, values::Value};
+use smallvec::{smallvec, SmallVec};
+use std::collections::VecDeque;
+
+/// Releases the element behind a handle. The element can only have been created if its structure
+/// is supported and enabled, so there is no need to check the structure here.
+pub fn destroy_internal(
+    context: &mut SafeNativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> SafeNativeResult<SmallVec<[Value; 1]>> {
+    assert_eq!(1, ty_args.len());
+    let handle = safely_pop_arg!(args, u64) as usize;
+    context.charge(ALGEBRA_DESTROY)?;
+    context
+        .extensions_mut()
+        .get_mut::<AlgebraContext>()
+        .destroy_element(handle)?;
+    Ok(smallvec![])
+}
@@ -7,18 +7,26 @@
 use crate::natives::cryptography::algebra::rand::rand_insecure_internal;
 use crate::natives::cryptography::algebra::{
     arithmetics::{
-        add::add_internal, double::double_internal, mul::mul_internal, neg::neg_internal,
-        sqr::sqr_internal, sub::sub_internal,
+        add::{add_batch_internal, add_internal},
+        double::double_internal,
+        mul::{mul_batch_internal, mul_internal},
+        neg::neg_internal,
+        sqr::sqr_internal,
+        sub::sub_internal,
     },
     casting::{downcast_internal, upcast_internal},
     constants::{one_internal, order_internal, zero_internal},
+    destroy::destroy_internal,
     eq::eq_internal,
     hash_to_structure::hash_to_internal,
     new::from_u64_internal,
     pairing::{multi_pairing_internal, pairing_internal},
     serialization::{deserialize_internal, serialize_internal},
 };
-use aptos_native_interface::{RawSafeNative, SafeNativeBuilder};
+use aptos_gas_schedule::gas_params::natives::aptos_framework::ALGEBRA_PER_RETAINED_ELEMENT;
+use aptos_native_interface::{
+    RawSafeNative, SafeNativeBuilder, SafeNativeContext, SafeNativeError, SafeNativeResult,
+};
 use aptos_types::on_chain_config::FeatureFlag;
 use arithmetics::{
     div::div_internal,
@@ -29,7 +37,7 @@ use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::CanonicalDeserialize;
 use better_any::{Tid, TidAble};
 use move_binary_format::errors::PartialVMError;
-use move_core_types::{language_storage::TypeTag, vm_status::StatusCode};
+use move_core_types::{gas_algebra::NumArgs, language_storage::TypeTag, vm_status::StatusCode};
 use move_vm_runtime::native_functions::NativeFunction;
 use once_cell::sync::Lazy;
 use std::{any::Any, hash::Hash, rc::Rc};
@@ -37,6 +45,7 @@ use std::{any::Any, hash::Hash, rc::Rc};
 pub mod arithmetics;
 pub mod casting;
 pub mod constants;
+pub mod destroy;
 pub mod eq;
 pub mod hash_to_structure;
 pub mod new;
@@ -186,10 +195,48 @@ const MEMORY_LIMIT_IN_BYTES: usize = 1 << 20;
 /// Equivalent to `std::error::resource_exhausted(3)` in Move.
 const E_TOO_MUCH_MEMORY_USED: u64 = 0x09_0003;
 
+/// Equivalent to `std::error::invalid_argument(4)` in Move.
+#[cfg(feature = "algebra-audit")]
+const E_INVALID_HANDLE: u64 = 0x01_0004;
+
+/// Equivalent to `std::error::invalid_argument(5)` in Move.
+#[cfg(feature = "algebra-audit")]
+const E_STRUCTURE_MISMATCH: u64 = 0x01_0005;
+
+/// Equivalent to `std::error::invalid_state(6)` in Move.
+const E_ELEMENT_DESTROYED: u64 = 0x03_0006;
+
+/// A handle is the index of its slot in the low bits, and the generation of the slot at the time
+/// the element was stored in the high bits. Elements that were stored in a fresh slot have a
+/// handle equal to their index.
+const HANDLE_INDEX_BITS: usize = 32;
+
+fn encode_handle(index: usize, generation: u32) -> usize {
+    ((generation as usize) << HANDLE_INDEX_BITS) | index
+}
+
+fn decode_handle(handle: usize) -> (usize, usize) {
+    (
+        handle & ((1 << HANDLE_INDEX_BITS) - 1),
+        handle >> HANDLE_INDEX_BITS,
+    )
+}
+
+/// A slot of the handle table. Its generation is bumped every time its element is destroyed, so
+/// that stale copies of the handle can be told apart from the handle of the slot's next element.
+struct ElementSlot {
+    generation: u32,
+    element: Option<(Rc<dyn Any>, usize)>,
+}
+
 #[derive(Tid, Default)]
 pub struct AlgebraContext {
     bytes_used: usize,
-    objs: Vec<Rc<dyn Any>>,
+    /// Element slots, indexed by the low bits of the handles.
+    objs: Vec<ElementSlot>,
+    /// Indices of the slots whose element was destroyed, to be reused by the next elements.
+    free_slots: Vec<usize>,
+    num_retained: usize,
 }
 
 impl AlgebraContext {
@@ -197,23 +244,139 @@ impl AlgebraContext {
         Self {
             bytes_used: 0,
             objs: Vec::new(),
+            free_slots: Vec::new(),
+            num_retained: 0,
+        }
+    }
+
+    /// Drops all the elements. Elements cannot be stored in global storage, so this is safe to
+    /// call once the Move code that created them has returned.
+    pub fn clear(&mut self) {
+        self.bytes_used = 0;
+        self.objs.clear();
+        self.free_slots.clear();
+        self.num_retained = 0;
+    }
+
+    /// Stores an element of the given size (already accounted for in `bytes_used`), and returns
+    /// its handle. The slot of a destroyed element is reused if there is one.
+    fn push_element(&mut self, element: Rc<dyn Any>, size: usize) -> usize {
+        self.num_retained += 1;
+        if let Some(index) = self.free_slots.pop() {
+            let slot = &mut self.objs[index];
+            slot.element = Some((element, size));
+            return encode_handle(index, slot.generation);
+        }
+        let index = self.objs.len();
+        self.objs.push(ElementSlot {
+            generation: 0,
+            element: Some((element, size)),
+        });
+        encode_handle(index, 0)
+    }
+
+    /// Returns the slot of the element the handle was issued for, and whether that element has
+    /// been destroyed since. Returns `None` for handles that were never issued.
+    fn slot(&self, handle: usize) -> Option<(usize, bool)> {
+        let (index, generation) = decode_handle(handle);
+        let slot = self.objs.get(index)?;
+        match (slot.generation as usize).cmp(&generation) {
+            std::cmp::Ordering::Less => None,
+            std::cmp::Ordering::Equal => Some((index, slot.element.is_none())),
+            std::cmp::Ordering::Greater => Some((index, true)),
+        }
+    }
+
+    /// Drops the element of the handle and releases the memory it accounted for, and makes its
+    /// slot available to new elements. Copies of the handle may still exist in Move, so using the
+    /// handle afterwards results in an abort, even once the slot holds another element.
+    fn destroy_element(&mut self, handle: usize) -> SafeNativeResult<()> {
+        match self.slot(handle) {
+            Some((index, false)) => {
+                let slot = &mut self.objs[index];
+                let size = slot.element.take().map_or(0, |(_, size)| size);
+                self.bytes_used -= size;
+                self.num_retained -= 1;
+                // A slot whose generation would wrap around is retired instead of reused.
+                if let Some(generation) = slot.generation.checked_add(1) {
+                    slot.generation = generation;
+                    self.free_slots.push(index);
+                }
+                Ok(())
+            },
+            Some((_, true)) => Err(SafeNativeError::Abort {
+                abort_code: E_ELEMENT_DESTROYED,
+            }),
+            #[cfg(feature = "algebra-audit")]
+            None => Err(SafeNativeError::Abort {
+                abort_code: E_INVALID_HANDLE,
+            }),
+            #[cfg(not(feature = "algebra-audit"))]
+            None => Err(SafeNativeError::InvariantViolation(
+                abort_invariant_violated(),
+            )),
+        }
+    }
+
+    /// Get a pointer to the `handle`-th element, which is expected to be of type `T`.
+    ///
+    /// Every element is tagged with its concrete type when stored (via `Any`), so a handle that
+    /// does not exist or refers to an element of another structure can only come from a framework
+    /// bug and is an invariant violation. With the `algebra-audit` feature, such uses result in a
+    /// typed abort instead, which allows the natives to be fuzzed with arbitrary handles.
+    /// Borrowing a destroyed element always results in a typed abort, as Move code can keep
+    /// copies of its handle around.
+    fn borrow_element<T: 'static>(&self, handle: usize) -> SafeNativeResult<Rc<dyn Any>> {
+        let element = match self.slot(handle) {
+            Some((_, true)) => {
+                return Err(SafeNativeError::Abort {
+                    abort_code: E_ELEMENT_DESTROYED,
+                })
+            },
+            Some((index, false)) => self.objs[index]
+                .element
+                .as_ref()
+                .map(|(element, _)| element),
+            None => None,
+        };
+        #[cfg(feature = "algebra-audit")]
+        match element {
+            None => {
+                return Err(SafeNativeError::Abort {
+                    abort_code: E_INVALID_HANDLE,
+                })
+            },
+            Some(element) if !element.is::<T>() => {
+                return Err(SafeNativeError::Abort {
+                    abort_code: E_STRUCTURE_MISMATCH,
+                })
+            },
+            _ => {},
         }
+        element
+            .cloned()
+            .ok_or_else(|| SafeNativeError::InvariantViolation(abort_invariant_violated()))
     }
 }
 
+/// Charges for storing a new element, proportionally to the number of elements already retained
+/// in the session, so that code which never destroys its elements pays for the memory it holds.
+fn charge_for_retained_elements(context: &mut SafeNativeContext) -> SafeNativeResult<()> {
+    let num_retained = context.extensions().get::<AlgebraContext>().num_retained;
+    context.charge(ALGEBRA_PER_RETAINED_ELEMENT * NumArgs::new(num_retained as u64))
+}
+
 /// Try getting a pointer to the `handle`-th elements in `context` and assign it to a local variable `ptr_out`.
 /// Then try casting it to a reference of `typ` and assign it in a local variable `ref_out`.
-/// Abort the VM execution with invariant violation if anything above fails.
+/// Abort the VM execution with invariant violation if anything above fails
+/// (or with a typed abort, if the `algebra-audit` feature is enabled).
 #[macro_export]
 macro_rules! safe_borrow_element {
     ($context:expr, $handle:expr, $typ:ty, $ptr_out:ident, $ref_out:ident) => {
         let $ptr_out = $context
             .extensions()
             .get::<AlgebraContext>()
-            .objs
-            .get($handle)
-            .ok_or_else(abort_invariant_violated)?
-            .clone();
+            .borrow_element::<$typ>($handle)?;
         let $ref_out = $ptr_out
             .downcast_ref::<$typ>()
             .ok_or_else(abort_invariant_violated)?;
@@ -223,18 +386,17 @@ macro_rules! safe_borrow_element {
 #[macro_export]
 macro_rules! store_element {
     ($context:expr, $obj:expr) => {{
+        $crate::natives::cryptography::algebra::charge_for_retained_elements($context)?;
         let context = &mut $context.extensions_mut().get_mut::<AlgebraContext>();
-        let new_size = context.bytes_used + std::mem::size_of_val(&$obj);
+        let size = std::mem::size_of_val(&$obj);
+        let new_size = context.bytes_used + size;
         if new_size > MEMORY_LIMIT_IN_BYTES {
             Err(SafeNativeError::Abort {
                 abort_code: E_TOO_MUCH_MEMORY_USED,
             })
         } else {
-            let target_vec = &mut context.objs;
             context.bytes_used = new_size;
-            let ret = target_vec.len();
-            target_vec.push(Rc::new($obj));
-            Ok(ret)
+            Ok(context.push_element(Rc::new($obj), size))
         }
     }};
 }
@@ -328,12 +490,15 @@ pub fn make_all(
             "deserialize_internal",
             deserialize_internal as RawSafeNative,
         ),
+        ("destroy_internal", destroy_internal),
         ("downcast_internal", downcast_internal),
         ("eq_internal", eq_internal),
         ("add_internal", add_internal),
+        ("add_batch_internal", add_batch_internal),
         ("div_internal", div_internal),
         ("inv_internal", inv_internal),
         ("mul_internal", mul_internal),
+        ("mul_batch_internal", mul_batch_internal),
         ("neg_internal", neg_internal),
         ("one_internal", one_internal),
         ("sqr_internal", sqr_internal),
@@ -360,3 +525,112 @@ pub fn make_all(
 
     builder.make_named_natives(natives)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn fuzz_borrow_element(
+            k in any::<u64>(),
+            num_elements in 1..10usize,
+            destroyed in any::<prop::sample::Index>(),
+            index in 0..20usize,
+            future_generation in any::<bool>(),
+        ) {
+            // Elements alternate between the scalar fields of BLS12-381 and BN254.
+            let mut context = AlgebraContext::new();
+            for i in 0..num_elements {
+                context.bytes_used += 32;
+                if i % 2 == 0 {
+                    context.push_element(Rc::new(ark_bls12_381::Fr::from(k)), 32);
+                } else {
+                    context.push_element(Rc::new(ark_bn254::Fr::from(k)), 32);
+                }
+            }
+            let destroyed = destroyed.index(num_elements);
+            prop_assert!(context.destroy_element(destroyed).is_ok());
+
+            // The slots have generation 0 or 1, so a handle with generation 2 was never issued.
+            let handle = encode_handle(index, if future_generation { 2 } else { 0 });
+            let result = context.borrow_element::<ark_bls12_381::Fr>(handle);
+            if index >= num_elements || future_generation {
+                #[cfg(feature = "algebra-audit")]
+                prop_assert!(matches!(
+                    result,
+                    Err(SafeNativeError::Abort { abort_code: E_INVALID_HANDLE })
+                ));
+                #[cfg(not(feature = "algebra-audit"))]
+                prop_assert!(matches!(result, Err(SafeNativeError::InvariantViolation(_))));
+            } else if index == destroyed {
+                prop_assert!(matches!(
+                    result,
+                    Err(SafeNativeError::Abort { abort_code: E_ELEMENT_DESTROYED })
+                ));
+            } else if index % 2 == 1 {
+                // Without the audit mode, the mismatch is caught when downcasting the element.
+                #[cfg(feature = "algebra-audit")]
+                prop_assert!(matches!(
+                    result,
+                    Err(SafeNativeError::Abort { abort_code: E_STRUCTURE_MISMATCH })
+                ));
+                #[cfg(not(feature = "algebra-audit"))]
+                prop_assert!(result.unwrap().downcast_ref::<ark_bls12_381::Fr>().is_none());
+            } else {
+                prop_assert_eq!(
+                    result.unwrap().downcast_ref::<ark_bls12_381::Fr>(),
+                    Some(&ark_bls12_381::Fr::from(k))
+                );
+            }
+        }
+
+        #[test]
+        fn fuzz_destroy_element(k in any::<u64>(), num_elements in 1..10usize) {
+            let mut context = AlgebraContext::new();
+            for _ in 0..num_elements {
+                context.bytes_used += 32;
+                context.push_element(Rc::new(ark_bls12_381::Fr::from(k)), 32);
+            }
+
+            // Destroying an element releases its memory, and keeps the other handles valid.
+            prop_assert!(context.destroy_element(0).is_ok());
+            prop_assert_eq!(context.bytes_used, 32 * (num_elements - 1));
+            prop_assert_eq!(context.num_retained, num_elements - 1);
+            for handle in 1..num_elements {
+                prop_assert!(context.borrow_element::<ark_bls12_381::Fr>(handle).is_ok());
+            }
+
+            // Using or destroying a destroyed element aborts.
+            prop_assert!(matches!(
+                context.borrow_element::<ark_bls12_381::Fr>(0),
+                Err(SafeNativeError::Abort { abort_code: E_ELEMENT_DESTROYED })
+            ));
+            prop_assert!(matches!(
+                context.destroy_element(0),
+                Err(SafeNativeError::Abort { abort_code: E_ELEMENT_DESTROYED })
+            ));
+
+            // The next element reuses the slot, under a new handle. The stale handle keeps
+            // aborting rather than aliasing the new element.
+            context.bytes_used += 32;
+            let handle = context.push_element(Rc::new(ark_bls12_381::Fr::from(k)), 32);
+            prop_assert_eq!(decode_handle(handle), (0, 1));
+            prop_assert_eq!(context.objs.len(), num_elements);
+            prop_assert!(context.borrow_element::<ark_bls12_381::Fr>(handle).is_ok());
+            prop_assert!(matches!(
+                context.borrow_element::<ark_bls12_381::Fr>(0),
+                Err(SafeNativeError::Abort { abort_code: E_ELEMENT_DESTROYED })
+            ));
+            prop_assert!(context.destroy_element(handle).is_ok());
+            let handle = context.push_element(Rc::new(ark_bls12_381::Fr::from(k)), 32);
+            prop_assert_eq!(decode_handle(handle), (0, 2));
+
+            // Clearing drops everything.
+            context.clear();
+            prop_assert_eq!(context.bytes_used, 0);
+            prop_assert_eq!(context.num_retained, 0);
+        }
+    }
+}
@@ -23,15 +23,13 @@ use std::{collections::VecDeque, rc::Rc};
 macro_rules! store_element {
     ($context:expr, $obj:expr) => {{
         let context = &mut $context.extensions_mut().get_mut::<AlgebraContext>();
-        let new_size = context.bytes_used + std::mem::size_of_val(&$obj);
+        let size = std::mem::size_of_val(&$obj);
+        let new_size = context.bytes_used + size;
         if new_size > MEMORY_LIMIT_IN_BYTES {
             Err(E_TOO_MUCH_MEMORY_USED)
         } else {
-            let target_vec = &mut context.objs;
             context.bytes_used = new_size;
-            let new_handle = target_vec.len();
-            target_vec.push(Rc::new($obj));
-            Ok(new_handle)
+            Ok(context.push_element(Rc::new($obj), size))
         }
     }};
 }
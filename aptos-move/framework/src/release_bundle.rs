@@ -2,20 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{built_package::BuiltPackage, natives::code::PackageMetadata, path_in_crate};
-use anyhow::Context;
+use anyhow::{anyhow, bail, Context};
 use aptos_types::account_address::AccountAddress;
 use move_binary_format::{access::ModuleAccess, errors::PartialVMError, CompiledModule};
 use move_command_line_common::files::{extension_equals, find_filenames, MOVE_EXTENSION};
 use move_core_types::language_storage::ModuleId;
 use move_model::{code_writer::CodeWriter, emit, emitln, model::Loc};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
 
 /// A release bundle consists of a list of release packages.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ReleaseBundle {
-    /// The packages in this release bundle. The order is bottom up regarding dependencies,
-    /// such the packages can be deployed in order as given.
+    /// The packages in this release bundle. The order is usually bottom up regarding
+    /// dependencies, but use `sorted_packages` to get an order in which the packages
+    /// can be deployed (e.g., if custom packages were added to the bundle).
     pub packages: Vec<ReleasePackage>,
     /// A set of paths to directories where Move sources constituting this package are found.
     /// This may or not may be populated.
@@ -86,6 +90,29 @@ impl ReleaseBundle {
         self.code().into_iter().map(|v| v.to_vec()).collect()
     }
 
+    /// Returns the packages of this bundle, topological sorted regarding dependencies (across
+    /// addresses), such that the packages can be deployed in the returned order. Otherwise,
+    /// the order of the bundle is preserved. Dependencies outside of this bundle are ignored.
+    pub fn sorted_packages(&self) -> anyhow::Result<Vec<&ReleasePackage>> {
+        let mut map = BTreeMap::new();
+        for pack in &self.packages {
+            let key = (pack.address()?, pack.name().to_string());
+            if map.insert(key.clone(), pack).is_some() {
+                bail!("duplicate package `{}` at address {}", key.1, key.0)
+            }
+        }
+        let mut order = vec![];
+        let mut visiting = BTreeSet::new();
+        for pack in &self.packages {
+            let key = (pack.address()?, pack.name().to_string());
+            sort_packages_by_deps(&map, &mut order, &mut visiting, key)?;
+        }
+        Ok(order
+            .into_iter()
+            .map(|key| *map.get(&key).unwrap())
+            .collect())
+    }
+
     /// Returns the Move source file names which are involved in this bundle.
     pub fn files(&self) -> anyhow::Result<Vec<String>> {
         assert!(
@@ -118,6 +145,15 @@ impl ReleasePackage {
         &self.metadata.name
     }
 
+    /// Returns the address the package is deployed at (i.e., the address of its modules).
+    pub fn address(&self) -> anyhow::Result<AccountAddress> {
+        let module = self
+            .code
+            .first()
+            .ok_or_else(|| anyhow!("package `{}` has no modules", self.name()))?;
+        Ok(*CompiledModule::deserialize(module)?.self_id().address())
+    }
+
     /// Returns a vector of code slices representing the bytecode of modules in this bundle.
     pub fn code(&self) -> Vec<&[u8]> {
         self.code.iter().map(|v| v.as_slice()).collect()
@@ -318,6 +354,35 @@ impl ReleasePackage {
     }
 }
 
+fn sort_packages_by_deps(
+    map: &BTreeMap<(AccountAddress, String), &ReleasePackage>,
+    order: &mut Vec<(AccountAddress, String)>,
+    visiting: &mut BTreeSet<(AccountAddress, String)>,
+    key: (AccountAddress, String),
+) -> anyhow::Result<()> {
+    if order.contains(&key) {
+        return Ok(());
+    }
+    if !visiting.insert(key.clone()) {
+        bail!(
+            "cyclic dependency on package `{}` at address {}",
+            key.1,
+            key.0
+        )
+    }
+    for dep in &map.get(&key).unwrap().metadata.deps {
+        // Only consider deps which are actually in this bundle. Deps for outside
+        // packages are expected to already be deployed.
+        let dep_key = (dep.account, dep.package_name.clone());
+        if map.contains_key(&dep_key) {
+            sort_packages_by_deps(map, order, visiting, dep_key)?;
+        }
+    }
+    visiting.remove(&key);
+    order.push(key);
+    Ok(())
+}
+
 fn sort_by_deps(
     map: &BTreeMap<ModuleId, (&[u8], CompiledModule)>,
     order: &mut Vec<ModuleId>,
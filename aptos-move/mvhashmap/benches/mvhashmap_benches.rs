@@ -0,0 +1,317 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// Run this bencher via `cargo bench -p aptos-mvhashmap`.
+//
+// Each benchmark group executes the same synthetic block sequentially through the
+// UnsyncMap (as done by sequential execution), and in parallel through the MVHashMap
+// with a simulated scheduler. The speedup is the ratio of the reported throughputs.
+//
+// The MVHashMap is benchmarked in different construction modes (see `MapMode`).
+use aptos_aggregator::delta_change_set::{delta_add, serialize, DeltaOp};
+use aptos_mvhashmap::{
+    types::{MVDataError, TxnIndex, ValueWithLayout},
+    unsync_map::UnsyncMap,
+    MVHashMap,
+};
+use aptos_types::{
+    access_path::AccessPath,
+    executable::{ExecutableTestType, ModulePath},
+    state_store::state_value::StateValue,
+    write_set::{TransactionWrite, WriteOpKind},
+};
+use bytes::Bytes;
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+use rand::{rngs::StdRng, seq::index, Rng, SeedableRng};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+const NUM_READS_PER_TXN: usize = 4;
+const NUM_WRITES_PER_TXN: usize = 2;
+
+type ParallelMap = MVHashMap<BenchKey, usize, BenchValue, ExecutableTestType, ()>;
+type SequentialMap = UnsyncMap<BenchKey, usize, BenchValue, ExecutableTestType, ()>;
+
+/// How the MVHashMap is constructed for each block.
+#[derive(Clone, Copy, Debug)]
+enum MapMode {
+    /// A new map is created for every block (`MVHashMap::new`).
+    New,
+    /// A new map, pre-sized to the number of keys, is created for every block
+    /// (`MVHashMap::with_capacity_hint`).
+    CapacityHint,
+}
+
+impl MapMode {
+    fn name(&self) -> &'static str {
+        match self {
+            MapMode::New => "mvhashmap",
+            MapMode::CapacityHint => "mvhashmap_capacity_hint",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct BenchKey(usize);
+
+impl ModulePath for BenchKey {
+    fn module_path(&self) -> Option<AccessPath> {
+        None
+    }
+}
+
+/// A value that holds a serialized u128, so that deltas can be applied to it.
+#[derive(Clone, Debug)]
+struct BenchValue(Bytes);
+
+impl BenchValue {
+    fn new(value: u128) -> Self {
+        Self(serialize(&value).into())
+    }
+}
+
+impl TransactionWrite for BenchValue {
+    fn bytes(&self) -> Option<&Bytes> {
+        Some(&self.0)
+    }
+
+    fn as_state_value(&self) -> Option<StateValue> {
+        Some(StateValue::new_legacy(self.0.clone()))
+    }
+
+    fn from_state_value(maybe_state_value: Option<StateValue>) -> Self {
+        Self(
+            maybe_state_value
+                .map(|state_value| state_value.bytes().clone())
+                .unwrap_or_default(),
+        )
+    }
+
+    fn write_op_kind(&self) -> WriteOpKind {
+        WriteOpKind::Modification
+    }
+
+    fn set_bytes(&mut self, bytes: Bytes) {
+        self.0 = bytes;
+    }
+
+    fn convert_read_to_modification(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+enum BenchWrite {
+    Value(u128),
+    Delta(DeltaOp),
+}
+
+#[derive(Clone, Debug)]
+struct BenchTransaction {
+    reads: Vec<BenchKey>,
+    writes: Vec<(BenchKey, BenchWrite)>,
+}
+
+/// The parameters of a synthetic workload. The conflict rate is controlled by
+/// the number of keys (i.e., fewer keys lead to more conflicts), and the delta
+/// density is the fraction of writes that are deltas (i.e., aggregator updates).
+#[derive(Clone, Copy, Debug)]
+struct Workload {
+    block_size: usize,
+    num_keys: usize,
+    delta_density: f64,
+}
+
+impl Workload {
+    fn name(&self) -> String {
+        format!(
+            "block_{}_keys_{}_deltas_{}",
+            self.block_size,
+            self.num_keys,
+            (self.delta_density * 100.0) as usize
+        )
+    }
+
+    fn generate_block(&self, rng: &mut StdRng) -> Vec<BenchTransaction> {
+        (0..self.block_size)
+            .map(|_| {
+                let reads = (0..NUM_READS_PER_TXN)
+                    .map(|_| BenchKey(rng.gen_range(0, self.num_keys)))
+                    .collect();
+                let writes = index::sample(rng, self.num_keys, NUM_WRITES_PER_TXN)
+                    .into_iter()
+                    .map(|key| {
+                        let write = if rng.gen_bool(self.delta_density) {
+                            BenchWrite::Delta(delta_add(1, u128::MAX))
+                        } else {
+                            BenchWrite::Value(rng.gen())
+                        };
+                        (BenchKey(key), write)
+                    })
+                    .collect();
+                BenchTransaction { reads, writes }
+            })
+            .collect()
+    }
+
+    /// Creates a map (according to the given mode) with base values for all keys,
+    /// and with estimates for all the writes in the block. Reads of writes that have
+    /// not yet been executed will then wait on the dependency, as they would in Block-STM.
+    fn create_parallel_map(&self, block: &[BenchTransaction], mode: MapMode) -> ParallelMap {
+        let map = match mode {
+            MapMode::New => ParallelMap::new(),
+            MapMode::CapacityHint => ParallelMap::with_capacity_hint(self.num_keys),
+        };
+        for key in 0..self.num_keys {
+            map.data().set_base_value(
+                BenchKey(key),
+                ValueWithLayout::RawFromStorage(Arc::new(BenchValue::new(0))),
+            );
+        }
+        for (txn_idx, txn) in block.iter().enumerate() {
+            let txn_idx = txn_idx as TxnIndex;
+            for (key, write) in &txn.writes {
+                match write {
+                    BenchWrite::Value(_) => {
+                        map.data()
+                            .write(*key, txn_idx, 0, (BenchValue::new(0), None))
+                    },
+                    BenchWrite::Delta(delta) => map.data().add_delta(*key, txn_idx, *delta),
+                }
+                map.data().mark_estimate(key, txn_idx);
+            }
+        }
+        map
+    }
+
+    fn create_sequential_map(&self) -> SequentialMap {
+        let map = SequentialMap::new();
+        for key in 0..self.num_keys {
+            map.set_base_value(
+                BenchKey(key),
+                ValueWithLayout::RawFromStorage(Arc::new(BenchValue::new(0))),
+            );
+        }
+        map
+    }
+}
+
+/// Executes the block in parallel, with each worker eagerly fetching the next
+/// transaction to execute (in order), and spinning on unresolved dependencies.
+fn execute_parallel(map: &ParallelMap, block: &[BenchTransaction], num_workers: usize) {
+    let next_txn_idx = AtomicUsize::new(0);
+    rayon::scope(|s| {
+        for _ in 0..num_workers {
+            s.spawn(|_| loop {
+                let txn_idx = next_txn_idx.fetch_add(1, Ordering::Relaxed);
+                if txn_idx >= block.len() {
+                    break;
+                }
+                let txn = &block[txn_idx];
+                let txn_idx = txn_idx as TxnIndex;
+
+                for key in &txn.reads {
+                    loop {
+                        match map.data().fetch_data(key, txn_idx) {
+                            Err(MVDataError::Dependency(_)) => thread::yield_now(),
+                            result => {
+                                black_box(result.ok());
+                                break;
+                            },
+                        }
+                    }
+                }
+                for (key, write) in &txn.writes {
+                    match write {
+                        BenchWrite::Value(value) => {
+                            map.data()
+                                .write(*key, txn_idx, 1, (BenchValue::new(*value), None))
+                        },
+                        BenchWrite::Delta(delta) => map.data().add_delta(*key, txn_idx, *delta),
+                    }
+                }
+            })
+        }
+    });
+}
+
+/// Executes the block sequentially, materializing deltas as they are written.
+fn execute_sequential(map: &SequentialMap, block: &[BenchTransaction]) {
+    for txn in block {
+        for key in &txn.reads {
+            black_box(map.fetch_data(key));
+        }
+        for (key, write) in &txn.writes {
+            let value = match write {
+                BenchWrite::Value(value) => *value,
+                BenchWrite::Delta(delta) => {
+                    let base = map
+                        .fetch_data(key)
+                        .and_then(|value| value.extract_value_no_layout().as_u128().ok())
+                        .flatten()
+                        .unwrap_or(0);
+                    delta.apply_to(base).unwrap_or(base)
+                },
+            };
+            map.write(*key, BenchValue::new(value), None);
+        }
+    }
+}
+
+fn mvhashmap_benches(c: &mut Criterion) {
+    let num_workers = rayon::current_num_threads();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for block_size in [1_000, 10_000] {
+        for num_keys in [10, 1_000, 100_000] {
+            for delta_density in [0.0, 0.5] {
+                let workload = Workload {
+                    block_size,
+                    num_keys,
+                    delta_density,
+                };
+                let block = workload.generate_block(&mut rng);
+
+                let mut group = c.benchmark_group(workload.name());
+                group.throughput(Throughput::Elements(block_size as u64));
+                group.sample_size(10);
+
+                group.bench_function(BenchmarkId::new("sequential", 1), |b| {
+                    b.iter_batched(
+                        || workload.create_sequential_map(),
+                        // return the map so the cost of dropping it is not counted
+                        |map| {
+                            execute_sequential(&map, &block);
+                            map
+                        },
+                        BatchSize::LargeInput,
+                    )
+                });
+                for mode in [MapMode::New, MapMode::CapacityHint] {
+                    group.bench_function(BenchmarkId::new(mode.name(), num_workers), |b| {
+                        b.iter_batched(
+                            || workload.create_parallel_map(&block, mode),
+                            // return the map so the cost of dropping it is not counted
+                            |map| {
+                                execute_parallel(&map, &block, num_workers);
+                                map
+                            },
+                            BatchSize::LargeInput,
+                        )
+                    });
+                }
+                group.finish();
+            }
+        }
+    }
+}
+
+criterion_group!(benches, mvhashmap_benches);
+criterion_main!(benches);
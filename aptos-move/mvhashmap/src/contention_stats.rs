@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use dashmap::DashMap;
+use std::{collections::HashMap, hash::Hash};
+
+/// Conflicts observed at a single key during (parallel) block execution.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct KeyContentionStats {
+    /// Number of times a transaction wrote to the key while a different transaction
+    /// in the block also had an entry (write or delta) recorded at the key. Subsequent
+    /// incarnations of a transaction that already wrote to the key are not counted.
+    pub write_write_conflicts: u64,
+    /// Number of times a read at the key hit an estimate, i.e. the reading
+    /// transaction had to wait for a lower transaction to be re-executed.
+    pub read_dependencies: u64,
+}
+
+impl KeyContentionStats {
+    pub fn total_conflicts(&self) -> u64 {
+        self.write_write_conflicts + self.read_dependencies
+    }
+
+    fn merge(&mut self, other: &KeyContentionStats) {
+        self.write_write_conflicts += other.write_write_conflicts;
+        self.read_dependencies += other.read_dependencies;
+    }
+}
+
+/// Per-key contention counters. Instrumentation is off by default, in which case
+/// recording is a no-op (and keys are never cloned).
+pub(crate) struct ContentionStats<K> {
+    stats: Option<DashMap<K, KeyContentionStats>>,
+}
+
+impl<K: Hash + Clone + Eq> ContentionStats<K> {
+    pub(crate) fn disabled() -> Self {
+        Self { stats: None }
+    }
+
+    pub(crate) fn enable(&mut self) {
+        if self.stats.is_none() {
+            self.stats = Some(DashMap::new());
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.stats.is_some()
+    }
+
+    pub(crate) fn record_write_write_conflict(&self, key: &K) {
+        if let Some(stats) = &self.stats {
+            stats.entry(key.clone()).or_default().write_write_conflicts += 1;
+        }
+    }
+
+    pub(crate) fn record_read_dependency(&self, key: &K) {
+        if let Some(stats) = &self.stats {
+            stats.entry(key.clone()).or_default().read_dependencies += 1;
+        }
+    }
+
+    /// Adds the recorded stats to the provided map.
+    pub(crate) fn collect_into(&self, collected: &mut HashMap<K, KeyContentionStats>) {
+        if let Some(stats) = &self.stats {
+            for entry in stats.iter() {
+                collected
+                    .entry(entry.key().clone())
+                    .or_default()
+                    .merge(entry.value());
+            }
+        }
+    }
+}
@@ -3,16 +3,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    versioned_data::VersionedData, versioned_delayed_fields::VersionedDelayedFields,
-    versioned_group_data::VersionedGroupData, versioned_modules::VersionedModules,
+    contention_stats::KeyContentionStats, versioned_data::VersionedData,
+    versioned_delayed_fields::VersionedDelayedFields, versioned_group_data::VersionedGroupData,
+    versioned_modules::VersionedModules,
 };
 use aptos_types::{
     executable::{Executable, ModulePath},
     write_set::TransactionWrite,
 };
 use serde::Serialize;
-use std::{fmt::Debug, hash::Hash};
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
 
+pub mod contention_stats;
+#[cfg(any(test, feature = "testing"))]
+pub mod test_utils;
 pub mod types;
 pub mod unsync_map;
 mod utils;
@@ -24,6 +28,10 @@ pub mod versioned_modules;
 #[cfg(test)]
 mod unit_tests;
 
+/// The maximum number of keys the data table is pre-sized for, so that an unexpectedly
+/// large hint doesn't allocate (and hold on to) an oversized table.
+pub const MAX_CAPACITY_HINT: usize = 1 << 16;
+
 /// Main multi-version data-structure used by threads to read/write during parallel
 /// execution.
 ///
@@ -31,6 +39,12 @@ mod unit_tests;
 /// given key, it holds exclusive access and doesn't need to explicitly synchronize
 /// with other reader/writers.
 ///
+/// Keys of different domains are stored separately, i.e. (resource and table item)
+/// data and aggregator deltas, resource groups, delayed fields and modules each have
+/// a dedicated data-structure with its own read/write API. Hence, e.g. a delta can't
+/// be mixed with module bytes stored under an equal key (writing a module key to the
+/// data, or a non-module key to the modules, panics).
+///
 /// TODO: separate V into different generic types for data and code modules with specialized
 /// traits (currently both WriteOp for executor).
 pub struct MVHashMap<K, T, V: TransactionWrite, X: Executable, I: Clone> {
@@ -60,6 +74,38 @@ impl<
         }
     }
 
+    /// Creates a map whose internal table for data is pre-sized to hold `num_keys_hint`
+    /// keys (at most MAX_CAPACITY_HINT). Large blocks touch many keys, and growing the
+    /// table during parallel execution re-allocates and re-hashes it (while holding the
+    /// shard locks), so pre-sizing reduces both the allocator pressure and contention.
+    /// Resource groups are only touched by some transactions, so their table isn't pre-sized.
+    pub fn with_capacity_hint(num_keys_hint: usize) -> MVHashMap<K, T, V, X, I> {
+        MVHashMap {
+            data: VersionedData::with_capacity(num_keys_hint.min(MAX_CAPACITY_HINT)),
+            group_data: VersionedGroupData::new(),
+            delayed_fields: VersionedDelayedFields::new(),
+            modules: VersionedModules::new(),
+        }
+    }
+
+    /// Enables recording, for each (group) data key, the number of write-write conflicts
+    /// and read dependencies observed during block execution. Disabled by default, since
+    /// recording adds synchronization on the hot path of the parallel execution.
+    pub fn enable_contention_stats(&mut self) {
+        self.data.enable_contention_stats();
+        self.group_data.enable_contention_stats();
+    }
+
+    /// Returns the contention stats recorded so far (e.g. after the block is committed),
+    /// keyed by state key. Only keys that observed at least one conflict are included,
+    /// and the result is empty if contention stats were not enabled.
+    pub fn contention_stats(&self) -> HashMap<K, KeyContentionStats> {
+        let mut stats = HashMap::new();
+        self.data.collect_contention_stats(&mut stats);
+        self.group_data.collect_contention_stats(&mut stats);
+        stats
+    }
+
     /// Contains 'simple' versioned data (nothing contained in groups).
     pub fn data(&self) -> &VersionedData<K, V> {
         &self.data
@@ -0,0 +1,503 @@
+// Copyright © Aptos Foundation
+// Parts of the project are originally copyright © Meta Platforms, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Utilities for testing the multi-version data-structures, exposed (with the `testing`
+//! feature) so that the crates built on top of MVHashMap can reuse them in their own tests.
+//!
+//! A [`Scenario`] is a block of operations (writes, removals, deltas and reads), one per
+//! transaction. Running it executes the operations in parallel on an MVHashMap, and asserts
+//! that every read observes the output of the latest preceding operations, as computed by a
+//! sequential baseline. Scenarios can be built deterministically with a [`ScenarioBuilder`],
+//! or generated with [`scenario_strategy`].
+
+use crate::{
+    types::{MVDataError, MVDataOutput, MVGroupError, TxnIndex, ValueWithLayout},
+    MVHashMap,
+};
+use aptos_aggregator::delta_change_set::{delta_add, delta_sub, DeltaOp};
+use aptos_types::{
+    access_path::AccessPath,
+    executable::{ExecutableTestType, ModulePath},
+    state_store::state_value::StateValue,
+    write_set::{TransactionWrite, WriteOpKind},
+};
+use bytes::Bytes;
+use claims::assert_none;
+use proptest::{collection::vec, prelude::*, sample::Index, strategy::Strategy};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    hash::Hash,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The maximum number of attempts (100ms apart) to read a value that is being written.
+const DEFAULT_TIMEOUT: u64 = 30;
+
+/// The tag of the single resource stored in each group, when testing the group data.
+const GROUP_TAG: usize = 5;
+
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd, Debug)]
+pub struct KeyType<K: Hash + Clone + Debug + Eq>(
+    /// Wrapping the types used for testing to add ModulePath trait implementation.
+    pub K,
+);
+
+impl<K: Hash + Clone + Eq + Debug> ModulePath for KeyType<K> {
+    fn module_path(&self) -> Option<AccessPath> {
+        None
+    }
+}
+
+/// The operation performed by a transaction on its key.
+#[derive(Debug, Clone)]
+pub enum Operator<V: Debug + Clone> {
+    Insert(V),
+    Remove,
+    Read,
+    Update(DeltaOp),
+}
+
+/// The expected output of a read, given the operations of the preceding transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectedOutput<V: Debug + Clone + PartialEq> {
+    NotInMap,
+    Deleted,
+    Value(V),
+    Resolved(u128),
+    Unresolved(DeltaOp),
+    Failure,
+}
+
+/// A value written by a transaction (serialized into 16 bytes, so that it can
+/// also serve as the base value of the deltas).
+#[derive(Debug, Clone)]
+pub struct Value<V> {
+    maybe_value: Option<V>,
+    maybe_bytes: Option<Bytes>,
+}
+
+impl<V: Into<Vec<u8>> + Clone> Value<V> {
+    pub fn new(maybe_value: Option<V>) -> Self {
+        let maybe_bytes = maybe_value.clone().map(|v| {
+            let mut bytes = v.into();
+            bytes.resize(16, 0);
+            bytes.into()
+        });
+        Self {
+            maybe_value,
+            maybe_bytes,
+        }
+    }
+}
+
+impl<V: Into<Vec<u8>> + Clone + Debug> TransactionWrite for Value<V> {
+    fn bytes(&self) -> Option<&Bytes> {
+        self.maybe_bytes.as_ref()
+    }
+
+    fn write_op_kind(&self) -> WriteOpKind {
+        unimplemented!("Irrelevant for the test")
+    }
+
+    fn from_state_value(_maybe_state_value: Option<StateValue>) -> Self {
+        unimplemented!("Irrelevant for the test")
+    }
+
+    fn as_state_value(&self) -> Option<StateValue> {
+        unimplemented!("Irrelevant for the test")
+    }
+
+    fn set_bytes(&mut self, bytes: Bytes) {
+        self.maybe_bytes = Some(bytes);
+    }
+
+    fn convert_read_to_modification(&self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        // If we have no bytes, no modification can be created.
+        self.maybe_bytes.as_ref().map(|_| self.clone())
+    }
+}
+
+enum Data<V> {
+    Write(Value<V>),
+    Delta(DeltaOp),
+}
+
+/// The sequential baseline, i.e. the writes and deltas of each key by transaction index.
+struct Baseline<K, V>(HashMap<K, BTreeMap<TxnIndex, Data<V>>>);
+
+impl<K, V> Baseline<K, V>
+where
+    K: Hash + Eq + Clone + Debug,
+    V: Clone + Into<Vec<u8>> + Debug + PartialEq,
+{
+    fn new(txns: &[(K, Operator<V>)], ignore_updates: bool) -> Self {
+        let mut baseline: HashMap<K, BTreeMap<TxnIndex, Data<V>>> = HashMap::new();
+        for (idx, (k, op)) in txns.iter().enumerate() {
+            let value_to_update = match op {
+                Operator::Insert(v) => Data::Write(Value::new(Some(v.clone()))),
+                Operator::Remove => Data::Write(Value::new(None)),
+                Operator::Update(d) => {
+                    if ignore_updates {
+                        continue;
+                    }
+                    Data::Delta(*d)
+                },
+                Operator::Read => continue,
+            };
+
+            baseline
+                .entry(k.clone())
+                .or_default()
+                .insert(idx as TxnIndex, value_to_update);
+        }
+        Self(baseline)
+    }
+
+    fn get(&self, key: &K, txn_idx: TxnIndex) -> ExpectedOutput<V> {
+        match self.0.get(key).map(|tree| tree.range(..txn_idx)) {
+            None => ExpectedOutput::NotInMap,
+            Some(mut iter) => {
+                let mut acc: Option<DeltaOp> = None;
+                let mut failure = false;
+                while let Some((_, data)) = iter.next_back() {
+                    match data {
+                        Data::Write(v) => match acc {
+                            Some(d) => {
+                                match v.as_u128().unwrap() {
+                                    Some(value) => {
+                                        assert!(!failure); // acc should be none.
+                                        match d.apply_to(value) {
+                                            Err(_) => return ExpectedOutput::Failure,
+                                            Ok(i) => return ExpectedOutput::Resolved(i),
+                                        }
+                                    },
+                                    None => {
+                                        // v must be a deletion.
+                                        assert_none!(v.bytes());
+                                        return ExpectedOutput::Deleted;
+                                    },
+                                }
+                            },
+                            None => match v.maybe_value.as_ref() {
+                                Some(w) => {
+                                    return if failure {
+                                        ExpectedOutput::Failure
+                                    } else {
+                                        ExpectedOutput::Value(w.clone())
+                                    };
+                                },
+                                None => return ExpectedOutput::Deleted,
+                            },
+                        },
+                        Data::Delta(d) => match acc.as_mut() {
+                            Some(a) => {
+                                if a.merge_with_previous_delta(*d).is_err() {
+                                    failure = true;
+                                }
+                            },
+                            None => acc = Some(*d),
+                        },
+                    }
+
+                    if failure {
+                        // for overriding the delta failure if entry is deleted.
+                        acc = None;
+                    }
+                }
+
+                if failure {
+                    ExpectedOutput::Failure
+                } else {
+                    match acc {
+                        Some(d) => ExpectedOutput::Unresolved(d),
+                        None => ExpectedOutput::NotInMap,
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Builds a scenario one transaction at a time, i.e. the i-th operation
+/// added is performed by the transaction with index i.
+pub struct ScenarioBuilder<K, V: Debug + Clone> {
+    operations: Vec<(K, Operator<V>)>,
+}
+
+impl<K, V: Debug + Clone> Default for ScenarioBuilder<K, V> {
+    fn default() -> Self {
+        Self { operations: vec![] }
+    }
+}
+
+impl<K, V: Debug + Clone> ScenarioBuilder<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(mut self, key: K, value: V) -> Self {
+        self.operations.push((key, Operator::Insert(value)));
+        self
+    }
+
+    pub fn remove(mut self, key: K) -> Self {
+        self.operations.push((key, Operator::Remove));
+        self
+    }
+
+    pub fn add_delta(mut self, key: K, delta: DeltaOp) -> Self {
+        self.operations.push((key, Operator::Update(delta)));
+        self
+    }
+
+    pub fn read(mut self, key: K) -> Self {
+        self.operations.push((key, Operator::Read));
+        self
+    }
+
+    pub fn build(self) -> Scenario<K, V> {
+        Scenario {
+            operations: self.operations,
+        }
+    }
+}
+
+/// A block of operations (one per transaction), along with the outputs expected by its reads.
+#[derive(Debug, Clone)]
+pub struct Scenario<K, V: Debug + Clone> {
+    operations: Vec<(K, Operator<V>)>,
+}
+
+impl<K, V> Scenario<K, V>
+where
+    K: Send + Clone + Hash + Eq + Sync + Debug,
+    V: Send + Into<Vec<u8>> + Debug + Clone + PartialEq + Sync,
+{
+    pub fn new(operations: Vec<(K, Operator<V>)>) -> Self {
+        Self { operations }
+    }
+
+    pub fn operations(&self) -> &[(K, Operator<V>)] {
+        &self.operations
+    }
+
+    /// Returns the output expected when the transaction at the given index reads its key
+    /// (from the versioned data if `test_group` is false, and from the group data otherwise).
+    pub fn expected_output(&self, txn_idx: TxnIndex, test_group: bool) -> ExpectedOutput<V> {
+        let baseline = Baseline::new(&self.operations, test_group);
+        baseline.get(&self.operations[txn_idx as usize].0, txn_idx)
+    }
+
+    /// Executes the operations on an MVHashMap using the given number of threads, and
+    /// asserts that every read observes its expected output. Before the execution, all the
+    /// versions to be written are marked as estimates, so that the reads wait for the
+    /// preceding transactions, and the outputs don't depend on the thread interleaving.
+    ///
+    /// If `test_group` is set, the group data is tested instead of the versioned data: the
+    /// deltas are ignored, as only the versioned data deals with AggregatorV1.
+    pub fn run(&self, num_threads: usize, test_group: bool) -> Result<(), TestCaseError> {
+        let transactions = &self.operations;
+        let baseline = Baseline::new(transactions, test_group);
+        // Only testing data, provide executable type ().
+        let map = MVHashMap::<KeyType<K>, usize, Value<V>, ExecutableTestType, ()>::new();
+
+        // make ESTIMATE placeholders for all versions to be updated.
+        // allows to test that correct values appear at the end of concurrent execution.
+        let versions_to_write = transactions
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, (key, op))| match op {
+                Operator::Read => None,
+                Operator::Insert(_) | Operator::Remove => Some((key.clone(), idx)),
+                Operator::Update(_) => (!test_group).then_some((key.clone(), idx)),
+            })
+            .collect::<Vec<_>>();
+        for (key, idx) in versions_to_write {
+            let key = KeyType(key);
+            let value = Value::new(None);
+            let idx = idx as TxnIndex;
+            if test_group {
+                map.group_data()
+                    .write(key.clone(), idx, 0, vec![(GROUP_TAG, (value, None))]);
+                map.group_data().mark_estimate(&key, idx);
+            } else {
+                map.data().write(key.clone(), idx, 0, (value, None));
+                map.data().mark_estimate(&key, idx);
+            }
+        }
+
+        let current_idx = AtomicUsize::new(0);
+
+        // Spawn a few threads in parallel to commit each operator.
+        rayon::scope(|s| {
+            for _ in 0..num_threads {
+                s.spawn(|_| loop {
+                    // Each thread will eagerly fetch an Operator to execute.
+                    let idx = current_idx.fetch_add(1, Ordering::Relaxed);
+                    if idx >= transactions.len() {
+                        // Abort when all transactions are processed.
+                        break;
+                    }
+                    let key = &transactions[idx].0;
+                    match &transactions[idx].1 {
+                        Operator::Read => {
+                            let baseline = baseline.get(key, idx as TxnIndex);
+                            assert_read(&map, key, idx as TxnIndex, baseline, test_group);
+                        },
+                        Operator::Remove => {
+                            let key = KeyType(key.clone());
+                            if test_group {
+                                let values = vec![(GROUP_TAG, (Value::new(None), None))];
+                                map.group_data().write(key, idx as TxnIndex, 1, values);
+                            } else {
+                                map.data().delete(key, idx as TxnIndex, 1);
+                            }
+                        },
+                        Operator::Insert(v) => {
+                            let key = KeyType(key.clone());
+                            let value = Value::new(Some(v.clone()));
+                            if test_group {
+                                let values = vec![(GROUP_TAG, (value, None))];
+                                map.group_data().write(key, idx as TxnIndex, 1, values);
+                            } else {
+                                map.data().write(key, idx as TxnIndex, 1, (value, None));
+                            }
+                        },
+                        Operator::Update(delta) => {
+                            if !test_group {
+                                map.data()
+                                    .add_delta(KeyType(key.clone()), idx as TxnIndex, *delta)
+                            }
+                        },
+                    }
+                })
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Reads the key at the given index (waiting for the estimates to be replaced by the
+/// actual writes) and asserts that the expected output is observed.
+fn assert_read<K, V>(
+    map: &MVHashMap<KeyType<K>, usize, Value<V>, ExecutableTestType, ()>,
+    key: &K,
+    idx: TxnIndex,
+    baseline: ExpectedOutput<V>,
+    test_group: bool,
+) where
+    K: Clone + Hash + Eq + Debug,
+    V: Into<Vec<u8>> + Debug + Clone + PartialEq,
+{
+    use MVDataError::*;
+    use MVDataOutput::*;
+
+    let assert_value =
+        |v: ValueWithLayout<Value<V>>| match v.extract_value_no_layout().maybe_value.as_ref() {
+            Some(w) => {
+                assert_eq!(baseline, ExpectedOutput::Value(w.clone()), "{:?}", idx);
+            },
+            None => {
+                assert_eq!(baseline, ExpectedOutput::Deleted, "{:?}", idx);
+            },
+        };
+
+    let mut retry_attempts = 0;
+    loop {
+        if test_group {
+            match map
+                .group_data()
+                .fetch_tagged_data(&KeyType(key.clone()), &GROUP_TAG, idx)
+            {
+                Ok((_, v)) => {
+                    assert_value(v);
+                    break;
+                },
+                Err(MVGroupError::Uninitialized) => {
+                    assert_eq!(baseline, ExpectedOutput::NotInMap, "{:?}", idx);
+                    break;
+                },
+                Err(MVGroupError::Dependency(_i)) => (),
+                Err(_) => unreachable!("Unreachable error cases for test"),
+            }
+        } else {
+            match map.data().fetch_data(&KeyType(key.clone()), idx) {
+                Ok(Versioned(_, v)) => {
+                    assert_value(v);
+                    break;
+                },
+                Ok(Deleted(_)) => {
+                    assert_eq!(baseline, ExpectedOutput::Deleted, "{:?}", idx);
+                    break;
+                },
+                Ok(Resolved(v)) => {
+                    assert_eq!(baseline, ExpectedOutput::Resolved(v), "{:?}", idx);
+                    break;
+                },
+                Err(Uninitialized) => {
+                    assert_eq!(baseline, ExpectedOutput::NotInMap, "{:?}", idx);
+                    break;
+                },
+                Err(DeltaApplicationFailure) => {
+                    assert_eq!(baseline, ExpectedOutput::Failure, "{:?}", idx);
+                    break;
+                },
+                Err(Unresolved(d)) => {
+                    assert_eq!(baseline, ExpectedOutput::Unresolved(d), "{:?}", idx);
+                    break;
+                },
+                Err(Dependency(_i)) => (),
+            }
+        }
+        retry_attempts += 1;
+        if retry_attempts > DEFAULT_TIMEOUT {
+            panic!("Failed to get value for {:?}", idx);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
+pub fn operator_strategy<V: Arbitrary + Clone>() -> impl Strategy<Value = Operator<V>> {
+    prop_oneof![
+        2 => any::<V>().prop_map(Operator::Insert),
+        4 => any::<u32>().prop_map(|v| {
+            // TODO: Is there a proptest way of doing that?
+            if v % 2 == 0 {
+                Operator::Update(delta_sub(v as u128, u32::MAX as u128))
+            } else {
+                Operator::Update(delta_add(v as u128, u32::MAX as u128))
+            }
+        }),
+        1 => Just(Operator::Remove),
+        1 => Just(Operator::Read),
+    ]
+}
+
+/// A strategy for scenarios of `num_operations` operations over `num_keys` keys.
+pub fn scenario_strategy<K, V>(
+    num_keys: usize,
+    num_operations: usize,
+) -> impl Strategy<Value = Scenario<K, V>>
+where
+    K: Arbitrary + Send + Clone + Hash + Eq + Sync + Debug,
+    V: Arbitrary + Send + Into<Vec<u8>> + Debug + Clone + PartialEq + Sync,
+{
+    (
+        vec(any::<K>(), num_keys),
+        vec((any::<Index>(), operator_strategy::<V>()), num_operations),
+    )
+        .prop_map(|(universe, transaction_gens)| {
+            Scenario::new(
+                transaction_gens
+                    .into_iter()
+                    .map(|(idx, op)| (idx.get(&universe).clone(), op))
+                    .collect(),
+            )
+        })
+}
@@ -103,6 +103,32 @@ pub enum MVDataOutput<V> {
     /// Information from the last versioned-write. Note that the version is returned
     /// and not the data to avoid copying big values around.
     Versioned(Version, ValueWithLayout<V>),
+    /// The last versioned-write was a deletion (recorded as a tombstone). As opposed to
+    /// MVDataError::Uninitialized, the value is known not to exist at the version, so
+    /// it does not need to be read from storage.
+    Deleted(Version),
+}
+
+/// The final output of a key after all transactions in a block have been committed,
+/// i.e. the entry of the highest transaction that updated the key (with deltas
+/// materialized). The index of that transaction is also returned.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MVFinalWrite<V> {
+    /// The key was written by the transaction.
+    Write(TxnIndex, ValueWithLayout<V>),
+    /// The key was deleted by the transaction.
+    Deletion(TxnIndex),
+    /// The aggregator value, after applying all deltas up to (and including) the transaction.
+    MaterializedDelta(TxnIndex, u128),
+}
+
+/// The final contents of a resource group after all transactions in a block have been
+/// committed, i.e. the latest value of every member that wasn't deleted, together with
+/// the index of the highest transaction that updated the group.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MVGroupFinalWrite<T, V> {
+    pub txn_idx: TxnIndex,
+    pub members: Vec<(T, ValueWithLayout<V>)>,
 }
 
 /// Returned as Ok(..) when read successfully from the multi-version data-structure.
@@ -244,28 +270,15 @@ pub enum UnknownOrLayout<'a> {
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
+    pub(crate) use crate::test_utils::KeyType;
     use aptos_aggregator::delta_change_set::serialize;
     use aptos_types::{
-        access_path::AccessPath,
-        executable::ModulePath,
         state_store::state_value::StateValue,
         write_set::{TransactionWrite, WriteOpKind},
     };
     use bytes::Bytes;
     use claims::{assert_err, assert_ok_eq};
-    use std::{fmt::Debug, hash::Hash, sync::Arc};
-
-    #[derive(Clone, Eq, Hash, PartialEq, Debug)]
-    pub(crate) struct KeyType<K: Hash + Clone + Debug + Eq>(
-        /// Wrapping the types used for testing to add ModulePath trait implementation.
-        pub K,
-    );
-
-    impl<K: Hash + Clone + Eq + Debug> ModulePath for KeyType<K> {
-        fn module_path(&self) -> Option<AccessPath> {
-            None
-        }
-    }
+    use std::sync::Arc;
 
     #[test]
     fn test_shifted_idx() {
@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{
+    contention_stats::KeyContentionStats,
     types::{
         test::{arc_value_for, u128_for, value_for, KeyType, TestValue},
-        MVDataError, MVDataOutput,
+        MVDataError, MVDataOutput, MVFinalWrite,
     },
     unsync_map::UnsyncMap,
     *,
@@ -16,11 +17,25 @@ use aptos_aggregator::{
     delta_change_set::{delta_add, delta_sub, DeltaOp},
     delta_math::DeltaHistory,
 };
-use aptos_types::executable::ExecutableTestType;
-use claims::{assert_err_eq, assert_none, assert_ok_eq, assert_some_eq};
-use std::sync::Arc;
+use aptos_types::{
+    access_path::AccessPath,
+    executable::{ExecutableTestType, ModulePath},
+    state_store::state_key::StateKey,
+};
+use claims::{assert_err_eq, assert_none, assert_ok, assert_ok_eq, assert_some_eq};
+use move_core_types::{
+    account_address::AccountAddress,
+    ident_str,
+    language_storage::{ModuleId, StructTag},
+};
+use std::{str::FromStr, sync::Arc};
 mod proptest_types;
 
+fn module_key() -> StateKey {
+    let module_id = ModuleId::new(AccountAddress::ONE, ident_str!("foo").to_owned());
+    StateKey::access_path(AccessPath::code_access_path(module_id))
+}
+
 fn match_unresolved(
     read_result: anyhow::Result<MVDataOutput<TestValue>, MVDataError>,
     update: SignedU128,
@@ -273,6 +288,232 @@ fn materialize_delta_shortcut() {
     assert_eq!(vd.fetch_data(&ap, 10), Ok(Resolved(50)));
 }
 
+#[test]
+fn deletion_tombstone() {
+    use MVDataError::*;
+    use MVDataOutput::*;
+
+    let vd: VersionedData<KeyType<Vec<u8>>, TestValue> = VersionedData::new();
+    let ap = KeyType(b"/foo/b".to_vec());
+    let limit = 10000;
+
+    // Never written, the value must be read from storage.
+    assert_eq!(vd.fetch_data(&ap, 10), Err(Uninitialized));
+
+    vd.write(ap.clone(), 3, 0, (value_for(3, 0), None));
+    vd.delete(ap.clone(), 5, 1);
+    let r_3 = Versioned(
+        Ok((3, 0)),
+        ValueWithLayout::Exchanged(arc_value_for(3, 0), None),
+    );
+    assert_ok_eq!(vd.fetch_data(&ap, 5), r_3);
+    assert_ok_eq!(vd.fetch_data(&ap, 6), Deleted(Ok((5, 1))));
+
+    // Deltas on top of the deletion resolve to the deletion.
+    vd.add_delta(ap.clone(), 7, delta_add(10, limit));
+    assert_ok_eq!(vd.fetch_data(&ap, 8), Deleted(Ok((5, 1))));
+
+    // Re-execution may replace the deletion with a write.
+    vd.mark_estimate(&ap, 5);
+    assert_err_eq!(vd.fetch_data(&ap, 6), Dependency(5));
+    vd.write(ap.clone(), 5, 2, (value_for(5, 2), None));
+    let r_5 = Versioned(
+        Ok((5, 2)),
+        ValueWithLayout::Exchanged(arc_value_for(5, 2), None),
+    );
+    assert_ok_eq!(vd.fetch_data(&ap, 6), r_5);
+
+    // And vice versa.
+    vd.delete(ap.clone(), 5, 3);
+    assert_ok_eq!(vd.fetch_data(&ap, 6), Deleted(Ok((5, 3))));
+    vd.remove(&ap, 5);
+    assert_ok_eq!(vd.fetch_data(&ap, 6), r_3);
+}
+
+#[test]
+fn final_writes() {
+    use MVFinalWrite::*;
+
+    let vd: VersionedData<KeyType<Vec<u8>>, TestValue> = VersionedData::new();
+    let ap1 = KeyType(b"/foo/a".to_vec());
+    let ap2 = KeyType(b"/foo/b".to_vec());
+    let ap3 = KeyType(b"/foo/c".to_vec());
+    let ap4 = KeyType(b"/foo/d".to_vec());
+    let limit = 10000;
+
+    // A key with only a base value (read but never written) has no final write.
+    vd.set_base_value(
+        ap1.clone(),
+        ValueWithLayout::RawFromStorage(Arc::new(value_for(0, 0))),
+    );
+
+    // The highest write is the final write (and writes beyond the block are ignored).
+    vd.write(ap2.clone(), 3, 1, (value_for(3, 1), None));
+    vd.write(ap2.clone(), 7, 0, (value_for(7, 0), None));
+    vd.write(ap2.clone(), 12, 0, (value_for(12, 0), None));
+
+    // Deltas are materialized on top of the base value.
+    vd.set_base_value(
+        ap3.clone(),
+        ValueWithLayout::RawFromStorage(Arc::new(TestValue::from_u128(5))),
+    );
+    vd.add_delta(ap3.clone(), 2, delta_add(10, limit));
+    vd.add_delta(ap3.clone(), 4, delta_add(20, limit));
+
+    // Deletions are returned as such.
+    vd.write(ap4.clone(), 1, 0, (value_for(1, 0), None));
+    vd.delete(ap4.clone(), 6, 0);
+
+    let final_writes = vd.final_writes(10).unwrap();
+    assert_eq!(
+        final_writes.keys().collect::<Vec<_>>(),
+        vec![&ap2, &ap3, &ap4]
+    );
+    assert_eq!(
+        final_writes[&ap2],
+        Write(7, ValueWithLayout::Exchanged(arc_value_for(7, 0), None))
+    );
+    assert_eq!(final_writes[&ap3], MaterializedDelta(4, 35));
+    assert_eq!(final_writes[&ap4], Deletion(6));
+
+    // A delta without a base value can't be materialized.
+    let ap5 = KeyType(b"/foo/e".to_vec());
+    vd.add_delta(ap5.clone(), 5, delta_add(10, limit));
+    assert_err_eq!(
+        vd.final_writes(10),
+        (ap5, MVDataError::Unresolved(delta_add(10, limit)))
+    );
+}
+
+#[test]
+fn contention_stats() {
+    let ap1 = KeyType(b"/foo/b".to_vec());
+    let ap2 = KeyType(b"/foo/c".to_vec());
+    let mut mvtbl: MVHashMap<KeyType<Vec<u8>>, usize, TestValue, ExecutableTestType, ()> =
+        MVHashMap::new();
+
+    // Nothing is recorded unless enabled.
+    mvtbl
+        .data()
+        .write(ap1.clone(), 1, 0, (value_for(1, 0), None));
+    mvtbl
+        .data()
+        .write(ap1.clone(), 2, 0, (value_for(2, 0), None));
+    assert!(mvtbl.contention_stats().is_empty());
+
+    let mut mvtbl: MVHashMap<KeyType<Vec<u8>>, usize, TestValue, ExecutableTestType, ()> =
+        MVHashMap::new();
+    mvtbl.enable_contention_stats();
+
+    // The base value does not conflict, the first write by a transaction doesn't either.
+    mvtbl.data().set_base_value(
+        ap1.clone(),
+        ValueWithLayout::RawFromStorage(arc_value_for(0, 0)),
+    );
+    mvtbl
+        .data()
+        .write(ap1.clone(), 1, 0, (value_for(1, 0), None));
+    mvtbl
+        .data()
+        .write(ap2.clone(), 1, 0, (value_for(1, 0), None));
+    assert!(mvtbl.contention_stats().is_empty());
+
+    // Another transaction writing the same key conflicts, re-executing it does not.
+    mvtbl
+        .data()
+        .write(ap1.clone(), 2, 0, (value_for(2, 0), None));
+    mvtbl
+        .data()
+        .write(ap1.clone(), 2, 1, (value_for(2, 1), None));
+
+    // Reads hitting an estimate are recorded as dependencies.
+    mvtbl.data().mark_estimate(&ap1, 2);
+    assert_eq!(
+        mvtbl.data().fetch_data(&ap1, 3),
+        Err(MVDataError::Dependency(2))
+    );
+    assert_eq!(
+        mvtbl.data().fetch_data(&ap1, 4),
+        Err(MVDataError::Dependency(2))
+    );
+    assert_ok!(mvtbl.data().fetch_data(&ap2, 4));
+
+    let stats = mvtbl.contention_stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[&ap1], KeyContentionStats {
+        write_write_conflicts: 1,
+        read_dependencies: 2,
+    });
+}
+
+#[test]
+fn capacity_hint_map() {
+    use MVDataOutput::*;
+
+    let ap = StateKey::raw(b"/foo/b".to_vec());
+    let mvtbl: MVHashMap<StateKey, usize, TestValue, ExecutableTestType, ()> =
+        MVHashMap::with_capacity_hint(16);
+
+    mvtbl
+        .data()
+        .write(ap.clone(), 10, 1, (value_for(10, 1), None));
+    assert_eq!(
+        mvtbl.data().fetch_data(&ap, 15),
+        Ok(Versioned(
+            Ok((10, 1)),
+            ValueWithLayout::Exchanged(arc_value_for(10, 1), None)
+        ))
+    );
+
+    // An oversized hint is capped, rather than allocating a huge table.
+    let mvtbl: MVHashMap<StateKey, usize, TestValue, ExecutableTestType, ()> =
+        MVHashMap::with_capacity_hint(usize::MAX / 2);
+    let capacity = mvtbl.data().capacity();
+    assert!(capacity >= MAX_CAPACITY_HINT);
+    assert!(capacity < 4 * MAX_CAPACITY_HINT);
+}
+
+#[test]
+fn module_path_domains() {
+    assert!(module_key().is_module_path());
+    assert!(!StateKey::raw(vec![0]).is_module_path());
+    let resource_key = StateKey::access_path(
+        AccessPath::resource_access_path(
+            AccountAddress::ONE,
+            StructTag::from_str("0x1::account::Account").unwrap(),
+        )
+        .unwrap(),
+    );
+    assert!(!resource_key.is_module_path());
+    let group_key = StateKey::access_path(AccessPath::resource_group_access_path(
+        AccountAddress::ONE,
+        StructTag::from_str("0x1::object::ObjectGroup").unwrap(),
+    ));
+    assert!(!group_key.is_module_path());
+}
+
+#[test]
+#[should_panic(expected = "to VersionedData")]
+fn module_delta_in_data_domain() {
+    let mvtbl: MVHashMap<StateKey, usize, TestValue, ExecutableTestType, ()> = MVHashMap::new();
+
+    // Deltas are only valid for data, so adding one under a module key must panic.
+    mvtbl
+        .data()
+        .add_delta(module_key(), 5, delta_add(10, 10000));
+}
+
+#[test]
+#[should_panic(expected = "to VersionedModules")]
+fn resource_write_in_module_domain() {
+    let mvtbl: MVHashMap<StateKey, usize, TestValue, ExecutableTestType, ()> = MVHashMap::new();
+
+    // Writing a resource into the modules must panic.
+    mvtbl
+        .modules()
+        .write(StateKey::raw(b"/foo/b".to_vec()), 5, value_for(5, 0));
+}
+
 #[test]
 #[should_panic]
 fn aggregator_base_mismatch() {
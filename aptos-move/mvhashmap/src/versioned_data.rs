@@ -2,18 +2,26 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::{
-    Flag, Incarnation, MVDataError, MVDataOutput, ShiftedTxnIndex, TxnIndex, ValueWithLayout,
+use crate::{
+    contention_stats::{ContentionStats, KeyContentionStats},
+    types::{
+        Flag, Incarnation, MVDataError, MVDataOutput, MVFinalWrite, ShiftedTxnIndex, TxnIndex,
+        ValueWithLayout,
+    },
 };
 use anyhow::Result;
 use aptos_aggregator::delta_change_set::DeltaOp;
-use aptos_types::write_set::TransactionWrite;
+use aptos_types::{executable::ModulePath, write_set::TransactionWrite};
 use claims::assert_some;
 use crossbeam::utils::CachePadded;
 use dashmap::DashMap;
 use move_core_types::value::MoveTypeLayout;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::{
-    collections::btree_map::{self, BTreeMap},
+    collections::{
+        btree_map::{self, BTreeMap},
+        HashMap,
+    },
     fmt::Debug,
     hash::Hash,
     sync::Arc,
@@ -37,6 +45,11 @@ enum EntryCell<V> {
     /// stored in a shared pointer (to ensure ownership and avoid clones).
     Write(Incarnation, ValueWithLayout<V>),
 
+    /// Recorded in the shared multi-version data-structure for each deletion
+    /// (i.e. a tombstone). Unlike a write of a deletion value, no data is stored,
+    /// and reads observe the deletion without having to interpret the value.
+    Deletion(Incarnation),
+
     /// Recorded in the shared multi-version data-structure for each delta.
     /// Option<u128> is a shortcut to aggregated value (to avoid traversing down
     /// beyond this index), which is created after the corresponding txn is committed.
@@ -52,6 +65,7 @@ struct VersionedValue<V> {
 /// Maps each key (access path) to an internal versioned value representation.
 pub struct VersionedData<K, V> {
     values: DashMap<K, VersionedValue<V>>,
+    contention_stats: ContentionStats<K>,
 }
 
 impl<V> Entry<V> {
@@ -62,6 +76,13 @@ impl<V> Entry<V> {
         }
     }
 
+    fn new_deletion(incarnation: Incarnation) -> Entry<V> {
+        Entry {
+            cell: EntryCell::Deletion(incarnation),
+            flag: Flag::Done,
+        }
+    }
+
     fn new_delta_from(data: DeltaOp) -> Entry<V> {
         Entry {
             cell: EntryCell::Delta(data, None),
@@ -125,6 +146,11 @@ impl<V: TransactionWrite> VersionedValue<V> {
             }
 
             match (&entry.cell, accumulator.as_mut()) {
+                (EntryCell::Deletion(incarnation), _) => {
+                    // Resolve to the deletion, which takes precedence over any speculative
+                    // delta accumulation errors on top (MoveVM will observe 'deletion').
+                    return Ok(Deleted(idx.idx().map(|idx| (idx, *incarnation))));
+                },
                 (EntryCell::Write(incarnation, data), None) => {
                     // Resolve to the write if no deltas were applied in between.
                     return Ok(Versioned(
@@ -207,16 +233,65 @@ impl<V: TransactionWrite> VersionedValue<V> {
             None => Err(Uninitialized),
         }
     }
+
+    /// Returns the final output after the first 'num_txns' transactions of the block
+    /// were committed, or None if none of them updated the value.
+    fn final_write(&self, num_txns: TxnIndex) -> Result<Option<MVFinalWrite<V>>, MVDataError> {
+        use MVFinalWrite::*;
+
+        // The highest transaction that updated the value (the base value is excluded).
+        let txn_idx = match self
+            .versioned_map
+            .range(ShiftedTxnIndex::new(0)..ShiftedTxnIndex::new(num_txns))
+            .next_back()
+        {
+            Some((idx, _)) => idx.idx().expect("Must be a transaction index"),
+            None => return Ok(None),
+        };
+
+        Ok(Some(match self.read(num_txns)? {
+            MVDataOutput::Versioned(_, data) => Write(txn_idx, data),
+            MVDataOutput::Deleted(_) => Deletion(txn_idx),
+            MVDataOutput::Resolved(value) => MaterializedDelta(txn_idx, value),
+        }))
+    }
 }
 
-impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
+impl<K: ModulePath + Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
     pub(crate) fn new() -> Self {
         Self {
             values: DashMap::new(),
+            contention_stats: ContentionStats::disabled(),
+        }
+    }
+
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: DashMap::with_capacity(capacity),
+            contention_stats: ContentionStats::disabled(),
         }
     }
 
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    pub(crate) fn enable_contention_stats(&mut self) {
+        self.contention_stats.enable();
+    }
+
+    pub(crate) fn collect_contention_stats(&self, collected: &mut HashMap<K, KeyContentionStats>) {
+        self.contention_stats.collect_into(collected);
+    }
+
     pub fn add_delta(&self, key: K, txn_idx: TxnIndex, delta: DeltaOp) {
+        assert!(
+            !key.is_module_path(),
+            "Writing a module {:?} to VersionedData",
+            key,
+        );
+
         let mut v = self.values.entry(key).or_default();
         v.versioned_map.insert(
             ShiftedTxnIndex::new(txn_idx),
@@ -250,13 +325,24 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
         key: &K,
         txn_idx: TxnIndex,
     ) -> anyhow::Result<MVDataOutput<V>, MVDataError> {
-        self.values
+        let result = self
+            .values
             .get(key)
             .map(|v| v.read(txn_idx))
-            .unwrap_or(Err(MVDataError::Uninitialized))
+            .unwrap_or(Err(MVDataError::Uninitialized));
+        if let Err(MVDataError::Dependency(_)) = result {
+            self.contention_stats.record_read_dependency(key);
+        }
+        result
     }
 
     pub fn set_base_value(&self, key: K, value: ValueWithLayout<V>) {
+        assert!(
+            !key.is_module_path(),
+            "Writing a module {:?} to VersionedData",
+            key,
+        );
+
         let mut v = self.values.entry(key).or_default();
         // For base value, incarnation is irrelevant, and is always set to 0.
 
@@ -310,25 +396,87 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
         incarnation: Incarnation,
         data: (V, Option<Arc<MoveTypeLayout>>),
     ) {
-        let mut v = self.values.entry(key).or_default();
-        let prev_entry = v.versioned_map.insert(
-            ShiftedTxnIndex::new(txn_idx),
-            CachePadded::new(Entry::new_write_from(
+        self.write_entry(
+            key,
+            txn_idx,
+            incarnation,
+            Entry::new_write_from(
                 incarnation,
                 ValueWithLayout::Exchanged(Arc::new(data.0), data.1),
-            )),
+            ),
         );
+    }
+
+    /// Versioned deletion of data at a given key (and version). The deletion is recorded
+    /// as a tombstone, and subsequent reads return MVDataOutput::Deleted, which (unlike
+    /// MVDataError::Uninitialized) means that the value need not be read from storage.
+    pub fn delete(&self, key: K, txn_idx: TxnIndex, incarnation: Incarnation) {
+        self.write_entry(key, txn_idx, incarnation, Entry::new_deletion(incarnation));
+    }
+
+    fn write_entry(&self, key: K, txn_idx: TxnIndex, incarnation: Incarnation, entry: Entry<V>) {
+        assert!(
+            !key.is_module_path(),
+            "Writing a module {:?} to VersionedData",
+            key,
+        );
+
+        // The key is only cloned when contention stats are recorded.
+        let stats_key = self.contention_stats.is_enabled().then(|| key.clone());
+        let mut v = self.values.entry(key).or_default();
+        let prev_entry = v
+            .versioned_map
+            .insert(ShiftedTxnIndex::new(txn_idx), CachePadded::new(entry));
+
+        // Entries at ShiftedTxnIndex::new(0) and above are written by transactions in the
+        // block (as opposed to the base value from storage), and include the one just written.
+        if let Some(stats_key) = stats_key {
+            if prev_entry.is_none()
+                && v.versioned_map
+                    .range(ShiftedTxnIndex::new(0)..)
+                    .nth(1)
+                    .is_some()
+            {
+                self.contention_stats
+                    .record_write_write_conflict(&stats_key);
+            }
+        }
 
         // Assert that the previous entry for txn_idx, if present, had lower incarnation.
         assert!(prev_entry.map_or(true, |entry| -> bool {
-            if let EntryCell::Write(i, _) = entry.cell {
-                i < incarnation
-            } else {
-                true
+            match entry.cell {
+                EntryCell::Write(i, _) | EntryCell::Deletion(i) => i < incarnation,
+                EntryCell::Delta(_, _) => true,
             }
         }));
     }
 
+    /// Returns the final writes of the block after the first 'num_txns' transactions were
+    /// committed: for every key, the output of the highest transaction that updated it, with
+    /// deltas materialized. The writes are ordered by key (as required for the storage commit),
+    /// and are computed in parallel over the shards of the map. Resource groups are written
+    /// here with their metadata op only, see VersionedGroupData::final_writes for the members.
+    ///
+    /// If the result is Err((key, error)), the final output of the key couldn't be determined,
+    /// e.g. because the base value to apply its deltas to hadn't been set.
+    pub fn final_writes(
+        &self,
+        num_txns: TxnIndex,
+    ) -> Result<BTreeMap<K, MVFinalWrite<V>>, (K, MVDataError)>
+    where
+        K: Ord + Send + Sync,
+        V: Send + Sync,
+    {
+        self.values
+            .par_iter()
+            .filter_map(|entry| match entry.value().final_write(num_txns) {
+                Ok(Some(final_write)) => Some(Ok((entry.key().clone(), final_write))),
+                Ok(None) => None,
+                Err(error) => Some(Err((entry.key().clone(), error))),
+            })
+            .collect()
+    }
+
     /// When a transaction is committed, this method can be called for its delta outputs to add
     /// a 'shortcut' to the corresponding materialized aggregator value, so any subsequent reads
     /// do not have to traverse below the index. It must be guaranteed by the caller that the
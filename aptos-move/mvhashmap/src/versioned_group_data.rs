@@ -1,16 +1,24 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::{
-    Flag, Incarnation, MVGroupError, ShiftedTxnIndex, TxnIndex, ValueWithLayout, Version,
+use crate::{
+    contention_stats::{ContentionStats, KeyContentionStats},
+    types::{
+        Flag, Incarnation, MVGroupError, MVGroupFinalWrite, ShiftedTxnIndex, TxnIndex,
+        ValueWithLayout, Version,
+    },
 };
 use anyhow::bail;
-use aptos_types::write_set::{TransactionWrite, WriteOpKind};
+use aptos_types::{
+    executable::ModulePath,
+    write_set::{TransactionWrite, WriteOpKind},
+};
 use aptos_vm_types::{resolver::ResourceGroupSize, resource_group_adapter::group_size_as_sum};
 use claims::{assert_matches, assert_none, assert_some};
 use crossbeam::utils::CachePadded;
 use dashmap::DashMap;
 use move_core_types::value::MoveTypeLayout;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde::Serialize;
 use std::{
     collections::{
@@ -67,6 +75,7 @@ pub(crate) struct VersionedGroupValue<T, V> {
 /// Maps each key (access path) to an internal VersionedValue.
 pub struct VersionedGroupData<K, T, V> {
     group_values: DashMap<K, VersionedGroupValue<T, V>>,
+    contention_stats: ContentionStats<K>,
 }
 
 impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> Default
@@ -327,6 +336,42 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
             })
     }
 
+    /// Returns the contents of the group after the first 'num_txns' transactions of the block
+    /// were committed, or None if none of them updated the group.
+    fn final_write(
+        &self,
+        num_txns: TxnIndex,
+    ) -> Result<Option<MVGroupFinalWrite<T, V>>, MVGroupError> {
+        // The highest transaction that updated the group (the base value is excluded).
+        let txn_idx = match self
+            .idx_to_update
+            .range(ShiftedTxnIndex::new(0)..ShiftedTxnIndex::new(num_txns))
+            .next_back()
+        {
+            Some((idx, _)) => idx.idx().expect("Must be a transaction index"),
+            None => return Ok(None),
+        };
+
+        let mut members = Vec::new();
+        for (tag, tree) in self.versioned_map.iter() {
+            if let Some((idx, entry)) = tree
+                .range(ShiftedTxnIndex::zero_idx()..ShiftedTxnIndex::new(num_txns))
+                .next_back()
+            {
+                if entry.flag == Flag::Estimate {
+                    return Err(MVGroupError::Dependency(
+                        idx.idx()
+                            .expect("Base version cannot be marked as estimate"),
+                    ));
+                }
+                if entry.value.write_op_kind() != WriteOpKind::Deletion {
+                    members.push((tag.clone(), entry.value.clone()));
+                }
+            }
+        }
+        Ok(Some(MVGroupFinalWrite { txn_idx, members }))
+    }
+
     fn get_latest_group_size(&self, txn_idx: TxnIndex) -> Result<ResourceGroupSize, MVGroupError> {
         if !self
             .idx_to_update
@@ -360,7 +405,7 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
 }
 
 impl<
-        K: Hash + Clone + Debug + Eq,
+        K: ModulePath + Hash + Clone + Debug + Eq,
         T: Hash + Clone + Debug + Eq + Serialize,
         V: TransactionWrite,
     > VersionedGroupData<K, T, V>
@@ -368,10 +413,31 @@ impl<
     pub(crate) fn new() -> Self {
         Self {
             group_values: DashMap::new(),
+            contention_stats: ContentionStats::disabled(),
+        }
+    }
+
+    pub(crate) fn enable_contention_stats(&mut self) {
+        self.contention_stats.enable();
+    }
+
+    pub(crate) fn collect_contention_stats(&self, collected: &mut HashMap<K, KeyContentionStats>) {
+        self.contention_stats.collect_into(collected);
+    }
+
+    fn record_if_dependency<R>(&self, key: &K, result: &Result<R, MVGroupError>) {
+        if let Err(MVGroupError::Dependency(_)) = result {
+            self.contention_stats.record_read_dependency(key);
         }
     }
 
     pub fn set_raw_base_values(&self, key: K, base_values: impl IntoIterator<Item = (T, V)>) {
+        assert!(
+            !key.is_module_path(),
+            "Writing a module {:?} to VersionedGroupData",
+            key,
+        );
+
         // Incarnation is irrelevant for storage version, set to 0.
         self.group_values
             .entry(key)
@@ -400,8 +466,34 @@ impl<
         incarnation: Incarnation,
         values: impl IntoIterator<Item = (T, (V, Option<Arc<MoveTypeLayout>>))>,
     ) -> bool {
-        self.group_values.entry(key).or_default().write(
-            ShiftedTxnIndex::new(txn_idx),
+        assert!(
+            !key.is_module_path(),
+            "Writing a module {:?} to VersionedGroupData",
+            key,
+        );
+
+        // The key is only cloned when contention stats are recorded.
+        let stats_key = self.contention_stats.is_enabled().then(|| key.clone());
+        let shifted_idx = ShiftedTxnIndex::new(txn_idx);
+        let mut group = self.group_values.entry(key).or_default();
+
+        // A conflict is recorded for the first incarnation of txn_idx that writes to the
+        // group, if a different transaction in the block has also written to it.
+        if let Some(stats_key) = stats_key {
+            if !group.idx_to_update.contains_key(&shifted_idx)
+                && group
+                    .idx_to_update
+                    .range(ShiftedTxnIndex::new(0)..)
+                    .next()
+                    .is_some()
+            {
+                self.contention_stats
+                    .record_write_write_conflict(&stats_key);
+            }
+        }
+
+        group.write(
+            shifted_idx,
             incarnation,
             values
                 .into_iter()
@@ -437,10 +529,12 @@ impl<
         tag: &T,
         txn_idx: TxnIndex,
     ) -> Result<(Version, ValueWithLayout<V>), MVGroupError> {
-        match self.group_values.get(key) {
+        let result = match self.group_values.get(key) {
             Some(g) => g.get_latest_tagged_value(tag, txn_idx),
             None => Err(MVGroupError::Uninitialized),
-        }
+        };
+        self.record_if_dependency(key, &result);
+        result
     }
 
     /// Returns the sum of latest sizes of all group members (and their respective tags),
@@ -453,10 +547,12 @@ impl<
         key: &K,
         txn_idx: TxnIndex,
     ) -> Result<ResourceGroupSize, MVGroupError> {
-        match self.group_values.get(key) {
+        let result = match self.group_values.get(key) {
             Some(g) => g.get_latest_group_size(txn_idx),
             None => Err(MVGroupError::Uninitialized),
-        }
+        };
+        self.record_if_dependency(key, &result);
+        result
     }
 
     /// For a given key that corresponds to a group, and an index of a transaction the last
@@ -483,6 +579,30 @@ impl<
         Ok(v.get_committed_group())
     }
 
+    /// Returns the final contents of the groups updated by the first 'num_txns' transactions
+    /// of the block, ordered by key and computed in parallel over the shards of the map.
+    ///
+    /// If the result is Err((key, error)), the final contents of the group couldn't be
+    /// determined, i.e. a member was still marked as an estimate.
+    pub fn final_writes(
+        &self,
+        num_txns: TxnIndex,
+    ) -> Result<BTreeMap<K, MVGroupFinalWrite<T, V>>, (K, MVGroupError)>
+    where
+        K: Ord + Send + Sync,
+        T: Send + Sync,
+        V: Send + Sync,
+    {
+        self.group_values
+            .par_iter()
+            .filter_map(|entry| match entry.value().final_write(num_txns) {
+                Ok(Some(final_write)) => Some(Ok((entry.key().clone(), final_write))),
+                Ok(None) => None,
+                Err(error) => Some(Err((entry.key().clone(), error))),
+            })
+            .collect()
+    }
+
     pub fn get_last_committed_group(
         &self,
         key: &K,
@@ -499,7 +619,9 @@ mod test {
         test::{KeyType, TestValue},
         StorageVersion,
     };
-    use claims::{assert_err, assert_matches, assert_none, assert_ok_eq, assert_some_eq};
+    use claims::{
+        assert_err, assert_err_eq, assert_matches, assert_none, assert_ok_eq, assert_some_eq,
+    };
     use test_case::test_case;
 
     #[should_panic]
@@ -770,6 +892,76 @@ mod test {
         map.finalize_group(key, idx).unwrap().into_iter().collect()
     }
 
+    #[test]
+    fn group_final_writes() {
+        let ap1 = KeyType(b"/foo/a".to_vec());
+        let ap2 = KeyType(b"/foo/b".to_vec());
+        let ap3 = KeyType(b"/foo/c".to_vec());
+        let map = VersionedGroupData::<KeyType<Vec<u8>>, usize, TestValue>::new();
+
+        // A group that was only read has no final write.
+        map.set_raw_base_values(ap1.clone(), vec![(1, TestValue::with_kind(1, true))]);
+
+        // Members are merged across transactions, deleted members are excluded, and
+        // writes beyond the block are ignored.
+        map.set_raw_base_values(
+            ap2.clone(),
+            (1..4).map(|i| (i, TestValue::with_kind(i, true))),
+        );
+        map.write(
+            ap2.clone(),
+            2,
+            1,
+            vec![
+                (1, (TestValue::deletion(), None)),
+                (2, (TestValue::with_kind(202, false), None)),
+            ],
+        );
+        map.write(
+            ap2.clone(),
+            4,
+            0,
+            vec![(4, (TestValue::with_kind(404, true), None))],
+        );
+        map.write(ap2.clone(), 12, 0, vec![(3, (TestValue::deletion(), None))]);
+
+        // A member still marked as an estimate can't be finalized.
+        map.set_raw_base_values(ap3.clone(), vec![]);
+        map.write(
+            ap3.clone(),
+            5,
+            0,
+            vec![(1, (TestValue::with_kind(501, true), None))],
+        );
+        map.mark_estimate(&ap3, 5);
+        assert_err_eq!(
+            map.final_writes(10),
+            (ap3.clone(), MVGroupError::Dependency(5))
+        );
+
+        map.remove(&ap3, 5);
+        let final_writes = map.final_writes(10).unwrap();
+        assert_eq!(final_writes.keys().collect::<Vec<_>>(), vec![&ap2]);
+        let final_write = &final_writes[&ap2];
+        assert_eq!(final_write.txn_idx, 4);
+        let mut members = final_write.members.clone();
+        members.sort_by_key(|(tag, _)| *tag);
+        assert_eq!(members, vec![
+            (
+                2,
+                ValueWithLayout::Exchanged(Arc::new(TestValue::with_kind(202, false)), None)
+            ),
+            (
+                3,
+                ValueWithLayout::RawFromStorage(Arc::new(TestValue::with_kind(3, true)))
+            ),
+            (
+                4,
+                ValueWithLayout::Exchanged(Arc::new(TestValue::with_kind(404, true)), None)
+            ),
+        ]);
+    }
+
     #[test]
     fn group_commit_idx() {
         let ap = KeyType(b"/foo/f".to_vec());
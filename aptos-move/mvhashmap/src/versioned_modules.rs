@@ -4,13 +4,14 @@
 use crate::types::{Flag, MVModulesError, MVModulesOutput, TxnIndex};
 use aptos_crypto::hash::{DefaultHasher, HashValue};
 use aptos_types::{
-    executable::{Executable, ExecutableDescriptor},
+    executable::{Executable, ExecutableDescriptor, ModulePath},
     write_set::TransactionWrite,
 };
 use crossbeam::utils::CachePadded;
 use dashmap::DashMap;
 use std::{
     collections::{btree_map::BTreeMap, HashMap},
+    fmt::Debug,
     hash::Hash,
     sync::Arc,
 };
@@ -100,7 +101,9 @@ impl<V: TransactionWrite, X: Executable> Default for VersionedValue<V, X> {
     }
 }
 
-impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> VersionedModules<K, V, X> {
+impl<K: ModulePath + Hash + Clone + Debug + Eq, V: TransactionWrite, X: Executable>
+    VersionedModules<K, V, X>
+{
     pub(crate) fn new() -> Self {
         Self {
             values: DashMap::new(),
@@ -119,6 +122,12 @@ impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> VersionedModules<
 
     /// Versioned write of module at a given key (and version).
     pub fn write(&self, key: K, txn_idx: TxnIndex, data: V) {
+        assert!(
+            key.is_module_path(),
+            "Writing a resource {:?} to VersionedModules",
+            key,
+        );
+
         let mut v = self.values.entry(key).or_default();
         v.versioned_map
             .insert(txn_idx, CachePadded::new(Entry::new_write_from(data)));
@@ -0,0 +1,241 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, Result};
+use aptos_types::{
+    access_path::Path,
+    account_config::{events::NewEpochEvent, AccountResource, CORE_CODE_ADDRESS},
+    account_view::AccountView,
+    contract_event::ContractEvent,
+    event::EventKey,
+    on_chain_config::{
+        Features, GasScheduleV2, OnChainConfig, OnChainConsensusConfig, OnChainExecutionConfig,
+        ValidatorSet, Version,
+    },
+    state_store::{
+        account_with_state_view::AsAccountWithStateView,
+        in_memory_state_view::InMemoryStateView,
+        state_key::{StateKey, StateKeyInner},
+        state_value::StateValue,
+    },
+    transaction::{ChangeSet, Transaction, WriteSetPayload},
+    validator_info::ValidatorInfo,
+    write_set::TransactionWrite,
+};
+use move_core_types::{account_address::AccountAddress, move_resource::MoveStructType};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A structured report of the state produced by a genesis change set. This allows
+/// genesis ceremony tooling to validate the configuration before publishing it.
+#[derive(Clone, Debug, Serialize)]
+pub struct GenesisReport {
+    pub chain_id: Option<u8>,
+    pub accounts: Vec<GenesisAccountReport>,
+    pub validator_set: Option<GenesisValidatorSetReport>,
+    pub on_chain_configs: GenesisOnChainConfigsReport,
+    pub events: Vec<GenesisEventReport>,
+    pub num_write_ops: usize,
+}
+
+/// An account created at genesis
+#[derive(Clone, Debug, Serialize)]
+pub struct GenesisAccountReport {
+    pub address: AccountAddress,
+    pub sequence_number: u64,
+    /// The APT balance of the account (if it holds a coin store)
+    pub balance: Option<u64>,
+}
+
+/// The validator set at the end of genesis
+#[derive(Clone, Debug, Serialize)]
+pub struct GenesisValidatorSetReport {
+    pub active_validators: Vec<GenesisValidatorReport>,
+    pub pending_active: Vec<GenesisValidatorReport>,
+    pub pending_inactive: Vec<GenesisValidatorReport>,
+    pub total_voting_power: u128,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GenesisValidatorReport {
+    pub address: AccountAddress,
+    pub voting_power: u64,
+}
+
+impl From<&ValidatorInfo> for GenesisValidatorReport {
+    fn from(validator_info: &ValidatorInfo) -> Self {
+        Self {
+            address: *validator_info.account_address(),
+            voting_power: validator_info.consensus_voting_power(),
+        }
+    }
+}
+
+/// The on-chain config values at the end of genesis
+#[derive(Clone, Debug, Serialize)]
+pub struct GenesisOnChainConfigsReport {
+    pub version: Option<u64>,
+    pub gas_feature_version: Option<u64>,
+    /// The ids of all enabled feature flags
+    pub enabled_features: Vec<u64>,
+    pub consensus_config: Option<OnChainConsensusConfig>,
+    pub execution_config: Option<OnChainExecutionConfig>,
+}
+
+/// An event emitted during genesis
+#[derive(Clone, Debug, Serialize)]
+pub struct GenesisEventReport {
+    pub type_tag: String,
+    pub event_key: Option<EventKey>,
+    pub sequence_number: Option<u64>,
+    pub size: usize,
+}
+
+impl From<&ContractEvent> for GenesisEventReport {
+    fn from(event: &ContractEvent) -> Self {
+        Self {
+            type_tag: event.type_tag().to_string(),
+            event_key: event.event_key().copied(),
+            sequence_number: event.v1().ok().map(|event| event.sequence_number()),
+            size: event.size(),
+        }
+    }
+}
+
+/// Executes the genesis transaction in a sandbox (see `dry_run_genesis_change_set()`)
+pub fn dry_run_genesis_transaction(genesis_transaction: &Transaction) -> Result<GenesisReport> {
+    match genesis_transaction {
+        Transaction::GenesisTransaction(WriteSetPayload::Direct(change_set)) => {
+            dry_run_genesis_change_set(change_set)
+        },
+        _ => bail!(
+            "Expected a direct genesis transaction, found: {:?}",
+            genesis_transaction
+        ),
+    }
+}
+
+/// Applies the genesis change set to an empty in-memory state (without touching
+/// any storage), verifies its consistency and reports the resulting state.
+pub fn dry_run_genesis_change_set(change_set: &ChangeSet) -> Result<GenesisReport> {
+    // Apply the write set to an empty state
+    let mut state_data = HashMap::new();
+    for (state_key, write_op) in change_set.write_set().iter() {
+        match write_op.bytes() {
+            Some(bytes) => {
+                state_data.insert(state_key.clone(), StateValue::new_legacy(bytes.clone()));
+            },
+            None => bail!(
+                "Genesis cannot delete state! Found deletion of: {:?}",
+                state_key
+            ),
+        }
+    }
+    let state_view = InMemoryStateView::new(state_data);
+
+    // Verify the genesis events
+    let new_epoch_events: Vec<_> = change_set
+        .events()
+        .iter()
+        .filter(|event| event.event_key() == Some(&NewEpochEvent::event_key()))
+        .collect();
+    ensure!(
+        new_epoch_events.len() == 1,
+        "There should only be exactly one NewEpochEvent, found: {}",
+        new_epoch_events.len()
+    );
+    ensure!(
+        new_epoch_events[0].v1()?.sequence_number() == 0,
+        "The NewEpochEvent should have sequence number 0"
+    );
+
+    // Identify all created accounts
+    let mut accounts = vec![];
+    for state_key in change_set
+        .write_set()
+        .iter()
+        .map(|(state_key, _)| state_key)
+    {
+        if let Some(address) = get_account_address(state_key) {
+            let account_view = state_view.as_account_with_state_view(&address);
+            let sequence_number = account_view
+                .get_account_resource()?
+                .map(|account| account.sequence_number())
+                .unwrap_or_default();
+            let balance = account_view
+                .get_coin_store_resource()?
+                .map(|coin_store| coin_store.coin());
+            accounts.push(GenesisAccountReport {
+                address,
+                sequence_number,
+                balance,
+            });
+        }
+    }
+
+    // Fetch the chain id and the validator set
+    let core_account_view = state_view.as_account_with_state_view(&CORE_CODE_ADDRESS);
+    let chain_id = core_account_view
+        .get_chain_id_resource()?
+        .map(|resource| resource.chain_id().id());
+    let validator_set =
+        ValidatorSet::fetch_config(&state_view).map(|validator_set| GenesisValidatorSetReport {
+            active_validators: to_validator_reports(&validator_set.active_validators),
+            pending_active: to_validator_reports(&validator_set.pending_active),
+            pending_inactive: to_validator_reports(&validator_set.pending_inactive),
+            total_voting_power: validator_set.total_voting_power,
+        });
+
+    // Fetch the on-chain configs
+    let enabled_features = Features::fetch_config(&state_view)
+        .map(|features| get_enabled_features(&features))
+        .unwrap_or_default();
+    let on_chain_configs = GenesisOnChainConfigsReport {
+        version: Version::fetch_config(&state_view).map(|version| version.major),
+        gas_feature_version: GasScheduleV2::fetch_config(&state_view)
+            .map(|gas_schedule| gas_schedule.feature_version),
+        enabled_features,
+        consensus_config: OnChainConsensusConfig::fetch_config(&state_view),
+        execution_config: OnChainExecutionConfig::fetch_config(&state_view),
+    };
+
+    Ok(GenesisReport {
+        chain_id,
+        accounts,
+        validator_set,
+        on_chain_configs,
+        events: change_set.events().iter().map(Into::into).collect(),
+        num_write_ops: change_set.write_set().iter().count(),
+    })
+}
+
+/// Returns the address of the account if the state key holds an account resource
+fn get_account_address(state_key: &StateKey) -> Option<AccountAddress> {
+    match state_key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            Path::Resource(struct_tag) if struct_tag == AccountResource::struct_tag() => {
+                Some(access_path.address)
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the ids of all enabled feature flags
+fn get_enabled_features(features: &Features) -> Vec<u64> {
+    features
+        .features
+        .iter()
+        .enumerate()
+        .flat_map(|(byte_index, byte)| {
+            (0..8usize)
+                .filter(move |bit| byte & (1u8 << bit) != 0)
+                .map(move |bit| (byte_index * 8 + bit) as u64)
+        })
+        .collect()
+}
+
+fn to_validator_reports(validators: &[ValidatorInfo]) -> Vec<GenesisValidatorReport> {
+    validators.iter().map(Into::into).collect()
+}
@@ -5,6 +5,8 @@
 #![forbid(unsafe_code)]
 
 mod genesis_context;
+mod genesis_report;
+mod validation;
 
 use crate::genesis_context::GenesisStateView;
 use aptos_crypto::{
@@ -44,6 +46,16 @@ use once_cell::sync::Lazy;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 
+pub use crate::genesis_report::{
+    dry_run_genesis_change_set, dry_run_genesis_transaction, GenesisAccountReport,
+    GenesisEventReport, GenesisOnChainConfigsReport, GenesisReport, GenesisValidatorReport,
+    GenesisValidatorSetReport,
+};
+pub use crate::validation::{
+    gas_schedule_from_blob, validate_gas_schedule, validate_genesis_config,
+    validate_genesis_config_for_chain, GenesisConfigError,
+};
+
 // The seed is arbitrarily picked to produce a consistent key. XXX make this more formal?
 const GENESIS_SEED: [u8; 32] = [42; 32];
 
@@ -71,6 +83,13 @@ pub struct GenesisConfiguration {
     pub voting_power_increase_limit: u64,
     pub employee_vesting_start: u64,
     pub employee_vesting_period_duration: u64,
+    // Delegation pools to create during genesis, before the validators are initialized.
+    pub delegation_pools: Vec<DelegationPool>,
+    // Feature flags to enable (on top of the default features) and disable at genesis.
+    pub features_to_enable: Vec<FeatureFlag>,
+    pub features_to_disable: Vec<FeatureFlag>,
+    // OIDC providers whose JWKs should be watched by the validators from genesis on.
+    pub oidc_providers: Vec<OIDCProvider>,
 }
 
 pub static GENESIS_KEYPAIR: Lazy<(Ed25519PrivateKey, Ed25519PublicKey)> = Lazy::new(|| {
@@ -95,9 +114,11 @@ pub fn encode_aptos_mainnet_genesis_transaction(
     framework: &ReleaseBundle,
     chain_id: ChainId,
     genesis_config: &GenesisConfiguration,
-) -> Transaction {
-    assert!(!genesis_config.is_test, "This is mainnet!");
-    validate_genesis_config(genesis_config);
+) -> Result<Transaction, GenesisConfigError> {
+    if genesis_config.is_test {
+        return Err(GenesisConfigError::TestChainOnMainnet);
+    }
+    validate_genesis_config_for_chain(genesis_config, chain_id)?;
 
     // Create a Move VM session so we can invoke on-chain genesis intializations.
     let mut state_view = GenesisStateView::new();
@@ -130,11 +151,15 @@ pub fn encode_aptos_mainnet_genesis_transaction(
         &execution_config,
         &gas_schedule,
     );
-    initialize_features(&mut session);
+    initialize_features(&mut session, genesis_config);
+    initialize_oidc_providers(&mut session, &genesis_config.oidc_providers);
     initialize_aptos_coin(&mut session);
     initialize_on_chain_governance(&mut session, genesis_config);
     create_accounts(&mut session, accounts);
     create_employee_validators(&mut session, employees, genesis_config);
+    if !genesis_config.delegation_pools.is_empty() {
+        create_delegation_pools(&mut session, &genesis_config.delegation_pools);
+    }
     create_and_initialize_validators_with_commission(&mut session, validators);
     set_genesis_end(&mut session);
 
@@ -173,7 +198,9 @@ pub fn encode_aptos_mainnet_genesis_transaction(
     let change_set = change_set
         .try_into_storage_change_set()
         .expect("Constructing a ChangeSet from VMChangeSet should always succeed at genesis");
-    Transaction::GenesisTransaction(WriteSetPayload::Direct(change_set))
+    Ok(Transaction::GenesisTransaction(WriteSetPayload::Direct(
+        change_set,
+    )))
 }
 
 pub fn encode_genesis_transaction(
@@ -185,8 +212,8 @@ pub fn encode_genesis_transaction(
     consensus_config: &OnChainConsensusConfig,
     execution_config: &OnChainExecutionConfig,
     gas_schedule: &GasScheduleV2,
-) -> Transaction {
-    Transaction::GenesisTransaction(WriteSetPayload::Direct(encode_genesis_change_set(
+) -> Result<Transaction, GenesisConfigError> {
+    let change_set = encode_genesis_change_set(
         &aptos_root_key,
         validators,
         framework,
@@ -195,6 +222,9 @@ pub fn encode_genesis_transaction(
         consensus_config,
         execution_config,
         gas_schedule,
+    )?;
+    Ok(Transaction::GenesisTransaction(WriteSetPayload::Direct(
+        change_set,
     )))
 }
 
@@ -207,8 +237,9 @@ pub fn encode_genesis_change_set(
     consensus_config: &OnChainConsensusConfig,
     execution_config: &OnChainExecutionConfig,
     gas_schedule: &GasScheduleV2,
-) -> ChangeSet {
-    validate_genesis_config(genesis_config);
+) -> Result<ChangeSet, GenesisConfigError> {
+    validate_genesis_config_for_chain(genesis_config, chain_id)?;
+    validate_gas_schedule(gas_schedule)?;
 
     // Create a Move VM session so we can invoke on-chain genesis intializations.
     let mut state_view = GenesisStateView::new();
@@ -238,13 +269,17 @@ pub fn encode_genesis_change_set(
         execution_config,
         gas_schedule,
     );
-    initialize_features(&mut session);
+    initialize_features(&mut session, genesis_config);
+    initialize_oidc_providers(&mut session, &genesis_config.oidc_providers);
     if genesis_config.is_test {
         initialize_core_resources_and_aptos_coin(&mut session, core_resources_key);
     } else {
         initialize_aptos_coin(&mut session);
     }
     initialize_on_chain_governance(&mut session, genesis_config);
+    if !genesis_config.delegation_pools.is_empty() {
+        create_delegation_pools(&mut session, &genesis_config.delegation_pools);
+    }
     create_and_initialize_validators(&mut session, validators);
     if genesis_config.is_test {
         allow_core_resources_to_set_version(&mut session);
@@ -283,45 +318,9 @@ pub fn encode_genesis_change_set(
         .concrete_write_set_iter()
         .any(|(_, op)| op.expect("expect only concrete write ops").is_deletion()));
     verify_genesis_write_set(change_set.events());
-    change_set
+    Ok(change_set
         .try_into_storage_change_set()
-        .expect("Constructing a ChangeSet from VMChangeSet should always succeed at genesis")
-}
-
-fn validate_genesis_config(genesis_config: &GenesisConfiguration) {
-    assert!(
-        genesis_config.min_stake <= genesis_config.max_stake,
-        "Min stake must be smaller than or equal to max stake"
-    );
-    assert!(
-        genesis_config.epoch_duration_secs > 0,
-        "Epoch duration must be > 0"
-    );
-    assert!(
-        genesis_config.recurring_lockup_duration_secs > 0,
-        "Recurring lockup duration must be > 0"
-    );
-    assert!(
-        genesis_config.recurring_lockup_duration_secs >= genesis_config.epoch_duration_secs,
-        "Recurring lockup duration must be at least as long as epoch duration"
-    );
-    assert!(
-        genesis_config.rewards_apy_percentage > 0 && genesis_config.rewards_apy_percentage < 100,
-        "Rewards APY must be > 0% and < 100%"
-    );
-    assert!(
-        genesis_config.voting_duration_secs > 0,
-        "On-chain voting duration must be > 0"
-    );
-    assert!(
-        genesis_config.voting_duration_secs < genesis_config.recurring_lockup_duration_secs,
-        "Voting duration must be strictly smaller than recurring lockup"
-    );
-    assert!(
-        genesis_config.voting_power_increase_limit > 0
-            && genesis_config.voting_power_increase_limit <= 50,
-        "voting_power_increase_limit must be > 0 and <= 50"
-    );
+        .expect("Constructing a ChangeSet from VMChangeSet should always succeed at genesis"))
 }
 
 fn exec_function(
@@ -447,15 +446,22 @@ pub fn default_features() -> Vec<FeatureFlag> {
     ]
 }
 
-fn initialize_features(session: &mut SessionExt) {
-    let features: Vec<u64> = default_features()
+fn initialize_features(session: &mut SessionExt, genesis_config: &GenesisConfiguration) {
+    let features_to_enable: Vec<u64> = default_features()
         .into_iter()
+        .chain(genesis_config.features_to_enable.iter().copied())
+        .filter(|feature| !genesis_config.features_to_disable.contains(feature))
         .map(|feature| feature as u64)
         .collect();
+    let features_to_disable: Vec<u64> = genesis_config
+        .features_to_disable
+        .iter()
+        .map(|feature| *feature as u64)
+        .collect();
 
     let mut serialized_values = serialize_values(&vec![MoveValue::Signer(CORE_CODE_ADDRESS)]);
-    serialized_values.push(bcs::to_bytes(&features).unwrap());
-    serialized_values.push(bcs::to_bytes(&Vec::<u64>::new()).unwrap());
+    serialized_values.push(bcs::to_bytes(&features_to_enable).unwrap());
+    serialized_values.push(bcs::to_bytes(&features_to_disable).unwrap());
 
     exec_function(
         session,
@@ -466,6 +472,22 @@ fn initialize_features(session: &mut SessionExt) {
     );
 }
 
+fn initialize_oidc_providers(session: &mut SessionExt, oidc_providers: &[OIDCProvider]) {
+    for provider in oidc_providers {
+        exec_function(
+            session,
+            "jwks",
+            "upsert_oidc_provider",
+            vec![],
+            serialize_values(&vec![
+                MoveValue::Signer(CORE_CODE_ADDRESS),
+                MoveValue::vector_u8(provider.name.as_bytes().to_vec()),
+                MoveValue::vector_u8(provider.config_url.as_bytes().to_vec()),
+            ]),
+        );
+    }
+}
+
 fn initialize_aptos_coin(session: &mut SessionExt) {
     exec_function(
         session,
@@ -588,6 +610,22 @@ fn create_and_initialize_validators_with_commission(
     );
 }
 
+/// Creates each delegation pool along with its owner and operator accounts, and funds and adds
+/// the stake of its initial delegators. This must happen before the validators are initialized,
+/// as that destroys the mint capability.
+fn create_delegation_pools(session: &mut SessionExt, delegation_pools: &[DelegationPool]) {
+    let pools_bytes = bcs::to_bytes(delegation_pools).expect("DelegationPools can be serialized");
+    let mut serialized_values = serialize_values(&vec![MoveValue::Signer(CORE_CODE_ADDRESS)]);
+    serialized_values.push(pools_bytes);
+    exec_function(
+        session,
+        GENESIS_MODULE_NAME,
+        "create_delegation_pools",
+        vec![],
+        serialized_values,
+    );
+}
+
 fn allow_core_resources_to_set_version(session: &mut SessionExt) {
     exec_function(
         session,
@@ -598,9 +636,13 @@ fn allow_core_resources_to_set_version(session: &mut SessionExt) {
     );
 }
 
-/// Publish the framework release bundle.
+/// Publish the framework release bundle. The bundle may also contain custom packages (at any
+/// address), which are published in dependency order along with the framework packages.
 fn publish_framework(session: &mut SessionExt, framework: &ReleaseBundle) {
-    for pack in &framework.packages {
+    let packages = framework
+        .sorted_packages()
+        .unwrap_or_else(|e| panic!("Failure ordering the genesis packages: {:?}", e));
+    for pack in packages {
         publish_package(session, pack)
     }
 }
@@ -828,11 +870,16 @@ pub fn generate_test_genesis(
             voting_power_increase_limit: 50,
             employee_vesting_start: 1663456089,
             employee_vesting_period_duration: 5 * 60, // 5 minutes
+            delegation_pools: vec![],
+            features_to_enable: vec![],
+            features_to_disable: vec![],
+            oidc_providers: vec![],
         },
         &OnChainConsensusConfig::default_for_genesis(),
         &OnChainExecutionConfig::default_for_genesis(),
         &default_gas_schedule(),
-    );
+    )
+    .expect("Test genesis configuration should be valid");
     (genesis, test_validators)
 }
 
@@ -854,14 +901,15 @@ pub fn generate_mainnet_genesis(
         &OnChainConsensusConfig::default_for_genesis(),
         &OnChainExecutionConfig::default_for_genesis(),
         &default_gas_schedule(),
-    );
+    )
+    .expect("Test genesis configuration should be valid");
     (genesis, test_validators)
 }
 
 fn mainnet_genesis_config() -> GenesisConfiguration {
     // TODO: Update once mainnet numbers are decided. These numbers are just placeholders.
     GenesisConfiguration {
-        allow_new_validators: true,
+        allow_new_validators: false,
         epoch_duration_secs: 2 * 3600, // 2 hours
         is_test: false,
         min_stake: 1_000_000 * APTOS_COINS_BASE_WITH_DECIMALS, // 1M APT
@@ -875,6 +923,10 @@ fn mainnet_genesis_config() -> GenesisConfiguration {
         voting_power_increase_limit: 30,
         employee_vesting_start: 1663456089,
         employee_vesting_period_duration: 5 * 60, // 5 minutes
+        delegation_pools: vec![],
+        features_to_enable: vec![],
+        features_to_disable: vec![],
+        oidc_providers: vec![],
     }
 }
 
@@ -902,6 +954,34 @@ pub struct ValidatorWithCommissionRate {
     pub join_during_genesis: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delegation {
+    pub delegator_address: AccountAddress,
+    /// The amount minted to the delegator and added to the pool at genesis.
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationPool {
+    pub owner_address: AccountAddress,
+    pub operator_address: AccountAddress,
+    /// In hundredths of a percent, i.e. 10000 is 100%.
+    pub operator_commission_percentage: u64,
+    /// Seed of the resource account the pool is created at.
+    pub delegation_pool_creation_seed: Vec<u8>,
+    pub delegations: Vec<Delegation>,
+}
+
+/// An OIDC provider to add to `0x1::jwks::SupportedOIDCProviders` at genesis.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OIDCProvider {
+    /// The issuer, e.g., "https://accounts.google.com".
+    pub name: String,
+    /// The OpenID configuration URL of the provider,
+    /// e.g., "https://accounts.google.com/.well-known/openid-configuration".
+    pub config_url: String,
+}
+
 #[test]
 pub fn test_genesis_module_publishing() {
     // create a state view for move_vm
@@ -928,6 +1008,46 @@ pub fn test_genesis_module_publishing() {
     publish_framework(&mut session, aptos_cached_packages::head_release_bundle());
 }
 
+#[test]
+pub fn test_genesis_publishing_resolves_package_order() {
+    // Reverse the packages, so that every package precedes its dependencies
+    let framework = aptos_cached_packages::head_release_bundle();
+    let mut packages = framework.packages.clone();
+    packages.reverse();
+    let reversed_framework = ReleaseBundle::new(packages, vec![]);
+
+    // Verify the packages are sorted regarding dependencies
+    let sorted_packages = reversed_framework.sorted_packages().unwrap();
+    assert_eq!(sorted_packages.len(), framework.packages.len());
+    for (index, pack) in sorted_packages.iter().enumerate() {
+        for dep in &pack.package_metadata().deps {
+            assert!(sorted_packages[..index]
+                .iter()
+                .any(|prev| prev.name() == dep.package_name
+                    && prev.address().unwrap() == dep.account));
+        }
+    }
+
+    // Verify the reversed bundle can be published at genesis
+    let mut state_view = GenesisStateView::new();
+    for (module_bytes, module) in reversed_framework.code_and_compiled_modules() {
+        state_view.add_module(&module.self_id(), module_bytes);
+    }
+    let data_cache = state_view.as_move_resolver();
+    let move_vm = MoveVmExt::new(
+        NativeGasParameters::zeros(),
+        MiscGasParameters::zeros(),
+        LATEST_GAS_FEATURE_VERSION,
+        ChainId::test().id(),
+        Features::default(),
+        TimedFeaturesBuilder::enable_all().build(),
+        &data_cache,
+    )
+    .unwrap();
+    let mut session = move_vm.new_session(&data_cache, SessionId::genesis(HashValue::zero()));
+    publish_framework(&mut session, &reversed_framework);
+}
+
 #[test]
 pub fn test_mainnet_end_to_end() {
     use aptos_types::{
@@ -1121,7 +1241,8 @@ pub fn test_mainnet_end_to_end() {
         aptos_cached_packages::head_release_bundle(),
         ChainId::mainnet(),
         &mainnet_genesis_config(),
-    );
+    )
+    .unwrap();
 
     let direct_writeset = if let Transaction::GenesisTransaction(direct_writeset) = transaction {
         direct_writeset
@@ -1175,3 +1296,280 @@ pub fn test_mainnet_end_to_end() {
     // join_during_genesis = false.
     assert!(!validator_set_addresses.contains(&same_owner_validator_3_pool_address));
 }
+
+#[test]
+pub fn test_mainnet_genesis_rejects_invalid_config() {
+    let encode = |genesis_config: &GenesisConfiguration| {
+        encode_aptos_mainnet_genesis_transaction(
+            &[],
+            &[],
+            &[],
+            aptos_cached_packages::head_release_bundle(),
+            ChainId::mainnet(),
+            genesis_config,
+        )
+        .err()
+    };
+
+    // The mainnet invariants are enforced before anything is executed
+    assert_eq!(
+        encode(&GenesisConfiguration {
+            allow_new_validators: true,
+            ..mainnet_genesis_config()
+        }),
+        Some(GenesisConfigError::NewValidatorsAllowedOnMainnet)
+    );
+    assert_eq!(
+        encode(&GenesisConfiguration {
+            min_stake: 0,
+            ..mainnet_genesis_config()
+        }),
+        Some(GenesisConfigError::ZeroMinStakeOnMainnet)
+    );
+    assert_eq!(
+        encode(&GenesisConfiguration {
+            is_test: true,
+            ..mainnet_genesis_config()
+        }),
+        Some(GenesisConfigError::TestChainOnMainnet)
+    );
+    assert_eq!(
+        encode(&GenesisConfiguration {
+            epoch_duration_secs: 0,
+            ..mainnet_genesis_config()
+        }),
+        Some(GenesisConfigError::ZeroEpochDuration)
+    );
+}
+
+#[test]
+pub fn test_mainnet_genesis_delegation_pools() {
+    use aptos_types::{
+        access_path::AccessPath, account_config::CoinStoreResource,
+        state_store::state_key::StateKey,
+    };
+    use move_core_types::move_resource::MoveStructType;
+
+    let balance = 10_000_000 * APTOS_COINS_BASE_WITH_DECIMALS;
+    let non_validator_balance = 10 * APTOS_COINS_BASE_WITH_DECIMALS;
+    let delegated_amount = 1_000 * APTOS_COINS_BASE_WITH_DECIMALS;
+
+    let owner = AccountAddress::from_hex_literal("0x44").unwrap();
+    let operator = AccountAddress::from_hex_literal("0x100").unwrap();
+    let voter = AccountAddress::from_hex_literal("0x200").unwrap();
+    let pool_owner = AccountAddress::from_hex_literal("0x300").unwrap();
+    let existing_delegator = AccountAddress::from_hex_literal("0x301").unwrap();
+    let new_delegator = AccountAddress::from_hex_literal("0x302").unwrap();
+
+    let accounts: Vec<_> = [
+        (owner, balance),
+        (operator, non_validator_balance),
+        (voter, non_validator_balance),
+        (pool_owner, non_validator_balance),
+        (existing_delegator, non_validator_balance),
+    ]
+    .into_iter()
+    .map(|(account_address, balance)| AccountBalance {
+        account_address,
+        balance,
+    })
+    .collect();
+
+    let mut validator = TestValidator::new_test_set(Some(1), Some(balance * 9 / 10))[0]
+        .data
+        .clone();
+    validator.owner_address = owner;
+    validator.operator_address = operator;
+    validator.voter_address = voter;
+    let validators = vec![ValidatorWithCommissionRate {
+        validator,
+        validator_commission_percentage: 10,
+        join_during_genesis: true,
+    }];
+
+    let genesis_config = GenesisConfiguration {
+        delegation_pools: vec![DelegationPool {
+            owner_address: pool_owner,
+            operator_address: pool_owner,
+            operator_commission_percentage: 1000,
+            delegation_pool_creation_seed: vec![1],
+            delegations: vec![
+                Delegation {
+                    delegator_address: existing_delegator,
+                    amount: delegated_amount,
+                },
+                Delegation {
+                    delegator_address: new_delegator,
+                    amount: delegated_amount,
+                },
+            ],
+        }],
+        ..mainnet_genesis_config()
+    };
+    let transaction = encode_aptos_mainnet_genesis_transaction(
+        &accounts,
+        &[],
+        &validators,
+        aptos_cached_packages::head_release_bundle(),
+        ChainId::mainnet(),
+        &genesis_config,
+    )
+    .unwrap();
+    let change_set = match transaction {
+        Transaction::GenesisTransaction(WriteSetPayload::Direct(change_set)) => change_set,
+        _ => panic!("Invalid GenesisTransaction"),
+    };
+
+    // The delegated coins are minted on top of the balances, so delegating doesn't
+    // take anything out of the balance an existing delegator was given.
+    let coin_balance = |address: AccountAddress| {
+        let state_key = StateKey::access_path(
+            AccessPath::resource_access_path(address, CoinStoreResource::struct_tag()).unwrap(),
+        );
+        let bytes = change_set
+            .write_set()
+            .get(&state_key)
+            .unwrap()
+            .extract_raw_bytes()
+            .unwrap();
+        bcs::from_bytes::<CoinStoreResource>(&bytes).unwrap().coin()
+    };
+    assert_eq!(coin_balance(existing_delegator), non_validator_balance);
+    assert_eq!(coin_balance(new_delegator), 0);
+    assert_eq!(coin_balance(pool_owner), non_validator_balance);
+}
+
+#[test]
+pub fn test_genesis_dry_run_report() {
+    let (change_set, test_validators) = test_genesis_change_set_and_validators(Some(3));
+    let report = dry_run_genesis_change_set(&change_set).unwrap();
+
+    // Verify the chain id and on-chain configs
+    assert_eq!(report.chain_id, Some(ChainId::test().id()));
+    assert_eq!(
+        report.on_chain_configs.version,
+        Some(APTOS_MAX_KNOWN_VERSION.major)
+    );
+    assert_eq!(
+        report.on_chain_configs.gas_feature_version,
+        Some(LATEST_GAS_FEATURE_VERSION)
+    );
+    for feature in default_features() {
+        assert!(report
+            .on_chain_configs
+            .enabled_features
+            .contains(&(feature as u64)));
+    }
+    assert!(report.on_chain_configs.consensus_config.is_some());
+    assert!(report.on_chain_configs.execution_config.is_some());
+
+    // Verify the validator set and the created accounts
+    let validator_set = report.validator_set.unwrap();
+    assert_eq!(validator_set.active_validators.len(), test_validators.len());
+    for test_validator in &test_validators {
+        let owner_address = test_validator.data.owner_address;
+        assert!(validator_set
+            .active_validators
+            .iter()
+            .any(|validator| validator.address == owner_address));
+        assert!(report
+            .accounts
+            .iter()
+            .any(|account| account.address == owner_address));
+    }
+
+    // Verify the events (including the single new epoch event)
+    assert_eq!(report.events.len(), change_set.events().len());
+    let new_epoch_event_key = NewEpochEvent::event_key();
+    assert_eq!(
+        report
+            .events
+            .iter()
+            .filter(|event| event.event_key == Some(new_epoch_event_key))
+            .count(),
+        1
+    );
+}
+
+#[test]
+pub fn test_genesis_feature_flag_overrides() {
+    // Create a genesis that enables and disables features on top of the defaults
+    let test_validators = TestValidator::new_test_set(Some(1), Some(1_000_000_000_000_000));
+    let validators: Vec<Validator> = test_validators.iter().map(|t| t.data.clone()).collect();
+    let genesis_config = GenesisConfiguration {
+        features_to_enable: vec![FeatureFlag::RESOURCE_GROUPS_CHARGE_AS_SIZE_SUM],
+        features_to_disable: vec![FeatureFlag::BN254_STRUCTURES],
+        ..mainnet_genesis_config()
+    };
+    let change_set = encode_genesis_change_set(
+        &GENESIS_KEYPAIR.1,
+        &validators,
+        aptos_cached_packages::head_release_bundle(),
+        ChainId::test(),
+        &genesis_config,
+        &OnChainConsensusConfig::default_for_genesis(),
+        &OnChainExecutionConfig::default_for_genesis(),
+        &default_gas_schedule(),
+    )
+    .unwrap();
+
+    // Verify the feature flags were initialized correctly
+    let report = dry_run_genesis_change_set(&change_set).unwrap();
+    let enabled_features = report.on_chain_configs.enabled_features;
+    assert!(enabled_features.contains(&(FeatureFlag::RESOURCE_GROUPS_CHARGE_AS_SIZE_SUM as u64)));
+    assert!(!enabled_features.contains(&(FeatureFlag::BN254_STRUCTURES as u64)));
+    for feature in default_features() {
+        if feature != FeatureFlag::BN254_STRUCTURES {
+            assert!(enabled_features.contains(&(feature as u64)));
+        }
+    }
+}
+
+#[test]
+pub fn test_genesis_oidc_providers() {
+    use aptos_types::{
+        access_path::AccessPath, jwks::SupportedOIDCProviders, state_store::state_key::StateKey,
+    };
+    use move_core_types::move_resource::MoveStructType;
+
+    // Create a genesis that configures an OIDC provider
+    let test_validators = TestValidator::new_test_set(Some(1), Some(1_000_000_000_000_000));
+    let validators: Vec<Validator> = test_validators.iter().map(|t| t.data.clone()).collect();
+    let genesis_config = GenesisConfiguration {
+        oidc_providers: vec![OIDCProvider {
+            name: "https://accounts.google.com".to_string(),
+            config_url: "https://accounts.google.com/.well-known/openid-configuration".to_string(),
+        }],
+        ..mainnet_genesis_config()
+    };
+    let change_set = encode_genesis_change_set(
+        &GENESIS_KEYPAIR.1,
+        &validators,
+        aptos_cached_packages::head_release_bundle(),
+        ChainId::test(),
+        &genesis_config,
+        &OnChainConsensusConfig::default_for_genesis(),
+        &OnChainExecutionConfig::default_for_genesis(),
+        &default_gas_schedule(),
+    )
+    .unwrap();
+    dry_run_genesis_change_set(&change_set).unwrap();
+
+    // Verify the provider was added to the supported OIDC providers
+    let state_key = StateKey::access_path(
+        AccessPath::resource_access_path(CORE_CODE_ADDRESS, SupportedOIDCProviders::struct_tag())
+            .unwrap(),
+    );
+    let bytes = change_set
+        .write_set()
+        .get(&state_key)
+        .unwrap()
+        .extract_raw_bytes()
+        .unwrap();
+    let supported_providers: SupportedOIDCProviders = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(supported_providers.providers.len(), 1);
+    assert_eq!(
+        supported_providers.providers[0].name,
+        b"https://accounts.google.com".to_vec()
+    );
+}
@@ -0,0 +1,317 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::GenesisConfiguration;
+use aptos_gas_schedule::{AptosGasParameters, FromOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION};
+use aptos_types::{chain_id::ChainId, on_chain_config::GasScheduleV2};
+use thiserror::Error;
+
+/// The maximum voting power increase limit (in percent) per epoch
+const MAX_VOTING_POWER_INCREASE_LIMIT: u64 = 50;
+
+/// The maximum delegation pool operator commission (in basis points, i.e., 100%)
+const MAX_OPERATOR_COMMISSION_PERCENTAGE: u64 = 10_000;
+
+/// An invariant violated by a genesis configuration
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum GenesisConfigError {
+    #[error("Min stake ({0}) must be smaller than or equal to max stake ({1})")]
+    MinStakeAboveMaxStake(u64, u64),
+    #[error("Epoch duration must be > 0")]
+    ZeroEpochDuration,
+    #[error("Recurring lockup duration must be > 0")]
+    ZeroRecurringLockupDuration,
+    #[error("Recurring lockup duration must be at least as long as epoch duration")]
+    RecurringLockupShorterThanEpoch,
+    #[error("Rewards APY must be > 0% and < 100%, found: {0}%")]
+    InvalidRewardsApy(u64),
+    #[error("On-chain voting duration must be > 0")]
+    ZeroVotingDuration,
+    #[error("Feature flags cannot be both enabled and disabled at genesis")]
+    ConflictingFeatureFlags,
+    #[error("Voting duration must be strictly smaller than recurring lockup")]
+    VotingDurationNotShorterThanLockup,
+    #[error("voting_power_increase_limit must be > 0 and <= 50, found: {0}")]
+    InvalidVotingPowerIncreaseLimit(u64),
+    #[error("Delegation pool operator commission must be <= 10000 (100%), found: {0}")]
+    InvalidOperatorCommission(u64),
+    #[error("OIDC provider name and config URL cannot be empty")]
+    EmptyOIDCProvider,
+    #[error("OIDC provider {0} is configured more than once")]
+    DuplicateOIDCProvider(String),
+    #[error("New validators cannot be allowed to join at the launch of mainnet")]
+    NewValidatorsAllowedOnMainnet,
+    #[error("The min stake must be > 0 on mainnet")]
+    ZeroMinStakeOnMainnet,
+    #[error("Mainnet cannot have a core resources account (that can mint coins and publish code)")]
+    TestChainOnMainnet,
+    #[error("Gas schedule blob cannot be decoded: {0}")]
+    MalformedGasScheduleBlob(String),
+    #[error("Gas schedule feature version ({0}) is newer than the latest known version ({1})")]
+    UnknownGasFeatureVersion(u64, u64),
+    #[error("Gas schedule is invalid: {0}")]
+    InvalidGasSchedule(String),
+}
+
+/// Validates the chain-independent invariants of the genesis configuration
+pub fn validate_genesis_config(
+    genesis_config: &GenesisConfiguration,
+) -> Result<(), GenesisConfigError> {
+    if genesis_config.min_stake > genesis_config.max_stake {
+        return Err(GenesisConfigError::MinStakeAboveMaxStake(
+            genesis_config.min_stake,
+            genesis_config.max_stake,
+        ));
+    }
+    if genesis_config.epoch_duration_secs == 0 {
+        return Err(GenesisConfigError::ZeroEpochDuration);
+    }
+    if genesis_config.recurring_lockup_duration_secs == 0 {
+        return Err(GenesisConfigError::ZeroRecurringLockupDuration);
+    }
+    if genesis_config.recurring_lockup_duration_secs < genesis_config.epoch_duration_secs {
+        return Err(GenesisConfigError::RecurringLockupShorterThanEpoch);
+    }
+    if genesis_config.rewards_apy_percentage == 0 || genesis_config.rewards_apy_percentage >= 100 {
+        return Err(GenesisConfigError::InvalidRewardsApy(
+            genesis_config.rewards_apy_percentage,
+        ));
+    }
+    if genesis_config.voting_duration_secs == 0 {
+        return Err(GenesisConfigError::ZeroVotingDuration);
+    }
+    if genesis_config
+        .features_to_enable
+        .iter()
+        .any(|feature| genesis_config.features_to_disable.contains(feature))
+    {
+        return Err(GenesisConfigError::ConflictingFeatureFlags);
+    }
+    if genesis_config.voting_duration_secs >= genesis_config.recurring_lockup_duration_secs {
+        return Err(GenesisConfigError::VotingDurationNotShorterThanLockup);
+    }
+    if genesis_config.voting_power_increase_limit == 0
+        || genesis_config.voting_power_increase_limit > MAX_VOTING_POWER_INCREASE_LIMIT
+    {
+        return Err(GenesisConfigError::InvalidVotingPowerIncreaseLimit(
+            genesis_config.voting_power_increase_limit,
+        ));
+    }
+    for pool in &genesis_config.delegation_pools {
+        if pool.operator_commission_percentage > MAX_OPERATOR_COMMISSION_PERCENTAGE {
+            return Err(GenesisConfigError::InvalidOperatorCommission(
+                pool.operator_commission_percentage,
+            ));
+        }
+    }
+    for (i, provider) in genesis_config.oidc_providers.iter().enumerate() {
+        if provider.name.is_empty() || provider.config_url.is_empty() {
+            return Err(GenesisConfigError::EmptyOIDCProvider);
+        }
+        if genesis_config.oidc_providers[..i]
+            .iter()
+            .any(|other| other.name == provider.name)
+        {
+            return Err(GenesisConfigError::DuplicateOIDCProvider(
+                provider.name.clone(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validates the genesis configuration for the given chain. On top of the
+/// chain-independent invariants, mainnet requires a permissioned launch
+/// (no new validators, a non-zero min stake and no test-only accounts).
+pub fn validate_genesis_config_for_chain(
+    genesis_config: &GenesisConfiguration,
+    chain_id: ChainId,
+) -> Result<(), GenesisConfigError> {
+    validate_genesis_config(genesis_config)?;
+
+    if chain_id.is_mainnet() {
+        if genesis_config.allow_new_validators {
+            return Err(GenesisConfigError::NewValidatorsAllowedOnMainnet);
+        }
+        if genesis_config.min_stake == 0 {
+            return Err(GenesisConfigError::ZeroMinStakeOnMainnet);
+        }
+        if genesis_config.is_test {
+            return Err(GenesisConfigError::TestChainOnMainnet);
+        }
+    }
+    Ok(())
+}
+
+/// Validates that the gas schedule defines all the gas parameters required at its
+/// feature version (e.g. when overriding the default gas schedule at genesis).
+pub fn validate_gas_schedule(gas_schedule: &GasScheduleV2) -> Result<(), GenesisConfigError> {
+    if gas_schedule.feature_version > LATEST_GAS_FEATURE_VERSION {
+        return Err(GenesisConfigError::UnknownGasFeatureVersion(
+            gas_schedule.feature_version,
+            LATEST_GAS_FEATURE_VERSION,
+        ));
+    }
+    let entries = gas_schedule.entries.iter().cloned().collect();
+    AptosGasParameters::from_on_chain_gas_schedule(&entries, gas_schedule.feature_version)
+        .map_err(GenesisConfigError::InvalidGasSchedule)?;
+    Ok(())
+}
+
+/// Decodes a BCS-encoded gas schedule blob (e.g. one fetched from another network),
+/// and validates it.
+pub fn gas_schedule_from_blob(blob: &[u8]) -> Result<GasScheduleV2, GenesisConfigError> {
+    let gas_schedule: GasScheduleV2 = bcs::from_bytes(blob)
+        .map_err(|error| GenesisConfigError::MalformedGasScheduleBlob(error.to_string()))?;
+    validate_gas_schedule(&gas_schedule)?;
+    Ok(gas_schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{default_gas_schedule, mainnet_genesis_config};
+
+    /// Returns a valid genesis configuration for mainnet
+    fn valid_mainnet_config() -> GenesisConfiguration {
+        mainnet_genesis_config()
+    }
+
+    #[test]
+    fn test_chain_independent_invariants() {
+        let valid_config = valid_mainnet_config();
+        assert_eq!(validate_genesis_config(&valid_config), Ok(()));
+
+        let invalid_configs = [
+            (
+                GenesisConfiguration {
+                    min_stake: valid_config.max_stake + 1,
+                    ..valid_mainnet_config()
+                },
+                GenesisConfigError::MinStakeAboveMaxStake(
+                    valid_config.max_stake + 1,
+                    valid_config.max_stake,
+                ),
+            ),
+            (
+                GenesisConfiguration {
+                    epoch_duration_secs: 0,
+                    ..valid_mainnet_config()
+                },
+                GenesisConfigError::ZeroEpochDuration,
+            ),
+            (
+                GenesisConfiguration {
+                    rewards_apy_percentage: 100,
+                    ..valid_mainnet_config()
+                },
+                GenesisConfigError::InvalidRewardsApy(100),
+            ),
+            (
+                GenesisConfiguration {
+                    voting_duration_secs: valid_config.recurring_lockup_duration_secs,
+                    ..valid_mainnet_config()
+                },
+                GenesisConfigError::VotingDurationNotShorterThanLockup,
+            ),
+            (
+                GenesisConfiguration {
+                    voting_power_increase_limit: 51,
+                    ..valid_mainnet_config()
+                },
+                GenesisConfigError::InvalidVotingPowerIncreaseLimit(51),
+            ),
+        ];
+        for (config, expected_error) in invalid_configs {
+            // The chain-independent invariants apply to all chains
+            for chain_id in [ChainId::test(), ChainId::testnet(), ChainId::mainnet()] {
+                assert_eq!(
+                    validate_genesis_config_for_chain(&config, chain_id),
+                    Err(expected_error.clone())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_chain_specific_invariants() {
+        // A matrix of (config, error on testnet, error on mainnet)
+        let test_cases = [
+            (valid_mainnet_config(), None, None),
+            (
+                GenesisConfiguration {
+                    allow_new_validators: true,
+                    ..valid_mainnet_config()
+                },
+                None,
+                Some(GenesisConfigError::NewValidatorsAllowedOnMainnet),
+            ),
+            (
+                GenesisConfiguration {
+                    min_stake: 0,
+                    ..valid_mainnet_config()
+                },
+                None,
+                Some(GenesisConfigError::ZeroMinStakeOnMainnet),
+            ),
+            (
+                GenesisConfiguration {
+                    is_test: true,
+                    ..valid_mainnet_config()
+                },
+                None,
+                Some(GenesisConfigError::TestChainOnMainnet),
+            ),
+        ];
+        for (config, testnet_error, mainnet_error) in test_cases {
+            for chain_id in [ChainId::test(), ChainId::testnet()] {
+                assert_eq!(
+                    validate_genesis_config_for_chain(&config, chain_id).err(),
+                    testnet_error
+                );
+            }
+            assert_eq!(
+                validate_genesis_config_for_chain(&config, ChainId::mainnet()).err(),
+                mainnet_error
+            );
+        }
+    }
+
+    #[test]
+    fn test_gas_schedule_override() {
+        // The default gas schedule round trips through a blob
+        let gas_schedule = default_gas_schedule();
+        let blob = bcs::to_bytes(&gas_schedule).unwrap();
+        assert_eq!(gas_schedule_from_blob(&blob), Ok(gas_schedule.clone()));
+
+        // Gas parameters can be overridden
+        let mut overridden_gas_schedule = gas_schedule.clone();
+        overridden_gas_schedule.entries[0].1 *= 2;
+        assert_eq!(validate_gas_schedule(&overridden_gas_schedule), Ok(()));
+
+        // But not removed
+        let mut incomplete_gas_schedule = gas_schedule.clone();
+        incomplete_gas_schedule.entries.pop();
+        assert!(matches!(
+            validate_gas_schedule(&incomplete_gas_schedule),
+            Err(GenesisConfigError::InvalidGasSchedule(_))
+        ));
+
+        // And the feature version needs to be known
+        let future_gas_schedule = GasScheduleV2 {
+            feature_version: LATEST_GAS_FEATURE_VERSION + 1,
+            ..gas_schedule
+        };
+        assert_eq!(
+            validate_gas_schedule(&future_gas_schedule),
+            Err(GenesisConfigError::UnknownGasFeatureVersion(
+                LATEST_GAS_FEATURE_VERSION + 1,
+                LATEST_GAS_FEATURE_VERSION
+            ))
+        );
+        assert!(matches!(
+            gas_schedule_from_blob(&[1, 2, 3]),
+            Err(GenesisConfigError::MalformedGasScheduleBlob(_))
+        ));
+    }
+}
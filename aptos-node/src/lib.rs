@@ -622,14 +622,20 @@ pub fn setup_environment_and_start_node(
     );
 
     // Start state sync and get the notification endpoints for mempool and consensus
-    let (aptos_data_client, state_sync_runtimes, mempool_listener, consensus_notifier) =
-        state_sync::start_state_sync_and_get_notification_handles(
-            &node_config,
-            storage_service_network_interfaces,
-            genesis_waypoint,
-            event_subscription_service,
-            db_rw.clone(),
-        )?;
+    let (
+        aptos_data_client,
+        state_sync_runtimes,
+        mempool_listener,
+        consensus_notifier,
+        storage_service_analytics,
+    ) = state_sync::start_state_sync_and_get_notification_handles(
+        &node_config,
+        storage_service_network_interfaces,
+        genesis_waypoint,
+        event_subscription_service,
+        db_rw.clone(),
+    )?;
+    admin_service.set_storage_service_analytics(storage_service_analytics);
 
     // Start the node inspection service
     services::start_node_inspection_service(
@@ -726,16 +732,18 @@ pub fn setup_environment_and_start_node(
         debug!("State sync initialization complete.");
 
         // Initialize and start consensus
-        let (runtime, consensus_db, quorum_store_db) = services::start_consensus_runtime(
-            &mut node_config,
-            db_rw,
-            consensus_reconfig_subscription,
-            consensus_network_interfaces,
-            consensus_notifier,
-            consensus_to_mempool_sender,
-            vtxn_read_client,
-        );
+        let (runtime, consensus_db, quorum_store_db, dag_status_provider) =
+            services::start_consensus_runtime(
+                &mut node_config,
+                db_rw,
+                consensus_reconfig_subscription,
+                consensus_network_interfaces,
+                consensus_notifier,
+                consensus_to_mempool_sender,
+                vtxn_read_client,
+            );
         admin_service.set_consensus_dbs(consensus_db, quorum_store_db);
+        admin_service.set_dag_status_provider(dag_status_provider);
         runtime
     });
 
@@ -7,7 +7,7 @@ use aptos_build_info::build_information;
 use aptos_config::config::NodeConfig;
 use aptos_consensus::{
     network_interface::ConsensusMsg, persistent_liveness_storage::StorageWriteProxy,
-    quorum_store::quorum_store_db::QuorumStoreDB,
+    quorum_store::quorum_store_db::QuorumStoreDB, DagStatusProvider,
 };
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_data_client::client::AptosDataClient;
@@ -105,7 +105,12 @@ pub fn start_consensus_runtime(
     consensus_notifier: ConsensusNotifier,
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     validator_txn_pool_client: vtxn_pool::ReadClient,
-) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
+) -> (
+    Runtime,
+    Arc<StorageWriteProxy>,
+    Arc<QuorumStoreDB>,
+    Arc<DagStatusProvider>,
+) {
     let instant = Instant::now();
     let consensus = aptos_consensus::consensus_provider::start_consensus(
         node_config,
@@ -28,6 +28,7 @@ use aptos_storage_interface::{DbReader, DbReaderWriter};
 use aptos_storage_service_client::StorageServiceClient;
 use aptos_storage_service_notifications::StorageServiceNotificationListener;
 use aptos_storage_service_server::{
+    analytics::StorageServiceAnalytics, config_handle::StorageServiceConfigHandle,
     network::StorageServiceNetworkEvents, storage::StorageReader, StorageServiceServer,
 };
 use aptos_storage_service_types::StorageServiceMessage;
@@ -120,6 +121,7 @@ pub fn start_state_sync_and_get_notification_handles(
     StateSyncRuntimes,
     MempoolNotificationListener,
     ConsensusNotifier,
+    StorageServiceAnalytics,
 )> {
     // Get the network client and events
     let network_client = storage_network_interfaces.network_client;
@@ -131,9 +133,9 @@ pub fn start_state_sync_and_get_notification_handles(
         setup_aptos_data_client(node_config, network_client, db_rw.reader.clone())?;
 
     // Start the data streaming service
-    let state_sync_config = node_config.state_sync;
+    let state_sync_config = node_config.state_sync.clone();
     let (streaming_service_client, streaming_service_runtime) =
-        setup_data_streaming_service(state_sync_config, aptos_data_client.clone())?;
+        setup_data_streaming_service(state_sync_config.clone(), aptos_data_client.clone())?;
 
     // Create the chunk executor and persistent storage
     let chunk_executor = Arc::new(ChunkExecutor::<AptosVM>::new(db_rw.clone()));
@@ -156,7 +158,7 @@ pub fn start_state_sync_and_get_notification_handles(
         aptos_storage_service_notifications::new_storage_service_notifier_listener_pair();
 
     // Start the state sync storage service
-    let storage_service_runtime = setup_state_sync_storage_service(
+    let (storage_service_runtime, storage_service_analytics) = setup_state_sync_storage_service(
         state_sync_config,
         peers_and_metadata,
         network_service_events,
@@ -194,6 +196,7 @@ pub fn start_state_sync_and_get_notification_handles(
         state_sync_runtimes,
         mempool_listener,
         consensus_notifier,
+        storage_service_analytics,
     ))
 }
 
@@ -246,21 +249,30 @@ fn setup_aptos_data_client(
     Ok((aptos_data_client, aptos_data_client_runtime))
 }
 
-/// Sets up the state sync storage service runtime
+/// Sets up the state sync storage service runtime (and returns a handle to its analytics)
 fn setup_state_sync_storage_service(
     config: StateSyncConfig,
     peers_and_metadata: Arc<PeersAndMetadata>,
     network_service_events: NetworkServiceEvents<StorageServiceMessage>,
     db_rw: &DbReaderWriter,
     storage_service_listener: StorageServiceNotificationListener,
-) -> anyhow::Result<Runtime> {
+) -> anyhow::Result<(Runtime, StorageServiceAnalytics)> {
     // Create a new state sync storage service runtime
     let storage_service_runtime = aptos_runtimes::spawn_named_runtime("stor-server".into(), None);
 
+    // Create a config handle shared by the storage reader and server (so
+    // that the storage service config can be updated at runtime).
+    let storage_service_config_handle = StorageServiceConfigHandle::new(config.storage_service);
+
     // Spawn the state sync storage service servers on the runtime
-    let storage_reader = StorageReader::new(config.storage_service, Arc::clone(&db_rw.reader));
-    let service = StorageServiceServer::new(
-        config,
+    let storage_reader = StorageReader::new_with_config_handle(
+        storage_service_config_handle.clone(),
+        Arc::clone(&db_rw.reader),
+    )
+    .with_trusted_peers(config.storage_service_trusted_peers);
+    let service = StorageServiceServer::new_with_config_handle(
+        config.aptos_data_client,
+        storage_service_config_handle,
         storage_service_runtime.handle().clone(),
         storage_reader,
         TimeService::real(),
@@ -268,7 +280,8 @@ fn setup_state_sync_storage_service(
         StorageServiceNetworkEvents::new(network_service_events),
         storage_service_listener,
     );
+    let storage_service_analytics = service.get_analytics();
     storage_service_runtime.spawn(service.start());
 
-    Ok(storage_service_runtime)
+    Ok((storage_service_runtime, storage_service_analytics))
 }
@@ -60,4 +60,8 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     {
         AptosVM::set_processed_transactions_detailed_counters();
     }
+
+    if node_config.execution.block_executor_contention_stats {
+        AptosVM::set_block_executor_contention_stats();
+    }
 }
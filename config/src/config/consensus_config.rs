@@ -66,6 +66,8 @@ pub struct ConsensusConfig {
     pub window_for_chain_health: usize,
     pub chain_health_backoff: Vec<ChainHealthBackoffValues>,
     pub qc_aggregator_type: QcAggregatorType,
+    // Decides how the payload of a proposed block is ordered
+    pub payload_prioritization: PayloadPrioritizationConfig,
     // Max blocks allowed for block retrieval requests
     pub max_blocks_per_sending_request: u64,
     pub max_blocks_per_sending_request_quorum_store_override: u64,
@@ -117,6 +119,29 @@ impl Default for DelayedQcAggregatorConfig {
     }
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PayloadPrioritizationConfig {
+    // The policy used to order the user payload pulled for a proposed block
+    pub policy: PayloadPrioritizationPolicy,
+    // Maximum number of validator txns in a proposed block. If not set,
+    // validator txns are only limited by the block limits.
+    pub max_validator_txns_per_block: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PayloadPrioritizationPolicy {
+    // Keep the user payload in the order it was pulled in
+    #[default]
+    Fifo,
+    // Order the user txns (or batches) by gas price, while keeping
+    // the txns of each account in order
+    GasPrice,
+    // Interleave the user txns (or batches) of different accounts
+    // (or batch authors), so that no single account dominates the block
+    AccountFairness,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub struct PipelineBackpressureValues {
     pub back_pressure_pipeline_latency_limit_ms: u64,
@@ -277,6 +302,7 @@ impl Default for ConsensusConfig {
             ],
 
             qc_aggregator_type: QcAggregatorType::default(),
+            payload_prioritization: PayloadPrioritizationConfig::default(),
             // This needs to fit into the network message size, so with quorum store it can be much bigger
             max_blocks_per_sending_request: 10,
             // TODO: this is for release compatibility, after release we can configure it to match the receiving max
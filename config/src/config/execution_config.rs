@@ -35,6 +35,9 @@ pub struct ExecutionConfig {
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
     pub processed_transactions_detailed_counters: bool,
+    /// Enables per-key contention stats (write-write conflicts and read dependencies)
+    /// in parallel execution, which are exported as metrics after each block
+    pub block_executor_contention_stats: bool,
     /// Enables filtering of transactions before they are sent to execution
     pub transaction_filter: Filter,
     /// Used during DB bootstrapping
@@ -68,6 +71,7 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             processed_transactions_detailed_counters: false,
+            block_executor_contention_stats: false,
             transaction_filter: Filter::empty(),
             genesis_waypoint: None,
         }
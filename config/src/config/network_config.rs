@@ -53,6 +53,7 @@ pub const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; /* 64 MiB */
 pub const CONNECTION_BACKOFF_BASE: u64 = 2;
 pub const IP_BYTE_BUCKET_RATE: usize = 102400 /* 100 KiB */;
 pub const IP_BYTE_BUCKET_SIZE: usize = IP_BYTE_BUCKET_RATE;
+pub const INBOUND_STREAM_TIMEOUT_MS: u64 = 60_000; /* 1 minute */
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -125,6 +126,12 @@ pub struct NetworkConfig {
     pub max_message_size: usize,
     /// The maximum number of parallel message deserialization tasks that can run (per application)
     pub max_parallel_deserialization_tasks: Option<usize>,
+    /// Per-protocol policies for when the queue of outbound messages (waiting behind large,
+    /// streamed messages) of a protocol is full, keyed by protocol name (e.g.,
+    /// "StorageServiceRpc"). By default, all protocols apply backpressure.
+    pub outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+    /// Limits for the reassembly of large (streamed) inbound messages
+    pub inbound_stream_config: InboundStreamConfig,
 }
 
 impl Default for NetworkConfig {
@@ -166,6 +173,8 @@ impl NetworkConfig {
             outbound_rx_buffer_size_bytes: None,
             outbound_tx_buffer_size_bytes: None,
             max_parallel_deserialization_tasks: None,
+            outbound_queue_policies: HashMap::new(),
+            inbound_stream_config: InboundStreamConfig::default(),
         };
 
         // Configure the number of parallel deserialization tasks
@@ -365,6 +374,35 @@ pub struct RestDiscovery {
     pub interval_secs: u64,
 }
 
+/// The policy applied to an outbound message when the outbound queue of its protocol
+/// is already full. RPC requests and responses are never dropped.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboundQueuePolicy {
+    DropNewest, // Drop the new direct send message
+    DropOldest, // Drop the oldest queued direct send message to make room for the new one
+    Block,      // Delay the senders of the protocol until the queue has room
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InboundStreamConfig {
+    /// Time (in ms) a remote peer has to send all fragments of a stream (after
+    /// the header) before the incomplete stream is discarded
+    pub stream_timeout_ms: u64,
+    /// Whether to disconnect from a remote peer when one of its streams times out
+    pub disconnect_on_timeout: bool,
+}
+
+impl Default for InboundStreamConfig {
+    fn default() -> Self {
+        Self {
+            stream_timeout_ms: INBOUND_STREAM_TIMEOUT_MS,
+            disconnect_on_timeout: false,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct RateLimitConfig {
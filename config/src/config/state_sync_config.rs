@@ -6,9 +6,10 @@ use crate::config::{
     config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
     node_config_loader::NodeType, Error, NodeConfig,
 };
-use aptos_types::chain_id::ChainId;
+use aptos_types::{chain_id::ChainId, PeerId};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::collections::HashSet;
 
 // The maximum message size per state sync message
 const MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024; /* 4 MiB */
@@ -23,13 +24,18 @@ const MAX_TRANSACTION_OUTPUT_CHUNK_SIZE: u64 = 1000;
 const MAX_CONCURRENT_REQUESTS: u64 = 6;
 const MAX_CONCURRENT_STATE_REQUESTS: u64 = 6;
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateSyncConfig {
     pub data_streaming_service: DataStreamingServiceConfig,
     pub aptos_data_client: AptosDataClientConfig,
     pub state_sync_driver: StateSyncDriverConfig,
     pub storage_service: StorageServiceConfig,
+    /// The peers (e.g., the VFNs of the same operator) that are allowed to request
+    /// larger chunks from the storage service (see `trusted_peer_chunk_size_multiplier`).
+    /// Note: this lives outside of the storage service config, as that config is
+    /// copied for every request.
+    pub storage_service_trusted_peers: HashSet<PeerId>,
 }
 
 /// The bootstrapping mode determines how the node will bootstrap to the latest
@@ -147,6 +153,32 @@ impl Default for StateSyncDriverConfig {
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StorageServiceConfig {
+    /// Whether the server should shrink the chunk sizes it advertises (in the
+    /// protocol metadata) when responses routinely overflow the network frame,
+    /// or take too long to serve (see `max_chunk_serving_latency_ms`).
+    pub enable_adaptive_chunk_sizing: bool,
+    /// Whether to enforce per-peer bandwidth quotas for public network peers
+    /// (see `max_peer_bandwidth_bytes_per_window` and `peer_bandwidth_window_secs`)
+    pub enable_peer_bandwidth_quotas: bool,
+    /// Whether the server should verify every proof it is about to send
+    /// against its own ledger before responding (for debugging only, as
+    /// this adds significant read and verification overhead).
+    pub enable_proof_self_check: bool,
+    /// Whether or not to send responses in a checksummed envelope (so that
+    /// clients can detect corrupted responses). Only the clients that ask for
+    /// checksummed responses receive them.
+    pub enable_response_checksums: bool,
+    /// Whether to serve transactions without proofs (e.g., for indexers
+    /// that sync from a trusted upstream and don't verify the data)
+    pub enable_transactions_without_proof: bool,
+    /// Whether to serve version lookups by timestamp (e.g., for data services
+    /// and light clients). These search over the committed blocks.
+    pub enable_version_by_timestamp: bool,
+    /// Maximum number of data requests per batch request
+    pub max_batch_request_size: u64,
+    /// Maximum average time (ms) to serve a chunk before the advertised chunk
+    /// sizes are reduced (only used with adaptive chunk sizing)
+    pub max_chunk_serving_latency_ms: u64,
     /// Maximum number of concurrent storage server tasks
     pub max_concurrent_requests: u64,
     /// Maximum number of epoch ending ledger infos per chunk
@@ -163,8 +195,14 @@ pub struct StorageServiceConfig {
     pub max_num_active_subscriptions: u64,
     /// Maximum period (ms) of pending optimistic fetch requests
     pub max_optimistic_fetch_period_ms: u64,
+    /// Maximum number of bytes served to a single peer within the bandwidth
+    /// window (only used with per-peer bandwidth quotas)
+    pub max_peer_bandwidth_bytes_per_window: u64,
     /// Maximum number of state keys and values per chunk
     pub max_state_chunk_size: u64,
+    /// Maximum time (ms) the storage summary refresher can fall behind its refresh
+    /// interval before subscriptions are paused (to avoid serving stale data)
+    pub max_storage_summary_staleness_ms: u64,
     /// Maximum period (ms) of pending subscription requests
     pub max_subscription_period_ms: u64,
     /// Maximum number of transactions per chunk
@@ -173,15 +211,33 @@ pub struct StorageServiceConfig {
     pub max_transaction_output_chunk_size: u64,
     /// Minimum time (secs) to ignore peers after too many invalid requests
     pub min_time_to_ignore_peers_secs: u64,
+    /// The duration (secs) of the sliding window used to enforce per-peer bandwidth quotas
+    pub peer_bandwidth_window_secs: u64,
+    /// The duration (secs) to forecast pruning for in the data summary (0 disables the forecast)
+    pub prune_window_forecast_secs: u64,
+    /// The duration (secs) of the rolling window of request analytics kept
+    /// in memory (e.g., for the admin service). 0 disables the analytics.
+    pub request_analytics_window_secs: u64,
     /// The interval (ms) to refresh the request moderator state
     pub request_moderator_refresh_interval_ms: u64,
     /// The interval (ms) to refresh the storage summary
     pub storage_summary_refresh_interval_ms: u64,
+    /// The factor by which the maximum chunk sizes are multiplied for
+    /// requests from trusted peers (see `storage_service_trusted_peers`)
+    pub trusted_peer_chunk_size_multiplier: u64,
 }
 
 impl Default for StorageServiceConfig {
     fn default() -> Self {
         Self {
+            enable_adaptive_chunk_sizing: false,
+            enable_peer_bandwidth_quotas: false,
+            enable_proof_self_check: false,
+            enable_response_checksums: true,
+            enable_transactions_without_proof: false,
+            enable_version_by_timestamp: false,
+            max_batch_request_size: 10,
+            max_chunk_serving_latency_ms: 2000, // 2 seconds
             max_concurrent_requests: 4000,
             max_epoch_chunk_size: MAX_EPOCH_CHUNK_SIZE,
             max_invalid_requests_per_peer: 500,
@@ -190,13 +246,19 @@ impl Default for StorageServiceConfig {
             max_network_chunk_bytes: MAX_MESSAGE_SIZE as u64,
             max_num_active_subscriptions: 30,
             max_optimistic_fetch_period_ms: 5000, // 5 seconds
+            max_peer_bandwidth_bytes_per_window: 1024 * 1024 * 1024, // 1 GiB (per window)
             max_state_chunk_size: MAX_STATE_CHUNK_SIZE,
-            max_subscription_period_ms: 30_000, // 30 seconds
+            max_storage_summary_staleness_ms: 10_000, // 10 seconds
+            max_subscription_period_ms: 30_000,       // 30 seconds
             max_transaction_chunk_size: MAX_TRANSACTION_CHUNK_SIZE,
             max_transaction_output_chunk_size: MAX_TRANSACTION_OUTPUT_CHUNK_SIZE,
             min_time_to_ignore_peers_secs: 300, // 5 minutes
+            peer_bandwidth_window_secs: 60,     // 1 minute
+            prune_window_forecast_secs: 3600,   // 1 hour
+            request_analytics_window_secs: 600, // 10 minutes
             request_moderator_refresh_interval_ms: 1000, // 1 second
             storage_summary_refresh_interval_ms: 100, // Optimal for <= 10 blocks per second
+            trusted_peer_chunk_size_multiplier: 4,
         }
     }
 }
@@ -382,6 +444,10 @@ pub struct AptosDataClientConfig {
     pub subscription_response_timeout_ms: u64,
     /// Whether or not to request compression for incoming data
     pub use_compression: bool,
+    /// Whether or not to request checksummed responses (so that corrupted responses are
+    /// detected before they are deserialized). Note: all peers must support checksummed
+    /// responses before this can be enabled.
+    pub use_response_checksums: bool,
 }
 
 impl Default for AptosDataClientConfig {
@@ -403,6 +469,7 @@ impl Default for AptosDataClientConfig {
             response_timeout_ms: 10_000,              // 10 seconds
             subscription_response_timeout_ms: 20_000, // 20 seconds (must be longer than a regular timeout because of pre-fetching)
             use_compression: true,
+            use_response_checksums: false,
         }
     }
 }
@@ -175,6 +175,10 @@ pub struct StorageConfig {
     /// If not specificed, will use `dir` as default.
     /// Only allowed when sharding is enabled.
     pub db_path_overrides: Option<DbPathConfig>,
+    /// Background state consistency checker configuration
+    pub consistency_checker_config: ConsistencyCheckerConfig,
+    /// In-memory cache of the latest values of hot state keys
+    pub state_value_cache_config: StateValueCacheConfig,
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -258,6 +262,48 @@ pub struct PrunerConfig {
     pub epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsistencyCheckerConfig {
+    /// Boolean to enable/disable the background consistency checker. The checker periodically
+    /// samples state keys at the latest state snapshot, recomputes their JMT proofs against the
+    /// root hash and cross-checks the state kv db against the state merkle db.
+    pub enable: bool,
+    /// Interval between two consecutive checks, in milliseconds.
+    pub check_interval_ms: u64,
+    /// Number of state keys sampled in each check.
+    pub num_sampled_keys: usize,
+}
+
+impl Default for ConsistencyCheckerConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            check_interval_ms: 60_000,
+            num_sampled_keys: 100,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateValueCacheConfig {
+    /// Boolean to enable/disable the state value cache. The cache holds the latest values of
+    /// recently read state keys (e.g., on-chain configs), saving the RocksDB reads for them.
+    pub enable: bool,
+    /// The max # of bytes of the state keys and values cached for each of the 16 state shards.
+    pub max_bytes_per_shard: usize,
+}
+
+impl Default for StateValueCacheConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            max_bytes_per_shard: 1 << 22,
+        }
+    }
+}
+
 impl Default for LedgerPrunerConfig {
     fn default() -> Self {
         LedgerPrunerConfig {
@@ -322,6 +368,8 @@ impl Default for StorageConfig {
             rocksdb_configs: RocksdbConfigs::default(),
             enable_indexer: false,
             db_path_overrides: None,
+            consistency_checker_config: ConsistencyCheckerConfig::default(),
+            state_value_cache_config: StateValueCacheConfig::default(),
             buffered_state_target_items: BUFFERED_STATE_TARGET_ITEMS,
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
         }
@@ -517,6 +565,16 @@ impl ConfigSanitizer for StorageConfig {
             ));
         }
 
+        if config.state_value_cache_config.enable
+            && config.state_value_cache_config.max_bytes_per_shard == 0
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "max_bytes_per_shard must be positive if the state value cache is enabled."
+                    .to_string(),
+            ));
+        }
+
         if let Some(db_path_overrides) = config.db_path_overrides.as_ref() {
             if !config.rocksdb_configs.enable_storage_sharding {
                 return Err(Error::ConfigSanitizerFailed(
@@ -4,6 +4,7 @@
 
 use crate::{
     counters,
+    dag::DagStatusProvider,
     epoch_manager::EpochManager,
     network::NetworkTask,
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
@@ -39,7 +40,12 @@ pub fn start_consensus(
     aptos_db: DbReaderWriter,
     reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     validator_txn_pool_client: vtxn_pool::ReadClient,
-) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
+) -> (
+    Runtime,
+    Arc<StorageWriteProxy>,
+    Arc<QuorumStoreDB>,
+    Arc<DagStatusProvider>,
+) {
     let runtime = aptos_runtimes::spawn_named_runtime("consensus".into(), None);
     let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
     let quorum_store_db = Arc::new(QuorumStoreDB::new(node_config.storage.dir()));
@@ -80,6 +86,7 @@ pub fn start_consensus(
         aptos_time_service::TimeService::real(),
         validator_txn_pool_client,
     );
+    let dag_status_provider = epoch_mgr.dag_status_provider();
 
     let (network_task, network_receiver) = NetworkTask::new(network_service_events, self_receiver);
 
@@ -87,5 +94,5 @@ pub fn start_consensus(
     runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
 
     debug!("Consensus started.");
-    (runtime, storage, quorum_store_db)
+    (runtime, storage, quorum_store_db, dag_status_provider)
 }
@@ -307,6 +307,69 @@ pub static PROPOSER_PENDING_BLOCKS_FILL_FRACTION: Lazy<Gauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Time (in seconds) spent pulling the proposal payload, per payload source
+pub static PROPOSER_PAYLOAD_PULL_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_proposer_payload_pull_seconds",
+        "Time (in seconds) spent pulling the proposal payload, per payload source",
+        &["source"],
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 14).unwrap(),
+    )
+    .unwrap()
+});
+
+/// Number of proposals made with a partial payload, because the payload pull deadline expired
+/// or a payload source failed
+pub static PROPOSER_PAYLOAD_PULL_TRUNCATED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_proposer_payload_pull_truncated_count",
+        "Number of proposals made with a partial payload, because the payload pull deadline cut a payload source short",
+    )
+    .unwrap()
+});
+
+/// Time (in seconds) spent in a payload pull, across all payload sources
+pub static PAYLOAD_PULL_LATENCY_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_consensus_payload_pull_latency_seconds",
+        "Time (in seconds) spent in a payload pull, across all payload sources",
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 14).unwrap(),
+    )
+    .unwrap()
+});
+
+/// Number of txns returned by a payload pull, per payload source
+pub static PAYLOAD_PULL_NUM_TXNS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_consensus_payload_pull_num_txns",
+        "Number of txns returned by a payload pull, per payload source",
+        &["source"],
+        NUM_CONSENSUS_TRANSACTIONS_BUCKETS.to_vec(),
+    )
+    .unwrap()
+});
+
+/// Number of bytes returned by a payload pull, across all payload sources
+pub static PAYLOAD_PULL_NUM_BYTES: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_consensus_payload_pull_num_bytes",
+        "Number of bytes returned by a payload pull, across all payload sources",
+        exponential_buckets(/*start=*/ 1024.0, /*factor=*/ 2.0, /*count=*/ 16).unwrap(),
+    )
+    .unwrap()
+});
+
+/// Number of entries in the exclusion filter of a payload pull, per payload source
+pub static PAYLOAD_PULL_FILTER_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_consensus_payload_pull_filter_size",
+        "Number of entries in the exclusion filter of a payload pull, per payload source",
+        &["source"],
+        NUM_CONSENSUS_TRANSACTIONS_BUCKETS.to_vec(),
+    )
+    .unwrap()
+});
+
 /// Next set of counters are computed at leader election time, with some delay.
 
 /// Current voting power fraction that participated in consensus
@@ -11,6 +11,7 @@ use super::{
     dag_store::Dag,
     order_rule::OrderRule,
     rb_handler::NodeBroadcastHandler,
+    status::{DagMode, DagStatusProvider, DagStatusSources},
     storage::DAGStorage,
     types::{CertifiedNodeMessage, DAGMessage},
     DAGRpcResult, ProofNotifier,
@@ -54,7 +55,12 @@ use futures_channel::{
     mpsc::{UnboundedReceiver, UnboundedSender},
     oneshot,
 };
-use std::{collections::HashMap, fmt, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{atomic::AtomicUsize, Arc},
+    time::Duration,
+};
 use tokio::{
     runtime::Handle,
     select,
@@ -138,6 +144,12 @@ impl ActiveMode {
                 .highest_ordered_anchor_round(),
         );
 
+        bootstrapper.update_status_provider(
+            DagMode::Active,
+            &self.base_state,
+            Some(self.fetch_service.num_pending_requests()),
+        );
+
         // Spawn the fetch service
         let handle = tokio::spawn(self.fetch_service.start());
         defer!({
@@ -222,6 +234,8 @@ impl SyncMode {
                 .highest_ordered_anchor_round(),
             local_committed_round = highest_committed_anchor_round
         );
+        bootstrapper.update_status_provider(DagMode::Sync, &self.base_state, None);
+
         let dag_fetcher = DagFetcher::new(
             bootstrapper.epoch_state.clone(),
             bootstrapper.dag_network_sender.clone(),
@@ -329,6 +343,7 @@ pub struct DagBootstrapper {
     quorum_store_enabled: bool,
     validator_txn_enabled: bool,
     executor: BoundedExecutor,
+    status_provider: Arc<DagStatusProvider>,
 }
 
 impl DagBootstrapper {
@@ -351,6 +366,7 @@ impl DagBootstrapper {
         quorum_store_enabled: bool,
         validator_txn_enabled: bool,
         executor: BoundedExecutor,
+        status_provider: Arc<DagStatusProvider>,
     ) -> Self {
         Self {
             self_peer,
@@ -370,6 +386,7 @@ impl DagBootstrapper {
             quorum_store_enabled,
             validator_txn_enabled,
             executor,
+            status_provider,
         }
     }
 
@@ -573,6 +590,24 @@ impl DagBootstrapper {
         (dag_handler, dag_fetcher)
     }
 
+    fn update_status_provider(
+        &self,
+        mode: DagMode,
+        base_state: &BootstrapBaseState,
+        num_pending_fetch_requests: Option<Arc<AtomicUsize>>,
+    ) {
+        self.status_provider.set_sources(DagStatusSources {
+            mode,
+            epoch_state: self.epoch_state.clone(),
+            dag: base_state.dag_store.clone(),
+            order_rule: base_state.order_rule.clone(),
+            ledger_info_provider: base_state.ledger_info_provider.clone(),
+            anchor_election: base_state.leader_reputation_adapter.clone(),
+            storage: self.storage.clone(),
+            num_pending_fetch_requests,
+        });
+    }
+
     fn full_bootstrap(&self) -> (BootstrapBaseState, NetworkHandler, DagFetcherService) {
         let leader_reputation_adapter = self.build_leader_reputation_components();
 
@@ -614,6 +649,7 @@ impl DagBootstrapper {
             select! {
                 biased;
                 Ok(ack_tx) = &mut shutdown_rx => {
+                    self.status_provider.clear();
                     let _ = ack_tx.send(());
                     info!(LogSchema::new(LogEvent::Shutdown), epoch = self.epoch_state.epoch);
                     return;
@@ -664,6 +700,7 @@ pub(super) fn bootstrap_dag_for_test(
         false,
         true,
         BoundedExecutor::new(2, Handle::current()),
+        Arc::new(DagStatusProvider::new()),
     );
 
     let (_base_state, handler, fetch_service) = bootstraper.full_bootstrap();
@@ -23,7 +23,10 @@ use futures::{stream::FuturesUnordered, Stream, StreamExt};
 use std::{
     collections::HashMap,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
     time::Duration,
 };
@@ -67,15 +70,20 @@ pub struct FetchRequester {
     request_tx: Sender<LocalFetchRequest>,
     node_waiter_tx: Sender<oneshot::Receiver<Node>>,
     certified_node_waiter_tx: Sender<oneshot::Receiver<CertifiedNode>>,
+    num_pending_requests: Arc<AtomicUsize>,
 }
 
 impl TFetchRequester for FetchRequester {
     fn request_for_node(&self, node: Node) -> anyhow::Result<()> {
         let (res_tx, res_rx) = oneshot::channel();
         let fetch_req = LocalFetchRequest::Node(node, res_tx);
-        self.request_tx
-            .try_send(fetch_req)
-            .map_err(|e| anyhow::anyhow!("unable to send node fetch request to channel: {}", e))?;
+        // Count the request before sending it, so the service can never decrement
+        // the counter for a request that has not been counted yet.
+        self.num_pending_requests.fetch_add(1, Ordering::Relaxed);
+        self.request_tx.try_send(fetch_req).map_err(|e| {
+            self.num_pending_requests.fetch_sub(1, Ordering::Relaxed);
+            anyhow::anyhow!("unable to send node fetch request to channel: {}", e)
+        })?;
         self.node_waiter_tx.try_send(res_rx)?;
         Ok(())
     }
@@ -83,7 +91,9 @@ impl TFetchRequester for FetchRequester {
     fn request_for_certified_node(&self, node: CertifiedNode) -> anyhow::Result<()> {
         let (res_tx, res_rx) = oneshot::channel();
         let fetch_req = LocalFetchRequest::CertifiedNode(node, res_tx);
+        self.num_pending_requests.fetch_add(1, Ordering::Relaxed);
         self.request_tx.try_send(fetch_req).map_err(|e| {
+            self.num_pending_requests.fetch_sub(1, Ordering::Relaxed);
             anyhow::anyhow!(
                 "unable to send certified node fetch request to channel: {}",
                 e
@@ -134,6 +144,7 @@ pub struct DagFetcherService {
     dag: Arc<RwLock<Dag>>,
     request_rx: Receiver<LocalFetchRequest>,
     ordered_authors: Vec<Author>,
+    num_pending_requests: Arc<AtomicUsize>,
 }
 
 impl DagFetcherService {
@@ -153,17 +164,20 @@ impl DagFetcherService {
         let (node_tx, node_rx) = tokio::sync::mpsc::channel(100);
         let (certified_node_tx, certified_node_rx) = tokio::sync::mpsc::channel(100);
         let ordered_authors = epoch_state.verifier.get_ordered_account_addresses();
+        let num_pending_requests = Arc::new(AtomicUsize::new(0));
         (
             Self {
                 inner: DagFetcher::new(epoch_state, network, time_service, config),
                 dag,
                 request_rx,
                 ordered_authors,
+                num_pending_requests: num_pending_requests.clone(),
             },
             FetchRequester {
                 request_tx,
                 node_waiter_tx: node_tx,
                 certified_node_waiter_tx: certified_node_tx,
+                num_pending_requests,
             },
             FetchWaiter::new(node_rx),
             FetchWaiter::new(certified_node_rx),
@@ -182,9 +196,15 @@ impl DagFetcherService {
                 Ok(_) => local_request.notify(),
                 Err(err) => error!("unable to complete fetch successfully: {}", err),
             }
+            self.num_pending_requests.fetch_sub(1, Ordering::Relaxed);
         }
     }
 
+    /// Returns a counter of the fetch requests that are queued or in progress.
+    pub fn num_pending_requests(&self) -> Arc<AtomicUsize> {
+        self.num_pending_requests.clone()
+    }
+
     pub(super) async fn fetch(
         &mut self,
         node: &Node,
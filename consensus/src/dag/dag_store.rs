@@ -404,6 +404,14 @@ impl Dag {
         None
     }
 
+    /// Returns the number of certified nodes in each round of the DAG.
+    pub(super) fn num_nodes_per_round(&self) -> BTreeMap<Round, usize> {
+        self.nodes_by_round
+            .iter()
+            .map(|(round, round_nodes)| (*round, round_nodes.iter().flatten().count()))
+            .collect()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.nodes_by_round.is_empty() && self.start_round > 1
     }
@@ -17,6 +17,7 @@ mod observability;
 mod order_rule;
 mod rb_handler;
 mod round_state;
+mod status;
 mod storage;
 #[cfg(test)]
 mod tests;
@@ -26,6 +27,7 @@ pub use adapter::{ProofNotifier, StorageAdapter};
 pub use bootstrap::DagBootstrapper;
 pub use commit_signer::DagCommitSigner;
 pub use dag_network::{RpcHandler, RpcWithFallback, TDAGNetworkSender};
+pub use status::{AnchorElectionStatus, DagMode, DagStatus, DagStatusProvider};
 pub use storage::DAGStorage;
 pub use types::{
     CertifiedNode, DAGMessage, DAGNetworkMessage, DAGRpcResult, Extensions, Node, NodeId, Vote,
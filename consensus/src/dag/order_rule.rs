@@ -76,6 +76,10 @@ impl OrderRule {
         order_rule
     }
 
+    pub fn lowest_unordered_anchor_round(&self) -> Round {
+        *self.lowest_unordered_anchor_round.read()
+    }
+
     /// Check if two rounds have the same parity
     fn check_parity(r1: Round, r2: Round) -> bool {
         (r1 ^ r2) & 1 == 0
@@ -0,0 +1,147 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    adapter::TLedgerInfoProvider, anchor_election::AnchorElection, dag_store::Dag,
+    order_rule::OrderRule, storage::DAGStorage,
+};
+use aptos_consensus_types::common::{Author, Round};
+use aptos_infallible::RwLock;
+use aptos_logger::warn;
+use aptos_types::epoch_state::EpochState;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DagMode {
+    /// The DAG is being driven by the local node.
+    Active,
+    /// The DAG is being synced from peers, after falling too far behind.
+    Sync,
+}
+
+impl fmt::Display for DagMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DagMode::Active => write!(f, "Active"),
+            DagMode::Sync => write!(f, "Sync"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnchorElectionStatus {
+    pub highest_committed_anchor_round: Round,
+    pub highest_ordered_anchor_round: Option<Round>,
+    /// The round of the next anchor to be ordered.
+    pub next_anchor_round: Round,
+    /// The elected author of the next anchor.
+    pub next_anchor: Author,
+    /// Whether the certified node of the next anchor is already in the DAG.
+    pub next_anchor_in_dag: bool,
+}
+
+/// A snapshot of the state of DAG consensus, used to diagnose stalls.
+#[derive(Clone, Debug)]
+pub struct DagStatus {
+    pub epoch: u64,
+    pub mode: DagMode,
+    /// The round of the node this validator is broadcasting, if any.
+    pub current_round: Option<Round>,
+    pub lowest_round: Round,
+    pub highest_round: Round,
+    pub highest_strong_links_round: Round,
+    pub lowest_incomplete_round: Round,
+    pub num_validators: usize,
+    /// The number of certified nodes in each round of the DAG.
+    pub num_nodes_per_round: BTreeMap<Round, usize>,
+    /// The number of missing-node fetch requests that are queued or in progress.
+    /// Not tracked in sync mode, where fetches are driven by the state synchronizer.
+    pub num_pending_fetch_requests: Option<usize>,
+    pub anchor_election: AnchorElectionStatus,
+}
+
+/// The components of a running DAG that the status is computed from.
+pub(super) struct DagStatusSources {
+    pub mode: DagMode,
+    pub epoch_state: Arc<EpochState>,
+    pub dag: Arc<RwLock<Dag>>,
+    pub order_rule: OrderRule,
+    pub ledger_info_provider: Arc<dyn TLedgerInfoProvider>,
+    pub anchor_election: Arc<dyn AnchorElection>,
+    pub storage: Arc<dyn DAGStorage>,
+    pub num_pending_fetch_requests: Option<Arc<AtomicUsize>>,
+}
+
+/// Reports the status of the DAG consensus of the current epoch. A single provider
+/// lives across epochs, and each `DagBootstrapper` points it to its own components.
+#[derive(Default)]
+pub struct DagStatusProvider {
+    sources: RwLock<Option<DagStatusSources>>,
+}
+
+impl DagStatusProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current status, or `None` if DAG consensus is not running.
+    pub fn status(&self) -> Option<DagStatus> {
+        self.sources.read().as_ref().map(Self::compute_status)
+    }
+
+    pub(super) fn set_sources(&self, sources: DagStatusSources) {
+        *self.sources.write() = Some(sources);
+    }
+
+    pub(super) fn clear(&self) {
+        *self.sources.write() = None;
+    }
+
+    fn compute_status(sources: &DagStatusSources) -> DagStatus {
+        let current_round = match sources.storage.get_pending_node() {
+            Ok(maybe_node) => maybe_node.map(|node| node.round()),
+            Err(e) => {
+                warn!("Unable to read the pending DAG node: {}", e);
+                None
+            },
+        };
+        let next_anchor_round = sources.order_rule.lowest_unordered_anchor_round();
+        let next_anchor = sources.anchor_election.get_anchor(next_anchor_round);
+
+        let dag_reader = sources.dag.read();
+        DagStatus {
+            epoch: sources.epoch_state.epoch,
+            mode: sources.mode,
+            current_round,
+            lowest_round: dag_reader.lowest_round(),
+            highest_round: dag_reader.highest_round(),
+            highest_strong_links_round: dag_reader
+                .highest_strong_links_round(&sources.epoch_state.verifier),
+            lowest_incomplete_round: dag_reader.lowest_incomplete_round(),
+            num_validators: sources.epoch_state.verifier.len(),
+            num_nodes_per_round: dag_reader.num_nodes_per_round(),
+            num_pending_fetch_requests: sources
+                .num_pending_fetch_requests
+                .as_ref()
+                .map(|counter| counter.load(Ordering::Relaxed)),
+            anchor_election: AnchorElectionStatus {
+                highest_committed_anchor_round: sources
+                    .ledger_info_provider
+                    .get_highest_committed_anchor_round(),
+                highest_ordered_anchor_round: dag_reader.highest_ordered_anchor_round(),
+                next_anchor_round,
+                next_anchor,
+                next_anchor_in_dag: dag_reader
+                    .get_node_ref(next_anchor_round, &next_anchor)
+                    .is_some(),
+            },
+        }
+    }
+}
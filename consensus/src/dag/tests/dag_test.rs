@@ -15,7 +15,10 @@ use aptos_types::{
     epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
     validator_signer::ValidatorSigner, validator_verifier::random_validator_verifier,
 };
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 pub struct MockStorage {
     node_data: Mutex<Option<Node>>,
@@ -277,3 +280,25 @@ fn test_dag_bitmask() {
         DagSnapshotBitmask::new(5, vec![vec![false; 4]; 2])
     );
 }
+
+#[test]
+fn test_dag_num_nodes_per_round() {
+    let (signers, epoch_state, mut dag, _) = setup();
+
+    assert!(dag.num_nodes_per_round().is_empty());
+
+    for round in 1..4 {
+        let parents = dag
+            .get_strong_links_for_round(round - 1, &epoch_state.verifier)
+            .unwrap_or_default();
+        let num_authors = if round == 3 { 1 } else { 3 };
+        for signer in &signers[0..num_authors] {
+            let node = new_certified_node(round, signer.author(), parents.clone());
+            assert!(dag.add_node(node).is_ok());
+        }
+    }
+    assert_eq!(
+        dag.num_nodes_per_round(),
+        BTreeMap::from([(1, 3), (2, 3), (3, 1)])
+    );
+}
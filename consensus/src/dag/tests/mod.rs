@@ -10,4 +10,5 @@ mod helpers;
 mod integration_tests;
 mod order_rule_tests;
 mod rb_handler_tests;
+mod status_tests;
 mod types_test;
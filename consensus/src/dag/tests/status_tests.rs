@@ -0,0 +1,188 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::dag::{
+    adapter::TLedgerInfoProvider,
+    anchor_election::{AnchorElection, RoundRobinAnchorElection},
+    dag_fetcher::{DagFetcherService, TFetchRequester},
+    dag_store::Dag,
+    order_rule::OrderRule,
+    status::{DagMode, DagStatusProvider, DagStatusSources},
+    storage::DAGStorage,
+    tests::{
+        dag_test::MockStorage,
+        helpers::{new_certified_node, new_node, MockPayloadManager, TEST_DAG_WINDOW},
+        order_rule_tests::TestNotifier,
+    },
+    DAGMessage, DAGRpcResult, RpcWithFallback, TDAGNetworkSender,
+};
+use aptos_config::config::DagFetcherConfig;
+use aptos_consensus_types::common::{Author, Round};
+use aptos_infallible::RwLock;
+use aptos_reliable_broadcast::RBNetworkSender;
+use aptos_time_service::TimeService;
+use aptos_types::{
+    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
+    validator_verifier::random_validator_verifier,
+};
+use async_trait::async_trait;
+use futures_channel::mpsc::unbounded;
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+struct MockDAGNetworkSender {}
+
+#[async_trait]
+impl RBNetworkSender<DAGMessage, DAGRpcResult> for MockDAGNetworkSender {
+    async fn send_rb_rpc(
+        &self,
+        _receiver: Author,
+        _message: DAGMessage,
+        _timeout: Duration,
+    ) -> anyhow::Result<DAGRpcResult> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl TDAGNetworkSender for MockDAGNetworkSender {
+    async fn send_rpc(
+        &self,
+        _receiver: Author,
+        _message: DAGMessage,
+        _timeout: Duration,
+    ) -> anyhow::Result<DAGRpcResult> {
+        unimplemented!()
+    }
+
+    async fn send_rpc_with_fallbacks(
+        self: Arc<Self>,
+        _responders: Vec<Author>,
+        _message: DAGMessage,
+        _retry_interval: Duration,
+        _rpc_timeout: Duration,
+        _min_concurrent_responders: u32,
+        _max_concurrent_responders: u32,
+    ) -> RpcWithFallback {
+        unimplemented!()
+    }
+}
+
+struct MockLedgerInfoProvider {
+    highest_committed_anchor_round: Round,
+}
+
+impl TLedgerInfoProvider for MockLedgerInfoProvider {
+    fn get_latest_ledger_info(&self) -> LedgerInfoWithSignatures {
+        unimplemented!()
+    }
+
+    fn get_highest_committed_anchor_round(&self) -> Round {
+        self.highest_committed_anchor_round
+    }
+}
+
+#[tokio::test]
+async fn test_dag_status_provider() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let storage = Arc::new(MockStorage::new());
+    let dag = Arc::new(RwLock::new(Dag::new(
+        epoch_state.clone(),
+        storage.clone(),
+        Arc::new(MockPayloadManager {}),
+        1,
+        TEST_DAG_WINDOW,
+    )));
+    let validators: Vec<Author> = signers.iter().map(|signer| signer.author()).collect();
+    let anchor_election = Arc::new(RoundRobinAnchorElection::new(validators));
+    let (tx, _rx) = unbounded();
+    let order_rule = OrderRule::new(
+        epoch_state.clone(),
+        1,
+        dag.clone(),
+        anchor_election.clone(),
+        Arc::new(TestNotifier { tx }),
+        storage.clone(),
+        TEST_DAG_WINDOW as Round,
+    );
+    let (fetch_service, fetch_requester, _, _) = DagFetcherService::new(
+        epoch_state.clone(),
+        Arc::new(MockDAGNetworkSender {}),
+        dag.clone(),
+        TimeService::mock(),
+        DagFetcherConfig::default(),
+    );
+
+    let provider = DagStatusProvider::new();
+    assert!(provider.status().is_none());
+
+    let num_pending_fetch_requests = fetch_service.num_pending_requests();
+    provider.set_sources(DagStatusSources {
+        mode: DagMode::Active,
+        epoch_state: epoch_state.clone(),
+        dag: dag.clone(),
+        order_rule,
+        ledger_info_provider: Arc::new(MockLedgerInfoProvider {
+            highest_committed_anchor_round: 0,
+        }),
+        anchor_election: anchor_election.clone(),
+        storage: storage.clone(),
+        num_pending_fetch_requests: Some(num_pending_fetch_requests),
+    });
+
+    // Round 1 is complete, round 2 only has the node of the first validator.
+    for signer in &signers[0..4] {
+        let node = new_certified_node(1, signer.author(), vec![]);
+        assert!(dag.write().add_node(node).is_ok());
+    }
+    let parents = dag
+        .read()
+        .get_strong_links_for_round(1, &epoch_state.verifier)
+        .unwrap();
+    let node = new_certified_node(2, signers[0].author(), parents.clone());
+    assert!(dag.write().add_node(node).is_ok());
+    storage
+        .save_pending_node(&new_node(3, 0, signers[0].author(), parents))
+        .unwrap();
+
+    let status = provider.status().unwrap();
+    assert_eq!(status.epoch, 1);
+    assert_eq!(status.mode, DagMode::Active);
+    assert_eq!(status.current_round, Some(3));
+    assert_eq!(status.lowest_round, 1);
+    assert_eq!(status.highest_round, 2);
+    assert_eq!(status.num_validators, 4);
+    assert_eq!(status.num_nodes_per_round, BTreeMap::from([(1, 4), (2, 1)]));
+    assert_eq!(status.num_pending_fetch_requests, Some(0));
+    assert_eq!(status.anchor_election.highest_committed_anchor_round, 0);
+    assert_eq!(status.anchor_election.next_anchor_round, 1);
+    assert_eq!(
+        status.anchor_election.next_anchor,
+        anchor_election.get_anchor(1)
+    );
+    assert!(status.anchor_election.next_anchor_in_dag);
+
+    // A queued fetch request is reported until the fetch service processes it.
+    let node = new_node(3, 0, signers[1].author(), vec![]);
+    fetch_requester.request_for_node(node.clone()).unwrap();
+    assert_eq!(
+        provider.status().unwrap().num_pending_fetch_requests,
+        Some(1)
+    );
+
+    // A request that can't be queued is not counted.
+    let mut num_queued = 1;
+    while fetch_requester.request_for_node(node.clone()).is_ok() {
+        num_queued += 1;
+    }
+    assert_eq!(
+        provider.status().unwrap().num_pending_fetch_requests,
+        Some(num_queued)
+    );
+
+    provider.clear();
+    assert!(provider.status().is_none());
+}
@@ -8,7 +8,7 @@ use crate::{
         BlockStore,
     },
     counters,
-    dag::{DagBootstrapper, DagCommitSigner, StorageAdapter},
+    dag::{DagBootstrapper, DagCommitSigner, DagStatusProvider, StorageAdapter},
     error::{error_kind, DbError},
     liveness::{
         cached_proposer_election::CachedProposerElection,
@@ -33,8 +33,9 @@ use crate::{
     },
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
     payload_client::{
-        mixed::MixedPayloadClient, user::quorum_store_client::QuorumStoreClient,
-        validator::ValidatorTxnPayloadClient, PayloadClient,
+        mixed::MixedPayloadClient, prioritization::create_payload_prioritizer,
+        user::quorum_store_client::QuorumStoreClient, validator::ValidatorTxnPayloadClient,
+        PayloadClient,
     },
     payload_manager::PayloadManager,
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
@@ -162,6 +163,7 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     dag_rpc_tx: Option<aptos_channel::Sender<AccountAddress, IncomingDAGRequest>>,
     dag_shutdown_tx: Option<oneshot::Sender<oneshot::Sender<()>>>,
     dag_config: DagConsensusConfig,
+    dag_status_provider: Arc<DagStatusProvider>,
     payload_manager: Arc<PayloadManager>,
 }
 
@@ -220,10 +222,15 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             dag_shutdown_tx: None,
             aptos_time_service,
             dag_config,
+            dag_status_provider: Arc::new(DagStatusProvider::new()),
             payload_manager: Arc::new(PayloadManager::DirectMempool),
         }
     }
 
+    pub(crate) fn dag_status_provider(&self) -> Arc<DagStatusProvider> {
+        self.dag_status_provider.clone()
+    }
+
     fn epoch_state(&self) -> &EpochState {
         self.epoch_state
             .as_ref()
@@ -1029,6 +1036,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             consensus_config.validator_txn_enabled(),
             self.validator_txn_pool_client.clone(),
             Arc::new(quorum_store_client),
+            create_payload_prioritizer(&self.config.payload_prioritization),
         );
         self.init_commit_state_computer(epoch_state, payload_manager.clone(), execution_config);
         self.start_quorum_store(quorum_store_builder);
@@ -1132,6 +1140,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             onchain_consensus_config.quorum_store_enabled(),
             onchain_consensus_config.validator_txn_enabled(),
             self.bounded_executor.clone(),
+            self.dag_status_provider.clone(),
         );
 
         let (dag_rpc_tx, dag_rpc_rx) = aptos_channel::new(QueueStyle::FIFO, 10, None);
@@ -9,8 +9,8 @@ use crate::{
     block_storage::BlockReader,
     counters::{
         CHAIN_HEALTH_BACKOFF_TRIGGERED, PIPELINE_BACKPRESSURE_ON_PROPOSAL_TRIGGERED,
-        PROPOSER_DELAY_PROPOSAL, PROPOSER_PENDING_BLOCKS_COUNT,
-        PROPOSER_PENDING_BLOCKS_FILL_FRACTION,
+        PROPOSER_DELAY_PROPOSAL, PROPOSER_PAYLOAD_PULL_SECONDS, PROPOSER_PAYLOAD_PULL_TRUNCATED,
+        PROPOSER_PENDING_BLOCKS_COUNT, PROPOSER_PENDING_BLOCKS_FILL_FRACTION,
     },
     payload_client::PayloadClient,
     util::time_service::TimeService,
@@ -31,13 +31,17 @@ use futures::future::BoxFuture;
 use std::{
     collections::{BTreeMap, HashSet},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 #[cfg(test)]
 #[path = "proposal_generator_test.rs"]
 mod proposal_generator_test;
 
+// How much longer than the poll time the payload pull may take (e.g. for the validator txn
+// pull), before the sources are cut short and the payload gathered so far is proposed.
+const PAYLOAD_PULL_DEADLINE_SLACK: Duration = Duration::from_millis(500);
+
 #[derive(Clone)]
 pub struct ChainHealthBackoffConfig {
     backoffs: BTreeMap<usize, ChainHealthBackoffValues>,
@@ -234,7 +238,8 @@ impl ProposalGenerator {
     /// The function generates a new proposal block: the returned future is fulfilled when the
     /// payload is delivered by the PayloadClient implementation.  At most one proposal can be
     /// generated per round (no proposal equivocation allowed).
-    /// Errors returned by the PayloadClient implementation are propagated to the caller.
+    /// Errors returned by the PayloadClient implementation are propagated to the caller. If
+    /// the payload isn't filled in time, the partial payload gathered so far is proposed.
     /// The logic for choosing the branch to extend is as follows:
     /// 1. The function gets the highest head of a one-chain from block tree.
     /// The new proposal must extend hqc to ensure optimistic responsiveness.
@@ -329,10 +334,12 @@ impl ProposalGenerator {
                 .collect();
             let validator_txn_filter =
                 vtxn_pool::TransactionFilter::PendingTxnHashSet(pending_validator_txn_hashes);
-            let (validator_txns, payload) = self
+            let max_poll_time = self.quorum_store_poll_time.saturating_sub(proposal_delay);
+            let partial_payload = self
                 .payload_client
-                .pull_payload(
-                    self.quorum_store_poll_time.saturating_sub(proposal_delay),
+                .pull_payload_with_deadline(
+                    max_poll_time,
+                    Instant::now() + max_poll_time + PAYLOAD_PULL_DEADLINE_SLACK,
                     max_block_txns,
                     max_block_bytes,
                     validator_txn_filter,
@@ -344,8 +351,26 @@ impl ProposalGenerator {
                 )
                 .await
                 .context("Fail to retrieve payload")?;
+            for (source, duration) in &partial_payload.pull_timings {
+                PROPOSER_PAYLOAD_PULL_SECONDS
+                    .with_label_values(&[&source.to_string()])
+                    .observe(duration.as_secs_f64());
+            }
+            if partial_payload.truncated {
+                PROPOSER_PAYLOAD_PULL_TRUNCATED.inc();
+                warn!(
+                    round = round,
+                    pull_timings = ?partial_payload.pull_timings,
+                    pull_stats = ?partial_payload.stats,
+                    "Payload pull was truncated, proposing the partial payload"
+                );
+            }
 
-            (validator_txns, payload, timestamp.as_micros() as u64)
+            (
+                partial_payload.validator_txns,
+                partial_payload.user_payload,
+                timestamp.as_micros() as u64,
+            )
         };
 
         let quorum_cert = hqc.as_ref().clone();
@@ -1,14 +1,23 @@
 // Copyright © Aptos Foundation
 
+#[cfg(test)]
+use crate::payload_client::prioritization::{create_payload_prioritizer, FifoPrioritizer};
 #[cfg(test)]
 use crate::payload_client::user;
 #[cfg(test)]
 use crate::payload_client::validator::DummyValidatorTxnClient;
 use crate::{
     error::QuorumStoreError,
-    payload_client::{user::UserPayloadClient, PayloadClient},
+    payload_client::{
+        prioritization::PayloadPrioritizer, user::UserPayloadClient, PartialPayload, PayloadClient,
+        PayloadPullStats, PayloadSource,
+    },
 };
+#[cfg(test)]
+use aptos_config::config::PayloadPrioritizationConfig;
 use aptos_consensus_types::common::{Payload, PayloadFilter};
+#[cfg(test)]
+use aptos_crypto::hash::CryptoHash;
 use aptos_logger::debug;
 use aptos_types::validator_txn::ValidatorTransaction;
 use aptos_validator_transaction_pool as vtxn_pool;
@@ -24,6 +33,7 @@ pub struct MixedPayloadClient {
     validator_txn_enabled: bool,
     validator_txn_pool_client: Arc<dyn crate::payload_client::validator::ValidatorTxnPayloadClient>,
     user_payload_client: Arc<dyn UserPayloadClient>,
+    payload_prioritizer: Arc<dyn PayloadPrioritizer>,
 }
 
 impl MixedPayloadClient {
@@ -33,13 +43,77 @@ impl MixedPayloadClient {
             dyn crate::payload_client::validator::ValidatorTxnPayloadClient,
         >,
         user_payload_client: Arc<dyn UserPayloadClient>,
+        payload_prioritizer: Arc<dyn PayloadPrioritizer>,
     ) -> Self {
         Self {
             validator_txn_enabled,
             validator_txn_pool_client,
             user_payload_client,
+            payload_prioritizer,
         }
     }
+
+    async fn pull_validator_txns(
+        &self,
+        max_poll_time: Duration,
+        max_items: u64,
+        max_bytes: u64,
+        validator_txn_filter: vtxn_pool::TransactionFilter,
+    ) -> Vec<ValidatorTransaction> {
+        let validator_txns = if self.validator_txn_enabled {
+            debug!("validator_txn_enabled=1");
+            // Apply the validator txn quota (if any).
+            let max_items = match self.payload_prioritizer.max_validator_txns() {
+                Some(max_validator_txns) => max_items.min(max_validator_txns),
+                None => max_items,
+            };
+            self.validator_txn_pool_client
+                .pull(max_poll_time, max_items, max_bytes, validator_txn_filter)
+                .await
+        } else {
+            debug!("validator_txn_enabled=0");
+            vec![]
+        };
+        debug!("num_validator_txns={}", validator_txns.len());
+        validator_txns
+    }
+}
+
+fn validator_txns_size_in_bytes(validator_txns: &[ValidatorTransaction]) -> u64 {
+    validator_txns
+        .iter()
+        .map(|txn| txn.size_in_bytes())
+        .sum::<usize>() as u64
+}
+
+/// Returns the poll time to use so that polling (starting now) ends by the deadline.
+fn poll_time_until_deadline(max_poll_time: Duration, deadline: Instant) -> Duration {
+    max_poll_time.min(deadline.saturating_duration_since(Instant::now()))
+}
+
+fn validator_txn_filter_size(validator_txn_filter: &vtxn_pool::TransactionFilter) -> usize {
+    match validator_txn_filter {
+        vtxn_pool::TransactionFilter::PendingTxnHashSet(txn_hashes) => txn_hashes.len(),
+    }
+}
+
+fn user_txn_filter_size(user_txn_filter: &PayloadFilter) -> usize {
+    match user_txn_filter {
+        PayloadFilter::DirectMempool(txns) => txns.len(),
+        PayloadFilter::InQuorumStore(batches) => batches.len(),
+        PayloadFilter::Empty => 0,
+    }
+}
+
+fn new_pull_stats(
+    validator_txn_filter: &vtxn_pool::TransactionFilter,
+    user_txn_filter: &PayloadFilter,
+) -> PayloadPullStats {
+    PayloadPullStats {
+        validator_txn_filter_size: validator_txn_filter_size(validator_txn_filter),
+        user_txn_filter_size: user_txn_filter_size(user_txn_filter),
+        ..PayloadPullStats::default()
+    }
 }
 
 #[async_trait::async_trait]
@@ -56,24 +130,17 @@ impl PayloadClient for MixedPayloadClient {
         pending_uncommitted_blocks: usize,
         recent_max_fill_fraction: f32,
     ) -> anyhow::Result<(Vec<ValidatorTransaction>, Payload), QuorumStoreError> {
+        let mut stats = new_pull_stats(&validator_txn_filter, &user_txn_filter);
+
         // Pull validator txns first.
         let validator_txn_pull_timer = Instant::now();
-        let validator_txns = if self.validator_txn_enabled {
-            debug!("validator_txn_enabled=1");
-            self.validator_txn_pool_client
-                .pull(max_poll_time, max_items, max_bytes, validator_txn_filter)
-                .await
-        } else {
-            debug!("validator_txn_enabled=0");
-            vec![]
-        };
-        debug!("num_validator_txns={}", validator_txns.len());
+        let validator_txns = self
+            .pull_validator_txns(max_poll_time, max_items, max_bytes, validator_txn_filter)
+            .await;
+        let validator_txn_bytes = validator_txns_size_in_bytes(&validator_txns);
         // Update constraints with validator txn pull results.
         max_items -= validator_txns.len() as u64;
-        max_bytes -= validator_txns
-            .iter()
-            .map(|txn| txn.size_in_bytes())
-            .sum::<usize>() as u64;
+        max_bytes -= validator_txn_bytes;
         max_poll_time = max_poll_time.saturating_sub(validator_txn_pull_timer.elapsed());
 
         // Pull user payload.
@@ -90,9 +157,95 @@ impl PayloadClient for MixedPayloadClient {
                 recent_max_fill_fraction,
             )
             .await?;
+        let user_payload = self
+            .payload_prioritizer
+            .prioritize_user_payload(user_payload);
+
+        stats.pull_duration = validator_txn_pull_timer.elapsed();
+        stats.num_validator_txns = validator_txns.len() as u64;
+        stats.num_user_txns = user_payload.len() as u64;
+        stats.num_bytes = validator_txn_bytes + user_payload.size() as u64;
+        stats.observe();
 
         Ok((validator_txns, user_payload))
     }
+
+    async fn pull_payload_with_deadline(
+        &self,
+        max_poll_time: Duration,
+        deadline: Instant,
+        mut max_items: u64,
+        mut max_bytes: u64,
+        validator_txn_filter: vtxn_pool::TransactionFilter,
+        user_txn_filter: PayloadFilter,
+        wait_callback: BoxFuture<'static, ()>,
+        pending_ordering: bool,
+        pending_uncommitted_blocks: usize,
+        recent_max_fill_fraction: f32,
+    ) -> anyhow::Result<PartialPayload, QuorumStoreError> {
+        let mut truncated = false;
+        let mut pull_timings = vec![];
+        let mut stats = new_pull_stats(&validator_txn_filter, &user_txn_filter);
+
+        // Pull validator txns first.
+        let validator_txn_pull_timer = Instant::now();
+        let validator_txn_poll_time = poll_time_until_deadline(max_poll_time, deadline);
+        truncated |= validator_txn_poll_time < max_poll_time;
+        let validator_txns = self
+            .pull_validator_txns(
+                validator_txn_poll_time,
+                max_items,
+                max_bytes,
+                validator_txn_filter,
+            )
+            .await;
+        pull_timings.push((
+            PayloadSource::ValidatorTxnPool,
+            validator_txn_pull_timer.elapsed(),
+        ));
+        let validator_txn_bytes = validator_txns_size_in_bytes(&validator_txns);
+        // Update constraints with validator txn pull results.
+        max_items -= validator_txns.len() as u64;
+        max_bytes -= validator_txn_bytes;
+        let max_poll_time = max_poll_time.saturating_sub(validator_txn_pull_timer.elapsed());
+
+        // Pull user payload, with whatever time is left. The user payload client
+        // returns the payload gathered so far once its poll time expires.
+        let user_txn_pull_timer = Instant::now();
+        let user_txn_poll_time = poll_time_until_deadline(max_poll_time, deadline);
+        truncated |= user_txn_poll_time < max_poll_time;
+        let user_payload = self
+            .user_payload_client
+            .pull(
+                user_txn_poll_time,
+                max_items,
+                max_bytes,
+                user_txn_filter,
+                wait_callback,
+                pending_ordering,
+                pending_uncommitted_blocks,
+                recent_max_fill_fraction,
+            )
+            .await?;
+        let user_payload = self
+            .payload_prioritizer
+            .prioritize_user_payload(user_payload);
+        pull_timings.push((PayloadSource::User, user_txn_pull_timer.elapsed()));
+
+        stats.pull_duration = validator_txn_pull_timer.elapsed();
+        stats.num_validator_txns = validator_txns.len() as u64;
+        stats.num_user_txns = user_payload.len() as u64;
+        stats.num_bytes = validator_txn_bytes + user_payload.size() as u64;
+        stats.observe();
+
+        Ok(PartialPayload {
+            validator_txns,
+            user_payload,
+            truncated,
+            pull_timings,
+            stats,
+        })
+    }
 }
 
 #[tokio::test]
@@ -110,6 +263,7 @@ async fn mixed_payload_client_should_prioritize_validator_txns() {
             all_validator_txns.clone(),
         )),
         user_payload_client: Arc::new(user::DummyClient::new(all_user_txns.clone())),
+        payload_prioritizer: Arc::new(FifoPrioritizer::default()),
     };
 
     let (pulled_validator_txns, Payload::DirectMempool(pulled_user_txns)) = client
@@ -197,6 +351,49 @@ async fn mixed_payload_client_should_prioritize_validator_txns() {
     assert_eq!(0, pulled_user_txns.len());
 }
 
+#[tokio::test]
+async fn mixed_payload_client_should_respect_validator_txn_quota() {
+    let all_validator_txns = vec![
+        ValidatorTransaction::dummy1(b"1".to_vec()),
+        ValidatorTransaction::dummy1(b"22".to_vec()),
+        ValidatorTransaction::dummy1(b"333".to_vec()),
+    ];
+
+    let all_user_txns = crate::test_utils::create_vec_signed_transactions(10);
+    let client = MixedPayloadClient {
+        validator_txn_enabled: true,
+        validator_txn_pool_client: Arc::new(DummyValidatorTxnClient::new(
+            all_validator_txns.clone(),
+        )),
+        user_payload_client: Arc::new(user::DummyClient::new(all_user_txns.clone())),
+        payload_prioritizer: create_payload_prioritizer(&PayloadPrioritizationConfig {
+            max_validator_txns_per_block: Some(2),
+            ..Default::default()
+        }),
+    };
+
+    let (pulled_validator_txns, Payload::DirectMempool(pulled_user_txns)) = client
+        .pull_payload(
+            Duration::from_millis(50), // max_poll_time
+            99,                        // max_items
+            1048576,                   // size limit: 1MB
+            vtxn_pool::TransactionFilter::PendingTxnHashSet(HashSet::new()),
+            PayloadFilter::Empty,
+            Box::pin(async {}),
+            false,
+            0,
+            0.,
+        )
+        .await
+        .unwrap()
+    else {
+        unreachable!()
+    };
+
+    assert_eq!(2, pulled_validator_txns.len());
+    assert_eq!(10, pulled_user_txns.len());
+}
+
 #[tokio::test]
 async fn mixed_payload_client_should_respect_validator_txn_feature_flag() {
     let all_validator_txns = vec![
@@ -212,6 +409,7 @@ async fn mixed_payload_client_should_respect_validator_txn_feature_flag() {
             all_validator_txns.clone(),
         )),
         user_payload_client: Arc::new(user::DummyClient::new(all_user_txns.clone())),
+        payload_prioritizer: Arc::new(FifoPrioritizer::default()),
     };
 
     let (pulled_validator_txns, Payload::DirectMempool(pulled_user_txns)) = client
@@ -235,3 +433,144 @@ async fn mixed_payload_client_should_respect_validator_txn_feature_flag() {
     assert_eq!(0, pulled_validator_txns.len());
     assert_eq!(10, pulled_user_txns.len());
 }
+
+#[tokio::test]
+async fn mixed_payload_client_should_return_partial_payload_on_deadline() {
+    let all_validator_txns = vec![
+        ValidatorTransaction::dummy1(b"1".to_vec()),
+        ValidatorTransaction::dummy1(b"22".to_vec()),
+    ];
+
+    let all_user_txns = crate::test_utils::create_vec_signed_transactions(1000);
+    let client = MixedPayloadClient {
+        validator_txn_enabled: true,
+        validator_txn_pool_client: Arc::new(DummyValidatorTxnClient::new(
+            all_validator_txns.clone(),
+        )),
+        user_payload_client: Arc::new(user::DummyClient::new(all_user_txns.clone())),
+        payload_prioritizer: Arc::new(FifoPrioritizer::default()),
+    };
+
+    // Pulling all user txns takes longer than the deadline, so the user pull is cut short
+    // (but the user txns pulled by the deadline are still returned).
+    let pull_timer = Instant::now();
+    let partial_payload = client
+        .pull_payload_with_deadline(
+            Duration::from_secs(10), // max_poll_time
+            Instant::now() + Duration::from_millis(100),
+            99999,   // max_items
+            1048576, // size limit: 1MB
+            vtxn_pool::TransactionFilter::PendingTxnHashSet(HashSet::new()),
+            PayloadFilter::Empty,
+            Box::pin(async {}),
+            false,
+            0,
+            0.,
+        )
+        .await
+        .unwrap();
+
+    assert!(pull_timer.elapsed() < Duration::from_secs(10));
+    assert_eq!(2, partial_payload.validator_txns.len());
+    let Payload::DirectMempool(pulled_user_txns) = &partial_payload.user_payload else {
+        unreachable!()
+    };
+    assert!(!pulled_user_txns.is_empty());
+    assert!(pulled_user_txns.len() < all_user_txns.len());
+    assert_eq!(
+        &all_user_txns[..pulled_user_txns.len()],
+        pulled_user_txns.as_slice()
+    );
+    assert!(partial_payload.truncated);
+    assert_eq!(
+        vec![PayloadSource::ValidatorTxnPool, PayloadSource::User],
+        partial_payload
+            .pull_timings
+            .iter()
+            .map(|(source, _)| *source)
+            .collect::<Vec<_>>()
+    );
+
+    // With enough time, the full payload is returned.
+    let partial_payload = client
+        .pull_payload_with_deadline(
+            Duration::from_millis(50), // max_poll_time
+            Instant::now() + Duration::from_secs(10),
+            10,      // max_items
+            1048576, // size limit: 1MB
+            vtxn_pool::TransactionFilter::PendingTxnHashSet(HashSet::new()),
+            PayloadFilter::Empty,
+            Box::pin(async {}),
+            false,
+            0,
+            0.,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(2, partial_payload.validator_txns.len());
+    let Payload::DirectMempool(pulled_user_txns) = partial_payload.user_payload else {
+        unreachable!()
+    };
+    assert_eq!(8, pulled_user_txns.len());
+    assert!(!partial_payload.truncated);
+}
+
+#[tokio::test]
+async fn mixed_payload_client_should_report_pull_stats() {
+    let all_validator_txns = vec![
+        ValidatorTransaction::dummy1(b"1".to_vec()),
+        ValidatorTransaction::dummy1(b"22".to_vec()),
+        ValidatorTransaction::dummy1(b"333".to_vec()),
+    ];
+
+    let all_user_txns = crate::test_utils::create_vec_signed_transactions(10);
+    let client = MixedPayloadClient {
+        validator_txn_enabled: true,
+        validator_txn_pool_client: Arc::new(DummyValidatorTxnClient::new(
+            all_validator_txns.clone(),
+        )),
+        user_payload_client: Arc::new(user::DummyClient::new(all_user_txns.clone())),
+        payload_prioritizer: Arc::new(FifoPrioritizer::default()),
+    };
+
+    // The dummy clients ignore the filters, but their sizes are still reported.
+    let validator_txn_filter = vtxn_pool::TransactionFilter::PendingTxnHashSet(HashSet::from([
+        all_validator_txns[0].hash(),
+    ]));
+    let user_txn_filter =
+        PayloadFilter::from(&vec![&Payload::DirectMempool(all_user_txns[0..2].to_vec())]);
+    let partial_payload = client
+        .pull_payload_with_deadline(
+            Duration::from_millis(50), // max_poll_time
+            Instant::now() + Duration::from_secs(10),
+            5,       // max_items
+            1048576, // size limit: 1MB
+            validator_txn_filter,
+            user_txn_filter,
+            Box::pin(async {}),
+            false,
+            0,
+            0.,
+        )
+        .await
+        .unwrap();
+
+    let Payload::DirectMempool(pulled_user_txns) = &partial_payload.user_payload else {
+        unreachable!()
+    };
+    let stats = &partial_payload.stats;
+    assert_eq!(3, stats.num_validator_txns);
+    assert_eq!(2, stats.num_user_txns);
+    assert_eq!(5, stats.num_items());
+    assert_eq!(1, stats.validator_txn_filter_size);
+    assert_eq!(2, stats.user_txn_filter_size);
+    assert_eq!(
+        validator_txns_size_in_bytes(&partial_payload.validator_txns)
+            + pulled_user_txns
+                .iter()
+                .map(|txn| txn.raw_txn_bytes_len() as u64)
+                .sum::<u64>(),
+        stats.num_bytes
+    );
+}
@@ -1,17 +1,105 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::error::QuorumStoreError;
+use crate::{
+    counters::{
+        PAYLOAD_PULL_FILTER_SIZE, PAYLOAD_PULL_LATENCY_SECONDS, PAYLOAD_PULL_NUM_BYTES,
+        PAYLOAD_PULL_NUM_TXNS,
+    },
+    error::QuorumStoreError,
+};
 use aptos_consensus_types::common::{Payload, PayloadFilter};
 use aptos_types::validator_txn::ValidatorTransaction;
 use aptos_validator_transaction_pool::TransactionFilter;
 use futures::future::BoxFuture;
-use std::time::Duration;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 
 pub mod mixed;
+pub mod prioritization;
 pub mod user;
 pub mod validator;
 
+/// The sources a payload is pulled from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayloadSource {
+    ValidatorTxnPool,
+    User,
+}
+
+impl fmt::Display for PayloadSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayloadSource::ValidatorTxnPool => write!(f, "validator_txn_pool"),
+            PayloadSource::User => write!(f, "user"),
+        }
+    }
+}
+
+/// Statistics of a single payload pull, so that proposal latency can be attributed to it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PayloadPullStats {
+    /// How long the pull took, across all sources.
+    pub pull_duration: Duration,
+    pub num_validator_txns: u64,
+    pub num_user_txns: u64,
+    /// Bytes returned, validator and user txns combined.
+    pub num_bytes: u64,
+    /// Number of pending validator txns the pull was asked to exclude.
+    pub validator_txn_filter_size: usize,
+    /// Number of pending user txns (or batches, with quorum store) the pull was asked to exclude.
+    pub user_txn_filter_size: usize,
+}
+
+impl PayloadPullStats {
+    pub fn num_items(&self) -> u64 {
+        self.num_validator_txns + self.num_user_txns
+    }
+
+    /// Records the stats in the payload pull counters.
+    pub fn observe(&self) {
+        PAYLOAD_PULL_LATENCY_SECONDS.observe(self.pull_duration.as_secs_f64());
+        PAYLOAD_PULL_NUM_BYTES.observe(self.num_bytes as f64);
+        for (source, num_txns, filter_size) in [
+            (
+                PayloadSource::ValidatorTxnPool,
+                self.num_validator_txns,
+                self.validator_txn_filter_size,
+            ),
+            (
+                PayloadSource::User,
+                self.num_user_txns,
+                self.user_txn_filter_size,
+            ),
+        ] {
+            let source = source.to_string();
+            PAYLOAD_PULL_NUM_TXNS
+                .with_label_values(&[&source])
+                .observe(num_txns as f64);
+            PAYLOAD_PULL_FILTER_SIZE
+                .with_label_values(&[&source])
+                .observe(filter_size as f64);
+        }
+    }
+}
+
+/// The payload gathered before the pull deadline expired.
+#[derive(Debug)]
+pub struct PartialPayload {
+    pub validator_txns: Vec<ValidatorTransaction>,
+    /// The user payload gathered by the deadline (this may be partially filled).
+    pub user_payload: Payload,
+    /// Whether a source had its poll time cut short by the deadline, so the
+    /// payload may contain less than what was available.
+    pub truncated: bool,
+    /// How long was spent pulling from each source, in pull order.
+    pub pull_timings: Vec<(PayloadSource, Duration)>,
+    /// Statistics of the pull; these are also recorded in the payload pull counters.
+    pub stats: PayloadPullStats,
+}
+
 #[async_trait::async_trait]
 pub trait PayloadClient: Send + Sync {
     async fn pull_payload(
@@ -27,5 +115,23 @@ pub trait PayloadClient: Send + Sync {
         recent_max_fill_fraction: f32,
     ) -> anyhow::Result<(Vec<ValidatorTransaction>, Payload), QuorumStoreError>;
 
+    /// Like `pull_payload`, but each source is only polled until `deadline`, after which
+    /// whatever it gathered so far is returned (marked as truncated). `max_poll_time` is
+    /// still how long to wait for the user payload to fill up, so the deadline should
+    /// leave some slack after it for the final pull. Errors are propagated to the caller.
+    async fn pull_payload_with_deadline(
+        &self,
+        max_poll_time: Duration,
+        deadline: Instant,
+        max_items: u64,
+        max_bytes: u64,
+        validator_txn_filter: TransactionFilter,
+        user_txn_filter: PayloadFilter,
+        wait_callback: BoxFuture<'static, ()>,
+        pending_ordering: bool,
+        pending_uncommitted_blocks: usize,
+        recent_max_fill_fraction: f32,
+    ) -> anyhow::Result<PartialPayload, QuorumStoreError>;
+
     fn trace_payloads(&self) {}
 }
@@ -0,0 +1,196 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::{PayloadPrioritizationConfig, PayloadPrioritizationPolicy};
+use aptos_consensus_types::common::Payload;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    sync::Arc,
+};
+
+/// Decides how the `MixedPayloadClient` assembles a block from the pulled payloads.
+pub trait PayloadPrioritizer: Send + Sync {
+    /// The maximum number of validator txns to pull into a block, if any.
+    fn max_validator_txns(&self) -> Option<u64>;
+
+    /// Reorders the user payload pulled from quorum store (or mempool).
+    fn prioritize_user_payload(&self, payload: Payload) -> Payload;
+}
+
+pub fn create_payload_prioritizer(
+    config: &PayloadPrioritizationConfig,
+) -> Arc<dyn PayloadPrioritizer> {
+    let max_validator_txns = config.max_validator_txns_per_block;
+    match config.policy {
+        PayloadPrioritizationPolicy::Fifo => Arc::new(FifoPrioritizer { max_validator_txns }),
+        PayloadPrioritizationPolicy::GasPrice => {
+            Arc::new(GasPricePrioritizer { max_validator_txns })
+        },
+        PayloadPrioritizationPolicy::AccountFairness => {
+            Arc::new(AccountFairnessPrioritizer { max_validator_txns })
+        },
+    }
+}
+
+/// Keeps the user payload in the order it was pulled in.
+#[derive(Default)]
+pub struct FifoPrioritizer {
+    max_validator_txns: Option<u64>,
+}
+
+impl PayloadPrioritizer for FifoPrioritizer {
+    fn max_validator_txns(&self) -> Option<u64> {
+        self.max_validator_txns
+    }
+
+    fn prioritize_user_payload(&self, payload: Payload) -> Payload {
+        payload
+    }
+}
+
+/// Orders user txns by gas price, highest first. The txns of each account keep their
+/// relative order, so that they remain executable. Quorum store batches are ordered
+/// by the gas bucket they were created in.
+#[derive(Default)]
+pub struct GasPricePrioritizer {
+    max_validator_txns: Option<u64>,
+}
+
+impl PayloadPrioritizer for GasPricePrioritizer {
+    fn max_validator_txns(&self) -> Option<u64> {
+        self.max_validator_txns
+    }
+
+    fn prioritize_user_payload(&self, payload: Payload) -> Payload {
+        match payload {
+            Payload::DirectMempool(txns) => Payload::DirectMempool(merge_by_priority(
+                txns,
+                |txn| txn.sender(),
+                |txn| txn.gas_unit_price(),
+            )),
+            Payload::InQuorumStore(mut proof_with_data) => {
+                proof_with_data
+                    .proofs
+                    .sort_by_key(|proof| Reverse(proof.gas_bucket_start()));
+                Payload::InQuorumStore(proof_with_data)
+            },
+        }
+    }
+}
+
+/// Interleaves the user txns of different accounts (and the quorum store batches of
+/// different authors) in a round-robin fashion, so that no single account fills up the
+/// front of the block.
+#[derive(Default)]
+pub struct AccountFairnessPrioritizer {
+    max_validator_txns: Option<u64>,
+}
+
+impl PayloadPrioritizer for AccountFairnessPrioritizer {
+    fn max_validator_txns(&self) -> Option<u64> {
+        self.max_validator_txns
+    }
+
+    fn prioritize_user_payload(&self, payload: Payload) -> Payload {
+        match payload {
+            Payload::DirectMempool(txns) => {
+                Payload::DirectMempool(interleave_by_group(txns, |txn| txn.sender()))
+            },
+            Payload::InQuorumStore(mut proof_with_data) => {
+                let proofs = std::mem::take(&mut proof_with_data.proofs);
+                proof_with_data.proofs = interleave_by_group(proofs, |proof| proof.author());
+                Payload::InQuorumStore(proof_with_data)
+            },
+        }
+    }
+}
+
+/// Splits the items into groups, keeping the order of the items within each group.
+/// The groups are returned in the order of their first item.
+fn group_in_order<T, K: Eq + Hash>(items: Vec<T>, group_of: impl Fn(&T) -> K) -> Vec<VecDeque<T>> {
+    let mut group_indices = HashMap::new();
+    let mut groups: Vec<VecDeque<T>> = vec![];
+    for item in items {
+        let next_index = groups.len();
+        let index = *group_indices.entry(group_of(&item)).or_insert(next_index);
+        if index == next_index {
+            groups.push(VecDeque::new());
+        }
+        groups[index].push_back(item);
+    }
+    groups
+}
+
+/// Repeatedly takes the next item of the group whose next item has the highest priority
+/// (ties are broken by the order of the groups).
+fn merge_by_priority<T, K: Eq + Hash>(
+    items: Vec<T>,
+    group_of: impl Fn(&T) -> K,
+    priority_of: impl Fn(&T) -> u64,
+) -> Vec<T> {
+    let mut ordered = Vec::with_capacity(items.len());
+    let mut groups = group_in_order(items, group_of);
+    let mut heap: BinaryHeap<_> = groups
+        .iter()
+        .enumerate()
+        .filter_map(|(index, group)| {
+            group
+                .front()
+                .map(|item| (priority_of(item), Reverse(index)))
+        })
+        .collect();
+    while let Some((_, Reverse(index))) = heap.pop() {
+        let group = &mut groups[index];
+        ordered.extend(group.pop_front());
+        if let Some(item) = group.front() {
+            heap.push((priority_of(item), Reverse(index)));
+        }
+    }
+    ordered
+}
+
+/// Takes one item of each group at a time, in the order of the groups.
+fn interleave_by_group<T, K: Eq + Hash>(items: Vec<T>, group_of: impl Fn(&T) -> K) -> Vec<T> {
+    let mut ordered = Vec::with_capacity(items.len());
+    let mut groups = group_in_order(items, group_of);
+    while !groups.is_empty() {
+        groups.retain_mut(|group| {
+            ordered.extend(group.pop_front());
+            !group.is_empty()
+        });
+    }
+    ordered
+}
+
+#[test]
+fn gas_price_prioritizer_should_keep_account_order() {
+    // (account, sequence number, gas price)
+    let items = vec![
+        (0, 0, 100),
+        (1, 0, 150),
+        (0, 1, 300),
+        (2, 0, 200),
+        (1, 1, 50),
+    ];
+    let expected = vec![
+        (2, 0, 200),
+        (1, 0, 150),
+        (0, 0, 100),
+        (0, 1, 300),
+        (1, 1, 50),
+    ];
+    assert_eq!(
+        merge_by_priority(items, |item| item.0, |item| item.2),
+        expected
+    );
+}
+
+#[test]
+fn account_fairness_prioritizer_should_interleave_accounts() {
+    // (account, sequence number)
+    let items = vec![(0, 0), (0, 1), (0, 2), (1, 0), (2, 0), (1, 1)];
+    let expected = vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (0, 2)];
+    assert_eq!(interleave_by_group(items, |item| item.0), expected);
+}
@@ -3,7 +3,10 @@
 
 use crate::{
     error::QuorumStoreError,
-    payload_client::{user::quorum_store_client::QuorumStoreClient, PayloadClient},
+    payload_client::{
+        user::quorum_store_client::QuorumStoreClient, PartialPayload, PayloadClient,
+        PayloadPullStats, PayloadSource,
+    },
 };
 use anyhow::Result;
 use aptos_consensus_types::{
@@ -19,7 +22,7 @@ use aptos_types::{
 use aptos_validator_transaction_pool as vtxn_pool;
 use futures::{channel::mpsc, future::BoxFuture};
 use rand::Rng;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[allow(dead_code)]
 pub struct MockPayloadManager {
@@ -72,4 +75,29 @@ impl PayloadClient for MockPayloadManager {
             random_payload(10),
         ))
     }
+
+    async fn pull_payload_with_deadline(
+        &self,
+        _max_poll_time: Duration,
+        _deadline: Instant,
+        _max_size: u64,
+        _max_bytes: u64,
+        _validator_txn_filter: vtxn_pool::TransactionFilter,
+        _user_txn_filter: PayloadFilter,
+        _wait_callback: BoxFuture<'static, ()>,
+        _pending_ordering: bool,
+        _pending_uncommitted_blocks: usize,
+        _recent_fill_fraction: f32,
+    ) -> Result<PartialPayload, QuorumStoreError> {
+        Ok(PartialPayload {
+            validator_txns: vec![ValidatorTransaction::dummy1(vec![0xFF; 1024])],
+            user_payload: random_payload(10),
+            truncated: false,
+            pull_timings: vec![
+                (PayloadSource::ValidatorTxnPool, Duration::ZERO),
+                (PayloadSource::User, Duration::ZERO),
+            ],
+            stats: PayloadPullStats::default(),
+        })
+    }
 }
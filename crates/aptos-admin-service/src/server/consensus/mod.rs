@@ -6,6 +6,7 @@ use anyhow::{bail, Error};
 use aptos_consensus::{
     persistent_liveness_storage::PersistentLivenessStorage,
     quorum_store::quorum_store_db::QuorumStoreStorage, util::db_tool::extract_txns_from_block,
+    DagStatusProvider,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::info;
@@ -127,6 +128,34 @@ pub async fn handle_dump_block_request(
     }
 }
 
+pub async fn handle_dag_status_request(
+    _req: Request<Body>,
+    dag_status_provider: Arc<DagStatusProvider>,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping dag status.");
+
+    match spawn_blocking(move || Ok(dump_dag_status(dag_status_provider.as_ref()))).await {
+        Ok(result) => {
+            info!("Finished dumping dag status.");
+            Ok(reply_with(vec![], result))
+        },
+        Err(e) => {
+            info!("Failed to dump dag status: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+fn dump_dag_status(dag_status_provider: &DagStatusProvider) -> String {
+    match dag_status_provider.status() {
+        Some(status) => format!("{status:#?}\n"),
+        None => "DAG consensus is not running.\n".to_string(),
+    }
+}
+
 fn dump_consensus_db(consensus_db: &dyn PersistentLivenessStorage) -> anyhow::Result<String> {
     let mut body = String::new();
 
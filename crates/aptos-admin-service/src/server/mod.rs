@@ -5,10 +5,12 @@ use crate::server::utils::reply_with_status;
 use aptos_config::config::{AuthenticationConfig, NodeConfig};
 use aptos_consensus::{
     persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
+    DagStatusProvider,
 };
 use aptos_infallible::RwLock;
 use aptos_logger::info;
 use aptos_storage_interface::DbReaderWriter;
+use aptos_storage_service_server::analytics::StorageServiceAnalytics;
 use hyper::{
     service::{make_service_fn, service_fn},
     Body, Request, Response, Server, StatusCode,
@@ -24,6 +26,7 @@ use tokio::runtime::Runtime;
 mod consensus;
 #[cfg(target_os = "linux")]
 mod profiling;
+mod state_sync;
 #[cfg(target_os = "linux")]
 mod thread_dump;
 mod utils;
@@ -35,6 +38,8 @@ pub struct Context {
     aptos_db: RwLock<Option<Arc<DbReaderWriter>>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
+    dag_status_provider: RwLock<Option<Arc<DagStatusProvider>>>,
+    storage_service_analytics: RwLock<Option<StorageServiceAnalytics>>,
 }
 
 impl Context {
@@ -50,6 +55,14 @@ impl Context {
         *self.consensus_db.write() = Some(consensus_db);
         *self.quorum_store_db.write() = Some(quorum_store_db);
     }
+
+    fn set_dag_status_provider(&self, dag_status_provider: Arc<DagStatusProvider>) {
+        *self.dag_status_provider.write() = Some(dag_status_provider);
+    }
+
+    fn set_storage_service_analytics(&self, storage_service_analytics: StorageServiceAnalytics) {
+        *self.storage_service_analytics.write() = Some(storage_service_analytics);
+    }
 }
 
 pub struct AdminService {
@@ -108,6 +121,18 @@ impl AdminService {
             .set_consensus_dbs(consensus_db, quorum_store_db)
     }
 
+    pub fn set_dag_status_provider(&self, dag_status_provider: Arc<DagStatusProvider>) {
+        self.context.set_dag_status_provider(dag_status_provider)
+    }
+
+    pub fn set_storage_service_analytics(
+        &self,
+        storage_service_analytics: StorageServiceAnalytics,
+    ) {
+        self.context
+            .set_storage_service_analytics(storage_service_analytics)
+    }
+
     fn start(&self, address: SocketAddr, enabled: bool) {
         let context = self.context.clone();
         self.runtime.spawn(async move {
@@ -211,6 +236,32 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/consensus/dag") => {
+                let dag_status_provider = context.dag_status_provider.read().clone();
+                if let Some(dag_status_provider) = dag_status_provider {
+                    consensus::handle_dag_status_request(req, dag_status_provider).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Dag status provider is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/state_sync/storage_service/analytics") => {
+                let storage_service_analytics = context.storage_service_analytics.read().clone();
+                if let Some(storage_service_analytics) = storage_service_analytics {
+                    state_sync::handle_storage_service_analytics_request(
+                        req,
+                        storage_service_analytics,
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Storage service analytics are not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
@@ -0,0 +1,17 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::reply_with;
+use aptos_logger::info;
+use aptos_storage_service_server::analytics::StorageServiceAnalytics;
+use hyper::{Body, Request, Response};
+
+pub async fn handle_storage_service_analytics_request(
+    _req: Request<Body>,
+    storage_service_analytics: StorageServiceAnalytics,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping storage service analytics.");
+
+    let snapshot = storage_service_analytics.get_snapshot();
+    Ok(reply_with(vec![], format!("{snapshot:#?}\n")))
+}
@@ -6,6 +6,7 @@ use crate::{
     endpoints::{AptosTapError, RejectionReason, RejectionReasonCode},
     helpers::{days_since_tap_epoch, get_current_time_secs},
 };
+use aptos_sdk::types::account_address::AccountAddress;
 use async_trait::async_trait;
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
@@ -14,8 +15,14 @@ use tokio::sync::Mutex;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MemoryRatelimitCheckerConfig {
+    /// Max number of requests per IP per day.
     pub max_requests_per_day: u32,
 
+    /// Max number of requests per receiver account per day. If not set,
+    /// requests are only limited per IP.
+    #[serde(default)]
+    pub max_requests_per_account_per_day: Option<u32>,
+
     #[serde(default = "MemoryRatelimitCheckerConfig::default_max_entries_in_map")]
     pub max_entries_in_map: NonZeroUsize,
 }
@@ -26,16 +33,22 @@ impl MemoryRatelimitCheckerConfig {
     }
 }
 
-/// Simple in memory storage that rejects if we've ever seen a request from an
-/// IP that has succeeded.
+/// Simple in memory storage that rejects if an IP (or, optionally, a receiver
+/// account) has exceeded its daily request limit.
 pub struct MemoryRatelimitChecker {
     pub max_requests_per_day: u32,
 
+    pub max_requests_per_account_per_day: Option<u32>,
+
     /// Map of IP to how many requests they've submitted today (where the
     /// response wasn't a 500). To avoid OOMing the server, we set a limit
     /// on how many entries we have in the table.
     pub ip_to_requests_today: Mutex<LruCache<IpAddr, u32>>,
 
+    /// Map of receiver account to how many requests have been submitted for
+    /// it today. This is only used if `max_requests_per_account_per_day` is set.
+    pub account_to_requests_today: Mutex<LruCache<AccountAddress, u32>>,
+
     /// Used for tracking daily ratelimit. See the comment in RedisRatelimitChecker
     /// for more information on how we track daily limits.
     pub current_day: AtomicU64,
@@ -45,7 +58,9 @@ impl MemoryRatelimitChecker {
     pub fn new(args: MemoryRatelimitCheckerConfig) -> Self {
         Self {
             max_requests_per_day: args.max_requests_per_day,
+            max_requests_per_account_per_day: args.max_requests_per_account_per_day,
             ip_to_requests_today: Mutex::new(LruCache::new(args.max_entries_in_map)),
+            account_to_requests_today: Mutex::new(LruCache::new(args.max_entries_in_map)),
             current_day: AtomicU64::new(days_since_tap_epoch(get_current_time_secs())),
         }
     }
@@ -59,6 +74,7 @@ impl MemoryRatelimitChecker {
                 std::sync::atomic::Ordering::Relaxed,
             );
             self.ip_to_requests_today.lock().await.clear();
+            self.account_to_requests_today.lock().await.clear();
         }
     }
 }
@@ -73,30 +89,69 @@ impl CheckerTrait for MemoryRatelimitChecker {
         self.clear_if_new_day().await;
 
         let mut ip_to_requests_today = self.ip_to_requests_today.lock().await;
+        let mut account_to_requests_today = self.account_to_requests_today.lock().await;
 
-        let requests_today = ip_to_requests_today.get_or_insert_mut(data.source_ip, || 1);
-        if *requests_today >= self.max_requests_per_day {
-            return Ok(vec![RejectionReason::new(
+        // Check all the limits before incrementing any of the counters, so that
+        // a request rejected by one limit doesn't count towards the others.
+        let mut rejection_reasons = vec![];
+        let ip_requests_today = ip_to_requests_today.get_or_insert_mut(data.source_ip, || 0);
+        if *ip_requests_today >= self.max_requests_per_day {
+            rejection_reasons.push(RejectionReason::new(
                 format!(
                     "IP {} has exceeded the daily limit of {} requests",
                     data.source_ip, self.max_requests_per_day
                 ),
                 RejectionReasonCode::IpUsageLimitExhausted,
-            )]);
-        } else if !dry_run {
-            *requests_today += 1;
+            ));
+        }
+        let account_requests_today = match self.max_requests_per_account_per_day {
+            Some(max_requests_per_account_per_day) => {
+                let account_requests_today =
+                    account_to_requests_today.get_or_insert_mut(data.receiver, || 0);
+                if *account_requests_today >= max_requests_per_account_per_day {
+                    rejection_reasons.push(RejectionReason::new(
+                        format!(
+                            "Account {} has exceeded the daily limit of {} requests",
+                            data.receiver, max_requests_per_account_per_day
+                        ),
+                        RejectionReasonCode::AccountUsageLimitExhausted,
+                    ));
+                }
+                Some(account_requests_today)
+            },
+            None => None,
+        };
+
+        if rejection_reasons.is_empty() && !dry_run {
+            *ip_requests_today += 1;
+            if let Some(account_requests_today) = account_requests_today {
+                *account_requests_today += 1;
+            }
         }
 
-        Ok(vec![])
+        Ok(rejection_reasons)
     }
 
     async fn complete(&self, data: CompleteData) -> Result<(), AptosTapError> {
         if data.response_is_500 {
-            *self
+            if let Some(requests_today) = self
                 .ip_to_requests_today
                 .lock()
                 .await
-                .get_or_insert_mut(data.checker_data.source_ip, || 1) -= 1;
+                .get_mut(&data.checker_data.source_ip)
+            {
+                *requests_today = requests_today.saturating_sub(1);
+            }
+            if self.max_requests_per_account_per_day.is_some() {
+                if let Some(requests_today) = self
+                    .account_to_requests_today
+                    .lock()
+                    .await
+                    .get_mut(&data.checker_data.receiver)
+                {
+                    *requests_today = requests_today.saturating_sub(1);
+                }
+            }
         }
         Ok(())
     }
@@ -105,3 +160,116 @@ impl CheckerTrait for MemoryRatelimitChecker {
         20
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endpoints::USE_HELPFUL_ERRORS;
+    use poem::http::HeaderMap;
+    use std::sync::Arc;
+
+    fn checker_data(source_ip: &str, receiver: AccountAddress) -> CheckerData {
+        CheckerData {
+            time_request_received_secs: get_current_time_secs(),
+            receiver,
+            source_ip: source_ip.parse().unwrap(),
+            headers: Arc::new(HeaderMap::new()),
+        }
+    }
+
+    fn new_checker(
+        max_requests_per_day: u32,
+        max_requests_per_account_per_day: Option<u32>,
+    ) -> MemoryRatelimitChecker {
+        let _ = USE_HELPFUL_ERRORS.set(true);
+        MemoryRatelimitChecker::new(MemoryRatelimitCheckerConfig {
+            max_requests_per_day,
+            max_requests_per_account_per_day,
+            max_entries_in_map: MemoryRatelimitCheckerConfig::default_max_entries_in_map(),
+        })
+    }
+
+    async fn check_codes(
+        checker: &MemoryRatelimitChecker,
+        data: CheckerData,
+    ) -> Vec<RejectionReasonCode> {
+        checker
+            .check(data, false)
+            .await
+            .unwrap()
+            .iter()
+            .map(|reason| reason.get_code())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_ip_limit() {
+        let checker = new_checker(2, None);
+
+        // Exactly max_requests_per_day requests are allowed, regardless of receiver
+        for _ in 0..2 {
+            let data = checker_data("127.0.0.1", AccountAddress::random());
+            assert!(check_codes(&checker, data).await.is_empty());
+        }
+        let data = checker_data("127.0.0.1", AccountAddress::random());
+        assert_eq!(check_codes(&checker, data).await, vec![
+            RejectionReasonCode::IpUsageLimitExhausted
+        ]);
+
+        // Other IPs are unaffected
+        let data = checker_data("127.0.0.2", AccountAddress::random());
+        assert!(check_codes(&checker, data).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_account_limit() {
+        let checker = new_checker(10, Some(1));
+        let receiver = AccountAddress::random();
+
+        // Dry runs don't count towards the limit
+        let data = checker_data("127.0.0.1", receiver);
+        assert!(checker.check(data, true).await.unwrap().is_empty());
+
+        let data = checker_data("127.0.0.1", receiver);
+        assert!(check_codes(&checker, data).await.is_empty());
+
+        // The account limit applies across IPs
+        let data = checker_data("127.0.0.2", receiver);
+        assert_eq!(check_codes(&checker, data).await, vec![
+            RejectionReasonCode::AccountUsageLimitExhausted
+        ]);
+
+        // The rejected request didn't count towards the IP limit of 127.0.0.2
+        assert_eq!(
+            checker
+                .ip_to_requests_today
+                .lock()
+                .await
+                .get(&"127.0.0.2".parse().unwrap()),
+            Some(&0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_500s_are_not_counted() {
+        let checker = new_checker(1, Some(1));
+        let data = checker_data("127.0.0.1", AccountAddress::random());
+
+        assert!(check_codes(&checker, data.clone()).await.is_empty());
+        checker
+            .complete(CompleteData {
+                checker_data: data.clone(),
+                txn_hashes: vec![],
+                response_is_500: true,
+            })
+            .await
+            .unwrap();
+
+        // Both the IP and the account counters were decremented
+        assert!(check_codes(&checker, data.clone()).await.is_empty());
+        assert_eq!(check_codes(&checker, data).await, vec![
+            RejectionReasonCode::IpUsageLimitExhausted,
+            RejectionReasonCode::AccountUsageLimitExhausted
+        ]);
+    }
+}
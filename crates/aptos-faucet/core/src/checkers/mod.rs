@@ -10,17 +10,20 @@ mod redis_ratelimit;
 mod referer_blocklist;
 mod tap_captcha;
 
-pub use self::tap_captcha::CaptchaManager;
 use self::{
     auth_token::AuthTokenChecker,
     google_captcha::{CaptchaChecker as GoogleCaptchaChecker, GoogleCaptchaCheckerConfig},
     ip_blocklist::IpBlocklistChecker,
     magic_header::{MagicHeaderChecker, MagicHeaderCheckerConfig},
-    memory_ratelimit::{MemoryRatelimitChecker, MemoryRatelimitCheckerConfig},
-    redis_ratelimit::{RedisRatelimitChecker, RedisRatelimitCheckerConfig},
+    memory_ratelimit::MemoryRatelimitChecker,
+    redis_ratelimit::RedisRatelimitChecker,
     referer_blocklist::RefererBlocklistChecker,
     tap_captcha::{TapCaptchaChecker, TapCaptchaCheckerConfig},
 };
+pub use self::{
+    memory_ratelimit::MemoryRatelimitCheckerConfig, redis_ratelimit::RedisRatelimitCheckerConfig,
+    tap_captcha::CaptchaManager,
+};
 use crate::{
     common::{IpRangeManagerConfig, ListManagerConfig},
     endpoints::{AptosTapError, RejectionReason},
@@ -144,6 +147,22 @@ pub enum Checker {
     TapCaptchaChecker,
 }
 
+impl Checker {
+    /// A short, stable name for the checker, used e.g. as a metrics label.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Checker::AuthTokenChecker(_) => "auth_token",
+            Checker::GoogleCaptchaChecker(_) => "google_captcha",
+            Checker::IpBlocklistChecker(_) => "ip_blocklist",
+            Checker::MagicHeaderChecker(_) => "magic_header",
+            Checker::MemoryRatelimitChecker(_) => "memory_ratelimit",
+            Checker::RedisRatelimitChecker(_) => "redis_ratelimit",
+            Checker::RefererBlocklistChecker(_) => "referer_blocklist",
+            Checker::TapCaptchaChecker(_) => "tap_captcha",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CheckerData {
     pub time_request_received_secs: u64,
@@ -13,7 +13,6 @@ use deadpool_redis::{
     Config, Connection, Pool, Runtime,
 };
 use serde::{Deserialize, Serialize};
-use std::net::IpAddr;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RedisRatelimitCheckerConfig {
@@ -38,6 +37,11 @@ pub struct RedisRatelimitCheckerConfig {
     /// Max number of requests per IP per day. 500s are not counted, because
     /// they are not the user's fault, but everything else is.
     pub max_requests_per_ip_per_day: u32,
+
+    /// Max number of requests per receiver account per day. If not set,
+    /// requests are only limited per IP. As above, 500s are not counted.
+    #[serde(default)]
+    pub max_requests_per_account_per_day: Option<u32>,
 }
 
 impl RedisRatelimitCheckerConfig {
@@ -79,7 +83,7 @@ impl RedisRatelimitCheckerConfig {
 /// If we're not careful, it is possible for people to exceed the intended limit
 /// by sending many requests simulatenously. We avoid this problem with this
 /// order of operations:
-///   1. Read the current value of the limit for source IP.
+///   1. Read the current value of the limit for source IP (and receiver account).
 ///   2. If value is greater than limit, reject.
 ///   3. Otherwise, increment and set TTL if necessary.
 ///   4. Increment returns the new value. Check if this is greater than the limit also.
@@ -128,28 +132,44 @@ impl RedisRatelimitChecker {
         })
     }
 
-    // Returns the key and the seconds until the next day.
-    fn get_key_and_secs_until_next_day(&self, source_ip: &IpAddr) -> (String, u64) {
+    // Returns the limits that apply to the given request, including the key
+    // for each limit. Also returns the seconds until the next day.
+    fn get_limits_and_secs_until_next_day(&self, data: &CheckerData) -> (Vec<Limit>, u64) {
         let now_secs = get_current_time_secs();
         let seconds_until_next_day = seconds_until_next_day(now_secs);
-        let key = format!("ip:{}:{}", source_ip, days_since_tap_epoch(now_secs));
-        (key, seconds_until_next_day)
+        let day = days_since_tap_epoch(now_secs);
+
+        let mut limits = vec![Limit {
+            key: format!("ip:{}:{}", data.source_ip, day),
+            subject: format!("IP {}", data.source_ip),
+            max_requests_per_day: self.args.max_requests_per_ip_per_day,
+            code: RejectionReasonCode::IpUsageLimitExhausted,
+        }];
+        if let Some(max_requests_per_account_per_day) = self.args.max_requests_per_account_per_day {
+            limits.push(Limit {
+                key: format!("account:{}:{}", data.receiver, day),
+                subject: format!("Account {}", data.receiver),
+                max_requests_per_day: max_requests_per_account_per_day,
+                code: RejectionReasonCode::AccountUsageLimitExhausted,
+            });
+        }
+        (limits, seconds_until_next_day)
     }
 
     fn check_limit_value(
         &self,
-        data: &CheckerData,
+        limit: &Limit,
         limit_value: Option<i64>,
         seconds_until_next_day: u64,
     ) -> Option<RejectionReason> {
-        if limit_value.unwrap_or(0) > self.args.max_requests_per_ip_per_day as i64 {
+        if limit_value.unwrap_or(0) > limit.max_requests_per_day as i64 {
             Some(
                 RejectionReason::new(
                     format!(
-                        "IP {} has reached the maximum allowed number of requests per day: {}",
-                        data.source_ip, self.args.max_requests_per_ip_per_day
+                        "{} has reached the maximum allowed number of requests per day: {}",
+                        limit.subject, limit.max_requests_per_day
                     ),
-                    RejectionReasonCode::IpUsageLimitExhausted,
+                    limit.code,
                 )
                 .retry_after(seconds_until_next_day),
             )
@@ -159,6 +179,17 @@ impl RedisRatelimitChecker {
     }
 }
 
+/// A single daily limit enforced by the RedisRatelimitChecker, e.g. the limit
+/// for the source IP of a request.
+struct Limit {
+    /// The redis key for the counter, which includes the current day.
+    key: String,
+    /// A human readable description of what is being limited, for rejections.
+    subject: String,
+    max_requests_per_day: u32,
+    code: RejectionReasonCode,
+}
+
 #[async_trait]
 impl CheckerTrait for RedisRatelimitChecker {
     async fn check(
@@ -171,70 +202,84 @@ impl CheckerTrait for RedisRatelimitChecker {
             .await
             .map_err(|e| AptosTapError::new_with_error_code(e, AptosTapErrorCode::StorageError))?;
 
-        // Generate a key corresponding to this IP address and the current day.
-        let (key, seconds_until_next_day) = self.get_key_and_secs_until_next_day(&data.source_ip);
+        // Generate a key for each limit (e.g. this IP address) and the current day.
+        let (limits, seconds_until_next_day) = self.get_limits_and_secs_until_next_day(&data);
 
-        // Get the value for the key, indicating how many non-500 requests we
-        // have serviced for this it today.
-        let limit_value: Option<i64> = conn.get(&key).await.map_err(|e| {
-            AptosTapError::new_with_error_code(
-                format!("Failed to get value for redis key {}: {}", key, e),
-                AptosTapErrorCode::StorageError,
-            )
-        })?;
-
-        // If the limit value is greater than what we allow per day, signal
-        // that we should reject this request.
-        if let Some(rejection_reason) =
-            self.check_limit_value(&data, limit_value, seconds_until_next_day)
-        {
-            return Ok(vec![rejection_reason]);
+        // Get the value for each key, indicating how many non-500 requests we
+        // have serviced for it today. If any limit value is greater than what
+        // we allow per day, signal that we should reject this request.
+        let mut limit_values = Vec::with_capacity(limits.len());
+        let mut rejection_reasons = vec![];
+        for limit in &limits {
+            let limit_value: Option<i64> = conn.get(&limit.key).await.map_err(|e| {
+                AptosTapError::new_with_error_code(
+                    format!("Failed to get value for redis key {}: {}", limit.key, e),
+                    AptosTapErrorCode::StorageError,
+                )
+            })?;
+            if let Some(rejection_reason) =
+                self.check_limit_value(limit, limit_value, seconds_until_next_day)
+            {
+                rejection_reasons.push(rejection_reason);
+            }
+            limit_values.push(limit_value);
+        }
+        if !rejection_reasons.is_empty() {
+            return Ok(rejection_reasons);
         }
 
-        // Atomically increment the counter for the given IP, creating it and
+        // Atomically increment the counter for each limit, creating it and
         // setting the expiration time if it doesn't already exist.
         if !dry_run {
-            // If the limit value already exists, just increment.
-            let incremented_limit_value = match limit_value {
-                Some(_) => conn.incr(&key, 1).await.map_err(|e| {
-                    AptosTapError::new_with_error_code(
-                        format!("Failed to increment redis key {}: {}", key, e),
-                        AptosTapErrorCode::StorageError,
-                    )
-                })?,
-                // If the limit value doesn't exist, create it and set the
-                // expiration time.
-                None => {
-                    let (incremented_limit_value,): (i64,) = redis::pipe()
-                        .atomic()
-                        .incr(&key, 1)
-                        // Expire at the end of the day roughly.
-                        .expire(&key, seconds_until_next_day as usize)
-                        // Only set the expiration if one isn't already set.
-                        // Only works with Redis 7 sadly.
-                        // .arg("NX")
-                        .ignore()
-                        .query_async(&mut *conn)
-                        .await
-                        .map_err(|e| {
-                            AptosTapError::new_with_error_code(
-                                format!("Failed to increment value for redis key {}: {}", key, e),
-                                AptosTapErrorCode::StorageError,
-                            )
-                        })?;
-                    incremented_limit_value
-                },
-            };
-
-            // Check limit again, to ensure there wasn't a get / set race.
-            if let Some(rejection_reason) =
-                self.check_limit_value(&data, Some(incremented_limit_value), seconds_until_next_day)
-            {
-                return Ok(vec![rejection_reason]);
+            for (limit, limit_value) in limits.iter().zip(limit_values) {
+                let key = &limit.key;
+                // If the limit value already exists, just increment.
+                let incremented_limit_value = match limit_value {
+                    Some(_) => conn.incr(key, 1).await.map_err(|e| {
+                        AptosTapError::new_with_error_code(
+                            format!("Failed to increment redis key {}: {}", key, e),
+                            AptosTapErrorCode::StorageError,
+                        )
+                    })?,
+                    // If the limit value doesn't exist, create it and set the
+                    // expiration time.
+                    None => {
+                        let (incremented_limit_value,): (i64,) = redis::pipe()
+                            .atomic()
+                            .incr(key, 1)
+                            // Expire at the end of the day roughly.
+                            .expire(key, seconds_until_next_day as usize)
+                            // Only set the expiration if one isn't already set.
+                            // Only works with Redis 7 sadly.
+                            // .arg("NX")
+                            .ignore()
+                            .query_async(&mut *conn)
+                            .await
+                            .map_err(|e| {
+                                AptosTapError::new_with_error_code(
+                                    format!(
+                                        "Failed to increment value for redis key {}: {}",
+                                        key, e
+                                    ),
+                                    AptosTapErrorCode::StorageError,
+                                )
+                            })?;
+                        incremented_limit_value
+                    },
+                };
+
+                // Check limit again, to ensure there wasn't a get / set race.
+                if let Some(rejection_reason) = self.check_limit_value(
+                    limit,
+                    Some(incremented_limit_value),
+                    seconds_until_next_day,
+                ) {
+                    rejection_reasons.push(rejection_reason);
+                }
             }
         }
 
-        Ok(vec![])
+        Ok(rejection_reasons)
     }
 
     /// All we have to do here is decrement the counter if the request was a
@@ -249,15 +294,20 @@ impl CheckerTrait for RedisRatelimitChecker {
             .await
             .map_err(|e| AptosTapError::new_with_error_code(e, AptosTapErrorCode::StorageError))?;
 
-        // Generate a key corresponding to this IP address and the current day.
-        let (key, _) = self.get_key_and_secs_until_next_day(&data.checker_data.source_ip);
+        // Generate a key for each limit (e.g. this IP address) and the current day.
+        let (limits, _) = self.get_limits_and_secs_until_next_day(&data.checker_data);
 
-        conn.decr(&key, 1).await.map_err(|e| {
-            AptosTapError::new_with_error_code(
-                format!("Failed to decrement value for redis key {}: {}", key, e),
-                AptosTapErrorCode::StorageError,
-            )
-        })?;
+        for limit in limits {
+            conn.decr(&limit.key, 1).await.map_err(|e| {
+                AptosTapError::new_with_error_code(
+                    format!(
+                        "Failed to decrement value for redis key {}: {}",
+                        limit.key, e
+                    ),
+                    AptosTapErrorCode::StorageError,
+                )
+            })?;
+        }
         Ok(())
     }
 
@@ -64,7 +64,11 @@ impl AptosTapError {
     pub fn status_and_retry_after(&self) -> (StatusCode, Option<u64>) {
         let (mut status_code, mut retry_after) = (self.error_code.status(), None);
         for rejection_reason in &self.rejection_reasons {
-            if rejection_reason.code == RejectionReasonCode::IpUsageLimitExhausted {
+            if matches!(
+                rejection_reason.code,
+                RejectionReasonCode::IpUsageLimitExhausted
+                    | RejectionReasonCode::AccountUsageLimitExhausted
+            ) {
                 status_code = StatusCode::TOO_MANY_REQUESTS;
                 retry_after = rejection_reason.retry_after;
                 break;
@@ -134,6 +138,9 @@ pub enum AptosTapErrorCode {
     /// The user tried to call an endpoint that is not enabled.
     EndpointNotEnabled = 45,
 
+    /// The requested fund job does not exist (or has expired).
+    JobNotFound = 46,
+
     /// Failed when making requests to the Aptos API.
     AptosApiError = 50,
 
@@ -168,6 +175,7 @@ impl AptosTapErrorCode {
             AptosTapErrorCode::InvalidRequest
             | AptosTapErrorCode::AccountDoesNotExist
             | AptosTapErrorCode::EndpointNotEnabled => StatusCode::BAD_REQUEST,
+            AptosTapErrorCode::JobNotFound => StatusCode::NOT_FOUND,
             AptosTapErrorCode::Rejected
             | AptosTapErrorCode::SourceIpMissing
             | AptosTapErrorCode::TransactionFailed => StatusCode::FORBIDDEN,
@@ -256,4 +264,7 @@ pub enum RejectionReasonCode {
 
     /// Referer was in the blocklist.
     RefererBlocklisted = 108,
+
+    /// Account has exhausted its usage limit.
+    AccountUsageLimitExhausted = 109,
 }
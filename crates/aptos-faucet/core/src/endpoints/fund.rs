@@ -3,6 +3,7 @@
 
 use super::{
     errors::{AptosTapError, AptosTapErrorResponse},
+    fund_queue::{FundJobStatus, FundQueue},
     ApiTags,
 };
 use crate::{
@@ -11,6 +12,7 @@ use crate::{
     endpoints::AptosTapErrorCode,
     funder::{Funder, FunderTrait},
     helpers::{get_current_time_secs, transaction_hashes},
+    middleware::{bump_checker_rejection_counters, get_trace_id, FUNDING_LATENCY},
 };
 use aptos_logger::info;
 use aptos_sdk::{
@@ -22,6 +24,7 @@ use aptos_sdk::{
 };
 use poem::{http::HeaderMap, web::RealIp};
 use poem_openapi::{
+    param::Path,
     payload::{Json, PlainText},
     ApiResponse, Object, OpenApi,
 };
@@ -50,6 +53,12 @@ pub struct FundResponse {
     pub txn_hashes: Vec<String>,
 }
 
+#[derive(Clone, Debug, Object)]
+pub struct FundAsyncResponse {
+    /// Use this with the `/status/{job_id}` endpoint to check on the job.
+    pub job_id: String,
+}
+
 impl FundRequest {
     pub fn receiver(&self) -> Option<AccountAddress> {
         if let Some(auth_key) = self.auth_key.as_ref() {
@@ -122,6 +131,64 @@ impl FundApi {
         }))
     }
 
+    /// Funds an account asynchronously
+    ///
+    /// This endpoint runs the same checks as `fund`, but rather than waiting
+    /// for the account to be funded, it enqueues the request and immediately
+    /// returns a job ID. Use `/status/{job_id}` to find out how the job went.
+    /// This endpoint is only available if the fund queue is enabled.
+    #[oai(
+        path = "/fund_async",
+        method = "post",
+        operation_id = "fund_async",
+        tag = "ApiTags::Fund"
+    )]
+    async fn fund_async(
+        &self,
+        fund_request: Json<FundRequest>,
+        // This automagically uses FromRequest to get this data from the request.
+        // It takes into things like X-Forwarded-IP and X-Real-IP.
+        source_ip: RealIp,
+        // Same thing, this uses FromRequest.
+        header_map: &HeaderMap,
+    ) -> poem::Result<Json<FundAsyncResponse>, AptosTapErrorResponse> {
+        let fund_queue = self.components.get_fund_queue()?;
+        let (checker_data, bypass, _semaphore_permit) = self
+            .components
+            .preprocess_request(&fund_request.0, source_ip, header_map, false)
+            .await?;
+        let job_id = fund_queue
+            .enqueue(fund_request.amount, checker_data, bypass)
+            .await?;
+        Ok(Json(FundAsyncResponse { job_id }))
+    }
+
+    /// Get the status of an asynchronous funding job
+    ///
+    /// With this endpoint you can check on a job created by `fund_async`. The
+    /// status of finished jobs is only kept around for a limited time, after
+    /// which this returns a 404.
+    #[oai(
+        path = "/status/:job_id",
+        method = "get",
+        operation_id = "status",
+        tag = "ApiTags::Fund"
+    )]
+    async fn status(
+        &self,
+        job_id: Path<String>,
+    ) -> poem::Result<Json<FundJobStatus>, AptosTapErrorResponse> {
+        let fund_queue = self.components.get_fund_queue()?;
+        match fund_queue.get_status(&job_id.0).await {
+            Some(status) => Ok(Json(status)),
+            None => Err(AptosTapError::new(
+                format!("Fund job {} not found", job_id.0),
+                AptosTapErrorCode::JobNotFound,
+            )
+            .into()),
+        }
+    }
+
     /// Check whether a given requester is eligible to be funded
     ///
     /// This function runs only the various eligibility checks that we perform
@@ -181,9 +248,22 @@ pub struct FundApiComponents {
     /// This semaphore is used to ensure we only process a certain number of
     /// requests concurrently.
     pub concurrent_requests_semaphore: Option<Arc<Semaphore>>,
+
+    /// If set, requests to `fund_async` are enqueued here and funded in the
+    /// background.
+    pub fund_queue: Option<Arc<FundQueue>>,
 }
 
 impl FundApiComponents {
+    fn get_fund_queue(&self) -> Result<&FundQueue, AptosTapError> {
+        self.fund_queue.as_deref().ok_or_else(|| {
+            AptosTapError::new(
+                "The fund queue is not enabled".to_string(),
+                AptosTapErrorCode::EndpointNotEnabled,
+            )
+        })
+    }
+
     /// Preprocesses the request to return the source IP, receiver account
     /// address and requested amount taking into account Funder configuration
     /// (i.e. max amount). It also ensures the request passes checkers.
@@ -258,9 +338,14 @@ impl FundApiComponents {
         // Ensure request passes checkers.
         let mut rejection_reasons = Vec::new();
         for checker in &self.checkers {
-            rejection_reasons.extend(checker.check(checker_data.clone(), dry_run).await.map_err(
-                |e| AptosTapError::new_with_error_code(e, AptosTapErrorCode::CheckerError),
-            )?);
+            let checker_rejection_reasons = checker
+                .check(checker_data.clone(), dry_run)
+                .await
+                .map_err(|e| {
+                    AptosTapError::new_with_error_code(e, AptosTapErrorCode::CheckerError)
+                })?;
+            bump_checker_rejection_counters(checker.name(), &checker_rejection_reasons);
+            rejection_reasons.extend(checker_rejection_reasons);
             if !rejection_reasons.is_empty() && self.return_rejections_early {
                 break;
             }
@@ -290,12 +375,47 @@ impl FundApiComponents {
         let (checker_data, bypass, _semaphore_permit) = self
             .preprocess_request(&fund_request, source_ip, header_map, dry_run)
             .await?;
+        let (fund_result, _) = self
+            .fund_and_complete(fund_request.amount, checker_data, bypass, None)
+            .await;
+        fund_result
+    }
 
+    /// Funds the account of a request that has already been preprocessed and
+    /// then runs the completion step of the Checkers. This returns the result
+    /// of funding along with the hashes of any submitted transactions. If
+    /// `worker_index` is set, the account is funded as that fund queue worker.
+    pub(super) async fn fund_and_complete(
+        &self,
+        amount: Option<u64>,
+        checker_data: CheckerData,
+        bypass: bool,
+        worker_index: Option<usize>,
+    ) -> (
+        poem::Result<Vec<SignedTransaction>, AptosTapError>,
+        Vec<String>,
+    ) {
         // Fund the account.
-        let fund_result = self
-            .funder
-            .fund(fund_request.amount, checker_data.receiver, false, bypass)
-            .await;
+        let start = std::time::Instant::now();
+        let fund_result = match worker_index {
+            Some(worker_index) => {
+                self.funder
+                    .fund_as_worker(worker_index, amount, checker_data.receiver, bypass)
+                    .await
+            },
+            None => {
+                self.funder
+                    .fund(amount, checker_data.receiver, false, bypass)
+                    .await
+            },
+        };
+        FUNDING_LATENCY
+            .with_label_values(&[if fund_result.is_ok() {
+                "success"
+            } else {
+                "failure"
+            }])
+            .observe(start.elapsed().as_secs_f64());
 
         // This might be empty if there is an error and we never got to the
         // point where we could submit a transaction.
@@ -308,9 +428,10 @@ impl FundApiComponents {
         info!(
             source_ip = checker_data.source_ip,
             address = checker_data.receiver,
-            requested_amount = fund_request.amount,
+            requested_amount = amount,
             txn_hashes = txn_hashes,
             success = fund_result.is_ok(),
+            trace_id = get_trace_id(&checker_data.headers),
         );
 
         // Give all Checkers the chance to run the completion step. We should
@@ -327,13 +448,19 @@ impl FundApiComponents {
                 response_is_500,
             };
             for checker in &self.checkers {
-                checker.complete(complete_data.clone()).await.map_err(|e| {
-                    AptosTapError::new_with_error_code(e, AptosTapErrorCode::CheckerError)
-                })?;
+                if let Err(e) = checker.complete(complete_data.clone()).await {
+                    return (
+                        Err(AptosTapError::new_with_error_code(
+                            e,
+                            AptosTapErrorCode::CheckerError,
+                        )),
+                        txn_hashes,
+                    );
+                }
             }
         }
 
-        fund_result
+        (fund_result, txn_hashes)
     }
 }
 
@@ -0,0 +1,273 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! When the fund queue is enabled, funding requests can be processed
+//! asynchronously. Requests that pass the Bypassers / Checkers are enqueued and
+//! a pool of workers funds them in the background, while the client gets a job
+//! ID back immediately that it can use to poll the status of the job.
+
+use super::{AptosTapError, AptosTapErrorCode, FundApiComponents};
+use crate::{checkers::CheckerData, middleware::NUM_QUEUED_FUND_JOBS};
+use aptos_logger::info;
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{mpsc, Mutex, RwLock},
+    task::JoinSet,
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FundQueueConfig {
+    /// The number of workers funding queued requests. Each worker funds one
+    /// request at a time. With a funder that sends from several accounts
+    /// (e.g. the WorkerPoolFunder), each worker is pinned to one of them and
+    /// manages its sequence number and outstanding requests on its own.
+    /// Otherwise the workers share the funder account, like the synchronous
+    /// path does.
+    #[serde(default = "FundQueueConfig::default_num_workers")]
+    pub num_workers: usize,
+
+    /// The maximum number of requests waiting to be funded. If the queue is
+    /// full, new requests are rejected.
+    #[serde(default = "FundQueueConfig::default_max_queued_jobs")]
+    pub max_queued_jobs: usize,
+
+    /// How long we keep the status of finished jobs around for.
+    #[serde(default = "FundQueueConfig::default_job_retention_secs")]
+    pub job_retention_secs: u64,
+}
+
+impl FundQueueConfig {
+    fn default_num_workers() -> usize {
+        4
+    }
+
+    fn default_max_queued_jobs() -> usize {
+        1000
+    }
+
+    fn default_job_retention_secs() -> u64 {
+        600
+    }
+}
+
+/// The state of a funding job.
+#[derive(Copy, Clone, Debug, Enum, Eq, PartialEq)]
+pub enum FundJobState {
+    /// The job is waiting for a worker.
+    Queued,
+    /// A worker is funding the account.
+    Processing,
+    /// The account was funded.
+    Succeeded,
+    /// Funding the account failed, see `error` for more details.
+    Failed,
+}
+
+impl FundJobState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, FundJobState::Succeeded | FundJobState::Failed)
+    }
+}
+
+#[derive(Clone, Debug, Object)]
+pub struct FundJobStatus {
+    pub job_id: String,
+    pub state: FundJobState,
+    /// Submitted transaction hashes, if it got to that point.
+    pub txn_hashes: Vec<String>,
+    /// If the job failed, the error explaining why.
+    pub error: Option<AptosTapError>,
+}
+
+/// A request that passed the Bypassers / Checkers and is waiting to be funded.
+struct FundJob {
+    job_id: String,
+    amount: Option<u64>,
+    checker_data: CheckerData,
+    bypass: bool,
+}
+
+#[derive(Default)]
+struct FundJobs {
+    statuses: HashMap<String, FundJobStatus>,
+    /// The IDs of the finished jobs, along with when they finished. Jobs are
+    /// appended as they finish, so the oldest ones are always at the front.
+    finished: VecDeque<(Instant, String)>,
+}
+
+impl FundJobs {
+    /// Forgets about jobs that finished more than `job_retention` ago.
+    fn remove_expired(&mut self, job_retention: Duration) {
+        while let Some((finished_at, _)) = self.finished.front() {
+            if finished_at.elapsed() < job_retention {
+                break;
+            }
+            let (_, job_id) = self.finished.pop_front().unwrap();
+            self.statuses.remove(&job_id);
+        }
+    }
+}
+
+pub struct FundQueue {
+    job_retention: Duration,
+    job_sender: mpsc::Sender<FundJob>,
+    job_receiver: Mutex<mpsc::Receiver<FundJob>>,
+    jobs: RwLock<FundJobs>,
+}
+
+impl FundQueue {
+    pub fn new(config: &FundQueueConfig) -> Self {
+        let (job_sender, job_receiver) = mpsc::channel(config.max_queued_jobs.max(1));
+        Self {
+            job_retention: Duration::from_secs(config.job_retention_secs),
+            job_sender,
+            job_receiver: Mutex::new(job_receiver),
+            jobs: RwLock::new(FundJobs::default()),
+        }
+    }
+
+    /// Spawns the workers that fund the queued requests.
+    pub fn spawn_workers(
+        self: &Arc<Self>,
+        num_workers: usize,
+        components: Arc<FundApiComponents>,
+        join_set: &mut JoinSet<anyhow::Result<()>>,
+    ) {
+        for worker_index in 0..num_workers {
+            let fund_queue = self.clone();
+            let components = components.clone();
+            join_set.spawn(async move {
+                fund_queue.run_worker(worker_index, &components).await;
+                Err(anyhow::anyhow!("Fund queue worker ended unexpectedly"))
+            });
+        }
+    }
+
+    /// Enqueues a request that has passed the Bypassers / Checkers, returning
+    /// the ID of the job.
+    pub(super) async fn enqueue(
+        &self,
+        amount: Option<u64>,
+        checker_data: CheckerData,
+        bypass: bool,
+    ) -> Result<String, AptosTapError> {
+        let job_id = format!("{:032x}", rand::random::<u128>());
+        let job = FundJob {
+            job_id: job_id.clone(),
+            amount,
+            checker_data,
+            bypass,
+        };
+
+        // Register the job before sending it so a worker can always find it.
+        self.insert_job(&job_id).await;
+        if self.job_sender.try_send(job).is_err() {
+            self.jobs.write().await.statuses.remove(&job_id);
+            return Err(AptosTapError::new(
+                "Fund queue is full, please try again later".to_string(),
+                AptosTapErrorCode::ServerOverloaded,
+            ));
+        }
+        NUM_QUEUED_FUND_JOBS.inc();
+
+        Ok(job_id)
+    }
+
+    /// Returns the status of the given job, if we know about it.
+    pub(super) async fn get_status(&self, job_id: &str) -> Option<FundJobStatus> {
+        self.jobs.read().await.statuses.get(job_id).cloned()
+    }
+
+    async fn insert_job(&self, job_id: &str) {
+        let mut jobs = self.jobs.write().await;
+
+        // Forget about jobs that finished a while ago.
+        jobs.remove_expired(self.job_retention);
+
+        jobs.statuses.insert(job_id.to_string(), FundJobStatus {
+            job_id: job_id.to_string(),
+            state: FundJobState::Queued,
+            txn_hashes: vec![],
+            error: None,
+        });
+    }
+
+    async fn update_job(
+        &self,
+        job_id: &str,
+        state: FundJobState,
+        txn_hashes: Vec<String>,
+        error: Option<AptosTapError>,
+    ) {
+        let mut jobs = self.jobs.write().await;
+        let jobs = &mut *jobs;
+        if let Some(status) = jobs.statuses.get_mut(job_id) {
+            status.state = state;
+            status.txn_hashes = txn_hashes;
+            status.error = error;
+            if state.is_finished() {
+                jobs.finished
+                    .push_back((Instant::now(), job_id.to_string()));
+            }
+        }
+    }
+
+    async fn run_worker(&self, worker_index: usize, components: &FundApiComponents) {
+        loop {
+            // Only hold the lock while waiting for the next job, so the other
+            // workers can pick up jobs while this one is funding.
+            let job = match self.job_receiver.lock().await.recv().await {
+                Some(job) => job,
+                None => return,
+            };
+            NUM_QUEUED_FUND_JOBS.dec();
+
+            self.update_job(&job.job_id, FundJobState::Processing, vec![], None)
+                .await;
+            let (fund_result, txn_hashes) = components
+                .fund_and_complete(job.amount, job.checker_data, job.bypass, Some(worker_index))
+                .await;
+            let (state, error) = match fund_result {
+                Ok(_) => (FundJobState::Succeeded, None),
+                Err(e) => (FundJobState::Failed, Some(e)),
+            };
+            info!(job_id = job.job_id, state = format!("{:?}", state));
+            self.update_job(&job.job_id, state, txn_hashes, error).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_finished_jobs_expire() {
+        let fund_queue = FundQueue::new(&FundQueueConfig {
+            num_workers: 1,
+            max_queued_jobs: 10,
+            job_retention_secs: 0,
+        });
+
+        // Finish one job and leave another one queued.
+        fund_queue.insert_job("finished").await;
+        fund_queue.insert_job("queued").await;
+        fund_queue
+            .update_job("finished", FundJobState::Succeeded, vec![], None)
+            .await;
+        assert!(fund_queue.get_status("finished").await.is_some());
+
+        // Only the finished job is forgotten once another job is inserted.
+        fund_queue.insert_job("new").await;
+        assert!(fund_queue.get_status("finished").await.is_none());
+        assert!(fund_queue.get_status("queued").await.is_some());
+        assert!(fund_queue.get_status("new").await.is_some());
+        assert!(fund_queue.jobs.read().await.finished.is_empty());
+    }
+}
@@ -7,6 +7,7 @@ mod captcha;
 mod error_converter;
 mod errors;
 mod fund;
+mod fund_queue;
 
 pub use self::captcha::{CaptchaApi, CAPTCHA_KEY, CAPTCHA_VALUE};
 pub use api::build_openapi_service;
@@ -15,7 +16,8 @@ pub use error_converter::convert_error;
 pub use errors::{
     AptosTapError, AptosTapErrorCode, RejectionReason, RejectionReasonCode, USE_HELPFUL_ERRORS,
 };
-pub use fund::{mint, FundApi, FundApiComponents, FundRequest, FundResponse};
+pub use fund::{mint, FundApi, FundApiComponents, FundAsyncResponse, FundRequest, FundResponse};
+pub use fund_queue::{FundJobState, FundJobStatus, FundQueue, FundQueueConfig};
 use poem_openapi::Tags;
 
 /// API categories for the OpenAPI spec
@@ -4,7 +4,7 @@
 
 use crate::{
     endpoints::{AptosTapError, AptosTapErrorCode},
-    middleware::NUM_OUTSTANDING_TRANSACTIONS,
+    middleware::{NUM_OUTSTANDING_TRANSACTIONS, SEQUENCE_NUMBER_RETRIES, TRANSACTION_GAS_USED},
 };
 use anyhow::{anyhow, Context, Result};
 use aptos_config::keys::ConfigKey;
@@ -285,6 +285,9 @@ pub async fn update_sequence_numbers(
 
         // Report the number of outstanding transactions.
         NUM_OUTSTANDING_TRANSACTIONS.set(num_outstanding as i64);
+        SEQUENCE_NUMBER_RETRIES
+            .with_label_values(&["too_many_outstanding"])
+            .inc();
 
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         (funder_seq, receiver_seq) =
@@ -298,6 +301,7 @@ pub async fn update_sequence_numbers(
         if funder_account.sequence_number() >= funder_seq + MAX_NUM_OUTSTANDING_TRANSACTIONS {
             info!("Resetting the sequence number counter.");
             funder_account.set_sequence_number(funder_seq);
+            SEQUENCE_NUMBER_RETRIES.with_label_values(&["reset"]).inc();
         } else {
             info!("Someone else reset the sequence number counter ahead of us.");
         }
@@ -362,7 +366,9 @@ pub async fn submit_transaction(
             client
                 .submit_and_wait_bcs(&signed_transaction)
                 .await
-                .map(|_| ())
+                .map(|response| {
+                    TRANSACTION_GAS_USED.observe(response.inner().info.gas_used() as f64)
+                })
                 .map_err(|e| {
                     AptosTapError::new_with_error_code(e, AptosTapErrorCode::TransactionFailed)
                 }),
@@ -394,6 +400,9 @@ pub async fn submit_transaction(
         },
         Err(e) => {
             faucet_account.write().await.decrement_sequence_number();
+            SEQUENCE_NUMBER_RETRIES
+                .with_label_values(&["submission_failure"])
+                .inc();
             warn!(
                 hash = signed_transaction.clone().committed_hash(),
                 address = receiver_address,
@@ -0,0 +1,573 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    common::{
+        submit_transaction, update_sequence_numbers, ApiConnectionConfig, GasUnitPriceManager,
+        TransactionSubmissionConfig,
+    },
+    FunderHealthMessage, FunderTrait,
+};
+use crate::endpoints::{AptosTapError, AptosTapErrorCode};
+use anyhow::{bail, Context, Result};
+use aptos_logger::info;
+use aptos_sdk::{
+    bcs,
+    crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    move_types::{identifier::Identifier, language_storage::ModuleId},
+    rest_client::Client,
+    transaction_builder::TransactionFactory,
+    types::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        transaction::{
+            authenticator::AuthenticationKey, EntryFunction, SignedTransaction, TransactionPayload,
+        },
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+
+/// The Move minter module the MinterContractFunder calls into, along with the
+/// names of its entry functions. The module is expected to expose:
+///
+/// ```text
+/// public entry fun mint(capability_holder: &signer, receiver: address, amount: u64)
+/// public entry fun rotate_capability(capability_holder: &signer, new_holder: address)
+/// public entry fun claim_capability(new_holder: &signer)
+/// ```
+///
+/// Since all funding goes through this module, it can enforce (and record) a
+/// cap on the total supply handed out by the faucet on chain.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MinterModuleConfig {
+    /// Address of the account that published the minter module.
+    pub minter_module_address: AccountAddress,
+
+    #[serde(default = "MinterModuleConfig::default_minter_module_name")]
+    pub minter_module_name: String,
+
+    #[serde(default = "MinterModuleConfig::default_mint_function_name")]
+    pub mint_function_name: String,
+
+    #[serde(default = "MinterModuleConfig::default_rotate_capability_function_name")]
+    pub rotate_capability_function_name: String,
+
+    #[serde(default = "MinterModuleConfig::default_claim_capability_function_name")]
+    pub claim_capability_function_name: String,
+}
+
+impl MinterModuleConfig {
+    fn default_minter_module_name() -> String {
+        "faucet_minter".to_string()
+    }
+
+    fn default_mint_function_name() -> String {
+        "mint".to_string()
+    }
+
+    fn default_rotate_capability_function_name() -> String {
+        "rotate_capability".to_string()
+    }
+
+    fn default_claim_capability_function_name() -> String {
+        "claim_capability".to_string()
+    }
+
+    fn module_id(&self) -> Result<ModuleId> {
+        Ok(ModuleId::new(
+            self.minter_module_address,
+            Identifier::new(self.minter_module_name.as_str())
+                .context("Invalid minter module name")?,
+        ))
+    }
+
+    /// Builds the payload for calling the given entry function of the minter module.
+    fn entry_function_payload(
+        &self,
+        function_name: &str,
+        args: Vec<Vec<u8>>,
+    ) -> Result<TransactionPayload> {
+        Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+            self.module_id()?,
+            Identifier::new(function_name)
+                .with_context(|| format!("Invalid minter function name: {}", function_name))?,
+            vec![],
+            args,
+        )))
+    }
+
+    fn mint_payload(
+        &self,
+        receiver_address: AccountAddress,
+        amount: u64,
+    ) -> Result<TransactionPayload> {
+        self.entry_function_payload(&self.mint_function_name, vec![
+            bcs::to_bytes(&receiver_address)?,
+            bcs::to_bytes(&amount)?,
+        ])
+    }
+
+    fn rotate_capability_payload(&self, new_holder: AccountAddress) -> Result<TransactionPayload> {
+        self.entry_function_payload(&self.rotate_capability_function_name, vec![
+            bcs::to_bytes(&new_holder)?,
+        ])
+    }
+
+    fn claim_capability_payload(&self) -> Result<TransactionPayload> {
+        self.entry_function_payload(&self.claim_capability_function_name, vec![])
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MinterContractFunderConfig {
+    #[serde(flatten)]
+    pub api_connection_config: ApiConnectionConfig,
+
+    #[serde(flatten)]
+    pub transaction_submission_config: TransactionSubmissionConfig,
+
+    #[serde(flatten)]
+    pub minter_module_config: MinterModuleConfig,
+
+    /// Address of the account holding the minter capability. If not given, we
+    /// use the account address corresponding to the given private key.
+    pub capability_account_address: Option<AccountAddress>,
+
+    /// If set, the capability is rotated to a freshly generated account on
+    /// startup, so the configured key is only used to hand over the capability.
+    /// Requires capability_key_file_path to be set.
+    #[serde(default)]
+    pub rotate_capability_on_startup: bool,
+
+    /// Where the key of the account the capability is rotated to gets stored.
+    /// The key is written before the rotation starts, so the capability is never
+    /// handed to an account whose key we could lose. If the file already exists
+    /// on startup, that account is used (and the rotation completed if needed)
+    /// rather than rotating again.
+    pub capability_key_file_path: Option<PathBuf>,
+
+    /// How many transactions the capability account should be able to pay gas
+    /// for when it is funded during a rotation.
+    #[serde(default = "MinterContractFunderConfig::default_capability_account_gas_txns")]
+    pub capability_account_gas_txns: u64,
+}
+
+impl MinterContractFunderConfig {
+    fn default_capability_account_gas_txns() -> u64 {
+        100
+    }
+
+    pub async fn build_funder(self) -> Result<MinterContractFunder> {
+        // Make sure the configured module and function names are valid.
+        self.minter_module_config
+            .mint_payload(AccountAddress::ZERO, 0)
+            .context("Invalid minter module config")?;
+        if self.rotate_capability_on_startup && self.capability_key_file_path.is_none() {
+            bail!("capability_key_file_path must be set to rotate the minter capability");
+        }
+
+        let key = self.api_connection_config.get_key()?;
+        let capability_account = LocalAccount::new(
+            self.capability_account_address.unwrap_or_else(|| {
+                AuthenticationKey::ed25519(&Ed25519PublicKey::from(&key)).account_address()
+            }),
+            key,
+            0,
+        );
+
+        let funder = MinterContractFunder::new(
+            self.api_connection_config.node_url.clone(),
+            self.api_connection_config.chain_id,
+            self.transaction_submission_config,
+            self.minter_module_config,
+            capability_account,
+            self.capability_account_gas_txns,
+        );
+
+        if let Some(capability_key_file_path) = self.capability_key_file_path {
+            if capability_key_file_path.exists() {
+                let new_account = load_capability_account(&capability_key_file_path)?;
+                if self.rotate_capability_on_startup {
+                    funder
+                        .resume_capability_rotation(new_account)
+                        .await
+                        .context("Failed to complete the minter capability rotation")?;
+                } else {
+                    *funder.capability_account.write().await = new_account;
+                }
+            } else if self.rotate_capability_on_startup {
+                funder
+                    .rotate_capability_account(&capability_key_file_path)
+                    .await
+                    .context("Failed to rotate the minter capability account")?;
+            }
+        }
+
+        Ok(funder)
+    }
+}
+
+/// This funder funds accounts by calling a custom minter module on chain, rather
+/// than minting through the core framework with the mint capability itself.
+pub struct MinterContractFunder {
+    /// URL of an Aptos node API.
+    node_url: Url,
+
+    txn_config: TransactionSubmissionConfig,
+
+    minter_module_config: MinterModuleConfig,
+
+    /// The account currently holding the minter capability.
+    capability_account: RwLock<LocalAccount>,
+
+    transaction_factory: TransactionFactory,
+
+    gas_unit_price_manager: GasUnitPriceManager,
+
+    /// When recovering from being overloaded, this struct ensures we handle
+    /// requests in the order they came in.
+    outstanding_requests: RwLock<Vec<(AccountAddress, u64)>>,
+
+    /// How many transactions a new capability account is funded to pay for.
+    capability_account_gas_txns: u64,
+}
+
+impl MinterContractFunder {
+    pub fn new(
+        node_url: Url,
+        chain_id: ChainId,
+        txn_config: TransactionSubmissionConfig,
+        minter_module_config: MinterModuleConfig,
+        capability_account: LocalAccount,
+        capability_account_gas_txns: u64,
+    ) -> Self {
+        let gas_unit_price_manager =
+            GasUnitPriceManager::new(node_url.clone(), txn_config.get_gas_unit_price_ttl_secs());
+        let transaction_factory = TransactionFactory::new(chain_id)
+            .with_max_gas_amount(txn_config.max_gas_amount)
+            .with_transaction_expiration_time(txn_config.transaction_expiration_secs);
+        Self {
+            node_url,
+            txn_config,
+            minter_module_config,
+            capability_account: RwLock::new(capability_account),
+            transaction_factory,
+            gas_unit_price_manager,
+            outstanding_requests: RwLock::new(vec![]),
+            capability_account_gas_txns,
+        }
+    }
+
+    async fn get_transaction_factory(&self) -> Result<TransactionFactory, AptosTapError> {
+        let gas_unit_price = match self.txn_config.gas_unit_price_override {
+            Some(gas_unit_price) => gas_unit_price,
+            None => self
+                .gas_unit_price_manager
+                .get_gas_unit_price()
+                .await
+                .map_err(|e| {
+                    AptosTapError::new_with_error_code(e, AptosTapErrorCode::AptosApiError)
+                })?,
+        };
+        Ok(self
+            .transaction_factory
+            .clone()
+            .with_gas_unit_price(gas_unit_price))
+    }
+
+    /// Within a single request we should just call this once and use this client
+    /// the entire time because it uses cookies, ensuring we're talking to the same
+    /// node behind the LB every time.
+    pub fn get_api_client(&self) -> Client {
+        Client::new(self.node_url.clone())
+    }
+
+    /// Hands the minter capability over to a freshly generated account, which
+    /// is then used for all subsequent requests. The key of the new account is
+    /// written to the given path before anything is submitted, so a failed or
+    /// interrupted rotation can be completed on the next startup.
+    pub async fn rotate_capability_account(&self, capability_key_file_path: &Path) -> Result<()> {
+        let new_account = LocalAccount::generate(&mut rand::rngs::OsRng);
+        std::fs::write(
+            capability_key_file_path,
+            bcs::to_bytes(new_account.private_key())?,
+        )
+        .with_context(|| {
+            format!(
+                "Failed to write the capability key file: {}",
+                capability_key_file_path.to_string_lossy()
+            )
+        })?;
+        self.resume_capability_rotation(new_account).await
+    }
+
+    /// Moves the minter capability to the given account, unless it already
+    /// holds it. The account is funded through the minter module with just
+    /// enough to pay for capability_account_gas_txns transactions.
+    pub async fn resume_capability_rotation(&self, new_account: LocalAccount) -> Result<()> {
+        let client = self.get_api_client();
+
+        // A previous rotation to this account completed if the account claimed
+        // the capability, i.e. it has sent a transaction.
+        if let Ok(account) = client.get_account_bcs(new_account.address()).await {
+            if account.inner().sequence_number() > 0 {
+                info!(
+                    "Minter capability was already rotated to {}",
+                    new_account.address()
+                );
+                new_account.set_sequence_number(account.inner().sequence_number());
+                *self.capability_account.write().await = new_account;
+                return Ok(());
+            }
+        }
+
+        let transaction_factory = self.get_transaction_factory().await?;
+        let gas_funds = transaction_factory
+            .get_gas_unit_price()
+            .saturating_mul(transaction_factory.get_max_gas_amount())
+            .saturating_mul(self.capability_account_gas_txns);
+        let balance = client
+            .get_account_balance(new_account.address())
+            .await
+            .map(|balance| balance.into_inner().get())
+            .unwrap_or(0);
+
+        // Create the new account (or top it up if a previous attempt got that
+        // far), wait for the response.
+        if balance < gas_funds {
+            self.process(
+                &client,
+                gas_funds - balance,
+                new_account.address(),
+                false,
+                true,
+            )
+            .await
+            .context("Failed to fund the new capability account")?;
+        }
+
+        // Hold the lock until the rotation completes, so that no other
+        // transactions are signed with the old account in the meantime.
+        let mut capability_account = self.capability_account.write().await;
+        client
+            .submit_and_wait(
+                &capability_account.sign_with_transaction_builder(
+                    transaction_factory.payload(
+                        self.minter_module_config
+                            .rotate_capability_payload(new_account.address())?,
+                    ),
+                ),
+            )
+            .await
+            .context("Failed to rotate the minter capability to the new account")?;
+        client
+            .submit_and_wait(&new_account.sign_with_transaction_builder(
+                transaction_factory.payload(self.minter_module_config.claim_capability_payload()?),
+            ))
+            .await
+            .context("Failed to claim the minter capability")?;
+
+        info!(
+            "Successfully rotated the minter capability from {} to {}",
+            capability_account.address(),
+            new_account.address()
+        );
+        *capability_account = new_account;
+
+        Ok(())
+    }
+
+    pub async fn process(
+        &self,
+        client: &Client,
+        amount: u64,
+        receiver_address: AccountAddress,
+        check_only: bool,
+        wait_for_transactions: bool,
+    ) -> Result<Vec<SignedTransaction>, AptosTapError> {
+        let (_capability_seq, receiver_seq) = update_sequence_numbers(
+            client,
+            &self.capability_account,
+            &self.outstanding_requests,
+            receiver_address,
+            amount,
+            self.txn_config.wait_for_outstanding_txns_secs,
+        )
+        .await?;
+
+        if receiver_seq.is_some() && amount == 0 {
+            return Err(AptosTapError::new(
+                format!(
+                    "Account {} already exists and amount asked for is 0",
+                    receiver_address
+                ),
+                AptosTapErrorCode::InvalidRequest,
+            ));
+        }
+
+        if check_only {
+            return Ok(vec![]);
+        }
+
+        let payload = self
+            .minter_module_config
+            .mint_payload(receiver_address, amount)
+            .map_err(|e| {
+                AptosTapError::new_with_error_code(e, AptosTapErrorCode::InvalidRequest)
+            })?;
+        let txn = {
+            let capability_account = self.capability_account.write().await;
+            let transaction_factory = self.get_transaction_factory().await?;
+            capability_account.sign_with_transaction_builder(transaction_factory.payload(payload))
+        };
+
+        Ok(vec![
+            submit_transaction(
+                client,
+                &self.capability_account,
+                txn,
+                &receiver_address,
+                wait_for_transactions,
+            )
+            .await?,
+        ])
+    }
+}
+
+/// Loads the account whose key was stored by a capability rotation.
+fn load_capability_account(capability_key_file_path: &Path) -> Result<LocalAccount> {
+    let key_bytes = std::fs::read(capability_key_file_path).with_context(|| {
+        format!(
+            "Failed to read the capability key file: {}",
+            capability_key_file_path.to_string_lossy()
+        )
+    })?;
+    let key: Ed25519PrivateKey = bcs::from_bytes(&key_bytes).with_context(|| {
+        format!(
+            "Invalid key in the capability key file: {}",
+            capability_key_file_path.to_string_lossy()
+        )
+    })?;
+    let address = AuthenticationKey::ed25519(&Ed25519PublicKey::from(&key)).account_address();
+    Ok(LocalAccount::new(address, key, 0))
+}
+
+#[async_trait]
+impl FunderTrait for MinterContractFunder {
+    async fn fund(
+        &self,
+        amount: Option<u64>,
+        receiver_address: AccountAddress,
+        check_only: bool,
+        did_bypass_checkers: bool,
+    ) -> Result<Vec<SignedTransaction>, AptosTapError> {
+        let client = self.get_api_client();
+        let amount = self.get_amount(amount, did_bypass_checkers);
+        self.process(
+            &client,
+            amount,
+            receiver_address,
+            check_only,
+            self.txn_config.wait_for_transactions,
+        )
+        .await
+    }
+
+    fn get_amount(&self, amount: Option<u64>, did_bypass_checkers: bool) -> u64 {
+        match (
+            amount,
+            self.txn_config.get_maximum_amount(did_bypass_checkers),
+        ) {
+            (Some(amount), Some(maximum_amount)) => std::cmp::min(amount, maximum_amount),
+            (Some(amount), None) => amount,
+            (None, Some(maximum_amount)) => maximum_amount,
+            (None, None) => 0,
+        }
+    }
+
+    /// Assert the capability account and the minter module actually exist.
+    async fn is_healthy(&self) -> FunderHealthMessage {
+        let client = self.get_api_client();
+        let account_address = self.capability_account.read().await.address();
+        if let Err(e) = client.get_account_bcs(account_address).await {
+            return FunderHealthMessage {
+                can_process_requests: false,
+                message: Some(format!(
+                    "Failed to read account information for {}, it may not exist or the fullnode might not be fully synced: {:#}",
+                    account_address, e
+                )),
+            };
+        }
+
+        let minter_module_config = &self.minter_module_config;
+        if let Err(e) = client
+            .get_account_module(
+                minter_module_config.minter_module_address,
+                &minter_module_config.minter_module_name,
+            )
+            .await
+        {
+            return FunderHealthMessage {
+                can_process_requests: false,
+                message: Some(format!(
+                    "Failed to read the minter module {}::{}: {:#}",
+                    minter_module_config.minter_module_address,
+                    minter_module_config.minter_module_name,
+                    e
+                )),
+            };
+        }
+
+        FunderHealthMessage {
+            can_process_requests: true,
+            message: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minter_module_config() {
+        let minter_module_config: MinterModuleConfig =
+            serde_yaml::from_str("minter_module_address: \"0xcafe\"").unwrap();
+        assert_eq!(minter_module_config.minter_module_name, "faucet_minter");
+
+        // Verify the mint payload calls the configured function with the expected args
+        let receiver_address = AccountAddress::random();
+        let payload = minter_module_config
+            .mint_payload(receiver_address, 100)
+            .unwrap();
+        match payload {
+            TransactionPayload::EntryFunction(entry_function) => {
+                assert_eq!(
+                    entry_function.module().address(),
+                    &AccountAddress::from_hex_literal("0xcafe").unwrap()
+                );
+                assert_eq!(entry_function.module().name().as_str(), "faucet_minter");
+                assert_eq!(entry_function.function().as_str(), "mint");
+                assert_eq!(entry_function.args(), &[
+                    bcs::to_bytes(&receiver_address).unwrap(),
+                    bcs::to_bytes(&100u64).unwrap(),
+                ]);
+            },
+            _ => panic!("Expected an entry function payload"),
+        }
+
+        // Verify invalid function names are rejected
+        let minter_module_config = MinterModuleConfig {
+            mint_function_name: "not a function".to_string(),
+            ..minter_module_config
+        };
+        assert!(minter_module_config
+            .mint_payload(receiver_address, 100)
+            .is_err());
+    }
+}
@@ -4,11 +4,17 @@
 mod common;
 mod fake;
 mod mint;
+mod minter_contract;
 mod transfer;
+mod worker_pool;
 
+#[cfg(test)]
+pub(crate) use self::worker_pool::{account_address_from_private_key, derive_worker_key};
 pub use self::{
     common::{ApiConnectionConfig, TransactionSubmissionConfig},
     mint::MintFunderConfig,
+    minter_contract::{MinterContractFunderConfig, MinterModuleConfig},
+    worker_pool::WorkerPoolFunderConfig,
 };
 use self::{fake::FakeFunderConfig, transfer::TransferFunderConfig};
 use crate::endpoints::AptosTapError;
@@ -18,9 +24,11 @@ use async_trait::async_trait;
 use enum_dispatch::enum_dispatch;
 pub use fake::FakeFunder;
 pub use mint::MintFunder;
+pub use minter_contract::MinterContractFunder;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 pub use transfer::TransferFunder;
+pub use worker_pool::WorkerPoolFunder;
 
 /// explain
 #[async_trait]
@@ -42,6 +50,22 @@ pub trait FunderTrait: Sync + Send + 'static {
         did_bypass_checkers: bool,
     ) -> Result<Vec<SignedTransaction>, AptosTapError>;
 
+    /// Like `fund` (without `check_only`), but for a caller that funds one
+    /// request at a time as the given worker, e.g. a fund queue worker.
+    /// Funders that send from several accounts pin each worker to one of
+    /// them, so that account's sequence number and outstanding requests are
+    /// only managed by that worker. By default this is the same as `fund`.
+    async fn fund_as_worker(
+        &self,
+        _worker_index: usize,
+        amount: Option<u64>,
+        receiver_address: AccountAddress,
+        did_bypass_checkers: bool,
+    ) -> Result<Vec<SignedTransaction>, AptosTapError> {
+        self.fund(amount, receiver_address, false, did_bypass_checkers)
+            .await
+    }
+
     /// Given a requested amount and any configuration internal to this funder,
     /// determine the amount that can be funded.
     fn get_amount(
@@ -72,9 +96,17 @@ pub enum FunderConfig {
     /// This funder uses the delegation + minting mechanism to fund.
     MintFunder(MintFunderConfig),
 
+    /// This funder funds accounts by calling a custom minter module on chain,
+    /// which lets the faucet supply be capped and audited on chain.
+    MinterContractFunder(MinterContractFunderConfig),
+
     /// This funder creates and funds accounts by using + transferring
     /// coins from a pre-funded account provided in configuration.
     TransferFunder(TransferFunderConfig),
+
+    /// This funder creates and funds accounts by transferring coins from a
+    /// pool of worker accounts, which are topped up from a root account.
+    WorkerPoolFunder(WorkerPoolFunderConfig),
 }
 
 impl FunderConfig {
@@ -87,12 +119,24 @@ impl FunderConfig {
                     .await
                     .context("Failed to build MintFunder")?,
             ))),
+            FunderConfig::MinterContractFunder(config) => Ok(Arc::new(Funder::from(
+                config
+                    .build_funder()
+                    .await
+                    .context("Failed to build MinterContractFunder")?,
+            ))),
             FunderConfig::TransferFunder(config) => Ok(Arc::new(Funder::from(
                 config
                     .build_funder()
                     .await
                     .context("Failed to build TransferFunder")?,
             ))),
+            FunderConfig::WorkerPoolFunder(config) => Ok(Arc::new(Funder::from(
+                config
+                    .build_funder()
+                    .await
+                    .context("Failed to build WorkerPoolFunder")?,
+            ))),
         }
     }
 }
@@ -102,7 +146,9 @@ impl FunderConfig {
 pub enum Funder {
     FakeFunder,
     MintFunder,
+    MinterContractFunder,
     TransferFunder,
+    WorkerPoolFunder,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,519 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    common::{
+        submit_transaction, update_sequence_numbers, ApiConnectionConfig, GasUnitPriceManager,
+        TransactionSubmissionConfig,
+    },
+    transfer::{AmountToFund, MinimumFunds},
+    FunderHealthMessage, FunderTrait,
+};
+use crate::endpoints::{AptosTapError, AptosTapErrorCode, RejectionReason, RejectionReasonCode};
+use anyhow::{ensure, Context, Result};
+use aptos_logger::{info, warn};
+use aptos_sdk::{
+    crypto::{
+        ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+        HashValue,
+    },
+    rest_client::Client,
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        transaction::{authenticator::AuthenticationKey, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Mutex, RwLock};
+
+/// Prefix used when deriving the worker keys from the root key, so they can't
+/// collide with keys derived the same way for other purposes.
+const WORKER_KEY_DOMAIN: &[u8] = b"APTOS_FAUCET_WORKER_ACCOUNT";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkerPoolFunderConfig {
+    /// The key given here is the one of the root account, which is only used
+    /// to top up the worker accounts.
+    #[serde(flatten)]
+    pub api_connection_config: ApiConnectionConfig,
+
+    #[serde(flatten)]
+    pub transaction_submission_config: TransactionSubmissionConfig,
+
+    /// The minimum amount of coins the root account should have. If it gets
+    /// below this, the funder reports itself as unhealthy.
+    pub minimum_funds: MinimumFunds,
+
+    /// The amount of coins to fund the receiver account.
+    pub amount_to_fund: AmountToFund,
+
+    /// The number of worker accounts funding transactions are rotated across.
+    #[serde(default = "WorkerPoolFunderConfig::default_num_workers")]
+    pub num_workers: usize,
+
+    /// If a worker account has less than this many coins when it is picked for
+    /// a request, it is topped up from the root account first. Transfers the
+    /// worker has submitted that haven't landed yet are not counted as funds.
+    pub worker_minimum_funds: u64,
+
+    /// The amount of coins the root account sends a worker account in a top up.
+    pub worker_top_up_amount: u64,
+}
+
+impl WorkerPoolFunderConfig {
+    fn default_num_workers() -> usize {
+        8
+    }
+
+    pub async fn build_funder(&self) -> Result<WorkerPoolFunder> {
+        ensure!(self.num_workers > 0, "num_workers must be positive");
+        ensure!(
+            self.worker_minimum_funds >= self.amount_to_fund.0,
+            "worker_minimum_funds must be at least amount_to_fund"
+        );
+        ensure!(
+            self.worker_top_up_amount >= self.worker_minimum_funds,
+            "worker_top_up_amount must be at least worker_minimum_funds"
+        );
+
+        let key = self.api_connection_config.get_key()?;
+        let workers = (0..self.num_workers)
+            .map(|index| {
+                let worker_key = derive_worker_key(&key, index)?;
+                Ok(Worker::new(LocalAccount::new(
+                    account_address_from_private_key(&worker_key),
+                    worker_key,
+                    0,
+                )))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let root_account = LocalAccount::new(account_address_from_private_key(&key), key, 0);
+
+        Ok(WorkerPoolFunder::new(
+            self.api_connection_config.node_url.clone(),
+            self.api_connection_config.chain_id,
+            self.transaction_submission_config.clone(),
+            root_account,
+            workers,
+            self.minimum_funds,
+            self.amount_to_fund,
+            self.worker_minimum_funds,
+            self.worker_top_up_amount,
+        ))
+    }
+}
+
+/// Derives the key of the worker account at the given index from the root
+/// key. This way the faucet finds the same worker accounts (and the funds left
+/// in them) again after a restart, without having to store any extra keys.
+pub(crate) fn derive_worker_key(
+    root_key: &Ed25519PrivateKey,
+    index: usize,
+) -> Result<Ed25519PrivateKey> {
+    let seed = HashValue::sha3_256_of(
+        &[
+            WORKER_KEY_DOMAIN,
+            &root_key.to_bytes()[..],
+            &(index as u64).to_le_bytes()[..],
+        ]
+        .concat(),
+    );
+    Ed25519PrivateKey::try_from(&seed[..]).context("Failed to derive a worker key")
+}
+
+pub(crate) fn account_address_from_private_key(private_key: &Ed25519PrivateKey) -> AccountAddress {
+    AuthenticationKey::ed25519(&Ed25519PublicKey::from(private_key)).account_address()
+}
+
+/// An account that funding transactions are sent from. Each worker tracks its
+/// own sequence number, so requests assigned to different workers don't have
+/// to wait for each other.
+struct Worker {
+    account: RwLock<LocalAccount>,
+
+    /// When recovering from being overloaded, this struct ensures we handle
+    /// requests in the order they came in.
+    outstanding_requests: RwLock<Vec<(AccountAddress, u64)>>,
+
+    /// Held while checking the balance of the worker and topping it up, so
+    /// that concurrent requests don't top up the same worker twice.
+    top_up_lock: Mutex<()>,
+}
+
+impl Worker {
+    fn new(account: LocalAccount) -> Self {
+        Self {
+            account: RwLock::new(account),
+            outstanding_requests: RwLock::new(vec![]),
+            top_up_lock: Mutex::new(()),
+        }
+    }
+}
+
+/// This funder creates and funds accounts by transferring coins from a pool of
+/// worker accounts, rotating across them for every request. The workers are
+/// topped up from the root account when they run low, so the root account
+/// only sends a transaction every now and then, rather than for every request.
+pub struct WorkerPoolFunder {
+    /// URL of an Aptos node API.
+    node_url: Url,
+
+    txn_config: TransactionSubmissionConfig,
+
+    transaction_factory: TransactionFactory,
+
+    gas_unit_price_manager: GasUnitPriceManager,
+
+    /// The account the workers are topped up from.
+    root_account: RwLock<LocalAccount>,
+
+    /// Outstanding top ups sent from the root account.
+    root_outstanding_requests: RwLock<Vec<(AccountAddress, u64)>>,
+
+    workers: Vec<Worker>,
+
+    /// Index of the worker to use for the next request, modulo the number of workers.
+    next_worker: AtomicUsize,
+
+    /// The minimum amount of funds the root account should have to operate.
+    minimum_funds: MinimumFunds,
+
+    /// Maximum amount we'll fund an account.
+    amount_to_fund: AmountToFund,
+
+    worker_minimum_funds: u64,
+
+    worker_top_up_amount: u64,
+}
+
+impl WorkerPoolFunder {
+    fn new(
+        node_url: Url,
+        chain_id: ChainId,
+        txn_config: TransactionSubmissionConfig,
+        root_account: LocalAccount,
+        workers: Vec<Worker>,
+        minimum_funds: MinimumFunds,
+        amount_to_fund: AmountToFund,
+        worker_minimum_funds: u64,
+        worker_top_up_amount: u64,
+    ) -> Self {
+        let gas_unit_price_manager =
+            GasUnitPriceManager::new(node_url.clone(), txn_config.get_gas_unit_price_ttl_secs());
+        let transaction_factory = TransactionFactory::new(chain_id)
+            .with_max_gas_amount(txn_config.max_gas_amount)
+            .with_transaction_expiration_time(txn_config.transaction_expiration_secs);
+        Self {
+            node_url,
+            txn_config,
+            transaction_factory,
+            gas_unit_price_manager,
+            root_account: RwLock::new(root_account),
+            root_outstanding_requests: RwLock::new(vec![]),
+            workers,
+            next_worker: AtomicUsize::new(0),
+            minimum_funds,
+            amount_to_fund,
+            worker_minimum_funds,
+            worker_top_up_amount,
+        }
+    }
+
+    /// Within a single request we should just call this once and use this client
+    /// the entire time because it uses cookies, ensuring we're talking to the same
+    /// node behind the LB every time.
+    pub fn get_api_client(&self) -> Client {
+        Client::new(self.node_url.clone())
+    }
+
+    async fn get_transaction_factory(&self) -> Result<TransactionFactory, AptosTapError> {
+        let gas_unit_price = match self.txn_config.gas_unit_price_override {
+            Some(gas_unit_price) => gas_unit_price,
+            None => self
+                .gas_unit_price_manager
+                .get_gas_unit_price()
+                .await
+                .map_err(|e| {
+                    AptosTapError::new_with_error_code(e, AptosTapErrorCode::AptosApiError)
+                })?,
+        };
+        Ok(self
+            .transaction_factory
+            .clone()
+            .with_gas_unit_price(gas_unit_price))
+    }
+
+    /// Picks the worker for the next request, in a round robin fashion.
+    fn next_worker(&self) -> &Worker {
+        let index = self.next_worker.fetch_add(1, Ordering::Relaxed) % self.workers.len();
+        &self.workers[index]
+    }
+
+    /// Funds the receiver from the given worker, topping it up first if needed.
+    async fn fund_from_worker(
+        &self,
+        client: &Client,
+        worker: &Worker,
+        amount: u64,
+        receiver_address: AccountAddress,
+    ) -> Result<Vec<SignedTransaction>, AptosTapError> {
+        self.maybe_top_up_worker(client, worker).await?;
+
+        // Update the sequence numbers of the worker and receiver accounts.
+        let (_worker_seq_num, receiver_seq_num) = update_sequence_numbers(
+            client,
+            &worker.account,
+            &worker.outstanding_requests,
+            receiver_address,
+            amount,
+            self.txn_config.wait_for_outstanding_txns_secs,
+        )
+        .await?;
+        if receiver_seq_num.is_some() {
+            return Err(account_already_exists_error(receiver_address));
+        }
+
+        let txn = self
+            .sign_and_submit(
+                client,
+                &worker.account,
+                aptos_stdlib::aptos_account_transfer(receiver_address, amount),
+                &receiver_address,
+                self.txn_config.wait_for_transactions,
+            )
+            .await?;
+        Ok(vec![txn])
+    }
+
+    /// Signs the given payload with the given account and submits it.
+    async fn sign_and_submit(
+        &self,
+        client: &Client,
+        account: &RwLock<LocalAccount>,
+        payload: TransactionPayload,
+        receiver_address: &AccountAddress,
+        wait_for_transactions: bool,
+    ) -> Result<SignedTransaction, AptosTapError> {
+        let transaction_factory = self.get_transaction_factory().await?;
+        let txn = account
+            .write()
+            .await
+            .sign_with_transaction_builder(transaction_factory.payload(payload));
+        submit_transaction(
+            client,
+            account,
+            txn,
+            receiver_address,
+            wait_for_transactions,
+        )
+        .await
+    }
+
+    /// Tops up the worker from the root account if it has less than
+    /// `worker_minimum_funds`, after taking off `amount_to_fund` for each
+    /// transfer the worker has submitted that isn't on chain yet. Without
+    /// `wait_for_transactions` those aren't reflected in the balance, so the
+    /// worker could otherwise drain below what its queued transfers need.
+    /// This also creates the worker account on chain
+    /// the first time it is used. We wait for the top up, so the worker can
+    /// fund the request right after.
+    async fn maybe_top_up_worker(
+        &self,
+        client: &Client,
+        worker: &Worker,
+    ) -> Result<(), AptosTapError> {
+        let _top_up_guard = worker.top_up_lock.lock().await;
+        let (worker_address, worker_seq_num) = {
+            let account = worker.account.read().await;
+            (account.address(), account.sequence_number())
+        };
+        // Read the on-chain sequence number before the balance, so a transfer
+        // landing in between is counted twice rather than not at all.
+        let onchain_seq_num = client
+            .get_account(worker_address)
+            .await
+            .map_or(0, |response| response.into_inner().sequence_number);
+        let worker_balance = match client
+            .get_account_balance_bcs(worker_address, "0x1::aptos_coin::AptosCoin")
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(e) => {
+                // Most likely the worker account doesn't exist yet.
+                warn!(
+                    worker_address = worker_address,
+                    error_message = format!("{:#}", e),
+                    event = "worker_balance_unavailable"
+                );
+                0
+            },
+        };
+        let num_in_flight = worker_seq_num.saturating_sub(onchain_seq_num);
+        let available_balance =
+            worker_balance.saturating_sub(num_in_flight.saturating_mul(self.amount_to_fund.0));
+        if available_balance >= self.worker_minimum_funds {
+            return Ok(());
+        }
+
+        update_sequence_numbers(
+            client,
+            &self.root_account,
+            &self.root_outstanding_requests,
+            worker_address,
+            self.worker_top_up_amount,
+            self.txn_config.wait_for_outstanding_txns_secs,
+        )
+        .await?;
+        self.sign_and_submit(
+            client,
+            &self.root_account,
+            aptos_stdlib::aptos_account_transfer(worker_address, self.worker_top_up_amount),
+            &worker_address,
+            true,
+        )
+        .await?;
+        info!(
+            worker_address = worker_address,
+            worker_balance = worker_balance,
+            num_in_flight = num_in_flight,
+            amount = self.worker_top_up_amount,
+            event = "worker_topped_up"
+        );
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FunderTrait for WorkerPoolFunder {
+    /// This works like the TransferFunder, except that the transfer is sent
+    /// from the next worker account rather than from a single funder account.
+    async fn fund(
+        &self,
+        amount: Option<u64>,
+        receiver_address: AccountAddress,
+        check_only: bool,
+        did_bypass_checkers: bool,
+    ) -> Result<Vec<SignedTransaction>, AptosTapError> {
+        let client = self.get_api_client();
+        let amount = self.get_amount(amount, did_bypass_checkers);
+
+        // We don't pick (or top up) a worker if we're not going to submit a
+        // transaction, we just check that the receiver doesn't exist yet.
+        if check_only {
+            if client.get_account(receiver_address).await.is_ok() {
+                return Err(account_already_exists_error(receiver_address));
+            }
+            return Ok(vec![]);
+        }
+
+        self.fund_from_worker(&client, self.next_worker(), amount, receiver_address)
+            .await
+    }
+
+    /// Fund queue workers are pinned to a worker account instead of taking
+    /// turns, so each of them manages the sequence number of its own account.
+    async fn fund_as_worker(
+        &self,
+        worker_index: usize,
+        amount: Option<u64>,
+        receiver_address: AccountAddress,
+        did_bypass_checkers: bool,
+    ) -> Result<Vec<SignedTransaction>, AptosTapError> {
+        let client = self.get_api_client();
+        let amount = self.get_amount(amount, did_bypass_checkers);
+        let worker = &self.workers[worker_index % self.workers.len()];
+        self.fund_from_worker(&client, worker, amount, receiver_address)
+            .await
+    }
+
+    fn get_amount(
+        &self,
+        amount: Option<u64>,
+        // Ignored, like with the TransferFunder.
+        _did_bypass_checkers: bool,
+    ) -> u64 {
+        match amount {
+            Some(amount) => std::cmp::min(amount, self.amount_to_fund.0),
+            None => self.amount_to_fund.0,
+        }
+    }
+
+    /// Assert the root account actually exists and has the minimum funds.
+    async fn is_healthy(&self) -> FunderHealthMessage {
+        let account_address = self.root_account.read().await.address();
+        let root_balance = match self
+            .get_api_client()
+            .get_account_balance_bcs(account_address, "0x1::aptos_coin::AptosCoin")
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(e) => return FunderHealthMessage {
+                can_process_requests: false,
+                message: Some(format!(
+                    "Failed to get account balance to determine whether the root account has sufficient funds: {:#}",
+                    e
+                )),
+            },
+        };
+
+        if root_balance < self.minimum_funds.0 {
+            FunderHealthMessage {
+                can_process_requests: false,
+                message: Some(format!(
+                    "Root account {} has insufficient funds. It has {}, but the minimum is {}",
+                    account_address, root_balance, self.minimum_funds.0
+                )),
+            }
+        } else {
+            FunderHealthMessage {
+                can_process_requests: true,
+                message: None,
+            }
+        }
+    }
+}
+
+fn account_already_exists_error(receiver_address: AccountAddress) -> AptosTapError {
+    AptosTapError::new(
+        "Account ineligible".to_string(),
+        AptosTapErrorCode::Rejected,
+    )
+    .rejection_reasons(vec![RejectionReason::new(
+        format!("Account {} already exists", receiver_address),
+        RejectionReasonCode::AccountAlreadyExists,
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::crypto::Uniform;
+
+    #[test]
+    fn test_derive_worker_key() {
+        let root_key = Ed25519PrivateKey::generate(&mut rand::rngs::OsRng);
+
+        // The worker keys are deterministic, and differ between workers and from the root key
+        let worker_addresses: Vec<_> = (0..4)
+            .map(|index| {
+                account_address_from_private_key(&derive_worker_key(&root_key, index).unwrap())
+            })
+            .collect();
+        assert_eq!(
+            worker_addresses[1],
+            account_address_from_private_key(&derive_worker_key(&root_key, 1).unwrap())
+        );
+        let mut unique_addresses = worker_addresses.clone();
+        unique_addresses.push(account_address_from_private_key(&root_key));
+        unique_addresses.sort();
+        unique_addresses.dedup();
+        assert_eq!(unique_addresses.len(), 5);
+    }
+}
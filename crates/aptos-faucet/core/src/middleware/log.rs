@@ -1,18 +1,31 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::metrics::{HISTOGRAM, RESPONSE_STATUS};
+use super::metrics::{HISTOGRAM, REQUEST_COUNT, RESPONSE_STATUS};
 use aptos_logger::{
     error, info,
     prelude::{sample, SampleRate},
     warn, Schema,
 };
 use poem::{
-    http::header, web::RealIp, Endpoint, FromRequest, Request, RequestBody, Response, Result,
+    http::{header, HeaderMap},
+    web::RealIp,
+    Endpoint, FromRequest, Request, RequestBody, Response, Result,
 };
 use poem_openapi::OperationId;
 use std::{net::IpAddr, time::Duration};
 
+/// Optional header that clients (or a proxy in front of the tap) can set to
+/// correlate the logs of a request across services.
+pub const TRACE_ID_HEADER: &str = "x-trace-id";
+
+/// Returns the trace ID of the request, if the client provided one.
+pub fn get_trace_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(TRACE_ID_HEADER)
+        .and_then(|v| v.to_str().ok().map(|v| v.to_string()))
+}
+
 /// Logs information about the request and response. We log at different log
 /// levels depending on the response status code / the operation ID. We do both
 /// structured logging as well as pushing counters / gauges / etc.
@@ -43,6 +56,7 @@ pub async fn middleware_log<E: Endpoint>(next: E, request: Request) -> Result<Re
             .headers()
             .get(header::FORWARDED)
             .and_then(|v| v.to_str().ok().map(|v| v.to_string())),
+        trace_id: get_trace_id(request.headers()),
     };
 
     let mut drop_logger = DropLogger::new(request_log);
@@ -77,6 +91,7 @@ pub struct HttpRequestLog {
     referer: Option<String>,
     user_agent: Option<String>,
     forwarded: Option<String>,
+    trace_id: Option<String>,
 }
 
 #[derive(Schema)]
@@ -138,6 +153,14 @@ impl<'a> Drop for DropLogger<'a> {
                     ])
                     .observe(response_log.elapsed.as_secs_f64());
 
+                // Count requests per-endpoint + status.
+                REQUEST_COUNT
+                    .with_label_values(&[
+                        response_log.operation_id,
+                        response_log.response_status.to_string().as_str(),
+                    ])
+                    .inc();
+
                 // For now log all requests, no sampling, unless it is for `/`.
                 if response_log.operation_id == "root" {
                     sample!(
@@ -153,6 +176,9 @@ impl<'a> Drop for DropLogger<'a> {
             None => {
                 // If we don't have a response log, it means the client
                 // hung up mid-request.
+                REQUEST_COUNT
+                    .with_label_values(&["operation_id_not_set", "hangup"])
+                    .inc();
                 warn!(self.request_log, process_info, destiny = "hangup");
             },
         }
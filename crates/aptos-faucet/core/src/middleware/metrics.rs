@@ -3,8 +3,8 @@
 
 use crate::endpoints::RejectionReason;
 use aptos_metrics_core::{
-    register_histogram_vec, register_int_counter_vec, register_int_gauge, HistogramVec,
-    IntCounterVec, IntGauge,
+    exponential_buckets, register_histogram, register_histogram_vec, register_int_counter_vec,
+    register_int_gauge, Histogram, HistogramVec, IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 
@@ -26,6 +26,15 @@ pub static RESPONSE_STATUS: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static REQUEST_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_tap_request_count",
+        "Number of tap requests grouped by operation_id and status. Requests where the client hung up have the status \"hangup\".",
+        &["operation_id", "status"]
+    )
+    .unwrap()
+});
+
 static REJECTION_REASONS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_tap_rejection_reason_count",
@@ -35,6 +44,42 @@ static REJECTION_REASONS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+static CHECKER_REJECTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_tap_checker_rejection_count",
+        "Number of times the given checker has returned the given rejection reason.",
+        &["checker", "rejection_reason_code"]
+    )
+    .unwrap()
+});
+
+pub static FUNDING_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_tap_funding_latency",
+        "Latency of funding an account (including waiting for outstanding transactions), grouped by result.",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub static TRANSACTION_GAS_USED: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_tap_transaction_gas_used",
+        "Gas used by the funding transactions that the tap waited for.",
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static SEQUENCE_NUMBER_RETRIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_tap_sequence_number_retry_count",
+        "Number of times the tap had to wait for, reset or roll back the funder account sequence number, grouped by reason.",
+        &["reason"]
+    )
+    .unwrap()
+});
+
 pub static NUM_OUTSTANDING_TRANSACTIONS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
         "aptos_tap_num_outstanding_transactions",
@@ -43,6 +88,14 @@ pub static NUM_OUTSTANDING_TRANSACTIONS: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static NUM_QUEUED_FUND_JOBS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_tap_num_queued_fund_jobs",
+        "Number of funding requests in the fund queue waiting for a worker.",
+    )
+    .unwrap()
+});
+
 // TODO: Consider using IntGaugeVec to attach the account address as a label.
 pub static TRANSFER_FUNDER_ACCOUNT_BALANCE: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -59,3 +112,11 @@ pub fn bump_rejection_reason_counters(rejection_reasons: &[RejectionReason]) {
             .inc();
     }
 }
+
+pub fn bump_checker_rejection_counters(checker: &str, rejection_reasons: &[RejectionReason]) {
+    for rejection_reason in rejection_reasons {
+        CHECKER_REJECTIONS
+            .with_label_values(&[checker, &format!("{}", rejection_reason.get_code() as u32)])
+            .inc();
+    }
+}
@@ -5,9 +5,10 @@ mod log;
 mod metrics;
 
 pub use self::{
-    log::middleware_log,
+    log::{get_trace_id, middleware_log, TRACE_ID_HEADER},
     metrics::{
-        bump_rejection_reason_counters, NUM_OUTSTANDING_TRANSACTIONS,
-        TRANSFER_FUNDER_ACCOUNT_BALANCE,
+        bump_checker_rejection_counters, bump_rejection_reason_counters, FUNDING_LATENCY,
+        NUM_OUTSTANDING_TRANSACTIONS, NUM_QUEUED_FUND_JOBS, SEQUENCE_NUMBER_RETRIES,
+        TRANSACTION_GAS_USED, TRANSFER_FUNDER_ACCOUNT_BALANCE,
     },
 };
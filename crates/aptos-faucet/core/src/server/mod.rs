@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod generate_openapi;
+mod ratelimit_args;
 mod run;
 mod server_args;
 mod validate_config;
@@ -0,0 +1,147 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bypasser::BypasserConfig,
+    checkers::{CheckerConfig, MemoryRatelimitCheckerConfig, RedisRatelimitCheckerConfig},
+    common::IpRangeManagerConfig,
+};
+use clap::Parser;
+use std::{num::NonZeroUsize, path::PathBuf};
+
+/// Arguments for configuring ratelimiting from the CLI (e.g. for `run-simple`).
+/// These build the equivalent MemoryRatelimit / RedisRatelimit Checker and
+/// IpAllowlist Bypasser configs, so a public faucet can be protected without
+/// writing a full config file or running an external proxy.
+#[derive(Clone, Debug, Parser)]
+pub struct RatelimitArgs {
+    /// Max number of requests per IP per day. If neither this nor
+    /// --max-requests-per-account-per-day is set, requests are not ratelimited.
+    #[clap(long)]
+    pub max_requests_per_ip_per_day: Option<u32>,
+
+    /// Max number of requests per receiver account per day.
+    #[clap(long)]
+    pub max_requests_per_account_per_day: Option<u32>,
+
+    /// Max number of IPs / accounts to track when ratelimiting in memory.
+    /// Ignored if --ratelimit-redis-address is set.
+    #[clap(long, default_value_t = NonZeroUsize::new(1000000).unwrap())]
+    pub ratelimit_max_entries_in_map: NonZeroUsize,
+
+    /// If set, ratelimit counters are stored in redis at this address (not
+    /// including port), so they are shared between faucet instances and survive
+    /// restarts. Otherwise, they are stored in memory.
+    #[clap(long)]
+    pub ratelimit_redis_address: Option<String>,
+
+    /// The port of the redis instance used for ratelimiting.
+    #[clap(long, default_value_t = 6379)]
+    pub ratelimit_redis_port: u16,
+
+    /// The password for the redis instance used for ratelimiting, if necessary.
+    #[clap(long)]
+    pub ratelimit_redis_password: Option<String>,
+
+    /// Path to a file containing one IP range per line (e.g. 32.143.133.32/24).
+    /// Requests from these IPs bypass ratelimiting (and any other Checkers).
+    #[clap(long, value_parser)]
+    pub ratelimit_ip_allowlist_file: Option<PathBuf>,
+}
+
+impl RatelimitArgs {
+    /// Build the Checker configs for the configured ratelimits, if any.
+    pub fn build_checker_configs(&self) -> Vec<CheckerConfig> {
+        if self.max_requests_per_ip_per_day.is_none()
+            && self.max_requests_per_account_per_day.is_none()
+        {
+            return vec![];
+        }
+
+        // The ratelimit Checkers always limit per IP, so if only a per account
+        // limit is given, we effectively disable the per IP limit.
+        let max_requests_per_ip_per_day = self.max_requests_per_ip_per_day.unwrap_or(u32::MAX);
+        let checker_config = match &self.ratelimit_redis_address {
+            Some(database_address) => CheckerConfig::RedisRatelimit(RedisRatelimitCheckerConfig {
+                database_address: database_address.clone(),
+                database_port: self.ratelimit_redis_port,
+                database_number: 0,
+                database_user: None,
+                database_password: self.ratelimit_redis_password.clone(),
+                max_requests_per_ip_per_day,
+                max_requests_per_account_per_day: self.max_requests_per_account_per_day,
+            }),
+            None => CheckerConfig::MemoryRatelimit(MemoryRatelimitCheckerConfig {
+                max_requests_per_day: max_requests_per_ip_per_day,
+                max_requests_per_account_per_day: self.max_requests_per_account_per_day,
+                max_entries_in_map: self.ratelimit_max_entries_in_map,
+            }),
+        };
+        vec![checker_config]
+    }
+
+    /// Build the Bypasser configs for the ratelimit allowlist, if any.
+    pub fn build_bypasser_configs(&self) -> Vec<BypasserConfig> {
+        self.ratelimit_ip_allowlist_file
+            .iter()
+            .map(|file| BypasserConfig::IpAllowlist(IpRangeManagerConfig { file: file.clone() }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> RatelimitArgs {
+        RatelimitArgs::try_parse_from(std::iter::once("ratelimit").chain(args.iter().copied()))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_no_ratelimit_flags() {
+        let args = parse(&[]);
+        assert!(args.build_checker_configs().is_empty());
+        assert!(args.build_bypasser_configs().is_empty());
+    }
+
+    #[test]
+    fn test_memory_ratelimit_flags() {
+        let args = parse(&["--max-requests-per-account-per-day", "3"]);
+        match args.build_checker_configs().as_slice() {
+            [CheckerConfig::MemoryRatelimit(config)] => {
+                // Only a per account limit was given, so the per IP limit is disabled
+                assert_eq!(config.max_requests_per_day, u32::MAX);
+                assert_eq!(config.max_requests_per_account_per_day, Some(3));
+            },
+            configs => panic!("Unexpected checker configs: {:?}", configs),
+        }
+    }
+
+    #[test]
+    fn test_redis_ratelimit_flags() {
+        let args = parse(&[
+            "--max-requests-per-ip-per-day",
+            "5",
+            "--ratelimit-redis-address",
+            "127.0.0.1",
+            "--ratelimit-ip-allowlist-file",
+            "/tmp/allowlist.txt",
+        ]);
+        match args.build_checker_configs().as_slice() {
+            [CheckerConfig::RedisRatelimit(config)] => {
+                assert_eq!(config.database_address, "127.0.0.1");
+                assert_eq!(config.database_port, 6379);
+                assert_eq!(config.max_requests_per_ip_per_day, 5);
+                assert_eq!(config.max_requests_per_account_per_day, None);
+            },
+            configs => panic!("Unexpected checker configs: {:?}", configs),
+        }
+        match args.build_bypasser_configs().as_slice() {
+            [BypasserConfig::IpAllowlist(config)] => {
+                assert_eq!(config.file, PathBuf::from("/tmp/allowlist.txt"));
+            },
+            configs => panic!("Unexpected bypasser configs: {:?}", configs),
+        }
+    }
+}
@@ -1,13 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::server_args::ServerConfig;
+use super::{ratelimit_args::RatelimitArgs, server_args::ServerConfig};
 use crate::{
     bypasser::{Bypasser, BypasserConfig},
     checkers::{CaptchaManager, Checker, CheckerConfig, CheckerTrait},
     endpoints::{
         build_openapi_service, convert_error, mint, BasicApi, CaptchaApi, FundApi,
-        FundApiComponents,
+        FundApiComponents, FundQueue, FundQueueConfig,
     },
     funder::{ApiConnectionConfig, FunderConfig, MintFunderConfig, TransactionSubmissionConfig},
     middleware::middleware_log,
@@ -43,6 +43,12 @@ pub struct HandlerConfig {
     /// This allows the tap to avoid overloading its Funder, as well as to
     /// signal to a healthchecker that it is overloaded (via `/`).
     pub max_concurrent_requests: Option<usize>,
+
+    /// If set, the tap exposes the `/fund_async` and `/status/{job_id}`
+    /// endpoints, with which funding requests are enqueued and funded in the
+    /// background by a pool of workers, rather than tying up the connection
+    /// until the account is funded.
+    pub fund_queue_config: Option<FundQueueConfig>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -127,6 +133,13 @@ impl RunConfig {
         // cost Checkers are at the start of the vec.
         checkers.sort_by_key(|a| a.cost());
 
+        // Build the fund queue, if enabled.
+        let fund_queue = self
+            .handler_config
+            .fund_queue_config
+            .as_ref()
+            .map(|config| Arc::new(FundQueue::new(config)));
+
         // Using those, build the fund API components.
         let fund_api_components = Arc::new(FundApiComponents {
             bypassers,
@@ -134,8 +147,21 @@ impl RunConfig {
             funder,
             return_rejections_early: self.handler_config.return_rejections_early,
             concurrent_requests_semaphore,
+            fund_queue: fund_queue.clone(),
         });
 
+        // Spawn the fund queue workers on the task manager, since they should
+        // never end either.
+        if let (Some(fund_queue), Some(config)) =
+            (fund_queue, &self.handler_config.fund_queue_config)
+        {
+            fund_queue.spawn_workers(
+                config.num_workers,
+                fund_api_components.clone(),
+                &mut join_set,
+            );
+        }
+
         let fund_api = FundApi {
             components: fund_api_components.clone(),
         };
@@ -277,6 +303,7 @@ impl RunConfig {
                 use_helpful_errors: true,
                 return_rejections_early: false,
                 max_concurrent_requests: None,
+                fund_queue_config: None,
             },
         }
     }
@@ -335,6 +362,9 @@ pub struct RunSimple {
 
     #[clap(long)]
     do_not_delegate: bool,
+
+    #[clap(flatten)]
+    ratelimit_args: RatelimitArgs,
 }
 
 impl RunSimple {
@@ -343,7 +373,7 @@ impl RunSimple {
             .api_connection_config
             .get_key()
             .context("Failed to load private key")?;
-        let run_config = RunConfig::build_for_cli(
+        let mut run_config = RunConfig::build_for_cli(
             self.api_connection_config.node_url.clone(),
             self.listen_address.clone(),
             self.listen_port,
@@ -351,6 +381,8 @@ impl RunSimple {
             self.do_not_delegate,
             Some(self.api_connection_config.chain_id),
         );
+        run_config.bypasser_configs = self.ratelimit_args.build_bypasser_configs();
+        run_config.checker_configs = self.ratelimit_args.build_checker_configs();
         run_config.run().await
     }
 }
@@ -363,8 +395,10 @@ mod test {
     use super::*;
     use crate::{
         endpoints::{
-            AptosTapError, AptosTapErrorCode, FundRequest, FundResponse, RejectionReasonCode,
+            AptosTapError, AptosTapErrorCode, FundAsyncResponse, FundJobState, FundJobStatus,
+            FundRequest, FundResponse, RejectionReasonCode,
         },
+        funder::{account_address_from_private_key, derive_worker_key},
         helpers::get_current_time_secs,
     };
     use anyhow::{bail, Result};
@@ -532,6 +566,55 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_fund_queue() -> Result<()> {
+        init();
+        let config_content = include_str!("../../../configs/testing_fund_queue.yaml");
+        let (port, _handle) = start_server(config_content).await?;
+
+        // Enqueue a request and see that we get a job ID back.
+        let response = unwrap_reqwest_result(
+            reqwest::Client::new()
+                .post(format!("{}/fund_async", get_root_endpoint(port)))
+                .body(get_fund_request(Some(10)).to_json_string())
+                .header(CONTENT_TYPE, "application/json")
+                .send()
+                .await,
+        )
+        .await?;
+        let fund_async_response =
+            FundAsyncResponse::parse_from_json_string(&response.text().await?)
+                .expect("Failed to read response as FundAsyncResponse");
+
+        // Poll the status of the job until the workers have funded it.
+        let status_endpoint = format!(
+            "{}/status/{}",
+            get_root_endpoint(port),
+            fund_async_response.job_id
+        );
+        let mut status = None;
+        for _ in 0..10 {
+            let response = unwrap_reqwest_result(reqwest::get(&status_endpoint).await).await?;
+            let job_status = FundJobStatus::parse_from_json_string(&response.text().await?)
+                .expect("Failed to read response as FundJobStatus");
+            if job_status.state.is_finished() {
+                status = Some(job_status);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let status = status.expect("Fund job didn't finish in time");
+        assert_eq!(status.state, FundJobState::Succeeded);
+        assert_eq!(status.job_id, fund_async_response.job_id);
+
+        // See that we get a 404 for a job that doesn't exist.
+        let response =
+            reqwest::get(format!("{}/status/not_a_job", get_root_endpoint(port))).await?;
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn test_checkers() -> Result<()> {
         init();
@@ -767,6 +850,91 @@ mod test {
         Ok(())
     }
 
+    // Like test_transfer_health, this relies on devnet, so we skip it by default.
+    #[ignore]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_worker_pool_funder() -> Result<()> {
+        // Create the root account and store its private key at the path
+        // expected by the config for this test.
+        let private_key = Ed25519PrivateKey::generate(&mut StdRng::from_seed(OsRng.gen()));
+        let serialized_keys = aptos_sdk::bcs::to_bytes(&private_key)?;
+        let mut key_file = std::fs::File::create("/tmp/worker_pool_funder_devnet.key")?;
+        key_file.write_all(&serialized_keys)?;
+
+        // Create it on chain using the prod devnet faucet, with enough funds to
+        // top up every worker and still stay above the minimum funds.
+        let root_address = account_address_from_private_key(&private_key);
+        unwrap_reqwest_result(
+            reqwest::Client::new()
+                .post("https://faucet.devnet.aptoslabs.com/fund")
+                .body(
+                    FundRequest {
+                        amount: Some(20_000_000),
+                        address: Some(root_address.to_string()),
+                        ..Default::default()
+                    }
+                    .to_json_string(),
+                )
+                .header(CONTENT_TYPE, "application/json")
+                .send()
+                .await,
+        )
+        .await?;
+
+        // Wait a few seconds for all the fullnodes to catch up.
+        tokio::time::sleep(Duration::from_secs(5)).await;
+
+        init();
+        let config_content = include_str!("../../../configs/testing_worker_pool_funder.yaml");
+        let (port, _handle) = start_server(config_content).await?;
+
+        // Make one more request than there are workers, so the first worker is
+        // picked twice.
+        for _ in 0..5 {
+            unwrap_reqwest_result(
+                reqwest::Client::new()
+                    .post(get_fund_endpoint(port))
+                    .body(get_fund_request(None).to_json_string())
+                    .header(CONTENT_TYPE, "application/json")
+                    .send()
+                    .await,
+            )
+            .await?;
+        }
+
+        // Wait a few seconds for all the fullnodes to catch up.
+        tokio::time::sleep(Duration::from_secs(5)).await;
+
+        let aptos_node_api_client = aptos_sdk::rest_client::Client::new(
+            reqwest::Url::from_str("https://fullnode.devnet.aptoslabs.com").unwrap(),
+        );
+
+        // The root account only sends the first top up of each worker, the
+        // requests themselves are all sent by the workers.
+        let root_account = aptos_node_api_client.get_account(root_address).await?;
+        assert_eq!(root_account.inner().sequence_number, 4);
+
+        // The requests are rotated across the workers, and each worker got a
+        // single top up that covers every transfer it sent.
+        for (index, expected_sequence_number) in [2, 1, 1, 1].into_iter().enumerate() {
+            let worker_address =
+                account_address_from_private_key(&derive_worker_key(&private_key, index)?);
+            let worker_account = aptos_node_api_client.get_account(worker_address).await?;
+            assert_eq!(
+                worker_account.inner().sequence_number,
+                expected_sequence_number
+            );
+            let worker_balance = aptos_node_api_client
+                .get_account_balance_bcs(worker_address, "0x1::aptos_coin::AptosCoin")
+                .await?
+                .into_inner();
+            assert!(worker_balance <= 1_000_000 - expected_sequence_number * 2000);
+            assert!(worker_balance >= 100_000);
+        }
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn test_mint_funder() -> Result<()> {
         // Assert that a local testnet is alive.
@@ -952,4 +1120,85 @@ mod test {
 
         Ok(())
     }
+
+    // This requires a local testnet with the faucet_minter module published at
+    // 0xcafe and its capability held by the key at /tmp/minter_capability.key.
+    #[ignore]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_minter_contract_funder() -> Result<()> {
+        // Assert that a local testnet is alive.
+        let aptos_node_api_client = aptos_sdk::rest_client::Client::new(
+            reqwest::Url::from_str("http://127.0.0.1:8080").unwrap(),
+        );
+        aptos_node_api_client
+            .get_index_bcs()
+            .await
+            .context("Local testnet API couldn't be reached at port 8080, have you started one?")?;
+
+        // Start from scratch, so the server rotates the capability on startup.
+        let capability_key_file_path = "/tmp/minter_capability_rotated.key";
+        if std::path::Path::new(capability_key_file_path).exists() {
+            std::fs::remove_file(capability_key_file_path)?;
+        }
+
+        init();
+        let config_content = include_str!("../../../configs/testing_minter_contract_funder.yaml");
+        let (port, _handle) = start_server(config_content).await?;
+
+        // Assert that the capability was handed to the persisted account, which
+        // claimed it.
+        let capability_key = std::fs::read(capability_key_file_path)
+            .context("The rotated capability key wasn't persisted")?;
+        let private_key: Ed25519PrivateKey = aptos_sdk::bcs::from_bytes(&capability_key)?;
+        let capability_address =
+            AuthenticationKey::ed25519(&private_key.public_key()).account_address();
+        let capability_account = aptos_node_api_client
+            .get_account_bcs(capability_address)
+            .await?
+            .into_inner();
+        assert_eq!(capability_account.sequence_number(), 1);
+
+        // Make a request to fund a new account through the minter module.
+        let fund_request = get_fund_request(Some(10));
+        let response = unwrap_reqwest_result(
+            reqwest::Client::new()
+                .post(get_fund_endpoint(port))
+                .body(fund_request.to_json_string())
+                .header(CONTENT_TYPE, "application/json")
+                .send()
+                .await,
+        )
+        .await?;
+        let fund_response = FundResponse::parse_from_json_string(&response.text().await?)
+            .expect("Failed to read response as FundResponse");
+        let response = aptos_node_api_client
+            .wait_for_transaction_by_hash(
+                HashValue::from_str(&fund_response.txn_hashes[0])?,
+                get_current_time_secs() + 30,
+                None,
+                None,
+            )
+            .await
+            .context("Failed to wait for transaction")?;
+        assert!(
+            response.inner().success(),
+            "Transaction failed: {:#?}",
+            response
+        );
+        let response = aptos_node_api_client
+            .get_account_balance(AccountAddress::from_str(&fund_request.address.unwrap()).unwrap())
+            .await?;
+        assert_eq!(response.into_inner().get(), 10);
+
+        // Restarting must reuse the persisted account rather than rotating again.
+        start_server(config_content).await?;
+        assert_eq!(std::fs::read(capability_key_file_path)?, capability_key);
+        let capability_account = aptos_node_api_client
+            .get_account_bcs(capability_address)
+            .await?
+            .into_inner();
+        assert_eq!(capability_account.sequence_number(), 2);
+
+        Ok(())
+    }
 }
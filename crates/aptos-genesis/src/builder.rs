@@ -27,11 +27,11 @@ use aptos_keygen::KeyGen;
 use aptos_logger::prelude::*;
 use aptos_types::{
     chain_id::ChainId,
-    on_chain_config::{GasScheduleV2, OnChainConsensusConfig, OnChainExecutionConfig},
+    on_chain_config::{FeatureFlag, GasScheduleV2, OnChainConsensusConfig, OnChainExecutionConfig},
     transaction::Transaction,
     waypoint::Waypoint,
 };
-use aptos_vm_genesis::default_gas_schedule;
+use aptos_vm_genesis::{default_gas_schedule, DelegationPool, OIDCProvider};
 use rand::Rng;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
@@ -428,6 +428,10 @@ pub struct GenesisConfiguration {
     pub voting_power_increase_limit: u64,
     pub employee_vesting_start: Option<u64>,
     pub employee_vesting_period_duration: Option<u64>,
+    pub delegation_pools: Vec<DelegationPool>,
+    pub features_to_enable: Vec<FeatureFlag>,
+    pub features_to_disable: Vec<FeatureFlag>,
+    pub oidc_providers: Vec<OIDCProvider>,
     pub consensus_config: OnChainConsensusConfig,
     pub execution_config: OnChainExecutionConfig,
     pub gas_schedule: GasScheduleV2,
@@ -645,6 +649,10 @@ impl Builder {
             voting_power_increase_limit: 50,
             employee_vesting_start: None,
             employee_vesting_period_duration: None,
+            delegation_pools: vec![],
+            features_to_enable: vec![],
+            features_to_disable: vec![],
+            oidc_providers: vec![],
             consensus_config: OnChainConsensusConfig::default_for_genesis(),
             execution_config: OnChainExecutionConfig::default_for_genesis(),
             gas_schedule: default_gas_schedule(),
@@ -662,7 +670,7 @@ impl Builder {
             &genesis_config,
         )?;
         let waypoint = genesis_info.generate_waypoint()?;
-        let genesis = genesis_info.get_genesis();
+        let genesis = genesis_info.get_genesis()?;
 
         // Insert genesis and waypoint into validators
         // TODO: verify genesis?
@@ -7,10 +7,13 @@ use aptos_types::{
     account_address::{AccountAddress, AccountAddressWithChecks},
     chain_id::ChainId,
     network_address::{DnsName, NetworkAddress, Protocol},
-    on_chain_config::{OnChainConsensusConfig, OnChainExecutionConfig},
+    on_chain_config::{FeatureFlag, GasScheduleV2, OnChainConsensusConfig, OnChainExecutionConfig},
     transaction::authenticator::AuthenticationKey,
 };
-use aptos_vm_genesis::{AccountBalance, EmployeePool, Validator, ValidatorWithCommissionRate};
+use aptos_vm_genesis::{
+    AccountBalance, DelegationPool, EmployeePool, OIDCProvider, Validator,
+    ValidatorWithCommissionRate,
+};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     collections::{BTreeMap, HashSet},
@@ -67,12 +70,29 @@ pub struct Layout {
     pub employee_vesting_start: Option<u64>,
     /// Duration of each vesting period (in seconds).
     pub employee_vesting_period_duration: Option<u64>,
+    /// Delegation pools to create at genesis, along with their initial delegations
+    #[serde(default)]
+    pub delegation_pools: Vec<DelegationPool>,
+    /// Feature flags to enable at genesis (in addition to the default features)
+    #[serde(default)]
+    pub features_to_enable: Vec<FeatureFlag>,
+    /// Feature flags to disable at genesis
+    #[serde(default)]
+    pub features_to_disable: Vec<FeatureFlag>,
+    /// OIDC providers whose JWKs the validators should watch from genesis on
+    #[serde(default)]
+    pub oidc_providers: Vec<OIDCProvider>,
     /// Onchain Consensus Config
     #[serde(default = "OnChainConsensusConfig::default_for_genesis")]
     pub on_chain_consensus_config: OnChainConsensusConfig,
     /// Onchain Execution Config
     #[serde(default = "OnChainExecutionConfig::default_for_genesis")]
     pub on_chain_execution_config: OnChainExecutionConfig,
+    /// Gas schedule to use instead of the default one
+    ///
+    /// Ignored for mainnet
+    #[serde(default)]
+    pub gas_schedule: Option<GasScheduleV2>,
 }
 
 impl Layout {
@@ -110,8 +130,13 @@ impl Default for Layout {
             total_supply: None,
             employee_vesting_start: Some(1663456089),
             employee_vesting_period_duration: Some(5 * 60), // 5 minutes
+            delegation_pools: vec![],
+            features_to_enable: vec![],
+            features_to_disable: vec![],
+            oidc_providers: vec![],
             on_chain_consensus_config: OnChainConsensusConfig::default(),
             on_chain_execution_config: OnChainExecutionConfig::default_for_genesis(),
+            gas_schedule: None,
         }
     }
 }
@@ -23,12 +23,12 @@ use aptos_storage_interface::DbReaderWriter;
 use aptos_temppath::TempPath;
 use aptos_types::{
     chain_id::ChainId,
-    on_chain_config::{GasScheduleV2, OnChainConsensusConfig, OnChainExecutionConfig},
+    on_chain_config::{FeatureFlag, GasScheduleV2, OnChainConsensusConfig, OnChainExecutionConfig},
     transaction::Transaction,
     waypoint::Waypoint,
 };
 use aptos_vm::AptosVM;
-use aptos_vm_genesis::Validator;
+use aptos_vm_genesis::{DelegationPool, GenesisConfigError, OIDCProvider, Validator};
 use std::convert::TryInto;
 
 /// Holder object for all pieces needed to generate a genesis transaction
@@ -66,6 +66,14 @@ pub struct GenesisInfo {
     pub voting_duration_secs: u64,
     /// Percent of current epoch's total voting power that can be added in this epoch.
     pub voting_power_increase_limit: u64,
+    /// Delegation pools to create, along with their initial delegations.
+    pub delegation_pools: Vec<DelegationPool>,
+    /// Feature flags to enable (on top of the default features) at genesis.
+    pub features_to_enable: Vec<FeatureFlag>,
+    /// Feature flags to disable at genesis.
+    pub features_to_disable: Vec<FeatureFlag>,
+    /// OIDC providers whose JWKs the validators should watch.
+    pub oidc_providers: Vec<OIDCProvider>,
 
     pub consensus_config: OnChainConsensusConfig,
     pub execution_config: OnChainExecutionConfig,
@@ -103,22 +111,24 @@ impl GenesisInfo {
             rewards_apy_percentage: genesis_config.rewards_apy_percentage,
             voting_duration_secs: genesis_config.voting_duration_secs,
             voting_power_increase_limit: genesis_config.voting_power_increase_limit,
+            delegation_pools: genesis_config.delegation_pools.clone(),
+            features_to_enable: genesis_config.features_to_enable.clone(),
+            features_to_disable: genesis_config.features_to_disable.clone(),
+            oidc_providers: genesis_config.oidc_providers.clone(),
             consensus_config: genesis_config.consensus_config.clone(),
             execution_config: genesis_config.execution_config.clone(),
             gas_schedule: genesis_config.gas_schedule.clone(),
         })
     }
 
-    pub fn get_genesis(&mut self) -> &Transaction {
-        if let Some(ref genesis) = self.genesis {
-            genesis
-        } else {
-            self.genesis = Some(self.generate_genesis_txn());
-            self.genesis.as_ref().unwrap()
+    pub fn get_genesis(&mut self) -> anyhow::Result<&Transaction> {
+        if self.genesis.is_none() {
+            self.genesis = Some(self.generate_genesis_txn()?);
         }
+        Ok(self.genesis.as_ref().unwrap())
     }
 
-    fn generate_genesis_txn(&self) -> Transaction {
+    fn generate_genesis_txn(&self) -> Result<Transaction, GenesisConfigError> {
         aptos_vm_genesis::encode_genesis_transaction(
             self.root_key.clone(),
             &self.validators,
@@ -138,6 +148,10 @@ impl GenesisInfo {
                 voting_power_increase_limit: self.voting_power_increase_limit,
                 employee_vesting_start: 1663456089,
                 employee_vesting_period_duration: 5 * 60, // 5 minutes
+                delegation_pools: self.delegation_pools.clone(),
+                features_to_enable: self.features_to_enable.clone(),
+                features_to_disable: self.features_to_disable.clone(),
+                oidc_providers: self.oidc_providers.clone(),
             },
             &self.consensus_config,
             &self.execution_config,
@@ -146,7 +160,7 @@ impl GenesisInfo {
     }
 
     pub fn generate_waypoint(&mut self) -> anyhow::Result<Waypoint> {
-        let genesis = self.get_genesis();
+        let genesis = self.get_genesis()?;
         let path = TempPath::new();
         let aptosdb = AptosDB::open(
             StorageDirPaths::from_path(path),
@@ -10,9 +10,14 @@ use aptos_db::AptosDB;
 use aptos_framework::ReleaseBundle;
 use aptos_storage_interface::DbReaderWriter;
 use aptos_temppath::TempPath;
-use aptos_types::{chain_id::ChainId, transaction::Transaction, waypoint::Waypoint};
+use aptos_types::{
+    chain_id::ChainId, on_chain_config::FeatureFlag, transaction::Transaction, waypoint::Waypoint,
+};
 use aptos_vm::AptosVM;
-use aptos_vm_genesis::{AccountBalance, EmployeePool, ValidatorWithCommissionRate};
+use aptos_vm_genesis::{
+    AccountBalance, DelegationPool, EmployeePool, GenesisConfigError, OIDCProvider,
+    ValidatorWithCommissionRate,
+};
 
 /// Holder object for all pieces needed to generate a genesis transaction
 #[derive(Clone)]
@@ -24,6 +29,9 @@ pub struct MainnetGenesisInfo {
     /// The genesis transaction, once it's been generated
     genesis: Option<Transaction>,
 
+    /// Whether new validators can join the validator set
+    pub allow_new_validators: bool,
+
     /// Duration of an epoch
     pub epoch_duration_secs: u64,
     /// Minimum stake to be in the validator set
@@ -54,6 +62,14 @@ pub struct MainnetGenesisInfo {
     employee_vesting_start: u64,
     /// Duration of each vesting period (in seconds).
     employee_vesting_period_duration: u64,
+    /// Delegation pools to create, along with their initial delegations.
+    delegation_pools: Vec<DelegationPool>,
+    /// Feature flags to enable (on top of the default features) at genesis.
+    features_to_enable: Vec<FeatureFlag>,
+    /// Feature flags to disable at genesis.
+    features_to_disable: Vec<FeatureFlag>,
+    /// OIDC providers whose JWKs the validators should watch.
+    oidc_providers: Vec<OIDCProvider>,
 }
 
 impl MainnetGenesisInfo {
@@ -82,6 +98,7 @@ impl MainnetGenesisInfo {
                 .collect(),
             framework,
             genesis: None,
+            allow_new_validators: genesis_config.allow_new_validators,
             epoch_duration_secs: genesis_config.epoch_duration_secs,
             min_stake: genesis_config.min_stake,
             min_voting_threshold: genesis_config.min_voting_threshold,
@@ -93,19 +110,21 @@ impl MainnetGenesisInfo {
             voting_power_increase_limit: genesis_config.voting_power_increase_limit,
             employee_vesting_start,
             employee_vesting_period_duration,
+            delegation_pools: genesis_config.delegation_pools.clone(),
+            features_to_enable: genesis_config.features_to_enable.clone(),
+            features_to_disable: genesis_config.features_to_disable.clone(),
+            oidc_providers: genesis_config.oidc_providers.clone(),
         })
     }
 
-    pub fn get_genesis(&mut self) -> &Transaction {
-        if let Some(ref genesis) = self.genesis {
-            genesis
-        } else {
-            self.genesis = Some(self.generate_genesis_txn());
-            self.genesis.as_ref().unwrap()
+    pub fn get_genesis(&mut self) -> anyhow::Result<&Transaction> {
+        if self.genesis.is_none() {
+            self.genesis = Some(self.generate_genesis_txn()?);
         }
+        Ok(self.genesis.as_ref().unwrap())
     }
 
-    fn generate_genesis_txn(&self) -> Transaction {
+    fn generate_genesis_txn(&self) -> Result<Transaction, GenesisConfigError> {
         aptos_vm_genesis::encode_aptos_mainnet_genesis_transaction(
             &self.accounts,
             &self.employee_vesting_accounts,
@@ -113,7 +132,7 @@ impl MainnetGenesisInfo {
             &self.framework,
             self.chain_id,
             &aptos_vm_genesis::GenesisConfiguration {
-                allow_new_validators: true,
+                allow_new_validators: self.allow_new_validators,
                 is_test: false,
                 epoch_duration_secs: self.epoch_duration_secs,
                 min_stake: self.min_stake,
@@ -126,12 +145,16 @@ impl MainnetGenesisInfo {
                 voting_power_increase_limit: self.voting_power_increase_limit,
                 employee_vesting_start: self.employee_vesting_start,
                 employee_vesting_period_duration: self.employee_vesting_period_duration,
+                delegation_pools: self.delegation_pools.clone(),
+                features_to_enable: self.features_to_enable.clone(),
+                features_to_disable: self.features_to_disable.clone(),
+                oidc_providers: self.oidc_providers.clone(),
             },
         )
     }
 
     pub fn generate_waypoint(&mut self) -> anyhow::Result<Waypoint> {
-        let genesis = self.get_genesis();
+        let genesis = self.get_genesis()?;
         let path = TempPath::new();
         let aptosdb = AptosDB::open(
             StorageDirPaths::from_path(path),
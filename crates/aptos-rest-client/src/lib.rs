@@ -1193,6 +1193,31 @@ impl Client {
         self.json(response).await
     }
 
+    pub async fn get_account_events_by_creation_number(
+        &self,
+        address: AccountAddress,
+        creation_number: u64,
+        start: Option<u64>,
+        limit: Option<u16>,
+    ) -> AptosResult<Response<Vec<VersionedEvent>>> {
+        let url = self.build_path(&format!(
+            "accounts/{}/events/{}",
+            address.to_hex_literal(),
+            creation_number
+        ))?;
+        let mut request = self.inner.get(url);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = request.send().await?;
+        self.json(response).await
+    }
+
     pub async fn get_account_events_bcs(
         &self,
         address: AccountAddress,
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::{
-    CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, ProfileOptions, RestOptions,
+    AccountBalance, CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode,
+    OutputOptions, ProfileOptions, RestOptions,
 };
 use aptos_types::account_address::AccountAddress;
 use async_trait::async_trait;
@@ -62,6 +63,8 @@ pub struct ListAccount {
     pub(crate) rest_options: RestOptions,
     #[clap(flatten)]
     pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputOptions,
 }
 
 #[async_trait]
@@ -88,17 +91,25 @@ impl CliCommand<Vec<serde_json::Value>> for ListAccount {
 
         let client = self.rest_options.client(&self.profile_options)?;
         let response = match self.query {
-            ListQuery::Balance => vec![
-                client
+            ListQuery::Balance => {
+                let coin_store = client
                     .get_account_resource(
                         account,
                         "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
                     )
                     .await?
                     .into_inner()
-                    .unwrap()
-                    .data,
-            ],
+                    .unwrap();
+                if self.output_options.is_json() {
+                    vec![json!(AccountBalance::from_coin_store(
+                        account,
+                        &coin_store.resource_type.to_string(),
+                        &coin_store.data,
+                    )?)]
+                } else {
+                    vec![coin_store.data]
+                }
+            },
             ListQuery::Modules => client
                 .get_account_modules(account)
                 .await?
@@ -1,7 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliTypedResult, TransactionOptions};
+use crate::common::types::{
+    AccountBalance, CliCommand, CliTypedResult, OutputOptions, TransactionOptions,
+};
 use aptos_cached_packages::aptos_stdlib;
 use aptos_rest_client::{
     aptos_api_types::{HashValue, WriteResource, WriteSetChange},
@@ -11,6 +13,7 @@ use aptos_types::account_address::AccountAddress;
 use async_trait::async_trait;
 use clap::Parser;
 use serde::Serialize;
+use serde_json::json;
 use std::collections::BTreeMap;
 
 // TODO: Add ability to transfer non-APT coins
@@ -29,6 +32,8 @@ pub struct TransferCoins {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputOptions,
 }
 
 #[async_trait]
@@ -38,13 +43,14 @@ impl CliCommand<TransferSummary> for TransferCoins {
     }
 
     async fn execute(self) -> CliTypedResult<TransferSummary> {
+        let output_options = self.output_options;
         self.txn_options
             .submit_transaction(aptos_stdlib::aptos_account_transfer(
                 self.account,
                 self.amount,
             ))
             .await
-            .map(TransferSummary::from)
+            .map(|transaction| TransferSummary::from_transaction(transaction, output_options))
     }
 }
 
@@ -55,6 +61,7 @@ const SUPPORTED_COINS: [&str; 1] = ["0x1::coin::CoinStore<0x1::aptos_coin::Aptos
 pub struct TransferSummary {
     pub gas_unit_price: u64,
     pub gas_used: u64,
+    /// The new coin stores of the accounts (or an [`AccountBalance`] with `--output json`)
     pub balance_changes: BTreeMap<AccountAddress, serde_json::Value>,
     pub sender: AccountAddress,
     pub success: bool,
@@ -71,6 +78,12 @@ impl TransferSummary {
 
 impl From<Transaction> for TransferSummary {
     fn from(transaction: Transaction) -> Self {
+        TransferSummary::from_transaction(transaction, OutputOptions::default())
+    }
+}
+
+impl TransferSummary {
+    fn from_transaction(transaction: Transaction, output_options: OutputOptions) -> Self {
         if let Transaction::UserTransaction(txn) = transaction {
             let vm_status = txn.info.vm_status;
             let success = txn.info.success;
@@ -85,11 +98,22 @@ impl From<Transaction> for TransferSummary {
                 .into_iter()
                 .filter_map(|change| match change {
                     WriteSetChange::WriteResource(WriteResource { address, data, .. }) => {
-                        if SUPPORTED_COINS.contains(&data.typ.to_string().as_str()) {
-                            Some((
-                                *address.inner(),
-                                serde_json::to_value(data.data).unwrap_or_default(),
-                            ))
+                        let coin_store_type = data.typ.to_string();
+                        if SUPPORTED_COINS.contains(&coin_store_type.as_str()) {
+                            let address = *address.inner();
+                            let coin_store = serde_json::to_value(data.data).unwrap_or_default();
+                            let balance_change = if output_options.is_json() {
+                                AccountBalance::from_coin_store(
+                                    address,
+                                    &coin_store_type,
+                                    &coin_store,
+                                )
+                                .map(|balance| json!(balance))
+                                .unwrap_or_default()
+                            } else {
+                                coin_store
+                            };
+                            Some((address, balance_change))
                         } else {
                             None
                         }
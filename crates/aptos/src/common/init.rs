@@ -6,8 +6,9 @@ use crate::{
     common::{
         types::{
             account_address_from_public_key, CliCommand, CliConfig, CliError, CliTypedResult,
-            ConfigSearchMode, EncodingOptions, HardwareWalletOptions, PrivateKeyInputOptions,
-            ProfileConfig, ProfileOptions, PromptOptions, RngArgs, DEFAULT_PROFILE,
+            ConfigSearchMode, EncodingOptions, HardwareWalletOptions, MultisigProfileConfig,
+            PrivateKeyInputOptions, ProfileConfig, ProfileOptions, PromptOptions, RngArgs,
+            DEFAULT_PROFILE,
         },
         utils::{fund_account, prompt_yes_with_override, read_line},
     },
@@ -15,11 +16,14 @@ use crate::{
 use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, ValidCryptoMaterialStringExt};
 use aptos_ledger;
 use aptos_rest_client::{
-    aptos_api_types::{AptosError, AptosErrorCode},
+    aptos_api_types::{AptosError, AptosErrorCode, EntryFunctionId, ViewRequest},
     error::{AptosErrorResponse, RestError},
+    Client,
 };
+use aptos_types::account_address::AccountAddress;
 use async_trait::async_trait;
 use clap::Parser;
+use once_cell::sync::Lazy;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, str::FromStr};
@@ -27,6 +31,14 @@ use std::{collections::BTreeMap, str::FromStr};
 /// 1 APT (might not actually get that much, depending on the faucet)
 const NUM_DEFAULT_OCTAS: u64 = 100000000;
 
+static MULTISIG_OWNERS_FUNCTION: Lazy<EntryFunctionId> =
+    Lazy::new(|| "0x1::multisig_account::owners".parse().unwrap());
+static MULTISIG_NUM_SIGNATURES_REQUIRED_FUNCTION: Lazy<EntryFunctionId> = Lazy::new(|| {
+    "0x1::multisig_account::num_signatures_required"
+        .parse()
+        .unwrap()
+});
+
 /// Tool to initialize current directory for the aptos tool
 ///
 /// Configuration will be pushed into .aptos/config.yaml
@@ -65,6 +77,18 @@ pub struct InitTool {
     #[clap(flatten)]
     pub(crate) hardware_wallet_options: HardwareWalletOptions,
 
+    /// Address of an existing multisig account to create the profile for
+    ///
+    /// The profile only references the multisig account (and records its owners), it doesn't
+    /// have any keys. Transactions for the multisig account must be proposed and approved with
+    /// the profiles of its owners.
+    #[clap(
+        long,
+        value_parser = crate::common::types::load_account_arg,
+        conflicts_with_all = ["ledger", "private_key_input", "derivation_path", "derivation_index"]
+    )]
+    pub multisig_address: Option<AccountAddress>,
+
     #[clap(flatten)]
     pub rng_args: RngArgs,
     #[clap(flatten)]
@@ -146,6 +170,26 @@ impl CliCommand<()> for InitTool {
             Network::Custom => self.custom_network(&mut profile_config)?,
         }
 
+        // A multisig profile only references an existing multisig account, so there are no keys
+        if let Some(multisig_address) = self.multisig_address {
+            let client = rest_client(&profile_config)?;
+            let multisig = fetch_multisig_profile_config(&client, multisig_address).await?;
+            eprintln!(
+                "Found multisig account {} with {} owners ({} signatures required)",
+                multisig_address,
+                multisig.owners.len(),
+                multisig.num_signatures_required
+            );
+
+            profile_config.private_key = None;
+            profile_config.public_key = None;
+            profile_config.derivation_path = None;
+            profile_config.account = Some(multisig_address);
+            profile_config.multisig = Some(multisig);
+            return self.save_profile(config, profile_name, profile_config, multisig_address);
+        }
+        profile_config.multisig = None;
+
         // Check if any ledger flag is set
         let derivation_path = if let Some(deri_path) =
             self.hardware_wallet_options.extract_derivation_path()?
@@ -243,14 +287,7 @@ impl CliCommand<()> for InitTool {
             private_key.clone().unwrap().public_key()
         };
 
-        let rest_url = Url::parse(
-            profile_config
-                .rest_url
-                .as_ref()
-                .expect("Must have rest client as created above"),
-        )
-        .map_err(|err| CliError::UnableToParse("rest_url", err.to_string()))?;
-        let client = aptos_rest_client::Client::new(rest_url);
+        let client = rest_client(&profile_config)?;
 
         // lookup the address from onchain instead of deriving it
         // if this is the rotated key, deriving it will outputs an incorrect address
@@ -327,6 +364,19 @@ impl CliCommand<()> for InitTool {
             eprintln!("Account {} has been initialized locally, but you must transfer coins to it to create the account onchain", address);
         }
 
+        self.save_profile(config, profile_name, profile_config, address)
+    }
+}
+
+impl InitTool {
+    /// Saves the profile to the config in the current directory
+    fn save_profile(
+        &self,
+        mut config: CliConfig,
+        profile_name: &str,
+        profile_config: ProfileConfig,
+        address: AccountAddress,
+    ) -> CliTypedResult<()> {
         // Ensure the loaded config has profiles setup for a possible empty file
         if config.profiles.is_none() {
             config.profiles = Some(BTreeMap::new());
@@ -340,9 +390,7 @@ impl CliCommand<()> for InitTool {
         eprintln!("\n---\nAptos CLI is now set up for account {} as profile {}!  Run `aptos --help` for more information about commands", address, self.profile_options.profile_name().unwrap_or(DEFAULT_PROFILE));
         Ok(())
     }
-}
 
-impl InitTool {
     /// Custom network created, which requires a REST URL
     fn custom_network(&self, profile_config: &mut ProfileConfig) -> CliTypedResult<()> {
         // Rest Endpoint
@@ -419,6 +467,68 @@ impl InitTool {
     }
 }
 
+/// Creates a REST client for the profile's REST URL
+fn rest_client(profile_config: &ProfileConfig) -> CliTypedResult<Client> {
+    let rest_url = Url::parse(
+        profile_config
+            .rest_url
+            .as_ref()
+            .expect("Must have rest client as created above"),
+    )
+    .map_err(|err| CliError::UnableToParse("rest_url", err.to_string()))?;
+    Ok(Client::new(rest_url))
+}
+
+/// Fetches the owners and the number of required signatures of a multisig account from onchain
+async fn fetch_multisig_profile_config(
+    client: &Client,
+    multisig_address: AccountAddress,
+) -> CliTypedResult<MultisigProfileConfig> {
+    let view_multisig = |function: &EntryFunctionId| {
+        let request = ViewRequest {
+            function: function.clone(),
+            type_arguments: vec![],
+            arguments: vec![serde_json::Value::String(multisig_address.to_hex_literal())],
+        };
+        async move {
+            client
+                .view(&request, None)
+                .await
+                .map(|response| response.into_inner())
+                .map_err(|err| {
+                    CliError::CommandArgumentError(format!(
+                        "Unable to find multisig account {}: {}",
+                        multisig_address, err
+                    ))
+                })
+        }
+    };
+
+    let owners = view_multisig(&MULTISIG_OWNERS_FUNCTION)
+        .await?
+        .into_iter()
+        .next()
+        .and_then(|owners| serde_json::from_value::<Vec<AccountAddress>>(owners).ok())
+        .ok_or_else(|| {
+            CliError::UnexpectedError("Unable to parse the multisig account owners".to_string())
+        })?;
+    let num_signatures_required = view_multisig(&MULTISIG_NUM_SIGNATURES_REQUIRED_FUNCTION)
+        .await?
+        .into_iter()
+        .next()
+        .and_then(|value| value.as_str().and_then(|value| value.parse::<u64>().ok()))
+        .ok_or_else(|| {
+            CliError::UnexpectedError(
+                "Unable to parse the multisig account signature threshold".to_string(),
+            )
+        })?;
+
+    Ok(MultisigProfileConfig {
+        owners,
+        num_signatures_required,
+    })
+}
+
 /// A simplified list of all networks supported by the CLI
 ///
 /// Any command using this, will be simpler to setup as profiles
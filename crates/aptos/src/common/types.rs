@@ -247,6 +247,55 @@ pub struct ProfileConfig {
     /// Derivation path index of the account on ledger
     #[serde(skip_serializing_if = "Option::is_none")]
     pub derivation_path: Option<String>,
+    /// Multisig account metadata, if the profile's account is a multisig account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<MultisigProfileConfig>,
+}
+
+impl ProfileConfig {
+    /// Returns an error if the profile can't sign transactions for its own account
+    /// (e.g., a multisig account, which only executes transactions approved by its owners)
+    pub fn ensure_can_sign(&self, profile_name: Option<&str>) -> CliTypedResult<()> {
+        if self.multisig.is_some() {
+            let account = self
+                .account
+                .map(|account| account.to_hex_literal())
+                .unwrap_or_else(|| "<unknown>".to_string());
+            return Err(CliError::CommandArgumentError(format!(
+                "Profile {} is for multisig account {}, which can't sign transactions. Use \
+                `aptos multisig create-transaction --multisig-address {}` with the profile of one \
+                of its owners instead",
+                profile_name.unwrap_or(DEFAULT_PROFILE),
+                account,
+                account
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the profile doesn't hold a raw private key that commands can use
+    /// directly (e.g., the private key of a hardware wallet profile never leaves the device)
+    pub fn ensure_has_private_key(&self, profile_name: Option<&str>) -> CliTypedResult<()> {
+        self.ensure_can_sign(profile_name)?;
+        if self.private_key.is_none() && self.derivation_path.is_some() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Profile {} is backed by a hardware wallet, and its private key can't be used \
+                directly by this command. Provide one of ['--private-key', '--private-key-file'] \
+                instead",
+                profile_name.unwrap_or(DEFAULT_PROFILE)
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Multisig account metadata stored in a profile
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MultisigProfileConfig {
+    /// Owners of the multisig account (at the time the profile was created)
+    pub owners: Vec<AccountAddress>,
+    /// Number of owner approvals required to execute a transaction
+    pub num_signatures_required: u64,
 }
 
 /// ProfileConfig but without the private parts
@@ -261,6 +310,8 @@ pub struct ProfileSummary {
     pub rest_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub faucet_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<MultisigProfileConfig>,
 }
 
 impl From<&ProfileConfig> for ProfileSummary {
@@ -271,6 +322,7 @@ impl From<&ProfileConfig> for ProfileSummary {
             account: config.account,
             rest_url: config.rest_url.clone(),
             faucet_url: config.faucet_url.clone(),
+            multisig: config.multisig.clone(),
         }
     }
 }
@@ -467,6 +519,25 @@ impl ProfileOptions {
         self.profile.as_ref().map(|inner| inner.trim())
     }
 
+    /// Returns an error if the profile exists but can't sign transactions (e.g., a multisig profile)
+    pub fn ensure_can_sign(&self) -> CliTypedResult<()> {
+        match CliConfig::load_profile(self.profile_name(), ConfigSearchMode::CurrentDirAndParents)?
+        {
+            Some(profile) => profile.ensure_can_sign(self.profile_name()),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns an error if the profile exists but has no usable private key (e.g., a hardware
+    /// wallet or multisig profile)
+    pub fn ensure_has_private_key(&self) -> CliTypedResult<()> {
+        match CliConfig::load_profile(self.profile_name(), ConfigSearchMode::CurrentDirAndParents)?
+        {
+            Some(profile) => profile.ensure_has_private_key(self.profile_name()),
+            None => Ok(()),
+        }
+    }
+
     pub fn profile(&self) -> CliTypedResult<ProfileConfig> {
         if let Some(profile) =
             CliConfig::load_profile(self.profile_name(), ConfigSearchMode::CurrentDirAndParents)?
@@ -563,6 +634,81 @@ pub struct EncodingOptions {
     pub encoding: EncodingType,
 }
 
+/// The format of command results
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Results contain the on-chain resources, as returned by the REST API
+    #[default]
+    Raw,
+    /// Results are structured, with stable field names (e.g., for automation)
+    Json,
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            OutputFormat::Raw => "raw",
+            OutputFormat::Json => "json",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// An insertable option for use with machine-readable outputs.
+#[derive(Clone, Copy, Debug, Default, Parser, PartialEq, Eq)]
+pub struct OutputOptions {
+    /// Format of the results as one of [raw, json]
+    ///
+    /// `json` replaces the raw on-chain resources in the results (e.g., coin stores and
+    /// stake pools) with structured values that have stable field names.
+    #[clap(long = "output", value_enum, ignore_case = true, default_value_t = OutputFormat::Raw)]
+    pub output_format: OutputFormat,
+}
+
+impl OutputOptions {
+    pub fn json() -> Self {
+        Self {
+            output_format: OutputFormat::Json,
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.output_format == OutputFormat::Json
+    }
+}
+
+/// The coin balance of an account, as output with `--output json`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AccountBalance {
+    pub account: AccountAddress,
+    pub coin_type: String,
+    pub balance: u64,
+}
+
+impl AccountBalance {
+    /// Extracts the balance from the JSON representation of a `0x1::coin::CoinStore`
+    pub fn from_coin_store(
+        account: AccountAddress,
+        coin_store_type: &str,
+        coin_store: &serde_json::Value,
+    ) -> CliTypedResult<Self> {
+        let coin_type = coin_store_type
+            .strip_prefix("0x1::coin::CoinStore<")
+            .and_then(|coin_type| coin_type.strip_suffix('>'))
+            .ok_or_else(|| CliError::UnableToParse("CoinStore", coin_store_type.to_string()))?;
+        let balance = coin_store
+            .pointer("/coin/value")
+            .and_then(|value| value.as_str())
+            .and_then(|value| u64::from_str(value).ok())
+            .ok_or_else(|| CliError::UnableToParse("CoinStore", coin_store.to_string()))?;
+        Ok(Self {
+            account,
+            coin_type: coin_type.to_string(),
+            balance,
+        })
+    }
+}
+
 #[derive(Debug, Parser)]
 pub struct AuthenticationKeyInputOptions {
     /// Authentication Key file input
@@ -788,6 +934,7 @@ impl PrivateKeyInputOptions {
                 },
             }
         } else {
+            profile.ensure_can_sign()?;
             Err(CliError::CommandArgumentError(
                 "One of ['--private-key', '--private-key-file'], or ['public_key'] must present in profile".to_string(),
             ))
@@ -828,6 +975,7 @@ impl PrivateKeyInputOptions {
                 },
             }
         } else {
+            profile.ensure_has_private_key()?;
             Err(CliError::CommandArgumentError(
                 "One of ['--private-key', '--private-key-file'] must be used".to_string(),
             ))
@@ -850,6 +998,7 @@ impl PrivateKeyInputOptions {
         {
             Ok(private_key)
         } else {
+            profile.ensure_has_private_key()?;
             Err(CliError::CommandArgumentError(
                 "One of ['--private-key', '--private-key-file'] must be used".to_string(),
             ))
@@ -1522,6 +1671,7 @@ impl TransactionOptions {
             self.profile_options.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
         )? {
+            profile.ensure_can_sign(self.profile_options.profile_name())?;
             if profile.private_key.is_some() {
                 Ok(AccountType::Local)
             } else {
@@ -1554,7 +1704,8 @@ impl TransactionOptions {
     }
 
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
-        Ok(self.get_key_and_address()?.1)
+        // Use the public key, so that hardware wallet profiles are supported
+        Ok(self.get_public_key_and_address()?.1)
     }
 
     pub fn get_public_key(&self) -> CliTypedResult<Ed25519PublicKey> {
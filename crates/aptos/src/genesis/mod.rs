@@ -36,7 +36,9 @@ use aptos_types::{
     account_address::{AccountAddress, AccountAddressWithChecks},
     on_chain_config::{OnChainConsensusConfig, OnChainExecutionConfig},
 };
-use aptos_vm_genesis::{default_gas_schedule, AccountBalance, EmployeePool};
+use aptos_vm_genesis::{
+    default_gas_schedule, validate_gas_schedule, AccountBalance, DelegationPool, EmployeePool,
+};
 use async_trait::async_trait;
 use clap::Parser;
 use std::{
@@ -115,12 +117,12 @@ impl CliCommand<Vec<PathBuf>> for GenerateGenesis {
         // Generate genesis and waypoint files
         let (genesis_bytes, waypoint) = if self.mainnet {
             let mut mainnet_genesis = fetch_mainnet_genesis_info(self.git_options)?;
-            let genesis_bytes = bcs::to_bytes(mainnet_genesis.clone().get_genesis())
+            let genesis_bytes = bcs::to_bytes(mainnet_genesis.clone().get_genesis()?)
                 .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
             (genesis_bytes, mainnet_genesis.generate_waypoint()?)
         } else {
             let mut test_genesis = fetch_genesis_info(self.git_options)?;
-            let genesis_bytes = bcs::to_bytes(test_genesis.clone().get_genesis())
+            let genesis_bytes = bcs::to_bytes(test_genesis.clone().get_genesis()?)
                 .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
             (genesis_bytes, test_genesis.generate_waypoint()?)
         };
@@ -134,6 +136,29 @@ impl CliCommand<Vec<PathBuf>> for GenerateGenesis {
     }
 }
 
+/// Checks that the initial balances plus the coins delegated to the genesis delegation pools,
+/// which are minted to the delegators on top of their balances, add up to the total supply.
+pub(crate) fn validate_total_supply(
+    total_supply: u64,
+    accounts: &[AccountBalance],
+    delegation_pools: &[DelegationPool],
+) -> CliTypedResult<()> {
+    let total_balance_supply: u64 = accounts.iter().map(|inner| inner.balance).sum();
+    let total_delegated_supply: u64 = delegation_pools
+        .iter()
+        .flat_map(|pool| pool.delegations.iter())
+        .map(|delegation| delegation.amount)
+        .sum();
+    let total_genesis_supply = total_balance_supply + total_delegated_supply;
+    if total_supply != total_genesis_supply {
+        return Err(CliError::UnexpectedError(format!(
+            "Total supply seen {} ({} in balances, {} delegated) doesn't match expected total supply {}",
+            total_genesis_supply, total_balance_supply, total_delegated_supply, total_supply
+        )));
+    }
+    Ok(())
+}
+
 /// Retrieves all information for mainnet genesis from the Git repository
 pub fn fetch_mainnet_genesis_info(git_options: GitOptions) -> CliTypedResult<MainnetGenesisInfo> {
     let client = git_options.get_client()?;
@@ -153,13 +178,7 @@ pub fn fetch_mainnet_genesis_info(git_options: GitOptions) -> CliTypedResult<Mai
     let accounts: Vec<AccountBalance> = account_balance_map.try_into()?;
 
     // Check that the supply matches the total
-    let total_balance_supply: u64 = accounts.iter().map(|inner| inner.balance).sum();
-    if total_supply != total_balance_supply {
-        return Err(CliError::UnexpectedError(format!(
-            "Total supply seen {} doesn't match expected total supply {}",
-            total_balance_supply, total_supply
-        )));
-    }
+    validate_total_supply(total_supply, &accounts, &layout.delegation_pools)?;
 
     // Check that the user has a reasonable amount of APT, since below the minimum gas amount is
     // not useful 1 APT minimally
@@ -241,7 +260,7 @@ pub fn fetch_mainnet_genesis_info(git_options: GitOptions) -> CliTypedResult<Mai
         validators,
         framework,
         &GenesisConfiguration {
-            allow_new_validators: true,
+            allow_new_validators: layout.allow_new_validators,
             epoch_duration_secs: layout.epoch_duration_secs,
             is_test: false,
             min_stake: layout.min_stake,
@@ -254,6 +273,10 @@ pub fn fetch_mainnet_genesis_info(git_options: GitOptions) -> CliTypedResult<Mai
             voting_power_increase_limit: layout.voting_power_increase_limit,
             employee_vesting_start: layout.employee_vesting_start,
             employee_vesting_period_duration: layout.employee_vesting_period_duration,
+            delegation_pools: layout.delegation_pools,
+            features_to_enable: layout.features_to_enable,
+            features_to_disable: layout.features_to_disable,
+            oidc_providers: layout.oidc_providers,
             consensus_config: OnChainConsensusConfig::default_for_genesis(),
             execution_config: OnChainExecutionConfig::default_for_genesis(),
             gas_schedule: default_gas_schedule(),
@@ -273,6 +296,11 @@ pub fn fetch_genesis_info(git_options: GitOptions) -> CliTypedResult<GenesisInfo
         ));
     }
 
+    let gas_schedule = layout.gas_schedule.unwrap_or_else(default_gas_schedule);
+    validate_gas_schedule(&gas_schedule).map_err(|error| {
+        CliError::UnexpectedError(format!("Layout field gas_schedule is invalid: {}", error))
+    })?;
+
     let validators = get_validator_configs(&client, &layout, false).map_err(parse_error)?;
     let framework = client.get_framework()?;
     Ok(GenesisInfo::new(
@@ -294,9 +322,13 @@ pub fn fetch_genesis_info(git_options: GitOptions) -> CliTypedResult<GenesisInfo
             voting_power_increase_limit: layout.voting_power_increase_limit,
             employee_vesting_start: layout.employee_vesting_start,
             employee_vesting_period_duration: layout.employee_vesting_period_duration,
+            delegation_pools: layout.delegation_pools,
+            features_to_enable: layout.features_to_enable,
+            features_to_disable: layout.features_to_disable,
+            oidc_providers: layout.oidc_providers,
             consensus_config: layout.on_chain_consensus_config,
             execution_config: layout.on_chain_execution_config,
-            gas_schedule: default_gas_schedule(),
+            gas_schedule,
         },
     )?)
 }
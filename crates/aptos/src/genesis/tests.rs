@@ -12,7 +12,7 @@ use crate::{
             FRAMEWORK_NAME,
         },
         keys::{GenerateKeys, GenerateLayoutTemplate, SetValidatorConfiguration, PUBLIC_KEYS_FILE},
-        GenerateGenesis,
+        validate_total_supply, GenerateGenesis,
     },
     CliCommand,
 };
@@ -30,7 +30,7 @@ use aptos_genesis::{
 use aptos_keygen::KeyGen;
 use aptos_temppath::TempPath;
 use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
-use aptos_vm_genesis::{AccountBalance, TestValidator};
+use aptos_vm_genesis::{AccountBalance, Delegation, DelegationPool, TestValidator};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
@@ -128,6 +128,47 @@ async fn test_mainnet_genesis_e2e_flow() {
     assert!(genesis_file.exists());
 }
 
+#[test]
+fn test_total_supply_includes_delegation_pools() {
+    let account_1 = AccountAddress::from_hex_literal("0x101").unwrap();
+    let account_2 = AccountAddress::from_hex_literal("0x102").unwrap();
+    let accounts = vec![
+        AccountBalance {
+            account_address: account_1,
+            balance: INITIAL_BALANCE,
+        },
+        AccountBalance {
+            account_address: account_2,
+            balance: INITIAL_BALANCE,
+        },
+    ];
+    // One delegator already has a balance, the other one doesn't exist yet.
+    let delegation_pools = vec![DelegationPool {
+        owner_address: account_1,
+        operator_address: account_1,
+        operator_commission_percentage: 1000,
+        delegation_pool_creation_seed: vec![1],
+        delegations: vec![
+            Delegation {
+                delegator_address: account_2,
+                amount: 20 * INITIAL_BALANCE,
+            },
+            Delegation {
+                delegator_address: AccountAddress::from_hex_literal("0x103").unwrap(),
+                amount: 30 * INITIAL_BALANCE,
+            },
+        ],
+    }];
+
+    validate_total_supply(INITIAL_BALANCE * 2, &accounts, &[]).unwrap();
+    validate_total_supply(INITIAL_BALANCE * 52, &accounts, &delegation_pools).unwrap();
+
+    // The delegated coins are minted on top of the balances, so they must be
+    // part of the total supply.
+    validate_total_supply(INITIAL_BALANCE * 2, &accounts, &delegation_pools).unwrap_err();
+    validate_total_supply(INITIAL_BALANCE * 32, &accounts, &delegation_pools).unwrap_err();
+}
+
 pub fn load_identity(base_dir: &Path, name: &str) -> PublicIdentity {
     let path = base_dir.join(name).join(PUBLIC_KEYS_FILE);
     from_yaml(&String::from_utf8(read_from_file(path.as_path()).unwrap()).unwrap()).unwrap()
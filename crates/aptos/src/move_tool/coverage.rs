@@ -5,10 +5,13 @@ use crate::common::types::{CliCommand, CliError, CliResult, CliTypedResult, Move
 use aptos_framework::extended_checks;
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
+use move_binary_format::CompiledModule;
 use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
 use move_coverage::{
-    coverage_map::CoverageMap, format_csv_summary, format_human_summary,
-    source_coverage::SourceCoverageBuilder, summary::summarize_inst_cov,
+    coverage_map::CoverageMap,
+    format_csv_summary, format_human_summary,
+    source_coverage::SourceCoverageBuilder,
+    summary::{summarize_inst_cov, ModuleSummary},
 };
 use move_disassembler::disassembler::Disassembler;
 use move_package::{compilation::compiled_package::CompiledPackage, BuildConfig, CompilerConfig};
@@ -35,23 +38,7 @@ pub struct SummaryCoverage {
 impl SummaryCoverage {
     pub fn coverage(self) -> CliTypedResult<()> {
         let (coverage_map, package) = compile_coverage(self.move_options)?;
-        let modules: Vec<_> = package
-            .root_modules()
-            .filter_map(|unit| {
-                let mut retain = true;
-                if let Some(filter_str) = &self.filter {
-                    if !&unit.unit.name().as_str().contains(filter_str.as_str()) {
-                        retain = false;
-                    }
-                }
-                match &unit.unit {
-                    CompiledUnit::Module(NamedCompiledModule { module, .. }) if retain => {
-                        Some(module.clone())
-                    },
-                    _ => None,
-                }
-            })
-            .collect();
+        let modules = filter_root_modules(&package, self.filter.as_deref());
         let coverage_map = coverage_map.to_unified_exec_map();
         if self.output_csv {
             format_csv_summary(
@@ -71,6 +58,38 @@ impl SummaryCoverage {
         }
         Ok(())
     }
+
+    /// Computes the instruction coverage of each module in the package, without printing it
+    pub fn module_summaries(self) -> CliTypedResult<Vec<ModuleSummary>> {
+        let (coverage_map, package) = compile_coverage(self.move_options)?;
+        let modules = filter_root_modules(&package, self.filter.as_deref());
+        let coverage_map = coverage_map.to_unified_exec_map();
+        Ok(modules
+            .iter()
+            .map(|module| summarize_inst_cov(module, &coverage_map))
+            .collect())
+    }
+}
+
+/// Returns the root modules of the package whose name contains the filter, if any
+fn filter_root_modules(package: &CompiledPackage, filter: Option<&str>) -> Vec<CompiledModule> {
+    package
+        .root_modules()
+        .filter_map(|unit| {
+            let mut retain = true;
+            if let Some(filter_str) = filter {
+                if !&unit.unit.name().as_str().contains(filter_str) {
+                    retain = false;
+                }
+            }
+            match &unit.unit {
+                CompiledUnit::Module(NamedCompiledModule { module, .. }) if retain => {
+                    Some(module.clone())
+                },
+                _ => None,
+            }
+        })
+        .collect()
 }
 
 #[async_trait]
@@ -516,10 +516,10 @@ impl CliCommand<&'static str> for TestPackage {
 #[derive(Parser)]
 pub struct ProvePackage {
     #[clap(flatten)]
-    move_options: MovePackageDir,
+    pub(crate) move_options: MovePackageDir,
 
     #[clap(flatten)]
-    prover_options: ProverOptions,
+    pub(crate) prover_options: ProverOptions,
 }
 
 #[async_trait]
@@ -9,7 +9,8 @@ use crate::{
     common::{
         types::{
             CliCommand, CliError, CliResult, CliTypedResult, OptionalPoolAddressArgs,
-            PoolAddressArgs, ProfileOptions, RestOptions, TransactionOptions, TransactionSummary,
+            OutputOptions, PoolAddressArgs, ProfileOptions, RestOptions, TransactionOptions,
+            TransactionSummary,
         },
         utils::read_from_file,
     },
@@ -753,6 +754,36 @@ pub struct ShowValidatorStake {
     pub(crate) rest_options: RestOptions,
     #[clap(flatten)]
     pub(crate) operator_args: OperatorArgs,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputOptions,
+}
+
+/// The stake of a validator, as output with `--output json`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ValidatorStakeSummary {
+    pub pool_address: AccountAddress,
+    pub active: u64,
+    pub inactive: u64,
+    pub pending_active: u64,
+    pub pending_inactive: u64,
+    pub locked_until_secs: u64,
+    pub operator_address: AccountAddress,
+    pub delegated_voter: AccountAddress,
+}
+
+impl ValidatorStakeSummary {
+    fn new(pool_address: AccountAddress, stake_pool: &StakePool) -> Self {
+        Self {
+            pool_address,
+            active: stake_pool.active,
+            inactive: stake_pool.inactive,
+            pending_active: stake_pool.pending_active,
+            pending_inactive: stake_pool.pending_inactive,
+            locked_until_secs: stake_pool.locked_until_secs,
+            operator_address: stake_pool.operator_address,
+            delegated_voter: stake_pool.delegated_voter,
+        }
+    }
 }
 
 #[async_trait]
@@ -766,6 +797,16 @@ impl CliCommand<serde_json::Value> for ShowValidatorStake {
         let address = self
             .operator_args
             .address_fallback_to_profile(&self.profile_options)?;
+        if self.output_options.is_json() {
+            let stake_pool = client
+                .get_account_resource_bcs::<StakePool>(address, "0x1::stake::StakePool")
+                .await?
+                .into_inner();
+            let summary = ValidatorStakeSummary::new(address, &stake_pool);
+            return serde_json::to_value(summary)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()));
+        }
+
         let response = client
             .get_resource(address, "0x1::stake::StakePool")
             .await?;
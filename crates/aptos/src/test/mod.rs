@@ -12,30 +12,31 @@ use crate::{
     common::{
         init::{InitTool, Network},
         types::{
-            account_address_from_public_key, AccountAddressWrapper, ArgWithTypeVec,
-            AuthenticationKeyInputOptions, CliError, CliTypedResult, EncodingOptions,
+            account_address_from_public_key, AccountAddressWrapper, AccountBalance, ArgWithTypeVec,
+            AuthenticationKeyInputOptions, CliConfig, CliError, CliTypedResult, EncodingOptions,
             EntryFunctionArguments, FaucetOptions, GasOptions, KeyType, MoveManifestAccountWrapper,
-            MovePackageDir, OptionalPoolAddressArgs, PoolAddressArgs, PrivateKeyInputOptions,
-            PromptOptions, PublicKeyInputOptions, RestOptions, RngArgs, SaveFile,
-            ScriptFunctionArguments, TransactionOptions, TransactionSummary, TypeArgVec,
+            MovePackageDir, OptionalPoolAddressArgs, OutputOptions, PoolAddressArgs,
+            PrivateKeyInputOptions, ProfileConfig, ProfileOptions, PromptOptions,
+            PublicKeyInputOptions, RestOptions, RngArgs, SaveFile, ScriptFunctionArguments,
+            TransactionOptions, TransactionSummary, TypeArgVec, DEFAULT_PROFILE,
         },
         utils::write_to_file,
     },
     governance::{
-        CompileScriptFunction, ProposalSubmissionSummary, SubmitProposal, SubmitProposalArgs,
-        SubmitVote, SubmitVoteArgs, VerifyProposal, VerifyProposalResponse,
+        CompileScriptFunction, ExecuteProposal, ProposalSubmissionSummary, SubmitProposal,
+        SubmitProposalArgs, SubmitVote, SubmitVoteArgs, VerifyProposal, VerifyProposalResponse,
     },
     move_tool::{
-        ArgWithType, CompilePackage, DownloadPackage, FrameworkPackageArgs, IncludedArtifacts,
-        IncludedArtifactsArgs, InitPackage, MemberId, PublishPackage, RunFunction, RunScript,
-        TestPackage,
+        coverage::SummaryCoverage, ArgWithType, CompilePackage, DownloadPackage,
+        FrameworkPackageArgs, IncludedArtifacts, IncludedArtifactsArgs, InitPackage, MemberId,
+        ProvePackage, PublishPackage, RunFunction, RunScript, TestPackage,
     },
     node::{
         AnalyzeMode, AnalyzeValidatorPerformance, GetStakePool, InitializeValidator,
         JoinValidatorSet, LeaveValidatorSet, OperatorArgs, OperatorConfigFileArgs,
         ShowValidatorConfig, ShowValidatorSet, ShowValidatorStake, StakePoolResult,
         UpdateConsensusKey, UpdateValidatorNetworkAddresses, ValidatorConfig,
-        ValidatorConsensusKeyArgs, ValidatorNetworkAddressesArgs,
+        ValidatorConsensusKeyArgs, ValidatorNetworkAddressesArgs, ValidatorStakeSummary,
     },
     op::key::{ExtractPeer, GenerateKey, NetworkKeyInputOptions, SaveKey},
     stake::{
@@ -48,19 +49,29 @@ use aptos_config::config::Peer;
 use aptos_crypto::{
     bls12381,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-    x25519, PrivateKey,
+    x25519, HashValue, PrivateKey,
 };
+use aptos_framework::prover::ProverOptions;
 use aptos_genesis::config::HostAndPort;
 use aptos_keygen::KeyGen;
 use aptos_logger::warn;
 use aptos_rest_client::{
-    aptos_api_types::{MoveStructTag, MoveType},
+    aptos_api_types::{Event, MoveStructTag, MoveType, VersionedEvent, ViewRequest},
     Transaction,
 };
-use aptos_sdk::move_types::{account_address::AccountAddress, language_storage::ModuleId};
+use aptos_sdk::{
+    move_types::{account_address::AccountAddress, language_storage::ModuleId},
+    transaction_builder::TransactionFactory,
+    types::LocalAccount,
+};
 use aptos_temppath::TempPath;
-use aptos_types::on_chain_config::ValidatorSet;
+use aptos_types::{
+    chain_id::ChainId,
+    on_chain_config::ValidatorSet,
+    transaction::{EntryFunction, TransactionPayload},
+};
 use move_core_types::ident_str;
+use move_coverage::summary::ModuleSummary;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -79,6 +90,15 @@ mod tests;
 
 pub const INVALID_ACCOUNT: &str = "0xDEADBEEFCAFEBABE";
 
+/// The max gas of transactions that are expected to fail. Setting the max gas skips
+/// the simulation, so that the transaction is committed (and fails) on-chain.
+pub const EXPECTED_FAILURE_MAX_GAS: u64 = 100_000;
+
+/// Proposal states, as returned by `0x1::voting::get_proposal_state`
+pub const PROPOSAL_STATE_PENDING: u64 = 0;
+pub const PROPOSAL_STATE_SUCCEEDED: u64 = 1;
+pub const PROPOSAL_STATE_FAILED: u64 = 3;
+
 pub const FIRST_MOVE_FILE: &str = "
 module NamedAddress0::store {
     use std::string;
@@ -96,25 +116,29 @@ module NamedAddress0::store {
     }
 }";
 
+/// The endpoints of a named profile of the CLI test framework
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CliTestProfile {
+    pub endpoint: Url,
+    pub faucet_endpoint: Url,
+}
+
 /// A framework for testing the CLI
+///
+/// Commands run against the endpoints of the active profile, which is the `default`
+/// profile unless switched with [`CliTestFramework::set_active_profile`].
 pub struct CliTestFramework {
     account_addresses: Vec<AccountAddress>,
     account_keys: Vec<Ed25519PrivateKey>,
-    endpoint: Url,
-    faucet_endpoint: Url,
+    profiles: BTreeMap<String, CliTestProfile>,
+    active_profile: String,
     move_dir: Option<PathBuf>,
 }
 
 impl CliTestFramework {
     pub fn local_new(num_accounts: usize) -> CliTestFramework {
         let dummy_url = Url::parse("http://localhost").unwrap();
-        let mut framework = CliTestFramework {
-            account_addresses: Vec::new(),
-            account_keys: Vec::new(),
-            endpoint: dummy_url.clone(),
-            faucet_endpoint: dummy_url,
-            move_dir: None,
-        };
+        let mut framework = CliTestFramework::with_default_profile(dummy_url.clone(), dummy_url);
         let mut keygen = KeyGen::from_seed([0; 32]);
         for _ in 0..num_accounts {
             let key = keygen.generate_ed25519_private_key();
@@ -124,13 +148,7 @@ impl CliTestFramework {
     }
 
     pub async fn new(endpoint: Url, faucet_endpoint: Url, num_accounts: usize) -> CliTestFramework {
-        let mut framework = CliTestFramework {
-            account_addresses: Vec::new(),
-            account_keys: Vec::new(),
-            endpoint,
-            faucet_endpoint,
-            move_dir: None,
-        };
+        let mut framework = CliTestFramework::with_default_profile(endpoint, faucet_endpoint);
         let mut keygen = KeyGen::from_seed([0; 32]);
 
         for _ in 0..num_accounts {
@@ -143,13 +161,104 @@ impl CliTestFramework {
         framework
     }
 
+    fn with_default_profile(endpoint: Url, faucet_endpoint: Url) -> CliTestFramework {
+        CliTestFramework {
+            account_addresses: Vec::new(),
+            account_keys: Vec::new(),
+            profiles: BTreeMap::from([(DEFAULT_PROFILE.to_string(), CliTestProfile {
+                endpoint,
+                faucet_endpoint,
+            })]),
+            active_profile: DEFAULT_PROFILE.to_string(),
+            move_dir: None,
+        }
+    }
+
+    /// Adds a named profile (e.g. for another network), without making it active
+    pub fn add_profile(&mut self, name: &str, endpoint: Url, faucet_endpoint: Url) {
+        let previous = self.profiles.insert(name.to_string(), CliTestProfile {
+            endpoint,
+            faucet_endpoint,
+        });
+        assert!(previous.is_none(), "Profile {} already exists", name);
+    }
+
+    /// Makes the next commands run against the given profile, returning the previously
+    /// active profile so that it can be switched back to
+    pub fn set_active_profile(&mut self, name: &str) -> String {
+        assert!(
+            self.profiles.contains_key(name),
+            "Profile {} doesn't exist",
+            name
+        );
+        mem::replace(&mut self.active_profile, name.to_string())
+    }
+
+    pub fn active_profile(&self) -> &str {
+        &self.active_profile
+    }
+
+    pub fn profile(&self, name: &str) -> &CliTestProfile {
+        self.profiles
+            .get(name)
+            .unwrap_or_else(|| panic!("Profile {} doesn't exist", name))
+    }
+
+    pub fn profile_names(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
+
+    /// The REST endpoint of the active profile
+    pub fn endpoint(&self) -> Url {
+        self.profile(&self.active_profile).endpoint.clone()
+    }
+
+    /// The faucet endpoint of the active profile
+    pub fn faucet_endpoint(&self) -> Url {
+        self.profile(&self.active_profile).faucet_endpoint.clone()
+    }
+
+    /// Selects the active profile, for commands resolving their settings from the CLI config
+    pub fn profile_options(&self) -> ProfileOptions {
+        ProfileOptions {
+            profile: Some(self.active_profile.clone()),
+        }
+    }
+
+    /// Builds a CLI config with all the profiles of the framework, using the given account
+    /// (if any) in each of them. Once saved, it can be used to test how commands resolve
+    /// their settings from profiles.
+    pub fn cli_config(&self, account_index: Option<usize>) -> CliConfig {
+        let profiles = self
+            .profiles
+            .iter()
+            .map(|(name, profile)| {
+                let mut profile_config = ProfileConfig {
+                    rest_url: Some(profile.endpoint.to_string()),
+                    faucet_url: Some(profile.faucet_endpoint.to_string()),
+                    ..Default::default()
+                };
+                if let Some(index) = account_index {
+                    let private_key = self.private_key(index);
+                    profile_config.public_key = Some(private_key.public_key());
+                    profile_config.private_key = Some(private_key.clone());
+                    profile_config.account = Some(self.account_id(index));
+                }
+                (name.clone(), profile_config)
+            })
+            .collect();
+        CliConfig {
+            profiles: Some(profiles),
+        }
+    }
+
     pub fn addresses(&self) -> Vec<AccountAddress> {
         self.account_addresses.clone()
     }
 
     async fn check_account_exists(&self, index: usize) -> bool {
         // Create account if it doesn't exist (and there's a faucet)
-        let client = aptos_rest_client::Client::new(self.endpoint.clone());
+        let client = aptos_rest_client::Client::new(self.endpoint());
         let address = self.account_id(index);
         client.get_account(address).await.is_ok()
     }
@@ -280,6 +389,7 @@ impl CliTestFramework {
             profile_options: Default::default(),
             account: Some(self.account_id(index)),
             query,
+            output_options: OutputOptions::json(),
         }
         .execute()
         .await
@@ -296,6 +406,7 @@ impl CliTestFramework {
             txn_options: self.transaction_options(sender_index, gas_options),
             account: self.account_id(receiver_index),
             amount,
+            output_options: OutputOptions::json(),
         }
         .execute()
         .await
@@ -359,14 +470,19 @@ impl CliTestFramework {
         .map(|v| (&v).into())
     }
 
-    pub async fn show_validator_stake(&self, pool_index: usize) -> CliTypedResult<Value> {
-        ShowValidatorStake {
+    pub async fn show_validator_stake(
+        &self,
+        pool_index: usize,
+    ) -> CliTypedResult<ValidatorStakeSummary> {
+        let result = ShowValidatorStake {
             rest_options: self.rest_options(),
             profile_options: Default::default(),
             operator_args: self.operator_args(Some(pool_index)),
+            output_options: OutputOptions::json(),
         }
         .execute()
-        .await
+        .await?;
+        serde_json::from_value(result).map_err(|err| CliError::UnexpectedError(err.to_string()))
     }
 
     pub async fn initialize_validator(
@@ -536,17 +652,18 @@ impl CliTestFramework {
     pub async fn init(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<()> {
         InitTool {
             network: Some(Network::Custom),
-            rest_url: Some(self.endpoint.clone()),
-            faucet_url: Some(self.faucet_endpoint.clone()),
+            rest_url: Some(self.endpoint()),
+            faucet_url: Some(self.faucet_endpoint()),
             faucet_auth_token: None,
             rng_args: RngArgs::from_seed([0; 32]),
             private_key_options: PrivateKeyInputOptions::from_private_key(private_key)?,
-            profile_options: Default::default(),
+            profile_options: self.profile_options(),
             prompt_options: PromptOptions::yes(),
             encoding_options: EncodingOptions::default(),
             skip_faucet: false,
             ledger: false,
             hardware_wallet_options: Default::default(),
+            multisig_address: None,
         }
         .execute()
         .await
@@ -656,13 +773,21 @@ impl CliTestFramework {
         result
     }
 
-    pub async fn account_balance_now(&self, index: usize) -> CliTypedResult<u64> {
+    /// Returns the (structured) balance of the account
+    pub async fn account_balance(&self, index: usize) -> CliTypedResult<AccountBalance> {
         let result = self.list_account(index, ListQuery::Balance).await?;
-        Ok(json_account_to_balance(result.first().unwrap()))
+        let balance = result.into_iter().next().ok_or_else(|| {
+            CliError::UnexpectedError(format!("No balance for account {}", self.account_id(index)))
+        })?;
+        serde_json::from_value(balance).map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+
+    pub async fn account_balance_now(&self, index: usize) -> CliTypedResult<u64> {
+        Ok(self.account_balance(index).await?.balance)
     }
 
     pub async fn assert_account_balance_now(&self, index: usize, expected: u64) {
-        let result = self.list_account(index, ListQuery::Balance).await;
+        let result = self.account_balance(index).await;
         assert!(
             result.is_ok(),
             "Account {} not yet created, {}, last 10 transactions: {}",
@@ -670,11 +795,9 @@ impl CliTestFramework {
             result.unwrap_err(),
             self.last_n_transactions_details(10).await
         );
-        let accounts = result.unwrap();
-        let account = accounts.first().unwrap();
-        let coin = json_account_to_balance(account);
+        let account = result.unwrap();
         assert_eq!(
-            coin,
+            account.balance,
             expected,
             "Account {} with state: {:?}, last 10 transactions: {}",
             self.account_id(index),
@@ -684,7 +807,7 @@ impl CliTestFramework {
     }
 
     async fn last_n_transactions_details(&self, count: u16) -> String {
-        let result = aptos_rest_client::Client::new(self.endpoint.clone())
+        let result = aptos_rest_client::Client::new(self.endpoint())
             .get_transactions(None, Some(count))
             .await;
         if let Err(e) = result {
@@ -716,6 +839,95 @@ impl CliTestFramework {
         format!("\n{}\n", lines.join("\n"))
     }
 
+    /// Fetches a committed transaction by hash, along with its (decoded) events
+    pub async fn transaction_events(
+        &self,
+        transaction_hash: HashValue,
+    ) -> CliTypedResult<TransactionEvents> {
+        let transaction = aptos_rest_client::Client::new(self.endpoint())
+            .get_transaction_by_hash(transaction_hash)
+            .await?
+            .into_inner();
+        match transaction {
+            Transaction::UserTransaction(txn) => Ok(TransactionEvents {
+                transaction_hash,
+                events: txn.events,
+            }),
+            transaction => Err(CliError::UnexpectedError(format!(
+                "Expected a committed user transaction for hash {}, found: {}",
+                transaction_hash,
+                transaction.type_str()
+            ))),
+        }
+    }
+
+    /// Fetches the most recent events of the given event stream of an account
+    pub async fn account_events(
+        &self,
+        address: AccountAddress,
+        event_stream: &AccountEventStream,
+    ) -> CliTypedResult<Vec<VersionedEvent>> {
+        let client = aptos_rest_client::Client::new(self.endpoint());
+        let events = match event_stream {
+            AccountEventStream::Handle {
+                struct_tag,
+                field_name,
+            } => {
+                client
+                    .get_account_events(address, struct_tag, field_name, None, None)
+                    .await?
+            },
+            AccountEventStream::CreationNumber(creation_number) => {
+                client
+                    .get_account_events_by_creation_number(address, *creation_number, None, None)
+                    .await?
+            },
+        };
+        Ok(events.into_inner())
+    }
+
+    /// Waits for an event of the given type to appear in the event stream of an account,
+    /// at (or after) the version of the given transaction
+    pub async fn wait_for_event(
+        &self,
+        address: AccountAddress,
+        event_stream: &AccountEventStream,
+        transaction_hash: HashValue,
+        event_type: &str,
+    ) -> CliTypedResult<VersionedEvent> {
+        let transaction_version = aptos_rest_client::Client::new(self.endpoint())
+            .get_transaction_by_hash(transaction_hash)
+            .await?
+            .into_inner()
+            .version()
+            .ok_or_else(|| {
+                CliError::UnexpectedError(format!(
+                    "Transaction {} is not committed",
+                    transaction_hash
+                ))
+            })?;
+
+        let start = Instant::now();
+        loop {
+            // The event stream may not exist until the first event is emitted
+            if let Ok(events) = self.account_events(address, event_stream).await {
+                let event = events.into_iter().find(|event| {
+                    event.version.0 >= transaction_version && event.typ.to_string() == event_type
+                });
+                if let Some(event) = event {
+                    return Ok(event);
+                }
+            }
+            if start.elapsed() >= Duration::from_secs(10) {
+                return Err(CliError::UnexpectedError(format!(
+                    "No {} event in stream {:?} of account {} after transaction {}",
+                    event_type, event_stream, address, transaction_hash
+                )));
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+    }
+
     pub async fn generate_x25519_key(
         &self,
         output_file: PathBuf,
@@ -855,6 +1067,66 @@ impl CliTestFramework {
         .await
     }
 
+    /// Runs the package's unit tests with coverage enabled, and returns the resulting
+    /// instruction coverage of each module. Build artifacts are written to a temporary
+    /// directory, so that repeated runs start from a clean build.
+    pub async fn coverage_package(
+        &self,
+        account_strs: BTreeMap<&str, &str>,
+        filter: Option<&str>,
+    ) -> CliTypedResult<PackageCoverage> {
+        let output_dir = TempPath::new();
+        output_dir
+            .create_as_dir()
+            .map_err(|err| CliError::IO(output_dir.path().display().to_string(), err))?;
+        let mut move_options = self.move_options(account_strs);
+        move_options.output_dir = Some(output_dir.path().to_path_buf());
+
+        TestPackage {
+            instruction_execution_bound: 100_000,
+            move_options: move_options.clone(),
+            filter: filter.map(|str| str.to_string()),
+            ignore_compile_warnings: false,
+            compute_coverage: true,
+            dump_state: false,
+        }
+        .execute()
+        .await?;
+
+        let module_summaries = SummaryCoverage {
+            summarize_functions: false,
+            output_csv: false,
+            filter: filter.map(|str| str.to_string()),
+            move_options,
+        }
+        .module_summaries()?;
+        Ok(PackageCoverage::from_module_summaries(&module_summaries))
+    }
+
+    /// Runs the Move prover on the package. Only the modules matching the filter (if any)
+    /// are verified. Requires the prover tools, see [`CliTestFramework::prover_tools_configured`].
+    pub async fn prove_package(
+        &self,
+        account_strs: BTreeMap<&str, &str>,
+        filter: Option<&str>,
+    ) -> CliTypedResult<&'static str> {
+        ProvePackage {
+            move_options: self.move_options(account_strs),
+            prover_options: ProverOptions {
+                filter: filter.map(|str| str.to_string()),
+                ..ProverOptions::default()
+            },
+        }
+        .execute()
+        .await
+    }
+
+    /// Returns true if the environment points to the tools required by the Move prover
+    pub fn prover_tools_configured() -> bool {
+        let is_set = |var: &str| std::env::var(var).map_or(false, |value| !value.is_empty());
+        is_set("BOOGIE_EXE") && is_set("Z3_EXE")
+    }
+
     pub async fn publish_package(
         &self,
         index: usize,
@@ -900,6 +1172,67 @@ impl CliTestFramework {
         args: Vec<&str>,
         type_args: Vec<&str>,
     ) -> CliTypedResult<TransactionSummary> {
+        RunFunction {
+            entry_function_args: Self::entry_function_args(function_id, args, type_args)?,
+            txn_options: self.transaction_options(index, gas_options),
+        }
+        .execute()
+        .await
+    }
+
+    /// Runs an entry function that is expected to fail execution, and returns the
+    /// committed (failed) transaction, looked up by its hash. Use the assertion
+    /// helpers (e.g., `assert_txn_aborts_with`) to verify why it failed.
+    pub async fn run_function_expecting_failure(
+        &self,
+        index: usize,
+        function_id: MemberId,
+        args: Vec<&str>,
+        type_args: Vec<&str>,
+    ) -> CliTypedResult<Transaction> {
+        let entry_function: EntryFunction =
+            Self::entry_function_args(function_id, args, type_args)?.try_into()?;
+
+        // Sign the transaction here (instead of running the command), so that its
+        // hash is known even though the submission fails
+        let client = aptos_rest_client::Client::new(self.endpoint());
+        let chain_id = ChainId::new(client.get_ledger_information().await?.into_inner().chain_id);
+        let gas_unit_price = client.estimate_gas_price().await?.into_inner().gas_estimate;
+        let sequence_number = client
+            .get_account(self.account_id(index))
+            .await?
+            .into_inner()
+            .sequence_number;
+        let transaction_factory = TransactionFactory::new(chain_id)
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(EXPECTED_FAILURE_MAX_GAS);
+        let transaction = LocalAccount::new(
+            self.account_id(index),
+            self.private_key(index).clone(),
+            sequence_number,
+        )
+        .sign_with_transaction_builder(
+            transaction_factory.payload(TransactionPayload::EntryFunction(entry_function)),
+        );
+        let transaction_hash = transaction.clone().committed_hash();
+
+        if client.submit_and_wait(&transaction).await.is_ok() {
+            return Err(CliError::UnexpectedError(format!(
+                "Expected transaction {} to fail, but it succeeded",
+                transaction_hash
+            )));
+        }
+        Ok(client
+            .get_transaction_by_hash(transaction_hash)
+            .await?
+            .into_inner())
+    }
+
+    fn entry_function_args(
+        function_id: MemberId,
+        args: Vec<&str>,
+        type_args: Vec<&str>,
+    ) -> CliTypedResult<EntryFunctionArguments> {
         let mut parsed_args = Vec::new();
         for arg in args {
             parsed_args.push(
@@ -916,19 +1249,14 @@ impl CliTestFramework {
             )
         }
 
-        RunFunction {
-            entry_function_args: EntryFunctionArguments {
-                function_id: Some(function_id),
-                arg_vec: ArgWithTypeVec { args: parsed_args },
-                type_arg_vec: TypeArgVec {
-                    type_args: parsed_type_args,
-                },
-                json_file: None,
+        Ok(EntryFunctionArguments {
+            function_id: Some(function_id),
+            arg_vec: ArgWithTypeVec { args: parsed_args },
+            type_arg_vec: TypeArgVec {
+                type_args: parsed_type_args,
             },
-            txn_options: self.transaction_options(index, gas_options),
-        }
-        .execute()
-        .await
+            json_file: None,
+        })
     }
 
     /// Runs the given script contents using the local aptos_framework directory.
@@ -1078,11 +1406,11 @@ impl CliTestFramework {
     }
 
     pub fn rest_options(&self) -> RestOptions {
-        RestOptions::new(Some(self.endpoint.clone()), None)
+        RestOptions::new(Some(self.endpoint()), None)
     }
 
     pub fn faucet_options(&self) -> FaucetOptions {
-        FaucetOptions::new(Some(self.faucet_endpoint.clone()), None)
+        FaucetOptions::new(Some(self.faucet_endpoint()), None)
     }
 
     fn transaction_options(
@@ -1205,28 +1533,167 @@ impl CliTestFramework {
         .execute()
         .await
     }
-}
 
-// ValidatorConfig/ValidatorSet doesn't match Move ValidatorSet struct,
-// and json is serialized with different types from both, so hardcoding deserialization.
+    /// Votes on a proposal with multiple stake pools, each given as the index of its
+    /// delegated voter and the address of the pool
+    pub async fn vote_with_stake_pools(
+        &self,
+        proposal_id: u64,
+        yes: bool,
+        voters: &[(usize, AccountAddress)],
+    ) -> CliTypedResult<Vec<TransactionSummary>> {
+        let mut summaries = vec![];
+        for (voter_index, pool_address) in voters {
+            let summary = SubmitVote {
+                pool_addresses: vec![*pool_address],
+                args: SubmitVoteArgs {
+                    proposal_id,
+                    yes,
+                    no: !yes,
+                    voting_power: None,
+                    txn_options: self.transaction_options(*voter_index, None),
+                },
+            }
+            .execute()
+            .await?;
+            summaries.extend(summary);
+        }
+        Ok(summaries)
+    }
 
-fn json_account_to_balance(value: &Value) -> u64 {
-    u64::from_str(
-        value
-            .as_object()
-            .unwrap()
-            .get("coin")
-            .unwrap()
-            .as_object()
-            .unwrap()
-            .get("value")
-            .unwrap()
+    async fn view_governance_proposal(
+        &self,
+        function: &str,
+        proposal_id: u64,
+    ) -> CliTypedResult<Value> {
+        let client = aptos_rest_client::Client::new(self.endpoint());
+        let response = client
+            .view(
+                &ViewRequest {
+                    function: function.parse().unwrap(),
+                    type_arguments: vec!["0x1::governance_proposal::GovernanceProposal"
+                        .parse()
+                        .unwrap()],
+                    arguments: vec![
+                        Value::String("0x1".to_string()),
+                        Value::String(proposal_id.to_string()),
+                    ],
+                },
+                None,
+            )
+            .await?;
+        response.into_inner().into_iter().next().ok_or_else(|| {
+            CliError::UnexpectedError(format!("Empty response from view function {}", function))
+        })
+    }
+
+    /// Returns the state of the proposal, one of the `PROPOSAL_STATE_*` constants
+    pub async fn proposal_state(&self, proposal_id: u64) -> CliTypedResult<u64> {
+        let state = self
+            .view_governance_proposal("0x1::voting::get_proposal_state", proposal_id)
+            .await?;
+        state
             .as_str()
-            .unwrap(),
-    )
-    .unwrap()
+            .and_then(|state| state.parse().ok())
+            .ok_or_else(|| CliError::UnexpectedError(format!("Invalid proposal state {}", state)))
+    }
+
+    /// Returns whether the proposal has been resolved (i.e. executed)
+    pub async fn is_proposal_resolved(&self, proposal_id: u64) -> CliTypedResult<bool> {
+        let resolved = self
+            .view_governance_proposal("0x1::voting::is_resolved", proposal_id)
+            .await?;
+        resolved.as_bool().ok_or_else(|| {
+            CliError::UnexpectedError(format!("Invalid proposal resolution {}", resolved))
+        })
+    }
+
+    /// Waits for the voting on the proposal to close (either because the voting period ended,
+    /// or because the early resolution threshold was reached), and returns the final state
+    pub async fn wait_for_proposal_voting_closed(
+        &self,
+        proposal_id: u64,
+        timeout: Duration,
+    ) -> CliTypedResult<u64> {
+        let start = Instant::now();
+        loop {
+            let state = self.proposal_state(proposal_id).await?;
+            if state != PROPOSAL_STATE_PENDING {
+                return Ok(state);
+            }
+            if start.elapsed() > timeout {
+                return Err(CliError::UnexpectedError(format!(
+                    "Voting on proposal {} did not close within {:?}",
+                    proposal_id, timeout
+                )));
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Executes the script of an approved proposal
+    pub async fn execute_proposal(
+        &self,
+        index: usize,
+        proposal_id: u64,
+        script_path: PathBuf,
+    ) -> CliTypedResult<TransactionSummary> {
+        ExecuteProposal {
+            proposal_id,
+            txn_options: self.transaction_options(index, None),
+            compile_proposal_args: CompileScriptFunction {
+                script_path: Some(script_path),
+                compiled_script_path: None,
+                framework_package_args: FrameworkPackageArgs {
+                    framework_git_rev: None,
+                    framework_local_dir: Some(Self::aptos_framework_dir()),
+                    skip_fetch_latest_git_deps: false,
+                },
+                bytecode_version: None,
+            },
+        }
+        .execute()
+        .await
+    }
+
+    /// Votes yes on the proposal with all the given stake pools, waits for the voting to
+    /// succeed, then executes the proposal script and verifies the proposal got resolved
+    pub async fn approve_and_execute_proposal(
+        &self,
+        executor_index: usize,
+        proposal_id: u64,
+        script_path: PathBuf,
+        voters: &[(usize, AccountAddress)],
+        voting_timeout: Duration,
+    ) -> CliTypedResult<TransactionSummary> {
+        self.vote_with_stake_pools(proposal_id, true, voters)
+            .await?;
+        let state = self
+            .wait_for_proposal_voting_closed(proposal_id, voting_timeout)
+            .await?;
+        if state != PROPOSAL_STATE_SUCCEEDED {
+            return Err(CliError::UnexpectedError(format!(
+                "Proposal {} did not succeed, state: {}",
+                proposal_id, state
+            )));
+        }
+
+        let summary = self
+            .execute_proposal(executor_index, proposal_id, script_path)
+            .await?;
+        if !self.is_proposal_resolved(proposal_id).await? {
+            return Err(CliError::UnexpectedError(format!(
+                "Proposal {} was executed but is not resolved",
+                proposal_id
+            )));
+        }
+        Ok(summary)
+    }
 }
 
+// ValidatorConfig/ValidatorSet doesn't match Move ValidatorSet struct,
+// and json is serialized with different types from both, so hardcoding deserialization.
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IndividualValidatorPerformance {
     successful_proposals: String,
@@ -1247,3 +1714,213 @@ impl IndividualValidatorPerformance {
 pub struct ValidatorPerformance {
     pub validators: Vec<IndividualValidatorPerformance>,
 }
+
+/// The instruction coverage of the modules of a package, as computed by a unit test run
+#[derive(Debug, Default)]
+pub struct PackageCoverage {
+    /// The number of covered and total instructions, keyed by module name
+    pub modules: BTreeMap<String, (u64, u64)>,
+}
+
+impl PackageCoverage {
+    pub fn from_module_summaries(module_summaries: &[ModuleSummary]) -> Self {
+        let modules = module_summaries
+            .iter()
+            .map(|summary| {
+                let (covered, total) = summary.function_summaries.values().fold(
+                    (0, 0),
+                    |(covered, total), function| {
+                        (covered + function.covered, total + function.total)
+                    },
+                );
+                (summary.module_name.name().to_string(), (covered, total))
+            })
+            .collect();
+        Self { modules }
+    }
+
+    /// Returns the coverage percentage of the given module, if it was part of the run
+    pub fn module_coverage(&self, module_name: &str) -> Option<f64> {
+        self.modules
+            .get(module_name)
+            .map(|(covered, total)| percentage(*covered, *total))
+    }
+
+    /// Returns the coverage percentage across all modules
+    pub fn total_coverage(&self) -> f64 {
+        let (covered, total) = self.modules.values().fold(
+            (0, 0),
+            |(covered, total), (module_covered, module_total)| {
+                (covered + module_covered, total + module_total)
+            },
+        );
+        percentage(covered, total)
+    }
+}
+
+fn percentage(covered: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        (covered as f64) / (total as f64) * 100.0
+    }
+}
+
+/// Identifies an event stream of an account
+#[derive(Clone, Debug)]
+pub enum AccountEventStream {
+    /// The event handle in the given field of a resource (e.g., `0x1::stake::StakePool`
+    /// and `add_stake_events`)
+    Handle {
+        struct_tag: String,
+        field_name: String,
+    },
+    /// The creation number of the event stream
+    CreationNumber(u64),
+}
+
+impl AccountEventStream {
+    pub fn handle(struct_tag: &str, field_name: &str) -> Self {
+        Self::Handle {
+            struct_tag: struct_tag.to_string(),
+            field_name: field_name.to_string(),
+        }
+    }
+}
+
+/// The events emitted by a committed transaction, with helpers to assert on them
+#[derive(Debug)]
+pub struct TransactionEvents {
+    pub transaction_hash: HashValue,
+    pub events: Vec<Event>,
+}
+
+impl TransactionEvents {
+    /// Returns all events of the given type (e.g., `0x1::coin::DepositEvent`)
+    pub fn events_of_type(&self, event_type: &str) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|event| event.typ.to_string() == event_type)
+            .collect()
+    }
+
+    /// Asserts that at least one event of the given type was emitted, and returns the first
+    pub fn assert_event_emitted(&self, event_type: &str) -> &Event {
+        match self.events_of_type(event_type).into_iter().next() {
+            Some(event) => event,
+            None => panic!(
+                "No event of type {} was emitted by transaction {}, events: {:?}",
+                event_type,
+                self.transaction_hash,
+                self.event_types()
+            ),
+        }
+    }
+
+    /// Asserts the number of events of the given type
+    pub fn assert_event_count(&self, event_type: &str, expected: usize) {
+        assert_eq!(
+            self.events_of_type(event_type).len(),
+            expected,
+            "Unexpected number of {} events emitted by transaction {}, events: {:?}",
+            event_type,
+            self.transaction_hash,
+            self.event_types()
+        );
+    }
+
+    /// Asserts that an event of the given type was emitted with the given field value.
+    /// Nested fields are separated by dots (e.g., `metadata.amount`). Note: values are
+    /// compared against the JSON representation of the event, so u64 values are strings.
+    pub fn assert_event_field(&self, event_type: &str, field_path: &str, expected: Value) {
+        let events = self.events_of_type(event_type);
+        assert!(
+            !events.is_empty(),
+            "No event of type {} was emitted by transaction {}, events: {:?}",
+            event_type,
+            self.transaction_hash,
+            self.event_types()
+        );
+
+        let values: Vec<_> = events
+            .iter()
+            .map(|event| get_event_field(event, field_path))
+            .collect();
+        assert!(
+            values.contains(&Some(&expected)),
+            "No {} event of transaction {} has field {} = {}, found values: {:?}",
+            event_type,
+            self.transaction_hash,
+            field_path,
+            expected,
+            values
+        );
+    }
+
+    fn event_types(&self) -> Vec<String> {
+        self.events
+            .iter()
+            .map(|event| event.typ.to_string())
+            .collect()
+    }
+}
+
+/// Returns the value of the (dot separated) field in the event data, if it exists
+pub fn get_event_field<'a>(event: &'a Event, field_path: &str) -> Option<&'a Value> {
+    field_path
+        .split('.')
+        .try_fold(&event.data, |value, field| value.get(field))
+}
+
+/// Asserts that the given transaction failed with the given VM status (as reported by
+/// the REST API, e.g., `Out of gas`)
+pub fn assert_txn_fails_with(transaction: &Transaction, expected_vm_status: &str) {
+    assert_txn_failed(transaction);
+    assert_eq!(
+        transaction.vm_status(),
+        expected_vm_status,
+        "Unexpected VM status of transaction {}",
+        transaction.version().unwrap_or(0),
+    );
+}
+
+/// Asserts that the given transaction aborted with the given Move abort code (e.g.,
+/// `0x10006` for `0x1::coin::EINSUFFICIENT_BALANCE`)
+pub fn assert_txn_aborts_with(transaction: &Transaction, move_abort_code: u64) {
+    assert_txn_failed(transaction);
+    let vm_status = transaction.vm_status();
+    assert_eq!(
+        parse_move_abort_code(&vm_status),
+        Some(move_abort_code),
+        "Transaction {} did not abort with code {:#x}, VM status: {}",
+        transaction.version().unwrap_or(0),
+        move_abort_code,
+        vm_status
+    );
+}
+
+fn assert_txn_failed(transaction: &Transaction) {
+    assert!(
+        !transaction.success(),
+        "Transaction {} was expected to fail, but it succeeded",
+        transaction.version().unwrap_or(0),
+    );
+}
+
+/// Returns the Move abort code of the given VM status (as explained by the REST API),
+/// e.g., `Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): ...`. Returns None
+/// if the VM status isn't a Move abort.
+pub fn parse_move_abort_code(vm_status: &str) -> Option<u64> {
+    let explanation = if let Some(code) = vm_status.strip_prefix("Move abort: code ") {
+        code // The abort happened in a script
+    } else {
+        let location_and_explanation = vm_status.strip_prefix("Move abort in ")?;
+        let (_location, explanation) = location_and_explanation.split_once(": ")?;
+        explanation
+    };
+    let code = match explanation.split_once("(0x") {
+        Some((_reason_name, code_and_description)) => code_and_description.split_once(')')?.0,
+        None => explanation.strip_prefix("0x")?,
+    };
+    u64::from_str_radix(code, 16).ok()
+}
@@ -2,10 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    common::types::{
+        AccountBalance, CliError, MultisigProfileConfig, ProfileConfig, ProfileSummary,
+    },
     move_tool::{ArgWithType, FunctionArgType},
+    test::{parse_move_abort_code, CliTestFramework, PackageCoverage},
     CliResult, Tool,
 };
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+use aptos_types::account_address::AccountAddress;
 use clap::Parser;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use move_coverage::summary::{FunctionSummary, ModuleSummary};
+use reqwest::Url;
+use serde_json::json;
 use std::str::FromStr;
 
 /// In order to ensure that there aren't duplicate input arguments for untested CLI commands,
@@ -119,6 +129,185 @@ async fn ensure_can_parse_args_with_urls() {
     );
 }
 
+/// Ensure coin stores are converted to structured balances (for `--output json`)
+#[test]
+fn ensure_can_parse_coin_store_balances() {
+    let account = AccountAddress::from_hex_literal("0xcafe").unwrap();
+    let coin_store_type = "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>";
+    let coin_store = json!({
+        "coin": { "value": "18446744073709551615" },
+        "frozen": false,
+    });
+    let balance = AccountBalance::from_coin_store(account, coin_store_type, &coin_store).unwrap();
+    assert_eq!(
+        balance,
+        AccountBalance {
+            account,
+            coin_type: "0x1::aptos_coin::AptosCoin".to_string(),
+            balance: u64::MAX,
+        }
+    );
+    assert_eq!(
+        serde_json::to_value(&balance).unwrap(),
+        json!({
+            "account": account.to_hex(),
+            "coin_type": "0x1::aptos_coin::AptosCoin",
+            "balance": u64::MAX,
+        })
+    );
+
+    // Verify malformed coin stores are rejected
+    let invalid_coin_store = json!({ "coin": { "value": 10 } });
+    assert!(
+        AccountBalance::from_coin_store(account, coin_store_type, &invalid_coin_store).is_err()
+    );
+    assert!(
+        AccountBalance::from_coin_store(account, "0x1::account::Account", &coin_store).is_err()
+    );
+}
+
+/// Ensure profiles only allow the operations their type supports
+#[test]
+fn ensure_profile_types_restrict_signing() {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let key_profile = ProfileConfig {
+        public_key: Some(private_key.public_key()),
+        private_key: Some(private_key),
+        account: Some(AccountAddress::from_hex_literal("0xcafe").unwrap()),
+        ..Default::default()
+    };
+    assert!(key_profile.ensure_can_sign(None).is_ok());
+    assert!(key_profile.ensure_has_private_key(None).is_ok());
+
+    // A hardware wallet can sign, but its private key can't be used directly
+    let ledger_profile = ProfileConfig {
+        account: Some(AccountAddress::from_hex_literal("0xcafe").unwrap()),
+        derivation_path: Some("m/44'/637'/0'/0'/0'".to_string()),
+        ..Default::default()
+    };
+    assert!(ledger_profile.ensure_can_sign(Some("ledger")).is_ok());
+    match ledger_profile.ensure_has_private_key(Some("ledger")) {
+        Err(CliError::CommandArgumentError(message)) => {
+            assert!(message.contains("Profile ledger is backed by a hardware wallet"))
+        },
+        result => panic!("Unexpected result: {:?}", result),
+    }
+
+    // A multisig profile can do neither
+    let multisig = MultisigProfileConfig {
+        owners: vec![AccountAddress::ONE, AccountAddress::TWO],
+        num_signatures_required: 2,
+    };
+    let multisig_profile = ProfileConfig {
+        account: Some(AccountAddress::from_hex_literal("0xbeef").unwrap()),
+        multisig: Some(multisig.clone()),
+        ..Default::default()
+    };
+    match multisig_profile.ensure_can_sign(None) {
+        Err(CliError::CommandArgumentError(message)) => {
+            assert!(message.contains("Profile default is for multisig account 0xbeef"))
+        },
+        result => panic!("Unexpected result: {:?}", result),
+    }
+    assert!(multisig_profile.ensure_has_private_key(None).is_err());
+
+    // The multisig metadata is kept in the config and shown in the summary
+    let config: ProfileConfig =
+        serde_yaml::from_str(&serde_yaml::to_string(&multisig_profile).unwrap()).unwrap();
+    assert_eq!(config.multisig, Some(multisig.clone()));
+    assert_eq!(ProfileSummary::from(&config).multisig, Some(multisig));
+    assert!(!serde_yaml::to_string(&key_profile)
+        .unwrap()
+        .contains("multisig"));
+}
+
+#[test]
+fn ensure_can_parse_move_abort_codes() {
+    // Aborts in modules (with and without abort info)
+    assert_eq!(
+        parse_move_abort_code(
+            "Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins to complete transaction"
+        ),
+        Some(0x10006)
+    );
+    assert_eq!(
+        parse_move_abort_code("Move abort in 0xcafe::message: 0x2a"),
+        Some(0x2a)
+    );
+
+    // Aborts in scripts
+    assert_eq!(parse_move_abort_code("Move abort: code 0x1"), Some(1));
+
+    // Other VM statuses
+    assert_eq!(parse_move_abort_code("Out of gas"), None);
+    assert_eq!(parse_move_abort_code("Executed successfully"), None);
+}
+
+#[test]
+fn ensure_can_summarize_package_coverage() {
+    let module_summary = |name: &str, functions: &[(&str, u64, u64)]| ModuleSummary {
+        module_name: ModuleId::new(AccountAddress::ONE, Identifier::new(name).unwrap()),
+        function_summaries: functions
+            .iter()
+            .map(|(function, covered, total)| {
+                (Identifier::new(*function).unwrap(), FunctionSummary {
+                    fn_is_native: false,
+                    total: *total,
+                    covered: *covered,
+                })
+            })
+            .collect(),
+    };
+    let coverage = PackageCoverage::from_module_summaries(&[
+        module_summary("message", &[("set_message", 6, 8), ("get_message", 2, 2)]),
+        module_summary("native", &[]),
+    ]);
+
+    assert_eq!(coverage.modules.get("message"), Some(&(8, 10)));
+    assert_eq!(coverage.module_coverage("message"), Some(80.0));
+    assert_eq!(coverage.module_coverage("native"), Some(0.0));
+    assert_eq!(coverage.module_coverage("missing"), None);
+    assert_eq!(coverage.total_coverage(), 80.0);
+    assert_eq!(PackageCoverage::default().total_coverage(), 0.0);
+}
+
+#[test]
+fn test_cli_test_framework_profiles() {
+    let mut cli = CliTestFramework::local_new(1);
+    let localnet = Url::parse("http://localhost").unwrap();
+    let devnet = Url::parse("https://fullnode.devnet.aptoslabs.com").unwrap();
+    let devnet_faucet = Url::parse("https://faucet.devnet.aptoslabs.com").unwrap();
+    cli.add_profile("devnet", devnet.clone(), devnet_faucet.clone());
+    assert_eq!(cli.profile_names(), vec!["default", "devnet"]);
+
+    // Commands run against the default profile, until another one is made active
+    assert_eq!(cli.active_profile(), "default");
+    assert_eq!(cli.rest_options().url, Some(localnet.clone()));
+    assert_eq!(cli.set_active_profile("devnet"), "default");
+    assert_eq!(cli.rest_options().url, Some(devnet.clone()));
+    assert_eq!(cli.faucet_endpoint(), devnet_faucet);
+    assert_eq!(cli.profile_options().profile, Some("devnet".to_string()));
+    assert_eq!(cli.set_active_profile("default"), "devnet");
+    assert_eq!(cli.endpoint(), localnet);
+
+    // The CLI config holds every profile, with the given account
+    let profiles = cli.cli_config(Some(0)).profiles.unwrap();
+    assert_eq!(profiles.len(), 2);
+    let devnet_profile = profiles.get("devnet").unwrap();
+    assert_eq!(devnet_profile.rest_url, Some(devnet.to_string()));
+    assert_eq!(devnet_profile.faucet_url, Some(devnet_faucet.to_string()));
+    assert_eq!(devnet_profile.account, Some(cli.account_id(0)));
+    assert!(cli.cli_config(None).profiles.unwrap()["default"]
+        .account
+        .is_none());
+}
+
+#[test]
+#[should_panic(expected = "Profile testnet doesn't exist")]
+fn test_cli_test_framework_unknown_profile() {
+    CliTestFramework::local_new(0).set_active_profile("testnet");
+}
+
 async fn assert_cmd_not_panic(args: &[&str]) {
     // When a command fails, it will have a panic in it due to an improperly setup command
     // thread 'main' panicked at 'Command propose: Argument names must be unique, but 'assume-yes' is
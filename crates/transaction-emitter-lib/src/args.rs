@@ -1,18 +1,20 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::emitter::failure_injection::MalformedTxnKind;
 use anyhow::{bail, format_err, Result};
 use aptos_config::keys::ConfigKey;
 use aptos_crypto::{ed25519::Ed25519PrivateKey, encoding_type::EncodingType};
 use aptos_sdk::types::chain_id::ChainId;
-use aptos_transaction_generator_lib::args::TransactionTypeArg;
-use clap::{ArgGroup, Parser};
+use aptos_transaction_generator_lib::args::{EntryFunctionArgs, TransactionTypeArg};
+use clap::{ArgGroup, Parser, ValueEnum};
 use serde::{Deserialize, Serialize};
 use std::{
     convert::TryFrom,
     fs::File,
     io::{BufRead, BufReader},
-    path::Path,
+    net::SocketAddr,
+    path::{Path, PathBuf},
 };
 use url::Url;
 
@@ -137,6 +139,9 @@ pub struct EmitArgs {
     )]
     pub transaction_type: Vec<TransactionTypeArg>,
 
+    #[clap(flatten)]
+    pub entry_function_args: EntryFunctionArgs,
+
     /// Number of copies of the modules that will be published,
     /// under separate accounts, creating independent contracts,
     /// removing contention.
@@ -167,6 +172,27 @@ pub struct EmitArgs {
     #[clap(long)]
     pub init_gas_price_multiplier: Option<u64>,
 
+    /// How to adjust the gas price of the submitted transactions (starting from --gas-price),
+    /// e.g. to keep the load test effective when the gas market of the network is active.
+    #[clap(long, value_enum, ignore_case = true)]
+    pub gas_pricing_strategy: Option<GasPricingStrategyArg>,
+
+    /// Percentile (between 0 and 1) of the gas prices of recent blocks to use, 0.9 if not set.
+    #[clap(long)]
+    pub gas_price_percentile: Option<f64>,
+
+    /// Number of recent blocks to compute the gas price percentile over, 10 if not set.
+    #[clap(long)]
+    pub gas_price_num_blocks: Option<u64>,
+
+    /// Multiplier applied to the gas price each time transactions expire, 1.5 if not set.
+    #[clap(long)]
+    pub gas_price_escalation_multiplier: Option<f64>,
+
+    /// Max gas price used by non-fixed gas pricing strategies, 10x --gas-price if not set.
+    #[clap(long)]
+    pub max_gas_price: Option<u64>,
+
     #[clap(long)]
     pub expected_max_txns: Option<u64>,
 
@@ -198,6 +224,50 @@ pub struct EmitArgs {
 
     #[clap(long)]
     pub coins_per_account_override: Option<u64>,
+
+    /// If set, the accounts stored in this (encrypted) file by a previous run are reused,
+    /// skipping minting and funding, and the accounts are stored into it at the end of the run.
+    /// If the file doesn't exist (or has too few accounts), new accounts are created.
+    #[clap(long, requires = "account_pool_passphrase")]
+    pub account_pool_file: Option<PathBuf>,
+
+    /// Passphrase used to encrypt the account pool file
+    #[clap(long, env)]
+    pub account_pool_passphrase: Option<String>,
+
+    /// If set, a JSON report with the stats per phase, across all phases and per worker
+    /// (including submission and commit latency percentiles and expiration rates)
+    /// is written to this file at the end of the run.
+    #[clap(long)]
+    pub report_json_file: Option<PathBuf>,
+
+    /// Fraction of generated transactions for which an additional, deliberately malformed
+    /// copy is submitted. Those are expected to be rejected, and are tracked separately.
+    #[clap(long)]
+    pub malformed_txn_fraction: Option<f64>,
+
+    /// Kinds of malformed transactions to inject (picked uniformly), all kinds if not set.
+    #[clap(long, value_enum, num_args = 1.., ignore_case = true)]
+    pub malformed_txn_kinds: Vec<MalformedTxnKind>,
+
+    /// If set, workers alternate between submitting transactions for this many seconds,
+    /// and pausing submission for --submission-pause-secs.
+    #[clap(long, requires = "submission_pause_secs")]
+    pub submission_burst_secs: Option<u64>,
+
+    #[clap(long, requires = "submission_burst_secs")]
+    pub submission_pause_secs: Option<u64>,
+}
+
+/// How to adjust the gas unit price of the submitted transactions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+pub enum GasPricingStrategyArg {
+    /// Always use --gas-price.
+    Fixed,
+    /// Use --gas-price-percentile of the gas prices in the last --gas-price-num-blocks blocks.
+    PercentileOfRecentBlocks,
+    /// Multiply the gas price by --gas-price-escalation-multiplier each time transactions expire.
+    EscalateOnTimeout,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Parser, Serialize)]
@@ -216,6 +286,50 @@ pub struct CreateAccountsArgs {
     pub account_minter_seed: Option<String>,
 }
 
+#[derive(Clone, Debug, Deserialize, Parser, Serialize)]
+pub struct CoordinatorArgs {
+    /// Address the coordinator listens on for worker requests.
+    #[clap(long, default_value = "0.0.0.0:9107")]
+    pub listen_address: SocketAddr,
+
+    /// Number of emitter workers. The load is split evenly across them,
+    /// and emission only starts once all of them have registered.
+    #[clap(long)]
+    pub num_workers: usize,
+
+    /// How long to wait for all the workers to register.
+    #[clap(long, default_value_t = 600)]
+    pub registration_timeout_secs: u64,
+
+    /// How long to wait for all the workers to report their stats, on top of the
+    /// emit duration (workers need to mint their accounts first).
+    #[clap(long, default_value_t = 1800)]
+    pub stats_timeout_secs: u64,
+
+    /// Delay between the load test start and the end of account minting, used for workers
+    /// when the emit args don't set `coordination_delay_between_instances`. Each worker
+    /// creates its own source account from the root account before minting.
+    #[clap(long, default_value_t = 300)]
+    pub coordination_delay_secs: u64,
+
+    /// Interval between the starts of consecutive workers, so that they don't all send
+    /// their transaction from the root account at the same time.
+    #[clap(long, default_value_t = 5)]
+    pub worker_start_interval_secs: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Parser, Serialize)]
+pub struct EmitWorkerArgs {
+    /// URL of the coordinator, e.g. `http://coordinator.mysite.com:9107`
+    #[clap(long)]
+    pub coordinator_url: Url,
+
+    /// How long to wait for the coordinator to be reachable and for
+    /// all the other workers to register.
+    #[clap(long, default_value_t = 600)]
+    pub coordinator_timeout_secs: u64,
+}
+
 fn parse_target(target: &str) -> Result<Url> {
     let mut url = Url::try_from(target).map_err(|e| {
         format_err!(
@@ -0,0 +1,483 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coordinated emission from multiple machines.
+//!
+//! A single coordinator waits for `num_workers` emitter processes to register over REST, hands
+//! each of them a share of the load (target TPS or mempool backlog) and its own account minter
+//! seed, so that the workers mint and use disjoint sets of accounts. Each worker moves its coins
+//! out of the shared root account into a source account of its own (see
+//! `coordination_delay_between_instances`), and the workers start at staggered times so that
+//! their transactions from the root account don't race on its sequence number. Once the workers
+//! are done, they report their stats back, and the coordinator aggregates them.
+
+use crate::{
+    args::{ClusterArgs, CoordinatorArgs, EmitArgs, EmitWorkerArgs},
+    emitter::{parse_seed, stats::TxnStats},
+    wrappers::emit_transactions,
+};
+use anyhow::{bail, Context, Result};
+use aptos_infallible::Mutex;
+use aptos_logger::{info, warn};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use warp::{http::StatusCode, Filter};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A worker registration. The worker id is chosen by the worker, so that a worker retrying
+/// its registration gets the same worker index back instead of taking up another slot.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WorkerRegistrationRequest {
+    pub worker_id: String,
+}
+
+/// The response to a worker registration.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WorkerRegistration {
+    pub worker_index: usize,
+    pub num_workers: usize,
+}
+
+/// The share of the work assigned to a worker.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkerAssignment {
+    /// How long to wait before starting to emit, so that workers start one after the other.
+    pub start_delay_secs: u64,
+    pub emit_args: EmitArgs,
+}
+
+struct CoordinatorState {
+    /// The assignment of each worker, indexed by worker index.
+    assignments: Vec<WorkerAssignment>,
+    /// The index of each registered worker, keyed by worker id.
+    registered: HashMap<String, usize>,
+    stats: Vec<Option<TxnStats>>,
+}
+
+impl CoordinatorState {
+    fn all_registered(&self) -> bool {
+        self.registered.len() == self.assignments.len()
+    }
+
+    /// Registers the worker, returning None if all the worker slots are already taken by
+    /// other workers. Registering the same worker again returns the same index.
+    fn register(&mut self, worker_id: String) -> Option<WorkerRegistration> {
+        let num_workers = self.assignments.len();
+        if let Some(worker_index) = self.registered.get(&worker_id) {
+            return Some(WorkerRegistration {
+                worker_index: *worker_index,
+                num_workers,
+            });
+        }
+        if self.all_registered() {
+            return None;
+        }
+        let worker_index = self.registered.len();
+        self.registered.insert(worker_id, worker_index);
+        info!(
+            "Registered worker {} out of {}",
+            worker_index + 1,
+            num_workers
+        );
+        Some(WorkerRegistration {
+            worker_index,
+            num_workers,
+        })
+    }
+
+    fn all_reported(&self) -> bool {
+        self.stats.iter().all(Option::is_some)
+    }
+}
+
+/// Runs the coordinator until all workers have reported their stats, and returns the
+/// stats aggregated across all workers.
+pub async fn coordinate_emit_transactions(
+    coordinator_args: &CoordinatorArgs,
+    emit_args: &EmitArgs,
+) -> Result<TxnStats> {
+    if coordinator_args.num_workers == 0 {
+        bail!("The number of workers must be positive");
+    }
+    let base_seed = match &emit_args.account_minter_seed {
+        Some(seed) => parse_seed(seed),
+        None => StdRng::from_entropy().gen(),
+    };
+    let assignments = create_worker_assignments(
+        emit_args,
+        coordinator_args.num_workers,
+        base_seed,
+        emit_args
+            .coordination_delay_between_instances
+            .unwrap_or(coordinator_args.coordination_delay_secs),
+        coordinator_args.worker_start_interval_secs,
+    );
+    let state = Arc::new(Mutex::new(CoordinatorState {
+        stats: vec![None; assignments.len()],
+        assignments,
+        registered: HashMap::new(),
+    }));
+
+    let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel::<()>();
+    let (address, server) = warp::serve(coordinator_routes(state.clone()))
+        .try_bind_with_graceful_shutdown(coordinator_args.listen_address, async {
+            shutdown_receiver.await.ok();
+        })
+        .context("Failed to bind the coordinator")?;
+    let server = tokio::spawn(server);
+    info!(
+        "Coordinator listening on {}, waiting for {} workers",
+        address, coordinator_args.num_workers
+    );
+
+    let result = wait_for_worker_stats(&state, coordinator_args, emit_args.duration).await;
+    let _ = shutdown_sender.send(());
+    let _ = server.await;
+    result
+}
+
+async fn wait_for_worker_stats(
+    state: &Mutex<CoordinatorState>,
+    coordinator_args: &CoordinatorArgs,
+    duration_secs: u64,
+) -> Result<TxnStats> {
+    let start_time = Instant::now();
+    let registration_timeout = Duration::from_secs(coordinator_args.registration_timeout_secs);
+    while !state.lock().all_registered() {
+        if start_time.elapsed() > registration_timeout {
+            bail!(
+                "Only {} out of {} workers registered within {}s",
+                state.lock().registered.len(),
+                coordinator_args.num_workers,
+                coordinator_args.registration_timeout_secs
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    info!("All workers registered, waiting for their stats");
+
+    let start_time = Instant::now();
+    let stats_timeout = Duration::from_secs(
+        duration_secs
+            + coordinator_args.stats_timeout_secs
+            + coordinator_args.worker_start_interval_secs * coordinator_args.num_workers as u64,
+    );
+    loop {
+        {
+            let state = state.lock();
+            if state.all_reported() {
+                let stats: Vec<_> = state.stats.iter().flatten().cloned().collect();
+                return Ok(aggregate_worker_stats(&stats));
+            }
+        }
+        if start_time.elapsed() > stats_timeout {
+            bail!(
+                "Not all workers reported their stats within {:?}",
+                stats_timeout
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+fn coordinator_routes(
+    state: Arc<Mutex<CoordinatorState>>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    // POST register
+    let register_state = state.clone();
+    let register = warp::path!("register")
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(move |request: WorkerRegistrationRequest| {
+            match register_state.lock().register(request.worker_id) {
+                Some(registration) => {
+                    warp::reply::with_status(warp::reply::json(&registration), StatusCode::OK)
+                },
+                None => warp::reply::with_status(
+                    warp::reply::json(&"All workers are already registered"),
+                    StatusCode::CONFLICT,
+                ),
+            }
+        });
+
+    // GET assignment/<worker_index> (null until all workers are registered)
+    let assignment_state = state.clone();
+    let assignment =
+        warp::path!("assignment" / usize)
+            .and(warp::get())
+            .map(move |worker_index: usize| {
+                let state = assignment_state.lock();
+                let assignment = state
+                    .assignments
+                    .get(worker_index)
+                    .filter(|_| state.all_registered());
+                warp::reply::json(&assignment)
+            });
+
+    // POST stats/<worker_index>
+    let stats = warp::path!("stats" / usize)
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(move |worker_index: usize, stats: TxnStats| {
+            let mut state = state.lock();
+            match state.stats.get_mut(worker_index) {
+                Some(worker_stats) => {
+                    info!("Worker {} reported stats: {}", worker_index, stats);
+                    *worker_stats = Some(stats);
+                    StatusCode::OK
+                },
+                None => StatusCode::NOT_FOUND,
+            }
+        });
+
+    register.or(assignment).or(stats)
+}
+
+/// Splits the load of `emit_args` across `num_workers` workers, and derives a distinct
+/// account minter seed for each of them from `base_seed`.
+///
+/// Workers start `start_interval_secs` apart, and each of them creates its own source account
+/// before minting (which a non-zero `coordination_delay_between_instances` enables). The delays
+/// shrink for later workers, so that all of them put load on the network at the same time.
+fn create_worker_assignments(
+    emit_args: &EmitArgs,
+    num_workers: usize,
+    base_seed: [u8; 32],
+    coordination_delay_secs: u64,
+    start_interval_secs: u64,
+) -> Vec<WorkerAssignment> {
+    let mut seed_rng = StdRng::from_seed(base_seed);
+    (0..num_workers)
+        .map(|worker_index| {
+            let seed: [u8; 32] = seed_rng.gen();
+            let start_delay_secs = start_interval_secs * worker_index as u64;
+            let remaining_workers = (num_workers - worker_index - 1) as u64;
+            WorkerAssignment {
+                start_delay_secs,
+                emit_args: EmitArgs {
+                    mempool_backlog: emit_args
+                        .mempool_backlog
+                        .map(|backlog| split_evenly(backlog, num_workers, worker_index)),
+                    target_tps: emit_args
+                        .target_tps
+                        .map(|tps| split_evenly(tps, num_workers, worker_index)),
+                    account_minter_seed: Some(format!("{:?}", seed)),
+                    coordination_delay_between_instances: Some(
+                        coordination_delay_secs.max(1) + start_interval_secs * remaining_workers,
+                    ),
+                    ..emit_args.clone()
+                },
+            }
+        })
+        .collect()
+}
+
+/// Returns the share of `total` for the given worker, giving the remainder to the first workers.
+fn split_evenly(total: usize, num_workers: usize, worker_index: usize) -> usize {
+    total / num_workers + usize::from(worker_index < total % num_workers)
+}
+
+/// Aggregates the stats of workers that emitted in parallel.
+fn aggregate_worker_stats(worker_stats: &[TxnStats]) -> TxnStats {
+    let mut total = TxnStats::default();
+    for stats in worker_stats {
+        let lasted = total.lasted.max(stats.lasted);
+        total = &total + stats;
+        total.lasted = lasted;
+    }
+    total
+}
+
+/// Registers with the coordinator, emits transactions with the assigned share of the load,
+/// and reports the resulting stats back to the coordinator.
+pub async fn emit_transactions_as_worker(
+    cluster_args: &ClusterArgs,
+    worker_args: &EmitWorkerArgs,
+) -> Result<TxnStats> {
+    let client = reqwest::Client::new();
+    let coordinator_url = &worker_args.coordinator_url;
+    // Retries reuse the id, so a registration whose response got lost isn't counted twice
+    let registration_request = WorkerRegistrationRequest {
+        worker_id: format!("{:x}", StdRng::from_entropy().gen::<u128>()),
+    };
+
+    let start_time = Instant::now();
+    let timeout = Duration::from_secs(worker_args.coordinator_timeout_secs);
+    let registration: WorkerRegistration = loop {
+        match client
+            .post(coordinator_url.join("register")?)
+            .json(&registration_request)
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => break response.json().await?,
+            Ok(response) => bail!("Failed to register with the coordinator: {:?}", response),
+            Err(e) if start_time.elapsed() < timeout => {
+                warn!("Coordinator is not reachable yet: {:?}", e);
+                tokio::time::sleep(POLL_INTERVAL).await;
+            },
+            Err(e) => return Err(e).context("Failed to reach the coordinator"),
+        }
+    };
+    let worker_index = registration.worker_index;
+    info!(
+        "Registered as worker {} out of {}, waiting for the other workers",
+        worker_index, registration.num_workers
+    );
+
+    let assignment_url = coordinator_url.join(&format!("assignment/{}", worker_index))?;
+    let assignment: WorkerAssignment = loop {
+        let assignment: Option<WorkerAssignment> = client
+            .get(assignment_url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(assignment) = assignment {
+            break assignment;
+        }
+        if start_time.elapsed() > timeout {
+            bail!("Not all workers registered with the coordinator in time");
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    };
+
+    if assignment.start_delay_secs > 0 {
+        info!(
+            "Waiting {}s for the previous workers to start",
+            assignment.start_delay_secs
+        );
+        tokio::time::sleep(Duration::from_secs(assignment.start_delay_secs)).await;
+    }
+    let stats = emit_transactions(cluster_args, &assignment.emit_args).await?;
+    client
+        .post(coordinator_url.join(&format!("stats/{}", worker_index))?)
+        .json(&stats)
+        .send()
+        .await?
+        .error_for_status()
+        .context("Failed to report stats to the coordinator")?;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_worker_emit_args() {
+        let emit_args = EmitArgs {
+            target_tps: Some(1003),
+            ..Default::default()
+        };
+        let assignments = create_worker_assignments(&emit_args, 4, [7; 32], 300, 5);
+        let worker_emit_args: Vec<_> = assignments
+            .iter()
+            .map(|assignment| assignment.emit_args.clone())
+            .collect();
+
+        let target_tps: Vec<_> = worker_emit_args
+            .iter()
+            .map(|args| args.target_tps.unwrap())
+            .collect();
+        assert_eq!(target_tps, vec![251, 251, 251, 250]);
+        assert!(worker_emit_args
+            .iter()
+            .all(|args| args.mempool_backlog.is_none()));
+
+        // Every worker mints its own accounts, and the seeds are deterministic
+        let seeds: Vec<_> = worker_emit_args
+            .iter()
+            .map(|args| parse_seed(args.account_minter_seed.as_ref().unwrap()))
+            .collect();
+        for (i, seed) in seeds.iter().enumerate() {
+            assert!(!seeds[i + 1..].contains(seed));
+        }
+        assert_eq!(
+            create_worker_assignments(&emit_args, 4, [7; 32], 300, 5)[0]
+                .emit_args
+                .account_minter_seed,
+            worker_emit_args[0].account_minter_seed
+        );
+
+        // Workers start one after the other, each with its own source account, and all of
+        // them start the load at the same time
+        let start_delays: Vec<_> = assignments
+            .iter()
+            .map(|assignment| assignment.start_delay_secs)
+            .collect();
+        assert_eq!(start_delays, vec![0, 5, 10, 15]);
+        for assignment in &assignments {
+            assert_eq!(
+                assignment.start_delay_secs
+                    + assignment
+                        .emit_args
+                        .coordination_delay_between_instances
+                        .unwrap(),
+                315
+            );
+        }
+
+        // A zero delay would make the workers share the root account
+        let assignments = create_worker_assignments(&emit_args, 1, [7; 32], 0, 5);
+        assert_eq!(
+            assignments[0]
+                .emit_args
+                .coordination_delay_between_instances,
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_worker_registration_is_idempotent() {
+        let assignments = create_worker_assignments(&EmitArgs::default(), 2, [7; 32], 300, 5);
+        let mut state = CoordinatorState {
+            stats: vec![None; assignments.len()],
+            assignments,
+            registered: HashMap::new(),
+        };
+
+        assert_eq!(state.register("a".to_string()).unwrap().worker_index, 0);
+        // A retry of the same worker gets the same index back
+        assert_eq!(state.register("a".to_string()).unwrap().worker_index, 0);
+        assert!(!state.all_registered());
+
+        let registration = state.register("b".to_string()).unwrap();
+        assert_eq!(registration.worker_index, 1);
+        assert_eq!(registration.num_workers, 2);
+        assert!(state.all_registered());
+
+        // Retries still work once all workers are registered, but new workers are rejected
+        assert_eq!(state.register("b".to_string()).unwrap().worker_index, 1);
+        assert!(state.register("c".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_aggregate_worker_stats() {
+        let worker_stats = vec![
+            TxnStats {
+                submitted: 10,
+                committed: 8,
+                lasted: Duration::from_secs(30),
+                ..Default::default()
+            },
+            TxnStats {
+                submitted: 20,
+                committed: 20,
+                lasted: Duration::from_secs(31),
+                ..Default::default()
+            },
+        ];
+        let total = aggregate_worker_stats(&worker_stats);
+        assert_eq!(total.submitted, 30);
+        assert_eq!(total.committed, 28);
+        assert_eq!(total.lasted, Duration::from_secs(31));
+    }
+}
@@ -68,19 +68,7 @@ impl<'t> AccountMinter<'t> {
         let expected_num_seed_accounts = (total_requested_accounts / 50)
             .clamp(1, (total_requested_accounts as f32).sqrt() as usize + 1);
         let num_accounts = total_requested_accounts - accounts.len(); // Only minting extra accounts
-        let coins_per_account = if let Some(val) = req.coins_per_account_override {
-            val
-        } else {
-            (req.expected_max_txns / total_requested_accounts as u64)
-                .checked_mul(SEND_AMOUNT + req.expected_gas_per_txn * req.gas_price)
-                .unwrap()
-                .checked_add(
-                    req.max_gas_per_txn * req.gas_price
-                        // for module publishing
-                        + 2 * req.max_gas_per_txn * req.gas_price * req.init_gas_price_multiplier,
-                )
-                .unwrap() // extra coins for secure to pay none zero gas price
-        };
+        let coins_per_account = coins_per_account(req, total_requested_accounts);
         let txn_factory = self.txn_factory.clone();
         let expected_children_per_seed_account =
             (num_accounts + expected_num_seed_accounts - 1) / expected_num_seed_accounts;
@@ -277,6 +265,93 @@ impl<'t> AccountMinter<'t> {
         Ok(accounts)
     }
 
+    /// Tops up the given (reused) accounts that have fewer coins than a newly
+    /// created account would get, by transferring the difference from the
+    /// source account.
+    pub async fn top_up_accounts(
+        &mut self,
+        txn_executor: &dyn ReliableTransactionSubmitter,
+        req: &EmitJobRequest,
+        accounts: &[LocalAccount],
+        max_submit_batch_size: usize,
+    ) -> Result<()> {
+        let coins_per_account = coins_per_account(req, accounts.len());
+        let balances = futures::stream::iter(
+            accounts
+                .iter()
+                .map(|account| txn_executor.get_account_balance(account.address())),
+        )
+        .buffered(CREATION_PARALLELISM)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+        let top_ups: Vec<_> = accounts
+            .iter()
+            .zip(balances)
+            .filter(|(_, balance)| *balance < coins_per_account)
+            .map(|(account, balance)| (account.address(), coins_per_account - balance))
+            .collect();
+        if top_ups.is_empty() {
+            info!(
+                "All {} reused accounts have at least {} coins",
+                accounts.len(),
+                coins_per_account
+            );
+            return Ok(());
+        }
+
+        let coins_for_source = top_ups
+            .iter()
+            .map(|(_, amount)| *amount)
+            .sum::<u64>()
+            .checked_add(
+                top_ups.len() as u64
+                    * req.max_gas_per_txn
+                    * req.gas_price
+                    * req.init_gas_price_multiplier,
+            )
+            .ok_or_else(|| format_err!("Coins needed to top up the accounts exceed u64"))?;
+        let balance = txn_executor
+            .get_account_balance(self.source_account.address())
+            .await?;
+        if balance < coins_for_source {
+            if !req.mint_to_root {
+                bail!(
+                    "Source ({}) doesn't have enough coins to top up the reused accounts, balance {} < needed {}",
+                    self.source_account.address(),
+                    balance,
+                    coins_for_source
+                );
+            }
+            self.mint_to_root(txn_executor, u64::MAX - balance - 1)
+                .await?;
+        }
+
+        info!(
+            "Topping up {} out of {} reused accounts to {} coins each",
+            top_ups.len(),
+            accounts.len(),
+            coins_per_account
+        );
+        for batch in top_ups.chunks(max_submit_batch_size) {
+            let txns: Vec<_> = batch
+                .iter()
+                .map(|(address, amount)| {
+                    self.source_account.sign_with_transaction_builder(
+                        self.txn_factory
+                            .payload(aptos_stdlib::aptos_account_transfer(*address, *amount)),
+                    )
+                })
+                .collect();
+            txn_executor
+                .execute_transactions(&txns)
+                .await
+                .context("Failed to top up the reused accounts")?;
+        }
+        Ok(())
+    }
+
     pub async fn mint_to_root(
         &mut self,
         txn_executor: &dyn ReliableTransactionSubmitter,
@@ -428,6 +503,24 @@ impl<'t> AccountMinter<'t> {
     }
 }
 
+/// The coins each account needs for the expected number of transactions
+/// (unless overridden), with extra for gas.
+fn coins_per_account(req: &EmitJobRequest, total_requested_accounts: usize) -> u64 {
+    if let Some(val) = req.coins_per_account_override {
+        val
+    } else {
+        (req.expected_max_txns / total_requested_accounts as u64)
+            .checked_mul(SEND_AMOUNT + req.expected_gas_per_txn * req.gas_price)
+            .unwrap()
+            .checked_add(
+                req.max_gas_per_txn * req.gas_price
+                    // for module publishing
+                    + 2 * req.max_gas_per_txn * req.gas_price * req.init_gas_price_multiplier,
+            )
+            .unwrap() // extra coins for secure to pay none zero gas price
+    }
+}
+
 /// Create `num_new_accounts` by transferring coins from `source_account`. Return Vec of created
 /// accounts
 async fn create_and_fund_new_accounts<R>(
@@ -541,3 +634,142 @@ pub fn prompt_yes(prompt: &str) -> bool {
     }
     result.unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_infallible::Mutex;
+    use aptos_sdk::{
+        bcs,
+        move_types::account_address::AccountAddress,
+        types::{chain_id::ChainId, transaction::TransactionPayload},
+    };
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+
+    /// Applies the transfers it executes to an in-memory set of balances
+    struct MockTransactionSubmitter {
+        balances: Mutex<HashMap<AccountAddress, u64>>,
+        transfers: Mutex<Vec<(AccountAddress, u64)>>,
+    }
+
+    impl MockTransactionSubmitter {
+        fn new(balances: HashMap<AccountAddress, u64>) -> Self {
+            Self {
+                balances: Mutex::new(balances),
+                transfers: Mutex::new(vec![]),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ReliableTransactionSubmitter for MockTransactionSubmitter {
+        async fn get_account_balance(&self, account_address: AccountAddress) -> Result<u64> {
+            Ok(*self.balances.lock().get(&account_address).unwrap_or(&0))
+        }
+
+        async fn query_sequence_number(&self, _account_address: AccountAddress) -> Result<u64> {
+            Ok(0)
+        }
+
+        async fn execute_transactions_with_counter(
+            &self,
+            txns: &[SignedTransaction],
+            _state: &CounterState,
+        ) -> Result<()> {
+            for txn in txns {
+                let entry_function = match txn.payload() {
+                    TransactionPayload::EntryFunction(entry_function) => entry_function,
+                    _ => bail!("Unexpected payload"),
+                };
+                let receiver: AccountAddress = bcs::from_bytes(&entry_function.args()[0])?;
+                let amount: u64 = bcs::from_bytes(&entry_function.args()[1])?;
+                let mut balances = self.balances.lock();
+                *balances.entry(txn.sender()).or_default() -= amount;
+                *balances.entry(receiver).or_default() += amount;
+                self.transfers.lock().push((receiver, amount));
+            }
+            Ok(())
+        }
+
+        fn create_counter_state(&self) -> CounterState {
+            unimplemented!()
+        }
+    }
+
+    fn create_account(rng: &mut StdRng) -> LocalAccount {
+        let account_key = AccountKey::generate(rng);
+        LocalAccount::new(
+            account_key.authentication_key().account_address(),
+            account_key,
+            0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_top_up_accounts() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let mut source_account = create_account(&mut rng);
+        let accounts: Vec<_> = (0..3).map(|_| create_account(&mut rng)).collect();
+        let txn_executor = MockTransactionSubmitter::new(HashMap::from([
+            (source_account.address(), 1_000_000_000),
+            (accounts[0].address(), 100),
+            (accounts[1].address(), 2_000),
+            (accounts[2].address(), 1_000),
+        ]));
+        let req = EmitJobRequest::default().coins_per_account_override(1_000);
+
+        let mut account_minter = AccountMinter::new(
+            &mut source_account,
+            TransactionFactory::new(ChainId::test()),
+            StdRng::from_seed([1; 32]),
+        );
+        account_minter
+            .top_up_accounts(&txn_executor, &req, &accounts, 2)
+            .await
+            .unwrap();
+
+        // Only the account below the coins per account is topped up
+        assert_eq!(*txn_executor.transfers.lock(), vec![(
+            accounts[0].address(),
+            900
+        )]);
+        for (account, expected_balance) in accounts.iter().zip([1_000, 2_000, 1_000]) {
+            assert_eq!(
+                txn_executor
+                    .get_account_balance(account.address())
+                    .await
+                    .unwrap(),
+                expected_balance
+            );
+        }
+
+        // Nothing is transferred once all the accounts are topped up
+        account_minter
+            .top_up_accounts(&txn_executor, &req, &accounts, 2)
+            .await
+            .unwrap();
+        assert_eq!(txn_executor.transfers.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_top_up_accounts_insufficient_funds() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let mut source_account = create_account(&mut rng);
+        let accounts: Vec<_> = (0..2).map(|_| create_account(&mut rng)).collect();
+        let txn_executor =
+            MockTransactionSubmitter::new(HashMap::from([(source_account.address(), 1_000)]));
+        let req = EmitJobRequest::default().coins_per_account_override(1_000);
+
+        let mut account_minter = AccountMinter::new(
+            &mut source_account,
+            TransactionFactory::new(ChainId::test()),
+            StdRng::from_seed([1; 32]),
+        );
+        assert!(account_minter
+            .top_up_accounts(&txn_executor, &req, &accounts, 2)
+            .await
+            .is_err());
+        assert!(txn_executor.transfers.lock().is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, format_err, Context, Result};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
+use aptos_sdk::{move_types::account_address::AccountAddress, types::LocalAccount};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+
+// The header of an account pool file. It is also authenticated (as additional
+// data) when encrypting the accounts, so that the format version can't be changed.
+const ACCOUNT_POOL_FILE_MAGIC: &[u8] = b"APTOS_EMITTER_ACCOUNT_POOL_V1";
+
+// Key derivation parameters for the passphrase
+const PBKDF2_ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+
+/// An account (key and sequence number) persisted in an account pool file
+#[derive(Debug, Deserialize, Serialize)]
+struct PersistedAccount {
+    address: AccountAddress,
+    private_key: String,
+    sequence_number: u64,
+}
+
+/// A file that holds the accounts generated by a previous emitter run,
+/// encrypted with a key derived from a passphrase. Reusing the accounts
+/// avoids minting and funding thousands of accounts on every run against
+/// long-lived networks.
+///
+/// File layout: magic | salt | nonce | AES-256-GCM(JSON encoded accounts).
+#[derive(Clone)]
+pub struct AccountPoolFile {
+    path: PathBuf,
+    passphrase: String,
+}
+
+impl AccountPoolFile {
+    pub fn new(path: PathBuf, passphrase: String) -> Self {
+        Self { path, passphrase }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Loads and decrypts the accounts stored in the file
+    pub fn load(&self) -> Result<Vec<LocalAccount>> {
+        let contents = fs::read(&self.path)
+            .with_context(|| format!("Failed to read account pool file {:?}", self.path))?;
+        let plaintext = decrypt(&contents, &self.passphrase)?;
+        let persisted_accounts: Vec<PersistedAccount> = serde_json::from_slice(&plaintext)?;
+
+        persisted_accounts
+            .into_iter()
+            .map(|account| {
+                let private_key = Ed25519PrivateKey::from_encoded_string(&account.private_key)
+                    .map_err(|e| format_err!("Invalid private key in account pool file: {}", e))?;
+                Ok(LocalAccount::new(
+                    account.address,
+                    private_key,
+                    account.sequence_number,
+                ))
+            })
+            .collect()
+    }
+
+    /// Encrypts and stores the given accounts in the file (overwriting it)
+    pub fn save(&self, accounts: &[LocalAccount]) -> Result<()> {
+        let persisted_accounts = accounts
+            .iter()
+            .map(|account| {
+                Ok(PersistedAccount {
+                    address: account.address(),
+                    private_key: account.private_key().to_encoded_string()?,
+                    sequence_number: account.sequence_number(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let plaintext = serde_json::to_vec(&persisted_accounts)?;
+        let contents = encrypt(&plaintext, &self.passphrase)?;
+
+        // Write to a temporary file first, so that an interrupted
+        // write doesn't destroy the previously saved accounts.
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, contents)
+            .with_context(|| format!("Failed to write account pool file {:?}", tmp_path))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to write account pool file {:?}", self.path))?;
+        Ok(())
+    }
+}
+
+// The passphrase is deliberately omitted
+impl fmt::Debug for AccountPoolFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccountPoolFile")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> LessSafeKey {
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).expect("key has the correct length"))
+}
+
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| format_err!("Failed to generate the salt and nonce"))?;
+
+    let mut ciphertext = plaintext.to_vec();
+    derive_key(passphrase, &salt)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(ACCOUNT_POOL_FILE_MAGIC),
+            &mut ciphertext,
+        )
+        .map_err(|_| format_err!("Failed to encrypt the account pool"))?;
+
+    let mut contents = ACCOUNT_POOL_FILE_MAGIC.to_vec();
+    contents.extend_from_slice(&salt);
+    contents.extend_from_slice(&nonce);
+    contents.extend_from_slice(&ciphertext);
+    Ok(contents)
+}
+
+fn decrypt(contents: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let header_len = ACCOUNT_POOL_FILE_MAGIC.len() + SALT_LEN + NONCE_LEN;
+    ensure!(
+        contents.len() >= header_len && contents.starts_with(ACCOUNT_POOL_FILE_MAGIC),
+        "Not a valid account pool file"
+    );
+    let (salt, rest) = contents[ACCOUNT_POOL_FILE_MAGIC.len()..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| format_err!("Invalid nonce in account pool file"))?;
+    let mut plaintext = ciphertext.to_vec();
+    let plaintext_len = match derive_key(passphrase, salt).open_in_place(
+        nonce,
+        Aad::from(ACCOUNT_POOL_FILE_MAGIC),
+        &mut plaintext,
+    ) {
+        Ok(plaintext) => plaintext.len(),
+        Err(_) => bail!("Failed to decrypt the account pool file (wrong passphrase?)"),
+    };
+    plaintext.truncate(plaintext_len);
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let plaintext = b"some accounts".to_vec();
+        let contents = encrypt(&plaintext, "passphrase").unwrap();
+        assert_eq!(decrypt(&contents, "passphrase").unwrap(), plaintext);
+
+        // A wrong passphrase or a tampered file can't be decrypted
+        assert!(decrypt(&contents, "wrong passphrase").is_err());
+        let mut tampered = contents.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&tampered, "passphrase").is_err());
+        assert!(decrypt(&contents[..10], "passphrase").is_err());
+    }
+}
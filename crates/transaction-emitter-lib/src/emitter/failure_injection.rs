@@ -0,0 +1,152 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_sdk::types::{
+    transaction::{RawTransaction, SignedTransaction},
+    LocalAccount,
+};
+use clap::ValueEnum;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Ways in which an injected transaction is made invalid. All of them are rejected
+/// on submission (by the API or mempool validation), so they never consume the
+/// sequence number of the sender.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, ValueEnum, Deserialize, Serialize)]
+pub enum MalformedTxnKind {
+    /// Sequence number that can never be valid (u64::MAX).
+    BadSequenceNumber,
+    /// Max gas amount below the minimum gas units required for any transaction.
+    InsufficientGas,
+    /// Expiration timestamp in the past.
+    Expired,
+}
+
+impl MalformedTxnKind {
+    /// Re-signs a copy of the given transaction (which needs to be sent by the given account),
+    /// made invalid in this way.
+    pub fn corrupt(&self, account: &LocalAccount, txn: &SignedTransaction) -> SignedTransaction {
+        assert_eq!(account.address(), txn.sender());
+        let mut sequence_number = txn.sequence_number();
+        let mut max_gas_amount = txn.max_gas_amount();
+        let mut expiration_timestamp_secs = txn.expiration_timestamp_secs();
+        match self {
+            MalformedTxnKind::BadSequenceNumber => sequence_number = u64::MAX,
+            MalformedTxnKind::InsufficientGas => max_gas_amount = 1,
+            MalformedTxnKind::Expired => expiration_timestamp_secs = 1,
+        }
+        account.sign_transaction(RawTransaction::new(
+            txn.sender(),
+            sequence_number,
+            txn.payload().clone(),
+            max_gas_amount,
+            txn.gas_unit_price(),
+            expiration_timestamp_secs,
+            txn.chain_id(),
+        ))
+    }
+}
+
+/// Alternates between submitting transactions for `active`, and not submitting
+/// anything for `paused`, starting with the active part.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmissionBursts {
+    pub active: Duration,
+    pub paused: Duration,
+}
+
+impl SubmissionBursts {
+    /// If submission is paused at the given time since the start of the job,
+    /// returns how long until it resumes.
+    pub fn paused_for(&self, elapsed: Duration) -> Option<Duration> {
+        let period = (self.active + self.paused).as_millis();
+        if period == 0 {
+            return None;
+        }
+        let offset = elapsed.as_millis() % period;
+        if offset < self.active.as_millis() {
+            None
+        } else {
+            Some(Duration::from_millis((period - offset) as u64))
+        }
+    }
+}
+
+/// Deliberate failures injected by the submission workers, to load test how
+/// mempool and consensus handle invalid traffic and bursty load.
+#[derive(Clone, Debug, Default)]
+pub struct FailureInjection {
+    /// Fraction of generated transactions for which an additional, malformed copy is
+    /// submitted alongside. Malformed copies are not counted as submitted, and are
+    /// tracked in separate stats instead.
+    pub malformed_txn_fraction: f64,
+    /// Kinds of malformed transactions to pick from (uniformly).
+    pub malformed_txn_kinds: Vec<MalformedTxnKind>,
+    pub bursts: Option<SubmissionBursts>,
+}
+
+impl FailureInjection {
+    pub fn injects_malformed_txns(&self) -> bool {
+        self.malformed_txn_fraction > 0.0 && !self.malformed_txn_kinds.is_empty()
+    }
+
+    /// Picks the kind of malformed copy to inject for a generated transaction, if any.
+    pub fn pick_malformed_kind<R: Rng>(&self, rng: &mut R) -> Option<MalformedTxnKind> {
+        if self.injects_malformed_txns() && rng.gen_bool(self.malformed_txn_fraction.min(1.0)) {
+            self.malformed_txn_kinds.choose(rng).copied()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::{
+        move_types::account_address::AccountAddress, transaction_builder::TransactionFactory,
+        types::chain_id::ChainId,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_paused_for() {
+        let bursts = SubmissionBursts {
+            active: Duration::from_secs(10),
+            paused: Duration::from_secs(5),
+        };
+        assert_eq!(bursts.paused_for(Duration::from_secs(0)), None);
+        assert_eq!(bursts.paused_for(Duration::from_secs(9)), None);
+        assert_eq!(
+            bursts.paused_for(Duration::from_secs(10)),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            bursts.paused_for(Duration::from_secs(13)),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(bursts.paused_for(Duration::from_secs(15)), None);
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let account = LocalAccount::generate(&mut rng);
+        let txn = account.sign_with_transaction_builder(
+            TransactionFactory::new(ChainId::test()).transfer(AccountAddress::random(), 1),
+        );
+
+        let bad_seq_num = MalformedTxnKind::BadSequenceNumber.corrupt(&account, &txn);
+        assert_eq!(bad_seq_num.sequence_number(), u64::MAX);
+        assert_eq!(bad_seq_num.payload(), txn.payload());
+
+        let insufficient_gas = MalformedTxnKind::InsufficientGas.corrupt(&account, &txn);
+        assert_eq!(insufficient_gas.sequence_number(), txn.sequence_number());
+        assert_eq!(insufficient_gas.max_gas_amount(), 1);
+
+        let expired = MalformedTxnKind::Expired.corrupt(&account, &txn);
+        assert_eq!(expired.expiration_timestamp_secs(), 1);
+        assert!(expired.verify_signature().is_ok());
+    }
+}
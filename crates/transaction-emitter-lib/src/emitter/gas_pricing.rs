@@ -0,0 +1,285 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_logger::{info, sample, sample::SampleRate, warn};
+use aptos_rest_client::Client as RestClient;
+use aptos_sdk::types::{
+    transaction::{RawTransaction, SignedTransaction},
+    LocalAccount,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// How the submission workers pick the gas unit price of the transactions they submit,
+/// starting from the gas price of the EmitJobRequest. Non-fixed strategies never go
+/// below that gas price, nor above their max gas price.
+///
+/// Note that accounts are funded based on the gas price of the EmitJobRequest, so with
+/// a non-fixed strategy, they might need to be funded with more coins (e.g. through
+/// coins_per_account_override).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum GasPricingStrategy {
+    /// Always use the gas price of the EmitJobRequest.
+    #[default]
+    Fixed,
+    /// Use a percentile (between 0 and 1) of the gas unit prices of the user transactions
+    /// committed in the most recent blocks, refreshed periodically.
+    PercentileOfRecentBlocks {
+        percentile: f64,
+        num_blocks: u64,
+        refresh_interval: Duration,
+        max_gas_price: u64,
+    },
+    /// Multiply the gas price each time transactions of a worker expire, and step it back
+    /// down each time all of them are committed.
+    EscalateOnTimeout { multiplier: f64, max_gas_price: u64 },
+}
+
+/// Picks the gas unit price of the transactions of a single submission worker.
+#[derive(Debug)]
+pub struct GasPricer {
+    strategy: GasPricingStrategy,
+    base_gas_price: u64,
+    // Gas price of the escalate-on-timeout strategy
+    escalated_gas_price: u64,
+    // Gas price of the percentile-of-recent-blocks strategy, shared across workers
+    recent_blocks_gas_price: Arc<AtomicU64>,
+}
+
+impl GasPricer {
+    pub fn new(
+        strategy: GasPricingStrategy,
+        base_gas_price: u64,
+        recent_blocks_gas_price: Arc<AtomicU64>,
+    ) -> Self {
+        Self {
+            strategy,
+            base_gas_price,
+            escalated_gas_price: base_gas_price,
+            recent_blocks_gas_price,
+        }
+    }
+
+    /// Returns the gas unit price to use for the next transactions.
+    pub fn gas_price(&self) -> u64 {
+        match &self.strategy {
+            GasPricingStrategy::Fixed => self.base_gas_price,
+            GasPricingStrategy::PercentileOfRecentBlocks { max_gas_price, .. } => self
+                .recent_blocks_gas_price
+                .load(Ordering::Relaxed)
+                .min(*max_gas_price)
+                .max(self.base_gas_price),
+            GasPricingStrategy::EscalateOnTimeout { .. } => self.escalated_gas_price,
+        }
+    }
+
+    /// Updates the gas price once the transactions submitted in a round are either committed
+    /// or expired.
+    pub fn record_round(&mut self, num_committed: usize, num_expired: usize) {
+        if let GasPricingStrategy::EscalateOnTimeout {
+            multiplier,
+            max_gas_price,
+        } = self.strategy
+        {
+            let previous_gas_price = self.escalated_gas_price;
+            if num_expired > 0 {
+                self.escalated_gas_price = ((previous_gas_price as f64 * multiplier).ceil() as u64)
+                    .min(max_gas_price)
+                    .max(previous_gas_price);
+            } else if num_committed > 0 {
+                self.escalated_gas_price = ((previous_gas_price as f64 / multiplier) as u64)
+                    .max(self.base_gas_price)
+                    .min(previous_gas_price);
+            }
+            if self.escalated_gas_price != previous_gas_price {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    info!(
+                        "txn_emitter worker changed gas price from {} to {}, after {} expired txns",
+                        previous_gas_price, self.escalated_gas_price, num_expired,
+                    )
+                );
+            }
+        }
+    }
+}
+
+/// Re-signs a copy of the given transaction (which needs to be sent by the given account),
+/// with the given gas unit price.
+pub fn with_gas_unit_price(
+    account: &LocalAccount,
+    txn: &SignedTransaction,
+    gas_unit_price: u64,
+) -> SignedTransaction {
+    assert_eq!(account.address(), txn.sender());
+    account.sign_transaction(RawTransaction::new(
+        txn.sender(),
+        txn.sequence_number(),
+        txn.payload().clone(),
+        txn.max_gas_amount(),
+        gas_unit_price,
+        txn.expiration_timestamp_secs(),
+        txn.chain_id(),
+    ))
+}
+
+/// Periodically sets the given gas price to a percentile of the gas prices of the user
+/// transactions in the most recent blocks, until the job is stopped. The gas price is
+/// kept as is if it fails to be refreshed.
+pub async fn run_recent_blocks_gas_price_refresher(
+    client: RestClient,
+    percentile: f64,
+    num_blocks: u64,
+    refresh_interval: Duration,
+    gas_price: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        match fetch_recent_blocks_gas_price(&client, percentile, num_blocks).await {
+            Ok(Some(recent_gas_price)) => gas_price.store(recent_gas_price, Ordering::Relaxed),
+            Ok(None) => (),
+            Err(e) => {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    warn!(
+                        "[{:?}] Failed to fetch gas prices of recent blocks: {:?}",
+                        client.path_prefix_string(),
+                        e
+                    )
+                );
+            },
+        }
+        tokio::time::sleep(refresh_interval).await;
+    }
+}
+
+/// Returns the percentile of the gas prices of the user transactions in the most recent
+/// blocks, if there are any.
+async fn fetch_recent_blocks_gas_price(
+    client: &RestClient,
+    percentile: f64,
+    num_blocks: u64,
+) -> Result<Option<u64>> {
+    let block_height = client
+        .get_ledger_information()
+        .await?
+        .into_inner()
+        .block_height;
+    let mut gas_prices = vec![];
+    for height in block_height.saturating_sub(num_blocks.saturating_sub(1))..=block_height {
+        let block = client
+            .get_block_by_height_bcs(height, true)
+            .await?
+            .into_inner();
+        gas_prices.extend(
+            block
+                .transactions
+                .into_iter()
+                .flatten()
+                .filter_map(|txn| Some(txn.transaction.try_as_signed_user_txn()?.gas_unit_price())),
+        );
+    }
+    Ok(gas_price_percentile(gas_prices, percentile))
+}
+
+/// Returns the percentile (between 0 and 1) of the given gas prices, if there are any.
+fn gas_price_percentile(mut gas_prices: Vec<u64>, percentile: f64) -> Option<u64> {
+    if gas_prices.is_empty() {
+        return None;
+    }
+    gas_prices.sort_unstable();
+    let index = ((gas_prices.len() - 1) as f64 * percentile.clamp(0.0, 1.0)).round() as usize;
+    Some(gas_prices[index])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::{
+        move_types::account_address::AccountAddress, transaction_builder::TransactionFactory,
+        types::chain_id::ChainId,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_gas_price_percentile() {
+        assert_eq!(gas_price_percentile(vec![], 0.5), None);
+        assert_eq!(gas_price_percentile(vec![300, 100, 200], 0.0), Some(100));
+        assert_eq!(gas_price_percentile(vec![300, 100, 200], 0.5), Some(200));
+        assert_eq!(gas_price_percentile(vec![300, 100, 200], 1.0), Some(300));
+        assert_eq!(gas_price_percentile((1..=100).collect(), 0.9), Some(90));
+    }
+
+    #[test]
+    fn test_escalate_on_timeout() {
+        let mut gas_pricer = GasPricer::new(
+            GasPricingStrategy::EscalateOnTimeout {
+                multiplier: 2.0,
+                max_gas_price: 500,
+            },
+            100,
+            Arc::new(AtomicU64::new(0)),
+        );
+        assert_eq!(gas_pricer.gas_price(), 100);
+
+        // Escalate on expiration, up to the max gas price
+        gas_pricer.record_round(5, 1);
+        assert_eq!(gas_pricer.gas_price(), 200);
+        gas_pricer.record_round(0, 10);
+        assert_eq!(gas_pricer.gas_price(), 400);
+        gas_pricer.record_round(0, 10);
+        assert_eq!(gas_pricer.gas_price(), 500);
+
+        // Step back down once all transactions commit, down to the base gas price
+        gas_pricer.record_round(10, 0);
+        assert_eq!(gas_pricer.gas_price(), 250);
+        gas_pricer.record_round(0, 0);
+        assert_eq!(gas_pricer.gas_price(), 250);
+        gas_pricer.record_round(10, 0);
+        gas_pricer.record_round(10, 0);
+        assert_eq!(gas_pricer.gas_price(), 100);
+    }
+
+    #[test]
+    fn test_percentile_of_recent_blocks() {
+        let recent_blocks_gas_price = Arc::new(AtomicU64::new(0));
+        let gas_pricer = GasPricer::new(
+            GasPricingStrategy::PercentileOfRecentBlocks {
+                percentile: 0.9,
+                num_blocks: 10,
+                refresh_interval: Duration::from_secs(10),
+                max_gas_price: 1000,
+            },
+            100,
+            recent_blocks_gas_price.clone(),
+        );
+
+        // Never below the base gas price, nor above the max gas price
+        assert_eq!(gas_pricer.gas_price(), 100);
+        recent_blocks_gas_price.store(300, Ordering::Relaxed);
+        assert_eq!(gas_pricer.gas_price(), 300);
+        recent_blocks_gas_price.store(5000, Ordering::Relaxed);
+        assert_eq!(gas_pricer.gas_price(), 1000);
+    }
+
+    #[test]
+    fn test_with_gas_unit_price() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let account = LocalAccount::generate(&mut rng);
+        let txn = account.sign_with_transaction_builder(
+            TransactionFactory::new(ChainId::test()).transfer(AccountAddress::random(), 1),
+        );
+
+        let repriced = with_gas_unit_price(&account, &txn, txn.gas_unit_price() * 3);
+        assert_eq!(repriced.gas_unit_price(), txn.gas_unit_price() * 3);
+        assert_eq!(repriced.sequence_number(), txn.sequence_number());
+        assert_eq!(repriced.payload(), txn.payload());
+        assert!(repriced.verify_signature().is_ok());
+    }
+}
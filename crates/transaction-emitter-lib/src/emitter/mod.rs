@@ -2,13 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod account_minter;
+pub mod account_pool;
+pub mod failure_injection;
+pub mod gas_pricing;
 pub mod stats;
 pub mod submission_worker;
 pub mod transaction_executor;
 
 use crate::emitter::{
     account_minter::AccountMinter,
-    stats::{DynamicStatsTracking, TxnStats},
+    account_pool::AccountPoolFile,
+    failure_injection::FailureInjection,
+    gas_pricing::{run_recent_blocks_gas_price_refresher, GasPricer, GasPricingStrategy},
+    stats::{DynamicStatsTracking, EmitReport, StatsAccumulator, TxnStats, WorkerStatsSummary},
     submission_worker::SubmissionWorker,
     transaction_executor::RestApiReliableTransactionSubmitter,
 };
@@ -25,14 +31,19 @@ use aptos_sdk::{
 use aptos_transaction_generator_lib::{create_txn_generator_creator, TransactionType};
 use futures::future::{try_join_all, FutureExt};
 use once_cell::sync::Lazy;
-use rand::{rngs::StdRng, seq::IteratorRandom, Rng};
+use rand::{
+    rngs::StdRng,
+    seq::{IteratorRandom, SliceRandom},
+    Rng,
+};
 use rand_core::SeedableRng;
 use std::{
     cmp::{max, min},
     collections::{HashMap, HashSet},
+    path::PathBuf,
     str::FromStr,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
     time::{Duration, Instant},
@@ -44,6 +55,11 @@ const MAX_TXNS: u64 = 1_000_000_000;
 
 const MAX_RETRIES: usize = 12;
 
+// Per worker stats use coarser histograms (1s buckets, up to 240s) than the
+// aggregate ones, to keep memory usage reasonable with many workers.
+const PER_WORKER_HISTOGRAM_CAPACITY: usize = 240;
+const PER_WORKER_HISTOGRAM_STEP_WIDTH: u64 = 1000;
+
 // This retry policy is used for important client calls necessary for setting
 // up the test (e.g. account creation) and collecting its results (e.g. checking
 // account sequence numbers). If these fail, the whole test fails. We do not use
@@ -125,6 +141,7 @@ pub struct EmitJobRequest {
     max_gas_per_txn: u64,
     gas_price: u64,
     init_gas_price_multiplier: u64,
+    gas_pricing_strategy: GasPricingStrategy,
 
     mint_to_root: bool,
 
@@ -145,6 +162,11 @@ pub struct EmitJobRequest {
 
     account_minter_seed: Option<[u8; 32]>,
     coins_per_account_override: Option<u64>,
+    account_pool_file: Option<AccountPoolFile>,
+
+    per_worker_stats: bool,
+
+    failure_injection: FailureInjection,
 }
 
 impl Default for EmitJobRequest {
@@ -158,6 +180,7 @@ impl Default for EmitJobRequest {
             max_gas_per_txn: aptos_global_constants::MAX_GAS_AMOUNT,
             gas_price: aptos_global_constants::GAS_UNIT_PRICE,
             init_gas_price_multiplier: 10,
+            gas_pricing_strategy: GasPricingStrategy::Fixed,
             mint_to_root: false,
             txn_expiration_time_secs: 60,
             init_expiration_multiplier: 3.0,
@@ -170,6 +193,9 @@ impl Default for EmitJobRequest {
             latency_polling_interval: Duration::from_millis(300),
             account_minter_seed: None,
             coins_per_account_override: None,
+            account_pool_file: None,
+            per_worker_stats: false,
+            failure_injection: FailureInjection::default(),
         }
     }
 }
@@ -199,6 +225,20 @@ impl EmitJobRequest {
         self
     }
 
+    /// How to adjust the gas price (set via gas_price) of the transactions submitted
+    /// by the workers, e.g. to keep up when the gas market of the network is active.
+    pub fn gas_pricing_strategy(mut self, gas_pricing_strategy: GasPricingStrategy) -> Self {
+        if let GasPricingStrategy::EscalateOnTimeout { multiplier, .. } = gas_pricing_strategy {
+            assert!(
+                multiplier >= 1.0,
+                "Gas price escalation multiplier ({}) needs to be at least 1",
+                multiplier,
+            );
+        }
+        self.gas_pricing_strategy = gas_pricing_strategy;
+        self
+    }
+
     pub fn expected_max_txns(mut self, expected_max_txns: u64) -> Self {
         self.expected_max_txns = expected_max_txns;
         self
@@ -274,11 +314,36 @@ impl EmitJobRequest {
         self
     }
 
+    /// Reuse the accounts stored in the given (encrypted) file, instead of minting
+    /// new ones, and store the accounts back into the file when the job is stopped.
+    pub fn account_pool_file(mut self, path: PathBuf, passphrase: String) -> Self {
+        self.account_pool_file = Some(AccountPoolFile::new(path, passphrase));
+        self
+    }
+
     pub fn set_mint_to_root(mut self) -> Self {
         self.mint_to_root = true;
         self
     }
 
+    /// Whether to also collect stats for each worker (across all phases), for the EmitReport.
+    pub fn per_worker_stats(mut self, per_worker_stats: bool) -> Self {
+        self.per_worker_stats = per_worker_stats;
+        self
+    }
+
+    /// Deliberately submit malformed transactions and/or pause submission in bursts,
+    /// to load test how mempool and consensus handle them.
+    pub fn failure_injection(mut self, failure_injection: FailureInjection) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&failure_injection.malformed_txn_fraction),
+            "Malformed txn fraction ({}) needs to be between 0 and 1",
+            failure_injection.malformed_txn_fraction,
+        );
+        self.failure_injection = failure_injection;
+        self
+    }
+
     pub fn calculate_mode_params(&self) -> EmitModeParams {
         let clients_count = self.rest_clients.len();
 
@@ -473,6 +538,8 @@ impl EmitModeParams {
 #[derive(Debug)]
 struct Worker {
     join_handle: JoinHandle<Vec<LocalAccount>>,
+    endpoint: String,
+    stats: Option<Arc<StatsAccumulator>>,
 }
 
 #[derive(Debug)]
@@ -481,6 +548,9 @@ pub struct EmitJob {
     stop: Arc<AtomicBool>,
     stats: Arc<DynamicStatsTracking>,
     phase_starts: Vec<Instant>,
+    // Accounts not handed to any worker, which are stored back into the account pool file
+    idle_accounts: Vec<LocalAccount>,
+    account_pool_file: Option<AccountPoolFile>,
 }
 
 impl EmitJob {
@@ -495,18 +565,56 @@ impl EmitJob {
         self.stats.get_cur_phase()
     }
 
-    pub async fn stop_and_accumulate(self) -> Vec<TxnStats> {
+    async fn stop_workers(&mut self) {
         self.stop.store(true, Ordering::Relaxed);
-        for worker in self.workers {
-            let _accounts = worker
-                .join_handle
-                .await
-                .expect("TxnEmitter worker thread failed");
+        let mut accounts = std::mem::take(&mut self.idle_accounts);
+        for worker in self.workers.iter_mut() {
+            accounts.extend(
+                (&mut worker.join_handle)
+                    .await
+                    .expect("TxnEmitter worker thread failed"),
+            );
         }
 
+        if let Some(account_pool_file) = &self.account_pool_file {
+            match account_pool_file.save(&accounts) {
+                Ok(()) => info!(
+                    "Stored {} accounts into the account pool file {:?}",
+                    accounts.len(),
+                    account_pool_file.path()
+                ),
+                Err(e) => error!("Failed to store the account pool: {:?}", e),
+            }
+        }
+    }
+
+    pub async fn stop_and_accumulate(mut self) -> Vec<TxnStats> {
+        self.stop_workers().await;
         self.stats.accumulate(&self.phase_starts)
     }
 
+    /// Stops the job, and returns the stats per phase, together with a machine-readable
+    /// report that also includes per worker stats (if requested in the EmitJobRequest).
+    pub async fn stop_and_report(mut self) -> (Vec<TxnStats>, EmitReport) {
+        self.stop_workers().await;
+        let phase_stats = self.stats.accumulate(&self.phase_starts);
+        let lasted = self.phase_starts[0].elapsed();
+        let worker_stats = self
+            .workers
+            .iter()
+            .enumerate()
+            .filter_map(|(worker_index, worker)| {
+                worker.stats.as_ref().map(|stats| WorkerStatsSummary {
+                    worker_index,
+                    endpoint: worker.endpoint.clone(),
+                    stats: stats.accumulate(lasted).summary(),
+                })
+            })
+            .collect();
+        let report = EmitReport::new(&phase_stats, worker_stats);
+        (phase_stats, report)
+    }
+
     pub fn peek_and_accumulate(&self) -> Vec<TxnStats> {
         self.stats.accumulate(&self.phase_starts)
     }
@@ -598,17 +706,35 @@ impl TxnEmitter {
             .with_transaction_expiration_time(init_expiration_time);
         let init_retries: usize =
             usize::try_from(init_expiration_time / req.init_retry_interval.as_secs()).unwrap();
-        let seed = req.account_minter_seed.unwrap_or_else(|| self.rng.gen());
-        let mut all_accounts = create_accounts(
-            root_account,
-            &init_txn_factory,
-            &req,
-            mode_params.max_submit_batch_size,
-            seed,
-            num_accounts,
-            init_retries,
-        )
-        .await?;
+        let (mut all_accounts, mut idle_accounts) =
+            match load_account_pool(&req, num_accounts).await? {
+                Some((accounts, idle_accounts)) => {
+                    top_up_accounts(
+                        root_account,
+                        &init_txn_factory,
+                        &req,
+                        mode_params.max_submit_batch_size,
+                        &accounts,
+                        init_retries,
+                    )
+                    .await?;
+                    (accounts, idle_accounts)
+                },
+                None => {
+                    let seed = req.account_minter_seed.unwrap_or_else(|| self.rng.gen());
+                    let accounts = create_accounts(
+                        root_account,
+                        &init_txn_factory,
+                        &req,
+                        mode_params.max_submit_batch_size,
+                        seed,
+                        num_accounts,
+                        init_retries,
+                    )
+                    .await?;
+                    (accounts, vec![])
+                },
+            };
         let stop = Arc::new(AtomicBool::new(false));
         let stats = Arc::new(DynamicStatsTracking::new(stats_tracking_phases));
         let tokio_handle = Handle::current();
@@ -656,6 +782,25 @@ impl TxnEmitter {
 
         let all_start_sleep_durations = mode_params.get_all_start_sleep_durations(self.from_rng());
 
+        // The gas price of recent blocks is shared across all workers (and only refreshed if needed)
+        let recent_blocks_gas_price = Arc::new(AtomicU64::new(req.gas_price));
+        if let GasPricingStrategy::PercentileOfRecentBlocks {
+            percentile,
+            num_blocks,
+            refresh_interval,
+            ..
+        } = req.gas_pricing_strategy
+        {
+            tokio_handle.spawn(run_recent_blocks_gas_price_refresher(
+                req.rest_clients[0].clone(),
+                percentile,
+                num_blocks,
+                refresh_interval,
+                recent_blocks_gas_price.clone(),
+                stop.clone(),
+            ));
+        }
+
         // Creating workers is slow with many workers (TODO check why)
         // so we create them all first, before starting them - so they start at the right time for
         // traffic pattern to be correct.
@@ -670,7 +815,18 @@ impl TxnEmitter {
 
                 let stop = stop.clone();
                 let stats = Arc::clone(&stats);
+                let worker_stats = req.per_worker_stats.then(|| {
+                    Arc::new(StatsAccumulator::new_with_histograms(
+                        PER_WORKER_HISTOGRAM_CAPACITY,
+                        PER_WORKER_HISTOGRAM_STEP_WIDTH,
+                    ))
+                });
                 let txn_generator = txn_generator_creator.create_transaction_generator();
+                let gas_pricer = GasPricer::new(
+                    req.gas_pricing_strategy.clone(),
+                    req.gas_price,
+                    recent_blocks_gas_price.clone(),
+                );
                 let worker_index = submission_workers.len();
 
                 let worker = SubmissionWorker::new(
@@ -678,7 +834,10 @@ impl TxnEmitter {
                     client.clone(),
                     stop,
                     mode_params.clone(),
+                    req.failure_injection.clone(),
+                    gas_pricer,
                     stats,
+                    worker_stats,
                     txn_generator,
                     all_start_sleep_durations[worker_index],
                     check_account_sequence_only_once_for.contains(&worker_index),
@@ -693,16 +852,21 @@ impl TxnEmitter {
         let workers = submission_workers
             .into_iter()
             .map(|worker| Worker {
+                endpoint: worker.endpoint(),
+                stats: worker.worker_stats(),
                 join_handle: tokio_handle.spawn(worker.run(phase_start).boxed()),
             })
             .collect();
         info!("Tx emitter workers started");
 
+        idle_accounts.append(&mut all_accounts);
         Ok(EmitJob {
             workers,
             stop,
             stats,
             phase_starts: vec![phase_start],
+            idle_accounts,
+            account_pool_file: req.account_pool_file,
         })
     }
 
@@ -712,7 +876,7 @@ impl TxnEmitter {
         emit_job_request: EmitJobRequest,
         duration: Duration,
         print_stats_interval: Option<u64>,
-    ) -> Result<TxnStats> {
+    ) -> Result<(TxnStats, EmitReport)> {
         let phases = emit_job_request.transaction_mix_per_phase.len();
 
         let mut job = self
@@ -737,9 +901,9 @@ impl TxnEmitter {
             }
         }
         info!("Ran for {} secs, stopping job...", duration.as_secs());
-        let stats = job.stop_job().await;
+        let (stats, report) = job.stop_and_report().await;
         info!("Stopped job");
-        Ok(stats.into_iter().next().unwrap())
+        Ok((stats.into_iter().next().unwrap(), report))
     }
 
     pub async fn emit_txn_for(
@@ -750,6 +914,7 @@ impl TxnEmitter {
     ) -> Result<TxnStats> {
         self.emit_txn_for_impl(source_account, emit_job_request, duration, None)
             .await
+            .map(|(stats, _report)| stats)
     }
 
     pub async fn emit_txn_for_with_stats(
@@ -766,6 +931,24 @@ impl TxnEmitter {
             Some(interval_secs),
         )
         .await
+        .map(|(stats, _report)| stats)
+    }
+
+    /// Same as emit_txn_for_with_stats, but also returns a machine-readable report of the run.
+    pub async fn emit_txn_for_with_report(
+        self,
+        source_account: &mut LocalAccount,
+        emit_job_request: EmitJobRequest,
+        duration: Duration,
+        interval_secs: u64,
+    ) -> Result<(TxnStats, EmitReport)> {
+        self.emit_txn_for_impl(
+            source_account,
+            emit_job_request,
+            duration,
+            Some(interval_secs),
+        )
+        .await
     }
 
     pub async fn submit_single_transaction(
@@ -1008,6 +1191,71 @@ pub fn parse_seed(seed_string: &str) -> [u8; 32] {
         .expect("failed to convert to array")
 }
 
+/// Loads the accounts stored in the account pool file (if one is configured and
+/// exists), and returns the accounts to use, together with the remaining (idle)
+/// accounts. Returns None if new accounts should be created instead. The
+/// balances of the returned accounts still need to be topped up.
+async fn load_account_pool(
+    req: &EmitJobRequest,
+    num_accounts: usize,
+) -> Result<Option<(Vec<LocalAccount>, Vec<LocalAccount>)>> {
+    let account_pool_file = match &req.account_pool_file {
+        Some(account_pool_file) if account_pool_file.exists() => account_pool_file,
+        _ => return Ok(None),
+    };
+
+    let mut accounts = account_pool_file.load()?;
+    if accounts.len() < num_accounts {
+        warn!(
+            "Account pool file {:?} only has {} accounts, but {} are needed. Creating new accounts instead.",
+            account_pool_file.path(),
+            accounts.len(),
+            num_accounts
+        );
+        return Ok(None);
+    }
+    info!(
+        "Reusing {} out of {} accounts from the account pool file {:?}",
+        num_accounts,
+        accounts.len(),
+        account_pool_file.path()
+    );
+    let idle_accounts = accounts.split_off(num_accounts);
+
+    // The stored sequence numbers might be stale (e.g., if some
+    // transactions expired), so refresh them from the chain.
+    let client = req.rest_clients.choose(&mut rand::thread_rng()).unwrap();
+    let addresses: Vec<_> = accounts.iter().map(LocalAccount::address).collect();
+    let (sequence_numbers, _) = query_sequence_numbers(client, addresses.iter()).await?;
+    for (account, (_, sequence_number)) in accounts.iter().zip(sequence_numbers) {
+        account.set_sequence_number(sequence_number);
+    }
+
+    Ok(Some((accounts, idle_accounts)))
+}
+
+/// Tops up the reused accounts that have fewer coins than newly created
+/// accounts would get, since previous runs spent some of them.
+pub async fn top_up_accounts(
+    root_account: &mut LocalAccount,
+    txn_factory: &TransactionFactory,
+    req: &EmitJobRequest,
+    max_submit_batch_size: usize,
+    accounts: &[LocalAccount],
+    retries: usize,
+) -> Result<()> {
+    let mut account_minter =
+        AccountMinter::new(root_account, txn_factory.clone(), StdRng::from_entropy());
+    let txn_executor = RestApiReliableTransactionSubmitter {
+        rest_clients: req.rest_clients.clone(),
+        max_retries: retries,
+        retry_after: req.init_retry_interval,
+    };
+    account_minter
+        .top_up_accounts(&txn_executor, req, accounts, max_submit_batch_size)
+        .await
+}
+
 pub async fn create_accounts(
     root_account: &mut LocalAccount,
     txn_factory: &TransactionFactory,
@@ -1,9 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::{
     fmt,
     ops::{Add, Sub},
+    path::Path,
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
@@ -11,15 +14,20 @@ use std::{
     time::{Duration, Instant},
 };
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct TxnStats {
     pub submitted: u64,
     pub committed: u64,
     pub expired: u64,
     pub failed_submission: u64,
+    pub injected_malformed: u64,
+    pub rejected_malformed: u64,
     pub latency: u64,
     pub latency_samples: u64,
     pub latency_buckets: AtomicHistogramSnapshot,
+    pub submission_latency: u64,
+    pub submission_latency_samples: u64,
+    pub submission_latency_buckets: AtomicHistogramSnapshot,
     pub lasted: Duration,
 }
 
@@ -29,6 +37,8 @@ pub struct TxnStatsRate {
     pub committed: u64,
     pub expired: u64,
     pub failed_submission: u64,
+    pub injected_malformed: u64,
+    pub rejected_malformed: u64,
     pub latency: u64,
     pub latency_samples: u64,
     pub p50_latency: u64,
@@ -40,11 +50,12 @@ impl fmt::Display for TxnStatsRate {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "committed: {} txn/s{}{}{}, latency: {} ms, (p50: {} ms, p90: {} ms, p99: {} ms), latency samples: {}",
+            "committed: {} txn/s{}{}{}{}, latency: {} ms, (p50: {} ms, p90: {} ms, p99: {} ms), latency samples: {}",
             self.committed,
             if self.submitted != self.committed { format!(", submitted: {} txn/s", self.submitted) } else { "".to_string()},
             if self.failed_submission != 0 { format!(", failed submission: {} txn/s", self.failed_submission) } else { "".to_string()},
             if self.expired != 0 { format!(", expired: {} txn/s", self.expired) } else { "".to_string()},
+            if self.injected_malformed != 0 { format!(", injected malformed: {} txn/s (rejected: {} txn/s)", self.injected_malformed, self.rejected_malformed) } else { "".to_string()},
             self.latency, self.p50_latency, self.p90_latency, self.p99_latency, self.latency_samples,
         )
     }
@@ -61,6 +72,8 @@ impl TxnStats {
             committed: self.committed / window_secs,
             expired: self.expired / window_secs,
             failed_submission: self.failed_submission / window_secs,
+            injected_malformed: self.injected_malformed / window_secs,
+            rejected_malformed: self.rejected_malformed / window_secs,
             latency: if self.latency_samples == 0 {
                 0u64
             } else {
@@ -72,6 +85,76 @@ impl TxnStats {
             p99_latency: self.latency_buckets.percentile(99, 100),
         }
     }
+
+    /// Summary of the stats, in a form suitable for machine-readable reports.
+    pub fn summary(&self) -> TxnStatsSummary {
+        TxnStatsSummary {
+            submitted: self.submitted,
+            committed: self.committed,
+            expired: self.expired,
+            failed_submission: self.failed_submission,
+            injected_malformed: self.injected_malformed,
+            rejected_malformed: self.rejected_malformed,
+            duration_secs: self.lasted.as_secs_f64(),
+            committed_tps: self.committed as f64 / self.lasted.as_secs_f64().max(1.0),
+            expiration_rate: if self.submitted == 0 {
+                0.0
+            } else {
+                self.expired as f64 / self.submitted as f64
+            },
+            commit_latency: LatencySummary::new(
+                self.latency,
+                self.latency_samples,
+                &self.latency_buckets,
+            ),
+            submission_latency: LatencySummary::new(
+                self.submission_latency,
+                self.submission_latency_samples,
+                &self.submission_latency_buckets,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySummary {
+    pub samples: u64,
+    pub avg_ms: u64,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+}
+
+impl LatencySummary {
+    fn new(sum: u64, samples: u64, buckets: &AtomicHistogramSnapshot) -> Self {
+        Self {
+            samples,
+            avg_ms: if samples == 0 { 0 } else { sum / samples },
+            p50_ms: buckets.percentile(50, 100),
+            p90_ms: buckets.percentile(90, 100),
+            p99_ms: buckets.percentile(99, 100),
+        }
+    }
+}
+
+/// Machine-readable summary of TxnStats. Commit latency is measured end-to-end,
+/// from the start of the submission loop until the transaction is observed committed,
+/// while submission latency only covers the submit request to the REST API.
+/// Injected malformed transactions are not included in submitted, and out of them,
+/// rejected_malformed counts the ones the node rejected on submission (as expected).
+#[derive(Debug, Clone, Serialize)]
+pub struct TxnStatsSummary {
+    pub submitted: u64,
+    pub committed: u64,
+    pub expired: u64,
+    pub failed_submission: u64,
+    pub injected_malformed: u64,
+    pub rejected_malformed: u64,
+    pub duration_secs: f64,
+    pub committed_tps: f64,
+    pub expiration_rate: f64,
+    pub commit_latency: LatencySummary,
+    pub submission_latency: LatencySummary,
 }
 
 impl fmt::Display for TxnStats {
@@ -93,9 +176,16 @@ impl Sub for &TxnStats {
             committed: self.committed - other.committed,
             expired: self.expired - other.expired,
             failed_submission: self.failed_submission - other.failed_submission,
+            injected_malformed: self.injected_malformed - other.injected_malformed,
+            rejected_malformed: self.rejected_malformed - other.rejected_malformed,
             latency: self.latency - other.latency,
             latency_samples: self.latency_samples - other.latency_samples,
             latency_buckets: &self.latency_buckets - &other.latency_buckets,
+            submission_latency: self.submission_latency - other.submission_latency,
+            submission_latency_samples: self.submission_latency_samples
+                - other.submission_latency_samples,
+            submission_latency_buckets: &self.submission_latency_buckets
+                - &other.submission_latency_buckets,
             lasted: self.lasted - other.lasted,
         }
     }
@@ -110,9 +200,16 @@ impl Add for &TxnStats {
             committed: self.committed + other.committed,
             expired: self.expired + other.expired,
             failed_submission: self.failed_submission + other.failed_submission,
+            injected_malformed: self.injected_malformed + other.injected_malformed,
+            rejected_malformed: self.rejected_malformed + other.rejected_malformed,
             latency: self.latency + other.latency,
             latency_samples: self.latency_samples + other.latency_samples,
             latency_buckets: &self.latency_buckets + &other.latency_buckets,
+            submission_latency: self.submission_latency + other.submission_latency,
+            submission_latency_samples: self.submission_latency_samples
+                + other.submission_latency_samples,
+            submission_latency_buckets: &self.submission_latency_buckets
+                + &other.submission_latency_buckets,
             lasted: self.lasted + other.lasted,
         }
     }
@@ -124,24 +221,88 @@ pub struct StatsAccumulator {
     pub committed: AtomicU64,
     pub expired: AtomicU64,
     pub failed_submission: AtomicU64,
+    pub injected_malformed: AtomicU64,
+    pub rejected_malformed: AtomicU64,
     pub latency: AtomicU64,
     pub latency_samples: AtomicU64,
     pub latencies: Arc<AtomicHistogramAccumulator>,
+    pub submission_latency: AtomicU64,
+    pub submission_latency_samples: AtomicU64,
+    pub submission_latencies: Arc<AtomicHistogramAccumulator>,
 }
 
 impl StatsAccumulator {
+    /// Creates an accumulator with histograms of the given size and bucket width (in ms),
+    /// e.g. to keep per worker stats cheaper than the default.
+    pub fn new_with_histograms(capacity: usize, step_width: u64) -> Self {
+        Self {
+            latencies: Arc::new(AtomicHistogramAccumulator::new(capacity, step_width)),
+            submission_latencies: Arc::new(AtomicHistogramAccumulator::new(capacity, step_width)),
+            ..Default::default()
+        }
+    }
+
     pub fn accumulate(&self, lasted: Duration) -> TxnStats {
         TxnStats {
             submitted: self.submitted.load(Ordering::Relaxed),
             committed: self.committed.load(Ordering::Relaxed),
             expired: self.expired.load(Ordering::Relaxed),
             failed_submission: self.failed_submission.load(Ordering::Relaxed),
+            injected_malformed: self.injected_malformed.load(Ordering::Relaxed),
+            rejected_malformed: self.rejected_malformed.load(Ordering::Relaxed),
             latency: self.latency.load(Ordering::Relaxed),
             latency_samples: self.latency_samples.load(Ordering::Relaxed),
             latency_buckets: self.latencies.snapshot(),
+            submission_latency: self.submission_latency.load(Ordering::Relaxed),
+            submission_latency_samples: self.submission_latency_samples.load(Ordering::Relaxed),
+            submission_latency_buckets: self.submission_latencies.snapshot(),
             lasted,
         }
     }
+
+    pub fn record_submission_latency(&self, latency_millis: u64, num_txns: u64) {
+        self.submission_latency
+            .fetch_add(latency_millis * num_txns, Ordering::Relaxed);
+        self.submission_latency_samples
+            .fetch_add(num_txns, Ordering::Relaxed);
+        self.submission_latencies
+            .record_data_point(latency_millis, num_txns);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatsSummary {
+    pub worker_index: usize,
+    pub endpoint: String,
+    pub stats: TxnStatsSummary,
+}
+
+/// Machine-readable report of an emit job, with stats per phase, across all phases,
+/// and (if collected) per worker across all phases.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmitReport {
+    pub total: TxnStatsSummary,
+    pub phases: Vec<TxnStatsSummary>,
+    pub workers: Vec<WorkerStatsSummary>,
+}
+
+impl EmitReport {
+    pub fn new(phase_stats: &[TxnStats], workers: Vec<WorkerStatsSummary>) -> Self {
+        let total = phase_stats
+            .iter()
+            .fold(TxnStats::default(), |total, stats| &total + stats);
+        Self {
+            total: total.summary(),
+            phases: phase_stats.iter().map(TxnStats::summary).collect(),
+            workers,
+        }
+    }
+
+    pub fn write_json_file(&self, path: &Path) -> Result<()> {
+        let report = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, report)
+            .with_context(|| format!("Failed to write report to {}", path.display()))
+    }
 }
 
 // have more slots than generally used txn expiration. (240s)
@@ -201,7 +362,7 @@ impl AtomicHistogramAccumulator {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AtomicHistogramSnapshot {
     capacity: usize,
     step_width: u64,
@@ -335,9 +496,10 @@ impl DynamicStatsTracking {
 #[cfg(test)]
 mod test {
     use crate::emitter::stats::{
-        AtomicHistogramAccumulator, AtomicHistogramSnapshot, TxnStats, DEFAULT_HISTOGRAM_CAPACITY,
-        DEFAULT_HISTOGRAM_STEP_WIDTH,
+        AtomicHistogramAccumulator, AtomicHistogramSnapshot, EmitReport, StatsAccumulator,
+        TxnStats, DEFAULT_HISTOGRAM_CAPACITY, DEFAULT_HISTOGRAM_STEP_WIDTH,
     };
+    use std::sync::atomic::Ordering;
     use std::time::Duration;
 
     #[test]
@@ -402,12 +564,44 @@ mod test {
             committed: 10,
             expired: 0,
             failed_submission: 0,
+            injected_malformed: 0,
+            rejected_malformed: 0,
             latency: 0,
             latency_samples: 0,
             latency_buckets: histogram.snapshot(),
+            submission_latency: 0,
+            submission_latency_samples: 0,
+            submission_latency_buckets: AtomicHistogramSnapshot::default(),
             lasted: Duration::from_secs(10),
         };
         let res = stat.latency_buckets.percentile(9, 10);
         assert_eq!(res, 900);
     }
+
+    #[test]
+    pub fn test_emit_report() {
+        let phase_stats: Vec<_> = (1..3)
+            .map(|i| {
+                let stats = StatsAccumulator::default();
+                stats.submitted.store(100 * i, Ordering::Relaxed);
+                stats.committed.store(90 * i, Ordering::Relaxed);
+                stats.expired.store(10 * i, Ordering::Relaxed);
+                stats.record_submission_latency(200 * i, 100 * i);
+                stats.accumulate(Duration::from_secs(10))
+            })
+            .collect();
+
+        let report = EmitReport::new(&phase_stats, vec![]);
+        assert_eq!(report.phases.len(), 2);
+        assert_eq!(report.phases[0].committed_tps, 9.0);
+        assert_eq!(report.phases[1].expiration_rate, 0.1);
+        assert_eq!(report.total.submitted, 300);
+        assert_eq!(report.total.committed, 270);
+        assert_eq!(report.total.duration_secs, 20.0);
+        assert_eq!(report.total.submission_latency.samples, 300);
+        // (200 * 100 + 400 * 200) / 300
+        assert_eq!(report.total.submission_latency.avg_ms, 333);
+        assert_eq!(report.total.submission_latency.p50_ms, 400);
+        assert_eq!(report.total.commit_latency.samples, 0);
+    }
 }
@@ -3,6 +3,8 @@
 
 use crate::{
     emitter::{
+        failure_injection::FailureInjection,
+        gas_pricing::{with_gas_unit_price, GasPricer},
         stats::{DynamicStatsTracking, StatsAccumulator},
         update_seq_num_and_get_num_expired, wait_for_accounts_sequence,
     },
@@ -21,7 +23,7 @@ use core::{
     sync::atomic::{AtomicBool, Ordering},
     time::Duration,
 };
-use futures::future::join_all;
+use futures::future::{join, join_all};
 use itertools::Itertools;
 use rand::seq::IteratorRandom;
 use std::{
@@ -33,10 +35,16 @@ use tokio::time::sleep;
 
 pub struct SubmissionWorker {
     pub(crate) accounts: Vec<LocalAccount>,
+    // Position of each account in `accounts`, used to find the signer of a generated transaction.
+    account_index_by_address: HashMap<AccountAddress, usize>,
     client: RestClient,
     stop: Arc<AtomicBool>,
     params: EmitModeParams,
+    failure_injection: FailureInjection,
+    gas_pricer: GasPricer,
     stats: Arc<DynamicStatsTracking>,
+    // Stats of this worker across all phases, if collected.
+    worker_stats: Option<Arc<StatsAccumulator>>,
     txn_generator: Box<dyn TransactionGenerator>,
     start_sleep_duration: Duration,
     skip_latency_stats: bool,
@@ -49,18 +57,30 @@ impl SubmissionWorker {
         client: RestClient,
         stop: Arc<AtomicBool>,
         params: EmitModeParams,
+        failure_injection: FailureInjection,
+        gas_pricer: GasPricer,
         stats: Arc<DynamicStatsTracking>,
+        worker_stats: Option<Arc<StatsAccumulator>>,
         txn_generator: Box<dyn TransactionGenerator>,
         start_sleep_duration: Duration,
         skip_latency_stats: bool,
         rng: ::rand::rngs::StdRng,
     ) -> Self {
+        let account_index_by_address = accounts
+            .iter()
+            .enumerate()
+            .map(|(index, account)| (account.address(), index))
+            .collect();
         Self {
             accounts,
+            account_index_by_address,
             client,
             stop,
             params,
+            failure_injection,
+            gas_pricer,
             stats,
+            worker_stats,
             txn_generator,
             start_sleep_duration,
             skip_latency_stats,
@@ -68,6 +88,14 @@ impl SubmissionWorker {
         }
     }
 
+    pub(crate) fn endpoint(&self) -> String {
+        self.client.path_prefix_string()
+    }
+
+    pub(crate) fn worker_stats(&self) -> Option<Arc<StatsAccumulator>> {
+        self.worker_stats.clone()
+    }
+
     #[allow(clippy::collapsible_if)]
     pub(crate) async fn run(mut self, start_instant: Instant) -> Vec<LocalAccount> {
         let mut wait_until = start_instant + self.start_sleep_duration;
@@ -80,7 +108,23 @@ impl SubmissionWorker {
 
         while !self.stop.load(Ordering::Relaxed) {
             let stats_clone = self.stats.clone();
-            let loop_stats = stats_clone.get_cur();
+            let worker_stats_clone = self.worker_stats.clone();
+            let loop_stats: Vec<&StatsAccumulator> = std::iter::once(stats_clone.get_cur())
+                .chain(worker_stats_clone.as_deref())
+                .collect();
+
+            let elapsed = Instant::now().duration_since(start_instant);
+            let paused_for = self
+                .failure_injection
+                .bursts
+                .as_ref()
+                .and_then(|bursts| bursts.paused_for(elapsed));
+            if let Some(paused_for) = paused_for {
+                self.sleep_check_done(paused_for).await;
+                // continue at the regular pace after the pause, instead of catching up.
+                wait_until = Instant::now();
+                continue;
+            }
 
             let loop_start_time = Instant::now();
             if wait_duration.as_secs() > 0
@@ -99,6 +143,7 @@ impl SubmissionWorker {
             wait_until += wait_duration;
 
             let requests = self.gen_requests();
+            let malformed_requests = self.gen_malformed_requests(&requests);
             if !requests.is_empty() {
                 let mut account_to_start_and_end_seq_num = HashMap::new();
                 for req in requests.iter() {
@@ -135,18 +180,27 @@ impl SubmissionWorker {
 
                 let txn_offset_time = Arc::new(AtomicU64::new(0));
 
-                join_all(
-                    requests
-                        .chunks(self.params.max_submit_batch_size)
-                        .map(|reqs| {
-                            submit_transactions(
-                                &self.client,
-                                reqs,
-                                loop_start_time,
-                                txn_offset_time.clone(),
-                                loop_stats,
-                            )
-                        }),
+                join(
+                    join_all(
+                        requests
+                            .chunks(self.params.max_submit_batch_size)
+                            .map(|reqs| {
+                                submit_transactions(
+                                    &self.client,
+                                    reqs,
+                                    loop_start_time,
+                                    txn_offset_time.clone(),
+                                    &loop_stats,
+                                )
+                            }),
+                    ),
+                    join_all(
+                        malformed_requests
+                            .chunks(self.params.max_submit_batch_size)
+                            .map(|reqs| {
+                                submit_malformed_transactions(&self.client, reqs, &loop_stats)
+                            }),
+                    ),
                 )
                 .await;
 
@@ -188,7 +242,7 @@ impl SubmissionWorker {
                     } else {
                         self.params.check_account_sequence_sleep
                     },
-                    loop_stats,
+                    &loop_stats,
                 )
                 .await;
             }
@@ -231,7 +285,7 @@ impl SubmissionWorker {
         skip_latency_stats: bool,
         txn_expiration_ts_secs: u64,
         check_account_sleep_duration: Duration,
-        loop_stats: &StatsAccumulator,
+        loop_stats: &[&StatsAccumulator],
     ) {
         let (latest_fetched_counts, sum_of_completion_timestamps_millis) =
             wait_for_accounts_sequence(
@@ -248,11 +302,14 @@ impl SubmissionWorker {
             account_to_start_and_end_seq_num,
             latest_fetched_counts,
         );
+        self.gas_pricer.record_round(num_committed, num_expired);
 
         if num_expired > 0 {
-            loop_stats
-                .expired
-                .fetch_add(num_expired as u64, Ordering::Relaxed);
+            for stats in loop_stats {
+                stats
+                    .expired
+                    .fetch_add(num_expired as u64, Ordering::Relaxed);
+            }
             sample!(
                 SampleRate::Duration(Duration::from_secs(120)),
                 warn!(
@@ -271,20 +328,22 @@ impl SubmissionWorker {
             let sum_latency = sum_of_completion_timestamps_millis
                 - (avg_txn_offset_time as u128 * num_committed as u128);
             let avg_latency = (sum_latency / num_committed as u128) as u64;
-            loop_stats
-                .committed
-                .fetch_add(num_committed as u64, Ordering::Relaxed);
-
-            if !skip_latency_stats {
-                loop_stats
-                    .latency
-                    .fetch_add(sum_latency as u64, Ordering::Relaxed);
-                loop_stats
-                    .latency_samples
+            for stats in loop_stats {
+                stats
+                    .committed
                     .fetch_add(num_committed as u64, Ordering::Relaxed);
-                loop_stats
-                    .latencies
-                    .record_data_point(avg_latency, num_committed as u64);
+
+                if !skip_latency_stats {
+                    stats
+                        .latency
+                        .fetch_add(sum_latency as u64, Ordering::Relaxed);
+                    stats
+                        .latency_samples
+                        .fetch_add(num_committed as u64, Ordering::Relaxed);
+                    stats
+                        .latencies
+                        .record_data_point(avg_latency, num_committed as u64);
+                }
             }
         }
     }
@@ -302,14 +361,120 @@ impl SubmissionWorker {
             .iter()
             .choose_multiple(&mut self.rng, batch_size);
 
-        accounts
+        let requests: Vec<_> = accounts
             .into_iter()
             .flat_map(|account| {
                 self.txn_generator
                     .generate_transactions(account, self.params.transactions_per_account)
             })
+            .collect();
+        self.apply_gas_price(requests)
+    }
+
+    fn account(&self, address: &AccountAddress) -> Option<&LocalAccount> {
+        self.account_index_by_address
+            .get(address)
+            .map(|index| &self.accounts[*index])
+    }
+
+    /// Re-signs the generated requests with the gas price picked by the gas pricing strategy.
+    fn apply_gas_price(&self, requests: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        let gas_price = self.gas_pricer.gas_price();
+        requests
+            .into_iter()
+            .map(|txn| {
+                if txn.gas_unit_price() == gas_price {
+                    return txn;
+                }
+                // We don't have the keys of burner accounts, so their transactions keep their gas price.
+                match self.account(&txn.sender()) {
+                    Some(account) => with_gas_unit_price(account, &txn, gas_price),
+                    None => txn,
+                }
+            })
             .collect()
     }
+
+    /// Re-signs malformed copies of a (configured) fraction of the generated requests.
+    fn gen_malformed_requests(&mut self, requests: &[SignedTransaction]) -> Vec<SignedTransaction> {
+        if !self.failure_injection.injects_malformed_txns() {
+            return Vec::new();
+        }
+        requests
+            .iter()
+            .filter_map(|txn| {
+                let kind = self.failure_injection.pick_malformed_kind(&mut self.rng)?;
+                // We don't have the keys of burner accounts, so their transactions are skipped.
+                let index = self.account_index_by_address.get(&txn.sender())?;
+                Some(kind.corrupt(&self.accounts[*index], txn))
+            })
+            .collect()
+    }
+}
+
+/// Submits deliberately malformed transactions, which are all expected to be rejected,
+/// and tracks how many of them were (and for which reasons).
+pub async fn submit_malformed_transactions(
+    client: &RestClient,
+    txns: &[SignedTransaction],
+    loop_stats: &[&StatsAccumulator],
+) {
+    for stats in loop_stats {
+        stats
+            .injected_malformed
+            .fetch_add(txns.len() as u64, Ordering::Relaxed);
+    }
+
+    match client.submit_batch_bcs(txns).await {
+        Err(e) => {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(60)),
+                warn!(
+                    "[{:?}] Failed to submit batch request with malformed txns: {:?}",
+                    client.path_prefix_string(),
+                    e
+                )
+            );
+        },
+        Ok(v) => {
+            let failures = v.into_inner().transaction_failures;
+            for stats in loop_stats {
+                stats
+                    .rejected_malformed
+                    .fetch_add(failures.len() as u64, Ordering::Relaxed);
+            }
+
+            let by_error = failures
+                .iter()
+                .map(|f| {
+                    f.error
+                        .vm_error_code
+                        .and_then(|c| StatusCode::try_from(c).ok())
+                })
+                .counts();
+            if failures.len() < txns.len() {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    warn!(
+                        "[{:?}] {} out of {} malformed txns were accepted, rejected error codes: {:?}",
+                        client.path_prefix_string(),
+                        txns.len() - failures.len(),
+                        txns.len(),
+                        by_error,
+                    )
+                );
+            } else {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(300)),
+                    info!(
+                        "[{:?}] Malformed txns rejected with error codes: {:?}",
+                        client.path_prefix_string(),
+                        by_error,
+                    )
+                );
+            }
+        },
+    };
 }
 
 pub async fn submit_transactions(
@@ -317,7 +482,7 @@ pub async fn submit_transactions(
     txns: &[SignedTransaction],
     loop_start_time: Instant,
     txn_offset_time: Arc<AtomicU64>,
-    stats: &StatsAccumulator,
+    loop_stats: &[&StatsAccumulator],
 ) {
     let cur_time = Instant::now();
     let offset = cur_time - loop_start_time;
@@ -325,15 +490,25 @@ pub async fn submit_transactions(
         txns.len() as u64 * offset.as_millis() as u64,
         Ordering::Relaxed,
     );
-    stats
-        .submitted
-        .fetch_add(txns.len() as u64, Ordering::Relaxed);
+    for stats in loop_stats {
+        stats
+            .submitted
+            .fetch_add(txns.len() as u64, Ordering::Relaxed);
+    }
 
-    match client.submit_batch_bcs(txns).await {
+    let result = client.submit_batch_bcs(txns).await;
+    let submission_latency = cur_time.elapsed().as_millis() as u64;
+    for stats in loop_stats {
+        stats.record_submission_latency(submission_latency, txns.len() as u64);
+    }
+
+    match result {
         Err(e) => {
-            stats
-                .failed_submission
-                .fetch_add(txns.len() as u64, Ordering::Relaxed);
+            for stats in loop_stats {
+                stats
+                    .failed_submission
+                    .fetch_add(txns.len() as u64, Ordering::Relaxed);
+            }
             sample!(
                 SampleRate::Duration(Duration::from_secs(60)),
                 warn!(
@@ -346,9 +521,11 @@ pub async fn submit_transactions(
         Ok(v) => {
             let failures = v.into_inner().transaction_failures;
 
-            stats
-                .failed_submission
-                .fetch_add(failures.len() as u64, Ordering::Relaxed);
+            for stats in loop_stats {
+                stats
+                    .failed_submission
+                    .fetch_add(failures.len() as u64, Ordering::Relaxed);
+            }
 
             let by_error = failures
                 .iter()
@@ -5,17 +5,21 @@
 
 mod args;
 mod cluster;
+mod coordinator;
 pub mod emitter;
 mod instance;
 mod wrappers;
 
 // These are the top level things you should need to run the emitter.
-pub use args::{ClusterArgs, CoinSourceArgs, CreateAccountsArgs, EmitArgs};
+pub use args::{
+    ClusterArgs, CoinSourceArgs, CoordinatorArgs, CreateAccountsArgs, EmitArgs, EmitWorkerArgs,
+};
 // We export these if you want finer grained control.
 pub use cluster::Cluster;
+pub use coordinator::{coordinate_emit_transactions, emit_transactions_as_worker};
 pub use emitter::{
     query_sequence_number, query_sequence_numbers,
-    stats::{TxnStats, TxnStatsRate},
+    stats::{EmitReport, TxnStats, TxnStatsRate},
     EmitJob, EmitJobMode, EmitJobRequest, EmitModeParams, TxnEmitter,
 };
 pub use wrappers::{create_accounts_command, emit_transactions, emit_transactions_with_cluster};
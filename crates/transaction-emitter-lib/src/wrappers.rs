@@ -2,10 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    args::{ClusterArgs, EmitArgs},
+    args::{ClusterArgs, EmitArgs, GasPricingStrategyArg},
     cluster::Cluster,
     emitter::{
-        create_accounts, parse_seed, stats::TxnStats, EmitJobMode, EmitJobRequest, TxnEmitter,
+        create_accounts,
+        failure_injection::{FailureInjection, MalformedTxnKind, SubmissionBursts},
+        gas_pricing::GasPricingStrategy,
+        parse_seed,
+        stats::TxnStats,
+        EmitJobMode, EmitJobRequest, TxnEmitter,
     },
     instance::Instance,
     CreateAccountsArgs,
@@ -15,6 +20,7 @@ use aptos_config::config::DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE;
 use aptos_logger::{error, info};
 use aptos_sdk::transaction_builder::TransactionFactory;
 use aptos_transaction_generator_lib::args::TransactionTypeArg;
+use clap::ValueEnum;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::time::{Duration, Instant};
 
@@ -81,6 +87,8 @@ pub async fn emit_transactions_with_cluster(
         StdRng::from_entropy(),
     );
 
+    args.entry_function_args
+        .register_workload(&args.transaction_type)?;
     let transaction_mix_per_phase = TransactionTypeArg::args_to_transaction_mix_per_phase(
         &args.transaction_type,
         &args.transaction_weights,
@@ -114,6 +122,29 @@ pub async fn emit_transactions_with_cluster(
         emit_job_request = emit_job_request.init_gas_price_multiplier(init_gas_price_multiplier);
     }
 
+    if let Some(gas_pricing_strategy) = args.gas_pricing_strategy {
+        let gas_price = args
+            .gas_price
+            .unwrap_or(aptos_global_constants::GAS_UNIT_PRICE);
+        let max_gas_price = args.max_gas_price.unwrap_or(gas_price * 10);
+        let gas_pricing_strategy = match gas_pricing_strategy {
+            GasPricingStrategyArg::Fixed => GasPricingStrategy::Fixed,
+            GasPricingStrategyArg::PercentileOfRecentBlocks => {
+                GasPricingStrategy::PercentileOfRecentBlocks {
+                    percentile: args.gas_price_percentile.unwrap_or(0.9),
+                    num_blocks: args.gas_price_num_blocks.unwrap_or(10),
+                    refresh_interval: Duration::from_secs(10),
+                    max_gas_price,
+                }
+            },
+            GasPricingStrategyArg::EscalateOnTimeout => GasPricingStrategy::EscalateOnTimeout {
+                multiplier: args.gas_price_escalation_multiplier.unwrap_or(1.5),
+                max_gas_price,
+            },
+        };
+        emit_job_request = emit_job_request.gas_pricing_strategy(gas_pricing_strategy);
+    }
+
     if let Some(expected_max_txns) = args.expected_max_txns {
         emit_job_request = emit_job_request.expected_max_txns(expected_max_txns);
     }
@@ -134,19 +165,57 @@ pub async fn emit_transactions_with_cluster(
         emit_job_request = emit_job_request.coins_per_account_override(coins);
     }
 
+    if let (Some(path), Some(passphrase)) = (&args.account_pool_file, &args.account_pool_passphrase)
+    {
+        emit_job_request = emit_job_request.account_pool_file(path.clone(), passphrase.clone());
+    }
+
     if let Some(latency_polling_interval_s) = args.latency_polling_interval_s {
         emit_job_request = emit_job_request
             .latency_polling_interval(Duration::from_secs_f32(latency_polling_interval_s));
     }
 
-    let stats = emitter
-        .emit_txn_for_with_stats(
-            &mut coin_source_account,
-            emit_job_request,
-            duration,
-            (args.duration / 10).clamp(1, 10),
-        )
-        .await?;
+    if args.malformed_txn_fraction.is_some() || args.submission_burst_secs.is_some() {
+        emit_job_request = emit_job_request.failure_injection(FailureInjection {
+            malformed_txn_fraction: args.malformed_txn_fraction.unwrap_or(0.0),
+            malformed_txn_kinds: if args.malformed_txn_kinds.is_empty() {
+                MalformedTxnKind::value_variants().to_vec()
+            } else {
+                args.malformed_txn_kinds.clone()
+            },
+            bursts: args
+                .submission_burst_secs
+                .zip(args.submission_pause_secs)
+                .map(|(active, paused)| SubmissionBursts {
+                    active: Duration::from_secs(active),
+                    paused: Duration::from_secs(paused),
+                }),
+        });
+    }
+
+    let print_stats_interval = (args.duration / 10).clamp(1, 10);
+    let stats = if let Some(report_json_file) = &args.report_json_file {
+        let (stats, report) = emitter
+            .emit_txn_for_with_report(
+                &mut coin_source_account,
+                emit_job_request.per_worker_stats(true),
+                duration,
+                print_stats_interval,
+            )
+            .await?;
+        report.write_json_file(report_json_file)?;
+        info!("Wrote report to {}", report_json_file.display());
+        stats
+    } else {
+        emitter
+            .emit_txn_for_with_stats(
+                &mut coin_source_account,
+                emit_job_request,
+                duration,
+                print_stats_interval,
+            )
+            .await?
+    };
     Ok(stats)
 }
 
@@ -7,7 +7,9 @@ mod diag;
 use anyhow::{Context, Result};
 use aptos_logger::{Level, Logger};
 use aptos_transaction_emitter_lib::{
-    create_accounts_command, emit_transactions, Cluster, ClusterArgs, CreateAccountsArgs, EmitArgs,
+    coordinate_emit_transactions, create_accounts_command, emit_transactions,
+    emit_transactions_as_worker, Cluster, ClusterArgs, CoordinatorArgs, CreateAccountsArgs,
+    EmitArgs, EmitWorkerArgs,
 };
 use clap::{Parser, Subcommand};
 use diag::diag;
@@ -25,6 +27,13 @@ enum TxnEmitterCommand {
     /// recording stats as we go.
     EmitTx(EmitTx),
 
+    /// Coordinates EmitTxWorker processes running on multiple machines: splits the
+    /// load and the accounts between them, and aggregates their stats.
+    EmitTxCoordinator(EmitTxCoordinator),
+
+    /// Emits transactions with the share of the load assigned by an EmitTxCoordinator.
+    EmitTxWorker(EmitTxWorker),
+
     /// Create test accounts, for use with EmitTx
     CreateAccounts(CreateAccounts),
 
@@ -46,6 +55,24 @@ struct EmitTx {
     emit_args: EmitArgs,
 }
 
+#[derive(Parser, Debug)]
+struct EmitTxCoordinator {
+    #[clap(flatten)]
+    coordinator_args: CoordinatorArgs,
+
+    #[clap(flatten)]
+    emit_args: EmitArgs,
+}
+
+#[derive(Parser, Debug)]
+struct EmitTxWorker {
+    #[clap(flatten)]
+    cluster_args: ClusterArgs,
+
+    #[clap(flatten)]
+    worker_args: EmitWorkerArgs,
+}
+
 #[derive(Parser, Debug)]
 struct CreateAccounts {
     #[clap(flatten)]
@@ -84,6 +111,22 @@ pub async fn main() -> Result<()> {
             println!("Average rate: {}", stats.rate());
             Ok(())
         },
+        TxnEmitterCommand::EmitTxCoordinator(args) => {
+            let stats = coordinate_emit_transactions(&args.coordinator_args, &args.emit_args)
+                .await
+                .context("Coordinated emission failed")?;
+            println!("Total stats across all workers: {}", stats);
+            println!("Average rate across all workers: {}", stats.rate());
+            Ok(())
+        },
+        TxnEmitterCommand::EmitTxWorker(args) => {
+            let stats = emit_transactions_as_worker(&args.cluster_args, &args.worker_args)
+                .await
+                .context("Emit transactions as a worker failed")?;
+            println!("Worker stats: {}", stats);
+            println!("Worker average rate: {}", stats.rate());
+            Ok(())
+        },
         TxnEmitterCommand::CreateAccounts(args) => {
             create_accounts_command(&args.cluster_args, &args.create_accounts_args)
                 .await
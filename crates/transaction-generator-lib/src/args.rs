@@ -1,9 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{publishing::module_simple::LoopType, EntryPoints, TransactionType};
+use crate::{
+    publishing::module_simple::LoopType, EntryFunctionWorkload, EntryPoints, TransactionType,
+};
+use anyhow::{Context, Result};
 use clap::{Parser, ValueEnum};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Utility class for specifying transaction type with predefined configurations through CLI
 #[derive(Debug, Copy, Clone, ValueEnum, Default, Deserialize, Parser, Serialize)]
@@ -13,10 +17,14 @@ pub enum TransactionTypeArg {
     CoinTransfer,
     CoinTransferWithInvalid,
     NonConflictingCoinTransfer,
+    CoinTransferHotAccounts,
+    CoinTransferHotAccountsSkewed,
     AccountGeneration,
     AccountGenerationLargePool,
     Batch100Transfer,
     PublishPackage,
+    /// Calls the entry function configured via `EntryFunctionArgs`
+    EntryFunction,
     // Simple EntryPoints
     NoOp,
     NoOp2Signers,
@@ -85,6 +93,22 @@ impl TransactionTypeArg {
                 invalid_transaction_ratio: 10,
                 sender_use_account_pool,
             },
+            TransactionTypeArg::CoinTransferHotAccounts => {
+                TransactionType::HotAccountsCoinTransfer {
+                    num_hot_accounts: 10,
+                    hot_fraction: 0.5,
+                    hotness_skew: 0.0,
+                    sender_use_account_pool,
+                }
+            },
+            TransactionTypeArg::CoinTransferHotAccountsSkewed => {
+                TransactionType::HotAccountsCoinTransfer {
+                    num_hot_accounts: 100,
+                    hot_fraction: 0.5,
+                    hotness_skew: 1.0,
+                    sender_use_account_pool,
+                }
+            },
             TransactionTypeArg::AccountGeneration => TransactionType::AccountGeneration {
                 add_created_accounts_to_pool: true,
                 max_account_working_set: 1_000_000,
@@ -98,6 +122,13 @@ impl TransactionTypeArg {
             TransactionTypeArg::PublishPackage => TransactionType::PublishPackage {
                 use_account_pool: sender_use_account_pool,
             },
+            TransactionTypeArg::EntryFunction => TransactionType::EntryFunction {
+                workload: EntryFunctionWorkload::registered().expect(
+                    "The entry function workload must be registered, see EntryFunctionArgs",
+                ),
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
             TransactionTypeArg::Batch100Transfer => {
                 TransactionType::BatchTransfer { batch_size: 100 }
             },
@@ -436,3 +467,56 @@ impl TransactionTypeArg {
         transaction_mix_per_phase
     }
 }
+
+/// Arguments configuring the workload of `--transaction-type entry-function`.
+#[derive(Clone, Debug, Default, Deserialize, Parser, Serialize)]
+pub struct EntryFunctionArgs {
+    /// Build directory of the package to publish, i.e. `build/<package_name>`
+    /// as generated by `aptos move compile --save-metadata`.
+    #[clap(long, requires = "entry_function")]
+    pub entry_function_package_dir: Option<PathBuf>,
+
+    /// Entry function to call, of the form `<module>::<function>`.
+    #[clap(long, requires = "entry_function_package_dir")]
+    pub entry_function: Option<String>,
+
+    /// Type arguments of the entry function, e.g. `0x1::aptos_coin::AptosCoin`.
+    #[clap(long, num_args = 0..)]
+    pub entry_function_type_args: Vec<String>,
+
+    /// Arguments of the entry function, see `ArgumentTemplate` for the format,
+    /// e.g. `u64:rand:1:100` or `address:sender`.
+    #[clap(long, num_args = 0..)]
+    pub entry_function_args: Vec<String>,
+}
+
+impl EntryFunctionArgs {
+    /// Builds and registers the configured workload, if the given transaction types use it.
+    /// The workload lives for the whole process, so if one is already registered (e.g. when
+    /// emitting is retried), it is kept.
+    pub fn register_workload(&self, transaction_types: &[TransactionTypeArg]) -> Result<()> {
+        if EntryFunctionWorkload::registered().is_some()
+            || !transaction_types
+                .iter()
+                .any(|t| matches!(t, TransactionTypeArg::EntryFunction))
+        {
+            return Ok(());
+        }
+        let package_dir = self
+            .entry_function_package_dir
+            .as_ref()
+            .context("--entry-function-package-dir is required for the entry-function workload")?;
+        let function = self
+            .entry_function
+            .as_ref()
+            .context("--entry-function is required for the entry-function workload")?;
+        EntryFunctionWorkload::new(
+            package_dir,
+            function,
+            &self.entry_function_type_args,
+            &self.entry_function_args,
+        )?
+        .register()?;
+        Ok(())
+    }
+}
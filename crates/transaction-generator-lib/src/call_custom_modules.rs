@@ -115,7 +115,7 @@ impl CustomModulesDelegationGeneratorCreator {
         accounts: &mut [LocalAccount],
         txn_executor: &dyn ReliableTransactionSubmitter,
         num_modules: usize,
-        package_name: &str,
+        mut package_handler: PackageHandler,
         workload: &mut dyn UserModuleTransactionGenerator,
     ) -> Self {
         let mut rng = StdRng::from_entropy();
@@ -123,7 +123,6 @@ impl CustomModulesDelegationGeneratorCreator {
         let mut requests_create = Vec::with_capacity(accounts.len());
         let mut requests_publish = Vec::with_capacity(accounts.len());
         let mut requests_initialize = Vec::with_capacity(accounts.len());
-        let mut package_handler = PackageHandler::new(package_name);
         let mut packages = Vec::new();
         for account in accounts.iter_mut().take(num_modules) {
             let mut publisher = LocalAccount::generate(&mut rng);
@@ -0,0 +1,364 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{publishing::publish_util::Package, ReliableTransactionSubmitter};
+use crate::call_custom_modules::{TransactionGeneratorWorker, UserModuleTransactionGenerator};
+use anyhow::{bail, ensure, format_err, Context, Result};
+use aptos_sdk::{
+    bcs,
+    move_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::TypeTag,
+    },
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use once_cell::sync::OnceCell;
+use rand::{
+    distributions::{Alphanumeric, Distribution, Uniform},
+    rngs::StdRng,
+    Rng,
+};
+use std::{path::Path, str::FromStr, sync::Arc};
+
+/// A template for an entry function argument, which is materialized (and BCS
+/// serialized) separately for every generated transaction. Templates are parsed
+/// from strings of the form `<type>:<value>`, e.g.:
+///   - `u64:100`, `bool:true`, `address:0x1`, `string:hello` (constants)
+///   - `u64:rand:1:100` (a random u64 in the inclusive range)
+///   - `string:rand:32` or `bytes:rand:32` (random alphanumeric bytes of the given length)
+///   - `address:sender` and `address:publisher`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ArgumentTemplate {
+    Constant(Vec<u8>),
+    RandomU64 { min: u64, max: u64 },
+    RandomBytes(usize),
+    SenderAddress,
+    PublisherAddress,
+}
+
+impl ArgumentTemplate {
+    fn materialize(
+        &self,
+        rng: &mut StdRng,
+        sender: AccountAddress,
+        publisher: AccountAddress,
+    ) -> Vec<u8> {
+        let arg = match self {
+            Self::Constant(arg) => return arg.clone(),
+            Self::RandomU64 { min, max } => {
+                bcs::to_bytes(&Uniform::new_inclusive(*min, *max).sample(rng))
+            },
+            Self::RandomBytes(len) => bcs::to_bytes(
+                &rng.sample_iter(&Alphanumeric)
+                    .take(*len)
+                    .map(|c| c as u8)
+                    .collect::<Vec<u8>>(),
+            ),
+            Self::SenderAddress => bcs::to_bytes(&sender),
+            Self::PublisherAddress => bcs::to_bytes(&publisher),
+        };
+        arg.expect("Argument must serialize")
+    }
+}
+
+impl FromStr for ArgumentTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (arg_type, value) = s
+            .split_once(':')
+            .with_context(|| format!("Argument {} must be of the form <type>:<value>", s))?;
+
+        // Random arguments
+        if let Some(range) = value.strip_prefix("rand:") {
+            return match arg_type {
+                "u64" => {
+                    let (min, max) = range
+                        .split_once(':')
+                        .with_context(|| format!("Argument {} must be u64:rand:<min>:<max>", s))?;
+                    let (min, max) = (min.parse()?, max.parse()?);
+                    ensure!(min <= max, "Argument {} has min > max", s);
+                    Ok(Self::RandomU64 { min, max })
+                },
+                "bytes" | "string" => Ok(Self::RandomBytes(range.parse()?)),
+                _ => bail!("Random arguments of type {} are not supported", arg_type),
+            };
+        }
+
+        // Constant arguments
+        let arg = match (arg_type, value) {
+            ("address", "sender") => return Ok(Self::SenderAddress),
+            ("address", "publisher") => return Ok(Self::PublisherAddress),
+            ("address", value) => bcs::to_bytes(&AccountAddress::from_str(value)?),
+            ("bool", value) => bcs::to_bytes(&bool::from_str(value)?),
+            ("u8", value) => bcs::to_bytes(&u8::from_str(value)?),
+            ("u16", value) => bcs::to_bytes(&u16::from_str(value)?),
+            ("u32", value) => bcs::to_bytes(&u32::from_str(value)?),
+            ("u64", value) => bcs::to_bytes(&u64::from_str(value)?),
+            ("u128", value) => bcs::to_bytes(&u128::from_str(value)?),
+            ("string", value) => bcs::to_bytes(value),
+            _ => bail!("Arguments of type {} are not supported", arg_type),
+        };
+        Ok(Self::Constant(arg?))
+    }
+}
+
+/// The workload used by `TransactionTypeArg::EntryFunction`, see `EntryFunctionWorkload::register()`.
+static REGISTERED_WORKLOAD: OnceCell<EntryFunctionWorkload> = OnceCell::new();
+
+/// A workload that publishes a user provided Move package, and then calls one
+/// of its entry functions with arguments generated from the given templates.
+/// This allows load tests to exercise realistic contract traffic.
+#[derive(Clone, Debug)]
+pub struct EntryFunctionWorkload {
+    pub package: Package,
+    pub module_name: String,
+    pub function_name: Identifier,
+    pub type_args: Vec<TypeTag>,
+    pub args: Vec<ArgumentTemplate>,
+}
+
+impl EntryFunctionWorkload {
+    /// Creates a workload for the package in the given build directory (see
+    /// `Package::from_build_dir()`). The function must be of the form
+    /// `<module>::<function>`, and each argument must be an `ArgumentTemplate`.
+    pub fn new(
+        package_build_dir: &Path,
+        function: &str,
+        type_args: &[String],
+        args: &[String],
+    ) -> Result<Self> {
+        let package = Package::from_build_dir(package_build_dir)?;
+        let (module_name, function_name) = function.split_once("::").with_context(|| {
+            format!(
+                "Function {} must be of the form <module>::<function>",
+                function
+            )
+        })?;
+        ensure!(
+            package.contains_module(module_name),
+            "Module {} not found in package {}",
+            module_name,
+            package_build_dir.display()
+        );
+
+        Ok(Self {
+            package,
+            module_name: module_name.to_string(),
+            function_name: Identifier::new(function_name)?,
+            type_args: type_args
+                .iter()
+                .map(|type_arg| TypeTag::from_str(type_arg))
+                .collect::<Result<_>>()?,
+            args: args
+                .iter()
+                .map(|arg| ArgumentTemplate::from_str(arg))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Registers this workload for the lifetime of the process, so that it can be used
+    /// by `TransactionType::EntryFunction` (which is `Copy`), and selected on the command
+    /// line via `TransactionTypeArg::EntryFunction`. Only one workload can be registered.
+    pub fn register(self) -> Result<&'static Self> {
+        REGISTERED_WORKLOAD
+            .set(self)
+            .map_err(|_| format_err!("An entry function workload is already registered"))?;
+        Ok(Self::registered().expect("Workload was just registered"))
+    }
+
+    /// Returns the registered workload, if any.
+    pub fn registered() -> Option<&'static Self> {
+        REGISTERED_WORKLOAD.get()
+    }
+}
+
+#[async_trait]
+impl UserModuleTransactionGenerator for EntryFunctionWorkload {
+    fn initialize_package(
+        &mut self,
+        _package: &Package,
+        _publisher: &mut LocalAccount,
+        _txn_factory: &TransactionFactory,
+        _rng: &mut StdRng,
+    ) -> Vec<SignedTransaction> {
+        vec![]
+    }
+
+    async fn create_generator_fn(
+        &self,
+        _init_accounts: &mut [LocalAccount],
+        _txn_factory: &TransactionFactory,
+        _txn_executor: &dyn ReliableTransactionSubmitter,
+        _rng: &mut StdRng,
+    ) -> Arc<TransactionGeneratorWorker> {
+        let workload = self.clone();
+        Arc::new(move |account, package, publisher, txn_factory, rng| {
+            let args = workload
+                .args
+                .iter()
+                .map(|arg| arg.materialize(rng, account.address(), publisher.address()))
+                .collect();
+            let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+                package.get_module_id(&workload.module_name),
+                workload.function_name.clone(),
+                workload.type_args.clone(),
+                args,
+            ));
+            account.sign_with_transaction_builder(txn_factory.payload(payload))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{args::TransactionTypeArg, TransactionType};
+    use rand::SeedableRng;
+
+    fn parse(arg: &str) -> ArgumentTemplate {
+        ArgumentTemplate::from_str(arg).unwrap()
+    }
+
+    // Writes the given package in the layout of a build directory
+    fn write_build_dir(package: &Package, build_dir: &Path) {
+        let Package::Simple(modules, metadata) = package;
+        std::fs::write(
+            build_dir.join("package-metadata.bcs"),
+            bcs::to_bytes(metadata).unwrap(),
+        )
+        .unwrap();
+        std::fs::create_dir(build_dir.join("bytecode_modules")).unwrap();
+        for (name, module) in modules {
+            let mut module_bytes = vec![];
+            module.serialize(&mut module_bytes).unwrap();
+            std::fs::write(
+                build_dir
+                    .join("bytecode_modules")
+                    .join(format!("{}.mv", name)),
+                module_bytes,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_argument_templates() {
+        assert_eq!(
+            parse("u64:100"),
+            ArgumentTemplate::Constant(bcs::to_bytes(&100u64).unwrap())
+        );
+        assert_eq!(
+            parse("bool:true"),
+            ArgumentTemplate::Constant(bcs::to_bytes(&true).unwrap())
+        );
+        assert_eq!(
+            parse("address:0x1"),
+            ArgumentTemplate::Constant(bcs::to_bytes(&AccountAddress::ONE).unwrap())
+        );
+        assert_eq!(
+            parse("string:a:b"),
+            ArgumentTemplate::Constant(bcs::to_bytes("a:b").unwrap())
+        );
+        assert_eq!(parse("u64:rand:1:100"), ArgumentTemplate::RandomU64 {
+            min: 1,
+            max: 100
+        });
+        assert_eq!(parse("bytes:rand:32"), ArgumentTemplate::RandomBytes(32));
+        assert_eq!(parse("address:sender"), ArgumentTemplate::SenderAddress);
+        assert_eq!(
+            parse("address:publisher"),
+            ArgumentTemplate::PublisherAddress
+        );
+
+        for invalid in [
+            "100",
+            "u8:256",
+            "u64:rand:100:1",
+            "u64:rand:1",
+            "bool:rand:1",
+            "vector:0x1",
+        ] {
+            assert!(ArgumentTemplate::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_materialize_arguments() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let sender = AccountAddress::random();
+        let publisher = AccountAddress::random();
+
+        for _ in 0..100 {
+            let value: u64 =
+                bcs::from_bytes(&parse("u64:rand:5:7").materialize(&mut rng, sender, publisher))
+                    .unwrap();
+            assert!((5..=7).contains(&value));
+        }
+        let bytes: Vec<u8> =
+            bcs::from_bytes(&parse("string:rand:16").materialize(&mut rng, sender, publisher))
+                .unwrap();
+        assert_eq!(bytes.len(), 16);
+        assert!(bytes.iter().all(u8::is_ascii_alphanumeric));
+        assert_eq!(
+            parse("address:sender").materialize(&mut rng, sender, publisher),
+            bcs::to_bytes(&sender).unwrap()
+        );
+        assert_eq!(
+            parse("address:publisher").materialize(&mut rng, sender, publisher),
+            bcs::to_bytes(&publisher).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_entry_function_workload() {
+        let build_dir = tempfile::tempdir().unwrap();
+        write_build_dir(&Package::by_name("simple"), build_dir.path());
+
+        assert!(EntryFunctionWorkload::new(build_dir.path(), "loop_nop", &[], &[]).is_err());
+        assert!(
+            EntryFunctionWorkload::new(build_dir.path(), "missing::loop_nop", &[], &[]).is_err()
+        );
+        assert!(EntryFunctionWorkload::new(
+            build_dir.path(),
+            "simple::loop_nop",
+            &[],
+            &["u64:abc".to_string()]
+        )
+        .is_err());
+
+        let workload = EntryFunctionWorkload::new(
+            build_dir.path(),
+            "simple::loop_nop",
+            &[],
+            &["u64:rand:1:10".to_string()],
+        )
+        .unwrap();
+        assert_eq!(workload.module_name, "simple");
+        assert_eq!(workload.function_name.as_str(), "loop_nop");
+        assert_eq!(workload.args, vec![ArgumentTemplate::RandomU64 {
+            min: 1,
+            max: 10
+        }]);
+
+        // Once registered, the workload can be selected via TransactionTypeArg
+        let registered = workload.clone().register().unwrap();
+        assert!(workload.register().is_err());
+        match TransactionTypeArg::EntryFunction.materialize(3, true) {
+            TransactionType::EntryFunction {
+                workload,
+                num_modules,
+                use_account_pool,
+            } => {
+                assert!(std::ptr::eq(workload, registered));
+                assert_eq!(num_modules, 3);
+                assert!(use_account_pool);
+            },
+            transaction_type => panic!("Unexpected transaction type: {:?}", transaction_type),
+        }
+    }
+}
@@ -27,6 +27,7 @@ mod accounts_pool_wrapper;
 pub mod args;
 mod batch_transfer;
 mod call_custom_modules;
+mod entry_function;
 mod entry_points;
 mod p2p_transaction_generator;
 pub mod publish_modules;
@@ -43,7 +44,9 @@ use crate::{
     accounts_pool_wrapper::AccountsPoolWrapperCreator,
     batch_transfer::BatchTransferTransactionGeneratorCreator,
     entry_points::EntryPointTransactionGenerator, p2p_transaction_generator::SamplingMode,
+    publishing::publish_util::PackageHandler,
 };
+pub use entry_function::{ArgumentTemplate, EntryFunctionWorkload};
 pub use publishing::module_simple::EntryPoints;
 
 pub const SEND_AMOUNT: u64 = 1;
@@ -58,6 +61,15 @@ pub enum TransactionType {
         invalid_transaction_ratio: usize,
         sender_use_account_pool: bool,
     },
+    /// Coin transfers where `hot_fraction` of the receivers are drawn from a small set of
+    /// `num_hot_accounts` shared accounts, to load test execution under contention.
+    /// See `HotspotSampler` for how `hotness_skew` distributes the load among them.
+    HotAccountsCoinTransfer {
+        num_hot_accounts: usize,
+        hot_fraction: f64,
+        hotness_skew: f64,
+        sender_use_account_pool: bool,
+    },
     AccountGeneration {
         add_created_accounts_to_pool: bool,
         max_account_working_set: usize,
@@ -71,6 +83,14 @@ pub enum TransactionType {
         num_modules: usize,
         use_account_pool: bool,
     },
+    /// Publishes the given package once per module (i.e. publisher account), and
+    /// then calls one of its entry functions with templated arguments.
+    /// See `EntryFunctionWorkload::register()` for how the workload is provided.
+    EntryFunction {
+        workload: &'static EntryFunctionWorkload,
+        num_modules: usize,
+        use_account_pool: bool,
+    },
     BatchTransfer {
         batch_size: usize,
     },
@@ -243,6 +263,26 @@ pub async fn create_txn_generator_creator(
                     *sender_use_account_pool,
                     accounts_pool.clone(),
                 ),
+                TransactionType::HotAccountsCoinTransfer {
+                    num_hot_accounts,
+                    hot_fraction,
+                    hotness_skew,
+                    sender_use_account_pool,
+                } => wrap_accounts_pool(
+                    Box::new(P2PTransactionGeneratorCreator::new(
+                        txn_factory.clone(),
+                        SEND_AMOUNT,
+                        addresses_pool.clone(),
+                        0,
+                        SamplingMode::Hotspot {
+                            num_hot_items: *num_hot_accounts,
+                            hot_fraction: *hot_fraction,
+                            hotness_skew: *hotness_skew,
+                        },
+                    )),
+                    *sender_use_account_pool,
+                    accounts_pool.clone(),
+                ),
                 TransactionType::AccountGeneration {
                     add_created_accounts_to_pool,
                     max_account_working_set,
@@ -272,7 +312,7 @@ pub async fn create_txn_generator_creator(
                             source_accounts,
                             txn_executor,
                             *num_modules,
-                            entry_point.package_name(),
+                            PackageHandler::new(entry_point.package_name()),
                             &mut EntryPointTransactionGenerator {
                                 entry_point: *entry_point,
                             },
@@ -282,6 +322,26 @@ pub async fn create_txn_generator_creator(
                     *use_account_pool,
                     accounts_pool.clone(),
                 ),
+                TransactionType::EntryFunction {
+                    workload,
+                    num_modules,
+                    use_account_pool,
+                } => wrap_accounts_pool(
+                    Box::new(
+                        CustomModulesDelegationGeneratorCreator::new(
+                            txn_factory.clone(),
+                            init_txn_factory.clone(),
+                            source_accounts,
+                            txn_executor,
+                            *num_modules,
+                            PackageHandler::new_with_package(workload.package.clone()),
+                            &mut (*workload).clone(),
+                        )
+                        .await,
+                    ),
+                    *use_account_pool,
+                    accounts_pool.clone(),
+                ),
                 TransactionType::BatchTransfer { batch_size } => {
                     Box::new(BatchTransferTransactionGeneratorCreator::new(
                         txn_factory.clone(),
@@ -8,7 +8,7 @@ use aptos_sdk::{
     types::{chain_id::ChainId, transaction::SignedTransaction, LocalAccount},
 };
 use rand::{
-    distributions::{Distribution, Standard},
+    distributions::{Distribution, Standard, WeightedIndex},
     prelude::SliceRandom,
     rngs::StdRng,
     Rng, RngCore, SeedableRng,
@@ -23,6 +23,12 @@ pub enum SamplingMode {
     Basic,
     /// See `BurnAndRecycleSampler`.
     BurnAndRecycle(usize),
+    /// See `HotspotSampler`.
+    Hotspot {
+        num_hot_items: usize,
+        hot_fraction: f64,
+        hotness_skew: f64,
+    },
 }
 
 /// Specifies how to get a given number of samples from an item pool.
@@ -143,6 +149,93 @@ fn test_burn_and_recycle_sampler() {
     );
 }
 
+/// A sampler that directs a fraction of the samples to a small set of "hot" items, to induce
+/// conflicts between the generated transactions. The first `num_hot_items` items of the pool are
+/// the hot items. Each sample is a hot item with probability `hot_fraction`, and otherwise a
+/// uniformly random item from the rest of the pool. Samples are replaced immediately.
+///
+/// Among the hot items, item `i` is picked with probability proportional to
+/// `1 / (i + 1) ^ hotness_skew`, so a skew of 0 picks them uniformly, while larger skews
+/// concentrate the contention on the first few of them (i.e. a Zipf distribution).
+pub struct HotspotSampler {
+    num_hot_items: usize,
+    hot_fraction: f64,
+    hot_distribution: WeightedIndex<f64>,
+}
+
+impl HotspotSampler {
+    fn new(num_hot_items: usize, hot_fraction: f64, hotness_skew: f64) -> Self {
+        assert!(num_hot_items > 0, "num_hot_items must be positive");
+        assert!(
+            (0.0..=1.0).contains(&hot_fraction),
+            "hot_fraction must be in [0, 1], got {}",
+            hot_fraction
+        );
+        assert!(
+            hotness_skew >= 0.0,
+            "hotness_skew must be non-negative, got {}",
+            hotness_skew
+        );
+        let weights = (0..num_hot_items).map(|i| 1.0 / ((i + 1) as f64).powf(hotness_skew));
+        Self {
+            num_hot_items,
+            hot_fraction,
+            hot_distribution: WeightedIndex::new(weights).unwrap(),
+        }
+    }
+
+    fn sample_index(&self, rng: &mut StdRng, pool_size: usize) -> usize {
+        let num_hot_items = min(self.num_hot_items, pool_size);
+        if num_hot_items == pool_size || rng.gen_bool(self.hot_fraction) {
+            // Resample in the (unlikely) case the pool is smaller than the hot set.
+            loop {
+                let idx = self.hot_distribution.sample(rng);
+                if idx < num_hot_items {
+                    return idx;
+                }
+            }
+        } else {
+            rng.gen_range(num_hot_items, pool_size)
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync> Sampler<T> for HotspotSampler {
+    fn sample_from_pool(
+        &mut self,
+        rng: &mut StdRng,
+        pool: &mut Vec<T>,
+        num_samples: usize,
+    ) -> Vec<T> {
+        let pool_size = pool.len();
+        (0..num_samples)
+            .map(|_| pool[self.sample_index(rng, pool_size)].clone())
+            .collect()
+    }
+}
+
+#[test]
+fn test_hotspot_sampler() {
+    let mut rng = StdRng::from_entropy();
+    let mut pool: Vec<u32> = (0..1000).collect();
+
+    // All samples hit the hot items, and with a high skew mostly the first one.
+    let mut sampler = HotspotSampler::new(10, 1.0, 3.0);
+    let samples = sampler.sample_from_pool(&mut rng, &mut pool, 1000);
+    assert!(samples.iter().all(|sample| *sample < 10));
+    assert!(samples.iter().filter(|sample| **sample == 0).count() > 500);
+
+    // No samples hit the hot items.
+    let mut sampler = HotspotSampler::new(10, 0.0, 0.0);
+    let samples = sampler.sample_from_pool(&mut rng, &mut pool, 1000);
+    assert!(samples.iter().all(|sample| *sample >= 10));
+
+    // A pool smaller than the hot set.
+    let mut small_pool: Vec<u32> = (0..3).collect();
+    let samples = sampler.sample_from_pool(&mut rng, &mut small_pool, 100);
+    assert!(samples.iter().all(|sample| *sample < 3));
+}
+
 pub struct P2PTransactionGenerator {
     rng: StdRng,
     send_amount: u64,
@@ -328,6 +421,15 @@ impl TransactionGeneratorCreator for P2PTransactionGeneratorCreator {
             SamplingMode::BurnAndRecycle(recycle_batch_size) => {
                 Box::new(BurnAndRecycleSampler::new(recycle_batch_size))
             },
+            SamplingMode::Hotspot {
+                num_hot_items,
+                hot_fraction,
+                hotness_skew,
+            } => Box::new(HotspotSampler::new(
+                num_hot_items,
+                hot_fraction,
+                hotness_skew,
+            )),
         };
         Box::new(P2PTransactionGenerator::new(
             rng,
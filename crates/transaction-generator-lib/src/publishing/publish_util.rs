@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::publishing::{module_simple, raw_module_data};
+use anyhow::{Context, Result};
 use aptos_framework::{natives::code::PackageMetadata, KnownAttribute};
 use aptos_sdk::{
     bcs,
@@ -15,6 +16,7 @@ use aptos_sdk::{
 };
 use move_binary_format::{access::ModuleAccess, file_format::SignatureToken, CompiledModule};
 use rand::{rngs::StdRng, Rng};
+use std::path::Path;
 
 // Information used to track a publisher and what allows to identify and
 // version the package published.
@@ -70,6 +72,20 @@ impl PackageHandler {
         }
     }
 
+    // Create a handler for the given (e.g. user provided) package.
+    // Unlike the "simple" package, it is never versioned or scrambled.
+    pub fn new_with_package(package: Package) -> Self {
+        let packages = vec![PackageTracker {
+            publishers: vec![],
+            suffix: 0,
+            package,
+        }];
+        PackageHandler {
+            packages,
+            is_simple: false,
+        }
+    }
+
     // Return a `Package` to be published. Packages are tracked by publisher so if
     // the same `LocalAccount` is used, the package will be an upgrade of the existing one
     // otherwise a "new" package will be generated (new suffix)
@@ -125,6 +141,32 @@ impl Package {
         Self::Simple(modules, metadata)
     }
 
+    // Load a compiled package from its build directory, i.e. `build/<package_name>`
+    // as generated by `aptos move compile --save-metadata`. Modules are loaded in
+    // the order listed in the package metadata, which is the order they are published in.
+    // The package is published under a new account, so the address it was compiled
+    // with doesn't matter.
+    pub fn from_build_dir(build_dir: &Path) -> Result<Self> {
+        let metadata_path = build_dir.join("package-metadata.bcs");
+        let package_bytes = std::fs::read(&metadata_path)
+            .with_context(|| format!("Failed to read {}", metadata_path.display()))?;
+        let metadata = bcs::from_bytes::<PackageMetadata>(&package_bytes)
+            .with_context(|| format!("Failed to deserialize {}", metadata_path.display()))?;
+
+        let mut modules = Vec::new();
+        for module_metadata in &metadata.modules {
+            let module_path = build_dir
+                .join("bytecode_modules")
+                .join(format!("{}.mv", module_metadata.name));
+            let module_bytes = std::fs::read(&module_path)
+                .with_context(|| format!("Failed to read {}", module_path.display()))?;
+            let module = CompiledModule::deserialize(&module_bytes)
+                .with_context(|| format!("Failed to deserialize {}", module_path.display()))?;
+            modules.push((module_metadata.name.clone(), module));
+        }
+        Ok(Self::Simple(modules, metadata))
+    }
+
     fn load_package(
         package_bytes: &[u8],
         modules_bytes: &[Vec<u8>],
@@ -193,6 +235,12 @@ impl Package {
         }
     }
 
+    pub fn contains_module(&self, module_name: &str) -> bool {
+        match self {
+            Self::Simple(modules, _) => modules.iter().any(|(name, _)| name == module_name),
+        }
+    }
+
     pub fn get_mut_module(&mut self, module_name: &str) -> &mut CompiledModule {
         match self {
             Self::Simple(modules, _) => {
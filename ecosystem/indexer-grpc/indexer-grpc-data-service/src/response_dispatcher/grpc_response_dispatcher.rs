@@ -5,7 +5,9 @@ use aptos_indexer_grpc_data_access::{
     access_trait::{StorageReadError, StorageReadStatus, StorageTransactionRead},
     StorageClient,
 };
-use aptos_indexer_grpc_utils::{chunk_transactions, constants::MESSAGE_SIZE_LIMIT};
+use aptos_indexer_grpc_utils::{
+    chunk_transactions, constants::MESSAGE_SIZE_LIMIT, resume_token_for_transactions,
+};
 use aptos_logger::prelude::{sample, SampleRate};
 use aptos_protos::indexer::v1::TransactionsResponse;
 use std::time::Duration;
@@ -59,6 +61,10 @@ impl GrpcResponseDispatcher {
                         return Ok(responses
                             .into_iter()
                             .map(|transactions| TransactionsResponse {
+                                resume_token: resume_token_for_transactions(
+                                    &transactions,
+                                    metadata.chain_id,
+                                ),
                                 transactions,
                                 chain_id: Some(metadata.chain_id),
                             })
@@ -136,12 +142,16 @@ impl GrpcResponseDispatcher {
                 let current_response_size = response.transactions.len() as u64;
                 if current_transaction_count + current_response_size > transaction_count {
                     let remaining_transaction_count = transaction_count - current_transaction_count;
-                    let truncated_transactions = response
+                    let truncated_transactions: Vec<_> = response
                         .transactions
                         .into_iter()
                         .take(remaining_transaction_count as usize)
                         .collect();
                     processed_responses.push(TransactionsResponse {
+                        resume_token: resume_token_for_transactions(
+                            &truncated_transactions,
+                            response.chain_id.unwrap_or_default(),
+                        ),
                         transactions: truncated_transactions,
                         chain_id: response.chain_id,
                     });
@@ -19,6 +19,7 @@ use aptos_indexer_grpc_utils::{
     },
     counters::{log_grpc_step, IndexerGrpcStep},
     file_store_operator::FileStoreOperator,
+    is_resume_token_transaction, resume_token_for_transactions,
     time_diff_since_pb_timestamp_in_secs,
     types::RedisUrl,
 };
@@ -135,9 +136,13 @@ impl RawData for RawDataServerWrapper {
 
         // Response channel to stream the data to the client.
         let (tx, rx) = channel(self.data_service_response_channel_size);
-        let mut current_version = match &request.starting_version {
-            Some(version) => *version,
-            None => {
+        // A resume token takes precedence over the starting version. The stream restarts at the
+        // token's transaction so that it can be verified, and then skips it.
+        let mut resume_token = request.resume_token;
+        let mut current_version = match (&resume_token, &request.starting_version) {
+            (Some(resume_token), _) => resume_token.version,
+            (None, Some(version)) => *version,
+            (None, None) => {
                 return Result::Err(Status::aborted("Starting version is not set"));
             },
         };
@@ -242,6 +247,23 @@ impl RawData for RawDataServerWrapper {
                     return;
                 }
 
+                if let Some(token_chain_id) = resume_token.as_ref().map(|token| token.chain_id) {
+                    if token_chain_id != chain_id {
+                        ERROR_COUNT
+                            .with_label_values(&["resume_token_chain_id_mismatch"])
+                            .inc();
+                        let _result = tx
+                            .send_timeout(
+                                Err(Status::failed_precondition(
+                                    "[Data Service] Resume token was issued for a different chain.",
+                                )),
+                                RESPONSE_CHANNEL_SEND_TIMEOUT,
+                            )
+                            .await;
+                        return;
+                    }
+                }
+
                 // Data service metrics.
                 let mut tps_calculator = MovingAverage::new(MOVING_AVERAGE_WINDOW_SIZE);
                 loop {
@@ -284,6 +306,33 @@ impl RawData for RawDataServerWrapper {
                         },
                     };
 
+                    // The first batch of a resumed stream starts at the token's transaction, which
+                    // the client has already processed; verify it and skip it.
+                    if let Some(token) = resume_token.take() {
+                        let token_matches = transaction_data
+                            .first()
+                            .map_or(false, |t| is_resume_token_transaction(&token, t));
+                        if !token_matches {
+                            ERROR_COUNT
+                                .with_label_values(&["resume_token_mismatch"])
+                                .inc();
+                            let _result = tx
+                                .send_timeout(
+                                    Err(Status::failed_precondition(
+                                        "[Data Service] Resume token does not match the transaction at its version; the chain may have been reset.",
+                                    )),
+                                    RESPONSE_CHANNEL_SEND_TIMEOUT,
+                                )
+                                .await;
+                            break;
+                        }
+                        transaction_data.remove(0);
+                        if transaction_data.is_empty() {
+                            current_version = token.version + 1;
+                            continue;
+                        }
+                    }
+
                     // TODO: Unify the truncation logic for start and end.
                     if let Some(count) = transactions_count {
                         if count == 0 {
@@ -435,6 +484,7 @@ fn get_transactions_responses_builder(
         .into_iter()
         .map(|chunk| TransactionsResponse {
             chain_id: Some(chain_id as u64),
+            resume_token: resume_token_for_transactions(&chunk, chain_id as u64),
             transactions: chunk,
         })
         .collect()
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{stream_coordinator::IndexerStreamCoordinator, ServiceContext};
+use aptos_indexer_grpc_utils::{is_resume_token_transaction, resume_token_for_transactions};
 use aptos_logger::error;
 use aptos_protos::{
     indexer::v1::{raw_data_server::RawData, GetTransactionsRequest, TransactionsResponse},
@@ -40,10 +41,23 @@ impl RawData for LocalnetDataService {
         // Some node metadata
         let context = self.service_context.context.clone();
         let r = req.into_inner();
-        let starting_version = r.starting_version.expect("Starting version must be set");
+        let ledger_chain_id = context.chain_id().id();
+        // A resume token takes precedence over the starting version. The stream restarts at the
+        // token's transaction so that it can be verified, and then skips it.
+        let mut resume_token = r.resume_token;
+        let starting_version = match resume_token.as_ref() {
+            Some(resume_token) => {
+                if resume_token.chain_id != ledger_chain_id as u64 {
+                    return Err(Status::failed_precondition(
+                        "[Indexer Fullnode] Resume token was issued for a different chain.",
+                    ));
+                }
+                resume_token.version
+            },
+            None => r.starting_version.expect("Starting version must be set"),
+        };
         let processor_batch_size = self.service_context.processor_batch_size;
         let output_batch_size = self.service_context.output_batch_size;
-        let ledger_chain_id = context.chain_id().id();
         let transactions_count = r.transactions_count;
         // Creates a channel to send the stream to the client
         let (tx, mut rx) = mpsc::channel(TRANSACTION_CHANNEL_SIZE);
@@ -86,22 +100,49 @@ impl RawData for LocalnetDataService {
                     }
                 }
 
-                let response = response.map(|t| TransactionsResponse {
-                    chain_id: Some(ledger_chain_id as u64),
-                    transactions: match t.response.expect("Response must be set") {
-                        transactions_from_node_response::Response::Data(transaction_output) => {
-                            let mut transactions = transaction_output.transactions;
-                            let current_transactions_count = transactions.len() as u64;
-                            if let Some(count) = response_transactions_count.as_mut() {
-                                transactions =
-                                    transactions.into_iter().take(*count as usize).collect();
-                                *count = count.saturating_sub(current_transactions_count);
+                let response = match response {
+                    Ok(t) => {
+                        let mut transactions = match t.response.expect("Response must be set") {
+                            transactions_from_node_response::Response::Data(transaction_output) => {
+                                transaction_output.transactions
+                            },
+                            _ => panic!("Unexpected response type."),
+                        };
+                        // The first batch of a resumed stream starts at the token's transaction,
+                        // which the client has already processed; verify it and skip it.
+                        if let Some(token) = resume_token.take() {
+                            let token_matches = transactions
+                                .first()
+                                .map_or(false, |t| is_resume_token_transaction(&token, t));
+                            if !token_matches {
+                                let _ = external_service_tx
+                                    .send(Err(Status::failed_precondition(
+                                        "[Indexer Fullnode] Resume token does not match the transaction at its version; the chain may have been reset.",
+                                    )))
+                                    .await;
+                                break;
                             }
-                            transactions
-                        },
-                        _ => panic!("Unexpected response type."),
+                            transactions.remove(0);
+                            if transactions.is_empty() {
+                                continue;
+                            }
+                        }
+                        let current_transactions_count = transactions.len() as u64;
+                        if let Some(count) = response_transactions_count.as_mut() {
+                            transactions = transactions.into_iter().take(*count as usize).collect();
+                            *count = count.saturating_sub(current_transactions_count);
+                        }
+                        Ok(TransactionsResponse {
+                            chain_id: Some(ledger_chain_id as u64),
+                            resume_token: resume_token_for_transactions(
+                                &transactions,
+                                ledger_chain_id as u64,
+                            ),
+                            transactions,
+                        })
                     },
-                });
+                    Err(e) => Err(e),
+                };
                 match external_service_tx.send(response).await {
                     Ok(_) => {},
                     Err(e) => {
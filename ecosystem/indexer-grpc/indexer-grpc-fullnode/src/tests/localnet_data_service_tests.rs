@@ -0,0 +1,110 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    localnet_data_service::LocalnetDataService,
+    tests::{super_new_test_context, TestContext},
+    ServiceContext,
+};
+use aptos_api_test_context::current_function_name;
+use aptos_config::config::NodeConfig;
+use aptos_protos::{
+    indexer::v1::{raw_data_server::RawData, GetTransactionsRequest, ResumeToken},
+    transaction::v1::Transaction,
+};
+use futures::StreamExt;
+use std::sync::Arc;
+use tonic::{Code, Request, Status};
+
+async fn new_test_context(test_name: String) -> TestContext {
+    let mut test_context = super_new_test_context(test_name, NodeConfig::default(), false);
+    // Commit a block, so that there are transactions after genesis to resume from
+    let account = test_context.gen_account();
+    let txn = test_context.create_user_account(&account).await;
+    test_context.commit_block(&[txn]).await;
+    test_context
+}
+
+fn new_service(test_context: &TestContext) -> LocalnetDataService {
+    LocalnetDataService {
+        service_context: ServiceContext {
+            context: Arc::new(test_context.context.clone()),
+            processor_task_count: 1,
+            processor_batch_size: 100,
+            output_batch_size: 100,
+        },
+        enable_expensive_logging: false,
+    }
+}
+
+/// Streams `transactions_count` transactions, and returns them with the resume token of the last
+/// response.
+async fn get_transactions(
+    service: &LocalnetDataService,
+    starting_version: Option<u64>,
+    resume_token: Option<ResumeToken>,
+    transactions_count: u64,
+) -> Result<(Vec<Transaction>, ResumeToken), Status> {
+    let request = GetTransactionsRequest {
+        starting_version,
+        transactions_count: Some(transactions_count),
+        batch_size: None,
+        resume_token,
+    };
+    let mut stream = service
+        .get_transactions(Request::new(request))
+        .await?
+        .into_inner();
+    let mut transactions = vec![];
+    let mut last_resume_token = None;
+    while (transactions.len() as u64) < transactions_count {
+        let response = stream.next().await.expect("Stream ended early")?;
+        transactions.extend(response.transactions);
+        last_resume_token = response.resume_token;
+    }
+    Ok((transactions, last_resume_token.unwrap()))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_resume_from_token() {
+    let test_context = new_test_context(current_function_name!()).await;
+    let service = new_service(&test_context);
+
+    let (transactions, resume_token) = get_transactions(&service, Some(0), None, 2).await.unwrap();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(resume_token.version, 1);
+
+    // The stream continues right after the transaction of the token
+    let (transactions, _) = get_transactions(&service, None, Some(resume_token), 1)
+        .await
+        .unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].version, 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_resume_token_chain_id_mismatch() {
+    let test_context = new_test_context(current_function_name!()).await;
+    let service = new_service(&test_context);
+
+    let (_, mut resume_token) = get_transactions(&service, Some(0), None, 2).await.unwrap();
+    resume_token.chain_id += 1;
+    let status = get_transactions(&service, None, Some(resume_token), 1)
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_resume_token_hash_mismatch() {
+    let test_context = new_test_context(current_function_name!()).await;
+    let service = new_service(&test_context);
+
+    let (_, mut resume_token) = get_transactions(&service, Some(0), None, 2).await.unwrap();
+    // A different hash at the same version, e.g., after a chain reset
+    resume_token.transaction_hash = vec![0; 32];
+    let status = get_transactions(&service, None, Some(resume_token), 1)
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+}
@@ -10,7 +10,7 @@ use aptos_indexer_grpc_fullnode::stream_coordinator::{
 use aptos_indexer_grpc_utils::counters::{log_grpc_step, IndexerGrpcStep};
 use aptos_logger::{debug, error, info, sample, sample::SampleRate};
 use aptos_storage_interface::{DbReaderWriter, DbWriter};
-use aptos_types::write_set::WriteSet;
+use aptos_types::{contract_event::ContractEvent, write_set::WriteSet};
 use std::{sync::Arc, time::Duration};
 use tonic::Status;
 
@@ -281,11 +281,14 @@ impl TableInfoService {
         let first_version = raw_txns.first().map(|txn| txn.version).unwrap();
         let write_sets: Vec<WriteSet> = raw_txns.iter().map(|txn| txn.changes.clone()).collect();
         let write_sets_slice: Vec<&WriteSet> = write_sets.iter().collect();
+        let events: Vec<&[ContractEvent]> =
+            raw_txns.iter().map(|txn| txn.events.as_slice()).collect();
         db_writer
             .index_table_info(
                 context.db.clone(),
                 first_version,
                 &write_sets_slice,
+                &events,
                 end_early_if_pending_on_empty,
             )
             .expect(
@@ -11,8 +11,9 @@ pub mod types;
 
 use anyhow::{Context, Result};
 use aptos_protos::{
-    indexer::v1::raw_data_client::RawDataClient,
-    internal::fullnode::v1::fullnode_data_client::FullnodeDataClient, transaction::v1::Transaction,
+    indexer::v1::{raw_data_client::RawDataClient, ResumeToken},
+    internal::fullnode::v1::fullnode_data_client::FullnodeDataClient,
+    transaction::v1::Transaction,
     util::timestamp::Timestamp,
 };
 use prost::Message;
@@ -141,10 +142,37 @@ pub fn chunk_transactions(
     chunked_transactions
 }
 
+/// Builds the token to resume a stream right after the last of `transactions`.
+pub fn resume_token_for_transactions(
+    transactions: &[Transaction],
+    chain_id: u64,
+) -> Option<ResumeToken> {
+    transactions.last().map(|transaction| ResumeToken {
+        version: transaction.version,
+        chain_id,
+        transaction_hash: transaction
+            .info
+            .as_ref()
+            .map(|info| info.hash.clone())
+            .unwrap_or_default(),
+    })
+}
+
+/// Returns true if `transaction` is the one the resume token was issued for, i.e., same version
+/// and same hash. A hash mismatch means the chain was reset since the token was issued.
+pub fn is_resume_token_transaction(resume_token: &ResumeToken, transaction: &Transaction) -> bool {
+    transaction.version == resume_token.version
+        && transaction
+            .info
+            .as_ref()
+            .map_or(false, |info| info.hash == resume_token.transaction_hash)
+}
+
 // Tests
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aptos_protos::transaction::v1::TransactionInfo;
 
     #[test]
     fn test_chunk_the_transactions_correctly_with_large_transaction() {
@@ -184,4 +212,38 @@ mod tests {
             .sum::<usize>();
         assert!(total_count == 10);
     }
+
+    #[test]
+    fn test_resume_token_for_transactions() {
+        let transactions = (1..=3)
+            .map(|version| Transaction {
+                version,
+                info: Some(TransactionInfo {
+                    hash: vec![version as u8; 32],
+                    ..TransactionInfo::default()
+                }),
+                ..Transaction::default()
+            })
+            .collect::<Vec<_>>();
+        assert!(resume_token_for_transactions(&[], 4).is_none());
+
+        // The token points at the last transaction.
+        let resume_token = resume_token_for_transactions(&transactions, 4).unwrap();
+        assert_eq!(resume_token.version, 3);
+        assert_eq!(resume_token.chain_id, 4);
+        assert_eq!(resume_token.transaction_hash, vec![3; 32]);
+        assert!(is_resume_token_transaction(&resume_token, &transactions[2]));
+        assert!(!is_resume_token_transaction(
+            &resume_token,
+            &transactions[1]
+        ));
+
+        // Same version with a different hash, e.g., after a chain reset.
+        let mut reset_transaction = transactions[2].clone();
+        reset_transaction.info.as_mut().unwrap().hash = vec![0; 32];
+        assert!(!is_resume_token_transaction(
+            &resume_token,
+            &reset_transaction
+        ));
+    }
 }
@@ -0,0 +1,253 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{CheckResult, Checker, CheckerError, CommonCheckerConfig};
+use crate::{
+    get_provider,
+    provider::{
+        api_index::ApiIndexProvider, indexer_grpc::IndexerGrpcProvider, Provider,
+        ProviderCollection,
+    },
+};
+use anyhow::Result;
+use aptos_protos::indexer::v1::{raw_data_client::RawDataClient, GetTransactionsRequest};
+use serde::{Deserialize, Serialize};
+use tokio::time::{Duration, Instant};
+use tonic::transport::Channel;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexerGrpcCheckerConfig {
+    #[serde(flatten)]
+    pub common: CommonCheckerConfig,
+
+    /// The number of recent transactions (ending at the ledger version of the
+    /// node) to request from the indexer gRPC data service.
+    #[serde(default = "IndexerGrpcCheckerConfig::default_num_transactions")]
+    pub num_transactions: u64,
+
+    /// If streaming the transactions takes longer than this, it will fail the
+    /// evaluation.
+    #[serde(default = "IndexerGrpcCheckerConfig::default_max_stream_latency_ms")]
+    pub max_stream_latency_ms: u64,
+}
+
+impl IndexerGrpcCheckerConfig {
+    fn default_num_transactions() -> u64 {
+        10
+    }
+
+    fn default_max_stream_latency_ms() -> u64 {
+        2000
+    }
+}
+
+/// The transactions streamed from the indexer gRPC data service.
+struct StreamedTransactions {
+    versions: Vec<u64>,
+    chain_ids: Vec<u64>,
+}
+
+#[derive(Debug)]
+pub struct IndexerGrpcChecker {
+    config: IndexerGrpcCheckerConfig,
+}
+
+impl IndexerGrpcChecker {
+    pub fn new(config: IndexerGrpcCheckerConfig) -> Self {
+        Self { config }
+    }
+
+    async fn stream_transactions(
+        mut client: RawDataClient<Channel>,
+        starting_version: u64,
+        transactions_count: u64,
+    ) -> Result<StreamedTransactions> {
+        let mut stream = client
+            .get_transactions(GetTransactionsRequest {
+                starting_version: Some(starting_version),
+                transactions_count: Some(transactions_count),
+                batch_size: None,
+                resume_token: None,
+            })
+            .await?
+            .into_inner();
+
+        let mut streamed_transactions = StreamedTransactions {
+            versions: vec![],
+            chain_ids: vec![],
+        };
+        while let Some(response) = stream.message().await? {
+            streamed_transactions
+                .versions
+                .extend(response.transactions.iter().map(|txn| txn.version));
+            streamed_transactions.chain_ids.extend(response.chain_id);
+        }
+        Ok(streamed_transactions)
+    }
+
+    fn build_stream_check_result(
+        &self,
+        streamed_transactions: StreamedTransactions,
+        starting_version: u64,
+        transactions_count: u64,
+        chain_id: u64,
+        stream_latency: Duration,
+    ) -> CheckResult {
+        // Verify the data service is serving the same chain as the API.
+        if let Some(other_chain_id) = streamed_transactions
+            .chain_ids
+            .iter()
+            .find(|id| **id != chain_id)
+        {
+            return Self::build_result(
+                "Indexer gRPC data service returned the wrong chain ID".to_string(),
+                0,
+                format!(
+                    "The indexer gRPC data service returned transactions for chain ID {}, \
+                    but your node's API reports chain ID {}.",
+                    other_chain_id, chain_id
+                ),
+            );
+        }
+
+        // Verify the stream contained exactly the requested versions, in order.
+        let expected_versions: Vec<u64> =
+            (starting_version..starting_version + transactions_count).collect();
+        if streamed_transactions.versions != expected_versions {
+            return Self::build_result(
+                "Indexer gRPC data service returned incomplete data".to_string(),
+                25,
+                format!(
+                    "Requested {} transactions starting at version {} (up to the ledger \
+                    version of your node), but the indexer gRPC data service returned {} \
+                    transactions with versions {:?}.",
+                    transactions_count,
+                    starting_version,
+                    streamed_transactions.versions.len(),
+                    streamed_transactions.versions,
+                ),
+            );
+        }
+
+        let stream_latency_ms = stream_latency.as_millis() as u64;
+        if stream_latency_ms > self.config.max_stream_latency_ms {
+            Self::build_result(
+                "Indexer gRPC data service is too slow".to_string(),
+                50,
+                format!(
+                    "The indexer gRPC data service returned all {} requested transactions, \
+                    but it took {}ms, which is higher than the maximum allowed latency of {}ms.",
+                    transactions_count, stream_latency_ms, self.config.max_stream_latency_ms
+                ),
+            )
+        } else {
+            Self::build_result(
+                "Indexer gRPC data service is healthy".to_string(),
+                100,
+                format!(
+                    "The indexer gRPC data service returned all {} requested transactions \
+                    (versions {} to {}) in {}ms, which is below the maximum allowed latency \
+                    of {}ms.",
+                    transactions_count,
+                    starting_version,
+                    starting_version + transactions_count - 1,
+                    stream_latency_ms,
+                    self.config.max_stream_latency_ms
+                ),
+            )
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for IndexerGrpcChecker {
+    /// Assert that the indexer gRPC data service of the target node can stream
+    /// the most recent transactions (according to the API of the target node),
+    /// that the stream is complete, and that it is served quickly enough.
+    async fn check(
+        &self,
+        providers: &ProviderCollection,
+    ) -> Result<Vec<CheckResult>, CheckerError> {
+        let target_api_index_provider = get_provider!(
+            providers.target_api_index_provider,
+            self.config.common.required,
+            ApiIndexProvider
+        );
+        let target_indexer_grpc_provider = get_provider!(
+            providers.target_indexer_grpc_provider,
+            self.config.common.required,
+            IndexerGrpcProvider
+        );
+
+        // Get the ledger version of the target node, to know what to request.
+        let (ledger_version, chain_id) = match target_api_index_provider.provide().await {
+            Ok(response) => (response.ledger_version.0, response.chain_id as u64),
+            Err(err) => {
+                return Ok(vec![Self::build_result(
+                    "Failed to determine the ledger version of your node".to_string(),
+                    0,
+                    format!("There was an error querying your node's API: {:#}", err),
+                )]);
+            },
+        };
+        let starting_version = (ledger_version + 1).saturating_sub(self.config.num_transactions);
+        let transactions_count = ledger_version + 1 - starting_version;
+
+        let client = match target_indexer_grpc_provider.provide().await {
+            Ok(client) => client,
+            Err(err) => {
+                return Ok(vec![Self::build_result(
+                    "Failed to connect to the indexer gRPC data service".to_string(),
+                    0,
+                    format!(
+                        "There was an error connecting to the indexer gRPC data service \
+                        of your node at {}: {:#}",
+                        target_indexer_grpc_provider.url, err
+                    ),
+                )]);
+            },
+        };
+
+        // Stream the transactions. We give up once the latency limit is well exceeded,
+        // since at that point the stream is most likely stuck.
+        let start = Instant::now();
+        let stream_result = tokio::time::timeout(
+            Duration::from_millis(self.config.max_stream_latency_ms * 2),
+            Self::stream_transactions(client, starting_version, transactions_count),
+        )
+        .await;
+        let stream_latency = start.elapsed();
+
+        let check_result = match stream_result {
+            Ok(Ok(streamed_transactions)) => self.build_stream_check_result(
+                streamed_transactions,
+                starting_version,
+                transactions_count,
+                chain_id,
+                stream_latency,
+            ),
+            Ok(Err(err)) => Self::build_result(
+                "Failed to stream transactions from the indexer gRPC data service".to_string(),
+                0,
+                format!(
+                    "There was an error streaming transactions {} to {} from the indexer \
+                    gRPC data service of your node: {:#}",
+                    starting_version, ledger_version, err
+                ),
+            ),
+            Err(_) => Self::build_result(
+                "Indexer gRPC data service timed out".to_string(),
+                0,
+                format!(
+                    "The indexer gRPC data service of your node did not finish streaming \
+                    transactions {} to {} within {}ms.",
+                    starting_version,
+                    ledger_version,
+                    stream_latency.as_millis()
+                ),
+            ),
+        };
+        Ok(vec![check_result])
+    }
+}
@@ -7,9 +7,11 @@ mod consensus_round;
 mod consensus_timeouts;
 mod handshake;
 mod hardware;
+mod indexer_grpc;
 mod latency;
 mod minimum_peers;
 mod node_identity;
+mod state_sync_lag;
 mod state_sync_version;
 mod tps;
 mod traits;
@@ -23,9 +25,11 @@ use self::{
     consensus_timeouts::{ConsensusTimeoutsChecker, ConsensusTimeoutsCheckerConfig},
     handshake::{HandshakeChecker, HandshakeCheckerConfig},
     hardware::{HardwareChecker, HardwareCheckerConfig},
+    indexer_grpc::{IndexerGrpcChecker, IndexerGrpcCheckerConfig},
     latency::{LatencyChecker, LatencyCheckerConfig},
     minimum_peers::{MinimumPeersChecker, MinimumPeersCheckerConfig},
     node_identity::{NodeIdentityChecker, NodeIdentityCheckerConfig},
+    state_sync_lag::{StateSyncLagChecker, StateSyncLagCheckerConfig},
     state_sync_version::{StateSyncVersionChecker, StateSyncVersionCheckerConfig},
     tps::{TpsChecker, TpsCheckerConfig},
     transaction_correctness::{TransactionCorrectnessChecker, TransactionCorrectnessCheckerConfig},
@@ -46,9 +50,11 @@ pub enum CheckerConfig {
     ConsensusTimeouts(ConsensusTimeoutsCheckerConfig),
     Handshake(HandshakeCheckerConfig),
     Hardware(HardwareCheckerConfig),
+    IndexerGrpc(IndexerGrpcCheckerConfig),
     Latency(LatencyCheckerConfig),
     MinimumPeers(MinimumPeersCheckerConfig),
     NodeIdentity(NodeIdentityCheckerConfig),
+    StateSyncLag(StateSyncLagCheckerConfig),
     StateSyncVersion(StateSyncVersionCheckerConfig),
     Tps(TpsCheckerConfig),
     TransactionCorrectness(TransactionCorrectnessCheckerConfig),
@@ -65,9 +71,11 @@ impl CheckerConfig {
             Self::ConsensusTimeouts(config) => Ok(Box::new(ConsensusTimeoutsChecker::new(config))),
             Self::Handshake(config) => Ok(Box::new(HandshakeChecker::new(config))),
             Self::Hardware(config) => Ok(Box::new(HardwareChecker::new(config))),
+            Self::IndexerGrpc(config) => Ok(Box::new(IndexerGrpcChecker::new(config))),
             Self::Latency(config) => Ok(Box::new(LatencyChecker::new(config))),
             Self::MinimumPeers(config) => Ok(Box::new(MinimumPeersChecker::new(config))),
             Self::NodeIdentity(config) => Ok(Box::new(NodeIdentityChecker::new(config))),
+            Self::StateSyncLag(config) => Ok(Box::new(StateSyncLagChecker::new(config))),
             Self::StateSyncVersion(config) => Ok(Box::new(StateSyncVersionChecker::new(config))),
             Self::Tps(config) => Ok(Box::new(TpsChecker::new(config)?)),
             Self::TransactionCorrectness(config) => {
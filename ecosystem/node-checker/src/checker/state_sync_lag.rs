@@ -0,0 +1,212 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{CheckResult, Checker, CheckerError, CommonCheckerConfig};
+use crate::{
+    get_provider,
+    provider::{api_index::ApiIndexProvider, Provider, ProviderCollection},
+};
+use anyhow::Result;
+use aptos_rest_client::aptos_api_types::IndexResponse;
+use serde::{Deserialize, Serialize};
+
+/// This checker compares the API index of the target node against that of
+/// the baseline node. Like the StateSyncVersionChecker, it measures the lag
+/// using the ledger version the node has committed (as reported by its API),
+/// rather than the state sync version metrics. The oldest ledger version in
+/// the index (the oldest version that has not been pruned yet) is used to
+/// evaluate how much of the history the target can serve.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateSyncLagCheckerConfig {
+    #[serde(flatten)]
+    pub common: CommonCheckerConfig,
+
+    /// The maximum number of versions the target node may be behind the
+    /// baseline node.
+    #[serde(default = "StateSyncLagCheckerConfig::default_max_version_lag")]
+    pub max_version_lag: u64,
+
+    /// The maximum number of seconds the ledger timestamp of the target node
+    /// may be behind that of the baseline node.
+    #[serde(default = "StateSyncLagCheckerConfig::default_max_timestamp_lag_secs")]
+    pub max_timestamp_lag_secs: u64,
+
+    /// The minimum number of versions the target node must retain (i.e. not
+    /// have pruned), relative to the number retained by the baseline node.
+    /// For example, 0.5 means the target must serve at least half as much
+    /// history as the baseline.
+    #[serde(default = "StateSyncLagCheckerConfig::default_min_history_ratio")]
+    pub min_history_ratio: f64,
+}
+
+impl StateSyncLagCheckerConfig {
+    fn default_max_version_lag() -> u64 {
+        5000
+    }
+
+    fn default_max_timestamp_lag_secs() -> u64 {
+        30
+    }
+
+    fn default_min_history_ratio() -> f64 {
+        0.5
+    }
+}
+
+#[derive(Debug)]
+pub struct StateSyncLagChecker {
+    config: StateSyncLagCheckerConfig,
+}
+
+impl StateSyncLagChecker {
+    pub fn new(config: StateSyncLagCheckerConfig) -> Self {
+        Self { config }
+    }
+
+    fn build_sync_lag_check_result(
+        &self,
+        target_response: &IndexResponse,
+        baseline_response: &IndexResponse,
+    ) -> CheckResult {
+        let target_version = target_response.ledger_version.0;
+        let baseline_version = baseline_response.ledger_version.0;
+        let version_lag = baseline_version.saturating_sub(target_version);
+
+        // The ledger timestamps are in microseconds.
+        let timestamp_lag_secs = baseline_response
+            .ledger_timestamp
+            .0
+            .saturating_sub(target_response.ledger_timestamp.0)
+            / 1_000_000;
+
+        if version_lag > self.config.max_version_lag
+            || timestamp_lag_secs > self.config.max_timestamp_lag_secs
+        {
+            Self::build_result(
+                "Node is lagging behind the baseline".to_string(),
+                50,
+                format!(
+                    "Your node is {} versions ({} seconds) behind the baseline node, \
+                    which is more than the allowed lag of {} versions ({} seconds). \
+                    Target version: {}. Baseline version: {}.",
+                    version_lag,
+                    timestamp_lag_secs,
+                    self.config.max_version_lag,
+                    self.config.max_timestamp_lag_secs,
+                    target_version,
+                    baseline_version,
+                ),
+            )
+        } else {
+            Self::build_result(
+                "Node is in sync with the baseline".to_string(),
+                100,
+                format!(
+                    "Your node is {} versions ({} seconds) behind the baseline node, \
+                    which is within the allowed lag of {} versions ({} seconds). \
+                    Target version: {}. Baseline version: {}.",
+                    version_lag,
+                    timestamp_lag_secs,
+                    self.config.max_version_lag,
+                    self.config.max_timestamp_lag_secs,
+                    target_version,
+                    baseline_version,
+                ),
+            )
+        }
+    }
+
+    fn build_data_completeness_check_result(
+        &self,
+        target_response: &IndexResponse,
+        baseline_response: &IndexResponse,
+    ) -> CheckResult {
+        let target_oldest_version = target_response.oldest_ledger_version.0;
+        let baseline_oldest_version = baseline_response.oldest_ledger_version.0;
+
+        // Compare the history the nodes retain up to the same (target) version,
+        // so that sync lag does not count against data completeness.
+        let target_version = target_response.ledger_version.0;
+        let target_history = target_version.saturating_sub(target_oldest_version);
+        let baseline_history = target_version.saturating_sub(baseline_oldest_version);
+
+        let history_ratio = if baseline_history == 0 {
+            1.0
+        } else {
+            target_history as f64 / baseline_history as f64
+        };
+
+        if history_ratio < self.config.min_history_ratio {
+            Self::build_result(
+                "Node has pruned too much history".to_string(),
+                50,
+                format!(
+                    "Your node has pruned all versions before {}, while the baseline node \
+                    retains versions from {}. Your node retains {:.2} times as much \
+                    history as the baseline, which is below the minimum of {:.2}.",
+                    target_oldest_version,
+                    baseline_oldest_version,
+                    history_ratio,
+                    self.config.min_history_ratio,
+                ),
+            )
+        } else {
+            Self::build_result(
+                "Node retains enough history".to_string(),
+                100,
+                format!(
+                    "Your node retains versions from {}, and the baseline node retains \
+                    versions from {}. Your node retains {:.2} times as much history as \
+                    the baseline, which meets the minimum of {:.2}.",
+                    target_oldest_version,
+                    baseline_oldest_version,
+                    history_ratio,
+                    self.config.min_history_ratio,
+                ),
+            )
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for StateSyncLagChecker {
+    /// Assert that the target node is within tolerance of the latest version
+    /// of the baseline node, and that it has not pruned significantly more
+    /// history than the baseline node.
+    async fn check(
+        &self,
+        providers: &ProviderCollection,
+    ) -> Result<Vec<CheckResult>, CheckerError> {
+        let baseline_api_index_provider = get_provider!(
+            providers.baseline_api_index_provider,
+            self.config.common.required,
+            ApiIndexProvider
+        );
+        let target_api_index_provider = get_provider!(
+            providers.target_api_index_provider,
+            self.config.common.required,
+            ApiIndexProvider
+        );
+
+        let target_response = match target_api_index_provider.provide().await {
+            Ok(response) => response,
+            Err(err) => {
+                return Ok(vec![Self::build_result(
+                    "Failed to determine the ledger summary of your node".to_string(),
+                    0,
+                    format!("There was an error querying your node's API: {:#}", err),
+                )]);
+            },
+        };
+
+        // If we cannot query the baseline node, we return an error instead
+        // of a negative evaluation, since it is not the fault of the target.
+        let baseline_response = baseline_api_index_provider.provide().await?;
+
+        Ok(vec![
+            self.build_sync_lag_check_result(&target_response, &baseline_response),
+            self.build_data_completeness_check_result(&target_response, &baseline_response),
+        ])
+    }
+}
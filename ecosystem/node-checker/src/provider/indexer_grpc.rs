@@ -0,0 +1,79 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Like the noise Provider, this Provider just makes it possible to connect to the
+//! indexer gRPC data service of the node. It is up to the Checker to make requests.
+
+use super::{
+    traits::{Provider, ProviderError},
+    CommonProviderConfig,
+};
+use anyhow::Result;
+use aptos_protos::indexer::v1::raw_data_client::RawDataClient;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tonic::transport::{Channel, Endpoint};
+use url::Url;
+
+const INDEXER_GRPC_ENDPOINT: &str = "indexer gRPC data service";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexerGrpcProviderConfig {
+    #[serde(default, flatten)]
+    pub common: CommonProviderConfig,
+
+    /// How long to wait for a connection to be established with the node.
+    #[serde(default = "IndexerGrpcProviderConfig::default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+}
+
+impl IndexerGrpcProviderConfig {
+    fn default_connect_timeout_ms() -> u64 {
+        4000
+    }
+}
+
+impl Default for IndexerGrpcProviderConfig {
+    fn default() -> Self {
+        Self {
+            common: CommonProviderConfig::default(),
+            connect_timeout_ms: Self::default_connect_timeout_ms(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexerGrpcProvider {
+    pub config: IndexerGrpcProviderConfig,
+
+    /// The URL (including the port) of the indexer gRPC data service.
+    pub url: Url,
+}
+
+impl IndexerGrpcProvider {
+    pub fn new(config: IndexerGrpcProviderConfig, url: Url) -> Self {
+        Self { config, url }
+    }
+}
+
+#[async_trait]
+impl Provider for IndexerGrpcProvider {
+    type Output = RawDataClient<Channel>;
+
+    async fn provide(&self) -> Result<Self::Output, ProviderError> {
+        let endpoint = Endpoint::from_shared(self.url.to_string())
+            .map_err(|e| ProviderError::NonRetryableEndpointError(INDEXER_GRPC_ENDPOINT, e.into()))?
+            .connect_timeout(Duration::from_millis(self.config.connect_timeout_ms));
+        let channel = endpoint
+            .connect()
+            .await
+            .map_err(|e| ProviderError::RetryableEndpointError(INDEXER_GRPC_ENDPOINT, e.into()))?;
+        Ok(RawDataClient::new(channel))
+    }
+
+    fn explanation() -> &'static str {
+        "The indexer gRPC port was not included in the request."
+    }
+}
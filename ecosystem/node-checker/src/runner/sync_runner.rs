@@ -6,8 +6,9 @@ use crate::{
     checker::{CheckResult, Checker, CheckerError},
     configuration::NodeAddress,
     provider::{
-        api_index::ApiIndexProvider, metrics::MetricsProvider, noise::NoiseProvider,
-        system_information::SystemInformationProvider, ProviderCollection, ProviderConfigs,
+        api_index::ApiIndexProvider, indexer_grpc::IndexerGrpcProvider, metrics::MetricsProvider,
+        noise::NoiseProvider, system_information::SystemInformationProvider, ProviderCollection,
+        ProviderConfigs,
     },
     CheckSummary,
 };
@@ -152,6 +153,14 @@ impl Runner for SyncRunner {
             }
         }
 
+        // Build the IndexerGrpcProvider for the target node.
+        if let Ok(indexer_grpc_url) = target_node_address.get_indexer_grpc_url() {
+            provider_collection.target_indexer_grpc_provider = Some(IndexerGrpcProvider::new(
+                self.provider_configs.indexer_grpc.clone(),
+                indexer_grpc_url,
+            ));
+        }
+
         // Call each of the Checkers without awaiting them yet.
         let mut futures = Vec::new();
         for checker in &self.checkers {
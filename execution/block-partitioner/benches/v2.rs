@@ -8,9 +8,11 @@ extern crate criterion;
 
 use aptos_block_partitioner::{
     pre_partition::connected_component::ConnectedComponentPartitioner,
-    test_utils::P2PBlockGenerator, v2::PartitionerV2, BlockPartitioner,
+    test_utils::{BlockGenerator, ConflictPattern},
+    v2::PartitionerV2,
+    BlockPartitioner,
 };
-use criterion::Criterion;
+use criterion::{Criterion, Throughput};
 use rand::thread_rng;
 
 fn bench_group(c: &mut Criterion) {
@@ -27,7 +29,6 @@ fn bench_group(c: &mut Criterion) {
     let merge_discards = true;
 
     let mut rng = thread_rng();
-    let block_gen = P2PBlockGenerator::new(num_accounts);
     let partitioner = PartitionerV2::new(
         num_threads,
         num_rounds_limit,
@@ -38,16 +39,32 @@ fn bench_group(c: &mut Criterion) {
             load_imbalance_tolerance: 2.0,
         }),
     );
-    group.bench_function(format!("acc={num_accounts},blk={block_size},shd={num_shards}/thr={num_threads},rnd={num_rounds_limit},avd={avoid_pct},mds={merge_discards}"), move |b| {
-        b.iter_with_setup(
-            || {
-                block_gen.rand_block(&mut rng, block_size)
-            },
-            |txns| {
-                let _txns = partitioner.partition(txns, num_shards);
-            },
-        )
-    });
+    group.throughput(Throughput::Elements(block_size as u64));
+    for conflict_pattern in [
+        ConflictPattern::P2P,
+        ConflictPattern::HotAccounts {
+            num_hot_accounts: 10,
+            hot_pct: 50,
+        },
+        ConflictPattern::DependencyChains { chain_length: 100 },
+        ConflictPattern::ModulePublishes {
+            publish_pct: 10,
+            num_modules: 20,
+        },
+    ] {
+        let block_gen = BlockGenerator::new(num_accounts).with_conflict_pattern(conflict_pattern);
+
+        group.bench_function(format!("{}/acc={num_accounts},blk={block_size},shd={num_shards}/thr={num_threads},rnd={num_rounds_limit},avd={avoid_pct},mds={merge_discards}", conflict_pattern.name()), |b| {
+            b.iter_with_setup(
+                || {
+                    block_gen.rand_block(&mut rng, block_size)
+                },
+                |txns| {
+                    let _txns = partitioner.partition(txns, num_shards);
+                },
+            )
+        });
+    }
     group.finish();
 }
 
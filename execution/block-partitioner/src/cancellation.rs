@@ -0,0 +1,30 @@
+// Copyright © Aptos Foundation
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag shared between the caller of `BlockPartitioner::try_partition()` and the in-flight partitioning job.
+///
+/// Once `cancel()` is called, the partitioner gives up at the next phase/round boundary,
+/// returns `None` and releases its worker threads, instead of finishing a result nobody will use.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the partitioning job(s) holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
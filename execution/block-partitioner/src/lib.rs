@@ -2,10 +2,12 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod cancellation;
 pub mod v2;
 
 pub mod test_utils;
 
+use crate::cancellation::CancellationToken;
 use aptos_types::{
     block_executor::partitioner::{PartitionedTransactions, ShardId},
     transaction::analyzed_transaction::{AnalyzedTransaction, StorageLocation},
@@ -32,6 +34,22 @@ pub trait BlockPartitioner: Send {
         transactions: Vec<AnalyzedTransaction>,
         num_shards: usize, //TODO: rethink about whether this is needed as part of `BlockPartitioner` API.
     ) -> PartitionedTransactions;
+
+    /// Same as `partition()`, but gives up early and returns `None` once `cancellation_token` is cancelled.
+    ///
+    /// The default implementation only checks the token before starting.
+    /// Implementations that can take long should override it and check the token between their phases.
+    fn try_partition(
+        &self,
+        transactions: Vec<AnalyzedTransaction>,
+        num_shards: usize,
+        cancellation_token: &CancellationToken,
+    ) -> Option<PartitionedTransactions> {
+        if cancellation_token.is_cancelled() {
+            return None;
+        }
+        Some(self.partition(transactions, num_shards))
+    }
 }
 
 /// When multiple transactions access the same storage location,
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 
 use aptos_block_partitioner::{
-    test_utils::P2PBlockGenerator, v2::config::PartitionerV2Config, PartitionerConfig,
+    test_utils::BlockGenerator, v2::config::PartitionerV2Config, PartitionerConfig,
 };
 use aptos_logger::info;
 use clap::Parser;
@@ -31,7 +31,7 @@ fn main() {
     aptos_logger::Logger::new().init();
     info!("Starting the block partitioning benchmark");
     let args = Args::parse();
-    let block_gen = P2PBlockGenerator::new(args.num_accounts);
+    let block_gen = BlockGenerator::new(args.num_accounts);
     let partitioner = PartitionerV2Config::default()
         .max_partitioning_rounds(4)
         .num_threads(8)
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 
 #[cfg(test)]
-use crate::test_utils::P2PBlockGenerator;
+use crate::test_utils::BlockGenerator;
 use crate::{
     pre_partition::PrePartitioner,
     v2::{
@@ -57,7 +57,7 @@ impl PrePartitioner for UniformPartitioner {
 
 #[test]
 fn test_uniform_partitioner() {
-    let block_gen = P2PBlockGenerator::new(10);
+    let block_gen = BlockGenerator::new(10);
     let mut rng = thread_rng();
     let txns = block_gen.rand_block(&mut rng, 18);
     let partitioner = UniformPartitioner {};
@@ -22,13 +22,15 @@ use aptos_types::block_executor::partitioner::TransactionWithDependencies;
 use aptos_types::block_executor::partitioner::GLOBAL_ROUND_ID;
 #[cfg(test)]
 use aptos_types::block_executor::partitioner::GLOBAL_SHARD_ID;
-#[cfg(test)]
-use aptos_types::state_store::state_key::StateKey;
 use aptos_types::{
+    access_path::AccessPath,
     chain_id::ChainId,
+    state_store::state_key::StateKey,
     transaction::{
-        analyzed_transaction::AnalyzedTransaction, EntryFunction, RawTransaction,
-        SignedTransaction, Transaction, TransactionPayload,
+        analyzed_transaction::{
+            account_resource_location, coin_store_location, AnalyzedTransaction, StorageLocation,
+        },
+        EntryFunction, RawTransaction, SignedTransaction, Transaction, TransactionPayload,
     },
     utility_coin::APTOS_COIN_TYPE,
 };
@@ -90,32 +92,118 @@ pub fn create_signed_p2p_transaction(
                 bcs::to_bytes(&1u64).unwrap(),
             ],
         ));
-
-        let raw_transaction = RawTransaction::new(
-            sender.account_address,
-            sender.sequence_number,
-            transaction_payload,
-            0,
-            0,
-            0,
-            ChainId::new(10),
-        );
-        sender.sequence_number += 1;
-        let txn = Transaction::UserTransaction(SignedTransaction::new(
-            raw_transaction.clone(),
-            sender.private_key.public_key().clone(),
-            sender.private_key.sign(&raw_transaction).unwrap(),
-        ));
-        transactions.push(txn.into())
+        transactions.push(create_signed_transaction(sender, transaction_payload).into())
     }
     transactions
 }
 
-pub struct P2PBlockGenerator {
+/// Creates a (non-P2P) transaction that publishes `num_modules` modules at the sender address.
+/// Such transactions can't be analyzed yet, so the hints are derived from the published modules
+/// (written) and the modules of the `dependency` package (read), if any.
+pub fn create_signed_module_publish_transaction(
+    sender: &mut TestAccount,
+    num_modules: usize,
+    dependency: Option<AccountAddress>,
+) -> AnalyzedTransaction {
+    let transaction_payload = TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(AccountAddress::ONE, Identifier::new("code").unwrap()),
+        Identifier::new("publish_package_txn").unwrap(),
+        vec![],
+        vec![],
+    ));
+    let mut write_hints = vec![
+        account_resource_location(sender.account_address),
+        coin_store_location(sender.account_address),
+    ];
+    write_hints.extend(module_locations(sender.account_address, num_modules));
+    let read_hints = dependency
+        .map(|address| module_locations(address, num_modules).collect())
+        .unwrap_or_default();
+    let transaction = create_signed_transaction(sender, transaction_payload);
+    AnalyzedTransaction::new_with_hints(transaction.into(), read_hints, write_hints)
+}
+
+fn module_locations(
+    address: AccountAddress,
+    num_modules: usize,
+) -> impl Iterator<Item = StorageLocation> {
+    (0..num_modules).map(move |i| {
+        let module_id = ModuleId::new(address, Identifier::new(format!("module_{}", i)).unwrap());
+        StorageLocation::Specific(StateKey::access_path(AccessPath::code_access_path(
+            module_id,
+        )))
+    })
+}
+
+fn create_signed_transaction(
+    sender: &mut TestAccount,
+    transaction_payload: TransactionPayload,
+) -> Transaction {
+    let raw_transaction = RawTransaction::new(
+        sender.account_address,
+        sender.sequence_number,
+        transaction_payload,
+        0,
+        0,
+        0,
+        ChainId::new(10),
+    );
+    sender.sequence_number += 1;
+    Transaction::UserTransaction(SignedTransaction::new(
+        raw_transaction.clone(),
+        sender.private_key.public_key().clone(),
+        sender.private_key.sign(&raw_transaction).unwrap(),
+    ))
+}
+
+/// The conflict pattern of the blocks generated by a [`BlockGenerator`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictPattern {
+    /// P2P transfers between uniformly random accounts.
+    P2P,
+    /// P2P transfers, where `hot_pct` percent of the transfers go to one of the first
+    /// `num_hot_accounts` accounts (i.e., the hot accounts).
+    HotAccounts {
+        num_hot_accounts: usize,
+        hot_pct: u8,
+    },
+    /// Chains of P2P transfers, where the receiver of each transfer is the sender of the
+    /// next transfer in the chain (i.e., each transfer depends on all previous ones).
+    DependencyChains { chain_length: usize },
+    /// P2P transfers, where `publish_pct` percent of the transactions are replaced by module
+    /// publishes. Each publish writes `num_modules` modules, and depends on (i.e., reads all
+    /// modules of) a random earlier publish in the block.
+    ModulePublishes { publish_pct: u8, num_modules: usize },
+}
+
+impl ConflictPattern {
+    /// A short name of the pattern (e.g., for benchmark ids)
+    pub fn name(&self) -> String {
+        match self {
+            ConflictPattern::P2P => "p2p".to_string(),
+            ConflictPattern::HotAccounts {
+                num_hot_accounts,
+                hot_pct,
+            } => format!("hot={num_hot_accounts},pct={hot_pct}"),
+            ConflictPattern::DependencyChains { chain_length } => {
+                format!("chain={chain_length}")
+            },
+            ConflictPattern::ModulePublishes {
+                publish_pct,
+                num_modules,
+            } => format!("publish_pct={publish_pct},modules={num_modules}"),
+        }
+    }
+}
+
+/// Generates random blocks of transactions with a configurable conflict pattern
+pub struct BlockGenerator {
     accounts: Arc<Vec<Mutex<TestAccount>>>,
+    conflict_pattern: ConflictPattern,
 }
 
-impl P2PBlockGenerator {
+impl BlockGenerator {
+    /// Creates a generator of P2P transfers between `num_accounts` accounts
     pub fn new(num_accounts: usize) -> Self {
         let accounts = (0..num_accounts)
             .into_par_iter()
@@ -123,21 +211,100 @@ impl P2PBlockGenerator {
             .collect();
         Self {
             accounts: Arc::new(accounts),
+            conflict_pattern: ConflictPattern::P2P,
+        }
+    }
+
+    pub fn with_conflict_pattern(mut self, conflict_pattern: ConflictPattern) -> Self {
+        if let ConflictPattern::HotAccounts {
+            num_hot_accounts, ..
+        } = conflict_pattern
+        {
+            assert!(self.accounts.len() > 1);
+            assert!(num_hot_accounts > 0 && num_hot_accounts <= self.accounts.len());
         }
+        if let ConflictPattern::DependencyChains { chain_length } = conflict_pattern {
+            assert!(chain_length > 1 && chain_length <= self.accounts.len());
+        }
+        self.conflict_pattern = conflict_pattern;
+        self
     }
 
     pub fn rand_block<R>(&self, rng: &mut R, block_size: usize) -> Vec<AnalyzedTransaction>
     where
         R: Rng,
     {
-        (0..block_size)
-            .map(|_| {
-                let indices = rand::seq::index::sample(rng, self.accounts.len(), 2);
-                let receiver = self.accounts[indices.index(1)].lock().unwrap();
-                let mut sender = self.accounts[indices.index(0)].lock().unwrap();
-                create_signed_p2p_transaction(&mut sender, vec![&receiver]).remove(0)
-            })
-            .collect()
+        match self.conflict_pattern {
+            ConflictPattern::P2P => (0..block_size)
+                .map(|_| {
+                    let indices = rand::seq::index::sample(rng, self.accounts.len(), 2);
+                    self.p2p_transaction(indices.index(0), indices.index(1))
+                })
+                .collect(),
+            ConflictPattern::HotAccounts {
+                num_hot_accounts,
+                hot_pct,
+            } => (0..block_size)
+                .map(|_| {
+                    let receiver_idx = if rng.gen_range(0, 100) < hot_pct {
+                        rng.gen_range(0, num_hot_accounts)
+                    } else {
+                        rng.gen_range(0, self.accounts.len())
+                    };
+                    // Pick the sender among the other accounts, so that no retries are needed
+                    // (even if all transfers go to a single hot account).
+                    let mut sender_idx = rng.gen_range(0, self.accounts.len() - 1);
+                    if sender_idx >= receiver_idx {
+                        sender_idx += 1;
+                    }
+                    self.p2p_transaction(sender_idx, receiver_idx)
+                })
+                .collect(),
+            ConflictPattern::DependencyChains { chain_length } => {
+                let mut transactions = Vec::with_capacity(block_size);
+                while transactions.len() < block_size {
+                    let chain = rand::seq::index::sample(rng, self.accounts.len(), chain_length);
+                    let chain = chain.into_vec();
+                    for pair in chain.windows(2).take(block_size - transactions.len()) {
+                        transactions.push(self.p2p_transaction(pair[0], pair[1]));
+                    }
+                }
+                transactions
+            },
+            ConflictPattern::ModulePublishes {
+                publish_pct,
+                num_modules,
+            } => {
+                let mut publishers = vec![];
+                (0..block_size)
+                    .map(|_| {
+                        let indices = rand::seq::index::sample(rng, self.accounts.len(), 2);
+                        if rng.gen_range(0, 100) < publish_pct {
+                            let dependency = if publishers.is_empty() {
+                                None
+                            } else {
+                                Some(publishers[rng.gen_range(0, publishers.len())])
+                            };
+                            let mut sender = self.accounts[indices.index(0)].lock().unwrap();
+                            publishers.push(sender.account_address);
+                            create_signed_module_publish_transaction(
+                                &mut sender,
+                                num_modules,
+                                dependency,
+                            )
+                        } else {
+                            self.p2p_transaction(indices.index(0), indices.index(1))
+                        }
+                    })
+                    .collect()
+            },
+        }
+    }
+
+    fn p2p_transaction(&self, sender_idx: usize, receiver_idx: usize) -> AnalyzedTransaction {
+        let receiver = self.accounts[receiver_idx].lock().unwrap();
+        let mut sender = self.accounts[sender_idx].lock().unwrap();
+        create_signed_p2p_transaction(&mut sender, vec![&receiver]).remove(0)
     }
 }
 
@@ -322,7 +489,7 @@ fn is_sorted(arr: &Vec<usize>) -> bool {
 #[cfg(test)]
 pub fn assert_deterministic_result(partitioner: Arc<dyn BlockPartitioner>) {
     let mut rng = thread_rng();
-    let block_gen = P2PBlockGenerator::new(1000);
+    let block_gen = BlockGenerator::new(1000);
     for _ in 0..10 {
         let txns = block_gen.rand_block(&mut rng, 100);
         let result_0 = partitioner.partition(txns.clone(), 10);
@@ -15,6 +15,7 @@ pub struct PartitionerV2Config {
     pub cross_shard_dep_avoid_threshold: f32,
     pub dashmap_num_shards: usize,
     pub partition_last_round: bool,
+    pub prune_redundant_dependent_edges: bool,
     pub pre_partitioner_config: Box<dyn PrePartitionerConfig>,
 }
 
@@ -44,6 +45,11 @@ impl PartitionerV2Config {
         self
     }
 
+    pub fn prune_redundant_dependent_edges(mut self, val: bool) -> Self {
+        self.prune_redundant_dependent_edges = val;
+        self
+    }
+
     pub fn pre_partitioner_config(mut self, val: Box<dyn PrePartitionerConfig>) -> Self {
         self.pre_partitioner_config = val;
         self
@@ -58,6 +64,7 @@ impl Default for PartitionerV2Config {
             cross_shard_dep_avoid_threshold: 0.9,
             dashmap_num_shards: 64,
             partition_last_round: false,
+            prune_redundant_dependent_edges: false,
             pre_partitioner_config: Box::<ConnectedComponentPartitionerConfig>::default(),
         }
     }
@@ -66,13 +73,16 @@ impl Default for PartitionerV2Config {
 impl PartitionerConfig for PartitionerV2Config {
     fn build(&self) -> Box<dyn BlockPartitioner> {
         let pre_partitioner = self.pre_partitioner_config.build();
-        Box::new(PartitionerV2::new(
-            self.num_threads,
-            self.max_partitioning_rounds,
-            self.cross_shard_dep_avoid_threshold,
-            self.dashmap_num_shards,
-            self.partition_last_round,
-            pre_partitioner,
-        ))
+        Box::new(
+            PartitionerV2::new(
+                self.num_threads,
+                self.max_partitioning_rounds,
+                self.cross_shard_dep_avoid_threshold,
+                self.dashmap_num_shards,
+                self.partition_last_round,
+                pre_partitioner,
+            )
+            .prune_redundant_dependent_edges(self.prune_redundant_dependent_edges),
+        )
     }
 }
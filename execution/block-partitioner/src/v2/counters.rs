@@ -1,7 +1,8 @@
 // Copyright © Aptos Foundation
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram, register_histogram_vec, Histogram, HistogramVec,
+    exponential_buckets, register_histogram, register_histogram_vec, register_int_counter_vec,
+    Histogram, HistogramVec, IntCounterVec,
 };
 use once_cell::sync::Lazy;
 
@@ -27,3 +28,14 @@ pub static MISC_TIMERS_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static BLOCK_PARTITIONING_CANCELLATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "aptos_block_partitioner_v2_cancellations_count",
+        // metric description
+        "The number of partitioning sessions cancelled, by the phase where the cancellation was observed.",
+        &["phase"]
+    )
+    .unwrap()
+});
@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+
+use crate::{
+    cancellation::CancellationToken,
+    v2::{counters::BLOCK_PARTITIONING_SECONDS, state::PartitionState, PartitionerV2},
+};
+use aptos_types::{
+    block_executor::partitioner::{PartitionedTransactions, ShardId},
+    transaction::analyzed_transaction::AnalyzedTransaction,
+};
+
+/// A `PartitionerV2` session for a block whose txns arrive incrementally (e.g., as quorum store batches).
+///
+/// The per-txn indexing (step 1 of `PartitionerV2::partition()`) is done as txns are added,
+/// so only the steps that need the full block remain once the block is closed with `finalize()`.
+/// Appending txns one batch at a time gives the same result as partitioning the whole block at once.
+pub struct IncrementalPartitioning<'a> {
+    partitioner: &'a PartitionerV2,
+    state: PartitionState,
+}
+
+impl<'a> IncrementalPartitioning<'a> {
+    /// Append txns to the block being built, and index them.
+    pub fn add_transactions(&mut self, txns: Vec<AnalyzedTransaction>) {
+        let ori_txn_idxs = self.state.append_txns(txns);
+        PartitionerV2::init_txns(&self.state, ori_txn_idxs);
+    }
+
+    pub fn num_txns(&self) -> usize {
+        self.state.num_txns()
+    }
+
+    /// Close the block and finish partitioning it.
+    pub fn finalize(self) -> PartitionedTransactions {
+        self.try_finalize(&CancellationToken::new())
+            .expect("Partitioning without a cancellation request should always complete.")
+    }
+
+    /// Same as `finalize()`, but gives up early and returns `None` once `cancellation_token` is cancelled.
+    pub fn try_finalize(
+        self,
+        cancellation_token: &CancellationToken,
+    ) -> Option<PartitionedTransactions> {
+        let _timer = BLOCK_PARTITIONING_SECONDS.start_timer();
+        if cancellation_token.is_cancelled() {
+            return self.partitioner.abort(self.state, "init");
+        }
+        self.partitioner
+            .partition_initialized(self.state, cancellation_token)
+    }
+}
+
+impl PartitionerV2 {
+    /// Start partitioning a block whose txns are not all known yet.
+    /// See `IncrementalPartitioning` for more details.
+    pub fn start_incremental_partitioning(
+        &self,
+        num_executor_shards: ShardId,
+    ) -> IncrementalPartitioning<'_> {
+        let state = PartitionState::new_empty(
+            self.thread_pool.clone(),
+            self.dashmap_num_shards,
+            num_executor_shards,
+            self.max_partitioning_rounds,
+            self.cross_shard_dep_avoid_threshold,
+            self.partition_last_round,
+            self.prune_redundant_dependent_edges,
+        );
+        IncrementalPartitioning {
+            partitioner: self,
+            state,
+        }
+    }
+}
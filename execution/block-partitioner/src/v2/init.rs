@@ -8,16 +8,22 @@ use crate::{
     },
 };
 use rayon::{iter::ParallelIterator, prelude::IntoParallelIterator};
-use std::sync::RwLock;
+use std::{ops::Range, sync::RwLock};
 
 impl PartitionerV2 {
     pub(crate) fn init(state: &mut PartitionState) {
+        Self::init_txns(state, 0..state.num_txns());
+    }
+
+    /// Build the sender/storage location indices for the given txns.
+    /// Txns can be indexed in multiple calls, as long as each txn is indexed exactly once.
+    pub(crate) fn init_txns(state: &PartitionState, ori_txn_idxs: Range<OriginalTxnIdx>) {
         let _timer = MISC_TIMERS_SECONDS
             .with_label_values(&["init"])
             .start_timer();
 
         state.thread_pool.install(|| {
-            (0..state.num_txns())
+            ori_txn_idxs
                 .into_par_iter()
                 .for_each(|ori_txn_idx: OriginalTxnIdx| {
                     let txn_read_guard = state.txns[ori_txn_idx].read().unwrap();
@@ -1,8 +1,12 @@
 // Copyright © Aptos Foundation
 
 use crate::{
-    pre_partition::PrePartitioner, v2::counters::BLOCK_PARTITIONING_SECONDS, BlockPartitioner,
+    cancellation::CancellationToken,
+    pre_partition::PrePartitioner,
+    v2::counters::{BLOCK_PARTITIONING_CANCELLATIONS, BLOCK_PARTITIONING_SECONDS},
+    BlockPartitioner,
 };
+use aptos_logger::info;
 use aptos_types::{
     block_executor::partitioner::{PartitionedTransactions, RoundId},
     transaction::analyzed_transaction::AnalyzedTransaction,
@@ -15,6 +19,7 @@ mod build_edge;
 pub mod config;
 mod conflicting_txn_tracker;
 pub mod counters;
+pub mod incremental;
 mod init;
 pub(crate) mod load_balance;
 mod partition_to_matrix;
@@ -100,6 +105,7 @@ pub struct PartitionerV2 {
     cross_shard_dep_avoid_threshold: f32,
     dashmap_num_shards: usize,
     partition_last_round: bool,
+    prune_redundant_dependent_edges: bool,
 }
 
 impl PartitionerV2 {
@@ -124,8 +130,16 @@ impl PartitionerV2 {
             cross_shard_dep_avoid_threshold,
             dashmap_num_shards,
             partition_last_round,
+            prune_redundant_dependent_edges: false,
         }
     }
+
+    /// Only add a dependent edge from a writer to the first follower in each sub-block,
+    /// since the followers in the same sub-block all receive the same cross-shard update.
+    pub fn prune_redundant_dependent_edges(mut self, val: bool) -> Self {
+        self.prune_redundant_dependent_edges = val;
+        self
+    }
 }
 
 impl BlockPartitioner for PartitionerV2 {
@@ -134,6 +148,16 @@ impl BlockPartitioner for PartitionerV2 {
         txns: Vec<AnalyzedTransaction>,
         num_executor_shards: usize,
     ) -> PartitionedTransactions {
+        self.try_partition(txns, num_executor_shards, &CancellationToken::new())
+            .expect("Partitioning without a cancellation request should always complete.")
+    }
+
+    fn try_partition(
+        &self,
+        txns: Vec<AnalyzedTransaction>,
+        num_executor_shards: usize,
+        cancellation_token: &CancellationToken,
+    ) -> Option<PartitionedTransactions> {
         let _timer = BLOCK_PARTITIONING_SECONDS.start_timer();
 
         let mut state = PartitionState::new(
@@ -144,16 +168,34 @@ impl BlockPartitioner for PartitionerV2 {
             self.max_partitioning_rounds,
             self.cross_shard_dep_avoid_threshold,
             self.partition_last_round,
+            self.prune_redundant_dependent_edges,
         );
         // Step 1: build some necessary indices for txn senders/storage locations.
         Self::init(&mut state);
+        if cancellation_token.is_cancelled() {
+            return self.abort(state, "init");
+        }
+
+        self.partition_initialized(state, cancellation_token)
+    }
+}
 
+impl PartitionerV2 {
+    /// Steps 2-6 of partitioning, for a state whose txns have all been indexed by `init()`/`init_txns()`.
+    fn partition_initialized(
+        &self,
+        mut state: PartitionState,
+        cancellation_token: &CancellationToken,
+    ) -> Option<PartitionedTransactions> {
         // Step 2: pre-partition.
         (
             state.ori_idxs_by_pre_partitioned,
             state.start_txn_idxs_by_shard,
             state.pre_partitioned,
         ) = self.pre_partitioner.pre_partition(&state);
+        if cancellation_token.is_cancelled() {
+            return self.abort(state, "pre_partition");
+        }
 
         // Step 3: update trackers.
         for txn_idx1 in 0..state.num_txns() {
@@ -176,10 +218,16 @@ impl BlockPartitioner for PartitionerV2 {
         // Step 4: remove cross-shard dependencies by move some txns into new rounds.
         // As a result, we get a txn matrix of no more than `self.max_partitioning_rounds` rows and exactly `num_executor_shards` columns.
         // It's guaranteed that inside every round other than the last round, there's no cross-shard dependency. (But cross-round dependencies are always possible.)
-        Self::remove_cross_shard_dependencies(&mut state);
+        // The token is also checked between discarding rounds.
+        if !Self::remove_cross_shard_dependencies(&mut state, cancellation_token) {
+            return self.abort(state, "remove_cross_shard_dependencies");
+        }
 
         // Step 5: build some additional indices of the resulting txn matrix from the previous step.
         Self::build_index_from_txn_matrix(&mut state);
+        if cancellation_token.is_cancelled() {
+            return self.abort(state, "build_index_from_txn_matrix");
+        }
 
         // Step 6: calculate all the cross-shard dependencies and prepare the input for sharded execution.
         let ret = Self::add_edges(&mut state);
@@ -188,7 +236,19 @@ impl BlockPartitioner for PartitionerV2 {
         self.thread_pool.spawn(move || {
             drop(state);
         });
-        ret
+        Some(ret)
+    }
+
+    /// Give up a cancelled partitioning session. Large states are still dropped asynchronously.
+    fn abort(&self, state: PartitionState, phase: &str) -> Option<PartitionedTransactions> {
+        info!("Block partitioning cancelled during phase {}.", phase);
+        BLOCK_PARTITIONING_CANCELLATIONS
+            .with_label_values(&[phase])
+            .inc();
+        self.thread_pool.spawn(move || {
+            drop(state);
+        });
+        None
     }
 }
 
@@ -1,11 +1,14 @@
 // Copyright © Aptos Foundation
 
-use crate::v2::{
-    counters::MISC_TIMERS_SECONDS,
-    extract_and_sort,
-    state::PartitionState,
-    types::{PrePartitionedTxnIdx, SenderIdx},
-    PartitionerV2,
+use crate::{
+    cancellation::CancellationToken,
+    v2::{
+        counters::MISC_TIMERS_SECONDS,
+        extract_and_sort,
+        state::PartitionState,
+        types::{PrePartitionedTxnIdx, SenderIdx},
+        PartitionerV2,
+    },
 };
 use aptos_logger::trace;
 use aptos_types::block_executor::partitioner::{RoundId, TxnIndex};
@@ -25,7 +28,13 @@ use std::{
 impl PartitionerV2 {
     /// Populate `state.finalized_txn_matrix` with txns flattened into a matrix (num_rounds by num_shards),
     /// in a way that avoid in-round cross-shard conflicts.
-    pub(crate) fn remove_cross_shard_dependencies(state: &mut PartitionState) {
+    ///
+    /// Return false if `cancellation_token` was cancelled before all the rounds were done,
+    /// in which case `state.finalized_txn_matrix` is incomplete and should be discarded.
+    pub(crate) fn remove_cross_shard_dependencies(
+        state: &mut PartitionState,
+        cancellation_token: &CancellationToken,
+    ) -> bool {
         let _timer = MISC_TIMERS_SECONDS
             .with_label_values(&["remove_cross_shard_dependencies"])
             .start_timer();
@@ -35,6 +44,9 @@ impl PartitionerV2 {
 
         let mut num_remaining_txns: usize;
         for round_id in 0..(state.num_rounds_limit - 1) {
+            if cancellation_token.is_cancelled() {
+                return false;
+            }
             let (accepted, discarded) = Self::discarding_round(state, round_id, remaining_txns);
             state.finalized_txn_matrix.push(accepted);
             remaining_txns = discarded;
@@ -47,6 +59,10 @@ impl PartitionerV2 {
             }
         }
 
+        if cancellation_token.is_cancelled() {
+            return false;
+        }
+
         let _timer = MISC_TIMERS_SECONDS
             .with_label_values(&["last_round"])
             .start_timer();
@@ -70,6 +86,7 @@ impl PartitionerV2 {
                 });
         });
         state.finalized_txn_matrix.push(remaining_txns);
+        true
     }
 
     /// Given some pre-partitioned txns, pull some off from each shard to avoid cross-shard conflict.
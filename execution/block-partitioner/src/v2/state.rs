@@ -28,6 +28,7 @@ use rayon::{
 };
 use std::{
     collections::HashSet,
+    ops::Range,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Mutex, RwLock,
@@ -45,6 +46,8 @@ pub struct PartitionState {
     pub(crate) dashmap_num_shards: usize,
     pub(crate) cross_shard_dep_avoid_threshold: f32,
     pub(crate) partition_last_round: bool,
+    /// If set, a writer only gets a dependent edge to the first follower in each sub-block.
+    pub(crate) prune_redundant_dependent_edges: bool,
     pub(crate) thread_pool: Arc<ThreadPool>,
     /// OriginalTxnIdx -> the actual txn.
     /// Wrapped in `RwLock` to allow being taking in parallel in `add_edges` phase and parallel reads in other phases.
@@ -114,45 +117,54 @@ impl PartitionState {
         num_rounds_limit: usize,
         cross_shard_dep_avoid_threshold: f32,
         partition_last_round: bool,
+        prune_redundant_dependent_edges: bool,
     ) -> Self {
         let _timer = MISC_TIMERS_SECONDS
             .with_label_values(&["new"])
             .start_timer();
-        let num_txns = txns.len();
-        let sender_counter = AtomicUsize::new(0);
-        let key_counter = AtomicUsize::new(0);
-        let mut senders: Vec<RwLock<Option<SenderIdx>>> = Vec::with_capacity(num_txns);
-        let mut wsets: Vec<RwLock<HashSet<StorageKeyIdx>>> = Vec::with_capacity(num_txns);
-        let mut rsets: Vec<RwLock<HashSet<StorageKeyIdx>>> = Vec::with_capacity(num_txns);
+        let mut state = Self::new_empty(
+            thread_pool,
+            dashmap_num_shards,
+            num_executor_shards,
+            num_rounds_limit,
+            cross_shard_dep_avoid_threshold,
+            partition_last_round,
+            prune_redundant_dependent_edges,
+        );
+        state.append_txns(txns);
+        state
+    }
+
+    /// Create a state with no txns, to which txns can be appended with `append_txns()`.
+    pub(crate) fn new_empty(
+        thread_pool: Arc<ThreadPool>,
+        dashmap_num_shards: usize,
+        num_executor_shards: ShardId,
+        num_rounds_limit: usize,
+        cross_shard_dep_avoid_threshold: f32,
+        partition_last_round: bool,
+        prune_redundant_dependent_edges: bool,
+    ) -> Self {
         let sender_idx_table: DashMap<Sender, SenderIdx> =
             DashMap::with_shard_amount(dashmap_num_shards);
         let key_idx_table: DashMap<StateKey, StorageKeyIdx> =
             DashMap::with_shard_amount(dashmap_num_shards);
         let trackers: DashMap<StorageKeyIdx, RwLock<ConflictingTxnTracker>> =
             DashMap::with_shard_amount(dashmap_num_shards);
-        for txn in txns.iter() {
-            senders.push(RwLock::new(None));
-            wsets.push(RwLock::new(HashSet::with_capacity(txn.write_hints().len())));
-            rsets.push(RwLock::new(HashSet::with_capacity(txn.read_hints().len())));
-        }
-        let takable_txns = thread_pool.install(|| {
-            txns.into_par_iter()
-                .map(|txn| RwLock::new(Some(txn)))
-                .collect()
-        });
 
         Self {
             dashmap_num_shards,
             partition_last_round,
+            prune_redundant_dependent_edges,
             thread_pool,
             num_executor_shards,
             pre_partitioned: vec![],
             start_txn_idxs_by_shard: vec![0; num_executor_shards],
-            sender_counter,
-            storage_key_counter: key_counter,
-            sender_idxs: senders,
-            write_sets: wsets,
-            read_sets: rsets,
+            sender_counter: AtomicUsize::new(0),
+            storage_key_counter: AtomicUsize::new(0),
+            sender_idxs: vec![],
+            write_sets: vec![],
+            read_sets: vec![],
             sender_idx_table,
             key_idx_table,
             trackers,
@@ -161,10 +173,35 @@ impl PartitionState {
             finalized_txn_matrix: Vec::with_capacity(num_rounds_limit),
             final_idxs_by_pre_partitioned: vec![],
             start_index_matrix: vec![],
-            txns: takable_txns,
+            txns: vec![],
             sub_block_matrix: vec![],
-            ori_idxs_by_pre_partitioned: vec![0; num_txns],
+            ori_idxs_by_pre_partitioned: vec![],
+        }
+    }
+
+    /// Append txns to the block, returning their `OriginalTxnIdx` range.
+    /// The new txns still need to be indexed with `PartitionerV2::init_txns()`.
+    pub(crate) fn append_txns(&mut self, txns: Vec<AnalyzedTransaction>) -> Range<OriginalTxnIdx> {
+        let start = self.num_txns();
+        let num_txns = start + txns.len();
+        self.sender_idxs.reserve(txns.len());
+        self.write_sets.reserve(txns.len());
+        self.read_sets.reserve(txns.len());
+        for txn in txns.iter() {
+            self.sender_idxs.push(RwLock::new(None));
+            self.write_sets
+                .push(RwLock::new(HashSet::with_capacity(txn.write_hints().len())));
+            self.read_sets
+                .push(RwLock::new(HashSet::with_capacity(txn.read_hints().len())));
         }
+        let takable_txns: Vec<_> = self.thread_pool.install(|| {
+            txns.into_par_iter()
+                .map(|txn| RwLock::new(Some(txn)))
+                .collect()
+        });
+        self.txns.extend(takable_txns);
+        self.ori_idxs_by_pre_partitioned.resize(num_txns, 0);
+        start..num_txns
     }
 
     pub(crate) fn num_txns(&self) -> usize {
@@ -322,6 +359,8 @@ impl PartitionState {
         }
 
         // Build dependent edges.
+        // The update of a key is sent once to each sub-block (no matter how many followers it has
+        // there), so when pruning redundant edges, only the first follower in each sub-block gets one.
         for &key_idx in self.write_sets[ori_txn_idx].read().unwrap().iter() {
             if Some(txn_idx) == self.last_writer(key_idx, SubBlockIdx { round_id, shard_id }) {
                 let start_of_next_sub_block = ShardedTxnIndexV2::new(round_id, shard_id + 1, 0);
@@ -330,11 +369,18 @@ impl PartitionState {
                     None => ShardedTxnIndexV2::new(self.num_rounds(), self.num_executor_shards, 0), // Guaranteed to be greater than any invalid idx...
                     Some(idx) => ShardedTxnIndexV2::new(idx.round_id(), idx.shard_id() + 1, 0),
                 };
+                let mut last_final_sub_blk_idx = None;
                 for follower_txn_idx in
                     self.all_txns_in_sub_block_range(key_idx, start_of_next_sub_block, end_follower)
                 {
                     let final_sub_blk_idx =
                         self.final_sub_block_idx(follower_txn_idx.sub_block_idx);
+                    if self.prune_redundant_dependent_edges
+                        && last_final_sub_blk_idx == Some(final_sub_blk_idx)
+                    {
+                        continue;
+                    }
+                    last_final_sub_blk_idx = Some(final_sub_blk_idx);
                     let dst_txn_idx = ShardedTxnIndex {
                         txn_index: *self.final_idxs_by_pre_partitioned
                             [follower_txn_idx.pre_partitioned_txn_idx]
@@ -3,20 +3,28 @@
 #![allow(clippy::arc_with_non_send_sync)]
 
 use crate::{
+    cancellation::CancellationToken,
     pre_partition::{
         connected_component::ConnectedComponentPartitioner, uniform_partitioner::UniformPartitioner,
     },
-    test_utils::{assert_deterministic_result, P2PBlockGenerator},
+    test_utils::{assert_deterministic_result, BlockGenerator, ConflictPattern},
     v2::PartitionerV2,
     BlockPartitioner,
 };
+use aptos_crypto::HashValue;
+use aptos_types::{
+    block_executor::partitioner::{
+        CrossShardEdges, PartitionedTransactions, RoundId, ShardId, ShardedTxnIndex,
+    },
+    state_store::state_key::StateKey,
+};
 use rand::{thread_rng, Rng};
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 #[test]
 fn test_partitioner_v2_uniform_correctness() {
     for merge_discarded in [false, true] {
-        let block_generator = P2PBlockGenerator::new(100);
+        let block_generator = BlockGenerator::new(100);
         let partitioner = PartitionerV2::new(
             8,
             4,
@@ -55,7 +63,7 @@ fn test_partitioner_v2_uniform_determinism() {
 #[test]
 fn test_partitioner_v2_connected_component_correctness() {
     for merge_discarded in [false, true] {
-        let block_generator = P2PBlockGenerator::new(100);
+        let block_generator = BlockGenerator::new(100);
         let partitioner = PartitionerV2::new(
             8,
             4,
@@ -78,6 +86,57 @@ fn test_partitioner_v2_connected_component_correctness() {
     }
 }
 
+#[test]
+fn test_partitioner_v2_conflict_patterns_correctness() {
+    let partitioner = PartitionerV2::new(
+        8,
+        4,
+        0.9,
+        64,
+        true,
+        Box::new(ConnectedComponentPartitioner {
+            load_imbalance_tolerance: 2.0,
+        }),
+    );
+    let mut rng = thread_rng();
+    for conflict_pattern in [
+        ConflictPattern::HotAccounts {
+            num_hot_accounts: 3,
+            hot_pct: 50,
+        },
+        ConflictPattern::DependencyChains { chain_length: 20 },
+        ConflictPattern::ModulePublishes {
+            publish_pct: 20,
+            num_modules: 10,
+        },
+    ] {
+        let block_generator = BlockGenerator::new(100).with_conflict_pattern(conflict_pattern);
+        for _run_id in 0..5 {
+            let num_shards = rng.gen_range(1, 10);
+            let block = block_generator.rand_block(&mut rng, 500);
+            assert_eq!(block.len(), 500);
+            let block_clone = block.clone();
+            let partitioned = partitioner.partition(block, num_shards);
+            crate::test_utils::verify_partitioner_output(&block_clone, &partitioned);
+        }
+    }
+}
+
+#[test]
+fn test_hot_accounts_single_hot_account() {
+    // With only two accounts and every transfer going to the hot account, the other account has
+    // to be the sender of every transfer.
+    let conflict_pattern = ConflictPattern::HotAccounts {
+        num_hot_accounts: 1,
+        hot_pct: 100,
+    };
+    let block_generator = BlockGenerator::new(2).with_conflict_pattern(conflict_pattern);
+    let block = block_generator.rand_block(&mut thread_rng(), 100);
+    assert_eq!(block.len(), 100);
+    let senders: HashSet<_> = block.iter().map(|txn| txn.sender()).collect();
+    assert_eq!(senders.len(), 1);
+}
+
 #[test]
 fn test_partitioner_v2_connected_component_determinism() {
     for merge_discarded in [false, true] {
@@ -94,3 +153,190 @@ fn test_partitioner_v2_connected_component_determinism() {
         assert_deterministic_result(partitioner);
     }
 }
+
+#[test]
+fn test_partitioner_v2_cancellation() {
+    let block_generator = BlockGenerator::new(100);
+    let partitioner = PartitionerV2::new(
+        4,
+        4,
+        0.9,
+        64,
+        false,
+        Box::new(ConnectedComponentPartitioner {
+            load_imbalance_tolerance: 2.0,
+        }),
+    );
+    let mut rng = thread_rng();
+    let block = block_generator.rand_block(&mut rng, 1000);
+
+    // A token that is never cancelled gives the same result as `partition()`.
+    let token = CancellationToken::new();
+    let partitioned = partitioner
+        .try_partition(block.clone(), 4, &token)
+        .expect("partitioning should not be cancelled");
+    crate::test_utils::verify_partitioner_output(&block, &partitioned);
+
+    // A cancelled token aborts partitioning.
+    token.cancel();
+    assert!(partitioner.try_partition(block, 4, &token).is_none());
+}
+
+#[test]
+fn test_partitioner_v2_incremental() {
+    let block_generator = BlockGenerator::new(100);
+    let partitioner = PartitionerV2::new(
+        4,
+        4,
+        0.9,
+        64,
+        false,
+        Box::new(ConnectedComponentPartitioner {
+            load_imbalance_tolerance: 2.0,
+        }),
+    );
+    let mut rng = thread_rng();
+    for _run_id in 0..5 {
+        let num_shards = rng.gen_range(1, 10);
+        let block = block_generator.rand_block(&mut rng, 500);
+
+        // Add the txns in batches of random sizes, as if they were arriving from quorum store.
+        let mut session = partitioner.start_incremental_partitioning(num_shards);
+        let mut remaining = block.as_slice();
+        while !remaining.is_empty() {
+            let batch_size = rng.gen_range(1, 100).min(remaining.len());
+            let (batch, rest) = remaining.split_at(batch_size);
+            session.add_transactions(batch.to_vec());
+            remaining = rest;
+        }
+        assert_eq!(session.num_txns(), block.len());
+        let partitioned = session.finalize();
+
+        crate::test_utils::verify_partitioner_output(&block, &partitioned);
+        assert_eq!(partitioned, partitioner.partition(block, num_shards));
+    }
+}
+
+#[test]
+fn test_partitioner_v2_prune_redundant_dependent_edges() {
+    let block_generator =
+        BlockGenerator::new(100).with_conflict_pattern(ConflictPattern::HotAccounts {
+            num_hot_accounts: 3,
+            hot_pct: 50,
+        });
+    let mut rng = thread_rng();
+    for merge_discarded in [false, true] {
+        let new_partitioner = || {
+            PartitionerV2::new(
+                8,
+                4,
+                0.9,
+                64,
+                merge_discarded,
+                Box::new(ConnectedComponentPartitioner {
+                    load_imbalance_tolerance: 2.0,
+                }),
+            )
+        };
+        let partitioner = new_partitioner();
+        let pruning_partitioner = new_partitioner().prune_redundant_dependent_edges(true);
+        for _run_id in 0..5 {
+            let num_shards = rng.gen_range(1, 10);
+            let block = block_generator.rand_block(&mut rng, 500);
+            let partitioned = partitioner.partition(block.clone(), num_shards);
+            crate::test_utils::verify_partitioner_output(&block, &partitioned);
+            let pruned = pruning_partitioner.partition(block, num_shards);
+
+            // The txns are executed in the same order, wait for the same keys,
+            // and the same cross-shard updates are sent to each sub-block.
+            let summary = EdgeSummary::new(&partitioned);
+            let pruned_summary = EdgeSummary::new(&pruned);
+            assert_eq!(pruned_summary.txns, summary.txns);
+            assert_eq!(pruned_summary.updates, summary.updates);
+
+            // But each update is only sent through a single edge.
+            assert_eq!(
+                pruned_summary.num_dependent_edges,
+                pruned_summary.updates.len()
+            );
+            assert!(pruned_summary.num_dependent_edges <= summary.num_dependent_edges);
+        }
+    }
+}
+
+/// A cross-shard update, i.e., the source txn, the key and the destination sub-block.
+type CrossShardUpdate = (ShardedTxnIndex, StateKey, RoundId, ShardId);
+
+/// The edges of a partitioned block.
+struct EdgeSummary {
+    /// The txns (in execution order), along with their required edges.
+    txns: Vec<(HashValue, CrossShardEdges)>,
+    /// The cross-shard updates sent through the dependent edges.
+    updates: HashSet<CrossShardUpdate>,
+    num_dependent_edges: usize,
+}
+
+impl EdgeSummary {
+    fn new(partitioned: &PartitionedTransactions) -> Self {
+        let mut summary = Self {
+            txns: vec![],
+            updates: HashSet::new(),
+            num_dependent_edges: 0,
+        };
+        let num_rounds = partitioned
+            .sharded_txns()
+            .first()
+            .map(|sub_blocks| sub_blocks.sub_blocks.len())
+            .unwrap_or(0);
+        for round_id in 0..num_rounds {
+            for (shard_id, sub_blocks) in partitioned.sharded_txns().iter().enumerate() {
+                let sub_block = &sub_blocks.sub_blocks[round_id];
+                for (pos, txn_with_deps) in sub_block.transactions_with_deps().iter().enumerate() {
+                    let src_txn_idx = ShardedTxnIndex {
+                        txn_index: sub_block.start_index + pos,
+                        shard_id,
+                        round_id,
+                    };
+                    summary.txns.push((
+                        txn_with_deps.txn().test_only_hash(),
+                        txn_with_deps
+                            .cross_shard_dependencies
+                            .required_edges()
+                            .clone(),
+                    ));
+                    for (dst_txn_idx, locations) in txn_with_deps
+                        .cross_shard_dependencies
+                        .dependent_edges()
+                        .iter()
+                    {
+                        for location in locations {
+                            summary.updates.insert((
+                                src_txn_idx,
+                                location.clone().into_state_key(),
+                                dst_txn_idx.round_id,
+                                dst_txn_idx.shard_id,
+                            ));
+                            summary.num_dependent_edges += 1;
+                        }
+                    }
+                }
+            }
+        }
+        for txn_with_deps in &partitioned.global_txns {
+            assert!(txn_with_deps
+                .cross_shard_dependencies
+                .dependent_edges()
+                .iter()
+                .next()
+                .is_none());
+            summary.txns.push((
+                txn_with_deps.txn().test_only_hash(),
+                txn_with_deps
+                    .cross_shard_dependencies
+                    .required_edges()
+                    .clone(),
+            ));
+        }
+        summary
+    }
+}
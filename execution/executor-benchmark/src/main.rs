@@ -22,7 +22,7 @@ use aptos_experimental_runtimes::thread_manager::{ThreadConfigStrategy, ThreadMa
 use aptos_metrics_core::{register_int_gauge, IntGauge};
 use aptos_profiler::{ProfilerConfig, ProfilerHandler};
 use aptos_push_metrics::MetricsPusher;
-use aptos_transaction_generator_lib::args::TransactionTypeArg;
+use aptos_transaction_generator_lib::args::{EntryFunctionArgs, TransactionTypeArg};
 use aptos_vm::AptosVM;
 use clap::{ArgGroup, Parser, Subcommand};
 use once_cell::sync::Lazy;
@@ -158,6 +158,8 @@ struct ShardingOpt {
     partitioner_v2_num_threads: usize,
     #[clap(long, default_value = "64")]
     partitioner_v2_dashmap_num_shards: usize,
+    #[clap(long)]
+    partitioner_v2_prune_redundant_dependent_edges: bool,
 }
 
 impl ShardingOpt {
@@ -180,6 +182,8 @@ impl ShardingOpt {
                 cross_shard_dep_avoid_threshold: self.partitioner_cross_shard_dep_avoid_threshold,
                 dashmap_num_shards: self.partitioner_v2_dashmap_num_shards,
                 partition_last_round: !self.use_global_executor,
+                prune_redundant_dependent_edges: self
+                    .partitioner_v2_prune_redundant_dependent_edges,
                 pre_partitioner_config: self.pre_partitioner_config(),
             },
             None => PartitionerV2Config::default(),
@@ -307,6 +311,9 @@ enum Command {
         )]
         transaction_type: Vec<TransactionTypeArg>,
 
+        #[clap(flatten)]
+        entry_function_args: EntryFunctionArgs,
+
         #[clap(long, num_args = 0..)]
         transaction_weights: Vec<usize>,
 
@@ -360,6 +367,7 @@ where
             main_signer_accounts,
             additional_dst_pool_accounts,
             transaction_type,
+            entry_function_args,
             transaction_weights,
             module_working_set_size,
             data_dir,
@@ -368,6 +376,9 @@ where
             let transaction_mix = if transaction_type.is_empty() {
                 None
             } else {
+                entry_function_args
+                    .register_workload(&transaction_type)
+                    .expect("Failed to load the entry function workload");
                 let mix_per_phase = TransactionTypeArg::args_to_transaction_mix_per_phase(
                     &transaction_type,
                     &transaction_weights,
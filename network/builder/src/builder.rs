@@ -12,10 +12,10 @@
 //! long as the latter is in its trusted peers set.
 use aptos_config::{
     config::{
-        DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, RoleType, CONNECTION_BACKOFF_BASE,
-        CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS, MAX_CONNECTION_DELAY_MS,
-        MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS,
-        NETWORK_CHANNEL_SIZE,
+        DiscoveryMethod, InboundStreamConfig, NetworkConfig, OutboundQueuePolicy, Peer, PeerRole,
+        PeerSet, RoleType, CONNECTION_BACKOFF_BASE, CONNECTIVITY_CHECK_INTERVAL_MS,
+        MAX_CONCURRENT_NETWORK_REQS, MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE,
+        MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS, NETWORK_CHANNEL_SIZE,
     },
     network_id::NetworkContext,
 };
@@ -45,7 +45,12 @@ use aptos_network::{
 use aptos_network_discovery::DiscoveryChangeListener;
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress};
-use std::{clone::Clone, collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    clone::Clone,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::runtime::Handle;
 
 #[derive(Debug, PartialEq, PartialOrd)]
@@ -90,6 +95,8 @@ impl NetworkBuilder {
         max_concurrent_network_reqs: usize,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+        inbound_stream_config: InboundStreamConfig,
     ) -> Self {
         // A network cannot exist without a PeerManager
         // TODO:  construct this in create and pass it to new() as a parameter. The complication is manual construction of NetworkBuilder in various tests.
@@ -107,6 +114,8 @@ impl NetworkBuilder {
             enable_proxy_protocol,
             inbound_connection_limit,
             tcp_buffer_cfg,
+            outbound_queue_policies,
+            inbound_stream_config,
         );
 
         NetworkBuilder {
@@ -147,6 +156,8 @@ impl NetworkBuilder {
             MAX_CONCURRENT_NETWORK_REQS,
             MAX_INBOUND_CONNECTIONS,
             TCPBufferCfg::default(),
+            HashMap::new(),
+            InboundStreamConfig::default(),
         );
 
         builder.add_connectivity_manager(
@@ -203,6 +214,8 @@ impl NetworkBuilder {
                 config.outbound_rx_buffer_size_bytes,
                 config.outbound_tx_buffer_size_bytes,
             ),
+            config.outbound_queue_policies.clone(),
+            config.inbound_stream_config,
         );
 
         network_builder.add_connection_monitoring(
@@ -14,6 +14,20 @@ pub const INBOUND_RPC_TIMEOUT_MS: u64 = 10_000;
 pub const MAX_CONCURRENT_OUTBOUND_RPCS: u32 = 100;
 /// Limit on concurrent Inbound RPC requests before backpressure is applied
 pub const MAX_CONCURRENT_INBOUND_RPCS: u32 = 100;
+/// Limit on outbound messages queued (behind large, streamed messages) per protocol before
+/// the queue policy of the protocol is applied
+pub const MAX_QUEUED_MESSAGES_PER_PROTOCOL: usize = 64;
+/// Limit on outbound stream messages (headers and fragments) buffered for the writer. Keeping
+/// this small paces large messages by the speed of the connection.
+pub const MAX_PENDING_STREAM_MESSAGES: usize = 4;
+/// Limit on inbound streams (from a single peer) that are reassembled concurrently
+pub const MAX_CONCURRENT_INBOUND_STREAMS: usize = 4;
+/// Interval at which timed out inbound streams are discarded
+pub const INBOUND_STREAM_TIMEOUT_CHECK_INTERVAL_MS: u64 = 1_000;
+/// Limit on outbound streams (to a single peer) whose fragments are interleaved. This must
+/// remain 1 until all peers can reassemble interleaved streams (older peers drop the active
+/// stream whenever a new stream header arrives).
+pub const MAX_CONCURRENT_OUTBOUND_STREAMS: usize = 1;
 
 // These are only used in tests
 // TODO: Fix this so the tests and the defaults in config are the same
@@ -35,6 +35,10 @@ pub const OUTBOUND_LABEL: &str = "outbound";
 pub const SERIALIZATION_LABEL: &str = "serialization";
 pub const DESERIALIZATION_LABEL: &str = "deserialization";
 
+// Outbound queue policy labels
+pub const DROP_NEWEST_LABEL: &str = "drop_newest";
+pub const DROP_OLDEST_LABEL: &str = "drop_oldest";
+
 pub static APTOS_CONNECTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_connections",
@@ -437,6 +441,117 @@ pub static PENDING_MULTIPLEX_STREAM: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of outbound messages dropped because their protocol exceeded its queue quota
+pub static APTOS_NETWORK_DROPPED_OUTBOUND_STREAMS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_dropped_outbound_streams",
+        "Number of outbound messages dropped due to the per-protocol queue quota",
+        &[
+            "role_type",
+            "network_id",
+            "peer_id",
+            "protocol_id",
+            "policy"
+        ]
+    )
+    .unwrap()
+});
+
+pub fn dropped_outbound_streams(
+    network_context: &NetworkContext,
+    protocol_id: Option<ProtocolId>,
+    policy_label: &str,
+) -> IntCounter {
+    let protocol_label = protocol_id.map_or("unknown", |protocol_id| protocol_id.as_str());
+    APTOS_NETWORK_DROPPED_OUTBOUND_STREAMS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        protocol_label,
+        policy_label,
+    ])
+}
+
+/// Counter of outbound direct send messages that had to wait for room in the outbound queue
+/// (because their protocol exceeded its quota, and it applies backpressure instead of dropping)
+pub static APTOS_NETWORK_BLOCKED_OUTBOUND_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_blocked_outbound_messages",
+        "Number of outbound messages that waited for room in the per-protocol outbound queue",
+        &["role_type", "network_id", "peer_id", "protocol_id"]
+    )
+    .unwrap()
+});
+
+pub fn blocked_outbound_messages(
+    network_context: &NetworkContext,
+    protocol_id: Option<ProtocolId>,
+) -> IntCounter {
+    let protocol_label = protocol_id.map_or("unknown", |protocol_id| protocol_id.as_str());
+    APTOS_NETWORK_BLOCKED_OUTBOUND_MESSAGES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        protocol_label,
+    ])
+}
+
+/// Counter of inbound streams that were discarded because they were not completed in time
+pub static APTOS_NETWORK_TIMED_OUT_INBOUND_STREAMS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_timed_out_inbound_streams",
+        "Number of inbound streams discarded because they were not completed in time",
+        &["role_type", "network_id", "peer_id"]
+    )
+    .unwrap()
+});
+
+pub fn timed_out_inbound_streams(network_context: &NetworkContext) -> IntCounter {
+    APTOS_NETWORK_TIMED_OUT_INBOUND_STREAMS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+    ])
+}
+
+/// Counter of outbound stream messages that were paced (i.e., the sender waited for
+/// the writer to drain the previous ones before handing them over)
+pub static APTOS_NETWORK_PACED_STREAM_FRAGMENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_paced_stream_fragments",
+        "Number of outbound stream fragments paced due to backpressure from the writer",
+        &["role_type", "network_id", "peer_id"]
+    )
+    .unwrap()
+});
+
+pub fn paced_stream_fragments(network_context: &NetworkContext) -> IntCounter {
+    APTOS_NETWORK_PACED_STREAM_FRAGMENTS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+    ])
+}
+
+/// Time from the start of an outbound stream until its last fragment was sent
+pub static APTOS_NETWORK_STREAM_TIME_TO_LAST_FRAGMENT: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_network_stream_time_to_last_fragment",
+        "Time (in seconds) from the start of an outbound stream until its last fragment was sent",
+        &["role_type", "network_id", "peer_id"],
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 16).unwrap(),
+    )
+    .unwrap()
+});
+
+pub fn stream_time_to_last_fragment(network_context: &NetworkContext) -> Histogram {
+    APTOS_NETWORK_STREAM_TIME_TO_LAST_FRAGMENT.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+    ])
+}
+
 /// Counter of pending requests in Direct Send
 pub static PENDING_DIRECT_SEND_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -13,7 +13,10 @@ use crate::{
     transport::{Connection, ConnectionId, ConnectionMetadata},
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{InboundStreamConfig, PeerRole},
+    network_id::NetworkContext,
+};
 use aptos_memsocket::MemorySocket;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_proptest_helpers::ValueGenerator;
@@ -21,7 +24,7 @@ use aptos_time_service::TimeService;
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use futures::{executor::block_on, future, io::AsyncReadExt, sink::SinkExt, stream::StreamExt};
 use proptest::{arbitrary::any, collection::vec};
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 /// Generate a sequence of `MultiplexMessage`, bcs serialize them, and write them
 /// out to a buffer using our length-prefixed message codec.
@@ -108,6 +111,8 @@ pub fn fuzz(data: &[u8]) {
         constants::MAX_CONCURRENT_OUTBOUND_RPCS,
         constants::MAX_FRAME_SIZE,
         constants::MAX_MESSAGE_SIZE,
+        HashMap::new(),
+        InboundStreamConfig::default(),
     );
     executor.spawn(peer.start());
 
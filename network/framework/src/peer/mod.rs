@@ -16,6 +16,7 @@
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
+    constants,
     counters::{
         self, network_application_inbound_traffic, network_application_outbound_traffic,
         FAILED_LABEL, RECEIVED_LABEL, SENT_LABEL,
@@ -25,17 +26,23 @@ use crate::{
     protocols::{
         direct_send::Message,
         rpc::{error::RpcError, InboundRpcRequest, InboundRpcs, OutboundRpcRequest, OutboundRpcs},
-        stream::{InboundStreamBuffer, OutboundStream, StreamMessage},
+        stream::{
+            InboundStreamBuffer, OutboundMessage, OutboundQueueCredits, OutboundStream,
+            OutboundStreamQueue, QueueOutcome, StreamMessage,
+        },
         wire::messaging::v1::{
-            DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
-            MultiplexMessageStream, NetworkMessage, Priority, ReadError, WriteError,
+            message_priority, DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
+            MultiplexMessageStream, NetworkMessage, ReadError, WriteError,
         },
     },
     transport::{self, Connection, ConnectionMetadata},
     ProtocolId,
 };
 use aptos_channels::aptos_channel;
-use aptos_config::network_id::NetworkContext;
+use aptos_config::{
+    config::{InboundStreamConfig, OutboundQueuePolicy},
+    network_id::NetworkContext,
+};
 use aptos_logger::prelude::*;
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{TimeService, TimeServiceTrait};
@@ -44,14 +51,23 @@ use bytes::Bytes;
 use futures::{
     self,
     channel::oneshot,
+    future::BoxFuture,
     io::{AsyncRead, AsyncWrite},
-    stream::StreamExt,
-    SinkExt,
+    stream::{FuturesUnordered, StreamExt},
+    FutureExt, SinkExt,
 };
 use futures_util::stream::select;
 use serde::Serialize;
-use std::{fmt, panic, time::Duration};
-use tokio::runtime::Handle;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt, mem, panic,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    runtime::Handle,
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
@@ -90,6 +106,7 @@ pub enum PeerNotification {
 pub enum DisconnectReason {
     Requested,
     ConnectionLost,
+    InboundStreamTimeout,
 }
 
 impl fmt::Display for DisconnectReason {
@@ -97,6 +114,7 @@ impl fmt::Display for DisconnectReason {
         let s = match self {
             DisconnectReason::Requested => "Requested",
             DisconnectReason::ConnectionLost => "ConnectionLost",
+            DisconnectReason::InboundStreamTimeout => "InboundStreamTimeout",
         };
         write!(f, "{}", s)
     }
@@ -138,6 +156,18 @@ pub struct Peer<TSocket> {
     max_message_size: usize,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
+    /// Whether to disconnect from the remote peer when one of its inbound streams times out
+    disconnect_on_inbound_stream_timeout: bool,
+    /// The policies applied when the outbound stream queue of a protocol is full
+    outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+    /// The outbound queue credits of the direct send messages (per protocol)
+    outbound_queue_credits: OutboundQueueCredits,
+    /// The direct send messages waiting for an outbound queue credit (per protocol, in FIFO
+    /// order). Only the senders of a blocked protocol are delayed: new requests of all other
+    /// protocols, inbound messages and RPC responses are still handled in the meantime.
+    blocked_direct_sends: HashMap<ProtocolId, VecDeque<Message>>,
+    /// The outbound queue credits awaited by the protocols with blocked direct sends
+    pending_credits: FuturesUnordered<BoxFuture<'static, (ProtocolId, OwnedSemaphorePermit)>>,
 }
 
 impl<TSocket> Peer<TSocket>
@@ -158,6 +188,8 @@ where
         max_concurrent_outbound_rpcs: u32,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+        inbound_stream_config: InboundStreamConfig,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -183,14 +215,27 @@ where
             ),
             outbound_rpcs: OutboundRpcs::new(
                 network_context,
-                time_service,
+                time_service.clone(),
                 remote_peer_id,
                 max_concurrent_outbound_rpcs,
             ),
             state: State::Connected,
             max_frame_size,
             max_message_size,
-            inbound_stream: InboundStreamBuffer::new(max_fragments),
+            inbound_stream: InboundStreamBuffer::new(
+                constants::MAX_CONCURRENT_INBOUND_STREAMS,
+                max_fragments,
+                Duration::from_millis(inbound_stream_config.stream_timeout_ms),
+                time_service,
+            ),
+            disconnect_on_inbound_stream_timeout: inbound_stream_config.disconnect_on_timeout,
+            outbound_queue_credits: OutboundQueueCredits::new(
+                constants::MAX_QUEUED_MESSAGES_PER_PROTOCOL,
+                outbound_queue_policies.clone(),
+            ),
+            outbound_queue_policies,
+            blocked_direct_sends: HashMap::new(),
+            pending_credits: FuturesUnordered::new(),
         }
     }
 
@@ -218,7 +263,7 @@ where
 
         // Start writer "process" as a separate task. We receive two handles to
         // communicate with the task:
-        //   1. `write_reqs_tx`: Queue of pending NetworkMessages to write.
+        //   1. `write_reqs_tx`: Queue of pending NetworkMessages (tagged with their protocols) to write.
         //   2. `close_tx`: Handle to close the task and underlying connection.
         let (mut write_reqs_tx, writer_close_tx) = Self::start_writer_task(
             &self.executor,
@@ -228,8 +273,15 @@ where
             writer,
             self.max_frame_size,
             self.max_message_size,
+            mem::take(&mut self.outbound_queue_policies),
         );
 
+        // Periodically discard the inbound streams that have timed out.
+        let inbound_stream_timeout_check = self.time_service.interval(Duration::from_millis(
+            constants::INBOUND_STREAM_TIMEOUT_CHECK_INTERVAL_MS,
+        ));
+        tokio::pin!(inbound_stream_timeout_check);
+
         // Start main Peer event loop.
         let reason = loop {
             if let State::ShuttingDown(reason) = self.state {
@@ -246,6 +298,11 @@ where
                         None => self.shutdown(DisconnectReason::Requested),
                     }
                 },
+                // Send the blocked direct send messages of a protocol once it has room
+                // in the outbound queue.
+                (protocol_id, credit) = self.pending_credits.select_next_some() => {
+                    self.send_blocked_direct_sends(protocol_id, credit, &mut write_reqs_tx).await;
+                },
                 // Handle a new inbound MultiplexMessage that we've just read off
                 // the wire from the remote peer.
                 maybe_message = reader.next() => {
@@ -307,6 +364,10 @@ where
                 (request_id, maybe_completed_request) = self.outbound_rpcs.next_completed_request() => {
                     self.outbound_rpcs.handle_completed_request(request_id, maybe_completed_request);
                 }
+                // Discard the inbound streams that the remote peer never completed.
+                _ = inbound_stream_timeout_check.select_next_some() => {
+                    self.handle_expired_inbound_streams();
+                }
             }
         };
 
@@ -330,15 +391,18 @@ where
         mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
         max_frame_size: usize,
         max_message_size: usize,
-    ) -> (aptos_channels::Sender<NetworkMessage>, oneshot::Sender<()>) {
+        outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+    ) -> (aptos_channels::Sender<OutboundMessage>, oneshot::Sender<()>) {
         let remote_peer_id = connection_metadata.remote_peer_id;
-        let (write_reqs_tx, mut write_reqs_rx): (aptos_channels::Sender<NetworkMessage>, _) =
+        let (write_reqs_tx, mut write_reqs_rx): (aptos_channels::Sender<OutboundMessage>, _) =
             aptos_channels::new(1024, &counters::PENDING_WIRE_MESSAGES);
         let (close_tx, mut close_rx) = oneshot::channel();
 
         let (mut msg_tx, msg_rx) = aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_MESSAGE);
-        let (stream_msg_tx, stream_msg_rx) =
-            aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
+        let (stream_msg_tx, stream_msg_rx) = aptos_channels::new(
+            constants::MAX_PENDING_STREAM_MESSAGES,
+            &counters::PENDING_MULTIPLEX_STREAM,
+        );
 
         // this task ends when the multiplex task ends (by dropping the senders)
         let writer_task = async move {
@@ -400,29 +464,115 @@ where
             }
         };
         let multiplex_task = async move {
-            let mut outbound_stream =
-                OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx);
+            let mut outbound_stream = OutboundStream::new(
+                network_context,
+                max_frame_size,
+                max_message_size,
+                constants::MAX_CONCURRENT_OUTBOUND_STREAMS,
+                stream_msg_tx,
+            );
+            let mut outbound_stream_queue = OutboundStreamQueue::new(
+                constants::MAX_QUEUED_MESSAGES_PER_PROTOCOL,
+                outbound_queue_policies,
+            );
             loop {
-                futures::select! {
-                    message = write_reqs_rx.select_next_some() => {
-                        // either channel full would block the other one
-                        let result = if outbound_stream.should_stream(&message) {
-                            outbound_stream.stream_message(message).await
+                // The messages of a protocol with a large message waiting to be sent are queued
+                // behind it (in FIFO order), and dequeued whenever they can be sent: by message
+                // priority, and in round-robin order across protocols with the same priority.
+                // This way, urgent messages are not queued behind bulk data, and a protocol with
+                // many large messages cannot starve the others. The fragments of active streams
+                // are sent one at a time (interleaved by priority, if more than one stream may be
+                // active). All other small messages are sent immediately.
+                while let Some(message) = outbound_stream_queue.pop_next(|message| {
+                    !outbound_stream.is_streaming(message.protocol_id)
+                        && (!outbound_stream.should_stream(&message.message)
+                            || outbound_stream.can_start_stream())
+                }) {
+                    let result = if outbound_stream.should_stream(&message.message) {
+                        outbound_stream.start_stream(message)
+                    } else {
+                        msg_tx
+                            .send(MultiplexMessage::Message(message.message))
+                            .await
+                            .map_err(|_| anyhow::anyhow!("Writer task ended"))
+                    };
+                    if let Err(err) = result {
+                        warn!(
+                            error = %err,
+                            "{} Error in sending message to peer: {}",
+                            network_context,
+                            remote_peer_id.short_str(),
+                        );
+                    }
+                }
+
+                let maybe_message = if !outbound_stream.has_active_streams() {
+                    futures::select! {
+                        message = write_reqs_rx.select_next_some() => Some(message),
+                        _ = close_rx => break,
+                    }
+                } else {
+                    // Only hand the next stream message to the writer once it is ready for it,
+                    // but keep accepting new messages in the meantime.
+                    futures::select_biased! {
+                        _ = close_rx => break,
+                        message = write_reqs_rx.select_next_some() => Some(message),
+                        _ = outbound_stream.writer_ready().fuse() => None,
+                    }
+                };
+
+                let result = match maybe_message {
+                    Some(message) => {
+                        // Keep the messages of each protocol in FIFO order
+                        let protocol_id = message.protocol_id;
+                        if outbound_stream.should_stream(&message.message)
+                            || outbound_stream.is_streaming(protocol_id)
+                            || outbound_stream_queue.num_queued_messages(protocol_id) > 0
+                        {
+                            match outbound_stream_queue.push(message) {
+                                QueueOutcome::Queued => Ok(()),
+                                QueueOutcome::DroppedNewest => {
+                                    counters::dropped_outbound_streams(
+                                        &network_context,
+                                        protocol_id,
+                                        counters::DROP_NEWEST_LABEL,
+                                    )
+                                    .inc();
+                                    Err(anyhow::anyhow!(
+                                        "Too many queued messages for protocol {:?}, dropped the new message",
+                                        protocol_id
+                                    ))
+                                },
+                                QueueOutcome::DroppedOldest => {
+                                    counters::dropped_outbound_streams(
+                                        &network_context,
+                                        protocol_id,
+                                        counters::DROP_OLDEST_LABEL,
+                                    )
+                                    .inc();
+                                    Err(anyhow::anyhow!(
+                                        "Too many queued messages for protocol {:?}, dropped the oldest message",
+                                        protocol_id
+                                    ))
+                                },
+                            }
                         } else {
-                            msg_tx.send(MultiplexMessage::Message(message)).await.map_err(|_| anyhow::anyhow!("Writer task ended"))
-                        };
-                        if let Err(err) = result {
-                            warn!(
-                                error = %err,
-                                "{} Error in sending message to peer: {}",
-                                network_context,
-                                remote_peer_id.short_str(),
-                            );
+                            // either channel full would block the other one
+                            msg_tx
+                                .send(MultiplexMessage::Message(message.message))
+                                .await
+                                .map_err(|_| anyhow::anyhow!("Writer task ended"))
                         }
                     },
-                    _ = close_rx => {
-                        break;
-                    }
+                    None => outbound_stream.send_next_stream_message().await,
+                };
+                if let Err(err) = result {
+                    warn!(
+                        error = %err,
+                        "{} Error in sending message to peer: {}",
+                        network_context,
+                        remote_peer_id.short_str(),
+                    );
                 }
             }
         };
@@ -476,6 +626,8 @@ where
     ) -> Result<(), PeerManagerError> {
         match message {
             StreamMessage::Header(header) => {
+                // Free up the slots of any timed out streams first
+                self.handle_expired_inbound_streams();
                 self.inbound_stream.new_stream(header)?;
             },
             StreamMessage::Fragment(fragment) => {
@@ -487,10 +639,34 @@ where
         Ok(())
     }
 
+    /// Discards the inbound streams that have timed out (i.e., the remote peer sent
+    /// the header, but not all of the fragments). If configured, the remote peer is
+    /// also disconnected.
+    fn handle_expired_inbound_streams(&mut self) {
+        let expired_streams = self.inbound_stream.remove_expired_streams();
+        if expired_streams.is_empty() {
+            return;
+        }
+
+        counters::timed_out_inbound_streams(&self.network_context)
+            .inc_by(expired_streams.len() as u64);
+        warn!(
+            NetworkSchema::new(&self.network_context)
+                .connection_metadata(&self.connection_metadata),
+            "{} Discarded timed out inbound streams {:?} from peer: {}",
+            self.network_context,
+            expired_streams,
+            self.remote_peer_id().short_str()
+        );
+        if self.disconnect_on_inbound_stream_timeout {
+            self.shutdown(DisconnectReason::InboundStreamTimeout);
+        }
+    }
+
     async fn handle_inbound_message(
         &mut self,
         message: Result<MultiplexMessage, ReadError>,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
     ) -> Result<(), PeerManagerError> {
         trace!(
             NetworkSchema::new(&self.network_context)
@@ -512,7 +688,9 @@ where
                     let error_code = ErrorCode::parsing_error(*message_type, *protocol_id);
                     let message = NetworkMessage::Error(error_code);
 
-                    write_reqs_tx.send(message).await?;
+                    write_reqs_tx
+                        .send(OutboundMessage::new(None, message))
+                        .await?;
                     return Err(err.into());
                 },
                 ReadError::IoError(_) => {
@@ -578,7 +756,7 @@ where
     async fn handle_outbound_request(
         &mut self,
         request: PeerRequest,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -586,34 +764,28 @@ where
             request
         );
         match request {
-            // To send an outbound DirectSendMsg, we just bump some counters and
-            // push it onto our outbound writer queue.
+            // To send an outbound DirectSendMsg, we acquire an outbound queue credit
+            // for its protocol (if the protocol applies backpressure), bump some
+            // counters and push it onto our outbound writer queue.
             PeerRequest::SendDirectSend(message) => {
-                // Create the direct send message
-                let message_len = message.mdata.len();
-                let protocol_id = message.protocol_id;
-                let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
-                    protocol_id,
-                    priority: Priority::default(),
-                    raw_msg: Vec::from(message.mdata.as_ref()),
-                });
-
-                match write_reqs_tx.send(message).await {
-                    Ok(_) => {
-                        self.update_outbound_direct_send_metrics(protocol_id, message_len as u64);
-                    },
-                    Err(e) => {
-                        counters::direct_send_messages(&self.network_context, FAILED_LABEL).inc();
-                        warn!(
-                            NetworkSchema::new(&self.network_context)
-                                .connection_metadata(&self.connection_metadata),
-                            error = ?e,
-                            "Failed to send direct send message for protocol {} to peer: {}. Error: {:?}",
-                            protocol_id,
-                            self.remote_peer_id().short_str(),
-                            e,
-                        );
+                let Some(credits) = self
+                    .outbound_queue_credits
+                    .protocol_credits(message.protocol_id)
+                else {
+                    return self.send_direct_send(message, None, write_reqs_tx).await;
+                };
+
+                // Keep the direct sends of the protocol in FIFO order
+                if self.blocked_direct_sends.contains_key(&message.protocol_id) {
+                    return self.block_direct_send(message, credits);
+                }
+                match credits.clone().try_acquire_owned() {
+                    Ok(credit) => {
+                        self.send_direct_send(message, Some(credit), write_reqs_tx)
+                            .await
                     },
+                    // The protocol has too many queued messages, so wait for a credit
+                    Err(_) => self.block_direct_send(message, credits),
                 }
             },
             PeerRequest::SendRpc(request) => {
@@ -637,6 +809,127 @@ where
         }
     }
 
+    /// Parks the direct send message until its protocol has an outbound queue credit. If
+    /// the protocol already has too many blocked messages, the new message is dropped.
+    fn block_direct_send(&mut self, message: Message, credits: Arc<Semaphore>) {
+        let protocol_id = message.protocol_id;
+        counters::blocked_outbound_messages(&self.network_context, Some(protocol_id)).inc();
+
+        let blocked_messages = self.blocked_direct_sends.entry(protocol_id).or_default();
+        if blocked_messages.len() >= constants::MAX_QUEUED_MESSAGES_PER_PROTOCOL {
+            counters::dropped_outbound_streams(
+                &self.network_context,
+                Some(protocol_id),
+                counters::DROP_NEWEST_LABEL,
+            )
+            .inc();
+            counters::direct_send_messages(&self.network_context, FAILED_LABEL).inc();
+            return;
+        }
+        blocked_messages.push_back(message);
+
+        // Only the oldest blocked message of a protocol waits for a credit
+        if blocked_messages.len() == 1 {
+            self.wait_for_credit(protocol_id, credits);
+        }
+    }
+
+    /// Waits for an outbound queue credit of the given protocol
+    fn wait_for_credit(&mut self, protocol_id: ProtocolId, credits: Arc<Semaphore>) {
+        let credit = async move {
+            let credit = credits
+                .acquire_owned()
+                .await
+                .expect("The outbound queue credits are never closed");
+            (protocol_id, credit)
+        };
+        self.pending_credits.push(credit.boxed());
+    }
+
+    /// Sends the blocked direct send messages of the given protocol (in FIFO order) for
+    /// as long as the protocol has outbound queue credits.
+    async fn send_blocked_direct_sends(
+        &mut self,
+        protocol_id: ProtocolId,
+        credit: OwnedSemaphorePermit,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
+    ) {
+        let mut next_credit = Some(credit);
+        while let Some(credit) = next_credit.take() {
+            let Some(message) = self
+                .blocked_direct_sends
+                .get_mut(&protocol_id)
+                .and_then(VecDeque::pop_front)
+            else {
+                break;
+            };
+            self.send_direct_send(message, Some(credit), write_reqs_tx)
+                .await;
+
+            // Acquire a credit for the next blocked message (or wait for one)
+            let has_blocked_messages = self
+                .blocked_direct_sends
+                .get(&protocol_id)
+                .is_some_and(|blocked_messages| !blocked_messages.is_empty());
+            if has_blocked_messages {
+                let credits = self
+                    .outbound_queue_credits
+                    .protocol_credits(protocol_id)
+                    .expect("Blocked protocols always apply backpressure");
+                match credits.clone().try_acquire_owned() {
+                    Ok(credit) => next_credit = Some(credit),
+                    Err(_) => self.wait_for_credit(protocol_id, credits),
+                }
+            }
+        }
+
+        // Remove the protocol once all of its blocked messages have been sent
+        if self
+            .blocked_direct_sends
+            .get(&protocol_id)
+            .is_some_and(VecDeque::is_empty)
+        {
+            self.blocked_direct_sends.remove(&protocol_id);
+        }
+    }
+
+    async fn send_direct_send(
+        &mut self,
+        message: Message,
+        credit: Option<OwnedSemaphorePermit>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
+    ) {
+        // Create the direct send message
+        let message_len = message.mdata.len();
+        let protocol_id = message.protocol_id;
+        let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: message_priority(protocol_id),
+            raw_msg: Vec::from(message.mdata.as_ref()),
+        });
+
+        match write_reqs_tx
+            .send(OutboundMessage::new(Some(protocol_id), message).with_credit(credit))
+            .await
+        {
+            Ok(_) => {
+                self.update_outbound_direct_send_metrics(protocol_id, message_len as u64);
+            },
+            Err(e) => {
+                counters::direct_send_messages(&self.network_context, FAILED_LABEL).inc();
+                warn!(
+                    NetworkSchema::new(&self.network_context)
+                        .connection_metadata(&self.connection_metadata),
+                    error = ?e,
+                    "Failed to send direct send message for protocol {} to peer: {}. Error: {:?}",
+                    protocol_id,
+                    self.remote_peer_id().short_str(),
+                    e,
+                );
+            },
+        }
+    }
+
     /// Updates the outbound direct send metrics (e.g., messages and bytes sent)
     fn update_outbound_direct_send_metrics(&mut self, protocol_id: ProtocolId, data_len: u64) {
         // Update the metrics for the sent direct send message
@@ -5,7 +5,7 @@
 use crate::{
     constants::{
         INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
-        MAX_FRAME_SIZE, MAX_MESSAGE_SIZE, NETWORK_CHANNEL_SIZE,
+        MAX_FRAME_SIZE, MAX_MESSAGE_SIZE, MAX_QUEUED_MESSAGES_PER_PROTOCOL, NETWORK_CHANNEL_SIZE,
     },
     peer::{DisconnectReason, Peer, PeerNotification, PeerRequest},
     peer_manager::TransportNotification,
@@ -15,8 +15,8 @@ use crate::{
         wire::{
             handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
             messaging::v1::{
-                DirectSendMsg, MultiplexMessage, MultiplexMessageSink, MultiplexMessageStream,
-                NetworkMessage, RpcRequest, RpcResponse,
+                message_priority, DirectSendMsg, MultiplexMessage, MultiplexMessageSink,
+                MultiplexMessageStream, NetworkMessage, RpcRequest, RpcResponse,
             },
         },
     },
@@ -24,7 +24,10 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{InboundStreamConfig, PeerRole},
+    network_id::NetworkContext,
+};
 use aptos_memsocket::MemorySocket;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_time_service::{MockTimeService, TimeService};
@@ -37,7 +40,11 @@ use futures::{
     stream::{StreamExt, TryStreamExt},
     SinkExt,
 };
-use std::{collections::HashSet, str::FromStr, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    time::Duration,
+};
 use tokio::runtime::{Handle, Runtime};
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
@@ -90,6 +97,8 @@ fn build_test_peer(
         MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE,
         MAX_MESSAGE_SIZE,
+        HashMap::new(),
+        InboundStreamConfig::default(),
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
@@ -237,6 +246,72 @@ fn peer_send_message() {
     rt.block_on(future::join3(peer.start(), server, client));
 }
 
+// A protocol that runs out of outbound queue credits should only delay its own direct
+// sends. The direct sends of other protocols should still be written to the wire, and
+// the blocked messages should follow (in order) once the protocol has credits again.
+#[test]
+fn peer_send_message_starved_protocol() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (mut peer, mut peer_handle, mut connection, _connection_notifs_rx, _peer_notifs_rx) =
+        build_test_peer(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Inbound,
+        );
+    let (mut client_sink, mut client_stream) = build_network_sink_stream(&mut connection);
+
+    // Starve the consensus protocol by holding all of its outbound queue credits
+    let starved_protocol = ProtocolId::ConsensusDirectSendBcs;
+    let held_credits = peer
+        .outbound_queue_credits
+        .protocol_credits(starved_protocol)
+        .unwrap()
+        .try_acquire_many_owned(MAX_QUEUED_MESSAGES_PER_PROTOCOL as u32)
+        .unwrap();
+    let (release_tx, release_rx) = oneshot::channel();
+
+    let send_msg = |protocol_id: ProtocolId, index: usize| Message {
+        protocol_id,
+        mdata: Bytes::from(format!("message {}", index)),
+    };
+    let recv_msg = |protocol_id: ProtocolId, index: usize| {
+        MultiplexMessage::Message(NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: message_priority(protocol_id),
+            raw_msg: Vec::from(format!("message {}", index)),
+        }))
+    };
+
+    let client = async {
+        // Client should receive the direct send messages of the other protocol.
+        for index in 0..10 {
+            let msg = client_stream.next().await.unwrap().unwrap();
+            assert_eq!(msg, recv_msg(PROTOCOL, index));
+        }
+        // Once the starved protocol has credits again, client should receive its messages.
+        release_tx.send(()).unwrap();
+        for index in 0..10 {
+            let msg = client_stream.next().await.unwrap().unwrap();
+            assert_eq!(msg, recv_msg(starved_protocol, index));
+        }
+        // Client then closes the connection.
+        client_sink.close().await.unwrap();
+    };
+
+    let server = async {
+        // Server sends the direct send messages of both protocols.
+        for index in 0..10 {
+            peer_handle.send_direct_send(send_msg(starved_protocol, index));
+            peer_handle.send_direct_send(send_msg(PROTOCOL, index));
+        }
+        // Server returns the credits of the starved protocol.
+        release_rx.await.unwrap();
+        drop(held_credits);
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
 // Reading an inbound DirectSendMsg off the wire should notify the PeerManager of
 // an inbound DirectSend.
 #[test]
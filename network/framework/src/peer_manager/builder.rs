@@ -18,7 +18,10 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::HANDSHAKE_VERSION, network_id::NetworkContext};
+use aptos_config::{
+    config::{InboundStreamConfig, OutboundQueuePolicy, HANDSHAKE_VERSION},
+    network_id::NetworkContext,
+};
 use aptos_crypto::x25519;
 use aptos_logger::prelude::*;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
@@ -79,6 +82,8 @@ struct PeerManagerContext {
     max_message_size: usize,
     inbound_connection_limit: usize,
     tcp_buffer_cfg: TCPBufferCfg,
+    outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+    inbound_stream_config: InboundStreamConfig,
 }
 
 impl PeerManagerContext {
@@ -102,6 +107,8 @@ impl PeerManagerContext {
         max_message_size: usize,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+        inbound_stream_config: InboundStreamConfig,
     ) -> Self {
         Self {
             pm_reqs_tx,
@@ -119,6 +126,8 @@ impl PeerManagerContext {
             max_message_size,
             inbound_connection_limit,
             tcp_buffer_cfg,
+            outbound_queue_policies,
+            inbound_stream_config,
         }
     }
 
@@ -177,6 +186,8 @@ impl PeerManagerBuilder {
         enable_proxy_protocol: bool,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+        inbound_stream_config: InboundStreamConfig,
     ) -> Self {
         // Setup channel to send requests to peer manager.
         let (pm_reqs_tx, pm_reqs_rx) = aptos_channel::new(
@@ -212,6 +223,8 @@ impl PeerManagerBuilder {
                 max_message_size,
                 inbound_connection_limit,
                 tcp_buffer_cfg,
+                outbound_queue_policies,
+                inbound_stream_config,
             )),
             peer_manager: None,
             listen_address,
@@ -346,6 +359,8 @@ impl PeerManagerBuilder {
             pm_context.max_frame_size,
             pm_context.max_message_size,
             pm_context.inbound_connection_limit,
+            pm_context.outbound_queue_policies,
+            pm_context.inbound_stream_config,
         );
 
         // PeerManager constructor appends a public key to the listen_address.
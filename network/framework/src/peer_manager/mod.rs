@@ -58,7 +58,7 @@ use crate::{
     peer_manager::transport::{TransportHandler, TransportRequest},
     protocols::network::SerializedRequest,
 };
-use aptos_config::config::PeerRole;
+use aptos_config::config::{InboundStreamConfig, OutboundQueuePolicy, PeerRole};
 use aptos_types::account_address::AccountAddress;
 pub use senders::*;
 pub use types::*;
@@ -119,6 +119,10 @@ where
     max_message_size: usize,
     /// Inbound connection limit separate of outbound connections
     inbound_connection_limit: usize,
+    /// Policies applied when the outbound stream queue of a protocol is full
+    outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+    /// Limits for the reassembly of large (streamed) inbound messages
+    inbound_stream_config: InboundStreamConfig,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -147,6 +151,8 @@ where
         max_frame_size: usize,
         max_message_size: usize,
         inbound_connection_limit: usize,
+        outbound_queue_policies: HashMap<String, OutboundQueuePolicy>,
+        inbound_stream_config: InboundStreamConfig,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = aptos_channels::new(
             channel_size,
@@ -189,6 +195,8 @@ where
             max_frame_size,
             max_message_size,
             inbound_connection_limit,
+            outbound_queue_policies,
+            inbound_stream_config,
         }
     }
 
@@ -672,6 +680,8 @@ where
             constants::MAX_CONCURRENT_OUTBOUND_RPCS,
             self.max_frame_size,
             self.max_message_size,
+            self.outbound_queue_policies.clone(),
+            self.inbound_stream_config,
         );
         self.executor.spawn(peer.start());
 
@@ -54,7 +54,10 @@ use crate::{
     peer::PeerNotification,
     protocols::{
         network::SerializedRequest,
-        wire::messaging::v1::{NetworkMessage, Priority, RequestId, RpcRequest, RpcResponse},
+        stream::OutboundMessage,
+        wire::messaging::v1::{
+            message_priority, NetworkMessage, RequestId, RpcRequest, RpcResponse,
+        },
     },
     ProtocolId,
 };
@@ -326,7 +329,7 @@ impl InboundRpcs {
     /// the outbound write queue.
     pub async fn send_outbound_response(
         &mut self,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
         maybe_response: Result<(RpcResponse, ProtocolId), RpcError>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
@@ -354,7 +357,9 @@ impl InboundRpcs {
             response.request_id,
         );
         let message = NetworkMessage::RpcResponse(response);
-        write_reqs_tx.send(message).await?;
+        write_reqs_tx
+            .send(OutboundMessage::new(Some(protocol_id), message))
+            .await?;
 
         // Update the outbound RPC response metrics
         self.update_outbound_rpc_response_metrics(protocol_id, res_len);
@@ -436,7 +441,7 @@ impl OutboundRpcs {
     pub async fn handle_outbound_request(
         &mut self,
         request: OutboundRpcRequest,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
         let peer_id = &self.remote_peer_id;
@@ -496,10 +501,12 @@ impl OutboundRpcs {
         let message = NetworkMessage::RpcRequest(RpcRequest {
             protocol_id,
             request_id,
-            priority: Priority::default(),
+            priority: message_priority(protocol_id),
             raw_request: Vec::from(request_data.as_ref()),
         });
-        write_reqs_tx.send(message).await?;
+        write_reqs_tx
+            .send(OutboundMessage::new(Some(protocol_id), message))
+            .await?;
 
         // Update the outbound RPC request metrics
         self.update_outbound_rpc_request_metrics(protocol_id, req_len);
@@ -1,15 +1,29 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::protocols::wire::messaging::v1::{MultiplexMessage, NetworkMessage};
+use crate::{
+    counters,
+    protocols::wire::{
+        handshake::v1::ProtocolId,
+        messaging::v1::{MultiplexMessage, NetworkMessage, Priority},
+    },
+};
 use anyhow::{bail, ensure};
 use aptos_channels::Sender;
+use aptos_config::{config::OutboundQueuePolicy, network_id::NetworkContext};
 use aptos_id_generator::{IdGenerator, U32IdGenerator};
-use futures_util::SinkExt;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use futures_util::{future, SinkExt};
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
@@ -58,45 +72,99 @@ impl Debug for StreamFragment {
     }
 }
 
+/// Buffers the inbound streams of a connection. The fragments of different streams
+/// may be interleaved (e.g., so that a high priority message can overtake a large
+/// low priority one), so each stream is reassembled separately (by request id).
+///
+/// Streams that are not completed within `stream_timeout` (e.g., because the remote
+/// peer sent the header but stopped sending fragments) can be discarded with
+/// `remove_expired_streams`, so that they don't occupy the buffer indefinitely.
 pub struct InboundStreamBuffer {
-    stream: Option<InboundStream>,
+    streams: HashMap<u32, InboundStream>,
+    max_concurrent_streams: usize,
     max_fragments: usize,
+    stream_timeout: Duration,
+    time_service: TimeService,
 }
 
 impl InboundStreamBuffer {
-    pub fn new(max_fragments: usize) -> Self {
+    pub fn new(
+        max_concurrent_streams: usize,
+        max_fragments: usize,
+        stream_timeout: Duration,
+        time_service: TimeService,
+    ) -> Self {
         Self {
-            stream: None,
+            streams: HashMap::new(),
+            max_concurrent_streams,
             max_fragments,
+            stream_timeout,
+            time_service,
         }
     }
 
     pub fn new_stream(&mut self, header: StreamHeader) -> anyhow::Result<()> {
-        if let Some(old) = self
-            .stream
-            .replace(InboundStream::new(header, self.max_fragments)?)
-        {
-            bail!("Discard existing stream {}", old.request_id)
-        } else {
-            Ok(())
+        let request_id = header.request_id;
+        if self.streams.contains_key(&request_id) {
+            // The existing stream can never complete, as its request id is reused
+            self.streams.remove(&request_id);
+            bail!("Discard existing stream {}", request_id)
         }
+        ensure!(
+            self.streams.len() < self.max_concurrent_streams,
+            "Too many concurrent streams, limit {}, discard stream {}",
+            self.max_concurrent_streams,
+            request_id
+        );
+        let stream = InboundStream::new(header, self.max_fragments, self.time_service.now())?;
+        self.streams.insert(request_id, stream);
+        Ok(())
     }
 
     pub fn append_fragment(
         &mut self,
         fragment: StreamFragment,
     ) -> anyhow::Result<Option<NetworkMessage>> {
+        let request_id = fragment.request_id;
         let stream = self
-            .stream
-            .as_mut()
-            .ok_or_else(|| anyhow::anyhow!("No stream exist"))?;
-        let stream_end = stream.append_fragment(fragment)?;
-        if stream_end {
-            Ok(Some(self.stream.take().unwrap().message))
-        } else {
-            Ok(None)
+            .streams
+            .get_mut(&request_id)
+            .ok_or_else(|| anyhow::anyhow!("No stream exist for request {}", request_id))?;
+        match stream.append_fragment(fragment) {
+            Ok(true) => Ok(self
+                .streams
+                .remove(&request_id)
+                .map(|stream| stream.message)),
+            Ok(false) => Ok(None),
+            Err(error) => {
+                // The stream is corrupted and can never complete
+                self.streams.remove(&request_id);
+                Err(error)
+            },
         }
     }
+
+    /// Discards the streams that were not completed within the stream timeout,
+    /// and returns their request ids. Any fragments of these streams that arrive
+    /// later are rejected (as there is no stream for them).
+    pub fn remove_expired_streams(&mut self) -> Vec<u32> {
+        let now = self.time_service.now();
+        let stream_timeout = self.stream_timeout;
+        let mut expired_streams = vec![];
+        self.streams.retain(|request_id, stream| {
+            let expired = now.saturating_duration_since(stream.start_time) >= stream_timeout;
+            if expired {
+                expired_streams.push(*request_id);
+            }
+            !expired
+        });
+        expired_streams
+    }
+
+    /// Returns the number of streams that are still being reassembled
+    pub fn num_streams(&self) -> usize {
+        self.streams.len()
+    }
 }
 
 pub struct InboundStream {
@@ -104,10 +172,15 @@ pub struct InboundStream {
     num_fragments: u8,
     current_fragment_id: u8,
     message: NetworkMessage,
+    start_time: Instant,
 }
 
 impl InboundStream {
-    fn new(header: StreamHeader, max_fragments: usize) -> anyhow::Result<Self> {
+    fn new(
+        header: StreamHeader,
+        max_fragments: usize,
+        start_time: Instant,
+    ) -> anyhow::Result<Self> {
         ensure!(
             !matches!(header.message, NetworkMessage::Error(_)),
             "Error message is not expected for stream"
@@ -121,6 +194,7 @@ impl InboundStream {
             num_fragments: header.num_fragments,
             current_fragment_id: 0,
             message: header.message,
+            start_time,
         })
     }
 
@@ -147,17 +221,47 @@ impl InboundStream {
     }
 }
 
+/// A message that is being streamed, i.e., the header and fragments
+/// of the message that have not yet been sent.
+struct ActiveOutboundStream {
+    request_id: u32,
+    protocol_id: Option<ProtocolId>,
+    priority: Priority,
+    pending_messages: VecDeque<StreamMessage>,
+    start_time: Instant,
+    _credit: Option<OwnedSemaphorePermit>, // Returned once the last fragment is sent
+}
+
+/// Splits large messages into a header and fragments, and sends them to the
+/// writer. Up to `max_concurrent_streams` messages are streamed at the same time,
+/// and their fragments are interleaved: the active stream with the highest message
+/// priority is always served first (in round-robin order for equal priorities).
+/// This prevents urgent messages from being blocked behind a huge stream.
+///
+/// Note: interleaving requires the remote peer to reassemble interleaved streams
+/// (see [`InboundStreamBuffer`]). Otherwise, `max_concurrent_streams` must be 1.
+///
+/// Fragments are paced: the writer only buffers a few stream messages (see
+/// [`crate::constants::MAX_PENDING_STREAM_MESSAGES`]), and the next fragment is only
+/// handed over once the writer is ready for it (see `writer_ready`). This way, a giant
+/// stream can't monopolize the connection, and other messages are still accepted while
+/// the writer drains. The time to send the last fragment of each stream is recorded.
 pub struct OutboundStream {
+    network_context: NetworkContext,
     request_id_gen: U32IdGenerator,
     max_frame_size: usize,
     max_message_size: usize,
+    max_concurrent_streams: usize,
+    active_streams: VecDeque<ActiveOutboundStream>,
     stream_tx: Sender<MultiplexMessage>,
 }
 
 impl OutboundStream {
     pub fn new(
+        network_context: NetworkContext,
         max_frame_size: usize,
         max_message_size: usize,
+        max_concurrent_streams: usize,
         stream_tx: Sender<MultiplexMessage>,
     ) -> Self {
         // some buffer for headers
@@ -168,10 +272,17 @@ impl OutboundStream {
             max_frame_size,
             max_message_size
         );
+        assert!(
+            max_concurrent_streams > 0,
+            "At least one concurrent stream must be supported"
+        );
         Self {
+            network_context,
             request_id_gen: U32IdGenerator::new(),
             max_frame_size,
             max_message_size,
+            max_concurrent_streams,
+            active_streams: VecDeque::new(),
             stream_tx,
         }
     }
@@ -180,7 +291,36 @@ impl OutboundStream {
         message.data_len() > self.max_frame_size
     }
 
-    pub async fn stream_message(&mut self, mut message: NetworkMessage) -> anyhow::Result<()> {
+    /// Returns true iff another message can be streamed concurrently
+    pub fn can_start_stream(&self) -> bool {
+        self.active_streams.len() < self.max_concurrent_streams
+    }
+
+    /// Returns true iff there are messages that have not been fully streamed
+    pub fn has_active_streams(&self) -> bool {
+        !self.active_streams.is_empty()
+    }
+
+    /// Returns true iff a message of the given protocol has not been fully streamed
+    pub fn is_streaming(&self, protocol_id: Option<ProtocolId>) -> bool {
+        self.active_streams
+            .iter()
+            .any(|stream| stream.protocol_id == protocol_id)
+    }
+
+    /// Splits the message into a header and fragments, and adds it to the active
+    /// streams. The stream messages are only sent by `send_next_stream_message`.
+    pub fn start_stream(&mut self, message: OutboundMessage) -> anyhow::Result<()> {
+        let OutboundMessage {
+            protocol_id,
+            mut message,
+            credit,
+        } = message;
+        ensure!(
+            self.can_start_stream(),
+            "Too many concurrent streams, limit {}",
+            self.max_concurrent_streams,
+        );
         ensure!(
             message.data_len() <= self.max_message_size,
             "Message length {} exceed size limit {}",
@@ -194,6 +334,7 @@ impl OutboundStream {
             self.max_frame_size,
         );
         let request_id = self.request_id_gen.next();
+        let priority = message.priority();
         let rest = match &mut message {
             NetworkMessage::Error(_) => {
                 unreachable!("NetworkMessage::Error should always fit in a single frame")
@@ -218,19 +359,303 @@ impl OutboundStream {
             num_fragments: chunks.len() as u8,
             message,
         });
-        self.stream_tx
-            .send(MultiplexMessage::Stream(header))
-            .await?;
-        for (index, chunk) in chunks.enumerate() {
-            let message = StreamMessage::Fragment(StreamFragment {
+        let fragments = chunks.enumerate().map(|(index, chunk)| {
+            StreamMessage::Fragment(StreamFragment {
                 request_id,
                 fragment_id: index as u8 + 1,
                 raw_data: Vec::from(chunk),
-            });
-            self.stream_tx
-                .send(MultiplexMessage::Stream(message))
-                .await?;
+            })
+        });
+        self.active_streams.push_back(ActiveOutboundStream {
+            request_id,
+            protocol_id,
+            priority,
+            pending_messages: std::iter::once(header).chain(fragments).collect(),
+            start_time: Instant::now(),
+            _credit: credit,
+        });
+        Ok(())
+    }
+
+    /// Sends the next header or fragment of the active stream with the highest
+    /// priority. Streams with equal priorities are served in round-robin order.
+    pub async fn send_next_stream_message(&mut self) -> anyhow::Result<()> {
+        let Some(highest_priority) = self.active_streams.iter().map(|s| s.priority).max() else {
+            return Ok(());
+        };
+        let index = self
+            .active_streams
+            .iter()
+            .position(|stream| stream.priority == highest_priority)
+            .expect("The highest priority stream must exist");
+        let mut stream = self
+            .active_streams
+            .remove(index)
+            .expect("The highest priority stream must exist");
+        let message = stream
+            .pending_messages
+            .pop_front()
+            .expect("Active streams must have pending messages");
+        self.stream_tx
+            .send(MultiplexMessage::Stream(message))
+            .await
+            .map_err(|error| {
+                anyhow::anyhow!("Failed to send stream {}: {}", stream.request_id, error)
+            })?;
+        if stream.pending_messages.is_empty() {
+            counters::stream_time_to_last_fragment(&self.network_context)
+                .observe(stream.start_time.elapsed().as_secs_f64());
+        } else {
+            self.active_streams.push_back(stream);
         }
         Ok(())
     }
+
+    /// Waits until the writer can accept another stream message, i.e., until it has
+    /// drained enough of the previous ones. Messages that had to wait are counted as paced.
+    pub async fn writer_ready(&mut self) -> anyhow::Result<()> {
+        let mut is_paced = false;
+        future::poll_fn(|cx| {
+            let poll = self.stream_tx.poll_ready_unpin(cx);
+            is_paced |= poll.is_pending();
+            poll
+        })
+        .await
+        .map_err(|error| anyhow::anyhow!("Failed to wait for the writer: {}", error))?;
+        if is_paced {
+            counters::paced_stream_fragments(&self.network_context).inc();
+        }
+        Ok(())
+    }
+
+    /// Streams the message to completion (along with any other active streams)
+    pub async fn stream_message(&mut self, message: NetworkMessage) -> anyhow::Result<()> {
+        self.start_stream(OutboundMessage::new(None, message))?;
+        while self.has_active_streams() {
+            self.writer_ready().await?;
+            self.send_next_stream_message().await?;
+        }
+        Ok(())
+    }
+}
+
+/// An outbound network message, tagged with the protocol it belongs to. The
+/// protocol isn't always on the wire (e.g., for RPC responses), so it is carried
+/// alongside the message to allow messages to be scheduled per protocol.
+#[derive(Debug)]
+pub struct OutboundMessage {
+    pub protocol_id: Option<ProtocolId>,
+    pub message: NetworkMessage,
+    pub credit: Option<OwnedSemaphorePermit>, // Returned once the message leaves the queue
+}
+
+impl OutboundMessage {
+    pub fn new(protocol_id: Option<ProtocolId>, message: NetworkMessage) -> Self {
+        Self {
+            protocol_id,
+            message,
+            credit: None,
+        }
+    }
+
+    /// Attaches the outbound queue credit of the message (see [`OutboundQueueCredits`])
+    pub fn with_credit(mut self, credit: Option<OwnedSemaphorePermit>) -> Self {
+        self.credit = credit;
+        self
+    }
+}
+
+/// The outcome of enqueueing an outbound message
+#[derive(Debug)]
+pub enum QueueOutcome {
+    Queued,        // The message was queued
+    DroppedNewest, // The queue was full, so the (direct send) message was dropped
+    DroppedOldest, // The queue was full, so the oldest queued direct send was dropped
+}
+
+/// Returns the policy applied when the queue of the given protocol is full. Unless a
+/// drop policy is configured, the senders of the protocol are backpressured.
+fn outbound_queue_policy(
+    queue_policies: &HashMap<String, OutboundQueuePolicy>,
+    protocol_id: Option<ProtocolId>,
+) -> OutboundQueuePolicy {
+    protocol_id
+        .and_then(|protocol_id| queue_policies.get(protocol_id.as_str()))
+        .copied()
+        .unwrap_or(OutboundQueuePolicy::Block)
+}
+
+/// Returns true iff the message may be dropped when the queue of its protocol is full.
+/// RPC messages are never dropped: they are already bounded by the RPC concurrency
+/// limits, and a dropped RPC would only surface as a timeout on the remote peer.
+fn is_droppable(message: &NetworkMessage) -> bool {
+    matches!(message, NetworkMessage::DirectSendMsg(_))
+}
+
+/// Limits the direct send messages (per protocol) that are waiting to be handed to the
+/// connection, for all protocols that apply backpressure (see [`OutboundQueuePolicy::Block`]).
+/// A credit must be acquired before a message is sent to the writer, and it is returned once
+/// the message leaves the outbound queue. A protocol that runs out of credits only delays its
+/// own senders, and the messages of other protocols (including all RPC responses) are not
+/// affected.
+pub struct OutboundQueueCredits {
+    max_credits_per_protocol: usize,
+    queue_policies: HashMap<String, OutboundQueuePolicy>, // Policy overrides (by protocol name)
+    credits: HashMap<ProtocolId, Arc<Semaphore>>,
+}
+
+impl OutboundQueueCredits {
+    pub fn new(
+        max_credits_per_protocol: usize,
+        queue_policies: HashMap<String, OutboundQueuePolicy>,
+    ) -> Self {
+        Self {
+            max_credits_per_protocol,
+            queue_policies,
+            credits: HashMap::new(),
+        }
+    }
+
+    /// Returns the credits of the given protocol, or None if the protocol doesn't
+    /// apply backpressure (i.e., its messages are dropped when its queue is full)
+    pub fn protocol_credits(&mut self, protocol_id: ProtocolId) -> Option<Arc<Semaphore>> {
+        if outbound_queue_policy(&self.queue_policies, Some(protocol_id))
+            != OutboundQueuePolicy::Block
+        {
+            return None;
+        }
+        let max_credits_per_protocol = self.max_credits_per_protocol;
+        let credits = self
+            .credits
+            .entry(protocol_id)
+            .or_insert_with(|| Arc::new(Semaphore::new(max_credits_per_protocol)));
+        Some(credits.clone())
+    }
+}
+
+/// Queues the outbound messages of the protocols that have a large message (i.e., one that
+/// must be streamed) waiting to be sent. Each protocol is served in FIFO order, so its small
+/// messages are never reordered ahead of its large ones, and the protocols are served in
+/// round-robin order. This prevents a single protocol with many large messages (e.g., state
+/// sync) from starving the large messages of another protocol (e.g., consensus block
+/// retrieval) on the same connection.
+///
+/// The queue never blocks: protocols that apply backpressure are bounded by their
+/// [`OutboundQueueCredits`] (and the RPC concurrency limits), and the drop policies
+/// are applied to the direct send messages of all other protocols.
+pub struct OutboundStreamQueue {
+    max_queued_messages_per_protocol: usize,
+    queue_policies: HashMap<String, OutboundQueuePolicy>, // Policy overrides (by protocol name)
+    queued_messages: HashMap<Option<ProtocolId>, VecDeque<OutboundMessage>>,
+    protocol_order: VecDeque<Option<ProtocolId>>, // Protocols with queued messages (in service order)
+}
+
+impl OutboundStreamQueue {
+    pub fn new(
+        max_queued_messages_per_protocol: usize,
+        queue_policies: HashMap<String, OutboundQueuePolicy>,
+    ) -> Self {
+        Self {
+            max_queued_messages_per_protocol,
+            queue_policies,
+            queued_messages: HashMap::new(),
+            protocol_order: VecDeque::new(),
+        }
+    }
+
+    /// Returns the policy applied when the queue of the given protocol is full
+    pub fn queue_policy(&self, protocol_id: Option<ProtocolId>) -> OutboundQueuePolicy {
+        outbound_queue_policy(&self.queue_policies, protocol_id)
+    }
+
+    /// Enqueues the message behind the other queued messages of its protocol. If the
+    /// protocol has already reached its quota of queued messages, and it applies a drop
+    /// policy, the policy is applied to its direct send messages.
+    pub fn push(&mut self, message: OutboundMessage) -> QueueOutcome {
+        let protocol_id = message.protocol_id;
+        let queue_policy = self.queue_policy(protocol_id);
+        let queue = self.queued_messages.entry(protocol_id).or_default();
+        let is_new_protocol = queue.is_empty();
+        let mut outcome = QueueOutcome::Queued;
+        if queue.len() >= self.max_queued_messages_per_protocol {
+            match queue_policy {
+                OutboundQueuePolicy::Block => (), // The senders are bounded by their credits
+                OutboundQueuePolicy::DropNewest => {
+                    if is_droppable(&message.message) {
+                        return QueueOutcome::DroppedNewest;
+                    }
+                },
+                OutboundQueuePolicy::DropOldest => {
+                    if let Some(index) = queue
+                        .iter()
+                        .position(|queued| is_droppable(&queued.message))
+                    {
+                        queue.remove(index);
+                        outcome = QueueOutcome::DroppedOldest;
+                    } else if is_droppable(&message.message) {
+                        return QueueOutcome::DroppedNewest;
+                    }
+                },
+            }
+        }
+        if is_new_protocol {
+            self.protocol_order.push_back(protocol_id);
+        }
+        queue.push_back(message);
+        outcome
+    }
+
+    /// Dequeues the next message that is ready to be sent (according to `is_ready`). Only
+    /// the oldest message of each protocol is considered, and the message with the highest
+    /// priority is dequeued first (rotating between protocols with the same priority).
+    pub fn pop_next(
+        &mut self,
+        is_ready: impl Fn(&OutboundMessage) -> bool,
+    ) -> Option<OutboundMessage> {
+        let mut next_message: Option<(usize, Priority)> = None; // (position, priority)
+        for (position, protocol_id) in self.protocol_order.iter().enumerate() {
+            let Some(message) = self
+                .queued_messages
+                .get(protocol_id)
+                .and_then(|queue| queue.front())
+            else {
+                continue;
+            };
+            let priority = message.message.priority();
+            let is_more_urgent =
+                next_message.map_or(true, |(_, next_priority)| priority > next_priority);
+            if is_more_urgent && is_ready(message) {
+                next_message = Some((position, priority));
+            }
+        }
+        let (position, _) = next_message?;
+        let protocol_id = self
+            .protocol_order
+            .remove(position)
+            .expect("The ready protocol must exist");
+        let queue = self
+            .queued_messages
+            .get_mut(&protocol_id)
+            .expect("Protocols in the service order must have queued messages");
+        let message = queue
+            .pop_front()
+            .expect("Protocols in the service order must have queued messages");
+        if queue.is_empty() {
+            self.queued_messages.remove(&protocol_id);
+        } else {
+            self.protocol_order.push_back(protocol_id);
+        }
+        Some(message)
+    }
+
+    /// Returns the number of messages queued for the given protocol
+    pub fn num_queued_messages(&self, protocol_id: Option<ProtocolId>) -> usize {
+        self.queued_messages
+            .get(&protocol_id)
+            .map_or(0, |queue| queue.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.protocol_order.is_empty()
+    }
 }
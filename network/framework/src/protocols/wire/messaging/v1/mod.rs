@@ -62,6 +62,16 @@ impl NetworkMessage {
             NetworkMessage::DirectSendMsg(message) => message.raw_msg.len(),
         }
     }
+
+    /// The priority of the message (higher values are more urgent)
+    pub fn priority(&self) -> Priority {
+        match self {
+            NetworkMessage::Error(_) => Priority::default(),
+            NetworkMessage::RpcRequest(request) => request.priority,
+            NetworkMessage::RpcResponse(response) => response.priority,
+            NetworkMessage::DirectSendMsg(message) => message.priority,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -103,6 +113,42 @@ pub type RequestId = u32;
 /// Create alias Priority for u8.
 pub type Priority = u8;
 
+/// Returns the priority of the outbound messages (i.e., direct sends and RPC requests) of the
+/// given protocol. The messages of the latency-sensitive consensus protocols are prioritized
+/// over bulk data (e.g., state sync), and RPC responses inherit the priority of the request.
+pub fn message_priority(protocol_id: ProtocolId) -> Priority {
+    use ProtocolId::*;
+    match protocol_id {
+        ConsensusRpcBcs
+        | ConsensusDirectSendBcs
+        | ConsensusDirectSendJson
+        | ConsensusRpcJson
+        | ConsensusRpcCompressed
+        | ConsensusDirectSendCompressed
+        | DKGDirectSendCompressed
+        | DKGDirectSendBcs
+        | DKGDirectSendJson
+        | DKGRpcCompressed
+        | DKGRpcBcs
+        | DKGRpcJson
+        | JWKConsensusDirectSendCompressed
+        | JWKConsensusDirectSendBcs
+        | JWKConsensusDirectSendJson
+        | JWKConsensusRpcCompressed
+        | JWKConsensusRpcBcs
+        | JWKConsensusRpcJson => 1,
+        MempoolDirectSend
+        | StateSyncDirectSend
+        | DiscoveryDirectSend
+        | HealthCheckerRpc
+        | StorageServiceRpc
+        | MempoolRpc
+        | PeerMonitoringServiceRpc
+        | NetbenchDirectSend
+        | NetbenchRpc => Priority::default(),
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
 pub struct RpcRequest {
@@ -4,14 +4,33 @@
 
 use super::*;
 use crate::{
-    protocols::stream::{InboundStreamBuffer, OutboundStream, StreamFragment, StreamHeader},
+    counters,
+    protocols::stream::{
+        InboundStreamBuffer, OutboundMessage, OutboundQueueCredits, OutboundStream,
+        OutboundStreamQueue, QueueOutcome, StreamFragment, StreamHeader,
+    },
     testutils::fake_socket::{ReadOnlyTestSocket, ReadWriteTestSocket},
 };
+use aptos_config::{config::OutboundQueuePolicy, network_id::NetworkContext};
 use aptos_memsocket::MemorySocket;
+use aptos_time_service::TimeService;
 use bcs::test_helpers::assert_canonical_encode_decode;
 use futures::{executor::block_on, future, sink::SinkExt, stream::StreamExt};
 use futures_util::stream::select;
 use proptest::{collection::vec, prelude::*};
+use std::{collections::HashMap, time::Duration};
+
+/// The inbound stream timeout used by the tests (unless time is mocked)
+const INBOUND_STREAM_TIMEOUT: Duration = Duration::from_secs(60);
+
+fn new_inbound_stream_buffer(max_concurrent_streams: usize) -> InboundStreamBuffer {
+    InboundStreamBuffer::new(
+        max_concurrent_streams,
+        255,
+        INBOUND_STREAM_TIMEOUT,
+        TimeService::real(),
+    )
+}
 
 // Ensure serialization of ProtocolId enum takes 1 byte.
 #[test]
@@ -76,6 +95,503 @@ fn stream_message() {
     ],);
 }
 
+/// Returns the ids of the queued messages (in the order they are dequeued), assuming all
+/// messages are ready to be sent
+fn drain_outbound_stream_queue(queue: &mut OutboundStreamQueue) -> Vec<(Option<ProtocolId>, u32)> {
+    std::iter::from_fn(|| queue.pop_next(|_| true))
+        .map(|message| match message.message {
+            NetworkMessage::RpcResponse(response) => (message.protocol_id, response.request_id),
+            NetworkMessage::DirectSendMsg(direct_send) => {
+                (message.protocol_id, direct_send.raw_msg[0] as u32)
+            },
+            message => panic!("Unexpected message: {:?}", message),
+        })
+        .collect()
+}
+
+fn create_queued_response(request_id: u32) -> NetworkMessage {
+    NetworkMessage::RpcResponse(RpcResponse {
+        request_id,
+        priority: 0,
+        raw_response: vec![0; 10],
+    })
+}
+
+fn create_queued_direct_send(protocol_id: ProtocolId, message_id: u8) -> NetworkMessage {
+    NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id,
+        priority: 0,
+        raw_msg: vec![message_id; 10],
+    })
+}
+
+#[test]
+fn outbound_stream_queue_round_robin() {
+    let state_sync = Some(ProtocolId::StorageServiceRpc);
+    let consensus = Some(ProtocolId::ConsensusRpcBcs);
+
+    // Queue several messages for state sync, and then one for consensus
+    let mut queue = OutboundStreamQueue::new(3, HashMap::new());
+    for request_id in 0..3 {
+        assert!(matches!(
+            queue.push(OutboundMessage::new(
+                state_sync,
+                create_queued_response(request_id)
+            )),
+            QueueOutcome::Queued
+        ));
+    }
+    assert!(matches!(
+        queue.push(OutboundMessage::new(consensus, create_queued_response(3))),
+        QueueOutcome::Queued
+    ));
+
+    // Verify the queue never blocks or drops RPC responses (even over the quota)
+    assert!(matches!(
+        queue.push(OutboundMessage::new(state_sync, create_queued_response(4))),
+        QueueOutcome::Queued
+    ));
+    assert_eq!(queue.num_queued_messages(state_sync), 4);
+    assert_eq!(queue.num_queued_messages(consensus), 1);
+
+    // Verify consensus isn't starved by the state sync backlog
+    assert_eq!(drain_outbound_stream_queue(&mut queue), vec![
+        (state_sync, 0),
+        (consensus, 3),
+        (state_sync, 1),
+        (state_sync, 2),
+        (state_sync, 4)
+    ]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn outbound_stream_queue_fifo() {
+    let state_sync = Some(ProtocolId::StorageServiceRpc);
+    let consensus = Some(ProtocolId::ConsensusRpcBcs);
+
+    // Queue a large and then a small message for state sync, and one for consensus
+    let large_response = NetworkMessage::RpcResponse(RpcResponse {
+        request_id: 0,
+        priority: 0,
+        raw_response: vec![0; 1000],
+    });
+    let mut queue = OutboundStreamQueue::new(3, HashMap::new());
+    for (protocol_id, message) in [
+        (state_sync, large_response),
+        (state_sync, create_queued_response(1)),
+        (consensus, create_queued_response(2)),
+    ] {
+        assert!(matches!(
+            queue.push(OutboundMessage::new(protocol_id, message)),
+            QueueOutcome::Queued
+        ));
+    }
+
+    // Verify the small state sync message is never sent ahead of the large one
+    let only_small_messages = |message: &OutboundMessage| message.message.data_len() < 100;
+    let message = queue.pop_next(only_small_messages).unwrap();
+    assert_eq!(message.protocol_id, consensus);
+    assert!(queue.pop_next(only_small_messages).is_none());
+    assert_eq!(queue.num_queued_messages(state_sync), 2);
+
+    // Verify the state sync messages are dequeued in order once they are ready
+    assert_eq!(drain_outbound_stream_queue(&mut queue), vec![
+        (state_sync, 0),
+        (state_sync, 1)
+    ]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn outbound_stream_queue_policies() {
+    let state_sync = Some(ProtocolId::StateSyncDirectSend);
+    let consensus = Some(ProtocolId::ConsensusDirectSendBcs);
+    let mempool = Some(ProtocolId::MempoolDirectSend);
+
+    // Override the policies for state sync and mempool, and rely on the default for consensus
+    let queue_policies = [
+        (
+            ProtocolId::StateSyncDirectSend.as_str().to_string(),
+            OutboundQueuePolicy::DropOldest,
+        ),
+        (
+            ProtocolId::MempoolDirectSend.as_str().to_string(),
+            OutboundQueuePolicy::DropNewest,
+        ),
+    ]
+    .into_iter()
+    .collect();
+    let mut queue = OutboundStreamQueue::new(2, queue_policies);
+    assert_eq!(
+        queue.queue_policy(state_sync),
+        OutboundQueuePolicy::DropOldest
+    );
+    assert_eq!(queue.queue_policy(consensus), OutboundQueuePolicy::Block);
+    assert_eq!(queue.queue_policy(mempool), OutboundQueuePolicy::DropNewest);
+
+    // Fill the queues of all protocols
+    for message_id in 0..2 {
+        for protocol_id in [state_sync, consensus, mempool] {
+            assert!(matches!(
+                queue.push(OutboundMessage::new(
+                    protocol_id,
+                    create_queued_direct_send(protocol_id.unwrap(), message_id)
+                )),
+                QueueOutcome::Queued
+            ));
+        }
+    }
+
+    // Verify each policy is applied once the queues are full
+    assert!(matches!(
+        queue.push(OutboundMessage::new(
+            state_sync,
+            create_queued_direct_send(ProtocolId::StateSyncDirectSend, 2)
+        )),
+        QueueOutcome::DroppedOldest
+    ));
+    assert!(matches!(
+        queue.push(OutboundMessage::new(
+            consensus,
+            create_queued_direct_send(ProtocolId::ConsensusDirectSendBcs, 2)
+        )),
+        QueueOutcome::Queued
+    ));
+    assert!(matches!(
+        queue.push(OutboundMessage::new(
+            mempool,
+            create_queued_direct_send(ProtocolId::MempoolDirectSend, 2)
+        )),
+        QueueOutcome::DroppedNewest
+    ));
+    assert_eq!(queue.num_queued_messages(state_sync), 2);
+    assert_eq!(queue.num_queued_messages(consensus), 3);
+    assert_eq!(queue.num_queued_messages(mempool), 2);
+
+    // Verify RPC messages are never dropped (even if the queue is full)
+    assert!(matches!(
+        queue.push(OutboundMessage::new(mempool, create_queued_response(3))),
+        QueueOutcome::Queued
+    ));
+    assert_eq!(queue.num_queued_messages(mempool), 3);
+
+    // Verify the oldest state sync message was replaced by the newest
+    let state_sync_message_ids: Vec<_> = drain_outbound_stream_queue(&mut queue)
+        .into_iter()
+        .filter(|(protocol_id, _)| *protocol_id == state_sync)
+        .map(|(_, message_id)| message_id)
+        .collect();
+    assert_eq!(state_sync_message_ids, vec![1, 2]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn outbound_stream_queue_priority() {
+    let create_request = |protocol_id, request_id| {
+        NetworkMessage::RpcRequest(RpcRequest {
+            protocol_id,
+            request_id,
+            priority: message_priority(protocol_id),
+            raw_request: vec![0; 10],
+        })
+    };
+    let state_sync = ProtocolId::StorageServiceRpc;
+    let mempool = ProtocolId::MempoolRpc;
+    let consensus = ProtocolId::ConsensusRpcBcs;
+    assert!(message_priority(consensus) > message_priority(state_sync));
+
+    // Queue messages for state sync and mempool, and then for consensus
+    let mut queue = OutboundStreamQueue::new(3, HashMap::new());
+    for (protocol_id, request_id) in [
+        (state_sync, 0),
+        (mempool, 1),
+        (state_sync, 2),
+        (consensus, 3),
+        (consensus, 4),
+    ] {
+        assert!(matches!(
+            queue.push(OutboundMessage::new(
+                Some(protocol_id),
+                create_request(protocol_id, request_id)
+            )),
+            QueueOutcome::Queued
+        ));
+    }
+
+    // Verify consensus is served first, and the others in round-robin order
+    let request_ids: Vec<_> = std::iter::from_fn(|| queue.pop_next(|_| true))
+        .map(|message| match message.message {
+            NetworkMessage::RpcRequest(request) => request.request_id,
+            message => panic!("Unexpected message: {:?}", message),
+        })
+        .collect();
+    assert_eq!(request_ids, vec![3, 4, 0, 1, 2]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn outbound_queue_credits() {
+    let queue_policies = [(
+        ProtocolId::MempoolDirectSend.as_str().to_string(),
+        OutboundQueuePolicy::DropNewest,
+    )]
+    .into_iter()
+    .collect();
+    let mut credits = OutboundQueueCredits::new(2, queue_policies);
+
+    // Verify protocols that drop messages don't need credits
+    assert!(credits
+        .protocol_credits(ProtocolId::MempoolDirectSend)
+        .is_none());
+
+    // Exhaust the consensus credits, and verify other protocols are unaffected
+    let consensus_credits = credits
+        .protocol_credits(ProtocolId::ConsensusDirectSendBcs)
+        .unwrap();
+    let consensus_credit = consensus_credits.clone().try_acquire_owned().unwrap();
+    let message = OutboundMessage::new(
+        Some(ProtocolId::ConsensusDirectSendBcs),
+        create_queued_direct_send(ProtocolId::ConsensusDirectSendBcs, 0),
+    )
+    .with_credit(Some(consensus_credits.clone().try_acquire_owned().unwrap()));
+    assert!(consensus_credits.clone().try_acquire_owned().is_err());
+    let state_sync_credits = credits
+        .protocol_credits(ProtocolId::StateSyncDirectSend)
+        .unwrap();
+    assert_eq!(state_sync_credits.available_permits(), 2);
+
+    // Verify the credits are returned once the messages are done
+    drop(message);
+    drop(consensus_credit);
+    let consensus_credits = credits
+        .protocol_credits(ProtocolId::ConsensusDirectSendBcs)
+        .unwrap();
+    assert_eq!(consensus_credits.available_permits(), 2);
+}
+
+#[test]
+fn outbound_stream_priority_interleaving() {
+    let create_request = |request_id, priority| {
+        NetworkMessage::RpcRequest(RpcRequest {
+            protocol_id: ProtocolId::StorageServiceRpc,
+            request_id,
+            priority,
+            raw_request: vec![request_id as u8; 200],
+        })
+    };
+
+    // Start three streams (each with a header and 3 fragments): two with
+    // low priority, and one with high priority.
+    let (stream_tx, stream_rx) = aptos_channels::new_test(1024);
+    let mut outbound_stream =
+        OutboundStream::new(NetworkContext::mock(), 128, 64 * 255, 3, stream_tx);
+    let protocol_id = Some(ProtocolId::StorageServiceRpc);
+    for (request_id, priority) in [(0, 0), (1, 0), (2, 1)] {
+        outbound_stream
+            .start_stream(OutboundMessage::new(
+                protocol_id,
+                create_request(request_id, priority),
+            ))
+            .unwrap();
+    }
+    assert!(!outbound_stream.can_start_stream());
+    assert!(outbound_stream.is_streaming(protocol_id));
+    assert!(!outbound_stream.is_streaming(Some(ProtocolId::ConsensusRpcBcs)));
+    assert!(outbound_stream
+        .start_stream(OutboundMessage::new(protocol_id, create_request(3, 1)))
+        .is_err());
+
+    // Send all stream messages
+    block_on(async {
+        while outbound_stream.has_active_streams() {
+            outbound_stream.send_next_stream_message().await.unwrap();
+        }
+    });
+    drop(outbound_stream);
+    let stream_messages: Vec<_> = block_on(stream_rx.collect::<Vec<_>>())
+        .into_iter()
+        .map(|message| match message {
+            MultiplexMessage::Stream(message) => message,
+            message => panic!("Unexpected message: {:?}", message),
+        })
+        .collect();
+
+    // Verify the high priority stream is sent first, and the others are interleaved
+    let stream_order: Vec<_> = stream_messages
+        .iter()
+        .map(|message| match message {
+            StreamMessage::Header(header) => (header.request_id, 0),
+            StreamMessage::Fragment(fragment) => (fragment.request_id, fragment.fragment_id),
+        })
+        .collect();
+    assert_eq!(stream_order, vec![
+        (2, 0),
+        (2, 1),
+        (2, 2),
+        (2, 3),
+        (0, 0),
+        (1, 0),
+        (0, 1),
+        (1, 1),
+        (0, 2),
+        (1, 2),
+        (0, 3),
+        (1, 3)
+    ]);
+
+    // Verify the interleaved streams are reassembled
+    let mut inbound_stream = new_inbound_stream_buffer(3);
+    let mut received_messages = vec![];
+    for message in stream_messages {
+        match message {
+            StreamMessage::Header(header) => inbound_stream.new_stream(header).unwrap(),
+            StreamMessage::Fragment(fragment) => {
+                if let Some(message) = inbound_stream.append_fragment(fragment).unwrap() {
+                    received_messages.push(message);
+                }
+            },
+        }
+    }
+    assert_eq!(received_messages, vec![
+        create_request(2, 1),
+        create_request(0, 0),
+        create_request(1, 0)
+    ]);
+    assert_eq!(inbound_stream.num_streams(), 0);
+}
+
+#[test]
+fn inbound_stream_buffer_limits() {
+    let create_header = |request_id| StreamHeader {
+        request_id,
+        num_fragments: 1,
+        message: NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id: ProtocolId::MempoolDirectSend,
+            priority: 0,
+            raw_msg: vec![0; 10],
+        }),
+    };
+
+    // Verify the concurrent stream limit is enforced
+    let mut inbound_stream = new_inbound_stream_buffer(2);
+    inbound_stream.new_stream(create_header(0)).unwrap();
+    inbound_stream.new_stream(create_header(1)).unwrap();
+    assert!(inbound_stream.new_stream(create_header(2)).is_err());
+    assert_eq!(inbound_stream.num_streams(), 2);
+
+    // Verify a reused request id discards the existing stream
+    assert!(inbound_stream.new_stream(create_header(1)).is_err());
+    assert_eq!(inbound_stream.num_streams(), 1);
+
+    // Verify fragments for unknown streams are rejected
+    let fragment = |request_id, fragment_id| StreamFragment {
+        request_id,
+        fragment_id,
+        raw_data: vec![1; 10],
+    };
+    assert!(inbound_stream.append_fragment(fragment(1, 1)).is_err());
+
+    // Verify a corrupted stream is discarded
+    assert!(inbound_stream.append_fragment(fragment(0, 2)).is_err());
+    assert_eq!(inbound_stream.num_streams(), 0);
+}
+
+#[test]
+fn inbound_stream_buffer_timeout() {
+    let create_header = |request_id| StreamHeader {
+        request_id,
+        num_fragments: 2,
+        message: NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id: ProtocolId::MempoolDirectSend,
+            priority: 0,
+            raw_msg: vec![0; 10],
+        }),
+    };
+    let time_service = TimeService::mock();
+    let mock_time_service = time_service.clone().into_mock();
+    let mut inbound_stream = InboundStreamBuffer::new(2, 255, INBOUND_STREAM_TIMEOUT, time_service);
+    let fragment = |request_id, fragment_id| StreamFragment {
+        request_id,
+        fragment_id,
+        raw_data: vec![1; 10],
+    };
+
+    // Start a stream and make some progress on it
+    inbound_stream.new_stream(create_header(0)).unwrap();
+    assert_eq!(
+        inbound_stream.append_fragment(fragment(0, 1)).unwrap(),
+        None
+    );
+
+    // Start another stream later, and verify nothing expires before the timeout
+    mock_time_service.advance(INBOUND_STREAM_TIMEOUT / 2);
+    inbound_stream.new_stream(create_header(1)).unwrap();
+    assert!(inbound_stream.remove_expired_streams().is_empty());
+    assert_eq!(inbound_stream.num_streams(), 2);
+
+    // Verify only the first (incomplete) stream is discarded once it times out
+    mock_time_service.advance(INBOUND_STREAM_TIMEOUT / 2);
+    assert_eq!(inbound_stream.remove_expired_streams(), vec![0]);
+    assert_eq!(inbound_stream.num_streams(), 1);
+
+    // Verify the remaining fragments of the expired stream are rejected
+    assert!(inbound_stream.append_fragment(fragment(0, 2)).is_err());
+
+    // Verify the discarded stream frees up a slot for a new stream
+    inbound_stream.new_stream(create_header(2)).unwrap();
+    assert_eq!(inbound_stream.num_streams(), 2);
+
+    // Verify the second stream can still complete
+    for fragment_id in 1..=2 {
+        let message = inbound_stream
+            .append_fragment(fragment(1, fragment_id))
+            .unwrap();
+        assert_eq!(message.is_some(), fragment_id == 2);
+    }
+    assert_eq!(inbound_stream.num_streams(), 1);
+}
+
+#[test]
+fn outbound_stream_pacing() {
+    let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: ProtocolId::StateSyncDirectSend,
+        priority: 0,
+        raw_msg: vec![0; 1000],
+    });
+
+    // Stream a message (with a header and 15 fragments) through a writer
+    // that can only hold a couple of stream messages at a time.
+    let network_context = NetworkContext::mock();
+    let num_paced_fragments = counters::paced_stream_fragments(&network_context).get();
+    let (stream_tx, stream_rx) = aptos_channels::new_test(1);
+    let mut outbound_stream = OutboundStream::new(network_context, 128, 64 * 255, 1, stream_tx);
+    let message_clone = message.clone();
+    let f_stream = async move {
+        outbound_stream.stream_message(message_clone).await.unwrap();
+    };
+    let (_, stream_messages) = block_on(future::join(f_stream, stream_rx.collect::<Vec<_>>()));
+
+    // Verify the fragments were paced, and the message was streamed in full
+    assert!(counters::paced_stream_fragments(&network_context).get() > num_paced_fragments);
+    let mut inbound_stream = new_inbound_stream_buffer(1);
+    let mut received_messages = vec![];
+    for message in stream_messages {
+        match message {
+            MultiplexMessage::Stream(StreamMessage::Header(header)) => {
+                inbound_stream.new_stream(header).unwrap()
+            },
+            MultiplexMessage::Stream(StreamMessage::Fragment(fragment)) => {
+                if let Some(message) = inbound_stream.append_fragment(fragment).unwrap() {
+                    received_messages.push(message);
+                }
+            },
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+    assert_eq!(received_messages, vec![message]);
+}
+
 #[test]
 fn aptosnet_wire_test_vectors() {
     let message = MultiplexMessage::Message(NetworkMessage::DirectSendMsg(DirectSendMsg {
@@ -237,8 +753,9 @@ proptest! {
         let message_rx = MultiplexMessageStream::new(socket_rx, 128);
         let (stream_tx, stream_rx) = aptos_channels::new_test(1024);
         let (mut msg_tx, msg_rx) = aptos_channels::new_test(1024);
-        let mut outbound_stream = OutboundStream::new(128, 64 * 255, stream_tx);
-        let mut inbound_stream = InboundStreamBuffer::new(255);
+        let mut outbound_stream =
+            OutboundStream::new(NetworkContext::mock(), 128, 64 * 255, 1, stream_tx);
+        let mut inbound_stream = new_inbound_stream_buffer(1);
 
         let messages_clone = messages.clone();
         let f_stream_all = async move {
@@ -18,6 +18,9 @@ impl serde::Serialize for GetTransactionsRequest {
         if self.batch_size.is_some() {
             len += 1;
         }
+        if self.resume_token.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.GetTransactionsRequest", len)?;
         if let Some(v) = self.starting_version.as_ref() {
             struct_ser.serialize_field("startingVersion", ToString::to_string(&v).as_str())?;
@@ -28,6 +31,9 @@ impl serde::Serialize for GetTransactionsRequest {
         if let Some(v) = self.batch_size.as_ref() {
             struct_ser.serialize_field("batchSize", ToString::to_string(&v).as_str())?;
         }
+        if let Some(v) = self.resume_token.as_ref() {
+            struct_ser.serialize_field("resumeToken", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -44,6 +50,8 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
             "transactionsCount",
             "batch_size",
             "batchSize",
+            "resume_token",
+            "resumeToken",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -51,6 +59,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
             StartingVersion,
             TransactionsCount,
             BatchSize,
+            ResumeToken,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -75,6 +84,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                             "startingVersion" | "starting_version" => Ok(GeneratedField::StartingVersion),
                             "transactionsCount" | "transactions_count" => Ok(GeneratedField::TransactionsCount),
                             "batchSize" | "batch_size" => Ok(GeneratedField::BatchSize),
+                            "resumeToken" | "resume_token" => Ok(GeneratedField::ResumeToken),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -97,6 +107,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                 let mut starting_version__ = None;
                 let mut transactions_count__ = None;
                 let mut batch_size__ = None;
+                let mut resume_token__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::StartingVersion => {
@@ -123,18 +134,158 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                                 map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
                             ;
                         }
+                        GeneratedField::ResumeToken => {
+                            if resume_token__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("resumeToken"));
+                            }
+                            resume_token__ = map.next_value()?;
+                        }
                     }
                 }
                 Ok(GetTransactionsRequest {
                     starting_version: starting_version__,
                     transactions_count: transactions_count__,
                     batch_size: batch_size__,
+                    resume_token: resume_token__,
                 })
             }
         }
         deserializer.deserialize_struct("aptos.indexer.v1.GetTransactionsRequest", FIELDS, GeneratedVisitor)
     }
 }
+impl serde::Serialize for ResumeToken {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.version != 0 {
+            len += 1;
+        }
+        if self.chain_id != 0 {
+            len += 1;
+        }
+        if !self.transaction_hash.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.ResumeToken", len)?;
+        if self.version != 0 {
+            struct_ser.serialize_field("version", ToString::to_string(&self.version).as_str())?;
+        }
+        if self.chain_id != 0 {
+            struct_ser.serialize_field("chainId", ToString::to_string(&self.chain_id).as_str())?;
+        }
+        if !self.transaction_hash.is_empty() {
+            struct_ser.serialize_field("transactionHash", pbjson::private::base64::encode(&self.transaction_hash).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for ResumeToken {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "version",
+            "chain_id",
+            "chainId",
+            "transaction_hash",
+            "transactionHash",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Version,
+            ChainId,
+            TransactionHash,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "version" => Ok(GeneratedField::Version),
+                            "chainId" | "chain_id" => Ok(GeneratedField::ChainId),
+                            "transactionHash" | "transaction_hash" => Ok(GeneratedField::TransactionHash),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ResumeToken;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.indexer.v1.ResumeToken")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<ResumeToken, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut version__ = None;
+                let mut chain_id__ = None;
+                let mut transaction_hash__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::Version => {
+                            if version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("version"));
+                            }
+                            version__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ChainId => {
+                            if chain_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("chainId"));
+                            }
+                            chain_id__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::TransactionHash => {
+                            if transaction_hash__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("transactionHash"));
+                            }
+                            transaction_hash__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(ResumeToken {
+                    version: version__.unwrap_or_default(),
+                    chain_id: chain_id__.unwrap_or_default(),
+                    transaction_hash: transaction_hash__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.indexer.v1.ResumeToken", FIELDS, GeneratedVisitor)
+    }
+}
 impl serde::Serialize for TransactionsInStorage {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -260,6 +411,9 @@ impl serde::Serialize for TransactionsResponse {
         if self.chain_id.is_some() {
             len += 1;
         }
+        if self.resume_token.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.TransactionsResponse", len)?;
         if !self.transactions.is_empty() {
             struct_ser.serialize_field("transactions", &self.transactions)?;
@@ -267,6 +421,9 @@ impl serde::Serialize for TransactionsResponse {
         if let Some(v) = self.chain_id.as_ref() {
             struct_ser.serialize_field("chainId", ToString::to_string(&v).as_str())?;
         }
+        if let Some(v) = self.resume_token.as_ref() {
+            struct_ser.serialize_field("resumeToken", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -280,12 +437,15 @@ impl<'de> serde::Deserialize<'de> for TransactionsResponse {
             "transactions",
             "chain_id",
             "chainId",
+            "resume_token",
+            "resumeToken",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
             Transactions,
             ChainId,
+            ResumeToken,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -309,6 +469,7 @@ impl<'de> serde::Deserialize<'de> for TransactionsResponse {
                         match value {
                             "transactions" => Ok(GeneratedField::Transactions),
                             "chainId" | "chain_id" => Ok(GeneratedField::ChainId),
+                            "resumeToken" | "resume_token" => Ok(GeneratedField::ResumeToken),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -330,6 +491,7 @@ impl<'de> serde::Deserialize<'de> for TransactionsResponse {
             {
                 let mut transactions__ = None;
                 let mut chain_id__ = None;
+                let mut resume_token__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::Transactions => {
@@ -346,11 +508,18 @@ impl<'de> serde::Deserialize<'de> for TransactionsResponse {
                                 map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
                             ;
                         }
+                        GeneratedField::ResumeToken => {
+                            if resume_token__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("resumeToken"));
+                            }
+                            resume_token__ = map.next_value()?;
+                        }
                     }
                 }
                 Ok(TransactionsResponse {
                     transactions: transactions__.unwrap_or_default(),
                     chain_id: chain_id__,
+                    resume_token: resume_token__,
                 })
             }
         }
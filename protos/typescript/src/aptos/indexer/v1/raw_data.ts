@@ -23,7 +23,7 @@ export interface TransactionsInStorage {
 }
 
 export interface GetTransactionsRequest {
-  /** Required; start version of current stream. */
+  /** Required, unless `resume_token` is present; start version of current stream. */
   startingVersion?:
     | bigint
     | undefined;
@@ -38,7 +38,14 @@ export interface GetTransactionsRequest {
    * Optional; number of transactions in each `TransactionsResponse` for current stream.
    * If not present, default to 1000. If larger than 1000, request will be rejected.
    */
-  batchSize?: bigint | undefined;
+  batchSize?:
+    | bigint
+    | undefined;
+  /**
+   * Optional; resume right after the transaction identified by a token from a previous stream.
+   * If present, `starting_version` is ignored.
+   */
+  resumeToken?: ResumeToken | undefined;
 }
 
 /** TransactionsResponse is a batch of transactions. */
@@ -48,7 +55,28 @@ export interface TransactionsResponse {
     | Transaction[]
     | undefined;
   /** Required; chain id. */
-  chainId?: bigint | undefined;
+  chainId?:
+    | bigint
+    | undefined;
+  /** Required; token to resume the stream right after the last transaction in this response. */
+  resumeToken?: ResumeToken | undefined;
+}
+
+/**
+ * ResumeToken identifies a transaction in a stream, so that processors can resume exactly where
+ * they left off after a disconnect, without negotiating the starting version out-of-band.
+ */
+export interface ResumeToken {
+  /** Required; version of the transaction. */
+  version?:
+    | bigint
+    | undefined;
+  /** Required; chain id of the stream. */
+  chainId?:
+    | bigint
+    | undefined;
+  /** Required; hash of the transaction, to detect that the chain was reset since the token was issued. */
+  transactionHash?: Uint8Array | undefined;
 }
 
 function createBaseTransactionsInStorage(): TransactionsInStorage {
@@ -167,7 +195,7 @@ export const TransactionsInStorage = {
 };
 
 function createBaseGetTransactionsRequest(): GetTransactionsRequest {
-  return { startingVersion: undefined, transactionsCount: undefined, batchSize: undefined };
+  return { startingVersion: undefined, transactionsCount: undefined, batchSize: undefined, resumeToken: undefined };
 }
 
 export const GetTransactionsRequest = {
@@ -190,6 +218,9 @@ export const GetTransactionsRequest = {
       }
       writer.uint32(24).uint64(message.batchSize.toString());
     }
+    if (message.resumeToken !== undefined) {
+      ResumeToken.encode(message.resumeToken, writer.uint32(34).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -221,6 +252,13 @@ export const GetTransactionsRequest = {
 
           message.batchSize = longToBigint(reader.uint64() as Long);
           continue;
+        case 4:
+          if (tag !== 34) {
+            break;
+          }
+
+          message.resumeToken = ResumeToken.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -269,6 +307,7 @@ export const GetTransactionsRequest = {
       startingVersion: isSet(object.startingVersion) ? BigInt(object.startingVersion) : undefined,
       transactionsCount: isSet(object.transactionsCount) ? BigInt(object.transactionsCount) : undefined,
       batchSize: isSet(object.batchSize) ? BigInt(object.batchSize) : undefined,
+      resumeToken: isSet(object.resumeToken) ? ResumeToken.fromJSON(object.resumeToken) : undefined,
     };
   },
 
@@ -283,6 +322,9 @@ export const GetTransactionsRequest = {
     if (message.batchSize !== undefined) {
       obj.batchSize = message.batchSize.toString();
     }
+    if (message.resumeToken !== undefined) {
+      obj.resumeToken = ResumeToken.toJSON(message.resumeToken);
+    }
     return obj;
   },
 
@@ -294,12 +336,15 @@ export const GetTransactionsRequest = {
     message.startingVersion = object.startingVersion ?? undefined;
     message.transactionsCount = object.transactionsCount ?? undefined;
     message.batchSize = object.batchSize ?? undefined;
+    message.resumeToken = (object.resumeToken !== undefined && object.resumeToken !== null)
+      ? ResumeToken.fromPartial(object.resumeToken)
+      : undefined;
     return message;
   },
 };
 
 function createBaseTransactionsResponse(): TransactionsResponse {
-  return { transactions: [], chainId: undefined };
+  return { transactions: [], chainId: undefined, resumeToken: undefined };
 }
 
 export const TransactionsResponse = {
@@ -315,6 +360,9 @@ export const TransactionsResponse = {
       }
       writer.uint32(16).uint64(message.chainId.toString());
     }
+    if (message.resumeToken !== undefined) {
+      ResumeToken.encode(message.resumeToken, writer.uint32(26).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -339,6 +387,13 @@ export const TransactionsResponse = {
 
           message.chainId = longToBigint(reader.uint64() as Long);
           continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.resumeToken = ResumeToken.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -388,6 +443,7 @@ export const TransactionsResponse = {
         ? object.transactions.map((e: any) => Transaction.fromJSON(e))
         : [],
       chainId: isSet(object.chainId) ? BigInt(object.chainId) : undefined,
+      resumeToken: isSet(object.resumeToken) ? ResumeToken.fromJSON(object.resumeToken) : undefined,
     };
   },
 
@@ -399,6 +455,9 @@ export const TransactionsResponse = {
     if (message.chainId !== undefined) {
       obj.chainId = message.chainId.toString();
     }
+    if (message.resumeToken !== undefined) {
+      obj.resumeToken = ResumeToken.toJSON(message.resumeToken);
+    }
     return obj;
   },
 
@@ -409,6 +468,136 @@ export const TransactionsResponse = {
     const message = createBaseTransactionsResponse();
     message.transactions = object.transactions?.map((e) => Transaction.fromPartial(e)) || [];
     message.chainId = object.chainId ?? undefined;
+    message.resumeToken = (object.resumeToken !== undefined && object.resumeToken !== null)
+      ? ResumeToken.fromPartial(object.resumeToken)
+      : undefined;
+    return message;
+  },
+};
+
+function createBaseResumeToken(): ResumeToken {
+  return { version: BigInt("0"), chainId: BigInt("0"), transactionHash: new Uint8Array(0) };
+}
+
+export const ResumeToken = {
+  encode(message: ResumeToken, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.version !== undefined && message.version !== BigInt("0")) {
+      if (BigInt.asUintN(64, message.version) !== message.version) {
+        throw new Error("value provided for field message.version of type uint64 too large");
+      }
+      writer.uint32(8).uint64(message.version.toString());
+    }
+    if (message.chainId !== undefined && message.chainId !== BigInt("0")) {
+      if (BigInt.asUintN(64, message.chainId) !== message.chainId) {
+        throw new Error("value provided for field message.chainId of type uint64 too large");
+      }
+      writer.uint32(16).uint64(message.chainId.toString());
+    }
+    if (message.transactionHash !== undefined && message.transactionHash.length !== 0) {
+      writer.uint32(26).bytes(message.transactionHash);
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): ResumeToken {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseResumeToken();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 8) {
+            break;
+          }
+
+          message.version = longToBigint(reader.uint64() as Long);
+          continue;
+        case 2:
+          if (tag !== 16) {
+            break;
+          }
+
+          message.chainId = longToBigint(reader.uint64() as Long);
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.transactionHash = reader.bytes();
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  // encodeTransform encodes a source of message objects.
+  // Transform<ResumeToken, Uint8Array>
+  async *encodeTransform(
+    source: AsyncIterable<ResumeToken | ResumeToken[]> | Iterable<ResumeToken | ResumeToken[]>,
+  ): AsyncIterable<Uint8Array> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [ResumeToken.encode(p).finish()];
+        }
+      } else {
+        yield* [ResumeToken.encode(pkt as any).finish()];
+      }
+    }
+  },
+
+  // decodeTransform decodes a source of encoded messages.
+  // Transform<Uint8Array, ResumeToken>
+  async *decodeTransform(
+    source: AsyncIterable<Uint8Array | Uint8Array[]> | Iterable<Uint8Array | Uint8Array[]>,
+  ): AsyncIterable<ResumeToken> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [ResumeToken.decode(p)];
+        }
+      } else {
+        yield* [ResumeToken.decode(pkt as any)];
+      }
+    }
+  },
+
+  fromJSON(object: any): ResumeToken {
+    return {
+      version: isSet(object.version) ? BigInt(object.version) : BigInt("0"),
+      chainId: isSet(object.chainId) ? BigInt(object.chainId) : BigInt("0"),
+      transactionHash: isSet(object.transactionHash) ? bytesFromBase64(object.transactionHash) : new Uint8Array(0),
+    };
+  },
+
+  toJSON(message: ResumeToken): unknown {
+    const obj: any = {};
+    if (message.version !== undefined && message.version !== BigInt("0")) {
+      obj.version = message.version.toString();
+    }
+    if (message.chainId !== undefined && message.chainId !== BigInt("0")) {
+      obj.chainId = message.chainId.toString();
+    }
+    if (message.transactionHash !== undefined && message.transactionHash.length !== 0) {
+      obj.transactionHash = base64FromBytes(message.transactionHash);
+    }
+    return obj;
+  },
+
+  create(base?: DeepPartial<ResumeToken>): ResumeToken {
+    return ResumeToken.fromPartial(base ?? {});
+  },
+  fromPartial(object: DeepPartial<ResumeToken>): ResumeToken {
+    const message = createBaseResumeToken();
+    message.version = object.version ?? BigInt("0");
+    message.chainId = object.chainId ?? BigInt("0");
+    message.transactionHash = object.transactionHash ?? new Uint8Array(0);
     return message;
   },
 };
@@ -451,6 +640,31 @@ export const RawDataClient = makeGenericClientConstructor(RawDataService, "aptos
   serviceName: string;
 };
 
+function bytesFromBase64(b64: string): Uint8Array {
+  if (globalThis.Buffer) {
+    return Uint8Array.from(globalThis.Buffer.from(b64, "base64"));
+  } else {
+    const bin = globalThis.atob(b64);
+    const arr = new Uint8Array(bin.length);
+    for (let i = 0; i < bin.length; ++i) {
+      arr[i] = bin.charCodeAt(i);
+    }
+    return arr;
+  }
+}
+
+function base64FromBytes(arr: Uint8Array): string {
+  if (globalThis.Buffer) {
+    return globalThis.Buffer.from(arr).toString("base64");
+  } else {
+    const bin: string[] = [];
+    arr.forEach((byte) => {
+      bin.push(globalThis.String.fromCharCode(byte));
+    });
+    return globalThis.btoa(bin.join(""));
+  }
+}
+
 type Builtin = Date | Function | Uint8Array | string | number | boolean | bigint | undefined;
 
 type DeepPartial<T> = T extends Builtin ? T
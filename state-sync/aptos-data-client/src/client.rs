@@ -34,15 +34,18 @@ use aptos_storage_interface::DbReader;
 use aptos_storage_service_client::StorageServiceClient;
 use aptos_storage_service_types::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
-        NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
-        StateValuesWithProofRequest, StorageServiceRequest,
+        BatchRequest, DataRequest, EpochEndingLedgerInfoRequest,
+        NewTransactionOutputsWithProofRequest, NewTransactionsOrOutputsWithProofRequest,
+        NewTransactionsWithProofRequest, StateValuesWithProofRequest, StorageServiceRequest,
         SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
     },
-    responses::{StorageServerSummary, StorageServiceResponse, TransactionOrOutputListWithProof},
+    responses::{
+        DataResponse, StorageServerSummary, StorageServiceResponse,
+        TransactionOrOutputListWithProof,
+    },
     Epoch, StorageServiceMessage,
 };
 use aptos_time_service::TimeService;
@@ -825,9 +828,7 @@ impl AptosDataClient {
                         },
                         _ => Error::UnexpectedErrorEncountered(rpc_error.to_string()),
                     },
-                    aptos_storage_service_client::Error::StorageServiceError(err) => {
-                        Error::UnexpectedErrorEncountered(err.to_string())
-                    },
+                    aptos_storage_service_client::Error::StorageServiceError(err) => err.into(),
                     _ => Error::UnexpectedErrorEncountered(error.to_string()),
                 };
 
@@ -875,11 +876,44 @@ impl AptosDataClient {
         E: Into<Error>,
     {
         let storage_request =
-            StorageServiceRequest::new(data_request, self.data_client_config.use_compression);
+            StorageServiceRequest::new(data_request, self.data_client_config.use_compression)
+                .with_checksum(self.data_client_config.use_response_checksums);
         self.send_request_and_decode(storage_request, request_timeout_ms)
             .await
     }
 
+    /// Fetches the responses to several data requests in a single round trip.
+    /// The responses are returned in the same order as the requests, but may
+    /// only cover a prefix of them (e.g., if all the responses would overflow
+    /// a single network message).
+    pub async fn get_batch(
+        &self,
+        data_requests: Vec<DataRequest>,
+        request_timeout_ms: u64,
+    ) -> crate::error::Result<Response<Vec<DataResponse>>> {
+        let num_requests = data_requests.len();
+        let data_request = DataRequest::GetBatch(BatchRequest {
+            requests: data_requests,
+        });
+        let response: Response<Vec<DataResponse>> = self
+            .create_and_send_storage_request(request_timeout_ms, data_request)
+            .await?;
+
+        // Verify the responses match a (non-empty) prefix of the requests
+        let num_responses = response.payload.len();
+        if num_responses == 0 || num_responses > num_requests {
+            response
+                .context
+                .response_callback
+                .notify_bad_response(ResponseError::InvalidData);
+            return Err(Error::InvalidResponse(format!(
+                "Expected between 1 and {} batch responses, found: {}",
+                num_requests, num_responses
+            )));
+        }
+        Ok(response)
+    }
+
     /// Updates the metrics for the responses received via the data client
     fn update_received_response_metrics(
         &self,
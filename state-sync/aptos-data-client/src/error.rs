@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_storage_service_types::{StorageServiceError, StorageServiceErrorCategory};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -54,6 +55,22 @@ impl From<aptos_storage_service_client::Error> for Error {
     }
 }
 
+impl From<StorageServiceError> for Error {
+    fn from(error: StorageServiceError) -> Self {
+        // Map the error using its category (and not the error variant)
+        match error.category() {
+            StorageServiceErrorCategory::Retryable | StorageServiceErrorCategory::Pruned => {
+                Self::DataIsUnavailable(error.to_string())
+            },
+            StorageServiceErrorCategory::TooLarge => Self::DataIsTooLarge(error.to_string()),
+            StorageServiceErrorCategory::Permission => Self::InvalidRequest(error.to_string()),
+            StorageServiceErrorCategory::Fatal => {
+                Self::UnexpectedErrorEncountered(error.to_string())
+            },
+        }
+    }
+}
+
 impl From<aptos_storage_service_types::responses::Error> for Error {
     fn from(error: aptos_storage_service_types::responses::Error) -> Self {
         Self::InvalidResponse(error.to_string())
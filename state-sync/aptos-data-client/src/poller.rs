@@ -405,7 +405,11 @@ pub(crate) fn poll_peer(
         // Construct the request for polling
         let data_request = DataRequest::GetStorageServerSummary;
         let use_compression = data_summary_poller.data_client_config.use_compression;
-        let storage_request = StorageServiceRequest::new(data_request, use_compression);
+        let use_checksum = data_summary_poller
+            .data_client_config
+            .use_response_checksums;
+        let storage_request =
+            StorageServiceRequest::new(data_request, use_compression).with_checksum(use_checksum);
 
         // Fetch the storage summary for the peer and stop the timer
         let request_timeout = data_summary_poller.data_client_config.response_timeout_ms;
@@ -0,0 +1,100 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    poller,
+    priority::PeerPriority,
+    tests::{mock::MockNetwork, utils},
+};
+use aptos_config::config::AptosDataClientConfig;
+use aptos_storage_service_types::{
+    requests::{BatchRequest, DataRequest, TransactionsWithProofRequest},
+    responses::{DataResponse, StorageServiceResponse},
+};
+use aptos_types::transaction::TransactionListWithProof;
+use claims::assert_matches;
+
+#[tokio::test]
+async fn get_batch() {
+    // Create a base config for a validator
+    let base_config = utils::create_validator_base_config();
+    let data_client_config = AptosDataClientConfig::default();
+
+    // Create the mock network, mock time, client and poller
+    let (mut mock_network, mut mock_time, client, poller) =
+        MockNetwork::new(Some(base_config), Some(data_client_config), None);
+
+    // Start the poller
+    tokio::spawn(poller::start_poller(poller));
+
+    // Add a connected peer
+    let (_, network_id) = utils::add_peer_to_network(PeerPriority::HighPriority, &mut mock_network);
+
+    // Advance time so the poller sends a data summary request
+    utils::advance_polling_timer(&mut mock_time, &data_client_config).await;
+
+    // Receive their request and respond
+    let network_request = utils::get_network_request(&mut mock_network, network_id).await;
+    utils::handle_storage_summary_request(network_request, utils::create_storage_summary(200));
+
+    // Let the poller finish processing the response
+    tokio::task::yield_now().await;
+
+    // Create the data requests (and the responses to them)
+    let data_requests: Vec<_> = [(0, 10), (11, 20), (21, 30)]
+        .into_iter()
+        .map(|(start_version, end_version)| {
+            DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+                proof_version: 200,
+                start_version,
+                end_version,
+                include_events: false,
+            })
+        })
+        .collect();
+    let data_response = DataResponse::TransactionsWithProof(TransactionListWithProof::new_empty());
+
+    // Handle the client's batch requests (the first is served in full,
+    // the second partially, and the third with too many responses).
+    let expected_requests = data_requests.clone();
+    let use_compression = data_client_config.use_compression;
+    let data_response_clone = data_response.clone();
+    tokio::spawn(async move {
+        for num_responses in [3, 1, 4] {
+            let network_request = utils::get_network_request(&mut mock_network, network_id).await;
+            assert_eq!(
+                network_request.storage_service_request.data_request,
+                DataRequest::GetBatch(BatchRequest {
+                    requests: expected_requests.clone(),
+                })
+            );
+            let batch_response =
+                DataResponse::Batch(vec![data_response_clone.clone(); num_responses]);
+            network_request
+                .response_sender
+                .send(Ok(StorageServiceResponse::new(
+                    batch_response,
+                    use_compression,
+                )
+                .unwrap()));
+        }
+    });
+
+    // Verify the client receives the full and partial batch responses
+    let request_timeout = data_client_config.response_timeout_ms;
+    for num_responses in [3, 1] {
+        let response = client
+            .get_batch(data_requests.clone(), request_timeout)
+            .await
+            .unwrap();
+        assert_eq!(response.payload, vec![data_response.clone(); num_responses]);
+    }
+
+    // Verify the client rejects a batch response with too many responses
+    let response = client
+        .get_batch(data_requests, request_timeout)
+        .await
+        .unwrap_err();
+    assert_matches!(response, Error::InvalidResponse(_));
+}
@@ -236,6 +236,9 @@ impl MockNetwork {
                 let message: StorageServiceMessage = bcs::from_bytes(data.as_ref()).unwrap();
                 let storage_service_request = match message {
                     StorageServiceMessage::Request(request) => request,
+                    StorageServiceMessage::ChecksummedRequest(request) => {
+                        request.with_checksum(true)
+                    },
                     _ => panic!("unexpected: {:?}", message),
                 };
                 let response_sender = ResponseSender::new(res_tx);
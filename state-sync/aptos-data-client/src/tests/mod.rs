@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod advertise;
+mod batch;
 mod compression;
 pub mod mock;
 mod multi_fetch;
@@ -50,15 +50,26 @@ impl<NetworkClient: NetworkClientInterface<StorageServiceMessage>>
     ) -> Result<StorageServiceResponse, Error> {
         let response = self
             .network_client
-            .send_to_peer_rpc(StorageServiceMessage::Request(request), timeout, recipient)
+            .send_to_peer_rpc(
+                StorageServiceMessage::new_request(request),
+                timeout,
+                recipient,
+            )
             .await
             .map_err(|error| Error::NetworkError(error.to_string()))?;
         match response {
             StorageServiceMessage::Response(Ok(response)) => Ok(response),
             StorageServiceMessage::Response(Err(err)) => Err(Error::StorageServiceError(err)),
-            StorageServiceMessage::Request(request) => Err(Error::NetworkError(format!(
-                "Got storage service request instead of response! Request: {:?}",
-                request
+            StorageServiceMessage::Request(request)
+            | StorageServiceMessage::ChecksummedRequest(request) => {
+                Err(Error::NetworkError(format!(
+                    "Got storage service request instead of response! Request: {:?}",
+                    request
+                )))
+            },
+            StorageServiceMessage::RequestEnvelope(envelope) => Err(Error::NetworkError(format!(
+                "Got storage service request envelope instead of response! Envelope: {:?}",
+                envelope
             ))),
         }
     }
@@ -0,0 +1,295 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::Mutex;
+use aptos_storage_service_types::requests::{DataRequest, StorageServiceRequest};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The duration of each bucket in the rolling analytics window
+const ANALYTICS_BUCKET_DURATION: Duration = Duration::from_secs(60);
+
+/// A rolling, in-memory collector of analytics on the requests received by the
+/// storage server (i.e., the request mix, the requested chunk sizes, the LRU
+/// cache hit ratio and the activity of each peer). Only the requests received
+/// within the analytics window are retained (at a granularity of one minute),
+/// so that operators can understand what their node is spending time serving.
+#[derive(Clone)]
+pub struct StorageServiceAnalytics {
+    buckets: Arc<Mutex<VecDeque<AnalyticsBucket>>>, // Most recent last
+    time_service: TimeService,
+    window: Duration,
+}
+
+impl StorageServiceAnalytics {
+    pub fn new(window_secs: u64, time_service: TimeService) -> Self {
+        Self {
+            buckets: Arc::new(Mutex::new(VecDeque::new())),
+            time_service,
+            window: Duration::from_secs(window_secs),
+        }
+    }
+
+    /// Returns true iff the analytics are being collected
+    pub fn is_enabled(&self) -> bool {
+        !self.window.is_zero()
+    }
+
+    /// Records a request received from the given peer
+    pub fn record_request(&self, peer_network_id: PeerNetworkId, request: &StorageServiceRequest) {
+        let request_label = request.data_request.get_label();
+        let chunk_size = get_requested_chunk_size(&request.data_request);
+        self.update_current_bucket(|bucket| {
+            *bucket.request_counts.entry(request_label).or_default() += 1;
+            if let Some(chunk_size) = chunk_size {
+                bucket
+                    .chunk_sizes
+                    .entry(request_label)
+                    .or_default()
+                    .add_chunk_size(chunk_size);
+            }
+
+            let peer_activity = bucket.peer_activity.entry(peer_network_id).or_default();
+            peer_activity.num_requests += 1;
+            peer_activity.num_requested_items += chunk_size.unwrap_or(0);
+        });
+    }
+
+    /// Records a request from the given peer that failed to be served
+    pub fn record_error(&self, peer_network_id: PeerNetworkId) {
+        self.update_current_bucket(|bucket| {
+            bucket
+                .peer_activity
+                .entry(peer_network_id)
+                .or_default()
+                .num_errors += 1;
+        });
+    }
+
+    /// Records a probe of the LRU response cache
+    pub fn record_cache_probe(&self, cache_hit: bool) {
+        self.update_current_bucket(|bucket| {
+            if cache_hit {
+                bucket.num_cache_hits += 1;
+            } else {
+                bucket.num_cache_misses += 1;
+            }
+        });
+    }
+
+    /// Returns a snapshot of the analytics over the current window
+    pub fn get_snapshot(&self) -> StorageServiceAnalyticsSnapshot {
+        let mut snapshot = StorageServiceAnalyticsSnapshot {
+            window_secs: self.window.as_secs(),
+            ..Default::default()
+        };
+
+        // Merge all buckets in the window
+        let now = self.time_service.now();
+        let mut peer_activity: HashMap<PeerNetworkId, PeerActivity> = HashMap::new();
+        for bucket in self.buckets.lock().iter() {
+            if self.is_expired(bucket, now) {
+                continue;
+            }
+            for (request_label, count) in &bucket.request_counts {
+                snapshot.num_requests += count;
+                *snapshot.request_counts.entry(*request_label).or_default() += count;
+            }
+            for (request_label, chunk_sizes) in &bucket.chunk_sizes {
+                snapshot
+                    .chunk_sizes
+                    .entry(*request_label)
+                    .or_default()
+                    .merge(chunk_sizes);
+            }
+            snapshot.num_cache_hits += bucket.num_cache_hits;
+            snapshot.num_cache_misses += bucket.num_cache_misses;
+            for (peer_network_id, activity) in &bucket.peer_activity {
+                peer_activity
+                    .entry(*peer_network_id)
+                    .or_default()
+                    .merge(activity);
+            }
+        }
+
+        // Sort the peers by activity (most active first)
+        snapshot.peer_activity = peer_activity.into_iter().collect();
+        snapshot
+            .peer_activity
+            .sort_by(|(peer_a, activity_a), (peer_b, activity_b)| {
+                activity_b
+                    .num_requests
+                    .cmp(&activity_a.num_requests)
+                    .then_with(|| peer_a.cmp(peer_b))
+            });
+
+        snapshot
+    }
+
+    /// Returns true iff the bucket has fallen out of the analytics window
+    fn is_expired(&self, bucket: &AnalyticsBucket, now: Instant) -> bool {
+        now.saturating_duration_since(bucket.start_time) >= self.window
+    }
+
+    /// Applies the given update to the current bucket (creating
+    /// a new bucket and removing any expired buckets, if required).
+    fn update_current_bucket(&self, update: impl FnOnce(&mut AnalyticsBucket)) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        // Start a new bucket if the current one is full
+        let now = self.time_service.now();
+        let mut buckets = self.buckets.lock();
+        let bucket_is_full = buckets.back().map_or(true, |bucket| {
+            now.saturating_duration_since(bucket.start_time) >= ANALYTICS_BUCKET_DURATION
+        });
+        if bucket_is_full {
+            buckets.push_back(AnalyticsBucket::new(now));
+        }
+
+        // Remove the expired buckets
+        while let Some(bucket) = buckets.front() {
+            if !self.is_expired(bucket, now) || buckets.len() == 1 {
+                break;
+            }
+            buckets.pop_front();
+        }
+
+        // Update the current bucket
+        if let Some(bucket) = buckets.back_mut() {
+            update(bucket);
+        }
+    }
+}
+
+/// The analytics of the requests received during a single bucket
+struct AnalyticsBucket {
+    start_time: Instant,
+    request_counts: HashMap<&'static str, u64>, // By request type
+    chunk_sizes: HashMap<&'static str, ChunkSizeStats>, // By request type
+    num_cache_hits: u64,
+    num_cache_misses: u64,
+    peer_activity: HashMap<PeerNetworkId, PeerActivity>,
+}
+
+impl AnalyticsBucket {
+    fn new(start_time: Instant) -> Self {
+        Self {
+            start_time,
+            request_counts: HashMap::new(),
+            chunk_sizes: HashMap::new(),
+            num_cache_hits: 0,
+            num_cache_misses: 0,
+            peer_activity: HashMap::new(),
+        }
+    }
+}
+
+/// A snapshot of the storage service request analytics over the analytics window
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageServiceAnalyticsSnapshot {
+    pub window_secs: u64,
+    pub num_requests: u64,
+    pub request_counts: BTreeMap<&'static str, u64>, // By request type
+    pub chunk_sizes: BTreeMap<&'static str, ChunkSizeStats>, // By request type
+    pub num_cache_hits: u64,
+    pub num_cache_misses: u64,
+    pub peer_activity: Vec<(PeerNetworkId, PeerActivity)>, // Most active peers first
+}
+
+impl StorageServiceAnalyticsSnapshot {
+    /// Returns the ratio of LRU cache probes that were hits (if any probes were made)
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        let num_cache_probes = self.num_cache_hits + self.num_cache_misses;
+        (num_cache_probes > 0).then(|| self.num_cache_hits as f64 / num_cache_probes as f64)
+    }
+}
+
+/// The distribution of the chunk sizes (i.e., the number of items) requested
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ChunkSizeStats {
+    pub num_requests: u64,
+    pub min_chunk_size: u64,
+    pub max_chunk_size: u64,
+    pub total_chunk_size: u64,
+}
+
+impl ChunkSizeStats {
+    /// Returns the average chunk size (if any chunks were requested)
+    pub fn average_chunk_size(&self) -> Option<f64> {
+        (self.num_requests > 0).then(|| self.total_chunk_size as f64 / self.num_requests as f64)
+    }
+
+    fn add_chunk_size(&mut self, chunk_size: u64) {
+        self.merge(&ChunkSizeStats {
+            num_requests: 1,
+            min_chunk_size: chunk_size,
+            max_chunk_size: chunk_size,
+            total_chunk_size: chunk_size,
+        });
+    }
+
+    fn merge(&mut self, other: &ChunkSizeStats) {
+        if other.num_requests == 0 {
+            return;
+        }
+        if self.num_requests == 0 {
+            *self = other.clone();
+            return;
+        }
+        self.num_requests += other.num_requests;
+        self.min_chunk_size = self.min_chunk_size.min(other.min_chunk_size);
+        self.max_chunk_size = self.max_chunk_size.max(other.max_chunk_size);
+        self.total_chunk_size = self.total_chunk_size.saturating_add(other.total_chunk_size);
+    }
+}
+
+/// The activity of a single peer
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PeerActivity {
+    pub num_requests: u64,
+    pub num_errors: u64,
+    pub num_requested_items: u64,
+}
+
+impl PeerActivity {
+    fn merge(&mut self, other: &PeerActivity) {
+        self.num_requests += other.num_requests;
+        self.num_errors += other.num_errors;
+        self.num_requested_items = self
+            .num_requested_items
+            .saturating_add(other.num_requested_items);
+    }
+}
+
+/// Returns the number of items (e.g., transactions or state values) requested
+/// by the given data request. Returns None if the request doesn't fetch a chunk.
+fn get_requested_chunk_size(data_request: &DataRequest) -> Option<u64> {
+    let (start, end) = match data_request {
+        DataRequest::GetEpochEndingLedgerInfos(request) => {
+            (request.start_epoch, request.expected_end_epoch)
+        },
+        DataRequest::GetStateValuesWithProof(request) => (request.start_index, request.end_index),
+        DataRequest::GetStateValuesByKeyRangeWithProof(request) => return Some(request.max_items),
+        DataRequest::GetTransactionOutputsWithProof(request) => {
+            (request.start_version, request.end_version)
+        },
+        DataRequest::GetTransactionsWithProof(request) => {
+            (request.start_version, request.end_version)
+        },
+        DataRequest::GetTransactionsOrOutputsWithProof(request) => {
+            (request.start_version, request.end_version)
+        },
+        DataRequest::GetTransactionsWithoutProof(request) => {
+            (request.start_version, request.end_version)
+        },
+        _ => return None,
+    };
+    Some(end.saturating_sub(start).saturating_add(1))
+}
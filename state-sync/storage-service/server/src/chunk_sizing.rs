@@ -0,0 +1,210 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::{
+    set_gauge, ADVERTISED_CHUNK_SIZES, NETWORK_FRAME_OVERFLOW, STORAGE_CHUNK_SERVING_LATENCY,
+    STORAGE_RESPONSE_SIZE_BYTES,
+};
+use aptos_config::config::StorageServiceConfig;
+use aptos_storage_service_types::responses::ProtocolMetadata;
+use std::collections::HashMap;
+
+/// Adaptive chunk sizing constants
+const CHUNK_SIZE_DECREASE_DIVISOR: u64 = 2; // The divisor applied to shrink a chunk size
+const CHUNK_SIZE_INCREASE_DIVISOR: u64 = 4; // Chunk sizes grow by 1/4 when they recover
+const MAX_OVERFLOW_RATIO: f64 = 0.1; // Responses "routinely" overflow above this ratio
+const MIN_ADVERTISED_CHUNK_SIZE: u64 = 1; // The smallest chunk size that can be advertised
+const MIN_RESPONSES_PER_ADJUSTMENT: u64 = 20; // Responses required before adjusting a chunk size
+
+/// The types of chunks whose sizes are advertised in the protocol metadata
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChunkType {
+    EpochEndingLedgerInfos,
+    StateValues,
+    Transactions,
+    TransactionOutputs,
+}
+
+impl ChunkType {
+    pub fn all() -> [ChunkType; 4] {
+        [
+            ChunkType::EpochEndingLedgerInfos,
+            ChunkType::StateValues,
+            ChunkType::Transactions,
+            ChunkType::TransactionOutputs,
+        ]
+    }
+
+    /// Returns the label of the data responses that serve this chunk type
+    pub fn get_response_label(&self) -> &'static str {
+        match self {
+            ChunkType::EpochEndingLedgerInfos => "epoch_ending_ledger_infos",
+            ChunkType::StateValues => "state_value_chunk_with_proof",
+            ChunkType::Transactions => "transactions_with_proof",
+            ChunkType::TransactionOutputs => "transaction_outputs_with_proof",
+        }
+    }
+
+    /// Returns the maximum chunk size for this chunk type in the given config
+    fn get_max_chunk_size(&self, config: &StorageServiceConfig) -> u64 {
+        match self {
+            ChunkType::EpochEndingLedgerInfos => config.max_epoch_chunk_size,
+            ChunkType::StateValues => config.max_state_chunk_size,
+            ChunkType::Transactions => config.max_transaction_chunk_size,
+            ChunkType::TransactionOutputs => config.max_transaction_output_chunk_size,
+        }
+    }
+}
+
+/// A snapshot of the (cumulative) metrics for a single chunk type
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChunkMetrics {
+    pub num_responses: u64,              // The number of responses sent
+    pub num_frame_overflows: u64,        // The number of responses that overflowed the frame
+    pub num_latency_samples: u64,        // The number of serving latency samples
+    pub total_serving_latency_secs: f64, // The sum of all serving latencies
+}
+
+impl ChunkMetrics {
+    /// Reads the current metrics for the given chunk type
+    fn read(chunk_type: ChunkType) -> Self {
+        let response_label = chunk_type.get_response_label();
+        let response_sizes = STORAGE_RESPONSE_SIZE_BYTES.with_label_values(&[response_label]);
+        let serving_latencies = STORAGE_CHUNK_SERVING_LATENCY.with_label_values(&[response_label]);
+        Self {
+            num_responses: response_sizes.get_sample_count(),
+            num_frame_overflows: NETWORK_FRAME_OVERFLOW
+                .with_label_values(&[response_label])
+                .get(),
+            num_latency_samples: serving_latencies.get_sample_count(),
+            total_serving_latency_secs: serving_latencies.get_sample_sum(),
+        }
+    }
+}
+
+/// The advertised chunk size (and the metrics it was last adjusted at) for a chunk type
+#[derive(Clone, Copy, Debug)]
+struct AdvertisedChunkSize {
+    chunk_size: u64,
+    last_metrics: ChunkMetrics,
+}
+
+/// Derives the maximum chunk sizes advertised to peers from the metrics of the
+/// responses recently served. If responses of a chunk type routinely overflow the
+/// network frame (forcing the server to shrink and re-fetch them), or take too long
+/// to serve, the advertised chunk size is halved. Once responses are served cleanly,
+/// the chunk size gradually grows back to the configured maximum. This way, clients
+/// naturally request chunks that the server can serve efficiently.
+#[derive(Debug, Default)]
+pub struct AdaptiveChunkSizer {
+    advertised_chunk_sizes: HashMap<ChunkType, AdvertisedChunkSize>,
+}
+
+impl AdaptiveChunkSizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the protocol metadata to advertise, adjusting the
+    /// chunk sizes using the latest metrics (if enabled).
+    pub fn get_protocol_metadata(&mut self, config: &StorageServiceConfig) -> ProtocolMetadata {
+        // Adjust the advertised chunk sizes
+        for chunk_type in ChunkType::all() {
+            let chunk_size = if config.enable_adaptive_chunk_sizing {
+                self.update_chunk_size(chunk_type, config, ChunkMetrics::read(chunk_type))
+            } else {
+                self.advertised_chunk_sizes.remove(&chunk_type);
+                chunk_type.get_max_chunk_size(config)
+            };
+            set_gauge(
+                &ADVERTISED_CHUNK_SIZES,
+                &format!("{:?}", chunk_type),
+                chunk_size,
+            );
+        }
+
+        // Create the protocol metadata
+        ProtocolMetadata {
+            max_epoch_chunk_size: self.get_chunk_size(ChunkType::EpochEndingLedgerInfos, config),
+            max_transaction_chunk_size: self.get_chunk_size(ChunkType::Transactions, config),
+            max_state_chunk_size: self.get_chunk_size(ChunkType::StateValues, config),
+            max_transaction_output_chunk_size: self
+                .get_chunk_size(ChunkType::TransactionOutputs, config),
+        }
+    }
+
+    /// Returns the chunk size currently advertised for the given chunk type
+    pub fn get_chunk_size(&self, chunk_type: ChunkType, config: &StorageServiceConfig) -> u64 {
+        let max_chunk_size = chunk_type.get_max_chunk_size(config);
+        self.advertised_chunk_sizes
+            .get(&chunk_type)
+            .map_or(max_chunk_size, |advertised| {
+                advertised.chunk_size.min(max_chunk_size)
+            })
+    }
+
+    /// Updates the advertised chunk size for the given chunk type using the
+    /// latest (cumulative) metrics, and returns the new chunk size. The chunk
+    /// size is only adjusted once enough new responses have been observed.
+    pub fn update_chunk_size(
+        &mut self,
+        chunk_type: ChunkType,
+        config: &StorageServiceConfig,
+        metrics: ChunkMetrics,
+    ) -> u64 {
+        // Fetch the currently advertised chunk size
+        let max_chunk_size = chunk_type.get_max_chunk_size(config);
+        let initial_chunk_size = AdvertisedChunkSize {
+            chunk_size: max_chunk_size,
+            last_metrics: metrics,
+        };
+        let advertised = self
+            .advertised_chunk_sizes
+            .entry(chunk_type)
+            .or_insert(initial_chunk_size);
+        let chunk_size = advertised.chunk_size.min(max_chunk_size);
+
+        // Wait until enough new responses have been served
+        let last_metrics = advertised.last_metrics;
+        let num_responses = metrics
+            .num_responses
+            .saturating_sub(last_metrics.num_responses);
+        if num_responses < MIN_RESPONSES_PER_ADJUSTMENT {
+            return chunk_size;
+        }
+
+        // Calculate the overflow ratio and the average serving latency
+        let num_frame_overflows = metrics
+            .num_frame_overflows
+            .saturating_sub(last_metrics.num_frame_overflows);
+        let overflow_ratio = num_frame_overflows as f64 / num_responses as f64;
+        let num_latency_samples = metrics
+            .num_latency_samples
+            .saturating_sub(last_metrics.num_latency_samples);
+        let average_latency_ms = if num_latency_samples > 0 {
+            let total_latency_secs =
+                metrics.total_serving_latency_secs - last_metrics.total_serving_latency_secs;
+            (total_latency_secs * 1000.0) / num_latency_samples as f64
+        } else {
+            0.0
+        };
+
+        // Shrink the chunk size if responses are too large or slow. Otherwise,
+        // grow the chunk size if responses are served comfortably.
+        let max_latency_ms = config.max_chunk_serving_latency_ms as f64;
+        let new_chunk_size =
+            if overflow_ratio > MAX_OVERFLOW_RATIO || average_latency_ms > max_latency_ms {
+                (chunk_size / CHUNK_SIZE_DECREASE_DIVISOR).max(MIN_ADVERTISED_CHUNK_SIZE)
+            } else if num_frame_overflows == 0 && average_latency_ms <= max_latency_ms / 2.0 {
+                let increase = (chunk_size / CHUNK_SIZE_INCREASE_DIVISOR).max(1);
+                chunk_size.saturating_add(increase).min(max_chunk_size)
+            } else {
+                chunk_size
+            };
+
+        // Update the advertised chunk size
+        advertised.chunk_size = new_chunk_size;
+        advertised.last_metrics = metrics;
+        new_chunk_size
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::StorageServiceConfig;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A handle to the storage service config that can be updated at runtime
+/// (e.g., from on-chain config or an admin endpoint) without restarting
+/// the storage service. All clones of the handle share the same config.
+///
+/// Note: the server reads the latest config for every request and every
+/// optimistic fetch and subscription refresh, so chunk sizes, subscription
+/// limits and timeouts take effect immediately. Updates to the LRU cache size
+/// resize (and clear) the cache. However, values used to construct the other
+/// server components (i.e., the maximum number of concurrent requests, the
+/// refresh intervals and the request moderator limits) are only read at startup.
+#[derive(Clone, Debug)]
+pub struct StorageServiceConfigHandle {
+    config_sender: Arc<watch::Sender<StorageServiceConfig>>,
+}
+
+impl StorageServiceConfigHandle {
+    pub fn new(storage_service_config: StorageServiceConfig) -> Self {
+        let (config_sender, _) = watch::channel(storage_service_config);
+        Self {
+            config_sender: Arc::new(config_sender),
+        }
+    }
+
+    /// Returns a copy of the latest storage service config
+    pub fn get_config(&self) -> StorageServiceConfig {
+        *self.config_sender.borrow()
+    }
+
+    /// Replaces the storage service config and notifies all subscribers
+    pub fn update_config(&self, storage_service_config: StorageServiceConfig) {
+        self.config_sender.send_replace(storage_service_config);
+    }
+
+    /// Returns a receiver that is notified whenever the config is updated
+    pub fn subscribe(&self) -> watch::Receiver<StorageServiceConfig> {
+        self.config_sender.subscribe()
+    }
+}
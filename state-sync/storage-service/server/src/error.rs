@@ -1,17 +1,28 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_storage_service_types::{StorageServiceError, StorageServiceErrorCategory};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Clone, Debug, Deserialize, Error, PartialEq, Eq, Serialize)]
 pub enum Error {
+    #[error("The requested data has been pruned (earliest available: {0}): {1}")]
+    DataIsPruned(u64, String),
+    #[error("The requested data is too large: {0}")]
+    DataIsTooLarge(String),
+    #[error("The requested data is unavailable: {0}")]
+    DataIsUnavailable(String),
     #[error("Invalid request received: {0}")]
     InvalidRequest(String),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
     #[error("Storage error encountered: {0}")]
     StorageErrorEncountered(String),
     #[error("Too many invalid requests: {0}")]
     TooManyInvalidRequests(String),
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
     #[error("Unexpected error encountered: {0}")]
     UnexpectedErrorEncountered(String),
 }
@@ -20,12 +31,46 @@ impl Error {
     /// Returns a summary label for the error type
     pub fn get_label(&self) -> &'static str {
         match self {
+            Error::DataIsPruned(..) => "data_is_pruned",
+            Error::DataIsTooLarge(_) => "data_is_too_large",
+            Error::DataIsUnavailable(_) => "data_is_unavailable",
             Error::InvalidRequest(_) => "invalid_request",
+            Error::PermissionDenied(_) => "permission_denied",
             Error::StorageErrorEncountered(_) => "storage_error",
             Error::TooManyInvalidRequests(_) => "too_many_invalid_requests",
+            Error::TooManyRequests(_) => "too_many_requests",
             Error::UnexpectedErrorEncountered(_) => "unexpected_error",
         }
     }
+
+    /// Returns the category of the error (as sent to the client)
+    pub fn get_category(&self) -> StorageServiceErrorCategory {
+        match self {
+            Error::DataIsPruned(..) => StorageServiceErrorCategory::Pruned,
+            Error::DataIsTooLarge(_) => StorageServiceErrorCategory::TooLarge,
+            // Throttled requests (e.g., due to bandwidth quotas) can be retried later
+            Error::DataIsUnavailable(_)
+            | Error::StorageErrorEncountered(_)
+            | Error::TooManyRequests(_) => StorageServiceErrorCategory::Retryable,
+            Error::InvalidRequest(_) | Error::UnexpectedErrorEncountered(_) => {
+                StorageServiceErrorCategory::Fatal
+            },
+            Error::PermissionDenied(_) | Error::TooManyInvalidRequests(_) => {
+                StorageServiceErrorCategory::Permission
+            },
+        }
+    }
+}
+
+impl From<Error> for StorageServiceError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::DataIsPruned(earliest_available_version, _) => {
+                StorageServiceError::new_data_pruned(earliest_available_version, error.to_string())
+            },
+            error => StorageServiceError::new(error.get_category(), error.to_string()),
+        }
+    }
 }
 
 impl From<aptos_storage_service_types::responses::Error> for Error {
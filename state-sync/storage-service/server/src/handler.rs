@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    analytics::StorageServiceAnalytics,
     error::Error,
     logging::{LogEntry, LogSchema},
     metrics,
@@ -12,7 +13,8 @@ use crate::{
     moderator::RequestModerator,
     network::ResponseSender,
     optimistic_fetch::OptimisticFetchRequest,
-    storage::StorageReaderInterface,
+    response_cache::ResponseCache,
+    storage::{check_overflow_network_frame, StorageReaderInterface},
     subscription::{SubscriptionRequest, SubscriptionStreamRequests},
     utils,
 };
@@ -21,9 +23,11 @@ use aptos_logger::{debug, error, sample, sample::SampleRate, trace, warn};
 use aptos_network::protocols::wire::handshake::v1::ProtocolId;
 use aptos_storage_service_types::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, StateValuesWithProofRequest,
-        StorageServiceRequest, TransactionOutputsWithProofRequest,
+        BatchRequest, BlockMetadataRequest, DataRequest, EpochEndingLedgerInfoRequest,
+        StateValuesByKeyRangeWithProofRequest, StateValuesWithProofRequest, StorageServiceRequest,
+        TransactionByHashWithProofRequest, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
+        TransactionsWithoutProofRequest, VersionByTimestampRequest,
     },
     responses::{
         DataResponse, ServerProtocolVersion, StorageServerSummary, StorageServiceResponse,
@@ -34,12 +38,14 @@ use aptos_time_service::TimeService;
 use aptos_types::transaction::Version;
 use arc_swap::ArcSwap;
 use dashmap::{mapref::entry::Entry, DashMap};
-use mini_moka::sync::Cache;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// Storage server constants
 const ERROR_LOG_FREQUENCY_SECS: u64 = 5; // The frequency to log errors
-const STORAGE_SERVER_VERSION: u64 = 1;
+pub(crate) const STORAGE_SERVER_VERSION: u64 = 1;
 const SUMMARY_LOG_FREQUENCY_SECS: u64 = 5; // The frequency to log the storage server summary (secs)
 
 /// The `Handler` is the "pure" inbound request handler. It contains all the
@@ -49,18 +55,19 @@ const SUMMARY_LOG_FREQUENCY_SECS: u64 = 5; // The frequency to log the storage s
 pub struct Handler<T> {
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
     time_service: TimeService,
+    analytics: Option<StorageServiceAnalytics>,
 }
 
 impl<T: StorageReaderInterface> Handler<T> {
     pub fn new(
         cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
         optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
-        lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+        lru_response_cache: ResponseCache,
         request_moderator: Arc<RequestModerator>,
         storage: T,
         subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
@@ -74,6 +81,15 @@ impl<T: StorageReaderInterface> Handler<T> {
             storage,
             subscriptions,
             time_service,
+            analytics: None,
+        }
+    }
+
+    /// Records the requests handled by this handler in the given analytics
+    pub fn with_analytics(self, analytics: StorageServiceAnalytics) -> Self {
+        Self {
+            analytics: Some(analytics),
+            ..self
         }
     }
 
@@ -102,6 +118,11 @@ impl<T: StorageReaderInterface> Handler<T> {
             request.get_label(),
         );
 
+        // Update the request analytics
+        if let Some(analytics) = &self.analytics {
+            analytics.record_request(peer_network_id, &request);
+        }
+
         // Handle any optimistic fetch requests
         if request.data_request.is_optimistic_fetch() {
             self.handle_optimistic_fetch_request(peer_network_id, request, response_sender);
@@ -119,9 +140,107 @@ impl<T: StorageReaderInterface> Handler<T> {
             return;
         }
 
+        // Handle any batch requests
+        if let DataRequest::GetBatch(batch_request) = &request.data_request {
+            let response = self.process_batch_request(
+                storage_service_config,
+                &peer_network_id,
+                batch_request,
+                request.use_compression,
+            );
+            self.send_response(
+                storage_service_config,
+                &peer_network_id,
+                request,
+                response,
+                response_sender,
+            );
+            return;
+        }
+
         // Process the request and return the response to the client
         let response = self.process_request(&peer_network_id, request.clone(), false);
-        self.send_response(request, response, response_sender);
+        self.send_response(
+            storage_service_config,
+            &peer_network_id,
+            request,
+            response,
+            response_sender,
+        );
+    }
+
+    /// Processes the given batch request by processing each batched request
+    /// (in order). If the combined responses would overflow the network
+    /// frame, only the responses to a prefix of the requests are returned.
+    fn process_batch_request(
+        &self,
+        storage_service_config: StorageServiceConfig,
+        peer_network_id: &PeerNetworkId,
+        batch_request: &BatchRequest,
+        use_compression: bool,
+    ) -> aptos_storage_service_types::Result<StorageServiceResponse> {
+        // Verify the batch is valid
+        let num_requests = batch_request.requests.len() as u64;
+        let max_batch_request_size = storage_service_config.max_batch_request_size;
+        if num_requests == 0 || num_requests > max_batch_request_size {
+            return Err(Error::InvalidRequest(format!(
+                "Batch requests must contain between 1 and {} requests, found: {}",
+                max_batch_request_size, num_requests
+            ))
+            .into());
+        }
+        if let Some(data_request) = batch_request
+            .requests
+            .iter()
+            .find(|data_request| !data_request.is_batchable())
+        {
+            return Err(Error::InvalidRequest(format!(
+                "Batch requests cannot contain request: {:?}",
+                data_request
+            ))
+            .into());
+        }
+
+        // Process each request until the network frame limit is reached. The
+        // size of a response is only known once it has been built, so we stop
+        // as soon as the largest response seen so far would no longer fit
+        // (instead of building a response that would then be discarded).
+        // Note: the first response is always served (it is already bounded).
+        let max_network_chunk_bytes = storage_service_config.max_network_chunk_bytes;
+        let (_, mut num_response_bytes) =
+            check_overflow_network_frame(&DataResponse::Batch(vec![]), max_network_chunk_bytes)?;
+        let mut max_num_bytes_per_response = 0;
+        let mut data_responses = vec![];
+        for data_request in &batch_request.requests {
+            if !data_responses.is_empty()
+                && num_response_bytes + max_num_bytes_per_response >= max_network_chunk_bytes
+            {
+                break;
+            }
+
+            // Process the request (the batch is compressed as a whole)
+            let request = StorageServiceRequest::new(data_request.clone(), false);
+            let data_response = self
+                .process_request(peer_network_id, request, false)?
+                .get_data_response()
+                .map_err(|error| StorageServiceError::from(Error::from(error)))?;
+
+            // Stop if the response doesn't fit in the network frame
+            let (_, num_bytes) =
+                check_overflow_network_frame(&data_response, max_network_chunk_bytes)?;
+            if !data_responses.is_empty()
+                && num_response_bytes + num_bytes >= max_network_chunk_bytes
+            {
+                break;
+            }
+            num_response_bytes += num_bytes;
+            max_num_bytes_per_response = max_num_bytes_per_response.max(num_bytes);
+            data_responses.push(data_response);
+        }
+
+        // Create the batch response
+        StorageServiceResponse::new(DataResponse::Batch(data_responses), use_compression)
+            .map_err(|error| Error::from(error).into())
     }
 
     /// Processes the given request and returns the response
@@ -143,6 +262,11 @@ impl<T: StorageReaderInterface> Handler<T> {
                         error.get_label().into(),
                     );
 
+                    // Update the request analytics
+                    if let Some(analytics) = &self.analytics {
+                        analytics.record_error(*peer_network_id);
+                    }
+
                     // Periodically log the failure
                     sample!(
                             SampleRate::Duration(Duration::from_secs(ERROR_LOG_FREQUENCY_SECS)),
@@ -179,13 +303,7 @@ impl<T: StorageReaderInterface> Handler<T> {
         );
 
         // Transform the request error into a storage service error (for the client)
-        process_result.map_err(|error| match error {
-            Error::InvalidRequest(error) => StorageServiceError::InvalidRequest(error),
-            Error::TooManyInvalidRequests(error) => {
-                StorageServiceError::TooManyInvalidRequests(error)
-            },
-            error => StorageServiceError::InternalError(error.to_string()),
-        })
+        process_result.map_err(StorageServiceError::from)
     }
 
     /// Validate the request and only handle it if the moderator allows
@@ -210,6 +328,16 @@ impl<T: StorageReaderInterface> Handler<T> {
                 StorageServiceResponse::new(data_response, request.use_compression)
                     .map_err(|error| error.into())
             },
+            DataRequest::GetPruneWindowForecast => {
+                let data_response = self.get_prune_window_forecast()?;
+                StorageServiceResponse::new(data_response, request.use_compression)
+                    .map_err(|error| error.into())
+            },
+            DataRequest::GetInternalIndexerSummary => {
+                let data_response = self.get_internal_indexer_summary()?;
+                StorageServiceResponse::new(data_response, request.use_compression)
+                    .map_err(|error| error.into())
+            },
             _ => self.process_cachable_request(peer_network_id, request),
         }
     }
@@ -217,10 +345,43 @@ impl<T: StorageReaderInterface> Handler<T> {
     /// Sends a response via the provided sender
     pub(crate) fn send_response(
         &self,
+        storage_service_config: StorageServiceConfig,
+        peer_network_id: &PeerNetworkId,
         request: StorageServiceRequest,
         response: aptos_storage_service_types::Result<StorageServiceResponse>,
         response_sender: ResponseSender,
     ) {
+        // Wrap the response in a checksummed envelope (if the client asked for it)
+        let response = if request.use_checksum && storage_service_config.enable_response_checksums {
+            response.and_then(|response| {
+                response
+                    .with_checksum()
+                    .map_err(|error| StorageServiceError::from(Error::from(error)))
+            })
+        } else {
+            response
+        };
+
+        // Update the response size metrics and the bytes served to the peer
+        if let Ok(response) = &response {
+            if let Ok(num_bytes) = response.serialized_size() {
+                let response_label = response.get_label();
+                metrics::observe_response_size(&response_label, num_bytes as u64);
+                self.request_moderator.record_bytes_served(
+                    peer_network_id,
+                    &response_label,
+                    num_bytes as u64,
+                );
+                if self.storage.is_trusted_peer(peer_network_id) {
+                    metrics::increment_trusted_peer_bytes_served(
+                        peer_network_id.network_id(),
+                        &response_label,
+                        num_bytes as u64,
+                    );
+                }
+            }
+        }
+
         log_storage_response(request, &response);
         response_sender.send(response);
     }
@@ -313,6 +474,7 @@ impl<T: StorageReaderInterface> Handler<T> {
                     {
                         // Handle the subscription failure
                         self.handle_subscription_request_failure(
+                            storage_service_config,
                             peer_network_id,
                             request,
                             error,
@@ -339,6 +501,7 @@ impl<T: StorageReaderInterface> Handler<T> {
     /// updating the subscription metrics, and notifying the client.
     fn handle_subscription_request_failure(
         &self,
+        storage_service_config: StorageServiceConfig,
         peer_network_id: PeerNetworkId,
         request: StorageServiceRequest,
         error: Error,
@@ -359,8 +522,10 @@ impl<T: StorageReaderInterface> Handler<T> {
 
         // Notify the client of the failure
         self.send_response(
+            storage_service_config,
+            &peer_network_id,
             request,
-            Err(StorageServiceError::InvalidRequest(error.to_string())),
+            Err(error.into()),
             subscription_request.take_response_sender(),
         );
     }
@@ -372,6 +537,22 @@ impl<T: StorageReaderInterface> Handler<T> {
         peer_network_id: &PeerNetworkId,
         request: &StorageServiceRequest,
     ) -> aptos_storage_service_types::Result<StorageServiceResponse, Error> {
+        // Serve requests from trusted peers with the larger chunk limits. These
+        // bypass the LRU cache, so that the larger responses are never served
+        // to other peers (and vice versa).
+        if self.storage.is_trusted_peer(peer_network_id) {
+            increment_counter(
+                &metrics::TRUSTED_PEER_REQUESTS,
+                peer_network_id.network_id(),
+                request.get_label(),
+            );
+            let handler = Handler {
+                storage: self.storage.with_trusted_peer_chunk_limits(),
+                ..self.clone()
+            };
+            return handler.fetch_storage_response(peer_network_id, request);
+        }
+
         // Increment the LRU cache probe counter
         increment_counter(
             &metrics::LRU_CACHE_EVENT,
@@ -380,7 +561,11 @@ impl<T: StorageReaderInterface> Handler<T> {
         );
 
         // Check if the response is already in the cache
-        if let Some(response) = self.lru_response_cache.get(request) {
+        let cached_response = self.lru_response_cache.get(request);
+        if let Some(analytics) = &self.analytics {
+            analytics.record_cache_probe(cached_response.is_some());
+        }
+        if let Some(response) = cached_response {
             increment_counter(
                 &metrics::LRU_CACHE_EVENT,
                 peer_network_id.network_id(),
@@ -389,11 +574,33 @@ impl<T: StorageReaderInterface> Handler<T> {
             return Ok(response.clone());
         }
 
-        // Otherwise, fetch the data from storage and time the operation
+        // Otherwise, fetch the data from storage
+        let storage_response = self.fetch_storage_response(peer_network_id, request)?;
+
+        // Cache the storage response
+        self.lru_response_cache
+            .insert(request.clone(), storage_response.clone());
+
+        // Return the storage response
+        Ok(storage_response)
+    }
+
+    /// Fetches the data for the given request from storage, and
+    /// creates the storage response (without touching the cache).
+    fn fetch_storage_response(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        request: &StorageServiceRequest,
+    ) -> aptos_storage_service_types::Result<StorageServiceResponse, Error> {
+        // Fetch the data from storage and time the operation
+        let serving_start_time = Instant::now();
         let fetch_data_response = || match &request.data_request {
             DataRequest::GetStateValuesWithProof(request) => {
                 self.get_state_value_chunk_with_proof(request)
             },
+            DataRequest::GetStateValuesByKeyRangeWithProof(request) => {
+                self.get_state_value_chunk_by_key_range_with_proof(request)
+            },
             DataRequest::GetEpochEndingLedgerInfos(request) => {
                 self.get_epoch_ending_ledger_infos(request)
             },
@@ -409,6 +616,14 @@ impl<T: StorageReaderInterface> Handler<T> {
             DataRequest::GetTransactionsOrOutputsWithProof(request) => {
                 self.get_transactions_or_outputs_with_proof(request)
             },
+            DataRequest::GetTransactionsWithoutProof(request) => {
+                self.get_transactions_without_proof(request)
+            },
+            DataRequest::GetTransactionByHashWithProof(request) => {
+                self.get_transaction_by_hash_with_proof(request)
+            },
+            DataRequest::GetVersionByTimestamp(request) => self.get_version_by_timestamp(request),
+            DataRequest::GetBlockMetadata(request) => self.get_block_metadata(request),
             _ => Err(Error::UnexpectedErrorEncountered(format!(
                 "Received an unexpected request: {:?}",
                 request
@@ -421,6 +636,7 @@ impl<T: StorageReaderInterface> Handler<T> {
             fetch_data_response,
             None,
         )?;
+        let response_label = data_response.get_label();
 
         // Create the storage response and time the operation
         let create_storage_response = || {
@@ -435,11 +651,16 @@ impl<T: StorageReaderInterface> Handler<T> {
             None,
         )?;
 
-        // Create and cache the storage response
-        self.lru_response_cache
-            .insert(request.clone(), storage_response.clone());
+        // Update the chunk serving latency (used to adapt the advertised chunk sizes).
+        // Trusted peers are served larger chunks, so they're excluded from this.
+        if !self.storage.is_trusted_peer(peer_network_id) {
+            metrics::observe_duration(
+                &metrics::STORAGE_CHUNK_SERVING_LATENCY,
+                vec![response_label.into()],
+                serving_start_time,
+            );
+        }
 
-        // Return the storage response
         Ok(storage_response)
     }
 
@@ -458,6 +679,22 @@ impl<T: StorageReaderInterface> Handler<T> {
         ))
     }
 
+    fn get_state_value_chunk_by_key_range_with_proof(
+        &self,
+        request: &StateValuesByKeyRangeWithProofRequest,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        let state_value_chunk_with_proof =
+            self.storage.get_state_value_chunk_by_key_range_with_proof(
+                request.version,
+                request.start_key_hash,
+                request.max_items,
+            )?;
+
+        Ok(DataResponse::StateValueChunkWithProof(
+            state_value_chunk_with_proof,
+        ))
+    }
+
     fn get_epoch_ending_ledger_infos(
         &self,
         request: &EpochEndingLedgerInfoRequest,
@@ -490,6 +727,33 @@ impl<T: StorageReaderInterface> Handler<T> {
         DataResponse::StorageServerSummary(storage_server_summary.as_ref().clone())
     }
 
+    fn get_prune_window_forecast(
+        &self,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        // Forecast against the cached summary (so the forecast is consistent
+        // with the ranges the peer has already been told about).
+        let storage_server_summary = self.cached_storage_server_summary.load();
+        let prune_window_forecast = self
+            .storage
+            .get_prune_window_forecast(&storage_server_summary.data_summary)?;
+
+        Ok(DataResponse::PruneWindowForecast(prune_window_forecast))
+    }
+
+    fn get_internal_indexer_summary(
+        &self,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        // Summarize the indexer up to the version of the cached summary
+        let storage_server_summary = self.cached_storage_server_summary.load();
+        let internal_indexer_summary = self
+            .storage
+            .get_internal_indexer_summary(&storage_server_summary.data_summary)?;
+
+        Ok(DataResponse::InternalIndexerSummary(
+            internal_indexer_summary,
+        ))
+    }
+
     fn get_transaction_outputs_with_proof(
         &self,
         request: &TransactionOutputsWithProofRequest,
@@ -537,6 +801,58 @@ impl<T: StorageReaderInterface> Handler<T> {
             outputs_with_proof,
         )))
     }
+
+    fn get_transactions_without_proof(
+        &self,
+        request: &TransactionsWithoutProofRequest,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        let transactions_without_proof = self.storage.get_transactions_without_proof(
+            request.start_version,
+            request.end_version,
+            request.include_events,
+        )?;
+
+        Ok(DataResponse::TransactionsWithoutProof(
+            transactions_without_proof,
+        ))
+    }
+
+    fn get_transaction_by_hash_with_proof(
+        &self,
+        request: &TransactionByHashWithProofRequest,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        let transaction_with_proof = self.storage.get_transaction_by_hash_with_proof(
+            request.transaction_hash,
+            request.proof_version,
+            request.include_events,
+        )?;
+
+        Ok(DataResponse::TransactionByHashWithProof(
+            transaction_with_proof,
+        ))
+    }
+
+    fn get_version_by_timestamp(
+        &self,
+        request: &VersionByTimestampRequest,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        let version_with_proof = self
+            .storage
+            .get_version_by_timestamp_with_proof(request.timestamp_usecs, request.ledger_version)?;
+
+        Ok(DataResponse::VersionByTimestamp(version_with_proof))
+    }
+
+    fn get_block_metadata(
+        &self,
+        request: &BlockMetadataRequest,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        let blocks = self
+            .storage
+            .get_block_metadata(request.start_version, request.end_version)?;
+
+        Ok(DataResponse::BlockMetadata(blocks))
+    }
 }
 
 /// Updates the subscription metrics with a created subscription stream event
@@ -5,30 +5,31 @@
 #![forbid(unsafe_code)]
 
 use crate::{
+    analytics::StorageServiceAnalytics,
+    chunk_sizing::AdaptiveChunkSizer,
+    config_handle::StorageServiceConfigHandle,
     logging::{LogEntry, LogSchema},
     network::StorageServiceNetworkEvents,
+    response_cache::ResponseCache,
     subscription::SubscriptionStreamRequests,
+    summary_freshness::StorageSummaryFreshness,
 };
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{StateSyncConfig, StorageServiceConfig},
+    config::{AptosDataClientConfig, StateSyncConfig, StorageServiceConfig},
     network_id::PeerNetworkId,
 };
 use aptos_logger::prelude::*;
 use aptos_network::application::storage::PeersAndMetadata;
 use aptos_storage_service_notifications::StorageServiceNotificationListener;
-use aptos_storage_service_types::{
-    requests::StorageServiceRequest,
-    responses::{ProtocolMetadata, StorageServerSummary, StorageServiceResponse},
-};
+use aptos_storage_service_types::responses::StorageServerSummary;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use arc_swap::ArcSwap;
 use dashmap::DashMap;
 use error::Error;
 use futures::stream::StreamExt;
 use handler::Handler;
-use mini_moka::sync::Cache;
 use moderator::RequestModerator;
 use optimistic_fetch::OptimisticFetchRequest;
 use std::{ops::Deref, sync::Arc, time::Duration};
@@ -36,15 +37,22 @@ use storage::StorageReaderInterface;
 use thiserror::Error;
 use tokio::runtime::Handle;
 
+pub mod analytics;
+mod chunk_sizing;
+pub mod config_handle;
 mod error;
 mod handler;
 mod logging;
 pub mod metrics;
+#[cfg(any(test, feature = "testing"))]
+pub mod mock_server;
 mod moderator;
 pub mod network;
 mod optimistic_fetch;
+mod response_cache;
 pub mod storage;
 mod subscription;
+mod summary_freshness;
 mod utils;
 
 #[cfg(test)]
@@ -62,17 +70,20 @@ pub struct StorageServiceServer<T> {
     bounded_executor: BoundedExecutor,
     network_requests: StorageServiceNetworkEvents,
     storage: T,
-    storage_service_config: StorageServiceConfig,
+    storage_service_config_handle: StorageServiceConfigHandle,
     time_service: TimeService,
 
     // A cached storage server summary to avoid hitting the DB for every
     // request. This is refreshed periodically.
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
 
+    // Tracks when the cached storage server summary was last refreshed
+    storage_summary_freshness: StorageSummaryFreshness,
+
     // An LRU cache for commonly requested data items.
     // Note: This is not just a database cache because it contains
     // responses that have already been serialized and compressed.
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
 
     // A set of active optimistic fetches for peers waiting for new data
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
@@ -83,6 +94,9 @@ pub struct StorageServiceServer<T> {
     // A moderator for incoming peer requests
     request_moderator: Arc<RequestModerator>,
 
+    // A rolling window of analytics on the requests received from peers
+    analytics: StorageServiceAnalytics,
+
     // The listener for notifications from state sync
     storage_service_listener: Option<StorageServiceNotificationListener>,
 }
@@ -97,9 +111,33 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         network_requests: StorageServiceNetworkEvents,
         storage_service_listener: StorageServiceNotificationListener,
     ) -> Self {
-        // Extract the individual component configs
-        let aptos_data_client_config = config.aptos_data_client;
-        let storage_service_config = config.storage_service;
+        Self::new_with_config_handle(
+            config.aptos_data_client,
+            StorageServiceConfigHandle::new(config.storage_service),
+            executor,
+            storage,
+            time_service,
+            peers_and_metadata,
+            network_requests,
+            storage_service_listener,
+        )
+    }
+
+    /// Creates a new storage service server that reads the latest
+    /// storage service config from the given handle. This allows
+    /// the config to be updated at runtime (see `StorageServiceConfigHandle`).
+    pub fn new_with_config_handle(
+        aptos_data_client_config: AptosDataClientConfig,
+        storage_service_config_handle: StorageServiceConfigHandle,
+        executor: Handle,
+        storage: T,
+        time_service: TimeService,
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        network_requests: StorageServiceNetworkEvents,
+        storage_service_listener: StorageServiceNotificationListener,
+    ) -> Self {
+        // Fetch the initial storage service config
+        let storage_service_config = storage_service_config_handle.get_config();
 
         // Create the required components
         let bounded_executor = BoundedExecutor::new(
@@ -108,8 +146,9 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         );
         let cached_storage_server_summary =
             Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
+        let storage_summary_freshness = StorageSummaryFreshness::new(time_service.clone());
         let optimistic_fetches = Arc::new(DashMap::new());
-        let lru_response_cache = Cache::new(storage_service_config.max_lru_cache_size);
+        let lru_response_cache = ResponseCache::new(storage_service_config.max_lru_cache_size);
         let subscriptions = Arc::new(DashMap::new());
         let request_moderator = Arc::new(RequestModerator::new(
             aptos_data_client_config,
@@ -118,23 +157,39 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
             storage_service_config,
             time_service.clone(),
         ));
+        let analytics = StorageServiceAnalytics::new(
+            storage_service_config.request_analytics_window_secs,
+            time_service.clone(),
+        );
         let storage_service_listener = Some(storage_service_listener);
 
         Self {
             bounded_executor,
             network_requests,
             storage,
-            storage_service_config,
+            storage_service_config_handle,
             time_service,
             cached_storage_server_summary,
+            storage_summary_freshness,
             lru_response_cache,
             optimistic_fetches,
             subscriptions,
             request_moderator,
+            analytics,
             storage_service_listener,
         }
     }
 
+    /// Returns a handle that can be used to update the storage service config at runtime
+    pub fn get_config_handle(&self) -> StorageServiceConfigHandle {
+        self.storage_service_config_handle.clone()
+    }
+
+    /// Returns a handle to the analytics of the requests received from peers
+    pub fn get_analytics(&self) -> StorageServiceAnalytics {
+        self.analytics.clone()
+    }
+
     /// Spawns all continuously running utility tasks
     async fn spawn_continuous_storage_summary_tasks(&mut self) {
         // Create channels to notify the optimistic fetch and subscription
@@ -162,6 +217,9 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
 
         // Spawn the refresher for the request moderator
         self.spawn_moderator_peer_refresher().await;
+
+        // Spawn the handler for storage service config updates
+        self.spawn_config_update_handler().await;
     }
 
     /// Spawns a non-terminating task that refreshes the cached storage server summary
@@ -171,8 +229,9 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
     ) {
         // Clone all required components for the task
         let cached_storage_server_summary = self.cached_storage_server_summary.clone();
-        let config = self.storage_service_config;
+        let config_handle = self.storage_service_config_handle.clone();
         let storage = self.storage.clone();
+        let storage_summary_freshness = self.storage_summary_freshness.clone();
         let time_service = self.time_service.clone();
 
         // Take the storage service listener
@@ -185,10 +244,14 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         self.bounded_executor
             .spawn(async move {
                 // Create a ticker for the refresh interval
-                let duration = Duration::from_millis(config.storage_summary_refresh_interval_ms);
+                let refresh_interval_ms = config_handle.get_config().storage_summary_refresh_interval_ms;
+                let duration = Duration::from_millis(refresh_interval_ms);
                 let ticker = time_service.interval(duration);
                 futures::pin_mut!(ticker);
 
+                // Create the sizer for the advertised chunk sizes
+                let mut adaptive_chunk_sizer = AdaptiveChunkSizer::new();
+
                 // Continuously refresh the cache
                 loop {
                     futures::select! {
@@ -197,7 +260,9 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                             refresh_cached_storage_summary(
                                 cached_storage_server_summary.clone(),
                                 storage.clone(),
-                                config,
+                                config_handle.get_config(),
+                                &mut adaptive_chunk_sizer,
+                                &storage_summary_freshness,
                                 cache_update_notifiers.clone(),
                             )
                         },
@@ -213,7 +278,9 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                             refresh_cached_storage_summary(
                                 cached_storage_server_summary.clone(),
                                 storage.clone(),
-                                config,
+                                config_handle.get_config(),
+                                &mut adaptive_chunk_sizer,
+                                &storage_summary_freshness,
                                 cache_update_notifiers.clone(),
                             )
                         },
@@ -234,7 +301,7 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         // Clone all required components for the task
         let bounded_executor = self.bounded_executor.clone();
         let cached_storage_server_summary = self.cached_storage_server_summary.clone();
-        let config = self.storage_service_config;
+        let config_handle = self.storage_service_config_handle.clone();
         let optimistic_fetches = self.optimistic_fetches.clone();
         let lru_response_cache = self.lru_response_cache.clone();
         let request_moderator = self.request_moderator.clone();
@@ -246,7 +313,8 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         self.bounded_executor
             .spawn(async move {
                 // Create a ticker for the refresh interval
-                let duration = Duration::from_millis(config.storage_summary_refresh_interval_ms);
+                let refresh_interval_ms = config_handle.get_config().storage_summary_refresh_interval_ms;
+                let duration = Duration::from_millis(refresh_interval_ms);
                 let ticker = time_service.interval(duration);
                 futures::pin_mut!(ticker);
 
@@ -258,7 +326,7 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                             handle_active_optimistic_fetches(
                                 bounded_executor.clone(),
                                 cached_storage_server_summary.clone(),
-                                config,
+                                config_handle.get_config(),
                                 optimistic_fetches.clone(),
                                 lru_response_cache.clone(),
                                 request_moderator.clone(),
@@ -276,7 +344,7 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                             handle_active_optimistic_fetches(
                                 bounded_executor.clone(),
                                 cached_storage_server_summary.clone(),
-                                config,
+                                config_handle.get_config(),
                                 optimistic_fetches.clone(),
                                 lru_response_cache.clone(),
                                 request_moderator.clone(),
@@ -302,11 +370,12 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         // Clone all required components for the task
         let bounded_executor = self.bounded_executor.clone();
         let cached_storage_server_summary = self.cached_storage_server_summary.clone();
-        let config = self.storage_service_config;
+        let config_handle = self.storage_service_config_handle.clone();
         let optimistic_fetches = self.optimistic_fetches.clone();
         let lru_response_cache = self.lru_response_cache.clone();
         let request_moderator = self.request_moderator.clone();
         let storage = self.storage.clone();
+        let storage_summary_freshness = self.storage_summary_freshness.clone();
         let subscriptions = self.subscriptions.clone();
         let time_service = self.time_service.clone();
 
@@ -314,7 +383,8 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         self.bounded_executor
             .spawn(async move {
                 // Create a ticker for the refresh interval
-                let duration = Duration::from_millis(config.storage_summary_refresh_interval_ms);
+                let refresh_interval_ms = config_handle.get_config().storage_summary_refresh_interval_ms;
+                let duration = Duration::from_millis(refresh_interval_ms);
                 let ticker = time_service.interval(duration);
                 futures::pin_mut!(ticker);
 
@@ -326,11 +396,12 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                             handle_active_subscriptions(
                                 bounded_executor.clone(),
                                 cached_storage_server_summary.clone(),
-                                config,
+                                config_handle.get_config(),
                                 optimistic_fetches.clone(),
                                 lru_response_cache.clone(),
                                 request_moderator.clone(),
                                 storage.clone(),
+                                storage_summary_freshness.clone(),
                                 subscriptions.clone(),
                                 time_service.clone(),
                             ).await;
@@ -344,11 +415,12 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                             handle_active_subscriptions(
                                 bounded_executor.clone(),
                                 cached_storage_server_summary.clone(),
-                                config,
+                                config_handle.get_config(),
                                 optimistic_fetches.clone(),
                                 lru_response_cache.clone(),
                                 request_moderator.clone(),
                                 storage.clone(),
+                                storage_summary_freshness.clone(),
                                 subscriptions.clone(),
                                 time_service.clone(),
                             ).await;
@@ -363,7 +435,7 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
     /// peer states in the request moderator.
     async fn spawn_moderator_peer_refresher(&mut self) {
         // Clone all required components for the task
-        let config = self.storage_service_config;
+        let config = self.storage_service_config_handle.get_config();
         let request_moderator = self.request_moderator.clone();
         let time_service = self.time_service.clone();
 
@@ -390,6 +462,29 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
             .await;
     }
 
+    /// Spawns a non-terminating task that applies storage service config
+    /// updates to the components created at startup (i.e., the LRU cache).
+    async fn spawn_config_update_handler(&mut self) {
+        // Clone all required components for the task
+        let mut config_receiver = self.storage_service_config_handle.subscribe();
+        let lru_response_cache = self.lru_response_cache.clone();
+
+        // Spawn the task
+        self.bounded_executor
+            .spawn(async move {
+                // Apply the latest config (in case it was updated before the task started)
+                let max_lru_cache_size = config_receiver.borrow_and_update().max_lru_cache_size;
+                lru_response_cache.resize(max_lru_cache_size);
+
+                // Resize the LRU cache whenever the config is updated
+                while config_receiver.changed().await.is_ok() {
+                    let max_lru_cache_size = config_receiver.borrow().max_lru_cache_size;
+                    lru_response_cache.resize(max_lru_cache_size);
+                }
+            })
+            .await;
+    }
+
     /// Starts the storage service server thread
     pub async fn start(mut self) {
         // Spawn the continuously running tasks
@@ -397,17 +492,32 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
 
         // Handle the storage requests as they arrive
         while let Some(network_request) = self.network_requests.next().await {
+            // Requests that could not be decoded are counted as invalid
+            // requests by the moderator (before responding with the error).
+            let network_request = match network_request {
+                Ok(network_request) => network_request,
+                Err(invalid_request) => {
+                    self.request_moderator
+                        .record_invalid_request(&invalid_request.peer_network_id);
+                    invalid_request
+                        .response_sender
+                        .send(Err(invalid_request.error));
+                    continue;
+                },
+            };
+
             // All handler methods are currently CPU-bound and synchronous
             // I/O-bound, so we want to spawn on the blocking thread pool to
             // avoid starving other async tasks on the same runtime.
             let storage = self.storage.clone();
-            let config = self.storage_service_config;
+            let config = self.storage_service_config_handle.get_config();
             let cached_storage_server_summary = self.cached_storage_server_summary.clone();
             let optimistic_fetches = self.optimistic_fetches.clone();
             let subscriptions = self.subscriptions.clone();
             let lru_response_cache = self.lru_response_cache.clone();
             let request_moderator = self.request_moderator.clone();
             let time_service = self.time_service.clone();
+            let analytics = self.analytics.clone();
             self.bounded_executor
                 .spawn_blocking(move || {
                     Handler::new(
@@ -419,6 +529,7 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
                         subscriptions,
                         time_service,
                     )
+                    .with_analytics(analytics)
                     .process_request_and_respond(
                         config,
                         network_request.peer_network_id,
@@ -431,6 +542,12 @@ impl<T: StorageReaderInterface + Send + Sync> StorageServiceServer<T> {
         }
     }
 
+    #[cfg(test)]
+    /// Returns a copy of the LRU response cache for test purposes
+    pub(crate) fn get_lru_response_cache(&self) -> ResponseCache {
+        self.lru_response_cache.clone()
+    }
+
     #[cfg(test)]
     /// Returns a copy of the request moderator for test purposes
     pub(crate) fn get_request_moderator(&self) -> Arc<RequestModerator> {
@@ -461,7 +578,7 @@ async fn handle_active_optimistic_fetches<T: StorageReaderInterface>(
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     config: StorageServiceConfig,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
@@ -493,9 +610,10 @@ async fn handle_active_subscriptions<T: StorageReaderInterface>(
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     config: StorageServiceConfig,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
+    storage_summary_freshness: StorageSummaryFreshness,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
     time_service: TimeService,
 ) {
@@ -507,6 +625,7 @@ async fn handle_active_subscriptions<T: StorageReaderInterface>(
         lru_response_cache,
         request_moderator,
         storage,
+        storage_summary_freshness,
         subscriptions,
         time_service,
     )
@@ -525,6 +644,8 @@ pub(crate) fn refresh_cached_storage_summary<T: StorageReaderInterface>(
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     storage: T,
     storage_config: StorageServiceConfig,
+    adaptive_chunk_sizer: &mut AdaptiveChunkSizer,
+    storage_summary_freshness: &StorageSummaryFreshness,
     cache_update_notifiers: Vec<aptos_channel::Sender<(), CachedSummaryUpdateNotification>>,
 ) {
     // Fetch the new data summary from storage
@@ -538,13 +659,11 @@ pub(crate) fn refresh_cached_storage_summary<T: StorageReaderInterface>(
         },
     };
 
-    // Initialize the protocol metadata
-    let new_protocol_metadata = ProtocolMetadata {
-        max_epoch_chunk_size: storage_config.max_epoch_chunk_size,
-        max_transaction_chunk_size: storage_config.max_transaction_chunk_size,
-        max_state_chunk_size: storage_config.max_state_chunk_size,
-        max_transaction_output_chunk_size: storage_config.max_transaction_output_chunk_size,
-    };
+    // The summary is fresh (even if it hasn't changed), as storage could be read
+    storage_summary_freshness.mark_refreshed();
+
+    // Initialize the protocol metadata (the chunk sizes may be adapted to recent responses)
+    let new_protocol_metadata = adaptive_chunk_sizer.get_protocol_metadata(&storage_config);
 
     // Create the new storage server summary
     let new_storage_server_summary = StorageServerSummary {
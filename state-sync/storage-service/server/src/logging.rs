@@ -39,6 +39,7 @@ pub enum LogEntry {
     OptimisticFetchRefresh,
     OptimisticFetchRequest,
     OptimisticFetchResponse,
+    ProofSelfCheck,
     ReceivedCacheUpdateNotification,
     ReceivedCommitNotification,
     ReceivedStorageRequest,
@@ -4,8 +4,9 @@
 
 use aptos_config::network_id::NetworkId;
 use aptos_metrics_core::{
-    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramVec,
-    IntCounterVec, IntGaugeVec,
+    exponential_buckets, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 use std::time::Instant;
@@ -20,6 +21,8 @@ pub const RESULT_FAILURE: &str = "failure";
 pub const SUBSCRIPTION_ADD: &str = "subscription_add";
 pub const SUBSCRIPTION_EXPIRE: &str = "subscription_expire";
 pub const SUBSCRIPTION_FAILURE: &str = "subscription_failure";
+pub const SUBSCRIPTION_FANOUT_HIT: &str = "subscription_fanout_hit";
+pub const SUBSCRIPTION_FANOUT_PROBE: &str = "subscription_fanout_probe";
 pub const SUBSCRIPTION_NEW_STREAM: &str = "subscription_new_stream";
 
 // Latency buckets for request processing latencies (seconds)
@@ -38,6 +41,16 @@ pub static IGNORED_PEER_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauge for tracking the number of peers that exceeded their bandwidth quota
+pub static THROTTLED_PEER_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_storage_service_server_throttled_peer_count",
+        "Gauge for tracking the number of peers that exceeded their bandwidth quota",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
 /// Counter for lru cache events in the storage service (server-side)
 pub static LRU_CACHE_EVENT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -110,6 +123,17 @@ pub static STORAGE_ERRORS_ENCOUNTERED: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for the number of proofs that failed the server-side self-check
+/// (i.e., proofs that would have been sent to peers but don't verify).
+pub static PROOF_SELF_CHECK_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_service_server_proof_self_check_failures",
+        "Counters for proofs that failed the storage server self-check",
+        &["response_type"]
+    )
+    .unwrap()
+});
+
 /// Counter for received storage service requests
 pub static STORAGE_REQUESTS_RECEIVED: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -130,6 +154,36 @@ pub static STORAGE_RESPONSES_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for the number of bytes served by the storage service (server-side)
+pub static STORAGE_BYTES_SERVED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_service_server_bytes_served",
+        "Counters for the number of bytes served by the storage server",
+        &["network_id", "response_type"]
+    )
+    .unwrap()
+});
+
+/// Counter for requests from trusted peers (served with larger chunk limits)
+pub static TRUSTED_PEER_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_service_server_trusted_peer_requests",
+        "Counters for the requests from trusted peers (served with larger chunk limits)",
+        &["network_id", "request_type"]
+    )
+    .unwrap()
+});
+
+/// Counter for the number of bytes served to trusted peers
+pub static TRUSTED_PEER_BYTES_SERVED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_service_server_trusted_peer_bytes_served",
+        "Counters for the number of bytes served to trusted peers",
+        &["network_id", "response_type"]
+    )
+    .unwrap()
+});
+
 /// Time it takes to read data from the storage service DB
 pub static STORAGE_DB_READ_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -150,6 +204,28 @@ pub static STORAGE_FETCH_PROCESSING_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Time it takes to serve a chunk (i.e., to fetch the data and create the
+/// response), used to adapt the advertised chunk sizes
+pub static STORAGE_CHUNK_SERVING_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_storage_service_server_chunk_serving_latency",
+        "Time it takes to serve a chunk, by response type",
+        &["response_type"],
+        REQUEST_PROCESSING_LATENCY_BUCKETS_SECS.to_vec(),
+    )
+    .unwrap()
+});
+
+/// Gauge for the maximum chunk sizes advertised to peers (by chunk type)
+pub static ADVERTISED_CHUNK_SIZES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_storage_service_server_advertised_chunk_sizes",
+        "Gauge for the maximum chunk sizes advertised to peers",
+        &["chunk_type"]
+    )
+    .unwrap()
+});
+
 /// Time it takes to create a storage service response
 pub static STORAGE_RESPONSE_CREATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -160,6 +236,17 @@ pub static STORAGE_RESPONSE_CREATION_LATENCY: Lazy<HistogramVec> = Lazy::new(||
     .unwrap()
 });
 
+/// Serialized sizes of the storage responses sent to peers (bytes)
+pub static STORAGE_RESPONSE_SIZE_BYTES: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_storage_service_server_response_size_bytes",
+        "Serialized sizes of the storage responses sent to peers",
+        &["response_type"],
+        exponential_buckets(/*start=*/ 256.0, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
 /// Time it takes to process a storage request
 pub static STORAGE_REQUEST_PROCESSING_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -182,6 +269,26 @@ pub static STORAGE_REQUEST_VALIDATION_LATENCY: Lazy<HistogramVec> = Lazy::new(||
     .unwrap()
 });
 
+/// Gauge for tracking how far (ms) the storage summary refresher
+/// has fallen behind its refresh interval.
+pub static STORAGE_SUMMARY_STALENESS_MS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_service_server_storage_summary_staleness_ms",
+        "Gauge for tracking how far the storage summary refresher has fallen behind (ms)"
+    )
+    .unwrap()
+});
+
+/// Counter for the number of times subscriptions were not served
+/// because the cached storage summary was stale.
+pub static STALE_SUMMARY_SUBSCRIPTION_PAUSES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_service_server_stale_summary_subscription_pauses",
+        "Counter for subscription handling rounds paused due to a stale storage summary"
+    )
+    .unwrap()
+});
+
 /// Gauge for tracking the number of active subscriptions
 pub static SUBSCRIPTION_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -220,6 +327,38 @@ pub fn increment_network_frame_overflow(response_type: &str) {
         .inc()
 }
 
+/// Increments the proof self-check failure counter for the given response
+pub fn increment_proof_self_check_failure(response_type: &str) {
+    PROOF_SELF_CHECK_FAILURES
+        .with_label_values(&[response_type])
+        .inc()
+}
+
+/// Observes the serialized size of a response with the given type
+pub fn observe_response_size(response_type: &str, num_bytes: u64) {
+    STORAGE_RESPONSE_SIZE_BYTES
+        .with_label_values(&[response_type])
+        .observe(num_bytes as f64)
+}
+
+/// Increments the bytes served counter for the given network and response type
+pub fn increment_bytes_served(network_id: NetworkId, response_type: &str, num_bytes: u64) {
+    STORAGE_BYTES_SERVED
+        .with_label_values(&[network_id.as_str(), response_type])
+        .inc_by(num_bytes)
+}
+
+/// Increments the trusted peer bytes served counter for the given network and response type
+pub fn increment_trusted_peer_bytes_served(
+    network_id: NetworkId,
+    response_type: &str,
+    num_bytes: u64,
+) {
+    TRUSTED_PEER_BYTES_SERVED
+        .with_label_values(&[network_id.as_str(), response_type])
+        .inc_by(num_bytes)
+}
+
 /// Increments the given counter with the provided label values.
 pub fn increment_counter(counter: &Lazy<IntCounterVec>, network_id: NetworkId, label: String) {
     counter
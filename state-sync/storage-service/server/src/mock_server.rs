@@ -0,0 +1,179 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{handler::STORAGE_SERVER_VERSION, network::NetworkRequest};
+use aptos_infallible::Mutex;
+use aptos_storage_service_types::{
+    requests::{DataRequest, StorageServiceRequest},
+    responses::{
+        DataResponse, ServerProtocolVersion, StorageServerSummary, StorageServiceResponse,
+    },
+    StorageServiceError, StorageServiceErrorCategory,
+};
+use futures::{Stream, StreamExt};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+/// A fault that the mock server can inject instead of serving a request
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MockServerFault {
+    /// The given error is returned to the client
+    Error(StorageServiceError),
+    /// The response sender is dropped, so the client sees an RPC failure
+    DropResponse,
+    /// The request is served normally, but only after the given delay
+    Delay(Duration),
+}
+
+/// The canned data and faults used by the mock server to respond to requests
+#[derive(Default)]
+struct MockServerState {
+    storage_server_summary: StorageServerSummary,
+    data_responses: HashMap<DataRequest, DataResponse>,
+    pending_faults: VecDeque<MockServerFault>,
+    received_requests: Vec<StorageServiceRequest>,
+}
+
+/// A lightweight, in-process implementation of the storage service wire
+/// protocol. Instead of reading from a database, the mock server responds
+/// to requests using canned summaries and data chunks, and can inject faults.
+/// This allows storage service clients (e.g., the data client and downstream
+/// SDKs) to test against realistic server behaviour.
+///
+/// The mock server is cheap to clone, and all clones share the same state
+/// (e.g., tests can keep a clone to update the canned data after serving starts).
+#[derive(Clone, Default)]
+pub struct MockStorageServiceServer {
+    state: Arc<Mutex<MockServerState>>,
+}
+
+impl MockStorageServiceServer {
+    pub fn new(storage_server_summary: StorageServerSummary) -> Self {
+        let mock_server = Self::default();
+        mock_server.set_storage_server_summary(storage_server_summary);
+        mock_server
+    }
+
+    /// Sets the summary returned for storage server summary requests
+    pub fn set_storage_server_summary(&self, storage_server_summary: StorageServerSummary) {
+        self.state.lock().storage_server_summary = storage_server_summary;
+    }
+
+    /// Sets the canned response for the given data request. Any previous
+    /// response for the same request is replaced.
+    pub fn set_data_response(&self, data_request: DataRequest, data_response: DataResponse) {
+        self.state
+            .lock()
+            .data_responses
+            .insert(data_request, data_response);
+    }
+
+    /// Removes the canned response for the given data request (if any)
+    pub fn remove_data_response(&self, data_request: &DataRequest) {
+        self.state.lock().data_responses.remove(data_request);
+    }
+
+    /// Injects the given fault for the next `num_requests` requests.
+    /// Faults are consumed in the order they were injected.
+    pub fn inject_fault(&self, fault: MockServerFault, num_requests: usize) {
+        let mut state = self.state.lock();
+        for _ in 0..num_requests {
+            state.pending_faults.push_back(fault.clone());
+        }
+    }
+
+    /// Removes all pending faults
+    pub fn clear_faults(&self) {
+        self.state.lock().pending_faults.clear();
+    }
+
+    /// Returns all requests received by the mock server (in order)
+    pub fn get_received_requests(&self) -> Vec<StorageServiceRequest> {
+        self.state.lock().received_requests.clone()
+    }
+
+    /// Processes the given request and returns the response. This does not
+    /// consider any injected faults (see `handle_network_request()`).
+    pub fn process_request(
+        &self,
+        request: &StorageServiceRequest,
+    ) -> aptos_storage_service_types::Result<StorageServiceResponse> {
+        // Identify the data response
+        let data_response = match &request.data_request {
+            DataRequest::GetServerProtocolVersion => {
+                DataResponse::ServerProtocolVersion(ServerProtocolVersion {
+                    protocol_version: STORAGE_SERVER_VERSION,
+                })
+            },
+            DataRequest::GetStorageServerSummary => {
+                DataResponse::StorageServerSummary(self.state.lock().storage_server_summary.clone())
+            },
+            data_request => self
+                .state
+                .lock()
+                .data_responses
+                .get(data_request)
+                .cloned()
+                .ok_or_else(|| {
+                    StorageServiceError::new(
+                        StorageServiceErrorCategory::Retryable,
+                        format!(
+                            "The mock server has no response for the request: {:?}",
+                            data_request
+                        ),
+                    )
+                })?,
+        };
+
+        // Create the storage service response
+        StorageServiceResponse::new(data_response, request.use_compression).map_err(|error| {
+            StorageServiceError::new(StorageServiceErrorCategory::Fatal, error.to_string())
+        })
+    }
+
+    /// Handles the given network request (including any injected
+    /// faults) and responds to the client via the response sender.
+    pub async fn handle_network_request(&self, network_request: NetworkRequest) {
+        let NetworkRequest {
+            storage_service_request,
+            response_sender,
+            ..
+        } = network_request;
+
+        // Record the request and fetch the next fault to inject
+        let fault = {
+            let mut state = self.state.lock();
+            state
+                .received_requests
+                .push(storage_service_request.clone());
+            state.pending_faults.pop_front()
+        };
+
+        // Handle the request
+        let response = match fault {
+            Some(MockServerFault::Error(error)) => Err(error),
+            Some(MockServerFault::DropResponse) => return, // The sender is dropped here
+            Some(MockServerFault::Delay(delay)) => {
+                tokio::time::sleep(delay).await;
+                self.process_request(&storage_service_request)
+            },
+            None => self.process_request(&storage_service_request),
+        };
+        response_sender.send(response);
+    }
+
+    /// Serves all network requests on the given stream until the stream
+    /// terminates. Each request is handled in a separate task, so delayed
+    /// requests do not block the rest of the stream.
+    pub async fn serve<S: Stream<Item = NetworkRequest> + Unpin>(self, mut network_requests: S) {
+        while let Some(network_request) = network_requests.next().await {
+            let mock_server = self.clone();
+            tokio::spawn(async move {
+                mock_server.handle_network_request(network_request).await;
+            });
+        }
+    }
+}
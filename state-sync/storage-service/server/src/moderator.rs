@@ -9,12 +9,14 @@ use aptos_config::{
 use aptos_logger::warn;
 use aptos_network::application::storage::PeersAndMetadata;
 use aptos_storage_service_types::{
-    requests::StorageServiceRequest, responses::StorageServerSummary,
+    requests::{DataRequest, StorageServiceRequest},
+    responses::{CompleteDataRange, DataSummary, StorageServerSummary},
 };
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use arc_swap::ArcSwap;
 use dashmap::DashMap;
 use std::{
+    collections::{HashMap, VecDeque},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -98,13 +100,74 @@ impl UnhealthyPeerState {
     }
 }
 
+/// A simple struct that tracks the bytes served to a peer. The bytes
+/// served within a sliding window are used to enforce bandwidth quotas.
+#[derive(Clone, Debug)]
+pub struct PeerBandwidthState {
+    bytes_served_by_type: HashMap<String, u64>, // The total bytes served (by response type)
+    bytes_served_in_window: u64,                // The bytes served within the sliding window
+    served_responses: VecDeque<(Instant, u64)>, // The responses (time, bytes) within the window
+    time_service: TimeService,                  // The time service
+    window_duration: Duration,                  // The duration of the sliding window
+}
+
+impl PeerBandwidthState {
+    pub fn new(window_duration: Duration, time_service: TimeService) -> Self {
+        Self {
+            bytes_served_by_type: HashMap::new(),
+            bytes_served_in_window: 0,
+            served_responses: VecDeque::new(),
+            time_service,
+            window_duration,
+        }
+    }
+
+    /// Records the number of bytes served to the peer for a response of the given type
+    pub fn record_bytes_served(&mut self, response_type: &str, num_bytes: u64) {
+        *self
+            .bytes_served_by_type
+            .entry(response_type.into())
+            .or_default() += num_bytes;
+        self.bytes_served_in_window += num_bytes;
+        self.served_responses
+            .push_back((self.time_service.now(), num_bytes));
+        self.expire_served_responses();
+    }
+
+    /// Returns the total number of bytes served to the peer (by response type)
+    pub fn get_bytes_served_by_type(&self) -> &HashMap<String, u64> {
+        &self.bytes_served_by_type
+    }
+
+    /// Returns the number of bytes served to the peer within the sliding window
+    pub fn get_bytes_served_in_window(&mut self) -> u64 {
+        self.expire_served_responses();
+        self.bytes_served_in_window
+    }
+
+    /// Removes the responses that have fallen out of the sliding window
+    fn expire_served_responses(&mut self) {
+        let now = self.time_service.now();
+        while let Some((served_time, num_bytes)) = self.served_responses.front() {
+            if now.duration_since(*served_time) < self.window_duration {
+                break; // The remaining responses are still within the window
+            }
+            self.bytes_served_in_window -= num_bytes;
+            self.served_responses.pop_front();
+        }
+    }
+}
+
 /// The request moderator is responsible for validating inbound storage
 /// requests and ensuring that only valid (and satisfiable) requests are processed.
 /// If a peer sends too many invalid requests, the moderator will mark the peer as
-/// "unhealthy" and will ignore requests from that peer for some time.
+/// "unhealthy" and will ignore requests from that peer for some time. Likewise, if
+/// bandwidth quotas are enabled, requests from peers that have been served too many
+/// bytes (within the sliding window) are rejected until the window moves on.
 pub struct RequestModerator {
     aptos_data_client_config: AptosDataClientConfig,
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
+    peer_bandwidth_states: Arc<DashMap<PeerNetworkId, PeerBandwidthState>>,
     peers_and_metadata: Arc<PeersAndMetadata>,
     storage_service_config: StorageServiceConfig,
     time_service: TimeService,
@@ -122,6 +185,7 @@ impl RequestModerator {
         Self {
             aptos_data_client_config,
             cached_storage_server_summary,
+            peer_bandwidth_states: Arc::new(DashMap::new()),
             unhealthy_peer_states: Arc::new(DashMap::new()),
             peers_and_metadata,
             storage_service_config,
@@ -148,6 +212,53 @@ impl RequestModerator {
                 }
             }
 
+            // If the peer has exceeded its bandwidth quota, return an error
+            if self.is_bandwidth_quota_exceeded(peer_network_id) {
+                return Err(Error::TooManyRequests(format!(
+                    "Peer has exceeded its bandwidth quota. Unable to handle request: {:?}",
+                    request
+                )));
+            }
+
+            // If the request is for unverifiable data, verify that the
+            // server has been configured to serve it. Note: this is not
+            // counted as an invalid request, as the peer cannot know our config.
+            let serves_transactions_without_proof = self
+                .storage_service_config
+                .enable_transactions_without_proof;
+            if !serves_transactions_without_proof
+                && matches!(
+                    request.data_request,
+                    DataRequest::GetTransactionsWithoutProof(_)
+                )
+            {
+                return Err(Error::PermissionDenied(format!(
+                    "Transactions without proofs are not served by this node. Request: {:?}",
+                    request
+                )));
+            }
+
+            // Similarly, verify that the server has been configured to serve
+            // version lookups by timestamp (as these search over the blocks),
+            // including those sent as part of a batch.
+            let is_version_by_timestamp = |data_request: &DataRequest| {
+                matches!(data_request, DataRequest::GetVersionByTimestamp(_))
+            };
+            let requests_version_by_timestamp = match &request.data_request {
+                DataRequest::GetBatch(batch_request) => {
+                    batch_request.requests.iter().any(is_version_by_timestamp)
+                },
+                data_request => is_version_by_timestamp(data_request),
+            };
+            let serves_version_by_timestamp =
+                self.storage_service_config.enable_version_by_timestamp;
+            if !serves_version_by_timestamp && requests_version_by_timestamp {
+                return Err(Error::PermissionDenied(format!(
+                    "Version lookups by timestamp are not served by this node. Request: {:?}",
+                    request
+                )));
+            }
+
             // Get the latest storage server summary
             let storage_server_summary = self.cached_storage_server_summary.load();
 
@@ -158,30 +269,18 @@ impl RequestModerator {
                 request,
             ) {
                 // Increment the invalid request count for the peer
-                let mut unhealthy_peer_state = self
-                    .unhealthy_peer_states
-                    .entry(*peer_network_id)
-                    .or_insert_with(|| {
-                        // Create a new unhealthy peer state (this is the first invalid request)
-                        let max_invalid_requests =
-                            self.storage_service_config.max_invalid_requests_per_peer;
-                        let min_time_to_ignore_peers_secs =
-                            self.storage_service_config.min_time_to_ignore_peers_secs;
-                        let time_service = self.time_service.clone();
-
-                        UnhealthyPeerState::new(
-                            max_invalid_requests,
-                            min_time_to_ignore_peers_secs,
-                            time_service,
-                        )
-                    });
-                unhealthy_peer_state.increment_invalid_request_count(peer_network_id);
+                self.record_invalid_request(peer_network_id);
 
                 // Return the validation error
-                return Err(Error::InvalidRequest(format!(
+                let error_message = format!(
                     "The given request cannot be satisfied. Request: {:?}, storage summary: {:?}",
                     request, storage_server_summary
-                )));
+                );
+                return Err(get_unserviceable_request_error(
+                    &request.data_request,
+                    &storage_server_summary.data_summary,
+                    error_message,
+                ));
             }
 
             Ok(()) // The request is valid
@@ -195,7 +294,77 @@ impl RequestModerator {
         )
     }
 
+    /// Increments the invalid request count for the given peer (e.g., because
+    /// the request cannot be serviced, or could not be decoded). Peers that send
+    /// too many invalid requests are ignored for a while.
+    pub fn record_invalid_request(&self, peer_network_id: &PeerNetworkId) {
+        let mut unhealthy_peer_state = self
+            .unhealthy_peer_states
+            .entry(*peer_network_id)
+            .or_insert_with(|| {
+                // Create a new unhealthy peer state (this is the first invalid request)
+                let max_invalid_requests =
+                    self.storage_service_config.max_invalid_requests_per_peer;
+                let min_time_to_ignore_peers_secs =
+                    self.storage_service_config.min_time_to_ignore_peers_secs;
+                let time_service = self.time_service.clone();
+
+                UnhealthyPeerState::new(
+                    max_invalid_requests,
+                    min_time_to_ignore_peers_secs,
+                    time_service,
+                )
+            });
+        unhealthy_peer_state.increment_invalid_request_count(peer_network_id);
+    }
+
+    /// Records the number of bytes served to the given peer for a response of the given type
+    pub fn record_bytes_served(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        response_type: &str,
+        num_bytes: u64,
+    ) {
+        // Update the bytes served metrics
+        metrics::increment_bytes_served(peer_network_id.network_id(), response_type, num_bytes);
+
+        // Update the bandwidth state of the peer
+        self.peer_bandwidth_states
+            .entry(*peer_network_id)
+            .or_insert_with(|| {
+                let window_duration =
+                    Duration::from_secs(self.storage_service_config.peer_bandwidth_window_secs);
+                PeerBandwidthState::new(window_duration, self.time_service.clone())
+            })
+            .record_bytes_served(response_type, num_bytes);
+    }
+
+    /// Returns true iff bandwidth quotas are enforced for the given peer.
+    /// Note: we only enforce quotas for peers on the public network.
+    fn enforces_bandwidth_quota(&self, peer_network_id: &PeerNetworkId) -> bool {
+        self.storage_service_config.enable_peer_bandwidth_quotas
+            && peer_network_id.network_id().is_public_network()
+    }
+
+    /// Returns true iff the given peer has exceeded its bandwidth quota
+    fn is_bandwidth_quota_exceeded(&self, peer_network_id: &PeerNetworkId) -> bool {
+        if !self.enforces_bandwidth_quota(peer_network_id) {
+            return false;
+        }
+
+        let max_bytes_per_window = self
+            .storage_service_config
+            .max_peer_bandwidth_bytes_per_window;
+        match self.peer_bandwidth_states.get_mut(peer_network_id) {
+            Some(mut peer_bandwidth_state) => {
+                peer_bandwidth_state.get_bytes_served_in_window() >= max_bytes_per_window
+            },
+            None => false, // No bytes have been served to the peer
+        }
+    }
+
     /// Refresh the unhealthy peer states and garbage collect disconnected peers
+    /// (this also garbage collects the bandwidth states of disconnected peers).
     pub fn refresh_unhealthy_peer_states(&self) -> Result<(), Error> {
         // Get the currently connected peers
         let connected_peers_and_metadata = self
@@ -234,9 +403,45 @@ impl RequestModerator {
             num_ignored_peers,
         );
 
+        // Remove disconnected peers and count the throttled peers
+        let max_bytes_per_window = self
+            .storage_service_config
+            .max_peer_bandwidth_bytes_per_window;
+        let mut num_throttled_peers = 0;
+        self.peer_bandwidth_states
+            .retain(|peer_network_id, peer_bandwidth_state| {
+                if connected_peers_and_metadata.contains_key(peer_network_id) {
+                    // If the peer has exceeded its quota, increment the throttled peer count
+                    if self.enforces_bandwidth_quota(peer_network_id)
+                        && peer_bandwidth_state.get_bytes_served_in_window() >= max_bytes_per_window
+                    {
+                        num_throttled_peers += 1;
+                    }
+
+                    true // The peer is still connected, so we should keep it
+                } else {
+                    false // The peer is no longer connected, so we should remove it
+                }
+            });
+
+        // Update the number of throttled peers
+        metrics::set_gauge(
+            &metrics::THROTTLED_PEER_COUNT,
+            NetworkId::Public.as_str(),
+            num_throttled_peers,
+        );
+
         Ok(())
     }
 
+    #[cfg(test)]
+    /// Returns a copy of the peer bandwidth states for testing
+    pub(crate) fn get_peer_bandwidth_states(
+        &self,
+    ) -> Arc<DashMap<PeerNetworkId, PeerBandwidthState>> {
+        self.peer_bandwidth_states.clone()
+    }
+
     #[cfg(test)]
     /// Returns a copy of the unhealthy peer states for testing
     pub(crate) fn get_unhealthy_peer_states(
@@ -246,6 +451,88 @@ impl RequestModerator {
     }
 }
 
+/// Returns the error for a request that cannot be serviced by the given data
+/// summary. This allows clients to distinguish between requests for data that
+/// has been pruned, data that may become available, and invalid requests.
+fn get_unserviceable_request_error(
+    data_request: &DataRequest,
+    data_summary: &DataSummary,
+    error_message: String,
+) -> Error {
+    // Identify the requested range and the range held by the server
+    let (requested_range, held_range) = match data_request {
+        DataRequest::GetEpochEndingLedgerInfos(request) => (
+            CompleteDataRange::new(request.start_epoch, request.expected_end_epoch),
+            data_summary.epoch_ending_ledger_infos,
+        ),
+        DataRequest::GetNumberOfStatesAtVersion(version) => (
+            CompleteDataRange::new(*version, *version),
+            data_summary.states,
+        ),
+        DataRequest::GetStateValuesWithProof(request) => {
+            if request.start_index > request.end_index {
+                return Error::InvalidRequest(error_message);
+            }
+            (
+                CompleteDataRange::new(request.version, request.version),
+                data_summary.states,
+            )
+        },
+        DataRequest::GetStateValuesByKeyRangeWithProof(request) => {
+            if request.max_items == 0 {
+                return Error::InvalidRequest(error_message);
+            }
+            (
+                CompleteDataRange::new(request.version, request.version),
+                data_summary.states,
+            )
+        },
+        DataRequest::GetTransactionOutputsWithProof(request) => (
+            CompleteDataRange::new(request.start_version, request.end_version),
+            data_summary.transaction_outputs,
+        ),
+        DataRequest::GetTransactionsWithProof(request) => (
+            CompleteDataRange::new(request.start_version, request.end_version),
+            data_summary.transactions,
+        ),
+        DataRequest::GetTransactionsOrOutputsWithProof(request) => (
+            CompleteDataRange::new(request.start_version, request.end_version),
+            data_summary.transactions,
+        ),
+        DataRequest::GetTransactionsWithoutProof(request) => (
+            CompleteDataRange::new(request.start_version, request.end_version),
+            data_summary.transactions,
+        ),
+        DataRequest::GetTransactionByHashWithProof(request) => (
+            CompleteDataRange::new(request.proof_version, request.proof_version),
+            data_summary.transactions,
+        ),
+        DataRequest::GetVersionByTimestamp(request) => (
+            CompleteDataRange::new(request.ledger_version, request.ledger_version),
+            data_summary.transactions,
+        ),
+        DataRequest::GetBlockMetadata(request) => (
+            CompleteDataRange::new(request.start_version, request.end_version),
+            data_summary.transactions,
+        ),
+        _ => return Error::DataIsUnavailable(error_message), // The server may catch up
+    };
+
+    // If the requested range is invalid, the request can never be serviced
+    let requested_range = match requested_range {
+        Ok(requested_range) => requested_range,
+        Err(_) => return Error::InvalidRequest(error_message),
+    };
+
+    // Otherwise, check if the requested data has already been pruned
+    match held_range {
+        Some(held_range) if requested_range.lowest() < held_range.lowest() => {
+            Error::DataIsPruned(held_range.lowest(), error_message)
+        },
+        _ => Error::DataIsUnavailable(error_message),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -408,4 +695,44 @@ mod tests {
             max_invalid_requests * 5
         );
     }
+
+    #[test]
+    fn test_peer_bandwidth_window() {
+        // Create a new peer bandwidth state
+        let window_duration = Duration::from_secs(60);
+        let time_service = TimeService::mock();
+        let mut peer_bandwidth_state =
+            PeerBandwidthState::new(window_duration, time_service.clone());
+        assert_eq!(peer_bandwidth_state.get_bytes_served_in_window(), 0);
+
+        // Record several responses and verify the bytes served
+        peer_bandwidth_state.record_bytes_served("transactions_with_proof", 100);
+        peer_bandwidth_state.record_bytes_served("state_value_chunk_with_proof", 50);
+        assert_eq!(peer_bandwidth_state.get_bytes_served_in_window(), 150);
+
+        // Elapse half the window and record another response
+        let time_service = time_service.into_mock();
+        time_service.advance(window_duration / 2);
+        peer_bandwidth_state.record_bytes_served("transactions_with_proof", 200);
+        assert_eq!(peer_bandwidth_state.get_bytes_served_in_window(), 350);
+
+        // Elapse the rest of the window and verify the first responses have expired
+        time_service.advance(window_duration / 2);
+        assert_eq!(peer_bandwidth_state.get_bytes_served_in_window(), 200);
+
+        // Elapse the window again and verify all responses have expired
+        time_service.advance(window_duration);
+        assert_eq!(peer_bandwidth_state.get_bytes_served_in_window(), 0);
+
+        // Verify the total bytes served by response type are never expired
+        let bytes_served_by_type = peer_bandwidth_state.get_bytes_served_by_type();
+        assert_eq!(
+            bytes_served_by_type.get("transactions_with_proof"),
+            Some(&300)
+        );
+        assert_eq!(
+            bytes_served_by_type.get("state_value_chunk_with_proof"),
+            Some(&50)
+        );
+    }
 }
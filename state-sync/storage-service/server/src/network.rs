@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_logger::warn;
 use aptos_network::{
     application::interface::NetworkServiceEvents,
     protocols::network::{Event, RpcError},
@@ -10,7 +11,7 @@ use aptos_network::{
 };
 use aptos_storage_service_types::{
     requests::StorageServiceRequest, responses::StorageServiceResponse, Result,
-    StorageServiceMessage,
+    StorageServiceError, StorageServiceMessage,
 };
 use bytes::Bytes;
 use futures::{
@@ -31,10 +32,18 @@ pub struct NetworkRequest {
     pub response_sender: ResponseSender,
 }
 
+/// A network request that could not be decoded (e.g., because the request
+/// envelope has an unknown message type).
+pub struct InvalidNetworkRequest {
+    pub peer_network_id: PeerNetworkId,
+    pub error: StorageServiceError,
+    pub response_sender: ResponseSender,
+}
+
 /// A stream of requests from network. Each request also comes with a callback to
-/// send the response.
+/// send the response. Requests that could not be decoded are returned as errors.
 pub struct StorageServiceNetworkEvents {
-    network_request_stream: BoxStream<'static, NetworkRequest>,
+    network_request_stream: BoxStream<'static, Result<NetworkRequest, InvalidNetworkRequest>>,
 }
 
 impl StorageServiceNetworkEvents {
@@ -63,8 +72,23 @@ impl StorageServiceNetworkEvents {
     fn event_to_request(
         network_id: NetworkId,
         event: Event<StorageServiceMessage>,
-    ) -> Option<NetworkRequest> {
+    ) -> Option<Result<NetworkRequest, InvalidNetworkRequest>> {
         match event {
+            Event::RpcRequest(
+                peer_id,
+                StorageServiceMessage::ChecksummedRequest(storage_service_request),
+                protocol_id,
+                response_tx,
+            ) => {
+                let response_sender = ResponseSender::new(response_tx);
+                let peer_network_id = PeerNetworkId::new(network_id, peer_id);
+                Some(Ok(NetworkRequest {
+                    peer_network_id,
+                    protocol_id,
+                    storage_service_request: storage_service_request.with_checksum(true),
+                    response_sender,
+                }))
+            },
             Event::RpcRequest(
                 peer_id,
                 StorageServiceMessage::Request(storage_service_request),
@@ -73,12 +97,43 @@ impl StorageServiceNetworkEvents {
             ) => {
                 let response_sender = ResponseSender::new(response_tx);
                 let peer_network_id = PeerNetworkId::new(network_id, peer_id);
-                Some(NetworkRequest {
+                Some(Ok(NetworkRequest {
                     peer_network_id,
                     protocol_id,
                     storage_service_request,
                     response_sender,
-                })
+                }))
+            },
+            Event::RpcRequest(
+                peer_id,
+                StorageServiceMessage::RequestEnvelope(envelope),
+                protocol_id,
+                response_tx,
+            ) => {
+                let response_sender = ResponseSender::new(response_tx);
+                let peer_network_id = PeerNetworkId::new(network_id, peer_id);
+
+                // Unwrap the request from the envelope. If this fails (e.g., the
+                // message type is unknown), return the error alongside the peer.
+                match StorageServiceRequest::try_from(envelope) {
+                    Ok(storage_service_request) => Some(Ok(NetworkRequest {
+                        peer_network_id,
+                        protocol_id,
+                        storage_service_request,
+                        response_sender,
+                    })),
+                    Err(error) => {
+                        warn!(
+                            "Failed to unwrap the request envelope from peer {:?}: {:?}",
+                            peer_network_id, error
+                        );
+                        Some(Err(InvalidNetworkRequest {
+                            peer_network_id,
+                            error,
+                            response_sender,
+                        }))
+                    },
+                }
             },
             _ => None, // We don't use direct send and don't care about connection events
         }
@@ -86,7 +141,7 @@ impl StorageServiceNetworkEvents {
 }
 
 impl Stream for StorageServiceNetworkEvents {
-    type Item = NetworkRequest;
+    type Item = Result<NetworkRequest, InvalidNetworkRequest>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         Pin::new(&mut self.network_request_stream).poll_next(cx)
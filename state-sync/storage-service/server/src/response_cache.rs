@@ -0,0 +1,63 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_storage_service_types::{
+    requests::StorageServiceRequest, responses::StorageServiceResponse,
+};
+use arc_swap::ArcSwap;
+use mini_moka::sync::Cache;
+use std::sync::Arc;
+
+/// An LRU cache for storage service responses that can be resized at
+/// runtime. All clones of the cache share the same underlying entries.
+#[derive(Clone)]
+pub struct ResponseCache {
+    inner: Arc<ArcSwap<SizedCache>>,
+}
+
+/// A cache alongside the maximum number of items it can hold
+struct SizedCache {
+    cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    max_capacity: u64,
+}
+
+impl SizedCache {
+    fn new(max_capacity: u64) -> Self {
+        Self {
+            cache: Cache::new(max_capacity),
+            max_capacity,
+        }
+    }
+}
+
+impl ResponseCache {
+    pub fn new(max_capacity: u64) -> Self {
+        Self {
+            inner: Arc::new(ArcSwap::from_pointee(SizedCache::new(max_capacity))),
+        }
+    }
+
+    /// Returns the cached response for the given request (if any)
+    pub fn get(&self, request: &StorageServiceRequest) -> Option<StorageServiceResponse> {
+        self.inner.load().cache.get(request)
+    }
+
+    /// Caches the response for the given request
+    pub fn insert(&self, request: StorageServiceRequest, response: StorageServiceResponse) {
+        self.inner.load().cache.insert(request, response);
+    }
+
+    /// Returns the maximum number of items the cache can hold
+    pub fn max_capacity(&self) -> u64 {
+        self.inner.load().max_capacity
+    }
+
+    /// Updates the maximum number of items the cache can hold. Note: the
+    /// cache capacity cannot be changed in place, so the cache is replaced
+    /// with a new (empty) cache. This is a no-op if the capacity is unchanged.
+    pub fn resize(&self, max_capacity: u64) {
+        if self.max_capacity() != max_capacity {
+            self.inner.store(Arc::new(SizedCache::new(max_capacity)));
+        }
+    }
+}
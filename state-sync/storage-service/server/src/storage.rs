@@ -1,21 +1,42 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::Error, metrics::increment_network_frame_overflow};
-use aptos_config::config::StorageServiceConfig;
-use aptos_logger::debug;
+use crate::{
+    config_handle::StorageServiceConfigHandle,
+    error::Error,
+    logging::{LogEntry, LogSchema},
+    metrics::{increment_network_frame_overflow, increment_proof_self_check_failure},
+};
+use anyhow::{anyhow, ensure};
+use aptos_config::{config::StorageServiceConfig, network_id::PeerNetworkId};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_logger::{debug, error};
 use aptos_storage_interface::{AptosDbError, DbReader, Result as StorageResult};
 use aptos_storage_service_types::responses::{
-    CompleteDataRange, DataResponse, DataSummary, TransactionOrOutputListWithProof,
+    BlockVersionRange, CompleteDataRange, DataResponse, DataSummary, InternalIndexerSummary,
+    PruneWindowForecast, TransactionListWithoutProof, TransactionOrOutputListWithProof,
+    VersionByTimestampWithProof,
 };
 use aptos_types::{
+    account_config::NewBlockEvent,
+    block_info::BlockInfo,
+    contract_event::ContractEvent,
     epoch_change::EpochChangeProof,
-    ledger_info::LedgerInfoWithSignatures,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::SparseMerkleLeafNode,
     state_store::state_value::StateValueChunkWithProof,
-    transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
+    transaction::{
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutputListWithProof,
+        TransactionWithProof, Version, WriteSetListWithProof,
+    },
+    PeerId,
 };
 use serde::Serialize;
-use std::{cmp::min, sync::Arc};
+use std::{
+    cmp::{max, min},
+    collections::HashSet,
+    sync::Arc,
+};
 
 /// The interface into local storage (e.g., the Aptos DB) used by the storage
 /// server to handle client requests and responses.
@@ -60,6 +81,18 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
         end_version: u64,
     ) -> aptos_storage_service_types::Result<TransactionOutputListWithProof, Error>;
 
+    /// Returns a list of write sets with a proof (of the corresponding
+    /// transaction infos) relative to the `proof_version`. The write set
+    /// list is expected to start at `start_version` and end at `end_version`
+    /// (inclusive). In some cases, less write sets may be returned (e.g.,
+    /// due to network or chunk limits).
+    fn get_write_sets_with_proof(
+        &self,
+        proof_version: u64,
+        start_version: u64,
+        end_version: u64,
+    ) -> aptos_storage_service_types::Result<WriteSetListWithProof, Error>;
+
     /// Returns a list of transaction or outputs with a proof relative to the
     /// `proof_version`. The data list is expected to start at `start_version`
     /// and end at `end_version` (inclusive). In some cases, less data may be
@@ -75,6 +108,65 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
         max_num_output_reductions: u64,
     ) -> aptos_storage_service_types::Result<TransactionOrOutputListWithProof, Error>;
 
+    /// Returns a list of transactions without any proofs. The transaction
+    /// list is expected to start at `start_version` and end at `end_version`
+    /// (inclusive). In some cases, less transactions may be returned (e.g.,
+    /// due to network or chunk limits). If `include_events` is true, events
+    /// are also returned.
+    fn get_transactions_without_proof(
+        &self,
+        start_version: u64,
+        end_version: u64,
+        include_events: bool,
+    ) -> aptos_storage_service_types::Result<TransactionListWithoutProof, Error>;
+
+    /// Returns the transaction with the given hash, along with a proof
+    /// relative to `proof_version`. If the transaction is not found (e.g.,
+    /// it doesn't exist or it has been pruned), None is returned. If
+    /// `include_events` is true, events are also returned.
+    fn get_transaction_by_hash_with_proof(
+        &self,
+        transaction_hash: HashValue,
+        proof_version: u64,
+        include_events: bool,
+    ) -> aptos_storage_service_types::Result<Option<TransactionWithProof>, Error>;
+
+    /// Returns the version of the last transaction committed before the given
+    /// timestamp (in microseconds), with a proof relative to `ledger_version`.
+    /// A block committed at or after the timestamp must exist by the ledger
+    /// version, otherwise the data is unavailable.
+    fn get_version_by_timestamp_with_proof(
+        &self,
+        timestamp_usecs: u64,
+        ledger_version: u64,
+    ) -> aptos_storage_service_types::Result<VersionByTimestampWithProof, Error>;
+
+    /// Returns the metadata of the blocks holding the transactions between
+    /// `start_version` and `end_version` (inclusive), ordered by height. In
+    /// some cases, less blocks may be returned (e.g., due to chunk limits).
+    fn get_block_metadata(
+        &self,
+        start_version: u64,
+        end_version: u64,
+    ) -> aptos_storage_service_types::Result<Vec<BlockVersionRange>, Error>;
+
+    /// Returns a forecast of the lowest versions (of the ranges in the given
+    /// data summary) that will still be held once the configured forecast
+    /// duration has elapsed. Returns None if the forecast is disabled or
+    /// can't be estimated.
+    fn get_prune_window_forecast(
+        &self,
+        data_summary: &DataSummary,
+    ) -> aptos_storage_service_types::Result<Option<PruneWindowForecast>, Error>;
+
+    /// Returns a summary of the data indexed by the internal indexer (up to
+    /// the synced version of the given data summary). Returns None if the
+    /// internal indexer is disabled.
+    fn get_internal_indexer_summary(
+        &self,
+        data_summary: &DataSummary,
+    ) -> aptos_storage_service_types::Result<Option<InternalIndexerSummary>, Error>;
+
     /// Returns the number of states in the state tree at the specified version.
     fn get_number_of_states(&self, version: u64)
         -> aptos_storage_service_types::Result<u64, Error>;
@@ -89,22 +181,81 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
         start_index: u64,
         end_index: u64,
     ) -> aptos_storage_service_types::Result<StateValueChunkWithProof, Error>;
+
+    /// Returns a chunk holding a list of state values starting at the
+    /// smallest state key hash that is greater or equal to `start_key_hash`,
+    /// and holding at most `max_items` state values. In some cases, less
+    /// state values may be returned (e.g., due to network or chunk limits).
+    fn get_state_value_chunk_by_key_range_with_proof(
+        &self,
+        version: u64,
+        start_key_hash: HashValue,
+        max_items: u64,
+    ) -> aptos_storage_service_types::Result<StateValueChunkWithProof, Error>;
+
+    /// Returns true iff the given peer is trusted, i.e., it is
+    /// allowed to request chunks larger than the default chunk sizes.
+    fn is_trusted_peer(&self, peer_network_id: &PeerNetworkId) -> bool;
+
+    /// Returns a reader that serves requests with the (larger) chunk
+    /// limits of trusted peers.
+    fn with_trusted_peer_chunk_limits(&self) -> Self;
 }
 
 /// The underlying implementation of the StorageReaderInterface, used by the
 /// storage server.
 #[derive(Clone)]
 pub struct StorageReader {
-    config: StorageServiceConfig,
+    config_handle: StorageServiceConfigHandle,
     storage: Arc<dyn DbReader>,
+    trusted_peers: Arc<HashSet<PeerId>>,
+    use_trusted_peer_chunk_limits: bool,
 }
 
 impl StorageReader {
     pub fn new(config: StorageServiceConfig, storage: Arc<dyn DbReader>) -> Self {
+        Self::new_with_config_handle(StorageServiceConfigHandle::new(config), storage)
+    }
+
+    /// Creates a storage reader that uses the latest config in the given
+    /// handle (e.g., so that chunk sizes can be updated at runtime).
+    pub fn new_with_config_handle(
+        config_handle: StorageServiceConfigHandle,
+        storage: Arc<dyn DbReader>,
+    ) -> Self {
         // Create a timed storage reader
         let storage = Arc::new(TimedStorageReader::new(storage));
 
-        Self { config, storage }
+        Self {
+            config_handle,
+            storage,
+            trusted_peers: Arc::new(HashSet::new()),
+            use_trusted_peer_chunk_limits: false,
+        }
+    }
+
+    /// Allows the given peers to request chunks larger than the default
+    /// chunk sizes (see `trusted_peer_chunk_size_multiplier`).
+    pub fn with_trusted_peers(mut self, trusted_peers: HashSet<PeerId>) -> Self {
+        self.trusted_peers = Arc::new(trusted_peers);
+        self
+    }
+
+    /// Returns the latest config, with the maximum chunk sizes
+    /// raised if the reader serves a trusted peer.
+    fn get_config(&self) -> StorageServiceConfig {
+        let mut config = self.config_handle.get_config();
+        if self.use_trusted_peer_chunk_limits {
+            let multiplier = max(config.trusted_peer_chunk_size_multiplier, 1);
+            config.max_epoch_chunk_size = config.max_epoch_chunk_size.saturating_mul(multiplier);
+            config.max_state_chunk_size = config.max_state_chunk_size.saturating_mul(multiplier);
+            config.max_transaction_chunk_size =
+                config.max_transaction_chunk_size.saturating_mul(multiplier);
+            config.max_transaction_output_chunk_size = config
+                .max_transaction_output_chunk_size
+                .saturating_mul(multiplier);
+        }
+        config
     }
 
     /// Returns the state values range held in the database (lowest to highest).
@@ -184,6 +335,389 @@ impl StorageReader {
             Ok(None)
         }
     }
+
+    /// Reads the transactions (and transaction infos) starting at `start_version`
+    /// directly from storage, without computing any proofs. If `include_events`
+    /// is true, the events of each transaction are also read.
+    fn fetch_transactions_without_proof(
+        &self,
+        start_version: Version,
+        num_transactions: u64,
+        include_events: bool,
+    ) -> aptos_storage_service_types::Result<TransactionListWithoutProof, Error> {
+        let transactions = self
+            .storage
+            .get_transaction_iterator(start_version, num_transactions)
+            .and_then(|iterator| iterator.collect::<StorageResult<Vec<_>>>())
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let transaction_infos = self
+            .storage
+            .get_transaction_info_iterator(start_version, num_transactions)
+            .and_then(|iterator| iterator.collect::<StorageResult<Vec<_>>>())
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let events = if include_events {
+            let events = self
+                .storage
+                .get_events_iterator(start_version, num_transactions)
+                .and_then(|iterator| iterator.collect::<StorageResult<Vec<_>>>())
+                .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            Some(events)
+        } else {
+            None
+        };
+
+        let first_transaction_version = if transactions.is_empty() {
+            None
+        } else {
+            Some(start_version)
+        };
+        Ok(TransactionListWithoutProof::new(
+            first_transaction_version,
+            transactions,
+            transaction_infos,
+            events,
+        ))
+    }
+
+    /// Returns a forecast of the lowest versions that will still be held
+    /// once the configured forecast duration has elapsed. The number of
+    /// versions committed in that time is estimated using the throughput
+    /// over the transactions currently held in storage. Returns None if the
+    /// forecast is disabled or the throughput can't be estimated.
+    fn fetch_prune_window_forecast(
+        &self,
+        latest_ledger_info: &LedgerInfo,
+        transactions_range: &Option<CompleteDataRange<Version>>,
+        transaction_outputs_range: &Option<CompleteDataRange<Version>>,
+        states_range: &Option<CompleteDataRange<Version>>,
+    ) -> aptos_storage_service_types::Result<Option<PruneWindowForecast>, Error> {
+        // Check if the forecast is enabled
+        let duration_secs = self.config_handle.get_config().prune_window_forecast_secs;
+        if duration_secs == 0 {
+            return Ok(None);
+        }
+
+        // Fetch the timestamp of the lowest transaction. Note: the block
+        // metadata for the lowest transaction may already have been pruned,
+        // in which case we simply skip the forecast.
+        let lowest_version = match transactions_range {
+            Some(transactions_range) => transactions_range.lowest(),
+            None => return Ok(None),
+        };
+        let lowest_timestamp_usecs = match self.storage.get_block_timestamp(lowest_version) {
+            Ok(lowest_timestamp_usecs) => lowest_timestamp_usecs,
+            Err(error) => {
+                debug!(
+                    "Unable to fetch the block timestamp for version {:?}: {:?}",
+                    lowest_version, error
+                );
+                return Ok(None);
+            },
+        };
+
+        // Estimate the number of versions committed in the forecast duration
+        let latest_version = latest_ledger_info.version();
+        let latest_timestamp_usecs = latest_ledger_info.timestamp_usecs();
+        if latest_version <= lowest_version || latest_timestamp_usecs <= lowest_timestamp_usecs {
+            return Ok(None); // We can't estimate the throughput
+        }
+        let elapsed_usecs = (latest_timestamp_usecs - lowest_timestamp_usecs) as u128;
+        let duration_usecs = (duration_secs as u128).saturating_mul(1_000_000);
+        let num_forecast_versions = ((latest_version - lowest_version) as u128)
+            .saturating_mul(duration_usecs)
+            .div_ceil(elapsed_usecs);
+        let forecast_latest_version =
+            latest_version.saturating_add(min(num_forecast_versions, Version::MAX as u128) as u64);
+
+        // Fetch the prune windows (if the pruners are enabled)
+        let ledger_pruner_enabled = self
+            .storage
+            .is_ledger_pruner_enabled()
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let ledger_prune_window = if ledger_pruner_enabled {
+            let prune_window = self
+                .storage
+                .get_ledger_prune_window()
+                .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            Some(prune_window)
+        } else {
+            None
+        };
+        let state_pruner_enabled = self
+            .storage
+            .is_state_merkle_pruner_enabled()
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let state_prune_window = if state_pruner_enabled {
+            let prune_window = self
+                .storage
+                .get_epoch_snapshot_prune_window()
+                .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            Some(prune_window)
+        } else {
+            None
+        };
+
+        // Forecast the lowest versions that will still be held
+        let forecast_lowest = |range: &Option<CompleteDataRange<Version>>,
+                               prune_window: Option<usize>| {
+            range.map(|range| {
+                forecast_lowest_version(range.lowest(), forecast_latest_version, prune_window)
+            })
+        };
+        Ok(Some(PruneWindowForecast {
+            duration_secs,
+            lowest_transaction_version: forecast_lowest(transactions_range, ledger_prune_window),
+            lowest_transaction_output_version: forecast_lowest(
+                transaction_outputs_range,
+                ledger_prune_window,
+            ),
+            lowest_state_version: forecast_lowest(states_range, state_prune_window),
+        }))
+    }
+
+    /// Returns a summary of the data indexed by the internal indexer (up to
+    /// the latest version). Returns None if the internal indexer is disabled.
+    fn fetch_internal_indexer_summary(
+        &self,
+        latest_version: Version,
+        transactions_range: &Option<CompleteDataRange<Version>>,
+    ) -> aptos_storage_service_types::Result<Option<InternalIndexerSummary>, Error> {
+        // Fetch the next version to be indexed (if the indexer is enabled)
+        let indexer_next_version = self
+            .storage
+            .get_indexer_next_version()
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let indexer_next_version = match indexer_next_version {
+            Some(indexer_next_version) => indexer_next_version,
+            None => return Ok(None),
+        };
+
+        // Identify the highest indexed version (if anything has been indexed)
+        let highest_indexed_version = match indexer_next_version.checked_sub(1) {
+            Some(highest_indexed_version) => min(highest_indexed_version, latest_version),
+            None => return Ok(Some(InternalIndexerSummary::default())),
+        };
+
+        // Table infos are indexed from genesis, and are never pruned
+        let table_infos = Some(CompleteDataRange::from_genesis(highest_indexed_version));
+
+        // Events are indexed by type from the version the index was introduced
+        // at, but they can only be served while the events are still held.
+        let event_by_type_start_version = self
+            .storage
+            .get_event_by_type_index_start_version()
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let events_by_type = match (event_by_type_start_version, transactions_range) {
+            (Some(event_by_type_start_version), Some(transactions_range)) => {
+                let lowest_version = max(event_by_type_start_version, transactions_range.lowest());
+                CompleteDataRange::new(lowest_version, highest_indexed_version).ok()
+            },
+            _ => None,
+        };
+
+        Ok(Some(InternalIndexerSummary {
+            table_infos,
+            events_by_type,
+        }))
+    }
+
+    /// Fetches a state value chunk (holding at most `expected_num_state_values`)
+    /// at the given version using the given fetch function. If the chunk overflows the network frame
+    /// size, the fetch is retried with half the number of state values. Returns
+    /// None iff the data cannot fit into a single network frame. Only the chunk
+    /// that is returned goes through the proof self-check.
+    fn fetch_state_value_chunk(
+        &self,
+        version: Version,
+        expected_num_state_values: u64,
+        fetch_chunk: impl Fn(usize) -> StorageResult<StateValueChunkWithProof>,
+    ) -> aptos_storage_service_types::Result<Option<StateValueChunkWithProof>, Error> {
+        let config = self.get_config();
+        let max_num_state_values = config.max_state_chunk_size;
+        let mut num_state_values_to_fetch = min(expected_num_state_values, max_num_state_values);
+
+        while num_state_values_to_fetch >= 1 {
+            let state_value_chunk_with_proof = fetch_chunk(num_state_values_to_fetch as usize)
+                .map_err(|error| match error {
+                    // The request is past the last state value, so retrying won't help
+                    AptosDbError::RangeStartsPastEnd(_) => Error::InvalidRequest(error.to_string()),
+                    error => Error::StorageErrorEncountered(error.to_string()),
+                })?;
+            if num_state_values_to_fetch == 1 {
+                // We cannot return less than a single item
+                return self.self_checked_state_value_chunk(version, state_value_chunk_with_proof);
+            }
+
+            // Attempt to divide up the request if it overflows the message size
+            let (overflow_frame, num_bytes) = check_overflow_network_frame(
+                &state_value_chunk_with_proof,
+                config.max_network_chunk_bytes,
+            )?;
+            if !overflow_frame {
+                return self.self_checked_state_value_chunk(version, state_value_chunk_with_proof);
+            } else {
+                increment_network_frame_overflow(
+                    DataResponse::StateValueChunkWithProof(state_value_chunk_with_proof)
+                        .get_label(),
+                );
+                let new_num_state_values_to_fetch = num_state_values_to_fetch / 2;
+                debug!("The request for {:?} state values was too large (num bytes: {:?}). Retrying with {:?}.",
+                    num_state_values_to_fetch, num_bytes, new_num_state_values_to_fetch);
+                num_state_values_to_fetch = new_num_state_values_to_fetch; // Try again with half the amount of data
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Runs the proof self-check on the state value chunk that is about to be
+    /// sent, and returns it if the check passes.
+    fn self_checked_state_value_chunk(
+        &self,
+        version: Version,
+        state_value_chunk_with_proof: StateValueChunkWithProof,
+    ) -> aptos_storage_service_types::Result<Option<StateValueChunkWithProof>, Error> {
+        self.self_check_proof("state_value_chunk_with_proof", || {
+            self.verify_state_value_chunk(version, &state_value_chunk_with_proof)
+        })?;
+        Ok(Some(state_value_chunk_with_proof))
+    }
+
+    /// Verifies the proof of a response that is about to be sent (iff the
+    /// proof self-check is enabled). Failures are logged and counted, and
+    /// an error is returned instead of the response.
+    fn self_check_proof(
+        &self,
+        response_type: &str,
+        verify_proof: impl FnOnce() -> anyhow::Result<()>,
+    ) -> aptos_storage_service_types::Result<(), Error> {
+        if !self.config_handle.get_config().enable_proof_self_check {
+            return Ok(());
+        }
+
+        verify_proof().map_err(|error| {
+            increment_proof_self_check_failure(response_type);
+            let error = Error::StorageErrorEncountered(format!(
+                "The proof failed the self-check! Error: {:?}",
+                error
+            ));
+            error!(LogSchema::new(LogEntry::ProofSelfCheck)
+                .error(&error)
+                .response(response_type));
+            error
+        })
+    }
+
+    /// Returns an (unsigned) ledger info that commits to the transaction
+    /// accumulator at the given version. This is all that is required to
+    /// verify the transaction accumulator range proofs at the version.
+    fn get_ledger_info_at_version(&self, version: Version) -> anyhow::Result<LedgerInfo> {
+        let accumulator_root_hash = self.storage.get_accumulator_root_hash(version)?;
+        let block_info = BlockInfo::new(
+            0,
+            0,
+            HashValue::zero(),
+            accumulator_root_hash,
+            version,
+            0,
+            None,
+        );
+        Ok(LedgerInfo::new(block_info, HashValue::zero()))
+    }
+
+    /// Verifies the state value chunk against the state checkpoint at the
+    /// given version, as proven by the latest ledger info (i.e., the ledger
+    /// info served to peers in the data summary). The left siblings of the
+    /// range proof are only known to a client that holds all previous chunks,
+    /// so they are taken from the membership proof of the last state value.
+    fn verify_state_value_chunk(
+        &self,
+        version: Version,
+        state_value_chunk_with_proof: &StateValueChunkWithProof,
+    ) -> anyhow::Result<()> {
+        // Verify the transaction info at the version against the served ledger info
+        let latest_ledger_info = self.storage.get_latest_ledger_info()?;
+        let latest_ledger_info = latest_ledger_info.ledger_info();
+        let transaction_info_with_proof = self
+            .storage
+            .get_transaction_by_version(version, latest_ledger_info.version(), false)?
+            .proof;
+        transaction_info_with_proof.verify(latest_ledger_info, version)?;
+
+        // Verify the root hash matches the state checkpoint
+        let expected_root_hash = transaction_info_with_proof
+            .transaction_info
+            .state_checkpoint_hash()
+            .ok_or_else(|| anyhow!("Version {} is not a state checkpoint!", version))?;
+        ensure!(
+            state_value_chunk_with_proof.root_hash == expected_root_hash,
+            "The chunk root hash ({}) doesn't match the state checkpoint hash ({})!",
+            state_value_chunk_with_proof.root_hash,
+            expected_root_hash
+        );
+
+        // Verify the indices and keys match the state values
+        let key_hashes: Vec<_> = state_value_chunk_with_proof
+            .raw_values
+            .iter()
+            .map(|(state_key, _)| CryptoHash::hash(state_key))
+            .collect();
+        let num_state_values = state_value_chunk_with_proof
+            .last_index
+            .checked_sub(state_value_chunk_with_proof.first_index)
+            .map(|num_state_values| num_state_values + 1);
+        ensure!(
+            num_state_values == Some(key_hashes.len() as u64),
+            "The chunk indices ({}, {}) don't match the number of state values ({})!",
+            state_value_chunk_with_proof.first_index,
+            state_value_chunk_with_proof.last_index,
+            key_hashes.len()
+        );
+        ensure!(
+            key_hashes.first() == Some(&state_value_chunk_with_proof.first_key)
+                && key_hashes.last() == Some(&state_value_chunk_with_proof.last_key),
+            "The first and last keys of the chunk don't match the state values!"
+        );
+        ensure!(
+            key_hashes.windows(2).all(|keys| keys[0] < keys[1]),
+            "The state values of the chunk are not sorted by key hash!"
+        );
+
+        // Verify the last state value is in the state checkpoint
+        let (last_state_key, last_state_value) = state_value_chunk_with_proof
+            .raw_values
+            .last()
+            .ok_or_else(|| anyhow!("The chunk holds no state values!"))?;
+        let last_key = state_value_chunk_with_proof.last_key;
+        let last_value_hash = CryptoHash::hash(last_state_value);
+        let (_, last_state_value_proof) = self
+            .storage
+            .get_state_value_with_proof_by_version(last_state_key, version)?;
+        last_state_value_proof.verify_by_hash(
+            expected_root_hash,
+            last_key,
+            Some(last_value_hash),
+        )?;
+
+        // Verify the range proof, using the left siblings on the path to the last state value
+        let siblings = last_state_value_proof.siblings();
+        let left_siblings = siblings
+            .iter()
+            .zip(
+                last_key
+                    .iter_bits()
+                    .rev()
+                    .skip(HashValue::LENGTH_IN_BITS - siblings.len()),
+            )
+            .filter(|(_, bit)| *bit)
+            .map(|(sibling, _)| *sibling)
+            .collect();
+        state_value_chunk_with_proof.proof.verify(
+            expected_root_hash,
+            SparseMerkleLeafNode::new(last_key, last_value_hash),
+            left_siblings,
+        )
+    }
 }
 
 impl StorageReaderInterface for StorageReader {
@@ -238,7 +772,8 @@ impl StorageReaderInterface for StorageReader {
     ) -> aptos_storage_service_types::Result<TransactionListWithProof, Error> {
         // Calculate the number of transactions to fetch
         let expected_num_transactions = inclusive_range_len(start_version, end_version)?;
-        let max_num_transactions = self.config.max_transaction_chunk_size;
+        let config = self.get_config();
+        let max_num_transactions = config.max_transaction_chunk_size;
         let mut num_transactions_to_fetch = min(expected_num_transactions, max_num_transactions);
 
         // Attempt to serve the request
@@ -252,6 +787,10 @@ impl StorageReaderInterface for StorageReader {
                     include_events,
                 )
                 .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            self.self_check_proof("transactions_with_proof", || {
+                let ledger_info = self.get_ledger_info_at_version(proof_version)?;
+                transaction_list_with_proof.verify(&ledger_info, Some(start_version))
+            })?;
             if num_transactions_to_fetch == 1 {
                 return Ok(transaction_list_with_proof); // We cannot return less than a single item
             }
@@ -259,7 +798,7 @@ impl StorageReaderInterface for StorageReader {
             // Attempt to divide up the request if it overflows the message size
             let (overflow_frame, num_bytes) = check_overflow_network_frame(
                 &transaction_list_with_proof,
-                self.config.max_network_chunk_bytes,
+                config.max_network_chunk_bytes,
             )?;
             if !overflow_frame {
                 return Ok(transaction_list_with_proof);
@@ -274,7 +813,7 @@ impl StorageReaderInterface for StorageReader {
             }
         }
 
-        Err(Error::UnexpectedErrorEncountered(format!(
+        Err(Error::DataIsTooLarge(format!(
             "Unable to serve the get_transactions_with_proof request! Proof version: {:?}, \
             start version: {:?}, end version: {:?}, include events: {:?}. The data cannot fit into \
             a single network frame!",
@@ -289,7 +828,8 @@ impl StorageReaderInterface for StorageReader {
     ) -> aptos_storage_service_types::Result<EpochChangeProof, Error> {
         // Calculate the number of ledger infos to fetch
         let expected_num_ledger_infos = inclusive_range_len(start_epoch, expected_end_epoch)?;
-        let max_num_ledger_infos = self.config.max_epoch_chunk_size;
+        let config = self.get_config();
+        let max_num_ledger_infos = config.max_epoch_chunk_size;
         let mut num_ledger_infos_to_fetch = min(expected_num_ledger_infos, max_num_ledger_infos);
 
         // Attempt to serve the request
@@ -310,10 +850,8 @@ impl StorageReaderInterface for StorageReader {
             }
 
             // Attempt to divide up the request if it overflows the message size
-            let (overflow_frame, num_bytes) = check_overflow_network_frame(
-                &epoch_change_proof,
-                self.config.max_network_chunk_bytes,
-            )?;
+            let (overflow_frame, num_bytes) =
+                check_overflow_network_frame(&epoch_change_proof, config.max_network_chunk_bytes)?;
             if !overflow_frame {
                 return Ok(epoch_change_proof);
             } else {
@@ -327,7 +865,7 @@ impl StorageReaderInterface for StorageReader {
             }
         }
 
-        Err(Error::UnexpectedErrorEncountered(format!(
+        Err(Error::DataIsTooLarge(format!(
             "Unable to serve the get_epoch_ending_ledger_infos request! Start epoch: {:?}, \
             expected end epoch: {:?}. The data cannot fit into a single network frame!",
             start_epoch, expected_end_epoch
@@ -342,7 +880,8 @@ impl StorageReaderInterface for StorageReader {
     ) -> aptos_storage_service_types::Result<TransactionOutputListWithProof, Error> {
         // Calculate the number of transaction outputs to fetch
         let expected_num_outputs = inclusive_range_len(start_version, end_version)?;
-        let max_num_outputs = self.config.max_transaction_output_chunk_size;
+        let config = self.get_config();
+        let max_num_outputs = config.max_transaction_output_chunk_size;
         let mut num_outputs_to_fetch = min(expected_num_outputs, max_num_outputs);
 
         // Attempt to serve the request
@@ -351,6 +890,10 @@ impl StorageReaderInterface for StorageReader {
                 .storage
                 .get_transaction_outputs(start_version, num_outputs_to_fetch, proof_version)
                 .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            self.self_check_proof("transaction_outputs_with_proof", || {
+                let ledger_info = self.get_ledger_info_at_version(proof_version)?;
+                output_list_with_proof.verify(&ledger_info, Some(start_version))
+            })?;
             if num_outputs_to_fetch == 1 {
                 return Ok(output_list_with_proof); // We cannot return less than a single item
             }
@@ -358,7 +901,7 @@ impl StorageReaderInterface for StorageReader {
             // Attempt to divide up the request if it overflows the message size
             let (overflow_frame, num_bytes) = check_overflow_network_frame(
                 &output_list_with_proof,
-                self.config.max_network_chunk_bytes,
+                config.max_network_chunk_bytes,
             )?;
             if !overflow_frame {
                 return Ok(output_list_with_proof);
@@ -373,13 +916,62 @@ impl StorageReaderInterface for StorageReader {
             }
         }
 
-        Err(Error::UnexpectedErrorEncountered(format!(
+        Err(Error::DataIsTooLarge(format!(
             "Unable to serve the get_transaction_outputs_with_proof request! Proof version: {:?}, \
             start version: {:?}, end version: {:?}. The data cannot fit into a single network frame!",
             proof_version, start_version, end_version
         )))
     }
 
+    fn get_write_sets_with_proof(
+        &self,
+        proof_version: u64,
+        start_version: u64,
+        end_version: u64,
+    ) -> aptos_storage_service_types::Result<WriteSetListWithProof, Error> {
+        // Calculate the number of write sets to fetch (write sets are
+        // bounded by the same chunk size as transaction outputs).
+        let expected_num_write_sets = inclusive_range_len(start_version, end_version)?;
+        let config = self.get_config();
+        let max_num_write_sets = config.max_transaction_output_chunk_size;
+        let mut num_write_sets_to_fetch = min(expected_num_write_sets, max_num_write_sets);
+
+        // Attempt to serve the request
+        while num_write_sets_to_fetch >= 1 {
+            let write_set_list_with_proof = self
+                .storage
+                .get_write_sets_with_proof(start_version, num_write_sets_to_fetch, proof_version)
+                .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            self.self_check_proof("write_sets_with_proof", || {
+                let ledger_info = self.get_ledger_info_at_version(proof_version)?;
+                write_set_list_with_proof.verify(&ledger_info, Some(start_version))
+            })?;
+            if num_write_sets_to_fetch == 1 {
+                return Ok(write_set_list_with_proof); // We cannot return less than a single item
+            }
+
+            // Attempt to divide up the request if it overflows the message size
+            let (overflow_frame, num_bytes) = check_overflow_network_frame(
+                &write_set_list_with_proof,
+                config.max_network_chunk_bytes,
+            )?;
+            if !overflow_frame {
+                return Ok(write_set_list_with_proof);
+            } else {
+                let new_num_write_sets_to_fetch = num_write_sets_to_fetch / 2;
+                debug!("The request for {:?} write sets was too large (num bytes: {:?}). Retrying with {:?}.",
+                    num_write_sets_to_fetch, num_bytes, new_num_write_sets_to_fetch);
+                num_write_sets_to_fetch = new_num_write_sets_to_fetch; // Try again with half the amount of data
+            }
+        }
+
+        Err(Error::DataIsTooLarge(format!(
+            "Unable to serve the get_write_sets_with_proof request! Proof version: {:?}, \
+            start version: {:?}, end version: {:?}. The data cannot fit into a single network frame!",
+            proof_version, start_version, end_version
+        )))
+    }
+
     fn get_transactions_or_outputs_with_proof(
         &self,
         proof_version: u64,
@@ -390,7 +982,8 @@ impl StorageReaderInterface for StorageReader {
     ) -> aptos_storage_service_types::Result<TransactionOrOutputListWithProof, Error> {
         // Calculate the number of transaction outputs to fetch
         let expected_num_outputs = inclusive_range_len(start_version, end_version)?;
-        let max_num_outputs = self.config.max_transaction_output_chunk_size;
+        let config = self.get_config();
+        let max_num_outputs = config.max_transaction_output_chunk_size;
         let mut num_outputs_to_fetch = min(expected_num_outputs, max_num_outputs);
 
         // Attempt to serve the outputs. Halve the data only as many
@@ -402,9 +995,13 @@ impl StorageReaderInterface for StorageReader {
                 .storage
                 .get_transaction_outputs(start_version, num_outputs_to_fetch, proof_version)
                 .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            self.self_check_proof("transactions_or_outputs_with_proof", || {
+                let ledger_info = self.get_ledger_info_at_version(proof_version)?;
+                output_list_with_proof.verify(&ledger_info, Some(start_version))
+            })?;
             let (overflow_frame, num_bytes) = check_overflow_network_frame(
                 &output_list_with_proof,
-                self.config.max_network_chunk_bytes,
+                config.max_network_chunk_bytes,
             )?;
 
             if !overflow_frame {
@@ -437,6 +1034,178 @@ impl StorageReaderInterface for StorageReader {
         Ok((Some(transactions_with_proof), None))
     }
 
+    fn get_transactions_without_proof(
+        &self,
+        start_version: u64,
+        end_version: u64,
+        include_events: bool,
+    ) -> aptos_storage_service_types::Result<TransactionListWithoutProof, Error> {
+        // Calculate the number of transactions to fetch
+        let expected_num_transactions = inclusive_range_len(start_version, end_version)?;
+        let config = self.get_config();
+        let max_num_transactions = config.max_transaction_chunk_size;
+        let mut num_transactions_to_fetch = min(expected_num_transactions, max_num_transactions);
+
+        // Attempt to serve the request
+        while num_transactions_to_fetch >= 1 {
+            let transaction_list = self.fetch_transactions_without_proof(
+                start_version,
+                num_transactions_to_fetch,
+                include_events,
+            )?;
+            if num_transactions_to_fetch == 1 {
+                return Ok(transaction_list); // We cannot return less than a single item
+            }
+
+            // Attempt to divide up the request if it overflows the message size
+            let (overflow_frame, num_bytes) =
+                check_overflow_network_frame(&transaction_list, config.max_network_chunk_bytes)?;
+            if !overflow_frame {
+                return Ok(transaction_list);
+            } else {
+                increment_network_frame_overflow(
+                    DataResponse::TransactionsWithoutProof(transaction_list).get_label(),
+                );
+                let new_num_transactions_to_fetch = num_transactions_to_fetch / 2;
+                debug!("The request for {:?} transactions without proof was too large (num bytes: {:?}). Retrying with {:?}.",
+                    num_transactions_to_fetch, num_bytes, new_num_transactions_to_fetch);
+                num_transactions_to_fetch = new_num_transactions_to_fetch; // Try again with half the amount of data
+            }
+        }
+
+        Err(Error::DataIsTooLarge(format!(
+            "Unable to serve the get_transactions_without_proof request! Start version: {:?}, \
+            end version: {:?}, include events: {:?}. The data cannot fit into a single network \
+            frame!",
+            start_version, end_version, include_events,
+        )))
+    }
+
+    fn get_transaction_by_hash_with_proof(
+        &self,
+        transaction_hash: HashValue,
+        proof_version: u64,
+        include_events: bool,
+    ) -> aptos_storage_service_types::Result<Option<TransactionWithProof>, Error> {
+        self.storage
+            .get_transaction_by_hash(transaction_hash, proof_version, include_events)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))
+    }
+
+    fn get_version_by_timestamp_with_proof(
+        &self,
+        timestamp_usecs: u64,
+        ledger_version: u64,
+    ) -> aptos_storage_service_types::Result<VersionByTimestampWithProof, Error> {
+        // Verify a block was committed at (or after) the timestamp by the ledger version
+        let ledger_timestamp_usecs = self
+            .storage
+            .get_block_timestamp(ledger_version)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        if ledger_timestamp_usecs < timestamp_usecs {
+            return Err(Error::DataIsUnavailable(format!(
+                "The block at ledger version {} is older than the timestamp: {} < {}",
+                ledger_version, ledger_timestamp_usecs, timestamp_usecs
+            )));
+        }
+
+        // Find the version, and the first versions of its block and the next one
+        let version = self
+            .storage
+            .get_last_version_before_timestamp(timestamp_usecs, ledger_version)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let (block_start_version, _, _) = self
+            .storage
+            .get_block_info_by_version(version)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+
+        // Fetch the first transactions of both blocks (with their events and proofs)
+        let block_start = self
+            .storage
+            .get_transaction_by_version(block_start_version, ledger_version, true)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let next_block_start = self
+            .storage
+            .get_transaction_by_version(version + 1, ledger_version, true)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+
+        Ok(VersionByTimestampWithProof {
+            version,
+            block_start,
+            next_block_start,
+        })
+    }
+
+    fn get_block_metadata(
+        &self,
+        start_version: u64,
+        end_version: u64,
+    ) -> aptos_storage_service_types::Result<Vec<BlockVersionRange>, Error> {
+        // Calculate the last version to fetch the blocks of. Every block
+        // holds at least one version, so the transaction chunk size also
+        // bounds the number of blocks.
+        let expected_num_versions = inclusive_range_len(start_version, end_version)?;
+        let config = self.get_config();
+        let num_versions_to_fetch = min(expected_num_versions, config.max_transaction_chunk_size);
+        let end_version = start_version + num_versions_to_fetch - 1;
+
+        // Walk the block index, one block at a time
+        let mut blocks = vec![];
+        let mut version = start_version;
+        while version <= end_version {
+            let (first_version, last_version, new_block_event) = self
+                .storage
+                .get_block_info_by_version(version)
+                .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            if last_version < version {
+                return Err(Error::UnexpectedErrorEncountered(format!(
+                    "The block holding version {} ends at version {}!",
+                    version, last_version
+                )));
+            }
+            blocks.push(BlockVersionRange {
+                block_height: new_block_event.height(),
+                block_timestamp_usecs: new_block_event.proposed_time(),
+                first_version,
+                last_version,
+            });
+
+            match last_version.checked_add(1) {
+                Some(next_version) => version = next_version,
+                None => break,
+            }
+        }
+
+        Ok(blocks)
+    }
+
+    fn get_prune_window_forecast(
+        &self,
+        data_summary: &DataSummary,
+    ) -> aptos_storage_service_types::Result<Option<PruneWindowForecast>, Error> {
+        let synced_ledger_info = match &data_summary.synced_ledger_info {
+            Some(synced_ledger_info) => synced_ledger_info,
+            None => return Ok(None), // We haven't synced any data yet
+        };
+        self.fetch_prune_window_forecast(
+            synced_ledger_info.ledger_info(),
+            &data_summary.transactions,
+            &data_summary.transaction_outputs,
+            &data_summary.states,
+        )
+    }
+
+    fn get_internal_indexer_summary(
+        &self,
+        data_summary: &DataSummary,
+    ) -> aptos_storage_service_types::Result<Option<InternalIndexerSummary>, Error> {
+        let synced_version = match &data_summary.synced_ledger_info {
+            Some(synced_ledger_info) => synced_ledger_info.ledger_info().version(),
+            None => return Ok(None), // We haven't synced any data yet
+        };
+        self.fetch_internal_indexer_summary(synced_version, &data_summary.transactions)
+    }
+
     fn get_number_of_states(
         &self,
         version: u64,
@@ -456,47 +1225,66 @@ impl StorageReaderInterface for StorageReader {
     ) -> aptos_storage_service_types::Result<StateValueChunkWithProof, Error> {
         // Calculate the number of state values to fetch
         let expected_num_state_values = inclusive_range_len(start_index, end_index)?;
-        let max_num_state_values = self.config.max_state_chunk_size;
-        let mut num_state_values_to_fetch = min(expected_num_state_values, max_num_state_values);
 
         // Attempt to serve the request
-        while num_state_values_to_fetch >= 1 {
-            let state_value_chunk_with_proof = self
-                .storage
-                .get_state_value_chunk_with_proof(
+        let state_value_chunk_with_proof =
+            self.fetch_state_value_chunk(version, expected_num_state_values, |num_state_values| {
+                self.storage.get_state_value_chunk_with_proof(
                     version,
                     start_index as usize,
-                    num_state_values_to_fetch as usize,
+                    num_state_values,
                 )
-                .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
-            if num_state_values_to_fetch == 1 {
-                return Ok(state_value_chunk_with_proof); // We cannot return less than a single item
-            }
+            })?;
+        state_value_chunk_with_proof.ok_or_else(|| {
+            Error::DataIsTooLarge(format!(
+                "Unable to serve the get_state_value_chunk_with_proof request! Version: {:?}, \
+                start index: {:?}, end index: {:?}. The data cannot fit into a single network frame!",
+                version, start_index, end_index
+            ))
+        })
+    }
 
-            // Attempt to divide up the request if it overflows the message size
-            let (overflow_frame, num_bytes) = check_overflow_network_frame(
-                &state_value_chunk_with_proof,
-                self.config.max_network_chunk_bytes,
-            )?;
-            if !overflow_frame {
-                return Ok(state_value_chunk_with_proof);
-            } else {
-                increment_network_frame_overflow(
-                    DataResponse::StateValueChunkWithProof(state_value_chunk_with_proof)
-                        .get_label(),
-                );
-                let new_num_state_values_to_fetch = num_state_values_to_fetch / 2;
-                debug!("The request for {:?} state values was too large (num bytes: {:?}). Retrying with {:?}.",
-                    num_state_values_to_fetch, num_bytes, new_num_state_values_to_fetch);
-                num_state_values_to_fetch = new_num_state_values_to_fetch; // Try again with half the amount of data
-            }
+    fn get_state_value_chunk_by_key_range_with_proof(
+        &self,
+        version: u64,
+        start_key_hash: HashValue,
+        max_items: u64,
+    ) -> aptos_storage_service_types::Result<StateValueChunkWithProof, Error> {
+        // Verify the number of state values to fetch
+        if max_items == 0 {
+            return Err(Error::InvalidRequest(
+                "The max number of state values to fetch must be > 0".into(),
+            ));
         }
 
-        Err(Error::UnexpectedErrorEncountered(format!(
-            "Unable to serve the get_state_value_chunk_with_proof request! Version: {:?}, \
-            start index: {:?}, end index: {:?}. The data cannot fit into a single network frame!",
-            version, start_index, end_index
-        )))
+        // Attempt to serve the request
+        let state_value_chunk_with_proof =
+            self.fetch_state_value_chunk(version, max_items, |num_state_values| {
+                self.storage.get_state_value_chunk_by_key_range_with_proof(
+                    version,
+                    start_key_hash,
+                    num_state_values,
+                )
+            })?;
+        state_value_chunk_with_proof.ok_or_else(|| {
+            Error::DataIsTooLarge(format!(
+                "Unable to serve the get_state_value_chunk_by_key_range_with_proof request! \
+                Version: {:?}, start key hash: {:?}, max items: {:?}. The data cannot fit \
+                into a single network frame!",
+                version, start_key_hash, max_items
+            ))
+        })
+    }
+
+    fn is_trusted_peer(&self, peer_network_id: &PeerNetworkId) -> bool {
+        self.trusted_peers.contains(&peer_network_id.peer_id())
+    }
+
+    fn with_trusted_peer_chunk_limits(&self) -> Self {
+        Self {
+            use_trusted_peer_chunk_limits: true,
+            ..self.clone()
+        }
     }
 }
 
@@ -509,17 +1297,21 @@ macro_rules! timed_read {
         $(
             $(#[$($attr)*])*
             fn $name(&self, $($arg: $ty),*) -> $return_type {
-                let read_operation = || {
-                    self.storage.$name($($arg),*).map_err(|e| e.into())
+                // Time the read directly (rather than via execute_and_time_duration),
+                // so that the type of any storage error is preserved for the caller.
+                let start_time = std::time::Instant::now();
+                let result = self.storage.$name($($arg),*);
+                let result_label = if result.is_ok() {
+                    crate::metrics::RESULT_SUCCESS
+                } else {
+                    crate::metrics::RESULT_FAILURE
                 };
-                let result = crate::utils::execute_and_time_duration(
+                crate::metrics::observe_duration(
                     &crate::metrics::STORAGE_DB_READ_LATENCY,
-                    None,
-                    Some(stringify!($name).into()),
-                    read_operation,
-                    None,
+                    vec![stringify!($name).into(), result_label.into()],
+                    start_time,
                 );
-                result.map_err(|e| AptosDbError::Other(e.to_string()))
+                result
             }
         )+
     };
@@ -557,6 +1349,38 @@ impl DbReader for TimedStorageReader {
             fetch_events: bool,
         ) -> StorageResult<TransactionListWithProof>;
 
+        fn get_transaction_by_hash(
+            &self,
+            hash: HashValue,
+            ledger_version: Version,
+            fetch_events: bool,
+        ) -> StorageResult<Option<TransactionWithProof>>;
+
+        fn get_transaction_by_version(
+            &self,
+            version: Version,
+            ledger_version: Version,
+            fetch_events: bool,
+        ) -> StorageResult<TransactionWithProof>;
+
+        fn get_transaction_iterator(
+            &self,
+            start_version: Version,
+            limit: u64,
+        ) -> StorageResult<Box<dyn Iterator<Item = StorageResult<Transaction>> + '_>>;
+
+        fn get_transaction_info_iterator(
+            &self,
+            start_version: Version,
+            limit: u64,
+        ) -> StorageResult<Box<dyn Iterator<Item = StorageResult<TransactionInfo>> + '_>>;
+
+        fn get_events_iterator(
+            &self,
+            start_version: Version,
+            limit: u64,
+        ) -> StorageResult<Box<dyn Iterator<Item = StorageResult<Vec<ContractEvent>>> + '_>>;
+
         fn get_epoch_ending_ledger_infos(
             &self,
             start_epoch: u64,
@@ -570,6 +1394,15 @@ impl DbReader for TimedStorageReader {
             ledger_version: Version,
         ) -> StorageResult<TransactionOutputListWithProof>;
 
+        fn get_write_sets_with_proof(
+            &self,
+            start_version: Version,
+            limit: u64,
+            ledger_version: Version,
+        ) -> StorageResult<WriteSetListWithProof>;
+
+        fn get_accumulator_root_hash(&self, version: Version) -> StorageResult<HashValue>;
+
         fn get_state_leaf_count(&self, version: Version) -> StorageResult<usize>;
 
         fn get_state_value_chunk_with_proof(
@@ -578,6 +1411,34 @@ impl DbReader for TimedStorageReader {
             start_idx: usize,
             chunk_size: usize,
         ) -> StorageResult<StateValueChunkWithProof>;
+
+        fn get_state_value_chunk_by_key_range_with_proof(
+            &self,
+            version: Version,
+            start_key_hash: HashValue,
+            chunk_size: usize,
+        ) -> StorageResult<StateValueChunkWithProof>;
+
+        fn get_last_version_before_timestamp(
+            &self,
+            timestamp: u64,
+            ledger_version: Version,
+        ) -> StorageResult<Version>;
+
+        fn get_block_timestamp(&self, version: Version) -> StorageResult<u64>;
+
+        fn get_block_info_by_version(
+            &self,
+            version: Version,
+        ) -> StorageResult<(Version, Version, NewBlockEvent)>;
+
+        fn is_ledger_pruner_enabled(&self) -> StorageResult<bool>;
+
+        fn get_ledger_prune_window(&self) -> StorageResult<usize>;
+
+        fn get_indexer_next_version(&self) -> StorageResult<Option<Version>>;
+
+        fn get_event_by_type_index_start_version(&self) -> StorageResult<Option<Version>>;
     );
 }
 
@@ -607,3 +1468,21 @@ pub(crate) fn check_overflow_network_frame<T: ?Sized + Serialize>(
     let overflow_frame = num_serialized_bytes >= max_network_frame_bytes;
     Ok((overflow_frame, num_serialized_bytes))
 }
+
+/// Returns the lowest version expected to be held once the highest version
+/// reaches `forecast_latest_version`, given the currently held lowest version
+/// and the prune window (if the pruner is enabled).
+fn forecast_lowest_version(
+    lowest_version: Version,
+    forecast_latest_version: Version,
+    prune_window: Option<usize>,
+) -> Version {
+    match prune_window {
+        Some(prune_window) if forecast_latest_version > prune_window as Version => {
+            // lowest_unpruned_version = forecast_latest_version - prune_window + 1
+            let lowest_unpruned_version = forecast_latest_version - prune_window as Version + 1;
+            max(lowest_version, lowest_unpruned_version)
+        },
+        _ => lowest_version, // No pruning will have occurred
+    }
+}
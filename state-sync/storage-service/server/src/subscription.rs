@@ -3,12 +3,17 @@
 
 use crate::{
     error::Error,
+    handler::Handler,
     metrics,
-    metrics::{increment_counter, SUBSCRIPTION_EXPIRE},
+    metrics::{
+        increment_counter, SUBSCRIPTION_EXPIRE, SUBSCRIPTION_FANOUT_HIT, SUBSCRIPTION_FANOUT_PROBE,
+    },
     moderator::RequestModerator,
     network::ResponseSender,
     optimistic_fetch::OptimisticFetchRequest,
+    response_cache::ResponseCache,
     storage::StorageReaderInterface,
+    summary_freshness::StorageSummaryFreshness,
     utils, LogEntry, LogSchema,
 };
 use aptos_bounded_executor::BoundedExecutor;
@@ -17,30 +22,33 @@ use aptos_config::{
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_infallible::Mutex;
-use aptos_logger::{error, warn};
+use aptos_logger::{error, sample, sample::SampleRate, warn};
 use aptos_storage_service_types::{
     requests::{
         DataRequest, StorageServiceRequest, SubscriptionStreamMetadata,
         TransactionOutputsWithProofRequest, TransactionsOrOutputsWithProofRequest,
         TransactionsWithProofRequest,
     },
-    responses::{DataResponse, StorageServerSummary, StorageServiceResponse},
+    responses::{DataResponse, StorageServerSummary},
+    StorageServiceError,
 };
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
 use arc_swap::ArcSwap;
 use dashmap::DashMap;
 use futures::future::join_all;
-use mini_moka::sync::Cache;
 use std::{
     cmp::min,
     collections::{BTreeMap, HashMap},
     fmt::Debug,
     ops::Deref,
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
+// The frequency (secs) to log that subscriptions are paused due to a stale summary
+const STALE_SUMMARY_LOG_FREQUENCY_SECS: u64 = 5;
+
 /// A single subscription request that is part of a stream
 pub struct SubscriptionRequest {
     request: StorageServiceRequest,  // The original request
@@ -512,12 +520,37 @@ pub(crate) async fn handle_active_subscriptions<T: StorageReaderInterface>(
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     config: StorageServiceConfig,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
+    storage_summary_freshness: StorageSummaryFreshness,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
     time_service: TimeService,
 ) -> Result<(), Error> {
+    // If the cached storage summary is stale (e.g., the DB has stalled), pause
+    // serving the subscriptions (instead of sending stale target ledger infos).
+    // Expired subscriptions are still removed, so that peers can move on.
+    if storage_summary_freshness.is_stale_for_subscriptions(&config) {
+        sample!(
+            SampleRate::Duration(Duration::from_secs(STALE_SUMMARY_LOG_FREQUENCY_SECS)),
+            warn!(LogSchema::new(LogEntry::SubscriptionRefresh).message(&format!(
+                "The cached storage summary is stale (behind by {:?})! Pausing subscriptions.",
+                storage_summary_freshness.get_staleness(&config)
+            )))
+        );
+        let peers_with_expired_subscriptions = subscriptions
+            .iter()
+            .filter(|subscription| {
+                subscription
+                    .value()
+                    .is_expired(config.max_subscription_period_ms)
+            })
+            .map(|subscription| *subscription.key())
+            .collect();
+        remove_expired_subscriptions(subscriptions, peers_with_expired_subscriptions);
+        return Ok(());
+    }
+
     // Continuously handle the subscriptions until we identify that
     // there are no more subscriptions ready to be served now.
     loop {
@@ -562,20 +595,29 @@ pub(crate) async fn handle_active_subscriptions<T: StorageReaderInterface>(
 
 /// Handles the ready subscriptions by removing them from the
 /// active map and notifying the peer of the new data.
+///
+/// Subscriptions that require identical missing data (e.g., peers
+/// with the same known version and target) are grouped together,
+/// so that the data is only fetched once and fanned out to all
+/// subscribers in the group.
 async fn handle_ready_subscriptions<T: StorageReaderInterface>(
     bounded_executor: BoundedExecutor,
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     config: StorageServiceConfig,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
     time_service: TimeService,
     peers_with_ready_subscriptions: Vec<(PeerNetworkId, LedgerInfoWithSignatures)>,
 ) {
-    // Go through all peers with ready subscriptions
-    let mut active_tasks = vec![];
+    // Go through all peers with ready subscriptions and group
+    // the subscriptions by the missing data they require.
+    let mut subscriptions_by_missing_data_request: HashMap<
+        StorageServiceRequest,
+        Vec<(PeerNetworkId, SubscriptionRequest, LedgerInfoWithSignatures)>,
+    > = HashMap::new();
     for (peer_network_id, target_ledger_info) in peers_with_ready_subscriptions {
         // Remove the subscription from the active subscription stream
         let subscription_request_and_known_version =
@@ -588,47 +630,116 @@ async fn handle_ready_subscriptions<T: StorageReaderInterface>(
                     )
                 });
 
-        // Handle the subscription
+        // Get the storage service request for the missing data
         if let Some((Some(subscription_request), known_version)) =
             subscription_request_and_known_version
         {
-            // Clone all required components for the task
-            let cached_storage_server_summary = cached_storage_server_summary.clone();
-            let optimistic_fetches = optimistic_fetches.clone();
-            let lru_response_cache = lru_response_cache.clone();
-            let request_moderator = request_moderator.clone();
-            let storage = storage.clone();
-            let subscriptions = subscriptions.clone();
-            let time_service = time_service.clone();
-
-            // Spawn a blocking task to handle the subscription
-            let active_task = bounded_executor
-                .spawn_blocking(move || {
+            match subscription_request.get_storage_request_for_missing_data(
+                config,
+                known_version,
+                &target_ledger_info,
+            ) {
+                Ok(missing_data_request) => {
+                    subscriptions_by_missing_data_request
+                        .entry(missing_data_request)
+                        .or_default()
+                        .push((peer_network_id, subscription_request, target_ledger_info));
+                },
+                Err(error) => {
+                    warn!(LogSchema::new(LogEntry::SubscriptionResponse)
+                        .error(&Error::UnexpectedErrorEncountered(error.to_string())));
+                },
+            }
+        }
+    }
+
+    // Handle each group of subscriptions
+    let mut active_tasks = vec![];
+    for (missing_data_request, ready_subscriptions) in subscriptions_by_missing_data_request {
+        // Update the fan-out metrics. Every subscription (other than
+        // the first) in the group is served without another fetch.
+        for (index, (peer_network_id, _, _)) in ready_subscriptions.iter().enumerate() {
+            increment_counter(
+                &metrics::SUBSCRIPTION_EVENTS,
+                peer_network_id.network_id(),
+                SUBSCRIPTION_FANOUT_PROBE.into(),
+            );
+            if index > 0 {
+                increment_counter(
+                    &metrics::SUBSCRIPTION_EVENTS,
+                    peer_network_id.network_id(),
+                    SUBSCRIPTION_FANOUT_HIT.into(),
+                );
+            }
+        }
+
+        // Create a handler for the task
+        let handler = Handler::new(
+            cached_storage_server_summary.clone(),
+            optimistic_fetches.clone(),
+            lru_response_cache.clone(),
+            request_moderator.clone(),
+            storage.clone(),
+            subscriptions.clone(),
+            time_service.clone(),
+        );
+        let request_moderator = request_moderator.clone();
+        let subscriptions = subscriptions.clone();
+
+        // Spawn a blocking task to handle the subscriptions
+        let active_task = bounded_executor
+            .spawn_blocking(move || {
+                // Validate the missing data request for every peer in the group. Each
+                // recipient is charged by the moderator as if it had sent the request
+                // itself, so rejected peers are notified of the error and skipped.
+                let mut served_subscriptions = vec![];
+                for (peer_network_id, subscription_request, target_ledger_info) in
+                    ready_subscriptions
+                {
+                    match request_moderator
+                        .validate_request(&peer_network_id, &missing_data_request)
+                    {
+                        Ok(()) => served_subscriptions.push((
+                            peer_network_id,
+                            subscription_request,
+                            target_ledger_info,
+                        )),
+                        Err(error) => handler.send_response(
+                            config,
+                            &peer_network_id,
+                            subscription_request.request.clone(),
+                            Err(StorageServiceError::from(error)),
+                            subscription_request.take_response_sender(),
+                        ),
+                    }
+                }
+                let Some((first_peer_network_id, _, _)) = served_subscriptions.first() else {
+                    return; // No peers remain to be served
+                };
+
+                // Fetch the missing data once for all remaining subscriptions in the group
+                let storage_response = handler.process_request(
+                    first_peer_network_id,
+                    missing_data_request.clone(),
+                    true,
+                );
+
+                // Notify each peer of the new data
+                for (peer_network_id, subscription_request, target_ledger_info) in
+                    served_subscriptions
+                {
                     // Get the subscription start time and request
                     let subscription_start_time = subscription_request.request_start_time;
                     let subscription_data_request = subscription_request.request.clone();
 
                     // Handle the subscription request and time the operation
                     let handle_request = || {
-                        // Get the storage service request for the missing data
-                        let missing_data_request = subscription_request
-                            .get_storage_request_for_missing_data(
-                                config,
-                                known_version,
-                                &target_ledger_info,
-                            )?;
-
                         // Notify the peer of the new data
-                        let data_response = utils::notify_peer_of_new_data(
-                            cached_storage_server_summary,
-                            optimistic_fetches,
-                            subscriptions.clone(),
-                            lru_response_cache,
-                            request_moderator,
-                            storage,
-                            time_service.clone(),
-                            &peer_network_id,
-                            missing_data_request,
+                        let data_response = utils::send_new_data_to_peer(
+                            config,
+                            &handler,
+                            missing_data_request.clone(),
+                            &storage_response,
                             target_ledger_info,
                             subscription_request.take_response_sender(),
                         )?;
@@ -656,12 +767,12 @@ async fn handle_ready_subscriptions<T: StorageReaderInterface>(
                         warn!(LogSchema::new(LogEntry::SubscriptionResponse)
                             .error(&Error::UnexpectedErrorEncountered(error.to_string())));
                     }
-                })
-                .await;
+                }
+            })
+            .await;
 
-            // Add the task to the list of active tasks
-            active_tasks.push(active_task);
-        }
+        // Add the task to the list of active tasks
+        active_tasks.push(active_task);
     }
 
     // Wait for all the active tasks to complete
@@ -676,7 +787,7 @@ pub(crate) async fn get_peers_with_ready_subscriptions<T: StorageReaderInterface
     config: StorageServiceConfig,
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
@@ -730,7 +841,7 @@ async fn identify_expired_invalid_and_ready_subscriptions<T: StorageReaderInterf
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
     time_service: TimeService,
@@ -803,7 +914,7 @@ async fn identify_ready_and_invalid_subscriptions<T: StorageReaderInterface>(
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
     time_service: TimeService,
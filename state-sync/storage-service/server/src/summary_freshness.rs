@@ -0,0 +1,60 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::{STALE_SUMMARY_SUBSCRIPTION_PAUSES, STORAGE_SUMMARY_STALENESS_MS};
+use aptos_config::config::StorageServiceConfig;
+use aptos_infallible::Mutex;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Tracks when the cached storage server summary was last refreshed. If the
+/// refresher falls behind (e.g., because the DB has stalled), the cached summary
+/// may be stale, and subscriptions should not be served with it.
+#[derive(Clone)]
+pub struct StorageSummaryFreshness {
+    last_refresh_time: Arc<Mutex<Instant>>,
+    time_service: TimeService,
+}
+
+impl StorageSummaryFreshness {
+    pub fn new(time_service: TimeService) -> Self {
+        let last_refresh_time = Arc::new(Mutex::new(time_service.now()));
+        Self {
+            last_refresh_time,
+            time_service,
+        }
+    }
+
+    /// Marks the cached storage summary as refreshed (now)
+    pub fn mark_refreshed(&self) {
+        *self.last_refresh_time.lock() = self.time_service.now();
+    }
+
+    /// Returns how far the refresher has fallen behind the configured
+    /// refresh interval (i.e., zero if the summary is fresh).
+    pub fn get_staleness(&self, config: &StorageServiceConfig) -> Duration {
+        let last_refresh_time = *self.last_refresh_time.lock();
+        let time_since_refresh = self
+            .time_service
+            .now()
+            .saturating_duration_since(last_refresh_time);
+        let refresh_interval = Duration::from_millis(config.storage_summary_refresh_interval_ms);
+        time_since_refresh.saturating_sub(refresh_interval)
+    }
+
+    /// Returns true iff the cached storage summary is too stale to serve
+    /// subscriptions. Also updates the staleness metrics.
+    pub fn is_stale_for_subscriptions(&self, config: &StorageServiceConfig) -> bool {
+        let staleness = self.get_staleness(config);
+        STORAGE_SUMMARY_STALENESS_MS.set(staleness.as_millis() as i64);
+
+        let is_stale = staleness > Duration::from_millis(config.max_storage_summary_staleness_ms);
+        if is_stale {
+            STALE_SUMMARY_SUBSCRIPTION_PAUSES.inc();
+        }
+        is_stale
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    analytics::{ChunkSizeStats, PeerActivity, StorageServiceAnalytics},
+    tests::{mock, mock::MockClient, utils},
+};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_storage_service_types::requests::{
+    DataRequest, StorageServiceRequest, TransactionsWithProofRequest,
+};
+use aptos_time_service::TimeService;
+use aptos_types::PeerId;
+use mockall::predicate::eq;
+use std::time::Duration;
+
+#[test]
+fn test_analytics_rolling_window() {
+    // Create the analytics (with a 5 minute window)
+    let time_service = TimeService::mock();
+    let mock_time_service = time_service.clone().into_mock();
+    let analytics = StorageServiceAnalytics::new(300, time_service);
+
+    // Record several requests from two peers
+    let peer_1 = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    let peer_2 = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    analytics.record_request(peer_1, &create_transactions_request(0, 99));
+    analytics.record_request(peer_1, &create_transactions_request(100, 149));
+    analytics.record_cache_probe(false);
+    analytics.record_cache_probe(true);
+    analytics.record_error(peer_1);
+
+    // Record more requests a few minutes later
+    mock_time_service.advance(Duration::from_secs(180));
+    analytics.record_request(peer_2, &create_transactions_request(0, 9));
+    analytics.record_request(peer_2, &create_transactions_request(10, 19));
+    analytics.record_request(peer_2, &create_summary_request());
+    analytics.record_cache_probe(true);
+
+    // Verify the snapshot covers all requests
+    let snapshot = analytics.get_snapshot();
+    assert_eq!(snapshot.window_secs, 300);
+    assert_eq!(snapshot.num_requests, 5);
+    assert_eq!(
+        snapshot.request_counts.get("get_transactions_with_proof"),
+        Some(&4)
+    );
+    assert_eq!(
+        snapshot.request_counts.get("get_storage_server_summary"),
+        Some(&1)
+    );
+    assert_eq!(
+        snapshot.chunk_sizes.get("get_transactions_with_proof"),
+        Some(&ChunkSizeStats {
+            num_requests: 4,
+            min_chunk_size: 10,
+            max_chunk_size: 100,
+            total_chunk_size: 170,
+        })
+    );
+    assert_eq!(snapshot.cache_hit_ratio(), Some(2.0 / 3.0));
+    assert_eq!(snapshot.peer_activity, vec![
+        (peer_2, PeerActivity {
+            num_requests: 3,
+            num_errors: 0,
+            num_requested_items: 20,
+        }),
+        (peer_1, PeerActivity {
+            num_requests: 2,
+            num_errors: 1,
+            num_requested_items: 150,
+        }),
+    ]);
+
+    // Elapse enough time for the first requests to fall out of the window
+    mock_time_service.advance(Duration::from_secs(180));
+
+    // Verify only the most recent requests remain
+    let snapshot = analytics.get_snapshot();
+    assert_eq!(snapshot.num_requests, 3);
+    assert_eq!(snapshot.cache_hit_ratio(), Some(1.0));
+    assert_eq!(snapshot.peer_activity.len(), 1);
+    assert_eq!(snapshot.peer_activity[0].0, peer_2);
+
+    // Elapse enough time for all requests to fall out of the window
+    mock_time_service.advance(Duration::from_secs(300));
+
+    // Verify the snapshot is empty
+    let snapshot = analytics.get_snapshot();
+    assert_eq!(snapshot.num_requests, 0);
+    assert_eq!(snapshot.cache_hit_ratio(), None);
+    assert!(snapshot.peer_activity.is_empty());
+}
+
+#[test]
+fn test_analytics_disabled() {
+    // Create the analytics (with analytics disabled)
+    let analytics = StorageServiceAnalytics::new(0, TimeService::mock());
+    assert!(!analytics.is_enabled());
+
+    // Record several requests
+    let peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    analytics.record_request(peer, &create_transactions_request(0, 99));
+    analytics.record_cache_probe(true);
+    analytics.record_error(peer);
+
+    // Verify nothing was recorded
+    let snapshot = analytics.get_snapshot();
+    assert_eq!(snapshot.num_requests, 0);
+    assert_eq!(snapshot.num_cache_hits, 0);
+    assert!(snapshot.peer_activity.is_empty());
+}
+
+#[tokio::test]
+async fn test_analytics_served_requests() {
+    // Create test data
+    let start_version = 0;
+    let end_version = 99;
+    let proof_version = end_version;
+    let include_events = false;
+
+    // Create the mock db reader (the data should be fetched from storage once)
+    let mut db_reader = mock::create_mock_db_reader();
+    let transaction_list_with_proof = utils::create_transaction_list_with_proof(
+        start_version,
+        end_version,
+        proof_version,
+        include_events,
+    );
+    db_reader
+        .expect_get_transactions()
+        .times(1)
+        .with(
+            eq(start_version),
+            eq(end_version - start_version + 1),
+            eq(proof_version),
+            eq(include_events),
+        )
+        .return_once(move |_, _, _, _| Ok(transaction_list_with_proof));
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, end_version, 10);
+    let analytics = service.get_analytics();
+    tokio::spawn(service.start());
+
+    // Fetch the same data several times
+    let num_requests = 3;
+    for _ in 0..num_requests {
+        utils::get_transactions_with_proof(
+            &mut mock_client,
+            start_version,
+            end_version,
+            proof_version,
+            include_events,
+            true,
+        )
+        .await
+        .unwrap();
+    }
+
+    // Verify the analytics recorded the requests and cache probes
+    let snapshot = analytics.get_snapshot();
+    assert_eq!(snapshot.num_requests, num_requests);
+    assert_eq!(
+        snapshot.request_counts.get("get_transactions_with_proof"),
+        Some(&num_requests)
+    );
+    assert_eq!(snapshot.num_cache_hits, num_requests - 1);
+    assert_eq!(snapshot.num_cache_misses, 1);
+    let chunk_sizes = snapshot
+        .chunk_sizes
+        .get("get_transactions_with_proof")
+        .unwrap();
+    assert_eq!(chunk_sizes.average_chunk_size(), Some(100.0));
+}
+
+/// Creates a request for transactions between the given versions (inclusive)
+fn create_transactions_request(start_version: u64, end_version: u64) -> StorageServiceRequest {
+    let data_request = DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+        proof_version: end_version,
+        start_version,
+        end_version,
+        include_events: false,
+    });
+    StorageServiceRequest::new(data_request, false)
+}
+
+/// Creates a request for the storage server summary
+fn create_summary_request() -> StorageServiceRequest {
+    StorageServiceRequest::new(DataRequest::GetStorageServerSummary, false)
+}
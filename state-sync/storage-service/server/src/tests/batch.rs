@@ -0,0 +1,198 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{
+    mock,
+    mock::{MockClient, MockDatabaseReader},
+    utils,
+};
+use aptos_config::config::StorageServiceConfig;
+use aptos_storage_service_types::{
+    requests::{
+        BatchRequest, DataRequest, EpochEndingLedgerInfoRequest, NewTransactionsWithProofRequest,
+    },
+    responses::{DataResponse, StorageServiceResponse},
+    StorageServiceError, StorageServiceErrorCategory,
+};
+use aptos_types::epoch_change::EpochChangeProof;
+use mockall::predicate::eq;
+
+#[tokio::test]
+async fn test_get_batch() {
+    for use_compression in [true, false] {
+        // Create test data
+        let version = 101;
+        let number_of_states: u64 = 560;
+        let start_epoch = 5;
+        let expected_end_epoch = 9;
+        let epoch_change_proof = EpochChangeProof {
+            ledger_info_with_sigs: (start_epoch..=expected_end_epoch)
+                .map(|epoch| utils::create_epoch_ending_ledger_info(epoch, epoch * 10))
+                .collect(),
+            more: false,
+        };
+
+        // Create the mock db reader
+        let mut db_reader = mock::create_mock_db_reader();
+        expect_get_state_leaf_count(&mut db_reader, version, number_of_states);
+        utils::expect_get_epoch_ending_ledger_infos(
+            &mut db_reader,
+            start_epoch,
+            expected_end_epoch + 1,
+            epoch_change_proof.clone(),
+        );
+
+        // Create the storage client and server
+        let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+        utils::update_storage_server_summary(&mut service, version, 10);
+        tokio::spawn(service.start());
+
+        // Process a batch request to fetch the number of states and epoch ending ledger infos
+        let data_requests = vec![
+            DataRequest::GetNumberOfStatesAtVersion(version),
+            DataRequest::GetEpochEndingLedgerInfos(EpochEndingLedgerInfoRequest {
+                start_epoch,
+                expected_end_epoch,
+            }),
+        ];
+        let response = get_batch(&mut mock_client, data_requests, use_compression)
+            .await
+            .unwrap();
+
+        // Verify the responses are correct (and in order)
+        assert_eq!(response.is_compressed(), use_compression);
+        assert_eq!(
+            response.get_data_response().unwrap(),
+            DataResponse::Batch(vec![
+                DataResponse::NumberOfStatesAtVersion(number_of_states),
+                DataResponse::EpochEndingLedgerInfos(epoch_change_proof),
+            ])
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_batch_invalid() {
+    // Create the storage client and server
+    let version = 101;
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Create the invalid batches
+    let max_batch_request_size = StorageServiceConfig::default().max_batch_request_size;
+    let oversized_batch =
+        vec![DataRequest::GetNumberOfStatesAtVersion(version); max_batch_request_size as usize + 1];
+    let nested_batch = vec![DataRequest::GetBatch(BatchRequest {
+        requests: vec![DataRequest::GetNumberOfStatesAtVersion(version)],
+    })];
+    let optimistic_fetch_batch = vec![DataRequest::GetNewTransactionsWithProof(
+        NewTransactionsWithProofRequest {
+            known_version: version,
+            known_epoch: 10,
+            include_events: false,
+        },
+    )];
+
+    // Verify that all invalid batches are rejected
+    for data_requests in [
+        vec![],
+        oversized_batch,
+        nested_batch,
+        optimistic_fetch_batch,
+    ] {
+        let response = get_batch(&mut mock_client, data_requests, false)
+            .await
+            .unwrap_err();
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
+    }
+}
+
+#[tokio::test]
+async fn test_get_batch_network_limit() {
+    for num_fitting_responses in [1, 2] {
+        // Create test data
+        let versions = [101, 102, 103];
+        let number_of_states: u64 = 560;
+
+        // Create the mock db reader (no request should be processed beyond the limit)
+        let mut db_reader = mock::create_mock_db_reader();
+        for version in &versions[..num_fitting_responses] {
+            expect_get_state_leaf_count(&mut db_reader, *version, number_of_states);
+        }
+
+        // Create a storage config where the network limit only fits the expected responses
+        let data_response = DataResponse::NumberOfStatesAtVersion(number_of_states);
+        let data_responses = vec![data_response; num_fitting_responses];
+        let batch_response = DataResponse::Batch(data_responses.clone());
+        let max_network_chunk_bytes = bcs::serialized_size(&batch_response).unwrap() as u64 + 1;
+        let storage_config = StorageServiceConfig {
+            max_network_chunk_bytes,
+            ..Default::default()
+        };
+
+        // Create the storage client and server
+        let (mut mock_client, mut service, _, _, _) =
+            MockClient::new(Some(db_reader), Some(storage_config));
+        utils::update_storage_server_summary(&mut service, 1000, 10);
+        tokio::spawn(service.start());
+
+        // Process a batch request to fetch the number of states at each version
+        let data_requests = versions
+            .iter()
+            .map(|version| DataRequest::GetNumberOfStatesAtVersion(*version))
+            .collect();
+        let response = get_batch(&mut mock_client, data_requests, false)
+            .await
+            .unwrap();
+
+        // Verify that only the fitting responses were returned
+        assert_eq!(response.get_data_response().unwrap(), batch_response);
+    }
+}
+
+#[tokio::test]
+async fn test_get_batch_not_serviceable() {
+    // Create the storage client and server (that cannot service the request)
+    let version = 101;
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, version - 1, 10);
+    tokio::spawn(service.start());
+
+    // Process a batch request where the last request cannot be serviced
+    let data_requests = vec![
+        DataRequest::GetStorageServerSummary,
+        DataRequest::GetNumberOfStatesAtVersion(version),
+    ];
+    let response = get_batch(&mut mock_client, data_requests, false)
+        .await
+        .unwrap_err();
+
+    // Verify the request is not serviceable
+    assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
+}
+
+/// Sets an expectation on the given mock db for a call to fetch the number of states
+fn expect_get_state_leaf_count(
+    mock_db: &mut MockDatabaseReader,
+    version: u64,
+    number_of_states: u64,
+) {
+    mock_db
+        .expect_get_state_leaf_count()
+        .times(1)
+        .with(eq(version))
+        .returning(move |_| Ok(number_of_states as usize));
+}
+
+/// Sends a batch request and processes the response
+async fn get_batch(
+    mock_client: &mut MockClient,
+    data_requests: Vec<DataRequest>,
+    use_compression: bool,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let data_request = DataRequest::GetBatch(BatchRequest {
+        requests: data_requests,
+    });
+    utils::send_storage_request(mock_client, use_compression, data_request).await
+}
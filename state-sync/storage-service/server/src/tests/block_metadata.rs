@@ -0,0 +1,151 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{mock, mock::MockClient, utils};
+use aptos_config::config::StorageServiceConfig;
+use aptos_storage_service_types::{
+    responses::{BlockVersionRange, DataResponse},
+    StorageServiceErrorCategory,
+};
+use aptos_types::{
+    account_address::AccountAddress, account_config::NewBlockEvent, transaction::Version,
+};
+
+#[tokio::test]
+async fn test_get_block_metadata() {
+    // Create the mock db reader (every block holds 10 versions)
+    let num_versions_per_block = 10;
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_block_info_by_version()
+        .times(3)
+        .returning(move |version| Ok(create_block_info(version, num_versions_per_block)));
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, 1000, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the blocks holding versions 15 to 34
+    let response = utils::get_block_metadata(&mut mock_client, 15, 34, true)
+        .await
+        .unwrap();
+
+    // Verify the response is correct
+    let expected_blocks = (1..=3)
+        .map(|block_height| create_block_version_range(block_height, num_versions_per_block))
+        .collect();
+    verify_block_metadata_response(response.get_data_response().unwrap(), expected_blocks);
+}
+
+#[tokio::test]
+async fn test_get_block_metadata_chunk_limit() {
+    // Create the mock db reader (every block holds 4 versions)
+    let num_versions_per_block = 4;
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_block_info_by_version()
+        .times(3)
+        .returning(move |version| Ok(create_block_info(version, num_versions_per_block)));
+
+    // Create the storage client and server (with a small transaction chunk size)
+    let storage_config = StorageServiceConfig {
+        max_transaction_chunk_size: 10,
+        ..Default::default()
+    };
+    let (mut mock_client, mut service, _, _, _) =
+        MockClient::new(Some(db_reader), Some(storage_config));
+    utils::update_storage_server_summary(&mut service, 1000, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the blocks holding versions 0 to 99
+    let response = utils::get_block_metadata(&mut mock_client, 0, 99, false)
+        .await
+        .unwrap();
+
+    // Verify only the blocks holding the first 10 versions are returned
+    let expected_blocks = (0..=2)
+        .map(|block_height| create_block_version_range(block_height, num_versions_per_block))
+        .collect();
+    verify_block_metadata_response(response.get_data_response().unwrap(), expected_blocks);
+}
+
+#[tokio::test]
+async fn test_get_block_metadata_invalid() {
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, 1000, 10);
+    tokio::spawn(service.start());
+
+    // Test invalid ranges
+    let start_version = 100;
+    for end_version in [0, 99] {
+        let response =
+            utils::get_block_metadata(&mut mock_client, start_version, end_version, true)
+                .await
+                .unwrap_err();
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
+    }
+}
+
+#[tokio::test]
+async fn test_get_block_metadata_not_serviceable() {
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, 100, 10);
+    tokio::spawn(service.start());
+
+    // Verify ranges beyond the synced ledger version are not serviceable
+    for (start_version, end_version) in [(50, 150), (101, 200)] {
+        let response =
+            utils::get_block_metadata(&mut mock_client, start_version, end_version, true)
+                .await
+                .unwrap_err();
+        assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
+    }
+}
+
+/// Creates the block info (as returned by the db reader) of the block
+/// holding the given version, where every block holds the same number
+/// of versions.
+fn create_block_info(
+    version: Version,
+    num_versions_per_block: u64,
+) -> (Version, Version, NewBlockEvent) {
+    let block_height = version / num_versions_per_block;
+    let block = create_block_version_range(block_height, num_versions_per_block);
+    let new_block_event = NewBlockEvent::new(
+        AccountAddress::ONE,
+        0,
+        block_height,
+        block_height,
+        vec![],
+        AccountAddress::ONE,
+        vec![],
+        block.block_timestamp_usecs,
+    );
+    (block.first_version, block.last_version, new_block_event)
+}
+
+/// Creates the block metadata of the block at the given height, where
+/// every block holds the same number of versions.
+fn create_block_version_range(block_height: u64, num_versions_per_block: u64) -> BlockVersionRange {
+    let first_version = block_height * num_versions_per_block;
+    BlockVersionRange {
+        block_height,
+        block_timestamp_usecs: block_height * 1_000,
+        first_version,
+        last_version: first_version + num_versions_per_block - 1,
+    }
+}
+
+/// Verifies that the response holds the expected block metadata
+fn verify_block_metadata_response(
+    data_response: DataResponse,
+    expected_blocks: Vec<BlockVersionRange>,
+) {
+    match data_response {
+        DataResponse::BlockMetadata(blocks) => assert_eq!(blocks, expected_blocks),
+        _ => panic!("Expected block metadata but got: {:?}", data_response),
+    }
+}
@@ -12,6 +12,7 @@ use mockall::{
     predicate::{always, eq},
     Sequence,
 };
+use std::time::Duration;
 
 #[tokio::test]
 async fn test_cachable_requests_compression() {
@@ -221,3 +222,80 @@ async fn test_cachable_requests_eviction() {
         utils::get_state_values_with_proof(&mut mock_client, version, start_index, end_index, true)
             .await;
 }
+
+#[tokio::test]
+async fn test_cachable_requests_resize() {
+    // Create test data
+    let start_version = 0;
+    let end_version = 454;
+    let proof_version = end_version;
+    let include_events = false;
+    let transaction_list_with_proof = utils::create_transaction_list_with_proof(
+        start_version,
+        end_version,
+        proof_version,
+        include_events,
+    );
+
+    // Expect the data to be fetched from storage twice (once before
+    // the cache is resized, and once after it is resized).
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_transactions()
+        .times(2)
+        .with(
+            eq(start_version),
+            eq(end_version - start_version + 1),
+            eq(proof_version),
+            eq(include_events),
+        )
+        .returning(move |_, _, _, _| Ok(transaction_list_with_proof.clone()));
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, end_version, 10);
+    let config_handle = service.get_config_handle();
+    let lru_response_cache = service.get_lru_response_cache();
+    tokio::spawn(service.start());
+
+    // Fetch the data several times (only the first fetch should hit storage)
+    for _ in 0..5 {
+        utils::get_transactions_with_proof(
+            &mut mock_client,
+            start_version,
+            end_version,
+            proof_version,
+            include_events,
+            true,
+        )
+        .await
+        .unwrap();
+    }
+
+    // Update the LRU cache size and wait for the cache to be resized
+    let max_lru_cache_size = 10;
+    config_handle.update_config(StorageServiceConfig {
+        max_lru_cache_size,
+        ..config_handle.get_config()
+    });
+    let check_cache_resized = async move {
+        while lru_response_cache.max_capacity() != max_lru_cache_size {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    utils::spawn_with_timeout(check_cache_resized, "Timed-out waiting for the resize!").await;
+
+    // Fetch the data several times (the resized cache starts empty)
+    for _ in 0..5 {
+        utils::get_transactions_with_proof(
+            &mut mock_client,
+            start_version,
+            end_version,
+            proof_version,
+            include_events,
+            true,
+        )
+        .await
+        .unwrap();
+    }
+}
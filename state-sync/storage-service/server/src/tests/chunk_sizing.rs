@@ -0,0 +1,129 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chunk_sizing::{AdaptiveChunkSizer, ChunkMetrics, ChunkType};
+use aptos_config::config::StorageServiceConfig;
+
+#[test]
+fn test_adaptive_chunk_sizing_disabled() {
+    // Create an adaptive chunk sizer (with adaptive sizing disabled)
+    let storage_service_config = StorageServiceConfig::default();
+    let mut adaptive_chunk_sizer = AdaptiveChunkSizer::new();
+
+    // Verify the configured chunk sizes are advertised
+    let protocol_metadata = adaptive_chunk_sizer.get_protocol_metadata(&storage_service_config);
+    assert_eq!(
+        protocol_metadata.max_epoch_chunk_size,
+        storage_service_config.max_epoch_chunk_size
+    );
+    assert_eq!(
+        protocol_metadata.max_state_chunk_size,
+        storage_service_config.max_state_chunk_size
+    );
+    assert_eq!(
+        protocol_metadata.max_transaction_chunk_size,
+        storage_service_config.max_transaction_chunk_size
+    );
+    assert_eq!(
+        protocol_metadata.max_transaction_output_chunk_size,
+        storage_service_config.max_transaction_output_chunk_size
+    );
+}
+
+#[test]
+fn test_adaptive_chunk_sizing_frame_overflows() {
+    // Create an adaptive chunk sizer
+    let storage_service_config = create_adaptive_sizing_config();
+    let max_chunk_size = storage_service_config.max_transaction_chunk_size;
+    let mut adaptive_chunk_sizer = AdaptiveChunkSizer::new();
+    let mut metrics = ChunkMetrics::default();
+    update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+
+    // Verify the chunk size isn't adjusted until enough responses are served
+    metrics.num_responses += 10;
+    metrics.num_frame_overflows += 10;
+    let chunk_size = update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+    assert_eq!(chunk_size, max_chunk_size);
+
+    // Verify the chunk size is halved when responses routinely overflow
+    metrics.num_responses += 10;
+    let chunk_size = update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+    assert_eq!(chunk_size, max_chunk_size / 2);
+
+    // Verify the chunk size is halved again if responses still overflow
+    metrics.num_responses += 100;
+    metrics.num_frame_overflows += 20;
+    let chunk_size = update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+    assert_eq!(chunk_size, max_chunk_size / 4);
+
+    // Verify the chunk size is unchanged if responses rarely overflow
+    metrics.num_responses += 100;
+    metrics.num_frame_overflows += 1;
+    let chunk_size = update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+    assert_eq!(chunk_size, max_chunk_size / 4);
+
+    // Verify the other chunk types are unaffected
+    assert_eq!(
+        adaptive_chunk_sizer.get_chunk_size(ChunkType::StateValues, &storage_service_config),
+        storage_service_config.max_state_chunk_size
+    );
+
+    // Verify the chunk size recovers (up to the maximum) once responses don't overflow
+    for _ in 0..20 {
+        metrics.num_responses += 100;
+        update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+    }
+    assert_eq!(
+        adaptive_chunk_sizer.get_chunk_size(ChunkType::Transactions, &storage_service_config),
+        max_chunk_size
+    );
+}
+
+#[test]
+fn test_adaptive_chunk_sizing_serving_latency() {
+    // Create an adaptive chunk sizer
+    let storage_service_config = create_adaptive_sizing_config();
+    let max_chunk_size = storage_service_config.max_transaction_chunk_size;
+    let max_latency_secs = storage_service_config.max_chunk_serving_latency_ms as f64 / 1000.0;
+    let mut adaptive_chunk_sizer = AdaptiveChunkSizer::new();
+    let mut metrics = ChunkMetrics::default();
+    update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+
+    // Verify the chunk size is halved when responses are too slow to serve
+    metrics.num_responses += 50;
+    metrics.num_latency_samples += 50;
+    metrics.total_serving_latency_secs += 50.0 * max_latency_secs * 2.0;
+    let chunk_size = update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+    assert_eq!(chunk_size, max_chunk_size / 2);
+
+    // Verify the chunk size is unchanged if responses are only slightly fast enough
+    metrics.num_responses += 50;
+    metrics.num_latency_samples += 50;
+    metrics.total_serving_latency_secs += 50.0 * max_latency_secs * 0.75;
+    let chunk_size = update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+    assert_eq!(chunk_size, max_chunk_size / 2);
+
+    // Verify the chunk size grows when responses are served quickly
+    metrics.num_responses += 50;
+    metrics.num_latency_samples += 50;
+    metrics.total_serving_latency_secs += 50.0 * max_latency_secs * 0.1;
+    let chunk_size = update_chunk_size(&mut adaptive_chunk_sizer, &storage_service_config, metrics);
+    assert_eq!(chunk_size, max_chunk_size / 2 + max_chunk_size / 8);
+}
+
+/// Creates a storage service config with adaptive chunk sizing enabled
+fn create_adaptive_sizing_config() -> StorageServiceConfig {
+    StorageServiceConfig {
+        enable_adaptive_chunk_sizing: true,
+        ..Default::default()
+    }
+}
+
+/// Updates the transaction chunk size with the given metrics and returns the new size
+fn update_chunk_size(
+    adaptive_chunk_sizer: &mut AdaptiveChunkSizer,
+    storage_service_config: &StorageServiceConfig,
+    metrics: ChunkMetrics,
+) -> u64 {
+    adaptive_chunk_sizer.update_chunk_size(ChunkType::Transactions, storage_service_config, metrics)
+}
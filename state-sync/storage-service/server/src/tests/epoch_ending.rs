@@ -8,7 +8,7 @@ use aptos_crypto::HashValue;
 use aptos_storage_service_types::{
     requests::{DataRequest, EpochEndingLedgerInfoRequest, StorageServiceRequest},
     responses::DataResponse,
-    Epoch, StorageServiceError,
+    Epoch, StorageServiceErrorCategory,
 };
 use aptos_types::{
     aggregate_signature::AggregateSignature,
@@ -16,7 +16,6 @@ use aptos_types::{
     epoch_change::EpochChangeProof,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
 };
-use claims::assert_matches;
 use mockall::{predicate::eq, Sequence};
 use rand::Rng;
 
@@ -136,7 +135,7 @@ async fn test_get_epoch_ending_ledger_infos_invalid() {
             .process_request(storage_request)
             .await
             .unwrap_err();
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
     }
 }
 
@@ -176,7 +175,7 @@ async fn test_get_epoch_ending_ledger_infos_not_serviceable() {
             .unwrap_err();
 
         // Verify the request is not serviceable
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
     }
 }
 
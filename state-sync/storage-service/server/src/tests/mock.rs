@@ -24,13 +24,15 @@ use aptos_network::{
 use aptos_storage_interface::{DbReader, ExecutedTrees, Order};
 use aptos_storage_service_notifications::StorageServiceNotifier;
 use aptos_storage_service_types::{
-    requests::StorageServiceRequest, responses::StorageServiceResponse, StorageServiceError,
-    StorageServiceMessage,
+    requests::{StorageServiceRequest, StorageServiceRequestEnvelope},
+    responses::StorageServiceResponse,
+    StorageServiceError, StorageServiceMessage,
 };
 use aptos_time_service::{MockTimeService, TimeService};
 use aptos_types::{
     account_address::AccountAddress,
-    contract_event::EventWithVersion,
+    account_config::NewBlockEvent,
+    contract_event::{ContractEvent, EventWithVersion},
     epoch_change::EpochChangeProof,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
@@ -41,15 +43,19 @@ use aptos_types::{
         state_value::{StateValue, StateValueChunkWithProof},
     },
     transaction::{
-        AccountTransactionsWithProof, TransactionListWithProof, TransactionOutputListWithProof,
-        TransactionWithProof, Version,
+        AccountTransactionsWithProof, Transaction, TransactionInfo, TransactionListWithProof,
+        TransactionOutputListWithProof, TransactionWithProof, Version, WriteSetListWithProof,
     },
     PeerId,
 };
 use futures::channel::{oneshot, oneshot::Receiver};
 use mockall::mock;
 use rand::{rngs::OsRng, Rng};
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::time::timeout;
 
 // Useful test constants
@@ -72,6 +78,22 @@ impl MockClient {
         StorageServiceNotifier,
         MockTimeService,
         Arc<PeersAndMetadata>,
+    ) {
+        Self::new_with_trusted_peers(db_reader, storage_config, HashSet::new())
+    }
+
+    /// Creates a mock client and storage server that treats the
+    /// given peers as trusted (i.e., exempt from the chunk limits).
+    pub fn new_with_trusted_peers(
+        db_reader: Option<MockDatabaseReader>,
+        storage_config: Option<StorageServiceConfig>,
+        trusted_peers: HashSet<PeerId>,
+    ) -> (
+        Self,
+        StorageServiceServer<StorageReader>,
+        StorageServiceNotifier,
+        MockTimeService,
+        Arc<PeersAndMetadata>,
     ) {
         utils::initialize_logger();
 
@@ -79,12 +101,14 @@ impl MockClient {
         let mut state_sync_config = StateSyncConfig::default();
         let storage_service_config = storage_config.unwrap_or_default();
         state_sync_config.storage_service = storage_service_config;
+        state_sync_config.storage_service_trusted_peers = trusted_peers.clone();
 
         // Create the storage reader
         let storage_reader = StorageReader::new(
             storage_service_config,
             Arc::new(db_reader.unwrap_or_else(create_mock_db_reader)),
-        );
+        )
+        .with_trusted_peers(trusted_peers);
 
         // Setup the networks and the network events
         let network_ids = vec![NetworkId::Validator, NetworkId::Vfn, NetworkId::Public];
@@ -150,6 +174,31 @@ impl MockClient {
         self.wait_for_response(receiver).await
     }
 
+    /// Send the given storage request envelope and wait for a response
+    pub async fn process_request_envelope(
+        &mut self,
+        envelope: StorageServiceRequestEnvelope,
+    ) -> Result<StorageServiceResponse, StorageServiceError> {
+        let receiver = self.send_request_envelope(envelope, None, None).await;
+        self.wait_for_response(receiver).await
+    }
+
+    /// Send the specified storage request envelope and return the receiver
+    /// on which to expect a result.
+    pub async fn send_request_envelope(
+        &mut self,
+        envelope: StorageServiceRequestEnvelope,
+        peer_id: Option<AccountAddress>,
+        network_id: Option<NetworkId>,
+    ) -> Receiver<Result<bytes::Bytes, aptos_network::protocols::network::RpcError>> {
+        self.send_message(
+            StorageServiceMessage::RequestEnvelope(envelope),
+            peer_id,
+            network_id,
+        )
+        .await
+    }
+
     /// Send the specified storage request and return the receiver on which to
     /// expect a result.
     pub async fn send_request(
@@ -157,14 +206,28 @@ impl MockClient {
         request: StorageServiceRequest,
         peer_id: Option<AccountAddress>,
         network_id: Option<NetworkId>,
+    ) -> Receiver<Result<bytes::Bytes, aptos_network::protocols::network::RpcError>> {
+        self.send_message(
+            StorageServiceMessage::new_request(request),
+            peer_id,
+            network_id,
+        )
+        .await
+    }
+
+    /// Send the specified storage message and return the receiver on which to
+    /// expect a result.
+    async fn send_message(
+        &mut self,
+        message: StorageServiceMessage,
+        peer_id: Option<AccountAddress>,
+        network_id: Option<NetworkId>,
     ) -> Receiver<Result<bytes::Bytes, aptos_network::protocols::network::RpcError>> {
         // Create the inbound rpc request
         let peer_id = peer_id.unwrap_or_else(PeerId::random);
         let network_id = network_id.unwrap_or_else(get_random_network_id);
         let protocol_id = ProtocolId::StorageServiceRpc;
-        let data = protocol_id
-            .to_bytes(&StorageServiceMessage::Request(request))
-            .unwrap();
+        let data = protocol_id.to_bytes(&message).unwrap();
         let (res_tx, res_rx) = oneshot::channel();
         let inbound_rpc = InboundRpcRequest {
             protocol_id,
@@ -255,6 +318,30 @@ mock! {
             fetch_events: bool,
         ) -> aptos_storage_interface::Result<TransactionWithProof>;
 
+        fn get_transaction_iterator(
+            &self,
+            start_version: Version,
+            limit: u64,
+        ) -> aptos_storage_interface::Result<
+            Box<dyn Iterator<Item = aptos_storage_interface::Result<Transaction>> + 'static>,
+        >;
+
+        fn get_transaction_info_iterator(
+            &self,
+            start_version: Version,
+            limit: u64,
+        ) -> aptos_storage_interface::Result<
+            Box<dyn Iterator<Item = aptos_storage_interface::Result<TransactionInfo>> + 'static>,
+        >;
+
+        fn get_events_iterator(
+            &self,
+            start_version: Version,
+            limit: u64,
+        ) -> aptos_storage_interface::Result<
+            Box<dyn Iterator<Item = aptos_storage_interface::Result<Vec<ContractEvent>>> + 'static>,
+        >;
+
         fn get_first_txn_version(&self) -> aptos_storage_interface::Result<Option<Version>>;
 
         fn get_first_write_set_version(&self) -> aptos_storage_interface::Result<Option<Version>>;
@@ -266,6 +353,13 @@ mock! {
             ledger_version: Version,
         ) -> aptos_storage_interface::Result<TransactionOutputListWithProof>;
 
+        fn get_write_sets_with_proof(
+            &self,
+            start_version: Version,
+            limit: u64,
+            ledger_version: Version,
+        ) -> aptos_storage_interface::Result<WriteSetListWithProof>;
+
         fn get_events(
             &self,
             event_key: &EventKey,
@@ -277,6 +371,11 @@ mock! {
 
         fn get_block_timestamp(&self, version: u64) -> aptos_storage_interface::Result<u64>;
 
+        fn get_block_info_by_version(
+            &self,
+            version: Version,
+        ) -> aptos_storage_interface::Result<(Version, Version, NewBlockEvent)>;
+
         fn get_last_version_before_timestamp(
             &self,
             _timestamp: u64,
@@ -348,9 +447,26 @@ mock! {
             chunk_size: usize,
         ) -> aptos_storage_interface::Result<StateValueChunkWithProof>;
 
+        fn get_state_value_chunk_by_key_range_with_proof(
+            &self,
+            version: Version,
+            start_key_hash: HashValue,
+            chunk_size: usize,
+        ) -> aptos_storage_interface::Result<StateValueChunkWithProof>;
+
         fn get_epoch_snapshot_prune_window(&self) -> aptos_storage_interface::Result<usize>;
 
         fn is_state_merkle_pruner_enabled(&self) -> aptos_storage_interface::Result<bool>;
+
+        fn is_ledger_pruner_enabled(&self) -> aptos_storage_interface::Result<bool>;
+
+        fn get_ledger_prune_window(&self) -> aptos_storage_interface::Result<usize>;
+
+        fn get_indexer_next_version(&self) -> aptos_storage_interface::Result<Option<Version>>;
+
+        fn get_event_by_type_index_start_version(
+            &self,
+        ) -> aptos_storage_interface::Result<Option<Version>>;
     }
 }
 
@@ -0,0 +1,131 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    mock_server::{MockServerFault, MockStorageServiceServer},
+    network::{NetworkRequest, ResponseSender},
+    tests::utils,
+};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_network::protocols::{network::RpcError, wire::handshake::v1::ProtocolId};
+use aptos_storage_service_types::{
+    requests::{DataRequest, StorageServiceRequest, TransactionsWithProofRequest},
+    responses::{DataResponse, DataSummary, StorageServerSummary, StorageServiceResponse},
+    StorageServiceError, StorageServiceErrorCategory, StorageServiceMessage,
+};
+use aptos_types::PeerId;
+use bytes::Bytes;
+use claims::assert_err;
+use futures::channel::{oneshot, oneshot::Receiver};
+
+#[tokio::test]
+async fn test_mock_server_summary_and_data() {
+    // Create a mock server with a canned summary
+    let synced_ledger_info = utils::create_test_ledger_info_with_sigs(10, 100);
+    let storage_server_summary = StorageServerSummary {
+        data_summary: DataSummary {
+            synced_ledger_info: Some(synced_ledger_info),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mock_server = MockStorageServiceServer::new(storage_server_summary.clone());
+
+    // Verify the summary is returned
+    for use_compression in [true, false] {
+        let request =
+            StorageServiceRequest::new(DataRequest::GetStorageServerSummary, use_compression);
+        let response = send_and_wait_for_response(&mock_server, request).await;
+        assert_eq!(
+            response.unwrap(),
+            StorageServiceResponse::new(
+                DataResponse::StorageServerSummary(storage_server_summary.clone()),
+                use_compression
+            )
+            .unwrap()
+        );
+    }
+
+    // Verify a request without a canned response fails
+    let data_request = DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+        proof_version: 100,
+        start_version: 0,
+        end_version: 10,
+        include_events: false,
+    });
+    let request = StorageServiceRequest::new(data_request.clone(), false);
+    let response = send_and_wait_for_response(&mock_server, request.clone()).await;
+    assert_eq!(
+        response.unwrap_err().category(),
+        StorageServiceErrorCategory::Retryable
+    );
+
+    // Add a canned response and verify it is returned
+    let transaction_list = utils::create_transaction_list_with_proof(0, 10, 100, false);
+    let data_response = DataResponse::TransactionsWithProof(transaction_list);
+    mock_server.set_data_response(data_request, data_response.clone());
+    let response = send_and_wait_for_response(&mock_server, request.clone()).await;
+    assert_eq!(
+        response.unwrap(),
+        StorageServiceResponse::new(data_response, false).unwrap()
+    );
+
+    // Verify all requests were recorded
+    assert_eq!(mock_server.get_received_requests().len(), 4);
+}
+
+#[tokio::test]
+async fn test_mock_server_fault_injection() {
+    // Create a mock server with a default summary
+    let mock_server = MockStorageServiceServer::new(StorageServerSummary::default());
+    let request = StorageServiceRequest::new(DataRequest::GetServerProtocolVersion, false);
+
+    // Inject an error for the next two requests and verify they fail
+    let error = StorageServiceError::InternalError("Injected error!".into());
+    mock_server.inject_fault(MockServerFault::Error(error.clone()), 2);
+    for _ in 0..2 {
+        let response = send_and_wait_for_response(&mock_server, request.clone()).await;
+        assert_eq!(response.unwrap_err(), error);
+    }
+
+    // Inject a dropped response and verify the RPC fails
+    mock_server.inject_fault(MockServerFault::DropResponse, 1);
+    let response_receiver = send_request(&mock_server, request.clone()).await;
+    assert_err!(response_receiver.await);
+
+    // Verify the next request is served normally
+    let response = send_and_wait_for_response(&mock_server, request).await;
+    assert!(response.is_ok());
+}
+
+/// Sends the request to the mock server and returns the response receiver
+async fn send_request(
+    mock_server: &MockStorageServiceServer,
+    storage_service_request: StorageServiceRequest,
+) -> Receiver<Result<Bytes, RpcError>> {
+    let (response_tx, response_rx) = oneshot::channel();
+    let network_request = NetworkRequest {
+        peer_network_id: PeerNetworkId::new(NetworkId::Public, PeerId::random()),
+        protocol_id: ProtocolId::StorageServiceRpc,
+        storage_service_request,
+        response_sender: ResponseSender::new(response_tx),
+    };
+    mock_server.handle_network_request(network_request).await;
+    response_rx
+}
+
+/// Sends the request to the mock server and deserializes the response
+async fn send_and_wait_for_response(
+    mock_server: &MockStorageServiceServer,
+    storage_service_request: StorageServiceRequest,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let response_bytes = send_request(mock_server, storage_service_request)
+        .await
+        .await
+        .unwrap()
+        .unwrap();
+    match bcs::from_bytes::<StorageServiceMessage>(&response_bytes).unwrap() {
+        StorageServiceMessage::Response(response) => response,
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
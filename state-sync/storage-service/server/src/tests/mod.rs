@@ -1,23 +1,36 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod analytics;
+mod batch;
+mod block_metadata;
 mod cache;
+mod chunk_sizing;
 mod epoch_ending;
 mod mock;
+mod mock_server;
 mod new_transaction_outputs;
 mod new_transactions;
 mod new_transactions_or_outputs;
 mod number_of_states;
 mod optimistic_fetch;
+mod proof_self_check;
 mod protocol_version;
+mod request_envelope;
 mod request_moderator;
 mod state_values;
+mod state_values_by_key_range;
 mod storage_summary;
 mod subscribe_transaction_outputs;
 mod subscribe_transactions;
 mod subscribe_transactions_or_outputs;
 mod subscription;
+mod transaction_by_hash;
 mod transaction_outputs;
 mod transactions;
 mod transactions_or_outputs;
+mod transactions_without_proof;
+mod trusted_peers;
 mod utils;
+mod version_by_timestamp;
+mod write_sets;
@@ -6,7 +6,7 @@ use anyhow::format_err;
 use aptos_storage_interface::AptosDbError;
 use aptos_storage_service_types::{
     responses::{DataResponse, StorageServiceResponse},
-    StorageServiceError,
+    StorageServiceErrorCategory,
 };
 use claims::assert_matches;
 use mockall::predicate::eq;
@@ -59,7 +59,7 @@ async fn test_get_number_of_states_at_version_not_serviceable() {
         .unwrap_err();
 
     // Verify the request is not serviceable
-    assert_matches!(response, StorageServiceError::InvalidRequest(_));
+    assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
 }
 
 #[tokio::test]
@@ -90,5 +90,5 @@ async fn test_get_number_of_states_at_version_invalid() {
         .unwrap_err();
 
     // Verify the response is correct
-    assert_matches!(response, StorageServiceError::InternalError(_));
+    assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
 }
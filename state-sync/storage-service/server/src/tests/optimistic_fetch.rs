@@ -6,6 +6,7 @@ use crate::{
     network::ResponseSender,
     optimistic_fetch,
     optimistic_fetch::OptimisticFetchRequest,
+    response_cache::ResponseCache,
     storage::StorageReader,
     tests::{mock, utils},
 };
@@ -27,7 +28,6 @@ use aptos_types::epoch_change::EpochChangeProof;
 use arc_swap::ArcSwap;
 use dashmap::DashMap;
 use futures::channel::oneshot;
-use mini_moka::sync::Cache;
 use rand::{rngs::OsRng, Rng};
 use std::sync::Arc;
 use tokio::runtime::Handle;
@@ -69,7 +69,7 @@ async fn test_peers_with_ready_optimistic_fetches() {
     let bounded_executor = BoundedExecutor::new(100, Handle::current());
     let cached_storage_server_summary =
         Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
-    let lru_response_cache = Cache::new(0);
+    let lru_response_cache = ResponseCache::new(0);
     let request_moderator = Arc::new(RequestModerator::new(
         AptosDataClientConfig::default(),
         cached_storage_server_summary.clone(),
@@ -175,7 +175,7 @@ async fn test_peers_with_ready_optimistic_fetches_update() {
     let bounded_executor = BoundedExecutor::new(100, Handle::current());
     let cached_storage_server_summary =
         Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
-    let lru_response_cache = Cache::new(0);
+    let lru_response_cache = ResponseCache::new(0);
     let request_moderator = Arc::new(RequestModerator::new(
         AptosDataClientConfig::default(),
         cached_storage_server_summary.clone(),
@@ -286,7 +286,7 @@ async fn test_remove_expired_optimistic_fetches() {
     let bounded_executor = BoundedExecutor::new(100, Handle::current());
     let cached_storage_server_summary =
         Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
-    let lru_response_cache = Cache::new(0);
+    let lru_response_cache = ResponseCache::new(0);
     let request_moderator = Arc::new(RequestModerator::new(
         AptosDataClientConfig::default(),
         cached_storage_server_summary.clone(),
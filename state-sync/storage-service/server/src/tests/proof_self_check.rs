@@ -0,0 +1,308 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    metrics::PROOF_SELF_CHECK_FAILURES,
+    storage::{StorageReader, StorageReaderInterface},
+    tests::mock,
+};
+use aptos_config::config::StorageServiceConfig;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{
+        SparseMerkleLeafNode, SparseMerkleProof, SparseMerkleRangeProof,
+        TransactionAccumulatorProof, TransactionAccumulatorRangeProof,
+        TransactionInfoListWithProof, TransactionInfoWithProof,
+    },
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
+    transaction::{
+        ExecutionStatus, Transaction, TransactionInfo, TransactionWithProof, WriteSetListWithProof,
+    },
+    write_set::WriteSet,
+};
+use claims::assert_matches;
+use mockall::predicate::eq;
+use std::sync::Arc;
+
+#[test]
+fn test_proof_self_check_disabled() {
+    // Create a write set list
+    let version = 0;
+    let write_set_list_with_proof = create_write_set_list_with_proof(version);
+
+    // Create the mock db reader (the proof should never be checked)
+    let mut db_reader = mock::create_mock_db_reader();
+    let write_sets = write_set_list_with_proof.clone();
+    db_reader
+        .expect_get_write_sets_with_proof()
+        .times(1)
+        .with(eq(version), eq(1), eq(version))
+        .returning(move |_, _, _| Ok(write_sets.clone()));
+
+    // Fetch the write sets and verify the invalid proof is still returned
+    let storage_reader = StorageReader::new(StorageServiceConfig::default(), Arc::new(db_reader));
+    let response = storage_reader
+        .get_write_sets_with_proof(version, version, version)
+        .unwrap();
+    assert_eq!(response, write_set_list_with_proof);
+}
+
+#[test]
+fn test_proof_self_check_enabled() {
+    // Create a write set list with a valid proof
+    let version = 0;
+    let write_set_list_with_proof = create_write_set_list_with_proof(version);
+    let accumulator_root_hash =
+        CryptoHash::hash(&write_set_list_with_proof.proof.transaction_infos[0]);
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    let write_sets = write_set_list_with_proof.clone();
+    db_reader
+        .expect_get_write_sets_with_proof()
+        .times(1)
+        .with(eq(version), eq(1), eq(version))
+        .returning(move |_, _, _| Ok(write_sets.clone()));
+    db_reader
+        .expect_get_accumulator_root_hash()
+        .times(1)
+        .with(eq(version))
+        .returning(move |_| Ok(accumulator_root_hash));
+
+    // Fetch the write sets and verify the response is correct
+    let storage_reader = StorageReader::new(create_self_check_config(), Arc::new(db_reader));
+    let response = storage_reader
+        .get_write_sets_with_proof(version, version, version)
+        .unwrap();
+    assert_eq!(response, write_set_list_with_proof);
+}
+
+#[test]
+fn test_proof_self_check_failure() {
+    // Create a write set list with a valid proof
+    let version = 0;
+    let write_set_list_with_proof = create_write_set_list_with_proof(version);
+
+    // Create the mock db reader (the accumulator root hash doesn't match the proof)
+    let mut db_reader = mock::create_mock_db_reader();
+    let write_sets = write_set_list_with_proof.clone();
+    db_reader
+        .expect_get_write_sets_with_proof()
+        .times(1)
+        .with(eq(version), eq(1), eq(version))
+        .returning(move |_, _, _| Ok(write_sets.clone()));
+    db_reader
+        .expect_get_accumulator_root_hash()
+        .times(1)
+        .with(eq(version))
+        .returning(move |_| Ok(HashValue::random()));
+
+    // Fetch the write sets and verify the response is rejected
+    let num_failures = get_num_self_check_failures();
+    let storage_reader = StorageReader::new(create_self_check_config(), Arc::new(db_reader));
+    let error = storage_reader
+        .get_write_sets_with_proof(version, version, version)
+        .unwrap_err();
+    assert_matches!(error, Error::StorageErrorEncountered(_));
+
+    // Verify the failure was counted
+    assert!(get_num_self_check_failures() > num_failures);
+}
+
+#[test]
+fn test_proof_self_check_state_value_chunk() {
+    // Create a state checkpoint holding a single state value
+    let version = 10;
+    let state_key = StateKey::raw(vec![1]);
+    let state_value = StateValue::from(vec![2]);
+    let state_key_hash = CryptoHash::hash(&state_key);
+    let leaf = SparseMerkleLeafNode::new(state_key_hash, CryptoHash::hash(&state_value));
+    let state_value_chunk_with_proof = StateValueChunkWithProof {
+        first_index: 0,
+        last_index: 0,
+        first_key: state_key_hash,
+        last_key: state_key_hash,
+        raw_values: vec![(state_key.clone(), state_value.clone())],
+        proof: SparseMerkleRangeProof::new(vec![]),
+        root_hash: leaf.hash(),
+    };
+
+    // Verify the chunk passes the self-check
+    let storage_reader = create_state_value_chunk_reader(
+        version,
+        &state_value_chunk_with_proof,
+        leaf.hash(),
+        (state_key.clone(), state_value.clone()),
+    );
+    let response = storage_reader
+        .get_state_value_chunk_with_proof(version, 0, 0)
+        .unwrap();
+    assert_eq!(response, state_value_chunk_with_proof);
+
+    // Verify a chunk with a different state value is rejected, even though
+    // its root hash matches the state checkpoint
+    let num_failures = get_num_state_value_chunk_self_check_failures();
+    let invalid_chunk = StateValueChunkWithProof {
+        raw_values: vec![(state_key.clone(), StateValue::from(vec![3]))],
+        ..state_value_chunk_with_proof.clone()
+    };
+    let storage_reader = create_state_value_chunk_reader(
+        version,
+        &invalid_chunk,
+        leaf.hash(),
+        (state_key.clone(), state_value.clone()),
+    );
+    let error = storage_reader
+        .get_state_value_chunk_with_proof(version, 0, 0)
+        .unwrap_err();
+    assert_matches!(error, Error::StorageErrorEncountered(_));
+    assert!(get_num_state_value_chunk_self_check_failures() > num_failures);
+
+    // Verify a chunk with an invalid range proof is rejected
+    let num_failures = get_num_state_value_chunk_self_check_failures();
+    let invalid_chunk = StateValueChunkWithProof {
+        proof: SparseMerkleRangeProof::new(vec![HashValue::random()]),
+        ..state_value_chunk_with_proof.clone()
+    };
+    let storage_reader = create_state_value_chunk_reader(
+        version,
+        &invalid_chunk,
+        leaf.hash(),
+        (state_key.clone(), state_value.clone()),
+    );
+    let error = storage_reader
+        .get_state_value_chunk_with_proof(version, 0, 0)
+        .unwrap_err();
+    assert_matches!(error, Error::StorageErrorEncountered(_));
+    assert!(get_num_state_value_chunk_self_check_failures() > num_failures);
+
+    // Verify a state checkpoint that isn't proven by the latest ledger info is rejected
+    let num_failures = get_num_state_value_chunk_self_check_failures();
+    let storage_reader = create_state_value_chunk_reader(
+        version,
+        &state_value_chunk_with_proof,
+        HashValue::random(),
+        (state_key, state_value),
+    );
+    let error = storage_reader
+        .get_state_value_chunk_with_proof(version, 0, 0)
+        .unwrap_err();
+    assert_matches!(error, Error::StorageErrorEncountered(_));
+    assert!(get_num_state_value_chunk_self_check_failures() > num_failures);
+}
+
+/// Creates a storage reader (with the proof self-check enabled) that serves the
+/// given chunk at the version. The latest ledger info is at the version, and
+/// proves a state checkpoint with the given root hash, holding only the given
+/// state value.
+fn create_state_value_chunk_reader(
+    version: u64,
+    state_value_chunk_with_proof: &StateValueChunkWithProof,
+    state_checkpoint_hash: HashValue,
+    (state_key, state_value): (StateKey, StateValue),
+) -> StorageReader {
+    let transaction_info = TransactionInfo::new(
+        HashValue::zero(),
+        HashValue::zero(),
+        HashValue::zero(),
+        Some(state_checkpoint_hash),
+        0,
+        ExecutionStatus::Success,
+    );
+    let block_info = BlockInfo::new(
+        0,
+        0,
+        HashValue::zero(),
+        CryptoHash::hash(&transaction_info),
+        version,
+        0,
+        None,
+    );
+    let latest_ledger_info = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(block_info, HashValue::zero()),
+        AggregateSignature::empty(),
+    );
+    let transaction_with_proof = TransactionWithProof::new(
+        version,
+        Transaction::StateCheckpoint(HashValue::zero()),
+        None,
+        TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), transaction_info),
+    );
+    let state_value_proof = SparseMerkleProof::new(
+        Some(SparseMerkleLeafNode::new(
+            CryptoHash::hash(&state_key),
+            CryptoHash::hash(&state_value),
+        )),
+        vec![],
+    );
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    let chunk = state_value_chunk_with_proof.clone();
+    db_reader
+        .expect_get_state_value_chunk_with_proof()
+        .times(1)
+        .with(eq(version), eq(0), eq(1))
+        .returning(move |_, _, _| Ok(chunk.clone()));
+    db_reader
+        .expect_get_latest_ledger_info()
+        .returning(move || Ok(latest_ledger_info.clone()));
+    db_reader
+        .expect_get_transaction_by_version()
+        .with(eq(version), eq(version), eq(false))
+        .returning(move |_, _, _| Ok(transaction_with_proof.clone()));
+    db_reader
+        .expect_get_state_value_with_proof_by_version()
+        .with(eq(state_key), eq(version))
+        .returning(move |_, _| Ok((Some(state_value.clone()), state_value_proof.clone())));
+
+    StorageReader::new(create_self_check_config(), Arc::new(db_reader))
+}
+
+/// Creates a storage service config with the proof self-check enabled
+fn create_self_check_config() -> StorageServiceConfig {
+    StorageServiceConfig {
+        enable_proof_self_check: true,
+        ..Default::default()
+    }
+}
+
+/// Creates a write set list holding a single write set at the given version.
+/// The proof is only valid for an accumulator holding a single transaction.
+fn create_write_set_list_with_proof(version: u64) -> WriteSetListWithProof {
+    let write_set = WriteSet::default();
+    let transaction_info = TransactionInfo::new(
+        HashValue::zero(),
+        CryptoHash::hash(&write_set),
+        HashValue::zero(),
+        None,
+        0,
+        ExecutionStatus::Success,
+    );
+    let proof = TransactionInfoListWithProof::new(
+        TransactionAccumulatorRangeProof::new_empty(),
+        vec![transaction_info],
+    );
+    WriteSetListWithProof::new(vec![write_set], Some(version), proof)
+}
+
+/// Returns the number of state value chunk proofs that failed the self-check
+fn get_num_state_value_chunk_self_check_failures() -> u64 {
+    PROOF_SELF_CHECK_FAILURES
+        .with_label_values(&["state_value_chunk_with_proof"])
+        .get()
+}
+
+/// Returns the number of write set proofs that failed the self-check
+fn get_num_self_check_failures() -> u64 {
+    PROOF_SELF_CHECK_FAILURES
+        .with_label_values(&["write_sets_with_proof"])
+        .get()
+}
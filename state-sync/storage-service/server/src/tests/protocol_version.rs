@@ -1,9 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::tests::{mock::MockClient, utils};
+use crate::tests::mock::MockClient;
+use aptos_config::config::StorageServiceConfig;
 use aptos_storage_service_types::{
-    requests::DataRequest,
+    requests::{DataRequest, StorageServiceRequest},
     responses::{DataResponse, ServerProtocolVersion, StorageServiceResponse},
 };
 use claims::assert_matches;
@@ -18,26 +19,83 @@ async fn test_get_server_protocol_version() {
     tokio::spawn(service.start());
 
     // Process a request to fetch the protocol version
-    let response = get_protocol_version(&mut mock_client, true).await;
+    let response = get_protocol_version(&mut mock_client, true, false).await;
 
     // Verify the response is correct
-    let expected_data_response = DataResponse::ServerProtocolVersion(ServerProtocolVersion {
-        protocol_version: PROTOCOL_VERSION,
-    });
     assert_matches!(response, StorageServiceResponse::CompressedResponse(_, _));
     assert_eq!(
         response.get_data_response().unwrap(),
-        expected_data_response
+        create_protocol_version_response()
+    );
+}
+
+#[tokio::test]
+async fn test_get_server_protocol_version_checksummed() {
+    for use_compression in [true, false] {
+        // Create the storage client and server
+        let (mut mock_client, service, _, _, _) = MockClient::new(None, None);
+        tokio::spawn(service.start());
+
+        // Process a request (that asks for a checksum) to fetch the protocol version
+        let response = get_protocol_version(&mut mock_client, use_compression, true).await;
+
+        // Verify the response is checksummed and correct
+        assert!(response.is_checksummed());
+        assert_eq!(response.is_compressed(), use_compression);
+        assert_eq!(
+            response.get_data_response().unwrap(),
+            create_protocol_version_response()
+        );
+
+        // Process a request (that doesn't ask for a checksum) to fetch the protocol version
+        let response = get_protocol_version(&mut mock_client, use_compression, false).await;
+
+        // Verify the response is not checksummed (e.g., for clients that can't decode it)
+        assert!(!response.is_checksummed());
+        assert_eq!(response.is_compressed(), use_compression);
+        assert_eq!(
+            response.get_data_response().unwrap(),
+            create_protocol_version_response()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_server_protocol_version_checksums_disabled() {
+    // Create the storage client and server (with response checksums disabled)
+    let storage_config = StorageServiceConfig {
+        enable_response_checksums: false,
+        ..Default::default()
+    };
+    let (mut mock_client, service, _, _, _) = MockClient::new(None, Some(storage_config));
+    tokio::spawn(service.start());
+
+    // Process a request (that asks for a checksum) to fetch the protocol version
+    let response = get_protocol_version(&mut mock_client, true, true).await;
+
+    // Verify the response is not checksummed
+    assert!(!response.is_checksummed());
+    assert_eq!(
+        response.get_data_response().unwrap(),
+        create_protocol_version_response()
     );
 }
 
+/// Creates the expected protocol version response
+fn create_protocol_version_response() -> DataResponse {
+    DataResponse::ServerProtocolVersion(ServerProtocolVersion {
+        protocol_version: PROTOCOL_VERSION,
+    })
+}
+
 /// Sends a protocol version request and processes the response
 async fn get_protocol_version(
     mock_client: &mut MockClient,
     use_compression: bool,
+    use_checksum: bool,
 ) -> StorageServiceResponse {
     let data_request = DataRequest::GetServerProtocolVersion;
-    utils::send_storage_request(mock_client, use_compression, data_request)
-        .await
-        .unwrap()
+    let storage_request =
+        StorageServiceRequest::new(data_request, use_compression).with_checksum(use_checksum);
+    mock_client.process_request(storage_request).await.unwrap()
 }
@@ -0,0 +1,129 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{mock::MockClient, utils};
+use aptos_config::{
+    config::StorageServiceConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_storage_service_types::{
+    requests::{DataRequest, StorageServiceRequest, StorageServiceRequestEnvelope},
+    responses::{DataResponse, ServerProtocolVersion},
+    StorageServiceErrorCategory,
+};
+use aptos_types::PeerId;
+
+#[tokio::test]
+async fn test_request_envelope() {
+    // Create the storage client and server
+    let (mut mock_client, service, _, _, _) = MockClient::new(None, None);
+    tokio::spawn(service.start());
+
+    for use_compression in [true, false] {
+        // Process a protocol version request wrapped in an envelope
+        let request =
+            StorageServiceRequest::new(DataRequest::GetServerProtocolVersion, use_compression);
+        let envelope = StorageServiceRequestEnvelope::new(&request).unwrap();
+        let response = mock_client
+            .process_request_envelope(envelope)
+            .await
+            .unwrap();
+
+        // Verify the response is the same as for the unwrapped request
+        let expected_response = utils::send_storage_request(
+            &mut mock_client,
+            use_compression,
+            DataRequest::GetServerProtocolVersion,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response, expected_response);
+        assert_eq!(
+            response.get_data_response().unwrap(),
+            DataResponse::ServerProtocolVersion(ServerProtocolVersion {
+                protocol_version: 1
+            })
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_request_envelope_invalid() {
+    // Create the storage client and server
+    let (mut mock_client, service, _, _, _) = MockClient::new(None, None);
+    tokio::spawn(service.start());
+
+    // Process a request envelope with an unknown message type
+    let envelope = StorageServiceRequestEnvelope {
+        message_type: u32::MAX,
+        payload: vec![],
+        use_compression: false,
+    };
+    let response = mock_client
+        .process_request_envelope(envelope)
+        .await
+        .unwrap_err();
+    assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
+
+    // Process a request envelope with a payload that doesn't match the message type
+    let request = StorageServiceRequest::new(DataRequest::GetStorageServerSummary, false);
+    let mut envelope = StorageServiceRequestEnvelope::new(&request).unwrap();
+    envelope.payload = vec![1, 2, 3];
+    let response = mock_client
+        .process_request_envelope(envelope)
+        .await
+        .unwrap_err();
+    assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
+}
+
+#[tokio::test]
+async fn test_request_envelope_invalid_moderated() {
+    // Create a storage service config with a small invalid request limit
+    let max_invalid_requests_per_peer = 3;
+    let storage_service_config = StorageServiceConfig {
+        max_invalid_requests_per_peer,
+        ..Default::default()
+    };
+
+    // Create the storage client and server
+    let (mut mock_client, service, _, _, _) = MockClient::new(None, Some(storage_service_config));
+    let unhealthy_peer_states = service.get_request_moderator().get_unhealthy_peer_states();
+    tokio::spawn(service.start());
+
+    // Send invalid request envelopes from a PFN
+    let pfn_peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    for _ in 0..max_invalid_requests_per_peer {
+        let envelope = StorageServiceRequestEnvelope {
+            message_type: u32::MAX,
+            payload: vec![],
+            use_compression: false,
+        };
+        let receiver = mock_client
+            .send_request_envelope(
+                envelope,
+                Some(pfn_peer_network_id.peer_id()),
+                Some(pfn_peer_network_id.network_id()),
+            )
+            .await;
+        let response = mock_client.wait_for_response(receiver).await.unwrap_err();
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
+    }
+
+    // Verify the moderator is now ignoring the PFN
+    assert!(unhealthy_peer_states
+        .get(&pfn_peer_network_id)
+        .unwrap()
+        .is_ignored());
+
+    // Verify that valid requests from the PFN are now rejected
+    let request = StorageServiceRequest::new(DataRequest::GetServerProtocolVersion, false);
+    let receiver = mock_client
+        .send_request(
+            request,
+            Some(pfn_peer_network_id.peer_id()),
+            Some(pfn_peer_network_id.network_id()),
+        )
+        .await;
+    let response = mock_client.wait_for_response(receiver).await.unwrap_err();
+    assert_eq!(response.category(), StorageServiceErrorCategory::Permission);
+}
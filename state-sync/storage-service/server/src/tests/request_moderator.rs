@@ -17,12 +17,11 @@ use aptos_network::{
 };
 use aptos_storage_service_types::{
     requests::{DataRequest, StorageServiceRequest, TransactionsWithProofRequest},
-    responses::StorageServiceResponse,
-    StorageServiceError,
+    responses::{CompleteDataRange, StorageServiceResponse},
+    StorageServiceError, StorageServiceErrorCategory,
 };
 use aptos_time_service::MockTimeService;
 use aptos_types::{account_address::AccountAddress, network_address::NetworkAddress, PeerId};
-use claims::assert_matches;
 use dashmap::DashMap;
 use std::{str::FromStr, sync::Arc, time::Duration};
 
@@ -67,10 +66,10 @@ async fn test_request_moderator_ignore_pfn() {
         )
         .await;
 
-        // Verify we get an invalid request error
-        assert_matches!(
-            response.unwrap_err(),
-            StorageServiceError::InvalidRequest(_)
+        // Verify we get a retryable error (the data is not yet available)
+        assert_eq!(
+            response.unwrap_err().category(),
+            StorageServiceErrorCategory::Retryable
         );
     }
 
@@ -81,9 +80,9 @@ async fn test_request_moderator_ignore_pfn() {
         pfn_peer_network_id,
     )
     .await;
-    assert_matches!(
-        response.unwrap_err(),
-        StorageServiceError::TooManyInvalidRequests(_)
+    assert_eq!(
+        response.unwrap_err().category(),
+        StorageServiceErrorCategory::Permission
     );
 
     // Process many invalid requests from a VFN and verify it is never ignored
@@ -97,10 +96,10 @@ async fn test_request_moderator_ignore_pfn() {
         )
         .await;
 
-        // Verify we get an invalid request error
-        assert_matches!(
-            response.unwrap_err(),
-            StorageServiceError::InvalidRequest(_)
+        // Verify we get a retryable error (the data is not yet available)
+        assert_eq!(
+            response.unwrap_err().category(),
+            StorageServiceErrorCategory::Retryable
         );
     }
 
@@ -168,10 +167,10 @@ async fn test_request_moderator_increase_time() {
             )
             .await;
 
-            // Verify we get an invalid request error
-            assert_matches!(
-                response.unwrap_err(),
-                StorageServiceError::InvalidRequest(_)
+            // Verify we get a retryable error (the data is not yet available)
+            assert_eq!(
+                response.unwrap_err().category(),
+                StorageServiceErrorCategory::Retryable
             );
         }
 
@@ -184,9 +183,9 @@ async fn test_request_moderator_increase_time() {
         .await;
 
         // Verify we get an error for too many invalid requests
-        assert_matches!(
-            response.unwrap_err(),
-            StorageServiceError::TooManyInvalidRequests(_)
+        assert_eq!(
+            response.unwrap_err().category(),
+            StorageServiceErrorCategory::Permission
         );
 
         // Verify the peer is now ignored
@@ -353,6 +352,130 @@ async fn test_request_moderator_peer_garbage_collect() {
     assert_eq!(unhealthy_peer_states.len(), 1);
 }
 
+#[tokio::test]
+async fn test_request_moderator_error_categories() {
+    // Create test data
+    let lowest_version = 100;
+    let highest_synced_version = 1000;
+    let highest_synced_epoch = 10;
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(
+        &mut service,
+        highest_synced_version,
+        highest_synced_epoch,
+    );
+
+    // Update the storage server summary to mark the lowest versions as pruned
+    let mut storage_server_summary = (**service.cached_storage_server_summary.load()).clone();
+    let data_range = CompleteDataRange::new(lowest_version, highest_synced_version).unwrap();
+    storage_server_summary.data_summary.transactions = Some(data_range);
+    service
+        .cached_storage_server_summary
+        .store(Arc::new(storage_server_summary));
+    tokio::spawn(service.start());
+
+    // Verify the error categories for different unserviceable requests
+    let peer_network_id = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    for (start_version, end_version, expected_category) in [
+        (0, 200, StorageServiceErrorCategory::Pruned), // The data has been pruned
+        (900, 1100, StorageServiceErrorCategory::Retryable), // The data is not yet available
+        (500, 400, StorageServiceErrorCategory::Fatal), // The request is invalid
+    ] {
+        let request = StorageServiceRequest::new(
+            DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+                proof_version: highest_synced_version,
+                start_version,
+                end_version,
+                include_events: false,
+            }),
+            true,
+        );
+        let receiver = mock_client
+            .send_request(
+                request,
+                Some(peer_network_id.peer_id()),
+                Some(peer_network_id.network_id()),
+            )
+            .await;
+
+        // Verify the error category
+        let error = mock_client.wait_for_response(receiver).await.unwrap_err();
+        assert_eq!(error.category(), expected_category);
+
+        // Verify the earliest available version is only returned for pruned data
+        let expected_version =
+            (expected_category == StorageServiceErrorCategory::Pruned).then_some(lowest_version);
+        assert_eq!(error.earliest_available_version(), expected_version);
+    }
+}
+
+#[tokio::test]
+async fn test_request_moderator_bandwidth_quota() {
+    // Create a storage service config with a tiny bandwidth quota
+    let peer_bandwidth_window_secs = 60;
+    let storage_service_config = StorageServiceConfig {
+        enable_peer_bandwidth_quotas: true,
+        max_peer_bandwidth_bytes_per_window: 1,
+        peer_bandwidth_window_secs,
+        ..Default::default()
+    };
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, time_service, _) =
+        MockClient::new(None, Some(storage_service_config));
+    utils::update_storage_server_summary(&mut service, 100, 10);
+
+    // Get the request moderator and verify the initial state
+    let request_moderator = service.get_request_moderator();
+    let peer_bandwidth_states = request_moderator.get_peer_bandwidth_states();
+    assert!(peer_bandwidth_states.is_empty());
+
+    // Spawn the server
+    tokio::spawn(service.start());
+
+    // Send a request from a PFN and verify it is served
+    let pfn_peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    send_protocol_version_request(&mut mock_client, pfn_peer_network_id)
+        .await
+        .unwrap();
+
+    // Verify the bytes served to the PFN were recorded
+    let bytes_served_by_type = peer_bandwidth_states
+        .get(&pfn_peer_network_id)
+        .unwrap()
+        .get_bytes_served_by_type()
+        .clone();
+    assert_eq!(bytes_served_by_type.len(), 1);
+    assert!(bytes_served_by_type
+        .values()
+        .all(|num_bytes| *num_bytes > 0));
+
+    // Send another request and verify the PFN is now throttled (and may retry later)
+    let response = send_protocol_version_request(&mut mock_client, pfn_peer_network_id).await;
+    assert_eq!(
+        response.unwrap_err().category(),
+        StorageServiceErrorCategory::Retryable
+    );
+
+    // Elapse the bandwidth window and verify the PFN is served again
+    time_service
+        .advance_secs_async(peer_bandwidth_window_secs)
+        .await;
+    send_protocol_version_request(&mut mock_client, pfn_peer_network_id)
+        .await
+        .unwrap();
+
+    // Send many requests from a VFN and verify it is never throttled
+    let vfn_peer_network_id = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    for _ in 0..5 {
+        send_protocol_version_request(&mut mock_client, vfn_peer_network_id)
+            .await
+            .unwrap();
+    }
+}
+
 /// Advances the given timer by the amount of time it takes to refresh the moderator
 async fn advance_moderator_refresh_time(mock_time: &MockTimeService) {
     let default_storage_config = StorageServiceConfig::default();
@@ -404,6 +527,22 @@ async fn send_invalid_transaction_request(
     mock_client.wait_for_response(receiver).await
 }
 
+/// Sends a request to get the server protocol version
+async fn send_protocol_version_request(
+    mock_client: &mut MockClient,
+    peer_network_id: PeerNetworkId,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let request = StorageServiceRequest::new(DataRequest::GetServerProtocolVersion, false);
+    let receiver = mock_client
+        .send_request(
+            request,
+            Some(peer_network_id.peer_id()),
+            Some(peer_network_id.network_id()),
+        )
+        .await;
+    mock_client.wait_for_response(receiver).await
+}
+
 /// Waits for the request moderator to garbage collect the peer state
 async fn wait_for_request_moderator_to_garbage_collect(
     unhealthy_peer_states: Arc<DashMap<PeerNetworkId, UnhealthyPeerState>>,
@@ -11,7 +11,7 @@ use aptos_crypto::hash::HashValue;
 use aptos_storage_service_types::{
     requests::{DataRequest, StateValuesWithProofRequest},
     responses::{DataResponse, StorageServiceResponse},
-    StorageServiceError,
+    StorageServiceError, StorageServiceErrorCategory,
 };
 use aptos_types::{
     proof::definition::SparseMerkleRangeProof,
@@ -138,7 +138,7 @@ async fn test_get_states_with_proof_invalid() {
             get_state_values_with_proof(&mut mock_client, 0, start_index, end_index, false)
                 .await
                 .unwrap_err();
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
     }
 }
 
@@ -172,7 +172,7 @@ async fn test_get_states_with_proof_not_serviceable() {
                 .unwrap_err();
 
         // Verify the request is not serviceable
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
     }
 }
 
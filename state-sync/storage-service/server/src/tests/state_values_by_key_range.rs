@@ -0,0 +1,240 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{
+    mock,
+    mock::{MockClient, MockDatabaseReader},
+    utils,
+};
+use aptos_config::config::StorageServiceConfig;
+use aptos_crypto::hash::HashValue;
+use aptos_storage_interface::AptosDbError;
+use aptos_storage_service_types::{
+    requests::{DataRequest, StateValuesByKeyRangeWithProofRequest},
+    responses::{DataResponse, StorageServiceResponse},
+    StorageServiceError, StorageServiceErrorCategory,
+};
+use aptos_types::{
+    proof::definition::SparseMerkleRangeProof, state_store::state_value::StateValueChunkWithProof,
+};
+use claims::assert_matches;
+use mockall::predicate::eq;
+
+#[tokio::test]
+async fn test_get_states_by_key_range_with_proof() {
+    // Test small and large chunk requests
+    let max_state_chunk_size = StorageServiceConfig::default().max_state_chunk_size;
+    for max_items in [1, 100, max_state_chunk_size] {
+        for use_compression in [true, false] {
+            // Create test data
+            let version = 101;
+            let start_key_hash = HashValue::random();
+            let state_value_chunk_with_proof =
+                create_state_value_chunk_with_proof(start_key_hash, max_items);
+
+            // Create the mock db reader
+            let mut db_reader = mock::create_mock_db_reader();
+            expect_get_state_values_by_key_range_with_proof(
+                &mut db_reader,
+                version,
+                start_key_hash,
+                max_items,
+                state_value_chunk_with_proof.clone(),
+            );
+
+            // Create the storage client and server
+            let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+            utils::update_storage_server_summary(&mut service, version, 10);
+            tokio::spawn(service.start());
+
+            // Process a request to fetch a states chunk with a proof
+            let response = get_state_values_by_key_range_with_proof(
+                &mut mock_client,
+                version,
+                start_key_hash,
+                max_items,
+                use_compression,
+            )
+            .await
+            .unwrap();
+
+            // Verify the response is correct
+            assert_eq!(
+                response.get_data_response().unwrap(),
+                DataResponse::StateValueChunkWithProof(state_value_chunk_with_proof)
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_get_states_by_key_range_with_proof_chunk_limit() {
+    // Create test data
+    let max_state_chunk_size = StorageServiceConfig::default().max_state_chunk_size;
+    let max_items = max_state_chunk_size * 10; // Set a chunk request larger than the max
+    let version = 101;
+    let start_key_hash = HashValue::random();
+    let state_value_chunk_with_proof =
+        create_state_value_chunk_with_proof(start_key_hash, max_state_chunk_size);
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    expect_get_state_values_by_key_range_with_proof(
+        &mut db_reader,
+        version,
+        start_key_hash,
+        max_state_chunk_size,
+        state_value_chunk_with_proof.clone(),
+    );
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch a states chunk with a proof
+    let response = get_state_values_by_key_range_with_proof(
+        &mut mock_client,
+        version,
+        start_key_hash,
+        max_items,
+        false,
+    )
+    .await
+    .unwrap();
+
+    // Verify the response is correct
+    assert_matches!(response, StorageServiceResponse::RawResponse(_));
+    assert_eq!(
+        response.get_data_response().unwrap(),
+        DataResponse::StateValueChunkWithProof(state_value_chunk_with_proof)
+    );
+}
+
+#[tokio::test]
+async fn test_get_states_by_key_range_with_proof_invalid() {
+    // Create the storage client and server
+    let version = 101;
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch zero state values
+    let response = get_state_values_by_key_range_with_proof(
+        &mut mock_client,
+        version,
+        HashValue::random(),
+        0,
+        false,
+    )
+    .await
+    .unwrap_err();
+
+    // Verify the request is invalid
+    assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
+}
+
+#[tokio::test]
+async fn test_get_states_by_key_range_with_proof_past_end() {
+    // Create the mock db reader (the start key hash is past the last state key)
+    let version = 101;
+    let start_key_hash = HashValue::random();
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_state_value_chunk_by_key_range_with_proof()
+        .times(1)
+        .with(eq(version), eq(start_key_hash), eq(100))
+        .returning(|_, _, _| Err(AptosDbError::RangeStartsPastEnd("test".into())));
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch a states chunk with a proof
+    let response = get_state_values_by_key_range_with_proof(
+        &mut mock_client,
+        version,
+        start_key_hash,
+        100,
+        false,
+    )
+    .await
+    .unwrap_err();
+
+    // Verify the request is invalid (rather than a retryable storage error)
+    assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
+}
+
+#[tokio::test]
+async fn test_get_states_by_key_range_with_proof_not_serviceable() {
+    // Create the storage client and server (that cannot service the request)
+    let version = 101;
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, version - 1, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch a states chunk with a proof
+    let response = get_state_values_by_key_range_with_proof(
+        &mut mock_client,
+        version,
+        HashValue::random(),
+        100,
+        false,
+    )
+    .await
+    .unwrap_err();
+
+    // Verify the request is not serviceable
+    assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
+}
+
+/// Creates a state value chunk with proof that starts at the given key hash
+fn create_state_value_chunk_with_proof(
+    start_key_hash: HashValue,
+    num_state_values: u64,
+) -> StateValueChunkWithProof {
+    let first_index = 100;
+    StateValueChunkWithProof {
+        first_index,
+        last_index: first_index + num_state_values - 1,
+        first_key: start_key_hash,
+        last_key: HashValue::random(),
+        raw_values: vec![],
+        proof: SparseMerkleRangeProof::new(vec![]),
+        root_hash: HashValue::random(),
+    }
+}
+
+/// Sets an expectation on the given mock db for a call to fetch
+/// state values (by key range) with proof.
+fn expect_get_state_values_by_key_range_with_proof(
+    mock_db: &mut MockDatabaseReader,
+    version: u64,
+    start_key_hash: HashValue,
+    chunk_size: u64,
+    state_value_chunk_with_proof: StateValueChunkWithProof,
+) {
+    mock_db
+        .expect_get_state_value_chunk_by_key_range_with_proof()
+        .times(1)
+        .with(eq(version), eq(start_key_hash), eq(chunk_size as usize))
+        .returning(move |_, _, _| Ok(state_value_chunk_with_proof.clone()));
+}
+
+/// Sends a state values by key range with proof request and processes the response
+async fn get_state_values_by_key_range_with_proof(
+    mock_client: &mut MockClient,
+    version: u64,
+    start_key_hash: HashValue,
+    max_items: u64,
+    use_compression: bool,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let data_request =
+        DataRequest::GetStateValuesByKeyRangeWithProof(StateValuesByKeyRangeWithProofRequest {
+            version,
+            start_key_hash,
+            max_items,
+        });
+    utils::send_storage_request(mock_client, use_compression, data_request).await
+}
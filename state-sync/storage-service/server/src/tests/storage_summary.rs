@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    chunk_sizing::AdaptiveChunkSizer,
+    config_handle::StorageServiceConfigHandle,
     refresh_cached_storage_summary,
-    storage::StorageReader,
+    storage::{StorageReader, StorageReaderInterface},
+    summary_freshness::StorageSummaryFreshness,
     tests::{
         mock,
         mock::{MockClient, MockDatabaseReader},
@@ -12,17 +15,25 @@ use crate::{
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::config::StorageServiceConfig;
+use aptos_crypto::HashValue;
 use aptos_storage_service_notifications::StorageServiceNotificationSender;
 use aptos_storage_service_types::{
     requests::DataRequest,
     responses::{
-        CompleteDataRange, DataResponse, DataSummary, ProtocolMetadata, StorageServerSummary,
-        StorageServiceResponse,
+        CompleteDataRange, DataResponse, DataSummary, InternalIndexerSummary, ProtocolMetadata,
+        PruneWindowForecast, StorageServerSummary, StorageServiceResponse,
     },
     StorageServiceError,
 };
-use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
+use aptos_time_service::TimeService;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::Version,
+};
 use arc_swap::ArcSwap;
+use claims::assert_matches;
 use futures::StreamExt;
 use std::{ops::Deref, sync::Arc, time::Duration};
 use tokio::time::timeout;
@@ -62,6 +73,8 @@ async fn test_refresh_cached_storage_summary() {
         cached_storage_server_summary.clone(),
         storage_reader.clone(),
         storage_service_config,
+        &mut AdaptiveChunkSizer::new(),
+        &StorageSummaryFreshness::new(TimeService::mock()),
         vec![cached_summary_update_notifier.clone()],
     );
 
@@ -102,6 +115,8 @@ async fn test_refresh_cached_storage_summary() {
         cached_storage_server_summary.clone(),
         storage_reader.clone(),
         storage_service_config,
+        &mut AdaptiveChunkSizer::new(),
+        &StorageSummaryFreshness::new(TimeService::mock()),
         vec![cached_summary_update_notifier.clone()],
     );
 
@@ -129,6 +144,8 @@ async fn test_refresh_cached_storage_summary() {
         cached_storage_server_summary.clone(),
         storage_reader.clone(),
         storage_service_config,
+        &mut AdaptiveChunkSizer::new(),
+        &StorageSummaryFreshness::new(TimeService::mock()),
         vec![cached_summary_update_notifier.clone()],
     );
 
@@ -262,6 +279,293 @@ async fn test_get_storage_server_summary_notification() {
     }
 }
 
+#[tokio::test]
+async fn test_get_storage_server_summary_config_update() {
+    // Create test data
+    let highest_version = 506;
+    let highest_epoch = 30;
+    let highest_ledger_info =
+        utils::create_test_ledger_info_with_sigs(highest_epoch, highest_version);
+
+    // Create the mock db reader
+    let db_reader = create_db_reader_with_expectations(101, 50, highest_ledger_info);
+
+    // Create the storage client and server
+    let (mut mock_client, service, _, mock_time, _) = MockClient::new(Some(db_reader), None);
+    let config_handle = service.get_config_handle();
+    tokio::spawn(service.start());
+
+    // Fetch the storage summary to ensure the server is running
+    get_storage_server_summary(&mut mock_client, true)
+        .await
+        .unwrap();
+
+    // Update the chunk sizes in the storage service config
+    let storage_service_config = StorageServiceConfig {
+        max_epoch_chunk_size: 11,
+        max_state_chunk_size: 22,
+        max_transaction_chunk_size: 33,
+        max_transaction_output_chunk_size: 44,
+        ..config_handle.get_config()
+    };
+    config_handle.update_config(storage_service_config);
+
+    // Elapse enough time to force a cache update
+    utils::advance_storage_refresh_time(&mock_time).await;
+
+    // Fetch the storage summary and verify the new chunk sizes are advertised
+    let response = get_storage_server_summary(&mut mock_client, true)
+        .await
+        .unwrap();
+    let storage_server_summary = match response.get_data_response().unwrap() {
+        DataResponse::StorageServerSummary(storage_server_summary) => storage_server_summary,
+        data_response => panic!("Unexpected data response: {:?}", data_response),
+    };
+    assert_eq!(storage_server_summary.protocol_metadata, ProtocolMetadata {
+        max_epoch_chunk_size: 11,
+        max_state_chunk_size: 22,
+        max_transaction_chunk_size: 33,
+        max_transaction_output_chunk_size: 44,
+    });
+}
+
+#[test]
+fn test_get_prune_window_forecast() {
+    // Create test data (the node commits 100 versions per second)
+    let lowest_version = 2_000;
+    let highest_version = 10_000;
+    let ledger_prune_window = 100_000;
+    let state_prune_window = 50_000;
+    let forecast_secs = 3600;
+
+    // Create the storage reader
+    let db_reader = create_db_reader_with_forecast_expectations(
+        lowest_version,
+        highest_version,
+        ledger_prune_window,
+        state_prune_window,
+    );
+    let storage_service_config = StorageServiceConfig {
+        prune_window_forecast_secs: forecast_secs,
+        ..Default::default()
+    };
+    let config_handle = StorageServiceConfigHandle::new(storage_service_config);
+    let storage_reader =
+        StorageReader::new_with_config_handle(config_handle.clone(), Arc::new(db_reader));
+
+    // Fetch the data summary and forecast the prune windows
+    let data_summary = storage_reader.get_data_summary().unwrap();
+    let prune_window_forecast = storage_reader
+        .get_prune_window_forecast(&data_summary)
+        .unwrap();
+
+    // Verify the forecast
+    assert_eq!(
+        prune_window_forecast,
+        Some(create_expected_prune_window_forecast(
+            highest_version,
+            ledger_prune_window,
+            state_prune_window,
+            forecast_secs,
+        ))
+    );
+
+    // Verify no forecast is returned for an empty data summary
+    let prune_window_forecast = storage_reader
+        .get_prune_window_forecast(&DataSummary::default())
+        .unwrap();
+    assert_eq!(prune_window_forecast, None);
+
+    // Disable the forecast and verify it is no longer returned
+    config_handle.update_config(StorageServiceConfig {
+        prune_window_forecast_secs: 0,
+        ..storage_service_config
+    });
+    let prune_window_forecast = storage_reader
+        .get_prune_window_forecast(&data_summary)
+        .unwrap();
+    assert_eq!(prune_window_forecast, None);
+}
+
+#[tokio::test]
+async fn test_get_prune_window_forecast_request() {
+    // Create test data (the node commits 100 versions per second)
+    let lowest_version = 500;
+    let highest_version = 20_000;
+    let ledger_prune_window = 50_000;
+    let state_prune_window = 20_000;
+    let forecast_secs = 600;
+
+    // Create the storage client and server
+    let db_reader = create_db_reader_with_forecast_expectations(
+        lowest_version,
+        highest_version,
+        ledger_prune_window,
+        state_prune_window,
+    );
+    let storage_service_config = StorageServiceConfig {
+        prune_window_forecast_secs: forecast_secs,
+        ..Default::default()
+    };
+    let (mut mock_client, service, _, _, _) =
+        MockClient::new(Some(db_reader), Some(storage_service_config));
+    tokio::spawn(service.start());
+
+    // Verify the storage server summary doesn't carry the forecast
+    let response = get_storage_server_summary(&mut mock_client, true)
+        .await
+        .unwrap();
+    assert_matches!(
+        response.get_data_response().unwrap(),
+        DataResponse::StorageServerSummary(_)
+    );
+
+    // Fetch the prune window forecast and verify the response
+    for use_compression in [true, false] {
+        let response = utils::send_storage_request(
+            &mut mock_client,
+            use_compression,
+            DataRequest::GetPruneWindowForecast,
+        )
+        .await
+        .unwrap();
+        let prune_window_forecast = Option::<PruneWindowForecast>::try_from(response).unwrap();
+        assert_eq!(
+            prune_window_forecast,
+            Some(create_expected_prune_window_forecast(
+                highest_version,
+                ledger_prune_window,
+                state_prune_window,
+                forecast_secs,
+            ))
+        );
+    }
+}
+
+#[test]
+fn test_get_internal_indexer_summary() {
+    // Create test data
+    let lowest_version = 500;
+    let highest_version = 1000;
+    let event_by_type_start_version = 200;
+    let highest_ledger_info = utils::create_test_ledger_info_with_sigs(10, highest_version);
+
+    // Test the different indexer states
+    for (indexer_next_version, expected_internal_indexer) in [
+        // The indexer is disabled
+        (None, None),
+        // Nothing has been indexed
+        (Some(0), Some(InternalIndexerSummary::default())),
+        // The indexer is behind the ledger
+        (
+            Some(800),
+            Some(InternalIndexerSummary {
+                table_infos: Some(CompleteDataRange::from_genesis(799)),
+                events_by_type: Some(CompleteDataRange::new(lowest_version, 799).unwrap()),
+            }),
+        ),
+        // The indexer is ahead of the ledger
+        (
+            Some(highest_version + 10),
+            Some(InternalIndexerSummary {
+                table_infos: Some(CompleteDataRange::from_genesis(highest_version)),
+                events_by_type: Some(
+                    CompleteDataRange::new(lowest_version, highest_version).unwrap(),
+                ),
+            }),
+        ),
+        // The indexed events have all been pruned
+        (
+            Some(400),
+            Some(InternalIndexerSummary {
+                table_infos: Some(CompleteDataRange::from_genesis(399)),
+                events_by_type: None,
+            }),
+        ),
+    ] {
+        // Create the mock db reader
+        let db_reader = create_db_reader_with_indexer_expectations(
+            lowest_version,
+            100,
+            highest_ledger_info.clone(),
+            indexer_next_version,
+            indexer_next_version.map(|_| event_by_type_start_version),
+        );
+
+        // Fetch the data summary and verify the indexer summary
+        let storage_reader =
+            StorageReader::new(StorageServiceConfig::default(), Arc::new(db_reader));
+        let data_summary = storage_reader.get_data_summary().unwrap();
+        let internal_indexer_summary = storage_reader
+            .get_internal_indexer_summary(&data_summary)
+            .unwrap();
+        assert_eq!(internal_indexer_summary, expected_internal_indexer);
+
+        // Verify no indexer summary is returned for an empty data summary
+        let internal_indexer_summary = storage_reader
+            .get_internal_indexer_summary(&DataSummary::default())
+            .unwrap();
+        assert_eq!(internal_indexer_summary, None);
+    }
+}
+
+#[tokio::test]
+async fn test_get_internal_indexer_summary_request() {
+    // Create test data
+    let lowest_version = 100;
+    let highest_version = 900;
+    let highest_epoch = 5;
+    let event_by_type_start_version = 50;
+    let highest_ledger_info =
+        utils::create_test_ledger_info_with_sigs(highest_epoch, highest_version);
+
+    // Create the storage client and server (the indexer is up to date)
+    let db_reader = create_db_reader_with_indexer_expectations(
+        lowest_version,
+        100,
+        highest_ledger_info.clone(),
+        Some(highest_version + 1),
+        Some(event_by_type_start_version),
+    );
+    let (mut mock_client, service, _, _, _) = MockClient::new(Some(db_reader), None);
+    tokio::spawn(service.start());
+
+    // Verify the storage server summary matches the expected (unchanged) format
+    let response = get_storage_server_summary(&mut mock_client, true)
+        .await
+        .unwrap();
+    verify_server_summary_response(
+        highest_version,
+        highest_epoch,
+        lowest_version,
+        100,
+        highest_ledger_info,
+        response,
+    );
+
+    // Fetch the internal indexer summary and verify the response
+    for use_compression in [true, false] {
+        let response = utils::send_storage_request(
+            &mut mock_client,
+            use_compression,
+            DataRequest::GetInternalIndexerSummary,
+        )
+        .await
+        .unwrap();
+        let internal_indexer_summary =
+            Option::<InternalIndexerSummary>::try_from(response).unwrap();
+        assert_eq!(
+            internal_indexer_summary,
+            Some(InternalIndexerSummary {
+                table_infos: Some(CompleteDataRange::from_genesis(highest_version)),
+                events_by_type: Some(
+                    CompleteDataRange::new(lowest_version, highest_version).unwrap()
+                ),
+            })
+        );
+    }
+}
+
 /// Creates a mock database reader with the necessary
 /// expectations to satisfy the storage server summary request.
 fn create_db_reader_with_expectations(
@@ -291,6 +595,103 @@ fn create_db_reader_with_expectations(
     db_reader
 }
 
+/// Creates a mock database reader with the necessary expectations to
+/// satisfy the storage server summary and internal indexer summary
+/// requests (the indexer is disabled if the next version is None).
+fn create_db_reader_with_indexer_expectations(
+    lowest_version: Version,
+    state_prune_window: usize,
+    highest_ledger_info: LedgerInfoWithSignatures,
+    indexer_next_version: Option<Version>,
+    event_by_type_start_version: Option<Version>,
+) -> MockDatabaseReader {
+    let mut db_reader =
+        create_db_reader_with_expectations(lowest_version, state_prune_window, highest_ledger_info);
+    db_reader
+        .expect_get_indexer_next_version()
+        .returning(move || Ok(indexer_next_version));
+    db_reader
+        .expect_get_event_by_type_index_start_version()
+        .returning(move || Ok(event_by_type_start_version));
+    db_reader
+}
+
+/// Creates a mock db reader for forecasting the prune windows. The
+/// node is expected to commit 100 versions per second (starting at
+/// the lowest version, at 10 seconds).
+fn create_db_reader_with_forecast_expectations(
+    lowest_version: Version,
+    highest_version: Version,
+    ledger_prune_window: usize,
+    state_prune_window: usize,
+) -> MockDatabaseReader {
+    // Create a ledger info with a timestamp
+    let lowest_timestamp_usecs = 10_000_000;
+    let highest_timestamp_usecs =
+        lowest_timestamp_usecs + (highest_version - lowest_version) * 10_000;
+    let block_info = BlockInfo::new(
+        10,
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        highest_version,
+        highest_timestamp_usecs,
+        None,
+    );
+    let highest_ledger_info = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(block_info, HashValue::zero()),
+        AggregateSignature::empty(),
+    );
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_latest_ledger_info()
+        .returning(move || Ok(highest_ledger_info.clone()));
+    db_reader
+        .expect_get_first_txn_version()
+        .returning(move || Ok(Some(lowest_version)));
+    db_reader
+        .expect_get_first_write_set_version()
+        .returning(move || Ok(Some(lowest_version)));
+    db_reader
+        .expect_get_block_timestamp()
+        .withf(move |version| *version == lowest_version)
+        .returning(move |_| Ok(lowest_timestamp_usecs));
+    db_reader
+        .expect_is_state_merkle_pruner_enabled()
+        .returning(move || Ok(true));
+    db_reader
+        .expect_get_epoch_snapshot_prune_window()
+        .returning(move || Ok(state_prune_window));
+    db_reader
+        .expect_is_ledger_pruner_enabled()
+        .returning(move || Ok(true));
+    db_reader
+        .expect_get_ledger_prune_window()
+        .returning(move || Ok(ledger_prune_window));
+
+    db_reader
+}
+
+/// Creates the expected prune window forecast for a node that
+/// commits 100 versions per second (and is at the highest version).
+fn create_expected_prune_window_forecast(
+    highest_version: Version,
+    ledger_prune_window: usize,
+    state_prune_window: usize,
+    forecast_secs: u64,
+) -> PruneWindowForecast {
+    let forecast_highest_version = highest_version + 100 * forecast_secs;
+    let lowest_ledger_version = forecast_highest_version - ledger_prune_window as u64 + 1;
+    PruneWindowForecast {
+        duration_secs: forecast_secs,
+        lowest_transaction_version: Some(lowest_ledger_version),
+        lowest_transaction_output_version: Some(lowest_ledger_version),
+        lowest_state_version: Some(forecast_highest_version - state_prune_window as u64 + 1),
+    }
+}
+
 /// Sends a storage summary request and processes the response
 async fn get_storage_server_summary(
     mock_client: &mut MockClient,
@@ -7,6 +7,10 @@ use aptos_config::{
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_network::protocols::network::RpcError;
+use aptos_storage_service_types::{
+    requests::{DataRequest, StorageServiceRequest},
+    StorageServiceErrorCategory,
+};
 use aptos_types::{
     epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures,
     transaction::TransactionListWithProof, PeerId,
@@ -218,6 +222,189 @@ async fn test_subscribe_transactions_epoch_change() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_subscribe_transactions_fanout() {
+    // Test event inclusion
+    for include_events in [true, false] {
+        // Create test data
+        let highest_version = 45576;
+        let highest_epoch = 43;
+        let lowest_version = 4566;
+        let peer_version = highest_version - 100;
+        let highest_ledger_info =
+            utils::create_test_ledger_info_with_sigs(highest_epoch, highest_version);
+        let transaction_list_with_proof = utils::create_transaction_list_with_proof(
+            peer_version + 1,
+            highest_version,
+            highest_version,
+            include_events,
+        );
+
+        // Create the mock db reader (the data should only be fetched once)
+        let mut db_reader =
+            mock::create_mock_db_with_summary_updates(highest_ledger_info.clone(), lowest_version);
+        utils::expect_get_transactions(
+            &mut db_reader,
+            peer_version + 1,
+            highest_version - peer_version,
+            highest_version,
+            include_events,
+            transaction_list_with_proof.clone(),
+        );
+
+        // Create the storage client and server
+        let (mut mock_client, service, storage_service_notifier, mock_time, _) =
+            MockClient::new(Some(db_reader), None);
+        let active_subscriptions = service.get_subscriptions();
+        tokio::spawn(service.start());
+
+        // Send identical subscription requests for several peers
+        let num_peers = 5;
+        let mut response_receivers = vec![];
+        for _ in 0..num_peers {
+            let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+            let response_receiver = utils::subscribe_to_transactions_for_peer(
+                &mut mock_client,
+                peer_version,
+                highest_epoch,
+                include_events,
+                utils::get_random_u64(),
+                0,
+                Some(peer_network_id),
+            )
+            .await;
+            response_receivers.push(response_receiver);
+        }
+
+        // Wait until the subscriptions are active
+        utils::wait_for_active_subscriptions(active_subscriptions.clone(), num_peers).await;
+
+        // Force the subscription handler to work
+        utils::force_subscription_handler_to_run(
+            &mut mock_client,
+            &mock_time,
+            &storage_service_notifier,
+        )
+        .await;
+
+        // Verify all peers receive the same data
+        for response_receiver in response_receivers {
+            utils::verify_new_transactions_with_proof(
+                &mut mock_client,
+                response_receiver,
+                transaction_list_with_proof.clone(),
+                highest_ledger_info.clone(),
+            )
+            .await;
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_subscribe_transactions_fanout_bandwidth_quota() {
+    // Create a storage service config with a tiny bandwidth quota
+    let storage_service_config = StorageServiceConfig {
+        enable_peer_bandwidth_quotas: true,
+        max_peer_bandwidth_bytes_per_window: 1,
+        peer_bandwidth_window_secs: 60 * 60,
+        request_moderator_refresh_interval_ms: 60 * 60 * 1000,
+        ..StorageServiceConfig::default()
+    };
+
+    // Create test data
+    let highest_version = 45576;
+    let highest_epoch = 43;
+    let lowest_version = 4566;
+    let peer_version = highest_version - 100;
+    let highest_ledger_info =
+        utils::create_test_ledger_info_with_sigs(highest_epoch, highest_version);
+    let transaction_list_with_proof = utils::create_transaction_list_with_proof(
+        peer_version + 1,
+        highest_version,
+        highest_version,
+        false,
+    );
+
+    // Create the mock db reader (the data should only be fetched once)
+    let mut db_reader =
+        mock::create_mock_db_with_summary_updates(highest_ledger_info.clone(), lowest_version);
+    utils::expect_get_transactions(
+        &mut db_reader,
+        peer_version + 1,
+        highest_version - peer_version,
+        highest_version,
+        false,
+        transaction_list_with_proof.clone(),
+    );
+
+    // Create the storage client and server
+    let (mut mock_client, service, storage_service_notifier, mock_time, _) =
+        MockClient::new(Some(db_reader), Some(storage_service_config));
+    let active_subscriptions = service.get_subscriptions();
+    tokio::spawn(service.start());
+
+    // Exhaust the bandwidth quota of a PFN
+    let pfn_peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    let request = StorageServiceRequest::new(DataRequest::GetServerProtocolVersion, false);
+    let response_receiver = mock_client
+        .send_request(
+            request,
+            Some(pfn_peer_network_id.peer_id()),
+            Some(pfn_peer_network_id.network_id()),
+        )
+        .await;
+    mock_client
+        .wait_for_response(response_receiver)
+        .await
+        .unwrap();
+
+    // Send identical subscription requests for the PFN and a VFN
+    let vfn_peer_network_id = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    let mut response_receivers = vec![];
+    for peer_network_id in [pfn_peer_network_id, vfn_peer_network_id] {
+        let response_receiver = utils::subscribe_to_transactions_for_peer(
+            &mut mock_client,
+            peer_version,
+            highest_epoch,
+            false,
+            utils::get_random_u64(),
+            0,
+            Some(peer_network_id),
+        )
+        .await;
+        response_receivers.push(response_receiver);
+    }
+
+    // Wait until the subscriptions are active
+    utils::wait_for_active_subscriptions(active_subscriptions.clone(), 2).await;
+
+    // Force the subscription handler to work
+    utils::force_subscription_handler_to_run(
+        &mut mock_client,
+        &mock_time,
+        &storage_service_notifier,
+    )
+    .await;
+
+    // Verify the PFN is charged for the fanned-out data and throttled
+    let vfn_response_receiver = response_receivers.pop().unwrap();
+    let pfn_response_receiver = response_receivers.pop().unwrap();
+    let response = mock_client.wait_for_response(pfn_response_receiver).await;
+    assert_eq!(
+        response.unwrap_err().category(),
+        StorageServiceErrorCategory::Retryable
+    );
+
+    // Verify the VFN still receives the new data
+    utils::verify_new_transactions_with_proof(
+        &mut mock_client,
+        vfn_response_receiver,
+        transaction_list_with_proof,
+        highest_ledger_info,
+    )
+    .await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_subscribe_transactions_max_chunk() {
     // Create a storage service config with a configured max chunk size
@@ -5,9 +5,11 @@ use crate::{
     error::Error,
     moderator::RequestModerator,
     network::ResponseSender,
+    response_cache::ResponseCache,
     storage::StorageReader,
     subscription,
     subscription::{SubscriptionRequest, SubscriptionStreamRequests},
+    summary_freshness::StorageSummaryFreshness,
     tests::{mock, mock::MockClient, utils},
 };
 use aptos_bounded_executor::BoundedExecutor;
@@ -22,7 +24,7 @@ use aptos_storage_service_types::{
         SubscriptionStreamMetadata,
     },
     responses::StorageServerSummary,
-    StorageServiceError,
+    StorageServiceErrorCategory,
 };
 use aptos_time_service::TimeService;
 use aptos_types::epoch_change::EpochChangeProof;
@@ -30,7 +32,6 @@ use arc_swap::ArcSwap;
 use claims::assert_matches;
 use dashmap::DashMap;
 use futures::channel::oneshot;
-use mini_moka::sync::Cache;
 use std::sync::Arc;
 use tokio::runtime::Handle;
 
@@ -78,7 +79,7 @@ async fn test_peers_with_ready_subscriptions() {
     let cached_storage_server_summary =
         Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
     let optimistic_fetches = Arc::new(DashMap::new());
-    let lru_response_cache = Cache::new(0);
+    let lru_response_cache = ResponseCache::new(0);
     let request_moderator = Arc::new(RequestModerator::new(
         AptosDataClientConfig::default(),
         cached_storage_server_summary.clone(),
@@ -198,7 +199,7 @@ async fn test_remove_expired_subscriptions_no_new_data() {
     let cached_storage_server_summary =
         Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
     let optimistic_fetches = Arc::new(DashMap::new());
-    let lru_response_cache = Cache::new(0);
+    let lru_response_cache = ResponseCache::new(0);
     let request_moderator = Arc::new(RequestModerator::new(
         AptosDataClientConfig::default(),
         cached_storage_server_summary.clone(),
@@ -330,7 +331,7 @@ async fn test_remove_expired_subscriptions_blocked_stream() {
     let cached_storage_server_summary =
         Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
     let optimistic_fetches = Arc::new(DashMap::new());
-    let lru_response_cache = Cache::new(0);
+    let lru_response_cache = ResponseCache::new(0);
     let request_moderator = Arc::new(RequestModerator::new(
         AptosDataClientConfig::default(),
         cached_storage_server_summary.clone(),
@@ -355,6 +356,7 @@ async fn test_remove_expired_subscriptions_blocked_stream() {
         lru_response_cache.clone(),
         request_moderator.clone(),
         storage_reader.clone(),
+        StorageSummaryFreshness::new(time_service.clone()),
         subscriptions.clone(),
         time_service.clone(),
     )
@@ -431,7 +433,7 @@ async fn test_remove_expired_subscriptions_blocked_stream_index() {
     let cached_storage_server_summary =
         Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
     let optimistic_fetches = Arc::new(DashMap::new());
-    let lru_response_cache = Cache::new(0);
+    let lru_response_cache = ResponseCache::new(0);
     let request_moderator = Arc::new(RequestModerator::new(
         AptosDataClientConfig::default(),
         cached_storage_server_summary.clone(),
@@ -575,6 +577,108 @@ async fn test_remove_expired_subscriptions_blocked_stream_index() {
     assert!(subscriptions.contains_key(&peer_network_ids[0]));
 }
 
+#[tokio::test]
+async fn test_stale_storage_summary_pauses_subscriptions() {
+    // Create a storage service config
+    let max_storage_summary_staleness_ms = 1000;
+    let max_subscription_period_ms = 100_000;
+    let storage_service_config = StorageServiceConfig {
+        max_storage_summary_staleness_ms,
+        max_subscription_period_ms,
+        ..Default::default()
+    };
+
+    // Create a mock time service and the storage summary freshness tracker
+    let time_service = TimeService::mock();
+    let storage_summary_freshness = StorageSummaryFreshness::new(time_service.clone());
+
+    // Create a batch of test subscriptions
+    let num_subscriptions_in_batch = 10;
+    let subscriptions = Arc::new(DashMap::new());
+    for i in 0..num_subscriptions_in_batch {
+        let subscription_stream_requests = create_subscription_stream_requests(
+            time_service.clone(),
+            Some(1),
+            Some(1),
+            Some(i as u64),
+            Some(0),
+        );
+        subscriptions.insert(PeerNetworkId::random(), subscription_stream_requests);
+    }
+
+    // Create test data with an empty storage server summary
+    let bounded_executor = BoundedExecutor::new(100, Handle::current());
+    let cached_storage_server_summary =
+        Arc::new(ArcSwap::from(Arc::new(StorageServerSummary::default())));
+    let optimistic_fetches = Arc::new(DashMap::new());
+    let lru_response_cache = ResponseCache::new(0);
+    let request_moderator = Arc::new(RequestModerator::new(
+        AptosDataClientConfig::default(),
+        cached_storage_server_summary.clone(),
+        mock::create_peers_and_metadata(vec![]),
+        StorageServiceConfig::default(),
+        time_service.clone(),
+    ));
+    let storage_reader = StorageReader::new(
+        storage_service_config,
+        Arc::new(mock::create_mock_db_reader()),
+    );
+
+    // Update the storage server summary so that there is new data (at version 5)
+    let _ = utils::update_storage_summary_cache(cached_storage_server_summary.clone(), 5, 1);
+
+    // Elapse enough time for the storage summary to become stale
+    let refresh_interval_ms = storage_service_config.storage_summary_refresh_interval_ms;
+    utils::elapse_time(
+        refresh_interval_ms + max_storage_summary_staleness_ms + 1,
+        &time_service,
+    )
+    .await;
+
+    // Handle the active subscriptions and verify that none were served
+    subscription::handle_active_subscriptions(
+        bounded_executor.clone(),
+        cached_storage_server_summary.clone(),
+        storage_service_config,
+        optimistic_fetches.clone(),
+        lru_response_cache.clone(),
+        request_moderator.clone(),
+        storage_reader.clone(),
+        storage_summary_freshness.clone(),
+        subscriptions.clone(),
+        time_service.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(subscriptions.len(), num_subscriptions_in_batch);
+    for subscription in subscriptions.iter() {
+        assert!(subscription.value().first_pending_request().is_some());
+    }
+
+    // Mark the storage summary as refreshed
+    storage_summary_freshness.mark_refreshed();
+
+    // Handle the active subscriptions and verify that they were all served
+    subscription::handle_active_subscriptions(
+        bounded_executor.clone(),
+        cached_storage_server_summary.clone(),
+        storage_service_config,
+        optimistic_fetches.clone(),
+        lru_response_cache.clone(),
+        request_moderator.clone(),
+        storage_reader.clone(),
+        storage_summary_freshness.clone(),
+        subscriptions.clone(),
+        time_service.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(subscriptions.len(), num_subscriptions_in_batch);
+    for subscription in subscriptions.iter() {
+        assert!(subscription.value().first_pending_request().is_none());
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_subscription_invalid_requests() {
     // Create a mock time service
@@ -756,7 +860,7 @@ async fn test_subscription_max_pending_requests() {
             .wait_for_response(response_receiver)
             .await
             .unwrap_err();
-        assert!(matches!(response, StorageServiceError::InvalidRequest(_)));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
     }
 
     // Verify the request indices that are pending
@@ -828,7 +932,7 @@ async fn test_subscription_max_pending_requests() {
             .wait_for_response(response_receiver)
             .await
             .unwrap_err();
-        assert!(matches!(response, StorageServiceError::InvalidRequest(_)));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
     }
 
     // Verify the request indices that are pending
@@ -0,0 +1,138 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{mock, mock::MockClient, utils};
+use aptos_crypto::HashValue;
+use aptos_storage_service_types::{responses::DataResponse, StorageServiceErrorCategory};
+use aptos_types::{
+    proof::{TransactionAccumulatorProof, TransactionInfoWithProof},
+    transaction::{ExecutionStatus, Transaction, TransactionInfo, TransactionWithProof},
+};
+use claims::assert_none;
+use mockall::predicate::eq;
+
+#[tokio::test]
+async fn test_get_transaction_by_hash_with_proof() {
+    // Test event inclusion
+    for include_events in [true, false] {
+        // Create test data
+        let transaction_hash = HashValue::random();
+        let proof_version = 1000;
+        let transaction_with_proof = create_transaction_with_proof(50, include_events);
+
+        // Create the mock db reader
+        let mut db_reader = mock::create_mock_db_reader();
+        let transaction_with_proof_clone = transaction_with_proof.clone();
+        db_reader
+            .expect_get_transaction_by_hash()
+            .times(1)
+            .with(eq(transaction_hash), eq(proof_version), eq(include_events))
+            .returning(move |_, _, _| Ok(Some(transaction_with_proof_clone.clone())));
+
+        // Create the storage client and server
+        let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+        utils::update_storage_server_summary(&mut service, proof_version, 10);
+        tokio::spawn(service.start());
+
+        // Create a request to fetch the transaction by hash
+        let response = utils::get_transaction_by_hash_with_proof(
+            &mut mock_client,
+            transaction_hash,
+            proof_version,
+            include_events,
+            true,
+        )
+        .await
+        .unwrap();
+
+        // Verify the response is correct
+        match response.get_data_response().unwrap() {
+            DataResponse::TransactionByHashWithProof(response_transaction) => {
+                assert_eq!(response_transaction, Some(transaction_with_proof))
+            },
+            _ => panic!("Expected a transaction by hash but got: {:?}", response),
+        };
+    }
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_hash_not_found() {
+    // Create test data
+    let transaction_hash = HashValue::random();
+    let proof_version = 1000;
+
+    // Create the mock db reader (the transaction doesn't exist)
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_transaction_by_hash()
+        .times(1)
+        .with(eq(transaction_hash), eq(proof_version), eq(true))
+        .returning(|_, _, _| Ok(None));
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, proof_version, 10);
+    tokio::spawn(service.start());
+
+    // Create a request to fetch the transaction by hash
+    let response = utils::get_transaction_by_hash_with_proof(
+        &mut mock_client,
+        transaction_hash,
+        proof_version,
+        true,
+        true,
+    )
+    .await
+    .unwrap();
+
+    // Verify that no transaction is returned
+    match response.get_data_response().unwrap() {
+        DataResponse::TransactionByHashWithProof(response_transaction) => {
+            assert_none!(response_transaction)
+        },
+        _ => panic!("Expected a transaction by hash but got: {:?}", response),
+    };
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_hash_not_serviceable() {
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, 100, 10);
+    tokio::spawn(service.start());
+
+    // Create a request to fetch a transaction with a proof version the server doesn't have
+    let response = utils::get_transaction_by_hash_with_proof(
+        &mut mock_client,
+        HashValue::random(),
+        200,
+        true,
+        true,
+    )
+    .await
+    .unwrap_err();
+
+    // Verify the request is not serviceable
+    assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
+}
+
+/// Creates a test transaction (with an empty proof) at the given version
+fn create_transaction_with_proof(version: u64, include_events: bool) -> TransactionWithProof {
+    let transaction_info = TransactionInfo::new(
+        HashValue::zero(),
+        HashValue::zero(),
+        HashValue::zero(),
+        None,
+        0,
+        ExecutionStatus::Success,
+    );
+    let proof =
+        TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), transaction_info);
+    let events = if include_events { Some(vec![]) } else { None };
+    TransactionWithProof::new(
+        version,
+        Transaction::StateCheckpoint(HashValue::random()),
+        events,
+        proof,
+    )
+}
@@ -6,9 +6,8 @@ use aptos_config::config::StorageServiceConfig;
 use aptos_storage_service_types::{
     requests::{DataRequest, TransactionOutputsWithProofRequest},
     responses::{DataResponse, StorageServiceResponse},
-    StorageServiceError,
+    StorageServiceError, StorageServiceErrorCategory,
 };
-use claims::assert_matches;
 use mockall::{predicate::eq, Sequence};
 
 #[tokio::test]
@@ -129,7 +128,7 @@ async fn test_get_transaction_outputs_with_proof_invalid() {
         )
         .await
         .unwrap_err();
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
     }
 }
 
@@ -168,7 +167,7 @@ async fn test_get_transaction_outputs_with_proof_not_serviceable() {
         .unwrap_err();
 
         // Verify the request is not serviceable
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
     }
 }
 
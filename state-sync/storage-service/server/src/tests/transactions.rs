@@ -3,8 +3,7 @@
 
 use crate::tests::{mock, mock::MockClient, utils};
 use aptos_config::config::StorageServiceConfig;
-use aptos_storage_service_types::{responses::DataResponse, StorageServiceError};
-use claims::assert_matches;
+use aptos_storage_service_types::{responses::DataResponse, StorageServiceErrorCategory};
 use mockall::{predicate::eq, Sequence};
 
 #[tokio::test]
@@ -138,7 +137,7 @@ async fn test_get_transactions_with_proof_invalid() {
         )
         .await
         .unwrap_err();
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
     }
 }
 
@@ -180,7 +179,7 @@ async fn test_get_transactions_with_proof_not_serviceable() {
             .unwrap_err();
 
             // Verify the request is not serviceable
-            assert_matches!(response, StorageServiceError::InvalidRequest(_));
+            assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
         }
     }
 }
@@ -6,10 +6,9 @@ use aptos_config::config::StorageServiceConfig;
 use aptos_storage_service_types::{
     requests::{DataRequest, TransactionsOrOutputsWithProofRequest},
     responses::{DataResponse, StorageServiceResponse},
-    StorageServiceError,
+    StorageServiceError, StorageServiceErrorCategory,
 };
 use aptos_types::transaction::{TransactionListWithProof, TransactionOutputListWithProof};
-use claims::assert_matches;
 use mockall::{predicate::eq, Sequence};
 
 #[tokio::test]
@@ -185,7 +184,7 @@ async fn test_get_transactions_or_outputs_with_proof_invalid() {
         )
         .await
         .unwrap_err();
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
     }
 }
 
@@ -226,7 +225,7 @@ async fn test_get_transactions_or_outputs_with_proof_not_serviceable() {
         .unwrap_err();
 
         // Verify the request is not serviceable
-        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+        assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
     }
 }
 
@@ -0,0 +1,193 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{mock, mock::MockClient, utils};
+use aptos_config::config::StorageServiceConfig;
+use aptos_storage_service_types::{responses::DataResponse, StorageServiceErrorCategory};
+
+#[tokio::test]
+async fn test_get_transactions_without_proof() {
+    // Test small and large chunk requests
+    let max_transaction_chunk_size = StorageServiceConfig::default().max_transaction_chunk_size;
+    for chunk_size in [1, 100, max_transaction_chunk_size] {
+        // Test event inclusion
+        for include_events in [true, false] {
+            // Create test data
+            let start_version = 0;
+            let end_version = start_version + chunk_size - 1;
+            let transaction_list_without_proof = utils::create_transaction_list_without_proof(
+                start_version,
+                end_version,
+                include_events,
+            );
+
+            // Create the mock db reader
+            let mut db_reader = mock::create_mock_db_reader();
+            utils::expect_get_transactions_without_proof(
+                &mut db_reader,
+                start_version,
+                chunk_size,
+                transaction_list_without_proof.clone(),
+            );
+
+            // Create the storage client and server (with transactions without proof enabled)
+            let storage_config = StorageServiceConfig {
+                enable_transactions_without_proof: true,
+                ..Default::default()
+            };
+            let (mut mock_client, mut service, _, _, _) =
+                MockClient::new(Some(db_reader), Some(storage_config));
+            utils::update_storage_server_summary(&mut service, end_version, 10);
+            tokio::spawn(service.start());
+
+            // Create a request to fetch transactions without a proof
+            let response = utils::get_transactions_without_proof(
+                &mut mock_client,
+                start_version,
+                end_version,
+                include_events,
+                true,
+            )
+            .await
+            .unwrap();
+
+            // Verify the response is correct
+            match response.get_data_response().unwrap() {
+                DataResponse::TransactionsWithoutProof(transactions_without_proof) => {
+                    assert_eq!(transactions_without_proof, transaction_list_without_proof)
+                },
+                _ => panic!(
+                    "Expected transactions without proof but got: {:?}",
+                    response
+                ),
+            };
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_get_transactions_without_proof_chunk_limit() {
+    // Test event inclusion
+    for include_events in [true, false] {
+        // Create test data
+        let max_transaction_chunk_size = StorageServiceConfig::default().max_transaction_chunk_size;
+        let chunk_size = max_transaction_chunk_size * 10; // Set a chunk request larger than the max
+        let start_version = 0;
+        let transaction_list_without_proof = utils::create_transaction_list_without_proof(
+            start_version,
+            start_version + max_transaction_chunk_size - 1,
+            include_events,
+        );
+
+        // Create the mock db reader (only the max chunk size should be read)
+        let mut db_reader = mock::create_mock_db_reader();
+        utils::expect_get_transactions_without_proof(
+            &mut db_reader,
+            start_version,
+            max_transaction_chunk_size,
+            transaction_list_without_proof.clone(),
+        );
+
+        // Create the storage client and server (with transactions without proof enabled)
+        let storage_config = StorageServiceConfig {
+            enable_transactions_without_proof: true,
+            ..Default::default()
+        };
+        let (mut mock_client, mut service, _, _, _) =
+            MockClient::new(Some(db_reader), Some(storage_config));
+        utils::update_storage_server_summary(&mut service, start_version + chunk_size, 10);
+        tokio::spawn(service.start());
+
+        // Create a request to fetch transactions without a proof
+        let response = utils::get_transactions_without_proof(
+            &mut mock_client,
+            start_version,
+            start_version + chunk_size - 1,
+            include_events,
+            true,
+        )
+        .await
+        .unwrap();
+
+        // Verify the response is correct
+        match response.get_data_response().unwrap() {
+            DataResponse::TransactionsWithoutProof(transactions_without_proof) => {
+                assert_eq!(transactions_without_proof, transaction_list_without_proof)
+            },
+            _ => panic!(
+                "Expected transactions without proof but got: {:?}",
+                response
+            ),
+        };
+    }
+}
+
+#[tokio::test]
+async fn test_get_transactions_without_proof_disabled() {
+    // Create the storage client and server (with transactions without proof disabled)
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, 1000, 10);
+    tokio::spawn(service.start());
+
+    // Test event inclusion
+    for include_events in [true, false] {
+        // Create a request to fetch transactions without a proof
+        let response =
+            utils::get_transactions_without_proof(&mut mock_client, 0, 100, include_events, true)
+                .await
+                .unwrap_err();
+
+        // Verify the request is rejected
+        assert_eq!(response.category(), StorageServiceErrorCategory::Permission);
+    }
+}
+
+#[tokio::test]
+async fn test_get_transactions_without_proof_invalid() {
+    // Create the storage client and server (with transactions without proof enabled)
+    let storage_config = StorageServiceConfig {
+        enable_transactions_without_proof: true,
+        ..Default::default()
+    };
+    let (mut mock_client, service, _, _, _) = MockClient::new(None, Some(storage_config));
+    tokio::spawn(service.start());
+
+    // Test invalid ranges
+    let start_version = 1000;
+    for end_version in [0, 999] {
+        let response = utils::get_transactions_without_proof(
+            &mut mock_client,
+            start_version,
+            end_version,
+            true,
+            true,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(response.category(), StorageServiceErrorCategory::Fatal);
+    }
+}
+
+#[tokio::test]
+async fn test_get_transactions_without_proof_not_serviceable() {
+    // Create the storage client and server (with transactions without proof enabled)
+    let storage_config = StorageServiceConfig {
+        enable_transactions_without_proof: true,
+        ..Default::default()
+    };
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, Some(storage_config));
+    utils::update_storage_server_summary(&mut service, 100, 10);
+    tokio::spawn(service.start());
+
+    // Test event inclusion
+    for include_events in [true, false] {
+        // Create a request to fetch transactions that are not held by the server
+        let response =
+            utils::get_transactions_without_proof(&mut mock_client, 50, 150, include_events, true)
+                .await
+                .unwrap_err();
+
+        // Verify the request is not serviceable
+        assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{mock, mock::MockClient, utils};
+use aptos_config::{config::StorageServiceConfig, network_id::NetworkId};
+use aptos_storage_service_types::{
+    requests::{DataRequest, StorageServiceRequest, TransactionsWithProofRequest},
+    responses::DataResponse,
+};
+use aptos_types::PeerId;
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn test_get_transactions_trusted_peer_chunk_limit() {
+    // Create test data
+    let storage_service_config = StorageServiceConfig::default();
+    let max_transaction_chunk_size = storage_service_config.max_transaction_chunk_size;
+    let trusted_chunk_size = max_transaction_chunk_size * 2; // Larger than the max, but within the trusted limit
+    let start_version = 0;
+    let proof_version = start_version + trusted_chunk_size - 1;
+    let trusted_transaction_list = utils::create_transaction_list_with_proof(
+        start_version,
+        proof_version,
+        proof_version,
+        true,
+    );
+    let untrusted_transaction_list = utils::create_transaction_list_with_proof(
+        start_version,
+        start_version + max_transaction_chunk_size - 1,
+        proof_version,
+        true,
+    );
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    utils::expect_get_transactions(
+        &mut db_reader,
+        start_version,
+        trusted_chunk_size,
+        proof_version,
+        true,
+        trusted_transaction_list.clone(),
+    );
+    utils::expect_get_transactions(
+        &mut db_reader,
+        start_version,
+        max_transaction_chunk_size,
+        proof_version,
+        true,
+        untrusted_transaction_list.clone(),
+    );
+
+    // Create the storage client and server (with a single trusted peer)
+    let trusted_peer = PeerId::random();
+    let (mut mock_client, mut service, _, _, _) = MockClient::new_with_trusted_peers(
+        Some(db_reader),
+        Some(storage_service_config),
+        HashSet::from([trusted_peer]),
+    );
+    utils::update_storage_server_summary(&mut service, proof_version, 10);
+    tokio::spawn(service.start());
+
+    // Send the same request from the trusted peer and an untrusted peer
+    let data_request = DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+        proof_version,
+        start_version,
+        end_version: proof_version,
+        include_events: true,
+    });
+    for (peer_id, expected_transaction_list) in [
+        (trusted_peer, trusted_transaction_list),
+        (PeerId::random(), untrusted_transaction_list),
+    ] {
+        let storage_request = StorageServiceRequest::new(data_request.clone(), true);
+        let receiver = mock_client
+            .send_request(storage_request, Some(peer_id), Some(NetworkId::Public))
+            .await;
+        let response = mock_client.wait_for_response(receiver).await.unwrap();
+
+        // Verify only the trusted peer is served more than the max chunk size
+        match response.get_data_response().unwrap() {
+            DataResponse::TransactionsWithProof(transactions_with_proof) => {
+                assert_eq!(transactions_with_proof, expected_transaction_list)
+            },
+            _ => panic!("Expected transactions with proof but got: {:?}", response),
+        };
+    }
+}
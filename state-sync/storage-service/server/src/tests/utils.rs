@@ -20,12 +20,16 @@ use aptos_storage_service_notifications::{
 };
 use aptos_storage_service_types::{
     requests::{
-        DataRequest, StateValuesWithProofRequest, StorageServiceRequest,
+        BlockMetadataRequest, DataRequest, StateValuesWithProofRequest, StorageServiceRequest,
         SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
-        SubscriptionStreamMetadata, TransactionsWithProofRequest,
+        SubscriptionStreamMetadata, TransactionByHashWithProofRequest,
+        TransactionsWithProofRequest, TransactionsWithoutProofRequest, VersionByTimestampRequest,
+    },
+    responses::{
+        CompleteDataRange, DataResponse, StorageServerSummary, StorageServiceResponse,
+        TransactionListWithoutProof,
     },
-    responses::{CompleteDataRange, DataResponse, StorageServerSummary, StorageServiceResponse},
     Epoch, StorageServiceError,
 };
 use aptos_time_service::{MockTimeService, TimeService};
@@ -39,7 +43,7 @@ use aptos_types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     on_chain_config::ValidatorSet,
     transaction::{
-        ExecutionStatus, RawTransaction, Script, SignedTransaction, Transaction,
+        ExecutionStatus, RawTransaction, Script, SignedTransaction, Transaction, TransactionInfo,
         TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
         TransactionPayload, TransactionStatus,
     },
@@ -278,6 +282,37 @@ pub fn create_transaction_list_with_proof(
     transaction_list_with_proof
 }
 
+/// Creates a test transaction list without proof
+pub fn create_transaction_list_without_proof(
+    start_version: u64,
+    end_version: u64,
+    include_events: bool,
+) -> TransactionListWithoutProof {
+    // Create the requested transactions and infos
+    let mut transactions = vec![];
+    let mut transaction_infos = vec![];
+    for sequence_number in start_version..=end_version {
+        transactions.push(create_test_transaction(sequence_number, vec![]));
+        transaction_infos.push(TransactionInfo::new(
+            HashValue::random(),
+            HashValue::zero(),
+            HashValue::zero(),
+            None,
+            sequence_number,
+            ExecutionStatus::Success,
+        ));
+    }
+
+    // Include events if required
+    let events = if include_events {
+        Some(vec![vec![]; transactions.len()])
+    } else {
+        None
+    };
+
+    TransactionListWithoutProof::new(Some(start_version), transactions, transaction_infos, events)
+}
+
 /// Creates a test transaction output
 fn create_test_transaction_output() -> TransactionOutput {
     TransactionOutput::new(
@@ -368,6 +403,34 @@ pub fn expect_get_transactions(
         .returning(move |_, _, _, _| Ok(transaction_list.clone()));
 }
 
+/// Sets an expectation on the given mock db for reading transactions without proof
+pub fn expect_get_transactions_without_proof(
+    mock_db: &mut MockDatabaseReader,
+    start_version: u64,
+    num_items: u64,
+    transaction_list: TransactionListWithoutProof,
+) {
+    let transactions = transaction_list.transactions;
+    mock_db
+        .expect_get_transaction_iterator()
+        .times(1)
+        .with(eq(start_version), eq(num_items))
+        .returning(move |_, _| Ok(Box::new(transactions.clone().into_iter().map(Ok))));
+    let transaction_infos = transaction_list.transaction_infos;
+    mock_db
+        .expect_get_transaction_info_iterator()
+        .times(1)
+        .with(eq(start_version), eq(num_items))
+        .returning(move |_, _| Ok(Box::new(transaction_infos.clone().into_iter().map(Ok))));
+    if let Some(events) = transaction_list.events {
+        mock_db
+            .expect_get_events_iterator()
+            .times(1)
+            .with(eq(start_version), eq(num_items))
+            .returning(move |_, _| Ok(Box::new(events.clone().into_iter().map(Ok))));
+    }
+}
+
 /// Extracts the peer and network ids from an optional peer network id
 pub fn extract_peer_and_network_id(
     peer_network_id: Option<PeerNetworkId>,
@@ -503,6 +566,67 @@ pub async fn get_transactions_with_proof(
     send_storage_request(mock_client, use_compression, data_request).await
 }
 
+/// Sends a transaction by hash request and processes the response
+pub async fn get_transaction_by_hash_with_proof(
+    mock_client: &mut MockClient,
+    transaction_hash: HashValue,
+    proof_version: u64,
+    include_events: bool,
+    use_compression: bool,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let data_request =
+        DataRequest::GetTransactionByHashWithProof(TransactionByHashWithProofRequest {
+            transaction_hash,
+            proof_version,
+            include_events,
+        });
+    send_storage_request(mock_client, use_compression, data_request).await
+}
+
+/// Sends a block metadata request and processes the response
+pub async fn get_block_metadata(
+    mock_client: &mut MockClient,
+    start_version: u64,
+    end_version: u64,
+    use_compression: bool,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let data_request = DataRequest::GetBlockMetadata(BlockMetadataRequest {
+        start_version,
+        end_version,
+    });
+    send_storage_request(mock_client, use_compression, data_request).await
+}
+
+/// Sends a version by timestamp request and processes the response
+pub async fn get_version_by_timestamp(
+    mock_client: &mut MockClient,
+    timestamp_usecs: u64,
+    ledger_version: u64,
+    use_compression: bool,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let data_request = DataRequest::GetVersionByTimestamp(VersionByTimestampRequest {
+        timestamp_usecs,
+        ledger_version,
+    });
+    send_storage_request(mock_client, use_compression, data_request).await
+}
+
+/// Sends a transactions without proof request and processes the response
+pub async fn get_transactions_without_proof(
+    mock_client: &mut MockClient,
+    start_version: u64,
+    end_version: u64,
+    include_events: bool,
+    use_compression: bool,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let data_request = DataRequest::GetTransactionsWithoutProof(TransactionsWithoutProofRequest {
+        start_version,
+        end_version,
+        include_events,
+    });
+    send_storage_request(mock_client, use_compression, data_request).await
+}
+
 /// Initializes the Aptos logger for tests
 pub fn initialize_logger() {
     aptos_logger::Logger::builder()
@@ -0,0 +1,295 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    tests::{mock, mock::MockClient, utils},
+    StorageReader, StorageServiceServer,
+};
+use anyhow::format_err;
+use aptos_config::config::StorageServiceConfig;
+use aptos_crypto::HashValue;
+use aptos_storage_interface::AptosDbError;
+use aptos_storage_service_types::{
+    requests::{BatchRequest, DataRequest, VersionByTimestampRequest},
+    responses::{
+        CompleteDataRange, DataResponse, StorageServerSummary, VersionByTimestampWithProof,
+    },
+    StorageServiceErrorCategory,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    block_metadata::NewBlockEvent,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{TransactionAccumulatorProof, TransactionInfoWithProof},
+    transaction::{ExecutionStatus, Transaction, TransactionInfo, TransactionWithProof, Version},
+};
+use mockall::predicate::eq;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_get_version_by_timestamp() {
+    // Create test data
+    let timestamp_usecs = 5_000;
+    let ledger_version = 1000;
+    let version = 420;
+    let block_start_version = 400;
+    let block_start = create_transaction_with_proof(block_start_version);
+    let next_block_start = create_transaction_with_proof(version + 1);
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_block_timestamp()
+        .times(1)
+        .with(eq(ledger_version))
+        .returning(move |_| Ok(timestamp_usecs + 1));
+    db_reader
+        .expect_get_last_version_before_timestamp()
+        .times(1)
+        .with(eq(timestamp_usecs), eq(ledger_version))
+        .returning(move |_, _| Ok(version));
+    db_reader
+        .expect_get_block_info_by_version()
+        .times(1)
+        .with(eq(version))
+        .returning(move |_| Ok((block_start_version, version, create_new_block_event())));
+    for transaction_with_proof in [block_start.clone(), next_block_start.clone()] {
+        db_reader
+            .expect_get_transaction_by_version()
+            .times(1)
+            .with(
+                eq(transaction_with_proof.version),
+                eq(ledger_version),
+                eq(true),
+            )
+            .returning(move |_, _, _| Ok(transaction_with_proof.clone()));
+    }
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) =
+        MockClient::new(Some(db_reader), Some(create_storage_config()));
+    update_storage_server_summary(&mut service, ledger_version, 10_000);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the version by timestamp
+    let response =
+        utils::get_version_by_timestamp(&mut mock_client, timestamp_usecs, ledger_version, true)
+            .await
+            .unwrap();
+
+    // Verify the response is correct
+    let expected_version_with_proof = VersionByTimestampWithProof {
+        version,
+        block_start,
+        next_block_start,
+    };
+    match response.get_data_response().unwrap() {
+        DataResponse::VersionByTimestamp(version_with_proof) => {
+            assert_eq!(version_with_proof, expected_version_with_proof)
+        },
+        _ => panic!("Expected a version by timestamp but got: {:?}", response),
+    };
+}
+
+#[tokio::test]
+async fn test_get_version_by_timestamp_disabled() {
+    // Create the storage client and server (with the default config)
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    update_storage_server_summary(&mut service, 1000, 10_000);
+    tokio::spawn(service.start());
+
+    // Verify the request is denied
+    let response = utils::get_version_by_timestamp(&mut mock_client, 5_000, 1000, true)
+        .await
+        .unwrap_err();
+    assert_eq!(response.category(), StorageServiceErrorCategory::Permission);
+
+    // Verify the request is also denied within a batch
+    let data_request = DataRequest::GetBatch(BatchRequest {
+        requests: vec![
+            DataRequest::GetNumberOfStatesAtVersion(100),
+            DataRequest::GetVersionByTimestamp(VersionByTimestampRequest {
+                timestamp_usecs: 5_000,
+                ledger_version: 1000,
+            }),
+        ],
+    });
+    let response = utils::send_storage_request(&mut mock_client, false, data_request)
+        .await
+        .unwrap_err();
+    assert_eq!(response.category(), StorageServiceErrorCategory::Permission);
+}
+
+#[tokio::test]
+async fn test_get_version_by_timestamp_ledger_block_too_old() {
+    // Create test data
+    let timestamp_usecs = 5_000;
+    let ledger_version = 1000;
+
+    // Create the mock db reader (the block at the ledger version is older than the timestamp)
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_block_timestamp()
+        .times(1)
+        .with(eq(ledger_version))
+        .returning(move |_| Ok(timestamp_usecs - 1));
+
+    // Create the storage client and server (the summary holds a newer timestamp)
+    let (mut mock_client, mut service, _, _, _) =
+        MockClient::new(Some(db_reader), Some(create_storage_config()));
+    update_storage_server_summary(&mut service, ledger_version + 10, 10_000);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the version by timestamp
+    let response =
+        utils::get_version_by_timestamp(&mut mock_client, timestamp_usecs, ledger_version, true)
+            .await
+            .unwrap_err();
+
+    // Verify the request failed (and can be retried against a later ledger version)
+    assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
+}
+
+#[tokio::test]
+async fn test_get_version_by_timestamp_storage_error() {
+    // Create test data
+    let timestamp_usecs = 5_000;
+    let ledger_version = 1000;
+
+    // Create the mock db reader (no block was committed before the timestamp)
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_get_block_timestamp()
+        .times(1)
+        .with(eq(ledger_version))
+        .returning(move |_| Ok(timestamp_usecs + 1));
+    db_reader
+        .expect_get_last_version_before_timestamp()
+        .times(1)
+        .with(eq(timestamp_usecs), eq(ledger_version))
+        .returning(move |_, _| {
+            Err(AptosDbError::NotFound(
+                format_err!("No block found before the timestamp!").to_string(),
+            ))
+        });
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) =
+        MockClient::new(Some(db_reader), Some(create_storage_config()));
+    update_storage_server_summary(&mut service, ledger_version, 10_000);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the version by timestamp
+    let response =
+        utils::get_version_by_timestamp(&mut mock_client, timestamp_usecs, ledger_version, false)
+            .await
+            .unwrap_err();
+
+    // Verify the request failed
+    assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
+}
+
+#[tokio::test]
+async fn test_get_version_by_timestamp_not_serviceable() {
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) =
+        MockClient::new(None, Some(create_storage_config()));
+    update_storage_server_summary(&mut service, 100, 10_000);
+    tokio::spawn(service.start());
+
+    // Verify requests beyond the synced ledger version and timestamp are not serviceable
+    for (timestamp_usecs, ledger_version) in [(5_000, 200), (20_000, 100)] {
+        let response = utils::get_version_by_timestamp(
+            &mut mock_client,
+            timestamp_usecs,
+            ledger_version,
+            true,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(response.category(), StorageServiceErrorCategory::Retryable);
+    }
+}
+
+/// Creates a new block event for the tests (its contents are not checked by the server)
+fn create_new_block_event() -> NewBlockEvent {
+    NewBlockEvent::new(
+        AccountAddress::ONE,
+        0,
+        0,
+        0,
+        vec![],
+        AccountAddress::ONE,
+        vec![],
+        0,
+    )
+}
+
+/// Creates a storage service config that serves version lookups by timestamp
+fn create_storage_config() -> StorageServiceConfig {
+    StorageServiceConfig {
+        enable_version_by_timestamp: true,
+        ..Default::default()
+    }
+}
+
+/// Creates a test transaction (with events and an empty proof) at the given version
+fn create_transaction_with_proof(version: Version) -> TransactionWithProof {
+    let transaction_info = TransactionInfo::new(
+        HashValue::zero(),
+        HashValue::zero(),
+        HashValue::zero(),
+        None,
+        0,
+        ExecutionStatus::Success,
+    );
+    let proof =
+        TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), transaction_info);
+    TransactionWithProof::new(
+        version,
+        Transaction::StateCheckpoint(HashValue::random()),
+        Some(vec![]),
+        proof,
+    )
+}
+
+/// Updates the storage server summary with a synced ledger
+/// info at the specified version and timestamp.
+fn update_storage_server_summary(
+    storage_server: &mut StorageServiceServer<StorageReader>,
+    highest_synced_version: u64,
+    highest_synced_timestamp_usecs: u64,
+) {
+    // Create a storage server summary
+    let mut storage_server_summary = StorageServerSummary::default();
+
+    // Set the highest synced ledger info
+    let data_summary = &mut storage_server_summary.data_summary;
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(
+            0,
+            0,
+            HashValue::zero(),
+            HashValue::zero(),
+            highest_synced_version,
+            highest_synced_timestamp_usecs,
+            None,
+        ),
+        HashValue::zero(),
+    );
+    data_summary.synced_ledger_info = Some(LedgerInfoWithSignatures::new(
+        ledger_info,
+        AggregateSignature::empty(),
+    ));
+
+    // Set the transaction range
+    let data_range = CompleteDataRange::new(0, highest_synced_version).unwrap();
+    data_summary.transactions = Some(data_range);
+
+    // Update the storage server summary
+    storage_server
+        .cached_storage_server_summary
+        .store(Arc::new(storage_server_summary));
+}
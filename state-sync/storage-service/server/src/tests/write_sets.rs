@@ -0,0 +1,120 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    storage::{StorageReader, StorageReaderInterface},
+    tests::mock,
+};
+use aptos_config::config::StorageServiceConfig;
+use aptos_types::{
+    proof::TransactionInfoListWithProof, transaction::WriteSetListWithProof, write_set::WriteSet,
+};
+use mockall::{predicate::eq, Sequence};
+use std::sync::Arc;
+
+#[test]
+fn test_get_write_sets_with_proof() {
+    // Test small and large chunk requests
+    let max_chunk_size = StorageServiceConfig::default().max_transaction_output_chunk_size;
+    for chunk_size in [1, 100, max_chunk_size] {
+        // Create test data
+        let start_version = 0;
+        let end_version = start_version + chunk_size - 1;
+        let proof_version = end_version;
+        let write_set_list_with_proof =
+            create_write_set_list_with_proof(start_version, end_version);
+
+        // Create the mock db reader
+        let mut db_reader = mock::create_mock_db_reader();
+        let write_sets = write_set_list_with_proof.clone();
+        db_reader
+            .expect_get_write_sets_with_proof()
+            .times(1)
+            .with(eq(start_version), eq(chunk_size), eq(proof_version))
+            .returning(move |_, _, _| Ok(write_sets.clone()));
+
+        // Fetch the write sets and verify the response is correct
+        let storage_reader =
+            StorageReader::new(StorageServiceConfig::default(), Arc::new(db_reader));
+        let response = storage_reader
+            .get_write_sets_with_proof(proof_version, start_version, end_version)
+            .unwrap();
+        assert_eq!(response, write_set_list_with_proof);
+    }
+}
+
+#[test]
+fn test_get_write_sets_with_proof_chunk_limit() {
+    // Create test data
+    let max_chunk_size = StorageServiceConfig::default().max_transaction_output_chunk_size;
+    let start_version = 0;
+    let end_version = start_version + max_chunk_size * 10; // Request more than the max chunk
+    let proof_version = end_version;
+    let write_set_list_with_proof =
+        create_write_set_list_with_proof(start_version, start_version + max_chunk_size - 1);
+
+    // Create the mock db reader (only the max chunk size should be fetched)
+    let mut db_reader = mock::create_mock_db_reader();
+    let write_sets = write_set_list_with_proof.clone();
+    db_reader
+        .expect_get_write_sets_with_proof()
+        .times(1)
+        .with(eq(start_version), eq(max_chunk_size), eq(proof_version))
+        .returning(move |_, _, _| Ok(write_sets.clone()));
+
+    // Fetch the write sets and verify the response is correct
+    let storage_reader = StorageReader::new(StorageServiceConfig::default(), Arc::new(db_reader));
+    let response = storage_reader
+        .get_write_sets_with_proof(proof_version, start_version, end_version)
+        .unwrap();
+    assert_eq!(response, write_set_list_with_proof);
+}
+
+#[test]
+fn test_get_write_sets_with_proof_network_limit() {
+    // Create test data
+    let start_version = 0;
+    let end_version = 99;
+    let proof_version = end_version;
+    let full_write_set_list = create_write_set_list_with_proof(start_version, end_version);
+    let half_write_set_list = create_write_set_list_with_proof(start_version, end_version / 2);
+
+    // Create a storage config where only half of the write sets fit into a network frame
+    let max_network_chunk_bytes = bcs::serialized_size(&half_write_set_list).unwrap() as u64 + 1;
+    let storage_config = StorageServiceConfig {
+        max_network_chunk_bytes,
+        ..Default::default()
+    };
+
+    // Create the mock db reader (the request should be retried with half the data)
+    let mut db_reader = mock::create_mock_db_reader();
+    let mut expectation_sequence = Sequence::new();
+    for (num_write_sets, write_set_list) in [
+        (100, full_write_set_list),
+        (50, half_write_set_list.clone()),
+    ] {
+        db_reader
+            .expect_get_write_sets_with_proof()
+            .times(1)
+            .with(eq(start_version), eq(num_write_sets), eq(proof_version))
+            .in_sequence(&mut expectation_sequence)
+            .returning(move |_, _, _| Ok(write_set_list.clone()));
+    }
+
+    // Fetch the write sets and verify only the first half was returned
+    let storage_reader = StorageReader::new(storage_config, Arc::new(db_reader));
+    let response = storage_reader
+        .get_write_sets_with_proof(proof_version, start_version, end_version)
+        .unwrap();
+    assert_eq!(response, half_write_set_list);
+}
+
+/// Creates a write set list (with an empty proof) for the given version range (inclusive)
+fn create_write_set_list_with_proof(start_version: u64, end_version: u64) -> WriteSetListWithProof {
+    let num_write_sets = (end_version - start_version + 1) as usize;
+    WriteSetListWithProof::new(
+        vec![WriteSet::default(); num_write_sets],
+        Some(start_version),
+        TransactionInfoListWithProof::new_empty(),
+    )
+}
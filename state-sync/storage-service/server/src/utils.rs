@@ -3,10 +3,10 @@
 
 use crate::{
     error::Error, handler::Handler, metrics, moderator::RequestModerator, network::ResponseSender,
-    optimistic_fetch::OptimisticFetchRequest, storage::StorageReaderInterface,
-    subscription::SubscriptionStreamRequests,
+    optimistic_fetch::OptimisticFetchRequest, response_cache::ResponseCache,
+    storage::StorageReaderInterface, subscription::SubscriptionStreamRequests,
 };
-use aptos_config::network_id::PeerNetworkId;
+use aptos_config::{config::StorageServiceConfig, network_id::PeerNetworkId};
 use aptos_metrics_core::HistogramVec;
 use aptos_storage_service_types::{
     requests::{DataRequest, EpochEndingLedgerInfoRequest, StorageServiceRequest},
@@ -16,7 +16,6 @@ use aptos_time_service::TimeService;
 use aptos_types::ledger_info::LedgerInfoWithSignatures;
 use arc_swap::ArcSwap;
 use dashmap::DashMap;
-use mini_moka::sync::Cache;
 use once_cell::sync::Lazy;
 use std::{sync::Arc, time::Instant};
 
@@ -26,7 +25,7 @@ pub fn get_epoch_ending_ledger_info<T: StorageReaderInterface>(
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
     epoch: u64,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     peer_network_id: &PeerNetworkId,
     storage: T,
@@ -84,10 +83,11 @@ pub fn get_epoch_ending_ledger_info<T: StorageReaderInterface>(
 /// Note: we don't need to check the size of the response because:
 /// (i) each sub-part should already be checked; and (ii) responses
 pub fn notify_peer_of_new_data<T: StorageReaderInterface>(
+    storage_service_config: StorageServiceConfig,
     cached_storage_server_summary: Arc<ArcSwap<StorageServerSummary>>,
     optimistic_fetches: Arc<DashMap<PeerNetworkId, OptimisticFetchRequest>>,
     subscriptions: Arc<DashMap<PeerNetworkId, SubscriptionStreamRequests>>,
-    lru_response_cache: Cache<StorageServiceRequest, StorageServiceResponse>,
+    lru_response_cache: ResponseCache,
     request_moderator: Arc<RequestModerator>,
     storage: T,
     time_service: TimeService,
@@ -97,7 +97,6 @@ pub fn notify_peer_of_new_data<T: StorageReaderInterface>(
     response_sender: ResponseSender,
 ) -> aptos_storage_service_types::Result<DataResponse, Error> {
     // Handle the storage service request to fetch the missing data
-    let use_compression = missing_data_request.use_compression;
     let handler = Handler::new(
         cached_storage_server_summary,
         optimistic_fetches,
@@ -110,7 +109,33 @@ pub fn notify_peer_of_new_data<T: StorageReaderInterface>(
     let storage_response =
         handler.process_request(peer_network_id, missing_data_request.clone(), true);
 
+    // Transform the missing data and send it to the peer
+    send_new_data_to_peer(
+        storage_service_config,
+        &handler,
+        missing_data_request,
+        &storage_response,
+        target_ledger_info,
+        response_sender,
+    )
+}
+
+/// Transforms the given response for the missing data into a new data
+/// response (at the target ledger info) and sends it to the peer. Returns
+/// a copy of the raw data response that was sent.
+///
+/// This allows the same missing data response to be sent to multiple
+/// peers (e.g., subscribers with identical missing data requests).
+pub fn send_new_data_to_peer<T: StorageReaderInterface>(
+    storage_service_config: StorageServiceConfig,
+    handler: &Handler<T>,
+    missing_data_request: StorageServiceRequest,
+    storage_response: &aptos_storage_service_types::Result<StorageServiceResponse>,
+    target_ledger_info: LedgerInfoWithSignatures,
+    response_sender: ResponseSender,
+) -> aptos_storage_service_types::Result<DataResponse, Error> {
     // Transform the missing data into an optimistic fetch response
+    let use_compression = missing_data_request.use_compression;
     let transformed_data_response = match storage_response {
         Ok(storage_response) => match storage_response.get_data_response() {
             Ok(DataResponse::TransactionsWithProof(transactions_with_proof)) => {
@@ -173,7 +198,13 @@ pub fn notify_peer_of_new_data<T: StorageReaderInterface>(
         };
 
     // Send the response to the peer
-    handler.send_response(missing_data_request, Ok(storage_response), response_sender);
+    handler.send_response(
+        storage_service_config,
+        peer_network_id,
+        missing_data_request,
+        Ok(storage_response),
+        response_sender,
+    );
 
     Ok(transformed_data_response)
 }
@@ -4,9 +4,13 @@
 
 #![forbid(unsafe_code)]
 
-use requests::StorageServiceRequest;
+use requests::{StorageServiceRequest, StorageServiceRequestEnvelope};
 use responses::StorageServiceResponse;
 use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+};
 use thiserror::Error;
 
 pub mod requests;
@@ -25,8 +29,21 @@ pub type Epoch = u64;
 /// Shorthand error typing
 pub type Result<T, E = StorageServiceError> = ::std::result::Result<T, E>;
 
+/// The version of the storage service error format. This should be bumped
+/// whenever a new error category is added, so that clients can tell which
+/// categories a server may return.
+pub const STORAGE_SERVICE_ERROR_VERSION: u64 = 1;
+
+/// The prefix of error messages that carry error metadata (e.g., the error
+/// category). The metadata is embedded in the messages of the existing error
+/// variants so that older clients can still decode (and log) the errors.
+const ERROR_METADATA_PREFIX: &str = "[storage service error: ";
+
 /// A storage service error that can be returned to the client on a failure
 /// to process a service request.
+///
+/// Note: new variants cannot be added without breaking older clients. Instead,
+/// the error metadata (see [`ErrorMetadata`]) is carried in the error message.
 #[derive(Clone, Debug, Deserialize, Error, PartialEq, Eq, Serialize)]
 pub enum StorageServiceError {
     #[error("Internal service error: {0}")]
@@ -37,6 +54,201 @@ pub enum StorageServiceError {
     TooManyInvalidRequests(String),
 }
 
+impl StorageServiceError {
+    /// Creates a new error with the given category and message
+    pub fn new(category: StorageServiceErrorCategory, message: String) -> Self {
+        Self::new_with_metadata(ErrorMetadata::new(category), message)
+    }
+
+    /// Creates a new error for a request that can't be served because the
+    /// data has been pruned (the earliest available version is also returned)
+    pub fn new_data_pruned(earliest_available_version: u64, message: String) -> Self {
+        let error_metadata = ErrorMetadata {
+            earliest_available_version: Some(earliest_available_version),
+            ..ErrorMetadata::new(StorageServiceErrorCategory::Pruned)
+        };
+        Self::new_with_metadata(error_metadata, message)
+    }
+
+    /// Creates a new error with the given metadata and message. The variant
+    /// is chosen so that older clients (that ignore the metadata) still handle
+    /// the error as before.
+    fn new_with_metadata(error_metadata: ErrorMetadata, message: String) -> Self {
+        let message = format!("{}{}", error_metadata, message);
+        match error_metadata.category {
+            StorageServiceErrorCategory::Retryable | StorageServiceErrorCategory::TooLarge => {
+                Self::InternalError(message)
+            },
+            StorageServiceErrorCategory::Fatal
+            | StorageServiceErrorCategory::Permission
+            | StorageServiceErrorCategory::Pruned => Self::InvalidRequest(message),
+        }
+    }
+
+    /// Returns the category of the error. Legacy errors (i.e., those
+    /// returned by older servers) are mapped to the closest category.
+    pub fn category(&self) -> StorageServiceErrorCategory {
+        if let Some(error_metadata) = self.metadata() {
+            return error_metadata.category;
+        }
+
+        match self {
+            Self::InternalError(_) => StorageServiceErrorCategory::Retryable,
+            Self::InvalidRequest(_) => StorageServiceErrorCategory::Fatal,
+            Self::TooManyInvalidRequests(_) => StorageServiceErrorCategory::Permission,
+        }
+    }
+
+    /// Returns the error version of the server (if the error carries metadata)
+    pub fn version(&self) -> Option<u64> {
+        self.metadata().map(|error_metadata| error_metadata.version)
+    }
+
+    /// Returns the earliest version (or epoch) still available on the server,
+    /// if the request failed because the requested data has been pruned. This
+    /// allows clients to fall back to other sync modes (e.g., snapshot sync)
+    /// instead of retrying requests that can never be served.
+    pub fn earliest_available_version(&self) -> Option<u64> {
+        self.metadata()
+            .and_then(|error_metadata| error_metadata.earliest_available_version)
+    }
+
+    /// Returns a summary label for the error
+    pub fn get_label(&self) -> &'static str {
+        self.category().get_label()
+    }
+
+    /// Returns the error metadata embedded in the message (if any)
+    fn metadata(&self) -> Option<ErrorMetadata> {
+        let message = match self {
+            Self::InternalError(message)
+            | Self::InvalidRequest(message)
+            | Self::TooManyInvalidRequests(message) => message,
+        };
+        ErrorMetadata::parse(message)
+    }
+}
+
+/// The metadata of a storage service error, carried at the start of the
+/// error message, e.g., "[storage service error: category=pruned, version=1,
+/// earliest_available_version=10] ...".
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct ErrorMetadata {
+    category: StorageServiceErrorCategory,
+    version: u64,
+    earliest_available_version: Option<u64>,
+}
+
+impl ErrorMetadata {
+    fn new(category: StorageServiceErrorCategory) -> Self {
+        Self {
+            category,
+            version: STORAGE_SERVICE_ERROR_VERSION,
+            earliest_available_version: None,
+        }
+    }
+
+    /// Parses the metadata at the start of the given error message. Returns
+    /// None if the message holds no (or invalid) metadata. Unknown fields
+    /// are ignored, so that newer servers can add more metadata.
+    fn parse(message: &str) -> Option<Self> {
+        let metadata = message.strip_prefix(ERROR_METADATA_PREFIX)?;
+        let (metadata, _) = metadata.split_once(']')?;
+
+        let mut category = None;
+        let mut version = None;
+        let mut earliest_available_version = None;
+        for field in metadata.split(", ") {
+            match field.split_once('=')? {
+                ("category", value) => {
+                    category = Some(StorageServiceErrorCategory::from_label(value)?)
+                },
+                ("version", value) => version = Some(value.parse().ok()?),
+                ("earliest_available_version", value) => {
+                    earliest_available_version = Some(value.parse().ok()?)
+                },
+                _ => { /* Ignore unknown fields */ },
+            }
+        }
+
+        Some(Self {
+            category: category?,
+            version: version?,
+            earliest_available_version,
+        })
+    }
+}
+
+impl Display for ErrorMetadata {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}category={}, version={}",
+            ERROR_METADATA_PREFIX, self.category, self.version
+        )?;
+        if let Some(earliest_available_version) = self.earliest_available_version {
+            write!(
+                f,
+                ", earliest_available_version={}",
+                earliest_available_version
+            )?;
+        }
+        write!(f, "] ")
+    }
+}
+
+/// The category of a storage service error. Categories are stable across
+/// releases (new categories may only be appended), so clients can decide how
+/// to handle an error (e.g., whether to retry) without parsing the message.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum StorageServiceErrorCategory {
+    Retryable,  // The request failed transiently (e.g., the data is not yet available)
+    Fatal,      // The request can never be served (e.g., it is malformed)
+    Permission, // The peer is not permitted to make the request (e.g., it is disabled)
+    TooLarge,   // The response cannot fit into a single network message
+    Pruned,     // The requested data has been pruned by the server
+}
+
+impl StorageServiceErrorCategory {
+    /// All error categories
+    pub const ALL: [StorageServiceErrorCategory; 5] = [
+        Self::Retryable,
+        Self::Fatal,
+        Self::Permission,
+        Self::TooLarge,
+        Self::Pruned,
+    ];
+
+    /// Returns the category with the given label (if it is known)
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.get_label() == label)
+    }
+
+    /// Returns a summary label for the error category
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::Retryable => "retryable",
+            Self::Fatal => "fatal",
+            Self::Permission => "permission",
+            Self::TooLarge => "too_large",
+            Self::Pruned => "pruned",
+        }
+    }
+
+    /// Returns true iff the request may succeed if it is retried
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Retryable)
+    }
+}
+
+impl Display for StorageServiceErrorCategory {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_label())
+    }
+}
+
 /// A single storage service message sent or received over AptosNet.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[allow(clippy::large_enum_variant)]
@@ -46,4 +258,22 @@ pub enum StorageServiceMessage {
     /// A response from the storage service. If there was an error while handling
     /// the request, the service will return an [`StorageServiceError`] error.
     Response(Result<StorageServiceResponse>),
+    /// A request to the storage service, wrapped in an envelope with an
+    /// explicit message type tag (see [`StorageServiceRequestEnvelope`]).
+    RequestEnvelope(StorageServiceRequestEnvelope),
+    /// A request to the storage service, for which the client accepts a checksummed
+    /// response (see [`responses::ChecksummedData`]). Older clients can't decode
+    /// checksummed responses, so servers only send them when asked to.
+    ChecksummedRequest(StorageServiceRequest),
+}
+
+impl StorageServiceMessage {
+    /// Returns the message used to send the given request
+    pub fn new_request(request: StorageServiceRequest) -> Self {
+        if request.use_checksum {
+            Self::ChecksummedRequest(request)
+        } else {
+            Self::Request(request)
+        }
+    }
 }
@@ -1,15 +1,20 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::COMPRESSION_SUFFIX_LABEL;
+use crate::{StorageServiceError, StorageServiceErrorCategory, COMPRESSION_SUFFIX_LABEL};
+use aptos_crypto::HashValue;
 use aptos_types::transaction::Version;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 /// A storage service request.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct StorageServiceRequest {
     pub data_request: DataRequest, // The data to fetch from the storage service
     pub use_compression: bool,     // Whether or not the client wishes data to be compressed
+    // Whether or not the client wishes the response to be checksummed. This is not part
+    // of the request format, it is signaled by the message (see `StorageServiceMessage`).
+    #[serde(skip)]
+    pub use_checksum: bool,
 }
 
 impl StorageServiceRequest {
@@ -17,9 +22,16 @@ impl StorageServiceRequest {
         Self {
             data_request,
             use_compression,
+            use_checksum: false,
         }
     }
 
+    /// Asks the server to send the response in a checksummed envelope
+    pub fn with_checksum(mut self, use_checksum: bool) -> Self {
+        self.use_checksum = use_checksum;
+        self
+    }
+
     /// Returns a summary label for the request
     pub fn get_label(&self) -> String {
         let mut label = self.data_request.get_label().to_string();
@@ -30,6 +42,257 @@ impl StorageServiceRequest {
     }
 }
 
+/// A storage service request wrapped in an envelope with an explicit message
+/// type tag (see [`DataRequestType`]). Unlike [`StorageServiceRequest`], whose
+/// wire format depends on the declaration order of the [`DataRequest`] variants,
+/// the envelope remains compatible if variants are reordered or renamed.
+///
+/// Note: servers accept both formats. Clients should only send envelopes once
+/// all servers they talk to are known to support them.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct StorageServiceRequestEnvelope {
+    pub message_type: u32, // The message type tag of the data request
+    #[serde(with = "serde_bytes")]
+    pub payload: Vec<u8>, // The BCS encoded data request (without the variant index)
+    pub use_compression: bool, // Whether or not the client wishes data to be compressed
+}
+
+impl StorageServiceRequestEnvelope {
+    pub fn new(request: &StorageServiceRequest) -> bcs::Result<Self> {
+        let data_request = &request.data_request;
+        let payload = match data_request {
+            DataRequest::GetEpochEndingLedgerInfos(request) => bcs::to_bytes(request),
+            DataRequest::GetNewTransactionOutputsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetNewTransactionsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetNumberOfStatesAtVersion(version) => bcs::to_bytes(version),
+            DataRequest::GetServerProtocolVersion => bcs::to_bytes(&()),
+            DataRequest::GetStateValuesWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetStorageServerSummary => bcs::to_bytes(&()),
+            DataRequest::GetTransactionOutputsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetTransactionsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetNewTransactionsOrOutputsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetTransactionsOrOutputsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::SubscribeTransactionOutputsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::SubscribeTransactionsOrOutputsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::SubscribeTransactionsWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetTransactionsWithoutProof(request) => bcs::to_bytes(request),
+            DataRequest::GetStateValuesByKeyRangeWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetBatch(request) => bcs::to_bytes(request),
+            DataRequest::GetTransactionByHashWithProof(request) => bcs::to_bytes(request),
+            DataRequest::GetVersionByTimestamp(request) => bcs::to_bytes(request),
+            DataRequest::GetBlockMetadata(request) => bcs::to_bytes(request),
+            DataRequest::GetPruneWindowForecast => bcs::to_bytes(&()),
+            DataRequest::GetInternalIndexerSummary => bcs::to_bytes(&()),
+        }?;
+
+        Ok(Self {
+            message_type: data_request.get_request_type().get_message_type(),
+            payload,
+            use_compression: request.use_compression,
+        })
+    }
+}
+
+impl TryFrom<StorageServiceRequestEnvelope> for StorageServiceRequest {
+    type Error = StorageServiceError;
+
+    fn try_from(envelope: StorageServiceRequestEnvelope) -> Result<Self, Self::Error> {
+        let request_type =
+            DataRequestType::from_message_type(envelope.message_type).ok_or_else(|| {
+                StorageServiceError::new(
+                    StorageServiceErrorCategory::Fatal,
+                    format!(
+                        "Unknown data request message type: {}",
+                        envelope.message_type
+                    ),
+                )
+            })?;
+
+        let payload = &envelope.payload;
+        let data_request = match request_type {
+            DataRequestType::GetEpochEndingLedgerInfos => {
+                DataRequest::GetEpochEndingLedgerInfos(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetNewTransactionOutputsWithProof => {
+                DataRequest::GetNewTransactionOutputsWithProof(decode_payload(
+                    request_type,
+                    payload,
+                )?)
+            },
+            DataRequestType::GetNewTransactionsWithProof => {
+                DataRequest::GetNewTransactionsWithProof(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetNumberOfStatesAtVersion => {
+                DataRequest::GetNumberOfStatesAtVersion(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetServerProtocolVersion => {
+                decode_payload::<()>(request_type, payload)?;
+                DataRequest::GetServerProtocolVersion
+            },
+            DataRequestType::GetStateValuesWithProof => {
+                DataRequest::GetStateValuesWithProof(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetStorageServerSummary => {
+                decode_payload::<()>(request_type, payload)?;
+                DataRequest::GetStorageServerSummary
+            },
+            DataRequestType::GetTransactionOutputsWithProof => {
+                DataRequest::GetTransactionOutputsWithProof(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetTransactionsWithProof => {
+                DataRequest::GetTransactionsWithProof(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetNewTransactionsOrOutputsWithProof => {
+                DataRequest::GetNewTransactionsOrOutputsWithProof(decode_payload(
+                    request_type,
+                    payload,
+                )?)
+            },
+            DataRequestType::GetTransactionsOrOutputsWithProof => {
+                DataRequest::GetTransactionsOrOutputsWithProof(decode_payload(
+                    request_type,
+                    payload,
+                )?)
+            },
+            DataRequestType::SubscribeTransactionOutputsWithProof => {
+                DataRequest::SubscribeTransactionOutputsWithProof(decode_payload(
+                    request_type,
+                    payload,
+                )?)
+            },
+            DataRequestType::SubscribeTransactionsOrOutputsWithProof => {
+                DataRequest::SubscribeTransactionsOrOutputsWithProof(decode_payload(
+                    request_type,
+                    payload,
+                )?)
+            },
+            DataRequestType::SubscribeTransactionsWithProof => {
+                DataRequest::SubscribeTransactionsWithProof(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetTransactionsWithoutProof => {
+                DataRequest::GetTransactionsWithoutProof(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetStateValuesByKeyRangeWithProof => {
+                DataRequest::GetStateValuesByKeyRangeWithProof(decode_payload(
+                    request_type,
+                    payload,
+                )?)
+            },
+            DataRequestType::GetBatch => {
+                DataRequest::GetBatch(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetTransactionByHashWithProof => {
+                DataRequest::GetTransactionByHashWithProof(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetVersionByTimestamp => {
+                DataRequest::GetVersionByTimestamp(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetBlockMetadata => {
+                DataRequest::GetBlockMetadata(decode_payload(request_type, payload)?)
+            },
+            DataRequestType::GetPruneWindowForecast => {
+                decode_payload::<()>(request_type, payload)?;
+                DataRequest::GetPruneWindowForecast
+            },
+            DataRequestType::GetInternalIndexerSummary => {
+                decode_payload::<()>(request_type, payload)?;
+                DataRequest::GetInternalIndexerSummary
+            },
+        };
+
+        Ok(StorageServiceRequest::new(
+            data_request,
+            envelope.use_compression,
+        ))
+    }
+}
+
+/// Decodes the payload of a request envelope with the given request type
+fn decode_payload<T: DeserializeOwned>(
+    request_type: DataRequestType,
+    payload: &[u8],
+) -> Result<T, StorageServiceError> {
+    bcs::from_bytes(payload).map_err(|error| {
+        StorageServiceError::new(
+            StorageServiceErrorCategory::Fatal,
+            format!(
+                "Failed to decode the payload of request type {:?}: {:?}",
+                request_type, error
+            ),
+        )
+    })
+}
+
+/// The message type tags of the data requests, used by the request envelope.
+/// These tags are part of the wire format: existing tags must never be changed
+/// or reused, and new data requests must be assigned new tags. The initial tags
+/// match the BCS variant indices of [`DataRequest`] (at the time of writing).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DataRequestType {
+    GetEpochEndingLedgerInfos = 0,
+    GetNewTransactionOutputsWithProof = 1,
+    GetNewTransactionsWithProof = 2,
+    GetNumberOfStatesAtVersion = 3,
+    GetServerProtocolVersion = 4,
+    GetStateValuesWithProof = 5,
+    GetStorageServerSummary = 6,
+    GetTransactionOutputsWithProof = 7,
+    GetTransactionsWithProof = 8,
+    GetNewTransactionsOrOutputsWithProof = 9,
+    GetTransactionsOrOutputsWithProof = 10,
+    SubscribeTransactionOutputsWithProof = 11,
+    SubscribeTransactionsOrOutputsWithProof = 12,
+    SubscribeTransactionsWithProof = 13,
+    GetTransactionsWithoutProof = 14,
+    GetStateValuesByKeyRangeWithProof = 15,
+    GetBatch = 16,
+    GetTransactionByHashWithProof = 17,
+    GetVersionByTimestamp = 18,
+    GetBlockMetadata = 19,
+    GetPruneWindowForecast = 20,
+    GetInternalIndexerSummary = 21,
+}
+
+impl DataRequestType {
+    /// All data request types (ordered by message type)
+    pub const ALL: [DataRequestType; 22] = [
+        Self::GetEpochEndingLedgerInfos,
+        Self::GetNewTransactionOutputsWithProof,
+        Self::GetNewTransactionsWithProof,
+        Self::GetNumberOfStatesAtVersion,
+        Self::GetServerProtocolVersion,
+        Self::GetStateValuesWithProof,
+        Self::GetStorageServerSummary,
+        Self::GetTransactionOutputsWithProof,
+        Self::GetTransactionsWithProof,
+        Self::GetNewTransactionsOrOutputsWithProof,
+        Self::GetTransactionsOrOutputsWithProof,
+        Self::SubscribeTransactionOutputsWithProof,
+        Self::SubscribeTransactionsOrOutputsWithProof,
+        Self::SubscribeTransactionsWithProof,
+        Self::GetTransactionsWithoutProof,
+        Self::GetStateValuesByKeyRangeWithProof,
+        Self::GetBatch,
+        Self::GetTransactionByHashWithProof,
+        Self::GetVersionByTimestamp,
+        Self::GetBlockMetadata,
+        Self::GetPruneWindowForecast,
+        Self::GetInternalIndexerSummary,
+    ];
+
+    /// Returns the request type for the given message type (if it is known)
+    pub fn from_message_type(message_type: u32) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|request_type| request_type.get_message_type() == message_type)
+    }
+
+    /// Returns the message type tag of the request type
+    pub fn get_message_type(&self) -> u32 {
+        *self as u32
+    }
+}
+
 /// A single data request.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum DataRequest {
@@ -47,6 +310,14 @@ pub enum DataRequest {
     SubscribeTransactionOutputsWithProof(SubscribeTransactionOutputsWithProofRequest), // Subscribes to transaction outputs with a proof
     SubscribeTransactionsOrOutputsWithProof(SubscribeTransactionsOrOutputsWithProofRequest), // Subscribes to transactions or outputs with a proof
     SubscribeTransactionsWithProof(SubscribeTransactionsWithProofRequest), // Subscribes to transactions with a proof
+    GetTransactionsWithoutProof(TransactionsWithoutProofRequest), // Fetches a list of transactions without a proof
+    GetStateValuesByKeyRangeWithProof(StateValuesByKeyRangeWithProofRequest), // Fetches a list of states (starting at a key hash) with a proof
+    GetBatch(BatchRequest), // Fetches the responses to several data requests in a single message
+    GetTransactionByHashWithProof(TransactionByHashWithProofRequest), // Fetches a committed transaction (by hash) with a proof
+    GetVersionByTimestamp(VersionByTimestampRequest), // Fetches the last version committed before a timestamp
+    GetBlockMetadata(BlockMetadataRequest), // Fetches the metadata (e.g., heights) of the blocks in a version range
+    GetPruneWindowForecast, // Fetches a forecast of the data that will still be held after some time
+    GetInternalIndexerSummary, // Fetches a summary of the data indexed by the internal indexer
 }
 
 impl DataRequest {
@@ -73,9 +344,76 @@ impl DataRequest {
                 "subscribe_transactions_or_outputs_with_proof"
             },
             Self::SubscribeTransactionsWithProof(_) => "subscribe_transactions_with_proof",
+            Self::GetTransactionsWithoutProof(_) => "get_transactions_without_proof",
+            Self::GetStateValuesByKeyRangeWithProof(_) => {
+                "get_state_values_by_key_range_with_proof"
+            },
+            Self::GetBatch(_) => "get_batch",
+            Self::GetTransactionByHashWithProof(_) => "get_transaction_by_hash_with_proof",
+            Self::GetVersionByTimestamp(_) => "get_version_by_timestamp",
+            Self::GetBlockMetadata(_) => "get_block_metadata",
+            Self::GetPruneWindowForecast => "get_prune_window_forecast",
+            Self::GetInternalIndexerSummary => "get_internal_indexer_summary",
         }
     }
 
+    /// Returns the type of the request (used to tag request envelopes)
+    pub fn get_request_type(&self) -> DataRequestType {
+        match self {
+            Self::GetEpochEndingLedgerInfos(_) => DataRequestType::GetEpochEndingLedgerInfos,
+            Self::GetNewTransactionOutputsWithProof(_) => {
+                DataRequestType::GetNewTransactionOutputsWithProof
+            },
+            Self::GetNewTransactionsWithProof(_) => DataRequestType::GetNewTransactionsWithProof,
+            Self::GetNumberOfStatesAtVersion(_) => DataRequestType::GetNumberOfStatesAtVersion,
+            Self::GetServerProtocolVersion => DataRequestType::GetServerProtocolVersion,
+            Self::GetStateValuesWithProof(_) => DataRequestType::GetStateValuesWithProof,
+            Self::GetStorageServerSummary => DataRequestType::GetStorageServerSummary,
+            Self::GetTransactionOutputsWithProof(_) => {
+                DataRequestType::GetTransactionOutputsWithProof
+            },
+            Self::GetTransactionsWithProof(_) => DataRequestType::GetTransactionsWithProof,
+            Self::GetNewTransactionsOrOutputsWithProof(_) => {
+                DataRequestType::GetNewTransactionsOrOutputsWithProof
+            },
+            Self::GetTransactionsOrOutputsWithProof(_) => {
+                DataRequestType::GetTransactionsOrOutputsWithProof
+            },
+            Self::SubscribeTransactionOutputsWithProof(_) => {
+                DataRequestType::SubscribeTransactionOutputsWithProof
+            },
+            Self::SubscribeTransactionsOrOutputsWithProof(_) => {
+                DataRequestType::SubscribeTransactionsOrOutputsWithProof
+            },
+            Self::SubscribeTransactionsWithProof(_) => {
+                DataRequestType::SubscribeTransactionsWithProof
+            },
+            Self::GetTransactionsWithoutProof(_) => DataRequestType::GetTransactionsWithoutProof,
+            Self::GetStateValuesByKeyRangeWithProof(_) => {
+                DataRequestType::GetStateValuesByKeyRangeWithProof
+            },
+            Self::GetBatch(_) => DataRequestType::GetBatch,
+            Self::GetTransactionByHashWithProof(_) => {
+                DataRequestType::GetTransactionByHashWithProof
+            },
+            Self::GetVersionByTimestamp(_) => DataRequestType::GetVersionByTimestamp,
+            Self::GetBlockMetadata(_) => DataRequestType::GetBlockMetadata,
+            Self::GetPruneWindowForecast => DataRequestType::GetPruneWindowForecast,
+            Self::GetInternalIndexerSummary => DataRequestType::GetInternalIndexerSummary,
+        }
+    }
+
+    pub fn is_batch_request(&self) -> bool {
+        matches!(self, &Self::GetBatch(_))
+    }
+
+    /// Returns true iff the request can be included in a batch request.
+    /// Optimistic fetches and subscriptions are not batchable (as they
+    /// are not served immediately), and neither are nested batches.
+    pub fn is_batchable(&self) -> bool {
+        !self.is_optimistic_fetch() && !self.is_subscription_request() && !self.is_batch_request()
+    }
+
     pub fn is_optimistic_fetch(&self) -> bool {
         matches!(self, &Self::GetNewTransactionOutputsWithProof(_))
             || matches!(self, &Self::GetNewTransactionsWithProof(_))
@@ -97,6 +435,15 @@ impl DataRequest {
     }
 }
 
+/// A storage service request for fetching the responses to several data
+/// requests in a single round trip. The responses are returned in the same
+/// order as the requests. If the combined responses would overflow a network
+/// message, the server only responds to a prefix of the requests.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct BatchRequest {
+    pub requests: Vec<DataRequest>, // The data requests to serve (in order)
+}
+
 /// A storage service request for fetching a list of epoch ending ledger infos.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct EpochEndingLedgerInfoRequest {
@@ -130,6 +477,18 @@ pub struct StateValuesWithProofRequest {
     pub end_index: u64,   // The index to stop fetching state values (inclusive)
 }
 
+/// A storage service request for fetching a list of state values at a
+/// specified version, starting at the smallest state key hash that is greater
+/// or equal to `start_key_hash`. Unlike index based requests, the key hash of
+/// the next chunk does not depend on the version, so clients can resume a
+/// snapshot deterministically (e.g., after switching to a newer version).
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct StateValuesByKeyRangeWithProofRequest {
+    pub version: u64,              // The version to fetch the state values at
+    pub start_key_hash: HashValue, // The key hash to start fetching state values (inclusive)
+    pub max_items: u64,            // The max number of state values to fetch
+}
+
 /// A storage service request for fetching a transaction output list with a
 /// corresponding proof.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -149,6 +508,49 @@ pub struct TransactionsWithProofRequest {
     pub include_events: bool, // Whether or not to include events in the response
 }
 
+/// A storage service request for fetching a committed transaction by its hash,
+/// along with a proof of its inclusion in the ledger (relative to the proof
+/// version). This allows clients to verify a known transaction without first
+/// having to find its version.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct TransactionByHashWithProofRequest {
+    pub transaction_hash: HashValue, // The hash of the transaction to fetch
+    pub proof_version: u64,          // The version the proof should be relative to
+    pub include_events: bool,        // Whether or not to include events in the response
+}
+
+/// A storage service request for fetching the version of the last transaction
+/// committed before the given timestamp (as seen by the ledger at the ledger
+/// version). A block committed at or after the timestamp must exist, otherwise
+/// the version cannot be determined (i.e., a later block could still be older).
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct VersionByTimestampRequest {
+    pub timestamp_usecs: u64, // The timestamp (in microseconds) to look up
+    pub ledger_version: u64,  // The ledger version at which to perform the lookup
+}
+
+/// A storage service request for fetching the metadata of the blocks holding
+/// the transactions between `start_version` and `end_version` (inclusive).
+/// This allows clients to map versions to block heights (and timestamps)
+/// without relying on the REST API. In some cases, less blocks may be
+/// returned (e.g., due to chunk limits).
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct BlockMetadataRequest {
+    pub start_version: u64, // The version held by the first block to fetch
+    pub end_version: u64,   // The version held by the last block to fetch (inclusive)
+}
+
+/// A storage service request for fetching a transaction list without a proof.
+/// This is only served if the server explicitly enables it, and the response
+/// cannot be verified by the client (e.g., it is intended for indexers that
+/// sync from a trusted upstream).
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct TransactionsWithoutProofRequest {
+    pub start_version: u64,   // The starting version of the transaction list
+    pub end_version: u64,     // The ending version of the transaction list (inclusive)
+    pub include_events: bool, // Whether or not to include events in the response
+}
+
 /// A storage service request for fetching a new transaction or output list
 /// beyond the already known version and epoch.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
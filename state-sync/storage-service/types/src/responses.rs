@@ -3,11 +3,13 @@
 
 use crate::{
     requests::DataRequest::{
-        GetEpochEndingLedgerInfos, GetNewTransactionOutputsWithProof,
-        GetNewTransactionsOrOutputsWithProof, GetNewTransactionsWithProof,
-        GetNumberOfStatesAtVersion, GetServerProtocolVersion, GetStateValuesWithProof,
-        GetStorageServerSummary, GetTransactionOutputsWithProof, GetTransactionsOrOutputsWithProof,
-        GetTransactionsWithProof, SubscribeTransactionOutputsWithProof,
+        GetBatch, GetBlockMetadata, GetEpochEndingLedgerInfos, GetInternalIndexerSummary,
+        GetNewTransactionOutputsWithProof, GetNewTransactionsOrOutputsWithProof,
+        GetNewTransactionsWithProof, GetNumberOfStatesAtVersion, GetPruneWindowForecast,
+        GetServerProtocolVersion, GetStateValuesByKeyRangeWithProof, GetStateValuesWithProof,
+        GetStorageServerSummary, GetTransactionByHashWithProof, GetTransactionOutputsWithProof,
+        GetTransactionsOrOutputsWithProof, GetTransactionsWithProof, GetTransactionsWithoutProof,
+        GetVersionByTimestamp, SubscribeTransactionOutputsWithProof,
         SubscribeTransactionsOrOutputsWithProof, SubscribeTransactionsWithProof,
     },
     responses::Error::DegenerateRangeError,
@@ -17,20 +19,29 @@ use aptos_compression::{client::CompressionClient, CompressedData};
 use aptos_config::config::{
     AptosDataClientConfig, StorageServiceConfig, MAX_APPLICATION_MESSAGE_SIZE,
 };
+use aptos_crypto::hash::CryptoHash;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
+    account_config::NewBlockEvent,
+    contract_event::ContractEvent,
     epoch_change::EpochChangeProof,
-    ledger_info::LedgerInfoWithSignatures,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::accumulator::InMemoryEventAccumulator,
     state_store::state_value::StateValueChunkWithProof,
-    transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
+    transaction::{
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutputListWithProof,
+        TransactionWithProof, Version,
+    },
 };
 use num_traits::{PrimInt, Zero};
 #[cfg(test)]
 use proptest::prelude::{any, Arbitrary, BoxedStrategy, Strategy};
 use serde::{Deserialize, Serialize};
+use siphasher::sip::SipHasher13;
 use std::{
     convert::TryFrom,
     fmt::{Display, Formatter},
+    hash::Hasher,
 };
 use thiserror::Error;
 
@@ -45,6 +56,10 @@ pub enum Error {
     UnexpectedErrorEncountered(String),
     #[error("Unexpected response error: {0}")]
     UnexpectedResponseError(String),
+    #[error("Response checksum mismatch! Expected: {0}, found: {1}")]
+    ChecksumMismatch(u64, u64),
+    #[error("Invalid proof: {0}")]
+    InvalidProof(String),
 }
 
 impl From<aptos_compression::Error> for Error {
@@ -59,6 +74,7 @@ impl From<aptos_compression::Error> for Error {
 pub enum StorageServiceResponse {
     CompressedResponse(String, CompressedData), // Store the label and the data (e.g., for logging/metrics)
     RawResponse(DataResponse),
+    ChecksummedResponse(ChecksummedData), // Note: this must be appended to preserve wire compatibility
 }
 
 impl StorageServiceResponse {
@@ -99,9 +115,30 @@ impl StorageServiceResponse {
                 Ok(data_response)
             },
             StorageServiceResponse::RawResponse(data_response) => Ok(data_response.clone()),
+            StorageServiceResponse::ChecksummedResponse(checksummed_data) => {
+                checksummed_data.get_data_response()
+            },
         }
     }
 
+    /// Returns the response in a checksummed envelope (preserving any compression)
+    pub fn with_checksum(self) -> Result<Self, Error> {
+        let checksummed_data = match self {
+            StorageServiceResponse::CompressedResponse(label, compressed_data) => {
+                ChecksummedData::new(label, compressed_data, true)
+            },
+            StorageServiceResponse::RawResponse(data_response) => {
+                let raw_data = bcs::to_bytes(&data_response)
+                    .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))?;
+                ChecksummedData::new(data_response.get_label().into(), raw_data, false)
+            },
+            StorageServiceResponse::ChecksummedResponse(_) => return Ok(self),
+        };
+        Ok(StorageServiceResponse::ChecksummedResponse(
+            checksummed_data,
+        ))
+    }
+
     /// Returns a summary label for the response
     pub fn get_label(&self) -> String {
         match self {
@@ -109,13 +146,111 @@ impl StorageServiceResponse {
             StorageServiceResponse::RawResponse(data_response) => {
                 data_response.get_label().to_string()
             },
+            StorageServiceResponse::ChecksummedResponse(checksummed_data) => {
+                checksummed_data.label.clone()
+            },
         }
     }
 
     /// Returns true iff the data response is compressed
     pub fn is_compressed(&self) -> bool {
-        matches!(self, Self::CompressedResponse(_, _))
+        match self {
+            Self::CompressedResponse(_, _) => true,
+            Self::RawResponse(_) => false,
+            Self::ChecksummedResponse(checksummed_data) => checksummed_data.compressed,
+        }
+    }
+
+    /// Returns true iff the data response is checksummed
+    pub fn is_checksummed(&self) -> bool {
+        matches!(self, Self::ChecksummedResponse(_))
     }
+
+    /// Returns the size of the BCS serialized response. For compressed and
+    /// checksummed responses, this is computed from the (already serialized)
+    /// payload, without serializing it again.
+    pub fn serialized_size(&self) -> Result<usize, Error> {
+        let payload_size = match self {
+            Self::CompressedResponse(label, compressed_data) => {
+                serialized_bytes_size(label.len()) + serialized_bytes_size(compressed_data.len())
+            },
+            Self::RawResponse(_) => {
+                return bcs::serialized_size(self)
+                    .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()));
+            },
+            Self::ChecksummedResponse(checksummed_data) => {
+                serialized_bytes_size(checksummed_data.label.len())
+                    + 1 // The compressed flag
+                    + serialized_bytes_size(checksummed_data.data.len())
+                    + 8 // The checksum
+            },
+        };
+        Ok(1 + payload_size) // The variant index takes a single byte
+    }
+}
+
+/// Returns the size of a BCS serialized byte sequence (or string) of the
+/// given length, i.e., the ULEB128 encoded length followed by the bytes.
+fn serialized_bytes_size(num_bytes: usize) -> usize {
+    let mut length_size = 1;
+    let mut remaining_length = num_bytes >> 7;
+    while remaining_length > 0 {
+        length_size += 1;
+        remaining_length >>= 7;
+    }
+    length_size + num_bytes
+}
+
+/// A BCS serialized (and optionally compressed) data response, together with
+/// a checksum of the serialized bytes. This allows clients to detect corrupted
+/// responses before deserializing them and attempting any proof verification.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ChecksummedData {
+    pub label: String, // The label of the data response (e.g., for logging/metrics)
+    pub compressed: bool,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    pub checksum: u64,
+}
+
+impl ChecksummedData {
+    pub fn new(label: String, data: Vec<u8>, compressed: bool) -> Self {
+        let checksum = compute_checksum(&data);
+        Self {
+            label,
+            compressed,
+            data,
+            checksum,
+        }
+    }
+
+    /// Verifies the checksum and returns the (decompressed and deserialized) data response
+    pub fn get_data_response(&self) -> Result<DataResponse, Error> {
+        let checksum = compute_checksum(&self.data);
+        if checksum != self.checksum {
+            return Err(Error::ChecksumMismatch(self.checksum, checksum));
+        }
+
+        let raw_data = if self.compressed {
+            aptos_compression::decompress(
+                &self.data,
+                CompressionClient::StateSync,
+                MAX_APPLICATION_MESSAGE_SIZE,
+            )?
+        } else {
+            self.data.clone()
+        };
+        bcs::from_bytes::<DataResponse>(&raw_data)
+            .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))
+    }
+}
+
+/// Computes the checksum of the given (serialized) data. We use SipHash
+/// (with fixed keys) as it is fast and stable across platforms and versions.
+pub fn compute_checksum(data: &[u8]) -> u64 {
+    let mut hasher = SipHasher13::new();
+    hasher.write(data);
+    hasher.finish()
 }
 
 /// A useful type to hold optional transaction data
@@ -124,6 +259,143 @@ pub type TransactionOrOutputListWithProof = (
     Option<TransactionOutputListWithProof>,
 );
 
+/// A list of transactions (and optionally, their events) without any proofs.
+/// The transaction infos are included for convenience, but nothing in the
+/// list can be verified against a ledger info, so clients must trust the server.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionListWithoutProof {
+    pub first_transaction_version: Option<Version>,
+    pub transactions: Vec<Transaction>,
+    pub transaction_infos: Vec<TransactionInfo>,
+    pub events: Option<Vec<Vec<ContractEvent>>>,
+}
+
+impl TransactionListWithoutProof {
+    pub fn new(
+        first_transaction_version: Option<Version>,
+        transactions: Vec<Transaction>,
+        transaction_infos: Vec<TransactionInfo>,
+        events: Option<Vec<Vec<ContractEvent>>>,
+    ) -> Self {
+        Self {
+            first_transaction_version,
+            transactions,
+            transaction_infos,
+            events,
+        }
+    }
+
+    /// Returns the number of transactions in the list
+    pub fn get_num_transactions(&self) -> usize {
+        self.transactions.len()
+    }
+}
+
+/// The metadata of a committed block, including the range of versions it holds.
+/// Note: the last version of the latest block is the latest committed version,
+/// so it may grow as more transactions are committed.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BlockVersionRange {
+    pub block_height: u64,          // The height of the block
+    pub block_timestamp_usecs: u64, // The timestamp (in microseconds) of the block
+    pub first_version: Version,     // The first version held by the block
+    pub last_version: Version,      // The last version held by the block (inclusive)
+}
+
+/// The version of the last transaction committed before a timestamp, with a
+/// proof. The proof holds the first transactions (with their events) of the
+/// block containing the version and of the next block. Their new block events
+/// show that the blocks are consecutive, and that they were proposed before
+/// and at (or after) the timestamp, respectively.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VersionByTimestampWithProof {
+    pub version: Version,                  // The last version before the timestamp
+    pub block_start: TransactionWithProof, // The first transaction of its block
+    pub next_block_start: TransactionWithProof, // The first transaction of the next block
+}
+
+impl VersionByTimestampWithProof {
+    /// Verifies the version against the ledger info at the requested ledger
+    /// version, for the given timestamp (in microseconds)
+    pub fn verify(&self, ledger_info: &LedgerInfo, timestamp_usecs: u64) -> Result<(), Error> {
+        let block = verify_block_start(&self.block_start, ledger_info)?;
+        let next_block = verify_block_start(&self.next_block_start, ledger_info)?;
+
+        // The version must be the last one of the block
+        if self.block_start.version > self.version
+            || self.next_block_start.version != self.version.saturating_add(1)
+        {
+            return Err(Error::InvalidProof(format!(
+                "Version {} is not the last version of the block starting at {} (next block starts at {})",
+                self.version, self.block_start.version, self.next_block_start.version
+            )));
+        }
+        if next_block.height() != block.height().saturating_add(1) {
+            return Err(Error::InvalidProof(format!(
+                "Blocks are not consecutive! Heights: {}, {}",
+                block.height(),
+                next_block.height()
+            )));
+        }
+
+        // The block must start before the timestamp, and the next block at (or after) it
+        if block.proposed_time() >= timestamp_usecs || next_block.proposed_time() < timestamp_usecs
+        {
+            return Err(Error::InvalidProof(format!(
+                "Block times ({}, {}) do not surround the timestamp {}",
+                block.proposed_time(),
+                next_block.proposed_time(),
+                timestamp_usecs
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Verifies the transaction (and its events) against the ledger info, and
+/// returns the new block event emitted by the transaction.
+fn verify_block_start(
+    transaction: &TransactionWithProof,
+    ledger_info: &LedgerInfo,
+) -> Result<NewBlockEvent, Error> {
+    let transaction_info = transaction.proof.transaction_info();
+    if transaction.transaction.hash() != transaction_info.transaction_hash() {
+        return Err(Error::InvalidProof(format!(
+            "Transaction hash mismatch at version {}",
+            transaction.version
+        )));
+    }
+
+    let events = transaction.events.as_ref().ok_or_else(|| {
+        Error::InvalidProof(format!("Missing events at version {}", transaction.version))
+    })?;
+    let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+    if InMemoryEventAccumulator::from_leaves(&event_hashes).root_hash()
+        != transaction_info.event_root_hash()
+    {
+        return Err(Error::InvalidProof(format!(
+            "Event root hash mismatch at version {}",
+            transaction.version
+        )));
+    }
+
+    transaction
+        .proof
+        .verify(ledger_info, transaction.version)
+        .map_err(|error| Error::InvalidProof(error.to_string()))?;
+
+    events
+        .iter()
+        .find_map(|event| NewBlockEvent::try_from(event).ok())
+        .ok_or_else(|| {
+            Error::InvalidProof(format!(
+                "No new block event at version {}",
+                transaction.version
+            ))
+        })
+}
+
 /// A single data response.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[allow(clippy::large_enum_variant)]
@@ -139,6 +411,13 @@ pub enum DataResponse {
     TransactionsWithProof(TransactionListWithProof),
     NewTransactionsOrOutputsWithProof((TransactionOrOutputListWithProof, LedgerInfoWithSignatures)),
     TransactionsOrOutputsWithProof(TransactionOrOutputListWithProof),
+    TransactionsWithoutProof(TransactionListWithoutProof),
+    Batch(Vec<DataResponse>),
+    TransactionByHashWithProof(Option<TransactionWithProof>),
+    VersionByTimestamp(VersionByTimestampWithProof),
+    BlockMetadata(Vec<BlockVersionRange>),
+    PruneWindowForecast(Option<PruneWindowForecast>),
+    InternalIndexerSummary(Option<InternalIndexerSummary>),
 }
 
 impl DataResponse {
@@ -156,6 +435,13 @@ impl DataResponse {
             Self::TransactionsWithProof(_) => "transactions_with_proof",
             Self::NewTransactionsOrOutputsWithProof(_) => "new_transactions_or_outputs_with_proof",
             Self::TransactionsOrOutputsWithProof(_) => "transactions_or_outputs_with_proof",
+            Self::TransactionsWithoutProof(_) => "transactions_without_proof",
+            Self::Batch(_) => "batch",
+            Self::TransactionByHashWithProof(_) => "transaction_by_hash_with_proof",
+            Self::VersionByTimestamp(_) => "version_by_timestamp",
+            Self::BlockMetadata(_) => "block_metadata",
+            Self::PruneWindowForecast(_) => "prune_window_forecast",
+            Self::InternalIndexerSummary(_) => "internal_indexer_summary",
         }
     }
 }
@@ -347,6 +633,111 @@ impl TryFrom<StorageServiceResponse> for TransactionOrOutputListWithProof {
     }
 }
 
+impl TryFrom<StorageServiceResponse> for TransactionListWithoutProof {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::TransactionsWithoutProof(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected transactions_without_proof, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<StorageServiceResponse> for Option<TransactionWithProof> {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::TransactionByHashWithProof(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected transaction_by_hash_with_proof, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<StorageServiceResponse> for VersionByTimestampWithProof {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::VersionByTimestamp(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected version_by_timestamp, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<StorageServiceResponse> for Vec<BlockVersionRange> {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::BlockMetadata(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected block_metadata, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<StorageServiceResponse> for Option<PruneWindowForecast> {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::PruneWindowForecast(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected prune_window_forecast, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<StorageServiceResponse> for Option<InternalIndexerSummary> {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::InternalIndexerSummary(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected internal_indexer_summary, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<StorageServiceResponse> for Vec<DataResponse> {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::Batch(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected batch, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
 /// The protocol version run by this server. Clients request this first to
 /// identify what API calls and data requests the server supports.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -433,6 +824,55 @@ pub struct DataSummary {
     pub transaction_outputs: Option<CompleteDataRange<Version>>,
 }
 
+/// A summary of the data indexed by the internal indexer of the storage
+/// service node. This allows peers (and tooling) to identify the nodes
+/// that can serve indexed queries (e.g., table infos and events by type).
+/// The summary is served on request (i.e., it is not part of the data
+/// summary), and is missing if the internal indexer is disabled.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct InternalIndexerSummary {
+    /// The range of versions for which the table infos have been indexed.
+    /// If the range is [(X,Y)], all tables created at versions X->Y
+    /// (inclusive) can be resolved.
+    pub table_infos: Option<CompleteDataRange<Version>>,
+    /// The range of versions for which events are indexed by type and
+    /// still held. If the range is [(X,Y)], the events emitted at
+    /// versions X->Y (inclusive) can be looked up by type.
+    pub events_by_type: Option<CompleteDataRange<Version>>,
+}
+
+/// A forecast of the data that will still be held by the storage service
+/// after `duration_secs` has elapsed, computed by the server from its pruner
+/// configs and recent throughput. This allows clients to plan long downloads
+/// (e.g., state snapshots) against ranges that won't be pruned mid-download.
+/// Forecasts are only a hint, and are served on request (i.e., they are not
+/// part of the data summary) as computing them requires additional reads.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PruneWindowForecast {
+    /// The number of seconds the forecast holds for
+    pub duration_secs: u64,
+    /// The lowest transaction version expected to be held for `duration_secs`
+    pub lowest_transaction_version: Option<Version>,
+    /// The lowest transaction output version expected to be held for `duration_secs`
+    pub lowest_transaction_output_version: Option<Version>,
+    /// The lowest state version expected to be held for `duration_secs`
+    pub lowest_state_version: Option<Version>,
+}
+
+impl InternalIndexerSummary {
+    /// Returns true iff the events emitted at the given versions (inclusive)
+    /// can be looked up by type using the internal indexer of the node.
+    pub fn can_service_events_by_type(&self, start_version: Version, end_version: Version) -> bool {
+        let desired_range = match CompleteDataRange::new(start_version, end_version) {
+            Ok(desired_range) => desired_range,
+            Err(_) => return false,
+        };
+        self.events_by_type
+            .map(|range| range.superset_of(&desired_range))
+            .unwrap_or(false)
+    }
+}
+
 impl DataSummary {
     /// Returns true iff the request can be serviced
     pub fn can_service(
@@ -442,7 +882,10 @@ impl DataSummary {
         request: &StorageServiceRequest,
     ) -> bool {
         match &request.data_request {
-            GetServerProtocolVersion | GetStorageServerSummary => true,
+            GetServerProtocolVersion
+            | GetStorageServerSummary
+            | GetPruneWindowForecast
+            | GetInternalIndexerSummary => true,
             GetEpochEndingLedgerInfos(request) => {
                 let desired_range =
                     match CompleteDataRange::new(request.start_epoch, request.expected_end_epoch) {
@@ -472,22 +915,8 @@ impl DataSummary {
                 .states
                 .map(|range| range.contains(*version))
                 .unwrap_or(false),
-            GetStateValuesWithProof(request) => {
-                let proof_version = request.version;
-
-                let can_serve_states = self
-                    .states
-                    .map(|range| range.contains(request.version))
-                    .unwrap_or(false);
-
-                let can_create_proof = self
-                    .synced_ledger_info
-                    .as_ref()
-                    .map(|li| li.ledger_info().version() >= proof_version)
-                    .unwrap_or(false);
-
-                can_serve_states && can_create_proof
-            },
+            GetStateValuesWithProof(request) => self.can_service_states(request.version),
+            GetStateValuesByKeyRangeWithProof(request) => self.can_service_states(request.version),
             GetTransactionOutputsWithProof(request) => {
                 let desired_range =
                     match CompleteDataRange::new(request.start_version, request.end_version) {
@@ -553,6 +982,74 @@ impl DataSummary {
 
                 can_serve_txns && can_serve_outputs && can_create_proof
             },
+            GetTransactionsWithoutProof(request) => {
+                let desired_range =
+                    match CompleteDataRange::new(request.start_version, request.end_version) {
+                        Ok(desired_range) => desired_range,
+                        Err(_) => return false,
+                    };
+
+                // No proof is required, so only the transactions must be held
+                self.transactions
+                    .map(|range| range.superset_of(&desired_range))
+                    .unwrap_or(false)
+            },
+            GetTransactionByHashWithProof(request) => {
+                // The version of the transaction is unknown until it is looked up
+                // (and it may have been pruned), so only the proof can be checked.
+                let proof_version = request.proof_version;
+
+                let can_serve_proof_version = self
+                    .transactions
+                    .map(|range| range.contains(proof_version))
+                    .unwrap_or(false);
+
+                let can_create_proof = self
+                    .synced_ledger_info
+                    .as_ref()
+                    .map(|li| li.ledger_info().version() >= proof_version)
+                    .unwrap_or(false);
+
+                can_serve_proof_version && can_create_proof
+            },
+            GetVersionByTimestamp(request) => {
+                let ledger_version = request.ledger_version;
+
+                let can_serve_ledger_version = self
+                    .transactions
+                    .map(|range| range.contains(ledger_version))
+                    .unwrap_or(false);
+
+                // A block must have been committed at (or after) the timestamp by
+                // the ledger version, otherwise the last version before the timestamp
+                // is still unknown. The summary only holds the timestamp of the synced
+                // ledger info, which bounds the timestamp at the ledger version from
+                // above. So this rules out the requests that can't be served, and the
+                // timestamp at the ledger version is checked when serving the request.
+                let can_determine_version = self
+                    .synced_ledger_info
+                    .as_ref()
+                    .map(|li| {
+                        let ledger_info = li.ledger_info();
+                        ledger_info.version() >= ledger_version
+                            && ledger_info.timestamp_usecs() >= request.timestamp_usecs
+                    })
+                    .unwrap_or(false);
+
+                can_serve_ledger_version && can_determine_version
+            },
+            GetBlockMetadata(request) => {
+                let desired_range =
+                    match CompleteDataRange::new(request.start_version, request.end_version) {
+                        Ok(desired_range) => desired_range,
+                        Err(_) => return false,
+                    };
+
+                // The block index is pruned along with the transactions
+                self.transactions
+                    .map(|range| range.superset_of(&desired_range))
+                    .unwrap_or(false)
+            },
             SubscribeTransactionOutputsWithProof(_) => can_service_subscription_request(
                 aptos_data_client_config,
                 time_service,
@@ -568,9 +1065,38 @@ impl DataSummary {
                 time_service,
                 self.synced_ledger_info.as_ref(),
             ),
+            GetBatch(request) => {
+                // Every request in the batch must be batchable and serviceable
+                !request.requests.is_empty()
+                    && request.requests.iter().all(|data_request| {
+                        data_request.is_batchable()
+                            && self.can_service(
+                                aptos_data_client_config,
+                                time_service.clone(),
+                                &StorageServiceRequest::new(data_request.clone(), false),
+                            )
+                    })
+            },
         }
     }
 
+    /// Returns true iff the states at the given version can be
+    /// served (and proven) by the storage service instance.
+    fn can_service_states(&self, version: Version) -> bool {
+        let can_serve_states = self
+            .states
+            .map(|range| range.contains(version))
+            .unwrap_or(false);
+
+        let can_create_proof = self
+            .synced_ledger_info
+            .as_ref()
+            .map(|li| li.ledger_info().version() >= version)
+            .unwrap_or(false);
+
+        can_serve_states && can_create_proof
+    }
+
     /// Returns the version of the synced ledger info (if one exists)
     pub fn get_synced_ledger_info_version(&self) -> Option<u64> {
         self.synced_ledger_info
@@ -3,28 +3,47 @@
 
 use crate::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
+        BatchRequest, BlockMetadataRequest, DataRequest, DataRequestType,
+        EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
         NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
-        StateValuesWithProofRequest, SubscribeTransactionOutputsWithProofRequest,
+        StateValuesByKeyRangeWithProofRequest, StateValuesWithProofRequest,
+        StorageServiceRequestEnvelope, SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
-        SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
-        TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
+        SubscriptionStreamMetadata, TransactionByHashWithProofRequest,
+        TransactionOutputsWithProofRequest, TransactionsOrOutputsWithProofRequest,
+        TransactionsWithProofRequest, TransactionsWithoutProofRequest, VersionByTimestampRequest,
     },
-    responses::{CompleteDataRange, DataSummary, ProtocolMetadata},
-    Epoch, StorageServiceRequest,
+    responses::{
+        ChecksummedData, CompleteDataRange, DataResponse, DataSummary, Error,
+        InternalIndexerSummary, ProtocolMetadata, ServerProtocolVersion, StorageServiceResponse,
+        VersionByTimestampWithProof,
+    },
+    Epoch, StorageServiceError, StorageServiceErrorCategory, StorageServiceMessage,
+    StorageServiceRequest, STORAGE_SERVICE_ERROR_VERSION,
 };
 use aptos_config::config::AptosDataClientConfig;
-use aptos_crypto::hash::HashValue;
+use aptos_crypto::hash::{CryptoHash, HashValue};
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{new_block_event_key, NewBlockEvent},
     aggregate_signature::AggregateSignature,
     block_info::BlockInfo,
+    block_metadata::BlockMetadata,
+    contract_event::ContractEvent,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    transaction::Version,
+    move_resource::MoveStructType,
+    proof::{
+        accumulator::{InMemoryEventAccumulator, InMemoryTransactionAccumulator},
+        TransactionAccumulatorProof, TransactionInfoWithProof,
+    },
+    transaction::{ExecutionStatus, Transaction, TransactionInfo, TransactionWithProof, Version},
 };
-use claims::{assert_err, assert_ok};
+use claims::{assert_err, assert_matches, assert_none, assert_ok};
+use move_core_types::language_storage::TypeTag;
 use proptest::{arbitrary::any, prelude::*};
 use rand::{thread_rng, Rng};
+use serde::Deserialize;
 
 #[test]
 fn test_complete_data_ranges() {
@@ -279,6 +298,228 @@ fn test_data_summary_service_transactions() {
     }
 }
 
+#[test]
+fn test_data_summary_service_transactions_without_proof() {
+    // Create a data client config and data summary
+    let data_client_config = AptosDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version(150)),
+        transactions: Some(create_data_range(100, 200)),
+        ..Default::default()
+    };
+
+    // Verify the different requests that can be serviced
+    for compression in [true, false] {
+        // Test the valid data ranges (no proofs are required, so
+        // ranges beyond the synced ledger info are also serviceable)
+        let valid_ranges = vec![(100, 200), (125, 175), (100, 100), (200, 200), (175, 200)];
+        verify_can_service_transactions_without_proof_requests(
+            &data_client_config,
+            &data_summary,
+            compression,
+            valid_ranges,
+            true,
+        );
+
+        // Test the missing and invalid data ranges
+        let invalid_ranges = vec![(99, 200), (100, 201), (50, 250), (150, 250), (200, 100)];
+        verify_can_service_transactions_without_proof_requests(
+            &data_client_config,
+            &data_summary,
+            compression,
+            invalid_ranges,
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_data_summary_service_transaction_by_hash() {
+    // Create a data client config and data summary
+    let data_client_config = AptosDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version(150)),
+        transactions: Some(create_data_range(100, 200)),
+        ..Default::default()
+    };
+
+    // Verify the different requests that can be serviced
+    for compression in [true, false] {
+        // Test the valid proof versions
+        let valid_proof_versions = vec![100, 125, 150];
+        verify_can_service_transaction_by_hash_requests(
+            &data_client_config,
+            &data_summary,
+            compression,
+            valid_proof_versions,
+            true,
+        );
+
+        // Test the missing and invalid proof versions
+        let invalid_proof_versions = vec![0, 99, 151, 200, 201];
+        verify_can_service_transaction_by_hash_requests(
+            &data_client_config,
+            &data_summary,
+            compression,
+            invalid_proof_versions,
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_data_summary_service_version_by_timestamp() {
+    // Create a data client config and data summary
+    let data_client_config = AptosDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version_and_timestamp(150, 1_000)),
+        transactions: Some(create_data_range(100, 200)),
+        ..Default::default()
+    };
+
+    // Verify the different requests that can be serviced
+    for compression in [true, false] {
+        // Test the valid ledger versions and timestamps
+        for (ledger_version, timestamp_usecs) in [(100, 0), (125, 500), (150, 1_000)] {
+            let request =
+                create_version_by_timestamp_request(ledger_version, timestamp_usecs, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, true);
+        }
+
+        // Test the missing and invalid ledger versions
+        for ledger_version in [0, 99, 151, 200, 201] {
+            let request = create_version_by_timestamp_request(ledger_version, 0, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, false);
+        }
+
+        // Test the timestamps beyond the synced ledger info
+        for timestamp_usecs in [1_001, 2_000] {
+            let request = create_version_by_timestamp_request(150, timestamp_usecs, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, false);
+        }
+    }
+}
+
+#[test]
+fn test_data_summary_service_block_metadata() {
+    // Create a data client config and data summary
+    let data_client_config = AptosDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version(150)),
+        transactions: Some(create_data_range(100, 200)),
+        ..Default::default()
+    };
+
+    // Verify the different requests that can be serviced
+    for compression in [true, false] {
+        // Test the valid data ranges
+        for (start_version, end_version) in [(100, 200), (125, 175), (100, 100), (200, 200)] {
+            let request = create_block_metadata_request(start_version, end_version, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, true);
+        }
+
+        // Test the missing and invalid data ranges
+        for (start_version, end_version) in [(99, 200), (100, 201), (50, 250), (200, 100)] {
+            let request = create_block_metadata_request(start_version, end_version, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, false);
+        }
+    }
+}
+
+#[test]
+fn test_data_summary_service_batch() {
+    // Create a data client config and data summary
+    let data_client_config = AptosDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version(250)),
+        epoch_ending_ledger_infos: Some(create_data_range(0, 100)),
+        transactions: Some(create_data_range(100, 200)),
+        ..Default::default()
+    };
+
+    for compression in [true, false] {
+        // Verify that a batch of serviceable requests can be serviced
+        let epoch_ending_request = create_epoch_ending_request(10, 20, compression);
+        let transactions_request = create_transactions_request(250, 100, 150, compression);
+        let batch_request = create_batch_request(
+            vec![
+                epoch_ending_request.data_request.clone(),
+                transactions_request.data_request.clone(),
+            ],
+            compression,
+        );
+        verify_serviceability(
+            &data_client_config,
+            &data_summary,
+            None,
+            batch_request,
+            true,
+        );
+
+        // Verify that a batch with a single unserviceable request cannot be serviced
+        let missing_transactions_request = create_transactions_request(250, 150, 250, compression);
+        let batch_request = create_batch_request(
+            vec![
+                epoch_ending_request.data_request.clone(),
+                missing_transactions_request.data_request,
+            ],
+            compression,
+        );
+        verify_serviceability(
+            &data_client_config,
+            &data_summary,
+            None,
+            batch_request,
+            false,
+        );
+
+        // Verify that empty and nested batches cannot be serviced
+        let empty_batch_request = create_batch_request(vec![], compression);
+        verify_serviceability(
+            &data_client_config,
+            &data_summary,
+            None,
+            empty_batch_request.clone(),
+            false,
+        );
+        let nested_batch_request = create_batch_request(
+            vec![
+                transactions_request.data_request.clone(),
+                empty_batch_request.data_request,
+            ],
+            compression,
+        );
+        verify_serviceability(
+            &data_client_config,
+            &data_summary,
+            None,
+            nested_batch_request,
+            false,
+        );
+
+        // Verify that batches with optimistic fetches or subscriptions cannot be serviced
+        for unbatchable_request in [
+            create_optimistic_fetch_request(200, compression),
+            create_subscription_request(200, compression),
+        ] {
+            let batch_request = create_batch_request(
+                vec![
+                    transactions_request.data_request.clone(),
+                    unbatchable_request.data_request,
+                ],
+                compression,
+            );
+            verify_serviceability(
+                &data_client_config,
+                &data_summary,
+                None,
+                batch_request,
+                false,
+            );
+        }
+    }
+}
+
 #[test]
 fn test_data_summary_service_transaction_outputs() {
     // Create a data client config and data summary
@@ -466,6 +707,25 @@ fn test_data_summary_can_service_state_chunk_request() {
     }
 }
 
+#[test]
+fn test_internal_indexer_summary_can_service_events_by_type() {
+    // Create an internal indexer summary that only indexes table infos
+    let mut internal_indexer_summary = InternalIndexerSummary {
+        table_infos: Some(create_data_range(0, 200)),
+        events_by_type: None,
+    };
+    assert!(!internal_indexer_summary.can_service_events_by_type(100, 150));
+
+    // Index the events by type and verify the valid and invalid ranges
+    internal_indexer_summary.events_by_type = Some(create_data_range(100, 200));
+    for (start_version, end_version) in [(100, 200), (125, 175), (150, 150)] {
+        assert!(internal_indexer_summary.can_service_events_by_type(start_version, end_version));
+    }
+    for (start_version, end_version) in [(0, 200), (99, 150), (150, 201), (175, 125)] {
+        assert!(!internal_indexer_summary.can_service_events_by_type(start_version, end_version));
+    }
+}
+
 #[test]
 fn test_protocol_metadata_service() {
     // Create the protocol metadata
@@ -492,6 +752,314 @@ fn test_protocol_metadata_service() {
     }
 }
 
+#[test]
+fn test_storage_service_error_categories() {
+    // Verify that legacy errors are mapped to the expected categories
+    for (error, expected_category) in [
+        (
+            StorageServiceError::InternalError("internal".into()),
+            StorageServiceErrorCategory::Retryable,
+        ),
+        (
+            StorageServiceError::InvalidRequest("invalid".into()),
+            StorageServiceErrorCategory::Fatal,
+        ),
+        (
+            StorageServiceError::TooManyInvalidRequests("too many".into()),
+            StorageServiceErrorCategory::Permission,
+        ),
+        (
+            StorageServiceError::InvalidRequest("[storage service error: category=".into()),
+            StorageServiceErrorCategory::Fatal,
+        ),
+    ] {
+        assert_eq!(error.category(), expected_category);
+        assert_none!(error.version());
+        assert_none!(error.earliest_available_version());
+    }
+
+    // Verify that pruned data errors carry the earliest available version
+    let error = StorageServiceError::new_data_pruned(100, "pruned".into());
+    assert_eq!(error.category(), StorageServiceErrorCategory::Pruned);
+    assert_eq!(error.earliest_available_version(), Some(100));
+    assert_matches!(error, StorageServiceError::InvalidRequest(_));
+    let serialized_error = bcs::to_bytes(&error).unwrap();
+    let deserialized_error: StorageServiceError = bcs::from_bytes(&serialized_error).unwrap();
+    assert_eq!(deserialized_error, error);
+
+    // Verify that categorized errors retain their category and version
+    for category in StorageServiceErrorCategory::ALL {
+        let error = StorageServiceError::new(category, "error".into());
+        assert_eq!(error.category(), category);
+        assert_eq!(error.version(), Some(STORAGE_SERVICE_ERROR_VERSION));
+        assert_none!(error.earliest_available_version());
+        assert_eq!(
+            error.category().is_retryable(),
+            category == StorageServiceErrorCategory::Retryable
+        );
+        assert!(error.to_string().ends_with("] error"));
+
+        // Verify the error can be serialized and deserialized
+        let serialized_error = bcs::to_bytes(&error).unwrap();
+        let deserialized_error: StorageServiceError = bcs::from_bytes(&serialized_error).unwrap();
+        assert_eq!(deserialized_error, error);
+    }
+
+    // Verify that unknown metadata fields are ignored
+    let error = StorageServiceError::InternalError(
+        "[storage service error: category=too_large, version=2, unknown=1] error".into(),
+    );
+    assert_eq!(error.category(), StorageServiceErrorCategory::TooLarge);
+    assert_eq!(error.version(), Some(2));
+}
+
+#[test]
+fn test_storage_service_error_wire_compatibility() {
+    // The legacy error format (i.e., the format older clients can decode)
+    #[derive(Debug, Deserialize, Eq, PartialEq)]
+    enum LegacyStorageServiceError {
+        InternalError(String),
+        InvalidRequest(String),
+        TooManyInvalidRequests(String),
+    }
+
+    // Verify that all errors can be decoded by older clients
+    let mut errors: Vec<_> = StorageServiceErrorCategory::ALL
+        .into_iter()
+        .map(|category| StorageServiceError::new(category, "error".into()))
+        .collect();
+    errors.push(StorageServiceError::new_data_pruned(10, "pruned".into()));
+    for error in errors {
+        let serialized_error = bcs::to_bytes(&error).unwrap();
+        let legacy_error: LegacyStorageServiceError = bcs::from_bytes(&serialized_error).unwrap();
+        let expected_legacy_error = match error {
+            StorageServiceError::InternalError(message) => {
+                LegacyStorageServiceError::InternalError(message)
+            },
+            StorageServiceError::InvalidRequest(message) => {
+                LegacyStorageServiceError::InvalidRequest(message)
+            },
+            StorageServiceError::TooManyInvalidRequests(message) => {
+                LegacyStorageServiceError::TooManyInvalidRequests(message)
+            },
+        };
+        assert_eq!(legacy_error, expected_legacy_error);
+    }
+}
+
+#[test]
+fn test_request_envelopes() {
+    for data_request in create_all_data_requests() {
+        for use_compression in [true, false] {
+            // Verify the request can be wrapped and unwrapped
+            let request = StorageServiceRequest::new(data_request.clone(), use_compression);
+            let envelope = StorageServiceRequestEnvelope::new(&request).unwrap();
+            assert_eq!(
+                envelope.message_type,
+                data_request.get_request_type().get_message_type()
+            );
+            assert_eq!(
+                StorageServiceRequest::try_from(envelope.clone()),
+                Ok(request)
+            );
+
+            // Verify the envelope can be serialized and deserialized
+            let serialized_envelope = bcs::to_bytes(&envelope).unwrap();
+            let deserialized_envelope: StorageServiceRequestEnvelope =
+                bcs::from_bytes(&serialized_envelope).unwrap();
+            assert_eq!(deserialized_envelope, envelope);
+
+            // Verify that trailing bytes in the payload are rejected
+            let mut invalid_envelope = envelope;
+            invalid_envelope.payload.push(0);
+            let error = StorageServiceRequest::try_from(invalid_envelope).unwrap_err();
+            assert_eq!(error.category(), StorageServiceErrorCategory::Fatal);
+        }
+    }
+
+    // Verify that unknown message types are rejected
+    let num_request_types = DataRequestType::ALL.len() as u32;
+    for message_type in [num_request_types, num_request_types + 1, u32::MAX] {
+        assert_eq!(DataRequestType::from_message_type(message_type), None);
+        let envelope = StorageServiceRequestEnvelope {
+            message_type,
+            payload: vec![],
+            use_compression: false,
+        };
+        let error = StorageServiceRequest::try_from(envelope).unwrap_err();
+        assert_eq!(error.category(), StorageServiceErrorCategory::Fatal);
+    }
+}
+
+#[test]
+fn test_data_request_wire_compatibility() {
+    // Verify that the message types are unique and stable
+    for (index, request_type) in DataRequestType::ALL.iter().enumerate() {
+        assert_eq!(request_type.get_message_type(), index as u32);
+        assert_eq!(
+            DataRequestType::from_message_type(index as u32),
+            Some(*request_type)
+        );
+    }
+
+    // Verify that the legacy (unwrapped) format still matches the message types.
+    // If this fails, the DataRequest variants were reordered, which breaks BCS
+    // compatibility with older nodes. New variants must be appended to the end.
+    let data_requests = create_all_data_requests();
+    assert_eq!(data_requests.len(), DataRequestType::ALL.len());
+    for data_request in data_requests {
+        let serialized_request = bcs::to_bytes(&data_request).unwrap();
+        assert_eq!(
+            serialized_request[0] as u32,
+            data_request.get_request_type().get_message_type(),
+            "The BCS variant index of {:?} does not match its message type!",
+            data_request
+        );
+    }
+}
+
+#[test]
+fn test_checksummed_responses() {
+    let data_response = DataResponse::ServerProtocolVersion(ServerProtocolVersion {
+        protocol_version: 1,
+    });
+    for use_compression in [true, false] {
+        // Verify the response can be checksummed (preserving compression)
+        let response = StorageServiceResponse::new(data_response.clone(), use_compression)
+            .unwrap()
+            .with_checksum()
+            .unwrap();
+        assert!(response.is_checksummed());
+        assert_eq!(response.is_compressed(), use_compression);
+        assert_eq!(response.get_data_response().unwrap(), data_response);
+        assert_eq!(response.clone().with_checksum().unwrap(), response);
+
+        // Verify the envelope can be serialized and deserialized
+        let serialized_response = bcs::to_bytes(&response).unwrap();
+        let deserialized_response: StorageServiceResponse =
+            bcs::from_bytes(&serialized_response).unwrap();
+        assert_eq!(deserialized_response, response);
+
+        // Verify that corrupted data is detected
+        let mut corrupted_response = response;
+        if let StorageServiceResponse::ChecksummedResponse(checksummed_data) =
+            &mut corrupted_response
+        {
+            checksummed_data.data[0] ^= 1;
+        }
+        assert_matches!(
+            corrupted_response.get_data_response(),
+            Err(Error::ChecksumMismatch(_, _))
+        );
+    }
+}
+
+#[test]
+fn test_checksummed_requests() {
+    let request = StorageServiceRequest::new(DataRequest::GetServerProtocolVersion, true);
+    let checksummed_request = request.clone().with_checksum(true);
+
+    // Verify the checksum opt-in is signaled by the message (and not the request format)
+    assert_matches!(
+        StorageServiceMessage::new_request(request.clone()),
+        StorageServiceMessage::Request(_)
+    );
+    assert_matches!(
+        StorageServiceMessage::new_request(checksummed_request.clone()),
+        StorageServiceMessage::ChecksummedRequest(_)
+    );
+    assert_eq!(
+        bcs::to_bytes(&checksummed_request).unwrap(),
+        bcs::to_bytes(&request).unwrap()
+    );
+}
+
+#[test]
+fn test_response_serialized_size() {
+    let data_response = DataResponse::ServerProtocolVersion(ServerProtocolVersion {
+        protocol_version: 1,
+    });
+    let mut responses = vec![];
+    for use_compression in [true, false] {
+        let response = StorageServiceResponse::new(data_response.clone(), use_compression).unwrap();
+        responses.push(response.clone().with_checksum().unwrap());
+        responses.push(response);
+    }
+
+    // Add payloads with multi-byte length prefixes
+    responses.push(StorageServiceResponse::CompressedResponse(
+        "label".into(),
+        vec![1; 20_000],
+    ));
+    responses.push(StorageServiceResponse::ChecksummedResponse(
+        ChecksummedData::new("label".into(), vec![1; 300], true),
+    ));
+
+    // Verify the size matches the serialized response
+    for response in responses {
+        assert_eq!(
+            response.serialized_size().unwrap(),
+            bcs::to_bytes(&response).unwrap().len()
+        );
+    }
+}
+
+#[test]
+fn test_version_by_timestamp_proof() {
+    // Create two consecutive blocks proposed at 1_000 and 2_000 (starting at versions 0 and 1)
+    let (version_with_proof, ledger_info) =
+        create_version_by_timestamp_proof([5, 6], [1_000, 2_000]);
+
+    // Verify the proof holds for the timestamps after the first block (up to the second block)
+    for timestamp_usecs in [1_001, 1_500, 2_000] {
+        assert_ok!(version_with_proof.verify(&ledger_info, timestamp_usecs));
+    }
+
+    // Verify the proof fails for the timestamps outside the blocks
+    for timestamp_usecs in [0, 1_000, 2_001] {
+        assert_matches!(
+            version_with_proof.verify(&ledger_info, timestamp_usecs),
+            Err(Error::InvalidProof(_))
+        );
+    }
+
+    // Verify the proof fails if the version is not the last of the block
+    let mut invalid_version = version_with_proof.clone();
+    invalid_version.version = 1;
+    assert_matches!(
+        invalid_version.verify(&ledger_info, 1_500),
+        Err(Error::InvalidProof(_))
+    );
+
+    // Verify the proof fails if the blocks are not consecutive
+    let (non_consecutive, ledger_info_2) =
+        create_version_by_timestamp_proof([5, 7], [1_000, 2_000]);
+    assert_matches!(
+        non_consecutive.verify(&ledger_info_2, 1_500),
+        Err(Error::InvalidProof(_))
+    );
+
+    // Verify the proof fails if the events are missing or tampered with
+    let mut missing_events = version_with_proof.clone();
+    missing_events.next_block_start.events = None;
+    assert_matches!(
+        missing_events.verify(&ledger_info, 1_500),
+        Err(Error::InvalidProof(_))
+    );
+    let mut tampered_events = version_with_proof.clone();
+    tampered_events.block_start.events = tampered_events.next_block_start.events.clone();
+    assert_matches!(
+        tampered_events.verify(&ledger_info, 1_500),
+        Err(Error::InvalidProof(_))
+    );
+
+    // Verify the proof fails against a different ledger
+    assert_matches!(
+        version_with_proof.verify(&ledger_info_2, 1_500),
+        Err(Error::InvalidProof(_))
+    );
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(1000))]
 
@@ -501,6 +1069,125 @@ proptest! {
     }
 }
 
+/// Creates a data request of every type (ordered by message type)
+fn create_all_data_requests() -> Vec<DataRequest> {
+    let subscription_stream_metadata = SubscriptionStreamMetadata {
+        known_version_at_stream_start: 100,
+        known_epoch_at_stream_start: 10,
+        subscription_stream_id: 1,
+    };
+    vec![
+        DataRequest::GetEpochEndingLedgerInfos(EpochEndingLedgerInfoRequest {
+            start_epoch: 10,
+            expected_end_epoch: 20,
+        }),
+        DataRequest::GetNewTransactionOutputsWithProof(NewTransactionOutputsWithProofRequest {
+            known_version: 100,
+            known_epoch: 10,
+        }),
+        DataRequest::GetNewTransactionsWithProof(NewTransactionsWithProofRequest {
+            known_version: 100,
+            known_epoch: 10,
+            include_events: true,
+        }),
+        DataRequest::GetNumberOfStatesAtVersion(100),
+        DataRequest::GetServerProtocolVersion,
+        DataRequest::GetStateValuesWithProof(StateValuesWithProofRequest {
+            version: 100,
+            start_index: 0,
+            end_index: 1000,
+        }),
+        DataRequest::GetStorageServerSummary,
+        DataRequest::GetTransactionOutputsWithProof(TransactionOutputsWithProofRequest {
+            proof_version: 200,
+            start_version: 100,
+            end_version: 150,
+        }),
+        DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+            proof_version: 200,
+            start_version: 100,
+            end_version: 150,
+            include_events: false,
+        }),
+        DataRequest::GetNewTransactionsOrOutputsWithProof(
+            NewTransactionsOrOutputsWithProofRequest {
+                known_version: 100,
+                known_epoch: 10,
+                include_events: true,
+                max_num_output_reductions: 3,
+            },
+        ),
+        DataRequest::GetTransactionsOrOutputsWithProof(TransactionsOrOutputsWithProofRequest {
+            proof_version: 200,
+            start_version: 100,
+            end_version: 150,
+            include_events: true,
+            max_num_output_reductions: 3,
+        }),
+        DataRequest::SubscribeTransactionOutputsWithProof(
+            SubscribeTransactionOutputsWithProofRequest {
+                subscription_stream_metadata,
+                subscription_stream_index: 5,
+            },
+        ),
+        DataRequest::SubscribeTransactionsOrOutputsWithProof(
+            SubscribeTransactionsOrOutputsWithProofRequest {
+                subscription_stream_metadata,
+                subscription_stream_index: 5,
+                include_events: false,
+                max_num_output_reductions: 3,
+            },
+        ),
+        DataRequest::SubscribeTransactionsWithProof(SubscribeTransactionsWithProofRequest {
+            subscription_stream_metadata,
+            subscription_stream_index: 5,
+            include_events: true,
+        }),
+        DataRequest::GetTransactionsWithoutProof(TransactionsWithoutProofRequest {
+            start_version: 100,
+            end_version: 150,
+            include_events: true,
+        }),
+        DataRequest::GetStateValuesByKeyRangeWithProof(StateValuesByKeyRangeWithProofRequest {
+            version: 101,
+            start_key_hash: HashValue::random(),
+            max_items: 1000,
+        }),
+        DataRequest::GetBatch(BatchRequest {
+            requests: vec![
+                DataRequest::GetNumberOfStatesAtVersion(100),
+                DataRequest::GetStorageServerSummary,
+            ],
+        }),
+        DataRequest::GetTransactionByHashWithProof(TransactionByHashWithProofRequest {
+            transaction_hash: HashValue::random(),
+            proof_version: 200,
+            include_events: true,
+        }),
+        DataRequest::GetVersionByTimestamp(VersionByTimestampRequest {
+            timestamp_usecs: 1_000,
+            ledger_version: 200,
+        }),
+        DataRequest::GetBlockMetadata(BlockMetadataRequest {
+            start_version: 100,
+            end_version: 150,
+        }),
+        DataRequest::GetPruneWindowForecast,
+        DataRequest::GetInternalIndexerSummary,
+    ]
+}
+
+/// Creates a batch request containing the given data requests
+fn create_batch_request(
+    data_requests: Vec<DataRequest>,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request = DataRequest::GetBatch(BatchRequest {
+        requests: data_requests,
+    });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
 /// Creates a new data range using the specified bounds
 fn create_data_range(lowest: u64, highest: u64) -> CompleteDataRange<u64> {
     CompleteDataRange::new(lowest, highest).unwrap()
@@ -546,6 +1233,90 @@ fn create_ledger_info_at_version_and_timestamp(
     )
 }
 
+/// Creates a version by timestamp proof for two blocks with the given heights
+/// and timestamps (each holding a single transaction, i.e., at versions 0 and
+/// 1), and the ledger info to verify it against.
+fn create_version_by_timestamp_proof(
+    heights: [u64; 2],
+    timestamps_usecs: [u64; 2],
+) -> (VersionByTimestampWithProof, LedgerInfo) {
+    // Create the block metadata transactions and their new block events
+    let (transactions, transaction_infos): (Vec<_>, Vec<_>) = heights
+        .iter()
+        .zip(timestamps_usecs)
+        .map(|(height, timestamp_usecs)| {
+            let transaction = Transaction::BlockMetadata(BlockMetadata::new(
+                HashValue::random(),
+                0,
+                *height,
+                AccountAddress::ZERO,
+                vec![],
+                vec![],
+                timestamp_usecs,
+            ));
+            let new_block_event = NewBlockEvent::new(
+                AccountAddress::ZERO,
+                0,
+                *height,
+                *height,
+                vec![],
+                AccountAddress::ZERO,
+                vec![],
+                timestamp_usecs,
+            );
+            let events = vec![ContractEvent::new_v1(
+                new_block_event_key(),
+                *height,
+                TypeTag::Struct(Box::new(NewBlockEvent::struct_tag())),
+                bcs::to_bytes(&new_block_event).unwrap(),
+            )];
+            let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+            let transaction_info = TransactionInfo::new(
+                transaction.hash(),
+                HashValue::zero(),
+                InMemoryEventAccumulator::from_leaves(&event_hashes).root_hash(),
+                None,
+                0,
+                ExecutionStatus::Success,
+            );
+            ((transaction, events), transaction_info)
+        })
+        .unzip();
+
+    // Create the ledger info (at version 1)
+    let leaves: Vec<_> = transaction_infos.iter().map(CryptoHash::hash).collect();
+    let root_hash = InMemoryTransactionAccumulator::from_leaves(&leaves).root_hash();
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(0, 0, HashValue::zero(), root_hash, 1, 0, None),
+        HashValue::zero(),
+    );
+
+    // Create the transactions with proofs (each leaf is the sibling of the other)
+    let mut transactions_with_proof = transactions
+        .into_iter()
+        .zip(transaction_infos)
+        .enumerate()
+        .map(|(version, ((transaction, events), transaction_info))| {
+            let sibling = leaves[1 - version];
+            TransactionWithProof::new(
+                version as Version,
+                transaction,
+                Some(events),
+                TransactionInfoWithProof::new(
+                    TransactionAccumulatorProof::new(vec![sibling]),
+                    transaction_info,
+                ),
+            )
+        });
+    let version_with_proof = VersionByTimestampWithProof {
+        version: 0,
+        block_start: transactions_with_proof.next().unwrap(),
+        next_block_start: transactions_with_proof.next().unwrap(),
+    };
+
+    (version_with_proof, ledger_info)
+}
+
 /// Creates a new optimistic request
 fn create_optimistic_fetch_request(
     known_version: u64,
@@ -650,6 +1421,60 @@ fn create_transactions_request(
     StorageServiceRequest::new(data_request, use_compression)
 }
 
+/// Creates a request for a transaction by hash
+fn create_transaction_by_hash_request(
+    proof_version: Version,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request =
+        DataRequest::GetTransactionByHashWithProof(TransactionByHashWithProofRequest {
+            transaction_hash: HashValue::random(),
+            proof_version,
+            include_events: true,
+        });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
+/// Creates a request for the last version before a timestamp
+fn create_version_by_timestamp_request(
+    ledger_version: Version,
+    timestamp_usecs: u64,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request = DataRequest::GetVersionByTimestamp(VersionByTimestampRequest {
+        timestamp_usecs,
+        ledger_version,
+    });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
+/// Creates a request for the metadata of the blocks in a version range
+fn create_block_metadata_request(
+    start_version: Version,
+    end_version: Version,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request = DataRequest::GetBlockMetadata(BlockMetadataRequest {
+        start_version,
+        end_version,
+    });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
+/// Creates a request for transactions without a proof
+fn create_transactions_without_proof_request(
+    start: Version,
+    end: Version,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request = DataRequest::GetTransactionsWithoutProof(TransactionsWithoutProofRequest {
+        start_version: start,
+        end_version: end,
+        include_events: true,
+    });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
 /// Creates a request for transactions or outputs
 fn create_transactions_or_outputs_request(
     proof: Version,
@@ -691,6 +1516,20 @@ fn create_state_values_request_at_version(
     create_state_values_request(version, 0, 1000, use_compression)
 }
 
+/// Creates a request for state values (by key range) at a given version
+fn create_state_values_by_key_range_request_at_version(
+    version: Version,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request =
+        DataRequest::GetStateValuesByKeyRangeWithProof(StateValuesByKeyRangeWithProofRequest {
+            version,
+            start_key_hash: HashValue::random(),
+            max_items: 1000,
+        });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
 /// Generates a random u64
 fn get_random_u64() -> u64 {
     thread_rng().gen()
@@ -758,17 +1597,22 @@ fn verify_can_service_state_chunk_requests(
     expect_service: bool,
 ) {
     for version in versions {
-        // Create the state chunk request
-        let request = create_state_values_request_at_version(version, use_compression);
+        // Create the state chunk requests (by index and by key range)
+        let requests = vec![
+            create_state_values_request_at_version(version, use_compression),
+            create_state_values_by_key_range_request_at_version(version, use_compression),
+        ];
 
-        // Verify the serviceability of the request
-        verify_serviceability(
-            data_client_config,
-            data_summary,
-            None,
-            request,
-            expect_service,
-        );
+        // Verify the serviceability of the requests
+        for request in requests {
+            verify_serviceability(
+                data_client_config,
+                data_summary,
+                None,
+                request,
+                expect_service,
+            );
+        }
     }
 }
 
@@ -824,6 +1668,57 @@ fn verify_can_service_transaction_requests(
     }
 }
 
+/// Verifies the serviceability of the transaction by hash requests (at
+/// the given proof versions) against the specified data summary. If
+/// `expect_service` is true, then the request should be serviceable.
+fn verify_can_service_transaction_by_hash_requests(
+    data_client_config: &AptosDataClientConfig,
+    data_summary: &DataSummary,
+    use_compression: bool,
+    proof_versions: Vec<u64>,
+    expect_service: bool,
+) {
+    for proof_version in proof_versions {
+        // Create the transaction by hash request
+        let request = create_transaction_by_hash_request(proof_version, use_compression);
+
+        // Verify the serviceability of the request
+        verify_serviceability(
+            data_client_config,
+            data_summary,
+            None,
+            request,
+            expect_service,
+        );
+    }
+}
+
+/// Verifies the serviceability of the transactions without proof request
+/// ranges against the specified data summary. If `expect_service` is
+/// true, then the request should be serviceable.
+fn verify_can_service_transactions_without_proof_requests(
+    data_client_config: &AptosDataClientConfig,
+    data_summary: &DataSummary,
+    use_compression: bool,
+    transaction_ranges: Vec<(u64, u64)>,
+    expect_service: bool,
+) {
+    for (start_version, end_version) in transaction_ranges {
+        // Create the transactions without proof request
+        let request =
+            create_transactions_without_proof_request(start_version, end_version, use_compression);
+
+        // Verify the serviceability of the request
+        verify_serviceability(
+            data_client_config,
+            data_summary,
+            None,
+            request,
+            expect_service,
+        );
+    }
+}
+
 /// Verifies the serviceability of the transaction or output request
 /// ranges against the specified data summary. If `expect_service` is
 /// true, then the request should be serviceable.
@@ -167,6 +167,7 @@ pub(crate) fn save_transactions(
             state_kv_metadata_batch,
             sharded_kv_schema_batch,
         )?;
+        state_store.invalidate_state_value_cache_by_write_sets(last_version, &write_sets);
 
         ledger_db.write_schemas(ledger_db_batch)?;
     }
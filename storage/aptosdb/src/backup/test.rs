@@ -2,14 +2,21 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::db::{
-    test_helper::{arb_blocks_to_commit, update_in_memory_state},
-    AptosDB,
+use crate::{
+    db::{
+        test_helper::{
+            arb_blocks_to_commit, arb_blocks_to_commit_with_block_nums, update_in_memory_state,
+        },
+        AptosDB,
+    },
+    get_restore_handler::GetRestoreHandler,
 };
 use anyhow::Result;
+use aptos_storage_interface::DbReader;
 use aptos_temppath::TempPath;
-use aptos_types::transaction::Version;
+use aptos_types::{transaction::Version, write_set::TransactionWrite};
 use proptest::prelude::*;
+use std::{collections::HashMap, sync::Arc};
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -72,4 +79,60 @@ proptest! {
             .unwrap();
         prop_assert_eq!(&non_existent, &[]);
     }
+
+    #[test]
+    fn test_replay_kv_invalidates_state_value_cache(
+        (input, _) in arb_blocks_to_commit_with_block_nums(2, 2),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = Arc::new(AptosDB::new_for_test(&tmp_dir));
+        let (first_block, _) = &input[0];
+        let (second_block, _) = &input[1];
+        let mut in_memory_state = db.state_store.buffered_state().lock().current_state().clone();
+        update_in_memory_state(&mut in_memory_state, first_block.as_slice());
+        db.save_transactions_for_test(
+            first_block,
+            0,
+            None,
+            Some(&input[0].1),
+            true, // sync commit
+            in_memory_state,
+        )
+        .unwrap();
+
+        // Cache the values of the keys the second block writes, as of the first block
+        db.state_store.enable_state_value_cache(1 << 20).unwrap();
+        let first_version = first_block.len() as Version;
+        for txn_to_commit in second_block {
+            for (key, _) in txn_to_commit.write_set().iter() {
+                db.state_store
+                    .get_state_value_by_version(key, first_version - 1)
+                    .unwrap();
+            }
+        }
+
+        // Replaying the second block on restore must evict the keys it writes
+        db.get_restore_handler()
+            .save_transactions_and_replay_kv(
+                first_version,
+                &second_block.iter().map(|t| t.transaction().clone()).collect::<Vec<_>>(),
+                &second_block.iter().map(|t| t.transaction_info().clone()).collect::<Vec<_>>(),
+                &second_block.iter().map(|t| t.events().to_vec()).collect::<Vec<_>>(),
+                second_block.iter().map(|t| t.write_set().clone()).collect(),
+            )
+            .unwrap();
+        let last_version = first_version + second_block.len() as Version - 1;
+        let mut expected = HashMap::new();
+        for txn_to_commit in second_block {
+            for (key, write_op) in txn_to_commit.write_set().iter() {
+                expected.insert(key.clone(), write_op.as_state_value());
+            }
+        }
+        for (key, value) in expected {
+            prop_assert_eq!(
+                db.state_store.get_state_value_by_version(&key, last_version).unwrap(),
+                value
+            );
+        }
+    }
 }
@@ -4,7 +4,9 @@
 use crate::{
     db::{
         get_first_seq_num_and_limit, test_helper,
-        test_helper::{arb_blocks_to_commit, put_as_state_root, put_transaction_info},
+        test_helper::{
+            arb_blocks_to_commit, put_and_index_events, put_as_state_root, put_transaction_info,
+        },
         AptosDB,
     },
     pruner::{LedgerPrunerManager, PrunerManager, StateMerklePrunerManager},
@@ -16,9 +18,10 @@ use aptos_config::config::{
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
-use aptos_storage_interface::{DbReader, ExecutedTrees, Order};
+use aptos_storage_interface::{DbReader, ExecutedTrees, Order, MAX_REQUEST_LIMIT};
 use aptos_temppath::TempPath;
 use aptos_types::{
+    contract_event::{ContractEvent, EventWithVersion},
     ledger_info::LedgerInfoWithSignatures,
     proof::SparseMerkleLeafNode,
     state_store::{
@@ -26,6 +29,7 @@ use aptos_types::{
     },
     transaction::{ExecutionStatus, TransactionInfo, TransactionToCommit, Version},
 };
+use move_core_types::language_storage::TypeTag;
 use proptest::prelude::*;
 use std::{collections::HashSet, sync::Arc};
 use test_helper::{test_save_blocks_impl, test_sync_transactions_impl};
@@ -84,6 +88,83 @@ fn test_too_many_requested() {
 
     assert!(db.get_transactions(0, 1001 /* limit */, 0, true).is_err());
     assert!(db.get_transaction_outputs(0, 1001 /* limit */, 0).is_err());
+    assert!(db.get_write_sets_with_proof(0, 1001 /* limit */, 0).is_err());
+}
+
+#[test]
+fn test_get_events_by_type() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_without_pruner(
+        &tmp_dir,
+        false, /* readonly */
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        true,  /* enable_indexer */
+        false, /* enable_indexer_async_v2 */
+    );
+
+    // Emit u8 and u64 events (which are adjacent in the index) over 5 versions
+    let event = |type_tag: TypeTag, version: u8, index: u8| {
+        ContractEvent::new_v2(type_tag, vec![version, index])
+    };
+    let events = vec![
+        vec![event(TypeTag::U8, 0, 0), event(TypeTag::U64, 0, 1)],
+        vec![event(TypeTag::U64, 1, 0)],
+        vec![event(TypeTag::U8, 2, 0), event(TypeTag::U8, 2, 1)],
+        vec![],
+        vec![event(TypeTag::U64, 4, 0), event(TypeTag::U8, 4, 1)],
+    ];
+    put_and_index_events(&db, &events);
+    let expected_events = |type_tag: &TypeTag, versions: std::ops::Range<usize>| {
+        versions
+            .flat_map(|version| {
+                events[version]
+                    .iter()
+                    .filter(|event| event.type_tag() == type_tag)
+                    .map(move |event| EventWithVersion::new(version as Version, event.clone()))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Fetch all events of each type
+    for type_tag in [TypeTag::U8, TypeTag::U64] {
+        assert_eq!(
+            db.get_events_by_type(&type_tag, 0, 100, 4).unwrap(),
+            expected_events(&type_tag, 0..5)
+        );
+    }
+    assert!(db
+        .get_events_by_type(&TypeTag::Bool, 0, 100, 4)
+        .unwrap()
+        .is_empty());
+
+    // Fetch the events from a start version, up to a limit or the ledger version
+    assert_eq!(
+        db.get_events_by_type(&TypeTag::U8, 1, 100, 4).unwrap(),
+        expected_events(&TypeTag::U8, 1..5)
+    );
+    assert_eq!(
+        db.get_events_by_type(&TypeTag::U8, 1, 2, 4).unwrap(),
+        expected_events(&TypeTag::U8, 2..3)
+    );
+    assert_eq!(
+        db.get_events_by_type(&TypeTag::U64, 0, 100, 3).unwrap(),
+        expected_events(&TypeTag::U64, 0..4)
+    );
+    assert!(db
+        .get_events_by_type(&TypeTag::U8, 5, 100, 4)
+        .unwrap()
+        .is_empty());
+
+    // Too many events can't be requested at once
+    assert!(db
+        .get_events_by_type(&TypeTag::U8, 0, MAX_REQUEST_LIMIT + 1, 4)
+        .is_err());
+
+    // Events can't be fetched by type without the indexer
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    assert!(db.get_events_by_type(&TypeTag::U8, 0, 100, 4).is_err());
 }
 
 #[test]
@@ -102,13 +183,17 @@ fn test_pruner_config() {
         assert_eq!(state_merkle_pruner.is_pruner_enabled(), enable);
         assert_eq!(state_merkle_pruner.get_prune_window(), 20);
 
-        let ledger_pruner =
-            LedgerPrunerManager::new(Arc::clone(&aptos_db.ledger_db), LedgerPrunerConfig {
+        let ledger_pruner = LedgerPrunerManager::new(
+            Arc::clone(&aptos_db.ledger_db),
+            LedgerPrunerConfig {
                 enable,
                 prune_window: 100,
                 batch_size: 1,
                 user_pruning_window_offset: 0,
-            });
+            },
+            None,
+            None,
+        );
         assert_eq!(ledger_pruner.is_pruner_enabled(), enable);
         assert_eq!(ledger_pruner.get_prune_window(), 100);
     }
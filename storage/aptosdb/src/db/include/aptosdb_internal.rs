@@ -11,6 +11,8 @@ impl AptosDB {
         hack_for_tests: bool,
         empty_buffered_state_for_restore: bool,
         skip_index_and_usage: bool,
+        indexer: Option<Arc<Indexer>>,
+        indexer_async_v2: Option<Arc<IndexerAsyncV2>>,
     ) -> Self {
         let ledger_db = Arc::new(ledger_db);
         let state_merkle_db = Arc::new(state_merkle_db);
@@ -38,8 +40,12 @@ impl AptosDB {
             skip_index_and_usage,
         ));
 
-        let ledger_pruner =
-            LedgerPrunerManager::new(Arc::clone(&ledger_db), pruner_config.ledger_pruner_config);
+        let ledger_pruner = LedgerPrunerManager::new(
+            Arc::clone(&ledger_db),
+            pruner_config.ledger_pruner_config,
+            indexer.clone(),
+            indexer_async_v2.clone(),
+        );
 
         AptosDB {
             ledger_db: Arc::clone(&ledger_db),
@@ -55,9 +61,10 @@ impl AptosDB {
                 state_kv_db,
             ),
             ledger_commit_lock: std::sync::Mutex::new(()),
-            indexer: None,
+            indexer,
             skip_index_and_usage,
-            indexer_async_v2: None,
+            indexer_async_v2,
+            consistency_checker: None,
         }
     }
 
@@ -84,7 +91,26 @@ impl AptosDB {
             max_num_nodes_per_lru_cache_shard,
         )?;
 
-        let mut myself = Self::new_with_dbs(
+        // The indexers are opened ahead of the ledger pruner, as it also prunes their event indices
+        let indexer = if !readonly && enable_indexer {
+            Some(Arc::new(Indexer::open(
+                db_paths.default_root_path(),
+                rocksdb_configs.index_db_config,
+            )?))
+        } else {
+            None
+        };
+        let indexer_async_v2 = if enable_indexer_async_v2 {
+            Some(Arc::new(IndexerAsyncV2::open(
+                db_paths.default_root_path(),
+                rocksdb_configs.index_db_config,
+                DashMap::new(),
+            )?))
+        } else {
+            None
+        };
+
+        let myself = Self::new_with_dbs(
             ledger_db,
             state_merkle_db,
             state_kv_db,
@@ -93,31 +119,18 @@ impl AptosDB {
             readonly,
             empty_buffered_state_for_restore,
             rocksdb_configs.enable_storage_sharding,
+            indexer,
+            indexer_async_v2,
         );
 
-        if !readonly && enable_indexer {
-            myself.open_indexer(
-                db_paths.default_root_path(),
-                rocksdb_configs.index_db_config,
-            )?;
-        }
-
-        if enable_indexer_async_v2 {
-            myself.open_indexer_async_v2(
-                db_paths.default_root_path(),
-                rocksdb_configs.index_db_config,
-            )?;
+        if let Some(indexer) = &myself.indexer {
+            myself.catch_up_indexer(indexer)?;
         }
 
         Ok(myself)
     }
 
-    fn open_indexer(
-        &mut self,
-        db_root_path: impl AsRef<Path>,
-        rocksdb_config: RocksdbConfig,
-    ) -> Result<()> {
-        let indexer = Indexer::open(&db_root_path, rocksdb_config)?;
+    fn catch_up_indexer(&self, indexer: &Indexer) -> Result<()> {
         let ledger_next_version = self.get_latest_version().map_or(0, |v| v + 1);
         info!(
             indexer_next_version = indexer.next_version(),
@@ -142,24 +155,27 @@ impl AptosDB {
                     .transaction_store
                     .get_write_sets(next_version, end_version)?;
                 let write_sets_ref: Vec<_> = write_sets.iter().collect();
-                indexer.index_with_annotator(&annotator, next_version, &write_sets_ref)?;
+                let events = self
+                    .ledger_db
+                    .event_db()
+                    .get_events_by_version_iter(
+                        next_version,
+                        (end_version - next_version) as usize,
+                    )?
+                    .collect::<Result<Vec<_>>>()?;
+                let events_ref: Vec<_> = events.iter().map(Vec::as_slice).collect();
+                indexer.index_with_annotator(
+                    &annotator,
+                    next_version,
+                    &write_sets_ref,
+                    &events_ref,
+                )?;
 
                 next_version = end_version;
             }
         }
         info!("AptosDB Indexer caught up.");
 
-        self.indexer = Some(indexer);
-        Ok(())
-    }
-
-    fn open_indexer_async_v2(
-        &mut self,
-        db_root_path: impl AsRef<Path>,
-        rocksdb_config: RocksdbConfig,
-    ) -> Result<()> {
-        let indexer_async_v2 = IndexerAsyncV2::open(db_root_path, rocksdb_config, DashMap::new())?;
-        self.indexer_async_v2 = Some(indexer_async_v2);
         Ok(())
     }
 
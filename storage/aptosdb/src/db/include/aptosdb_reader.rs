@@ -253,6 +253,51 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Returns the write sets of up to `limit` transactions starting at `start_version`,
+    /// along with a proof of their transaction infos relative to `ledger_version`.
+    fn get_write_sets_with_proof(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<WriteSetListWithProof> {
+        gauged_api("get_write_sets_with_proof", || {
+            error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+            if start_version > ledger_version || limit == 0 {
+                return Ok(WriteSetListWithProof::new_empty());
+            }
+
+            self.error_if_ledger_pruned("Write set", start_version)?;
+
+            let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+
+            let (txn_infos, write_sets) = (start_version..start_version + limit)
+                .map(|version| {
+                    let txn_info = self.ledger_store.get_transaction_info(version)?;
+                    let write_set = self.transaction_store.get_write_set(version)?;
+                    Ok((txn_info, write_set))
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .unzip();
+            let proof = TransactionInfoListWithProof::new(
+                self.ledger_store.get_transaction_range_proof(
+                    Some(start_version),
+                    limit,
+                    ledger_version,
+                )?,
+                txn_infos,
+            );
+
+            Ok(WriteSetListWithProof::new(
+                write_sets,
+                Some(start_version),
+                proof,
+            ))
+        })
+    }
+
     fn get_events(
         &self,
         event_key: &EventKey,
@@ -406,6 +451,19 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_values_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<Option<StateValue>>> {
+        gauged_api("get_state_values_by_version", || {
+            self.error_if_state_kv_pruned("StateValue", version)?;
+
+            self.state_store
+                .get_state_values_by_version(state_keys, version)
+        })
+    }
+
     fn get_state_value_with_version_by_version(
         &self,
         state_key: &StateKey,
@@ -696,6 +754,21 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_commit_progress(&self) -> Result<CommitProgressReport> {
+        gauged_api("get_commit_progress", || {
+            self.state_store.get_commit_progress()
+        })
+    }
+
+    fn get_latest_consistency_check_report(&self) -> Result<Option<ConsistencyCheckReport>> {
+        gauged_api("get_latest_consistency_check_report", || {
+            Ok(self
+                .consistency_checker
+                .as_ref()
+                .and_then(|checker| checker.latest_report()))
+        })
+    }
+
     fn get_state_snapshot_before(
         &self,
         next_version: Version,
@@ -757,6 +830,22 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_value_chunk_by_key_range_with_proof(
+        &self,
+        version: Version,
+        start_key_hash: HashValue,
+        chunk_size: usize,
+    ) -> Result<StateValueChunkWithProof> {
+        gauged_api("get_state_value_chunk_by_key_range_with_proof", || {
+            self.error_if_state_merkle_pruned("State merkle", version)?;
+            self.state_store.get_value_chunk_by_key_range_with_proof(
+                version,
+                start_key_hash,
+                chunk_size,
+            )
+        })
+    }
+
     fn is_state_merkle_pruner_enabled(&self) -> Result<bool> {
         gauged_api("is_state_merkle_pruner_enabled", || {
             Ok(self
@@ -796,11 +885,52 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        gauged_api("get_events_by_type", || {
+            error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+            self.error_if_ledger_pruned("Event", start_version)?;
+            if start_version > ledger_version {
+                return Ok(vec![]);
+            }
+
+            self.lookup_events_by_type(type_tag, start_version, ledger_version + 1, limit)?
+                .into_iter()
+                .map(|(version, index)| {
+                    let event = self
+                        .event_store
+                        .get_event_by_version_and_index(version, index)?;
+                    Ok(EventWithVersion::new(version, event))
+                })
+                .collect()
+        })
+    }
+
     /// Returns whether the indexer DB has been enabled or not
     fn indexer_enabled(&self) -> bool {
         self.indexer.is_some()
     }
 
+    fn get_indexer_next_version(&self) -> Result<Option<Version>> {
+        gauged_api("get_indexer_next_version", || {
+            Ok(self.indexer.as_ref().map(|indexer| indexer.next_version()))
+        })
+    }
+
+    fn get_event_by_type_index_start_version(&self) -> Result<Option<Version>> {
+        gauged_api("get_event_by_type_index_start_version", || {
+            Ok(self
+                .indexer
+                .as_ref()
+                .map(|indexer| indexer.event_by_type_start_version()))
+        })
+    }
+
     /// Returns whether the indexer async v2 DB has been enabled or not
     fn indexer_async_v2_enabled(&self) -> bool {
         self.indexer_async_v2.is_some()
@@ -1027,6 +1157,33 @@ impl AptosDB {
         }
     }
 
+    fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        end_version: Version,
+        limit: u64,
+    ) -> Result<Vec<(Version, u64)>> {
+        if self.indexer_async_v2_enabled() {
+            return match &self.indexer_async_v2 {
+                Some(indexer_async_v2) => indexer_async_v2.lookup_events_by_type(
+                    type_tag,
+                    start_version,
+                    end_version,
+                    limit,
+                ),
+                None => bail!("Indexer Async V2 not enabled."),
+            };
+        }
+
+        match &self.indexer {
+            Some(indexer) => {
+                indexer.lookup_events_by_type(type_tag, start_version, end_version, limit)
+            },
+            None => bail!("Indexer not enabled."),
+        }
+    }
+
     /// TODO(jill): deprecate Indexer once Indexer Async V2 is ready
     fn get_table_info_from_indexer(&self, handle: TableHandle) -> Result<Option<TableInfo>> {
         match &self.indexer {
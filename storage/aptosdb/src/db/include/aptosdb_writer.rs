@@ -149,7 +149,7 @@ impl DbWriter for AptosDB {
                 &transactions,
                 &transaction_infos,
                 &events,
-                wsets,
+                wsets.clone(),
                 Option::Some((
                     &mut ledger_db_batch,
                     &mut sharded_kv_batch,
@@ -183,6 +183,8 @@ impl DbWriter for AptosDB {
             //
             // state kv and SMT should use shared way of committing.
             self.ledger_db.write_schemas(ledger_db_batch)?;
+            self.state_store
+                .invalidate_state_value_cache_by_write_sets(version, &wsets);
 
             self.ledger_pruner.save_min_readable_version(version)?;
             self.state_store
@@ -208,6 +210,7 @@ impl DbWriter for AptosDB {
         db_reader: Arc<dyn DbReader>,
         first_version: Version,
         write_sets: &[&WriteSet],
+        events: &[&[ContractEvent]],
         end_early_if_pending_on_empty: bool,
     ) -> Result<()> {
         gauged_api("index_table_info", || {
@@ -218,6 +221,7 @@ impl DbWriter for AptosDB {
                         db_reader,
                         first_version,
                         write_sets,
+                        events,
                         end_early_if_pending_on_empty,
                     )
                 })
@@ -465,6 +469,13 @@ impl AptosDB {
                     .unwrap();
             });
         });
+        self.state_store.invalidate_state_value_cache(
+            last_version,
+            &txns_to_commit
+                .iter()
+                .map(|txn_to_commit| txn_to_commit.state_updates())
+                .collect::<Vec<_>>(),
+        );
 
         Ok(())
     }
@@ -656,7 +667,13 @@ impl AptosDB {
                 .with_label_values(&["indexer_index"])
                 .start_timer();
             let write_sets: Vec<_> = txns_to_commit.iter().map(|txn| txn.write_set()).collect();
-            indexer.index(self.state_store.clone(), first_version, &write_sets)?;
+            let events: Vec<_> = txns_to_commit.iter().map(|txn| txn.events()).collect();
+            indexer.index(
+                self.state_store.clone(),
+                first_version,
+                &write_sets,
+                &events,
+            )?;
         }
 
         // Once everything is successfully persisted, update the latest in-memory ledger info.
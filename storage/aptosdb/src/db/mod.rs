@@ -21,12 +21,15 @@ use crate::{
     },
     state_kv_db::StateKvDb,
     state_merkle_db::StateMerkleDb,
-    state_store::StateStore,
+    state_store::{
+        consistency_checker::ConsistencyChecker, snapshot_file::StateSnapshotFileHeader, StateStore,
+    },
     transaction_store::TransactionStore,
     utils::new_sharded_kv_schema_batch,
 };
 use aptos_config::config::{
-    PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG,
+    ConsistencyCheckerConfig, PrunerConfig, RocksdbConfigs, StateValueCacheConfig, StorageDirPaths,
+    NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::HashValue;
 use aptos_db_indexer::{db_v2::IndexerAsyncV2, Indexer};
@@ -38,6 +41,8 @@ use aptos_scratchpad::SparseMerkleTree;
 use aptos_storage_interface::{
     block_info::{BlockInfo, BlockInfoV0},
     cached_state_view::ShardedStateCache,
+    commit_progress::CommitProgressReport,
+    consistency_check::ConsistencyCheckReport,
     db_anyhow as anyhow, db_ensure as ensure, db_other_bail as bail,
     state_delta::StateDelta,
     state_view::DbStateView,
@@ -70,16 +75,19 @@ use aptos_types::{
     transaction::{
         AccountTransactionsWithProof, Transaction, TransactionInfo, TransactionListWithProof,
         TransactionOutput, TransactionOutputListWithProof, TransactionToCommit,
-        TransactionWithProof, Version,
+        TransactionWithProof, Version, WriteSetListWithProof,
     },
     write_set::WriteSet,
 };
 use aptos_vm::data_cache::AsMoveResolver;
 use dashmap::DashMap;
+use move_core_types::language_storage::TypeTag;
 use move_resource_viewer::MoveValueAnnotator;
 use rayon::prelude::*;
 use std::{
     fmt::{Debug, Formatter},
+    fs::File,
+    io::{BufReader, BufWriter},
     iter::Iterator,
     path::Path,
     sync::Arc,
@@ -103,9 +111,10 @@ pub struct AptosDB {
     ledger_pruner: LedgerPrunerManager,
     _rocksdb_property_reporter: RocksdbPropertyReporter,
     ledger_commit_lock: std::sync::Mutex<()>,
-    indexer: Option<Indexer>,
+    pub(crate) indexer: Option<Arc<Indexer>>,
     skip_index_and_usage: bool,
-    indexer_async_v2: Option<IndexerAsyncV2>,
+    pub(crate) indexer_async_v2: Option<Arc<IndexerAsyncV2>>,
+    consistency_checker: Option<ConsistencyChecker>,
 }
 
 // DbReader implementations and private functions used by them.
@@ -188,6 +197,25 @@ impl AptosDB {
         Ok((ledger_db, state_merkle_db, state_kv_db))
     }
 
+    /// Starts the background state consistency checker, if enabled in the config.
+    pub fn start_consistency_checker(&mut self, config: ConsistencyCheckerConfig) {
+        if config.enable {
+            self.consistency_checker = Some(ConsistencyChecker::new(
+                Arc::clone(&self.state_store.state_db),
+                config,
+            ));
+        }
+    }
+
+    /// Enables the in-memory cache of hot state values, if enabled in the config.
+    pub fn enable_state_value_cache(&self, config: StateValueCacheConfig) -> Result<()> {
+        if config.enable {
+            self.state_store
+                .enable_state_value_cache(config.max_bytes_per_shard)?;
+        }
+        Ok(())
+    }
+
     /// Gets an instance of `BackupHandler` for data backup purpose.
     pub fn get_backup_handler(&self) -> BackupHandler {
         BackupHandler::new(
@@ -223,6 +251,27 @@ impl AptosDB {
         Ok(())
     }
 
+    /// Exports all the state values at the given state snapshot version into a portable,
+    /// chunked and checksummed file at `path`, which can be imported with
+    /// `import_state_snapshot`.
+    pub fn export_state_snapshot(
+        &self,
+        version: Version,
+        chunk_size: usize,
+        path: impl AsRef<Path>,
+    ) -> Result<StateSnapshotFileHeader> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.state_store
+            .export_state_snapshot(version, chunk_size, &mut writer)
+    }
+
+    /// Imports a state snapshot file written by `export_state_snapshot`. The DB must not
+    /// have any state snapshot yet.
+    pub fn import_state_snapshot(&self, path: impl AsRef<Path>) -> Result<StateSnapshotFileHeader> {
+        let mut reader = BufReader::new(File::open(path)?);
+        self.state_store.import_state_snapshot(&mut reader)
+    }
+
     pub fn commit_genesis_ledger_info(&self, genesis_li: &LedgerInfoWithSignatures) -> Result<()> {
         let ledger_batch = SchemaBatch::new();
         let current_epoch = self
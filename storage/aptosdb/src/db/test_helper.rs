@@ -17,7 +17,9 @@ use aptos_crypto::HashValue;
 use aptos_executor_types::ProofReader;
 use aptos_jellyfish_merkle::node_type::{Node, NodeKey};
 use aptos_schemadb::SchemaBatch;
-use aptos_storage_interface::{state_delta::StateDelta, DbReader, DbWriter, Order, Result};
+use aptos_storage_interface::{
+    mock::MockDbReaderWriter, state_delta::StateDelta, DbReader, DbWriter, Order, Result,
+};
 use aptos_temppath::TempPath;
 #[cfg(test)]
 use aptos_types::state_store::state_storage_usage::StateStorageUsage;
@@ -33,12 +35,13 @@ use aptos_types::{
         ShardedStateUpdates,
     },
     transaction::{Transaction, TransactionInfo, TransactionToCommit, Version},
+    write_set::WriteSet,
 };
 #[cfg(test)]
 use arr_macro::arr;
 use proptest::{collection::vec, prelude::*, sample::Index};
 use rayon::prelude::*;
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 prop_compose! {
     pub fn arb_state_kv_sets(
@@ -450,6 +453,20 @@ pub fn test_save_blocks_impl(
             .flat_map(|(txns_to_commit, _)| txns_to_commit.iter())
             .collect(),
     );
+
+    // every db has caught up with the overall commit progress
+    let latest_version = cur_ver.checked_sub(1);
+    let commit_progress = db.get_commit_progress().unwrap();
+    assert_eq!(commit_progress.overall_commit_progress, latest_version);
+    assert_eq!(commit_progress.ledger_commit_progress, latest_version);
+    assert_eq!(commit_progress.state_kv_commit_progress, latest_version);
+    assert!(commit_progress
+        .state_kv_shard_commit_progress
+        .iter()
+        .all(|progress| *progress == latest_version));
+    assert!(commit_progress.state_merkle_commit_progress <= latest_version);
+    assert!(commit_progress.startup_truncations.is_empty());
+    assert!(!commit_progress.is_diverged());
 }
 
 fn verify_snapshots(
@@ -889,6 +906,16 @@ pub fn verify_committed_transactions(
                 .verify(ledger_info, Some(cur_ver))
                 .unwrap();
             assert_eq!(txn_output_list_with_proof.transactions_and_outputs.len(), 1);
+
+            let write_set_list_with_proof = db
+                .get_write_sets_with_proof(cur_ver, 1, ledger_version)
+                .unwrap();
+            write_set_list_with_proof
+                .verify(ledger_info, Some(cur_ver))
+                .unwrap();
+            assert_eq!(write_set_list_with_proof.write_sets, vec![
+                txn_to_commit.write_set().clone()
+            ]);
         }
         cur_ver += 1;
     }
@@ -913,6 +940,48 @@ pub fn put_transaction_info(db: &AptosDB, version: Version, txn_info: &Transacti
     db.ledger_db.transaction_db().write_schemas(batch).unwrap();
 }
 
+/// Writes the events of the transactions starting at version 0 to the event store, and indexes
+/// them by type in the enabled indexers.
+pub fn put_and_index_events(db: &AptosDB, events: &[Vec<ContractEvent>]) {
+    let batch = SchemaBatch::new();
+    for (version, events_for_version) in events.iter().enumerate() {
+        db.event_store
+            .put_events(
+                version as u64,
+                events_for_version,
+                /*skip_index=*/ false,
+                &batch,
+            )
+            .unwrap();
+    }
+    db.ledger_db.event_db().write_schemas(batch).unwrap();
+
+    let write_sets = vec![WriteSet::default(); events.len()];
+    let write_sets_ref: Vec<_> = write_sets.iter().collect();
+    let events_ref: Vec<_> = events.iter().map(Vec::as_slice).collect();
+    if let Some(indexer) = &db.indexer {
+        indexer
+            .index(
+                Arc::new(MockDbReaderWriter),
+                0,
+                &write_sets_ref,
+                &events_ref,
+            )
+            .unwrap();
+    }
+    if let Some(indexer_async_v2) = &db.indexer_async_v2 {
+        indexer_async_v2
+            .index_table_info(
+                Arc::new(MockDbReaderWriter),
+                0,
+                &write_sets_ref,
+                &events_ref,
+                false, /* end_early_if_pending_on_empty */
+            )
+            .unwrap();
+    }
+}
+
 pub fn put_as_state_root(db: &AptosDB, version: Version, key: StateKey, value: StateValue) {
     let leaf_node = Node::new_leaf(key.hash(), value.hash(), (key.clone(), version));
     db.state_merkle_db()
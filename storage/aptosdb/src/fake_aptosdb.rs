@@ -14,7 +14,8 @@ use aptos_crypto::{
 };
 use aptos_infallible::Mutex;
 use aptos_storage_interface::{
-    cached_state_view::ShardedStateCache, state_delta::StateDelta, AptosDbError, DbReader,
+    cached_state_view::ShardedStateCache, commit_progress::CommitProgressReport,
+    consistency_check::ConsistencyCheckReport, state_delta::StateDelta, AptosDbError, DbReader,
     DbWriter, ExecutedTrees, MAX_REQUEST_LIMIT,
 };
 use aptos_types::{
@@ -649,6 +650,14 @@ impl DbReader for FakeAptosDB {
         })
     }
 
+    fn get_commit_progress(&self) -> Result<CommitProgressReport> {
+        self.inner.get_commit_progress()
+    }
+
+    fn get_latest_consistency_check_report(&self) -> Result<Option<ConsistencyCheckReport>> {
+        self.inner.get_latest_consistency_check_report()
+    }
+
     fn get_state_snapshot_before(
         &self,
         next_version: Version,
@@ -820,6 +829,19 @@ impl DbReader for FakeAptosDB {
             .get_state_value_chunk_with_proof(version, start_idx, chunk_size)
     }
 
+    fn get_state_value_chunk_by_key_range_with_proof(
+        &self,
+        version: Version,
+        start_key_hash: HashValue,
+        chunk_size: usize,
+    ) -> Result<StateValueChunkWithProof> {
+        self.inner.get_state_value_chunk_by_key_range_with_proof(
+            version,
+            start_key_hash,
+            chunk_size,
+        )
+    }
+
     fn is_state_merkle_pruner_enabled(&self) -> Result<bool> {
         self.inner.is_state_merkle_pruner_enabled()
     }
@@ -840,10 +862,29 @@ impl DbReader for FakeAptosDB {
         self.inner.get_table_info(handle)
     }
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &move_core_types::language_storage::TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.inner
+            .get_events_by_type(type_tag, start_version, limit, ledger_version)
+    }
+
     fn indexer_enabled(&self) -> bool {
         self.inner.indexer_enabled()
     }
 
+    fn get_indexer_next_version(&self) -> Result<Option<Version>> {
+        self.inner.get_indexer_next_version()
+    }
+
+    fn get_event_by_type_index_start_version(&self) -> Result<Option<Version>> {
+        self.inner.get_event_by_type_index_start_version()
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         self.inner.get_state_storage_usage(version)
     }
@@ -41,7 +41,7 @@ impl FastSyncStorageWrapper {
     /// If the db is empty and configured to do fast sync, we return a FastSyncStorageWrapper
     /// Otherwise, we returns AptosDB directly and the FastSyncStorageWrapper is None
     pub fn initialize_dbs(config: &NodeConfig) -> Result<Either<AptosDB, Self>> {
-        let db_main = AptosDB::open(
+        let mut db_main = AptosDB::open(
             config.storage.get_dir_paths(),
             /*readonly=*/ false,
             config.storage.storage_pruner_config,
@@ -52,6 +52,8 @@ impl FastSyncStorageWrapper {
             config.indexer_table_info.enabled,
         )
         .map_err(|err| anyhow!("fast sync DB failed to open {}", err))?;
+        db_main.start_consistency_checker(config.storage.consistency_checker_config);
+        db_main.enable_state_value_cache(config.storage.state_value_cache_config)?;
 
         let mut db_dir = config.storage.dir();
         // when the db is empty and configured to do fast sync, we will create a second DB
@@ -11,6 +11,7 @@
 //! Key-Value storage system, and implements aptos data structures on top of it.
 
 pub use crate::db::AptosDB;
+pub use crate::state_store::snapshot_file::StateSnapshotFileHeader;
 
 #[cfg(feature = "consensus-only-perf-test")]
 pub mod fake_aptosdb;
@@ -39,5 +40,6 @@ mod pruner;
 mod state_kv_db;
 mod state_merkle_db;
 mod state_store;
+mod state_value_cache;
 mod transaction_store;
 mod versioned_node_cache;
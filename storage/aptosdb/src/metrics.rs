@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram_vec, register_int_counter, register_int_gauge,
-    register_int_gauge_vec, HistogramVec, IntCounter, IntGauge, IntGaugeVec,
+    exponential_buckets, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -172,6 +173,64 @@ pub(crate) static LATEST_CHECKPOINT_VERSION: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static BUFFERED_STATE_LAG_VERSIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_buffered_state_lag_versions",
+        "Number of versions the in-memory state is ahead of the latest persisted snapshot."
+    )
+    .unwrap()
+});
+
+pub(crate) static BUFFERED_STATE_LAG_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_buffered_state_lag_bytes",
+        "Estimated size in bytes of the checkpointed state updates not yet persisted in a snapshot."
+    )
+    .unwrap()
+});
+
+pub(crate) static STATE_CONSISTENCY_CHECK_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_state_consistency_check_version",
+        "Version of the state snapshot checked by the latest consistency check."
+    )
+    .unwrap()
+});
+
+pub(crate) static STATE_CONSISTENCY_CHECKED_KEYS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_state_consistency_checked_keys",
+        "Number of state keys checked by the consistency checker."
+    )
+    .unwrap()
+});
+
+pub(crate) static STATE_VALUE_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_state_value_cache",
+        "Number of state value cache lookups, by result (hit or miss).",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub(crate) static STATE_INCONSISTENCIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_state_inconsistencies",
+        "Number of inconsistencies found by the consistency checker, by kind.",
+        &["kind"]
+    )
+    .unwrap()
+});
+
+pub(crate) static STATE_CONSISTENCY_CHECK_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_state_consistency_check_errors",
+        "Number of consistency checks that failed to complete (e.g., due to pruning)."
+    )
+    .unwrap()
+});
+
 // Backup progress gauges:
 
 pub(crate) static BACKUP_EPOCH_ENDING_EPOCH: Lazy<IntGauge> = Lazy::new(|| {
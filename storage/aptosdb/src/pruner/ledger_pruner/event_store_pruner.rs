@@ -6,6 +6,7 @@ use crate::{
     pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
     schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
 };
+use aptos_db_indexer::{db_v2::IndexerAsyncV2, Indexer};
 use aptos_logger::info;
 use aptos_schemadb::SchemaBatch;
 use aptos_storage_interface::Result;
@@ -15,6 +16,9 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct EventStorePruner {
     ledger_db: Arc<LedgerDb>,
+    /// The indexers holding events by type, pruned along with the events.
+    indexer: Option<Arc<Indexer>>,
+    indexer_async_v2: Option<Arc<IndexerAsyncV2>>,
 }
 
 impl DBSubPruner for EventStorePruner {
@@ -23,6 +27,25 @@ impl DBSubPruner for EventStorePruner {
     }
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
+        // Prune the type indices first, as the events are needed to find their entries
+        if self.indexer.is_some() || self.indexer_async_v2.is_some() {
+            let events = self
+                .ledger_db
+                .event_db()
+                .get_events_by_version_iter(
+                    current_progress,
+                    (target_version - current_progress) as usize,
+                )?
+                .collect::<Result<Vec<_>>>()?;
+            let events_ref: Vec<_> = events.iter().map(Vec::as_slice).collect();
+            if let Some(indexer) = &self.indexer {
+                indexer.prune_events_by_type(current_progress, &events_ref)?;
+            }
+            if let Some(indexer_async_v2) = &self.indexer_async_v2 {
+                indexer_async_v2.prune_events_by_type(current_progress, &events_ref)?;
+            }
+        }
+
         let batch = SchemaBatch::new();
         self.ledger_db
             .event_db()
@@ -38,6 +61,8 @@ impl DBSubPruner for EventStorePruner {
 impl EventStorePruner {
     pub(in crate::pruner) fn new(
         ledger_db: Arc<LedgerDb>,
+        indexer: Option<Arc<Indexer>>,
+        indexer_async_v2: Option<Arc<IndexerAsyncV2>>,
         metadata_progress: Version,
     ) -> Result<Self> {
         let progress = get_or_initialize_subpruner_progress(
@@ -46,7 +71,11 @@ impl EventStorePruner {
             metadata_progress,
         )?;
 
-        let myself = EventStorePruner { ledger_db };
+        let myself = EventStorePruner {
+            ledger_db,
+            indexer,
+            indexer_async_v2,
+        };
 
         info!(
             progress = progress,
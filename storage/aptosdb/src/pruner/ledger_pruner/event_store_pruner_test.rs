@@ -1,18 +1,22 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{AptosDB, EventStore, LedgerPrunerManager, PrunerManager};
+use crate::{
+    db::test_helper::put_and_index_events, AptosDB, EventStore, LedgerPrunerManager, PrunerManager,
+};
 use aptos_config::config::LedgerPrunerConfig;
 use aptos_proptest_helpers::Index;
 use aptos_schemadb::SchemaBatch;
+use aptos_storage_interface::{DbReader, MAX_REQUEST_LIMIT};
 use aptos_temppath::TempPath;
 use aptos_types::{
-    contract_event::ContractEvent,
+    contract_event::{ContractEvent, EventWithVersion},
     proptest_types::{AccountInfoUniverse, ContractEventGen},
     transaction::Version,
 };
+use move_core_types::language_storage::TypeTag;
 use proptest::{collection::vec, prelude::*, proptest};
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -50,6 +54,23 @@ proptest! {
 
         verify_event_store_pruner_disabled(event_batches);
     }
+
+    #[test]
+    fn test_event_store_pruner_with_indexer(
+        mut universe in any_with::<AccountInfoUniverse>(3),
+        gen_batches in vec(vec((any::<Index>(), any::<ContractEventGen>()), 0..=2), 0..20),
+    ) {
+        let event_batches = gen_batches
+            .into_iter()
+            .map(|gens| {
+                gens.into_iter()
+                    .map(|(index, gen)| gen.materialize(*index, &mut universe))
+                    .collect()
+            })
+            .collect();
+
+        verify_event_store_pruner_with_indexer(event_batches);
+    }
 }
 
 fn verify_event_store_pruner(events: Vec<Vec<ContractEvent>>) {
@@ -72,12 +93,17 @@ fn verify_event_store_pruner(events: Vec<Vec<ContractEvent>>) {
     }
     aptos_db.ledger_db.event_db().write_schemas(batch).unwrap();
 
-    let pruner = LedgerPrunerManager::new(Arc::clone(&aptos_db.ledger_db), LedgerPrunerConfig {
-        enable: true,
-        prune_window: 0,
-        batch_size: 1,
-        user_pruning_window_offset: 0,
-    });
+    let pruner = LedgerPrunerManager::new(
+        Arc::clone(&aptos_db.ledger_db),
+        LedgerPrunerConfig {
+            enable: true,
+            prune_window: 0,
+            batch_size: 1,
+            user_pruning_window_offset: 0,
+        },
+        None,
+        None,
+    );
     // start pruning events batches of size 2 and verify transactions have been pruned from DB
     for i in (0..=num_versions).step_by(2) {
         pruner
@@ -98,6 +124,71 @@ fn verify_event_store_pruner(events: Vec<Vec<ContractEvent>>) {
     }
 }
 
+fn verify_event_store_pruner_with_indexer(events: Vec<Vec<ContractEvent>>) {
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test_with_indexer(&tmp_dir);
+    let num_versions = events.len();
+    put_and_index_events(&aptos_db, &events);
+    let type_tags: HashSet<TypeTag> = events
+        .iter()
+        .flatten()
+        .map(|event| event.type_tag().clone())
+        .collect();
+
+    let pruner = LedgerPrunerManager::new(
+        Arc::clone(&aptos_db.ledger_db),
+        LedgerPrunerConfig {
+            enable: true,
+            prune_window: 0,
+            batch_size: 1,
+            user_pruning_window_offset: 0,
+        },
+        aptos_db.indexer.clone(),
+        aptos_db.indexer_async_v2.clone(),
+    );
+    // start pruning events batches of size 2 and verify the type indices have been pruned too
+    for i in (0..=num_versions).step_by(2) {
+        pruner
+            .wake_and_wait_pruner(i as u64 /* latest_version */)
+            .unwrap();
+        for type_tag in &type_tags {
+            let expected_events: Vec<_> = (i..num_versions)
+                .flat_map(|version| {
+                    events[version]
+                        .iter()
+                        .filter(|event| event.type_tag() == type_tag)
+                        .map(move |event| EventWithVersion::new(version as Version, event.clone()))
+                })
+                .collect();
+
+            // The async v2 indexer serves the lookups (as it's enabled)
+            let ledger_version = num_versions as Version - 1;
+            assert_eq!(
+                aptos_db
+                    .get_events_by_type(type_tag, 0, MAX_REQUEST_LIMIT, ledger_version)
+                    .unwrap(),
+                expected_events
+            );
+
+            // The internal indexer no longer holds the pruned events either
+            let expected_versions: Vec<_> = expected_events
+                .iter()
+                .map(|event| event.transaction_version)
+                .collect();
+            let versions: Vec<_> = aptos_db
+                .indexer
+                .as_ref()
+                .unwrap()
+                .lookup_events_by_type(type_tag, 0, num_versions as Version, MAX_REQUEST_LIMIT)
+                .unwrap()
+                .into_iter()
+                .map(|(version, _)| version)
+                .collect();
+            assert_eq!(versions, expected_versions);
+        }
+    }
+}
+
 fn verify_event_store_pruner_disabled(events: Vec<Vec<ContractEvent>>) {
     let tmp_dir = TempPath::new();
     let aptos_db = AptosDB::new_for_test(&tmp_dir);
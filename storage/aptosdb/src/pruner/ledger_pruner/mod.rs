@@ -25,6 +25,7 @@ use crate::{
     transaction_store::TransactionStore,
 };
 use anyhow::anyhow;
+use aptos_db_indexer::{db_v2::IndexerAsyncV2, Indexer};
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_logger::info;
 use aptos_storage_interface::Result;
@@ -110,7 +111,11 @@ impl DBPruner for LedgerPruner {
 }
 
 impl LedgerPruner {
-    pub fn new(ledger_db: Arc<LedgerDb>) -> Result<Self> {
+    pub fn new(
+        ledger_db: Arc<LedgerDb>,
+        indexer: Option<Arc<Indexer>>,
+        indexer_async_v2: Option<Arc<IndexerAsyncV2>>,
+    ) -> Result<Self> {
         info!(name = LEDGER_PRUNER_NAME, "Initializing...");
 
         let ledger_metadata_pruner = Box::new(
@@ -129,6 +134,8 @@ impl LedgerPruner {
 
         let event_store_pruner = Box::new(EventStorePruner::new(
             Arc::clone(&ledger_db),
+            indexer,
+            indexer_async_v2,
             metadata_progress,
         )?);
         let transaction_accumulator_pruner = Box::new(TransactionAccumulatorPruner::new(
@@ -280,6 +280,12 @@ impl StateMerkleDb {
             .map_err(Into::into)
     }
 
+    pub fn get_leaf_index(&self, key: HashValue, version: Version) -> Result<usize> {
+        JellyfishMerkleTree::new(self)
+            .get_leaf_index(key, version)
+            .map_err(Into::into)
+    }
+
     pub fn batch_put_value_set_for_shard(
         &self,
         shard_id: u8,
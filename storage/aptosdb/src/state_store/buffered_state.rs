@@ -4,10 +4,17 @@
 //! This file defines state store buffered state that has been committed.
 
 use crate::{
-    metrics::LATEST_CHECKPOINT_VERSION,
-    state_store::{state_snapshot_committer::StateSnapshotCommitter, StateDb},
+    metrics::{BUFFERED_STATE_LAG_BYTES, BUFFERED_STATE_LAG_VERSIONS, LATEST_CHECKPOINT_VERSION},
+    state_store::{
+        state_snapshot_committer::StateSnapshotCommitter, StateDb, MAX_WRITE_SETS_AFTER_SNAPSHOT,
+    },
+};
+use aptos_infallible::Mutex;
+use aptos_logger::{
+    info,
+    prelude::{sample, SampleRate},
+    warn,
 };
-use aptos_logger::info;
 use aptos_scratchpad::SmtAncestors;
 use aptos_storage_interface::{db_ensure as ensure, state_delta::StateDelta, AptosDbError, Result};
 use aptos_types::{
@@ -15,16 +22,21 @@ use aptos_types::{
     transaction::Version,
 };
 use std::{
+    collections::VecDeque,
     sync::{
         mpsc,
         mpsc::{Sender, SyncSender},
         Arc,
     },
     thread::JoinHandle,
+    time::Duration,
 };
 
 pub(crate) const ASYNC_COMMIT_CHANNEL_BUFFER_SIZE: u64 = 1;
 pub(crate) const TARGET_SNAPSHOT_INTERVAL_IN_VERSION: u64 = 100_000;
+// Warn once the in-memory state gets this far ahead of the latest persisted snapshot, since
+// the node refuses to restart once the gap exceeds MAX_WRITE_SETS_AFTER_SNAPSHOT.
+const LAG_WARNING_THRESHOLD_IN_VERSION: u64 = MAX_WRITE_SETS_AFTER_SNAPSHOT / 10 * 8;
 
 /// The in-memory buffered state that consists of two pieces:
 /// `state_until_checkpoint`: The ready-to-commit data in range (last snapshot, latest checkpoint].
@@ -41,6 +53,41 @@ pub struct BufferedState {
     state_commit_sender: SyncSender<CommitMessage<Arc<StateDelta>>>,
     target_items: usize,
     join_handle: Option<JoinHandle<()>>,
+    // The version of the latest snapshot persisted by the async commit threads.
+    persisted_snapshot_version: Arc<Mutex<Option<Version>>>,
+    // The checkpoints that are not yet persisted in a snapshot.
+    unpersisted_checkpoints: UnpersistedCheckpoints,
+}
+
+/// The estimated sizes of the checkpoints that are not yet persisted in a snapshot, along with
+/// their running total, so the lag can be reported without rescanning the buffered updates.
+#[derive(Debug, Default)]
+pub(crate) struct UnpersistedCheckpoints {
+    // (checkpoint version, estimated bytes of the updates since the previous checkpoint)
+    checkpoints: VecDeque<(Version, usize)>,
+    total_bytes: usize,
+}
+
+impl UnpersistedCheckpoints {
+    pub(crate) fn push(&mut self, version: Version, bytes: usize) {
+        self.checkpoints.push_back((version, bytes));
+        self.total_bytes += bytes;
+    }
+
+    /// Drops the checkpoints at or before the persisted snapshot version.
+    pub(crate) fn prune(&mut self, persisted_snapshot_version: Option<Version>) {
+        while let Some(&(version, bytes)) = self.checkpoints.front() {
+            if persisted_snapshot_version.map_or(true, |persisted| version > persisted) {
+                break;
+            }
+            self.total_bytes -= bytes;
+            self.checkpoints.pop_front();
+        }
+    }
+
+    pub(crate) fn total_bytes(&self) -> usize {
+        self.total_bytes
+    }
 }
 
 pub(crate) enum CommitMessage<T> {
@@ -60,6 +107,8 @@ impl BufferedState {
         let arc_state_db = Arc::clone(state_db);
         let smt_ancestors = SmtAncestors::new(state_after_checkpoint.base.clone());
         let smt_ancestors_clone = smt_ancestors.clone();
+        let persisted_snapshot_version = Arc::new(Mutex::new(state_after_checkpoint.base_version));
+        let persisted_snapshot_version_clone = Arc::clone(&persisted_snapshot_version);
         // Create a new thread with receiver subscribing to state commit changes
         let join_handle = std::thread::Builder::new()
             .name("state-committer".to_string())
@@ -68,19 +117,23 @@ impl BufferedState {
                     arc_state_db,
                     state_commit_receiver,
                     smt_ancestors_clone,
+                    persisted_snapshot_version_clone,
                 );
                 committer.run();
             })
             .expect("Failed to spawn state committer thread.");
-        let myself = Self {
+        let mut myself = Self {
             state_until_checkpoint: None,
             state_after_checkpoint,
             state_commit_sender,
             target_items,
             // The join handle of the async state commit thread for graceful drop.
             join_handle: Some(join_handle),
+            persisted_snapshot_version,
+            unpersisted_checkpoints: UnpersistedCheckpoints::default(),
         };
         myself.report_latest_committed_version();
+        myself.report_buffered_state_lag();
         (myself, smt_ancestors)
     }
 
@@ -151,6 +204,38 @@ impl BufferedState {
         );
     }
 
+    /// Reports the gap between the current in-memory state and the latest persisted snapshot,
+    /// warning if it is approaching MAX_WRITE_SETS_AFTER_SNAPSHOT. The updates after the latest
+    /// checkpoint are only counted in the bytes once they reach a checkpoint.
+    fn report_buffered_state_lag(&mut self) {
+        let persisted_snapshot_version = *self.persisted_snapshot_version.lock();
+        self.unpersisted_checkpoints
+            .prune(persisted_snapshot_version);
+
+        let lag_versions = self
+            .state_after_checkpoint
+            .current_version
+            .map_or(0, |v| v + 1)
+            .saturating_sub(persisted_snapshot_version.map_or(0, |v| v + 1));
+        let lag_bytes = self.unpersisted_checkpoints.total_bytes();
+        BUFFERED_STATE_LAG_VERSIONS.set(lag_versions as i64);
+        BUFFERED_STATE_LAG_BYTES.set(lag_bytes as i64);
+
+        if lag_versions > LAG_WARNING_THRESHOLD_IN_VERSION {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(10)),
+                warn!(
+                    persisted_snapshot_version = persisted_snapshot_version,
+                    current_version = self.state_after_checkpoint.current_version,
+                    lag_versions = lag_versions,
+                    lag_bytes = lag_bytes,
+                    max_lag_versions = MAX_WRITE_SETS_AFTER_SNAPSHOT,
+                    "State snapshot commit is falling behind. The node will fail to restart if the lag exceeds the max.",
+                )
+            );
+        }
+    }
+
     /// This method updates the buffered state with new data.
     pub fn update(
         &mut self,
@@ -176,6 +261,14 @@ impl BufferedState {
                 &mut self.state_after_checkpoint.updates_since_base,
                 updates_until_next_checkpoint_since_current,
             );
+            // The updates since the previous checkpoint are moved out of state_after_checkpoint
+            // below, so each of them is only sized once.
+            self.unpersisted_checkpoints.push(
+                new_state_after_checkpoint
+                    .base_version
+                    .expect("Checkpoint version must exist."),
+                updates_size(&self.state_after_checkpoint.updates_since_base),
+            );
             self.state_after_checkpoint.current = new_state_after_checkpoint.base.clone();
             self.state_after_checkpoint.current_version = new_state_after_checkpoint.base_version;
             let state_after_checkpoint = self
@@ -195,10 +288,19 @@ impl BufferedState {
         }
         self.maybe_commit(sync_commit);
         self.report_latest_committed_version();
+        self.report_buffered_state_lag();
         Ok(())
     }
 }
 
+pub(crate) fn updates_size(updates: &ShardedStateUpdates) -> usize {
+    updates
+        .iter()
+        .flat_map(|shard| shard.iter())
+        .map(|(key, value)| key.size() + value.as_ref().map_or(0, |v| v.size()))
+        .sum()
+}
+
 impl Drop for BufferedState {
     fn drop(&mut self) {
         self.sync_commit();
@@ -0,0 +1,58 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::buffered_state::{updates_size, UnpersistedCheckpoints};
+use aptos_types::state_store::{
+    create_empty_sharded_state_updates, state_key::StateKey, state_value::StateValue,
+};
+
+#[test]
+fn test_updates_size() {
+    let mut updates = create_empty_sharded_state_updates();
+    assert_eq!(updates_size(&updates), 0);
+
+    updates[0].insert(
+        StateKey::raw(vec![1; 3]),
+        Some(StateValue::from(vec![2; 5])),
+    );
+    updates[15].insert(
+        StateKey::raw(vec![3; 7]),
+        Some(StateValue::from(vec![4; 11])),
+    );
+    // Deletions only count the key.
+    updates[15].insert(StateKey::raw(vec![5; 13]), None);
+    assert_eq!(updates_size(&updates), 3 + 5 + 7 + 11 + 13);
+}
+
+#[test]
+fn test_unpersisted_checkpoints() {
+    let mut checkpoints = UnpersistedCheckpoints::default();
+    assert_eq!(checkpoints.total_bytes(), 0);
+
+    checkpoints.push(10, 100);
+    checkpoints.push(20, 200);
+    checkpoints.push(30, 300);
+    assert_eq!(checkpoints.total_bytes(), 600);
+
+    // Nothing is persisted yet.
+    checkpoints.prune(None);
+    assert_eq!(checkpoints.total_bytes(), 600);
+
+    // A snapshot before the first checkpoint doesn't cover any of them.
+    checkpoints.prune(Some(5));
+    assert_eq!(checkpoints.total_bytes(), 600);
+
+    // A snapshot at a checkpoint covers it and everything before it.
+    checkpoints.prune(Some(20));
+    assert_eq!(checkpoints.total_bytes(), 300);
+
+    checkpoints.push(40, 400);
+    assert_eq!(checkpoints.total_bytes(), 700);
+
+    checkpoints.prune(Some(40));
+    assert_eq!(checkpoints.total_bytes(), 0);
+
+    // Pruning again is a no-op.
+    checkpoints.prune(Some(40));
+    assert_eq!(checkpoints.total_bytes(), 0);
+}
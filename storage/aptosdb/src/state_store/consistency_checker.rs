@@ -0,0 +1,240 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A background task that detects silent divergence between the state kv db and the state
+//! merkle db (e.g., after a crash). It periodically samples state keys at the latest state
+//! snapshot, recomputes their JMT proofs against the root hash and cross-checks the values
+//! stored in the state kv db against the merkle leaves.
+
+use crate::{
+    metrics::{
+        STATE_CONSISTENCY_CHECKED_KEYS, STATE_CONSISTENCY_CHECK_ERRORS,
+        STATE_CONSISTENCY_CHECK_VERSION, STATE_INCONSISTENCIES,
+    },
+    schema::transaction_info::TransactionInfoSchema,
+    state_store::StateDb,
+};
+use aptos_config::config::ConsistencyCheckerConfig;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_infallible::Mutex;
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::{
+    consistency_check::{ConsistencyCheckReport, StateInconsistency, StateInconsistencyKind},
+    DbReader, Result,
+};
+use aptos_types::{state_store::state_key::StateKey, transaction::Version};
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+    thread::JoinHandle,
+    time::Duration,
+};
+
+#[derive(Debug)]
+pub(crate) struct ConsistencyChecker {
+    latest_report: Arc<Mutex<Option<ConsistencyCheckReport>>>,
+    sender: Mutex<mpsc::Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl ConsistencyChecker {
+    pub(crate) fn new(state_db: Arc<StateDb>, config: ConsistencyCheckerConfig) -> Self {
+        let latest_report = Arc::new(Mutex::new(None));
+        let report_holder = latest_report.clone();
+        let (send, recv) = mpsc::channel();
+        let join_handle = Some(
+            thread::Builder::new()
+                .name("state_consistency_checker".into())
+                .spawn(move || loop {
+                    match recv.recv_timeout(Duration::from_millis(config.check_interval_ms)) {
+                        Ok(_) => break,
+                        Err(mpsc::RecvTimeoutError::Timeout) => (),
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+
+                    match check_latest_snapshot(&state_db, config.num_sampled_keys) {
+                        Ok(Some(report)) => {
+                            update_metrics(&report);
+                            *report_holder.lock() = Some(report);
+                        },
+                        Ok(None) => (),
+                        Err(error) => {
+                            // The snapshot can e.g. be pruned while being checked.
+                            STATE_CONSISTENCY_CHECK_ERRORS.inc();
+                            warn!(error = ?error, "State consistency check failed.");
+                        },
+                    }
+                })
+                .expect("Failed to spawn the state consistency checker thread."),
+        );
+        Self {
+            latest_report,
+            sender: Mutex::new(send),
+            join_handle,
+        }
+    }
+
+    /// Returns the report of the latest completed check, if any
+    pub(crate) fn latest_report(&self) -> Option<ConsistencyCheckReport> {
+        self.latest_report.lock().clone()
+    }
+}
+
+impl Drop for ConsistencyChecker {
+    fn drop(&mut self) {
+        // Notify the checker thread to exit
+        self.sender.lock().send(()).unwrap();
+        self.join_handle
+            .take()
+            .expect("State consistency checker thread must exist.")
+            .join()
+            .expect("State consistency checker thread should join peacefully.");
+    }
+}
+
+/// Checks a random sample of state keys at the latest state snapshot. Returns None
+/// if there is no state snapshot yet.
+fn check_latest_snapshot(
+    state_db: &StateDb,
+    num_sampled_keys: usize,
+) -> Result<Option<ConsistencyCheckReport>> {
+    match state_db.get_state_snapshot_before(Version::MAX)? {
+        Some((version, _root_hash)) => {
+            check_state_consistency(state_db, version, num_sampled_keys).map(Some)
+        },
+        None => Ok(None),
+    }
+}
+
+/// Checks the consistency of a random sample of state keys at the given snapshot version.
+pub(crate) fn check_state_consistency(
+    state_db: &StateDb,
+    version: Version,
+    num_sampled_keys: usize,
+) -> Result<ConsistencyCheckReport> {
+    let root_hash = state_db.state_merkle_db.get_root_hash(version)?;
+    let mut report = ConsistencyCheckReport::new(version, root_hash);
+
+    // The root hash must match the state checkpoint hash committed in the ledger (if the
+    // transaction info is available, i.e., it wasn't pruned or skipped by a state restore).
+    if let Some(txn_info) = state_db
+        .ledger_db
+        .transaction_info_db()
+        .get::<TransactionInfoSchema>(&version)?
+    {
+        if let Some(ledger_root_hash) = txn_info.state_checkpoint_hash() {
+            if ledger_root_hash != root_hash {
+                report.mismatched_ledger_root_hash = Some(ledger_root_hash);
+            }
+        }
+    }
+
+    for _ in 0..num_sampled_keys {
+        // Key hashes are uniformly distributed, so the first leaf
+        // after a random hash is a (roughly) random sample.
+        let Some(state_key) = sample_state_key(state_db, version, HashValue::random())? else {
+            break; // The tree is empty
+        };
+        if let Some(kind) = check_state_key(state_db, &state_key, version, root_hash)? {
+            report
+                .inconsistencies
+                .push(StateInconsistency { state_key, kind });
+        }
+        report.num_keys_checked += 1;
+    }
+
+    Ok(report)
+}
+
+/// Returns the key of the first leaf at or after the given key hash. If there is no such
+/// leaf, the search wraps around to the first leaf of the tree, so that every sample hits
+/// a key unless the tree is empty.
+pub(crate) fn sample_state_key(
+    state_db: &StateDb,
+    version: Version,
+    start_key_hash: HashValue,
+) -> Result<Option<StateKey>> {
+    for key_hash in [start_key_hash, HashValue::zero()] {
+        let leaf =
+            JellyfishMerkleIterator::new(Arc::clone(&state_db.state_merkle_db), version, key_hash)?
+                .next()
+                .transpose()?;
+        if let Some((_key_hash, (state_key, _leaf_version))) = leaf {
+            return Ok(Some(state_key));
+        }
+    }
+    Ok(None)
+}
+
+/// Recomputes the JMT proof of the given key against the root hash, and cross-checks
+/// the value in the state kv db against the merkle leaf.
+fn check_state_key(
+    state_db: &StateDb,
+    state_key: &StateKey,
+    version: Version,
+    root_hash: HashValue,
+) -> Result<Option<StateInconsistencyKind>> {
+    let (leaf, proof) = state_db
+        .state_merkle_db
+        .get_with_proof_ext(state_key, version)?;
+    let leaf_value_hash = leaf.as_ref().map(|(value_hash, _)| *value_hash);
+    if proof
+        .verify_by_hash(root_hash, state_key.hash(), leaf_value_hash)
+        .is_err()
+    {
+        return Ok(Some(StateInconsistencyKind::InvalidProof));
+    }
+    let (leaf_value_hash, leaf_version) = match leaf {
+        Some((value_hash, (_, leaf_version))) => (value_hash, leaf_version),
+        // The key was sampled from the tree, so a non-inclusion proof is invalid
+        None => return Ok(Some(StateInconsistencyKind::InvalidProof)),
+    };
+
+    let kv_value = state_db.get_state_value_with_version_by_version(state_key, version)?;
+    let inconsistency = match kv_value {
+        None => Some(StateInconsistencyKind::MissingValue { leaf_version }),
+        Some((kv_version, _)) if kv_version != leaf_version => {
+            Some(StateInconsistencyKind::VersionMismatch {
+                leaf_version,
+                kv_version,
+            })
+        },
+        Some((_, value)) => {
+            let kv_value_hash = CryptoHash::hash(&value);
+            (kv_value_hash != leaf_value_hash).then_some(
+                StateInconsistencyKind::ValueHashMismatch {
+                    leaf_value_hash,
+                    kv_value_hash,
+                },
+            )
+        },
+    };
+    Ok(inconsistency)
+}
+
+fn update_metrics(report: &ConsistencyCheckReport) {
+    STATE_CONSISTENCY_CHECK_VERSION.set(report.version as i64);
+    STATE_CONSISTENCY_CHECKED_KEYS.inc_by(report.num_keys_checked as u64);
+    if report.mismatched_ledger_root_hash.is_some() {
+        STATE_INCONSISTENCIES
+            .with_label_values(&["ledger_root_hash_mismatch"])
+            .inc();
+    }
+    for inconsistency in &report.inconsistencies {
+        STATE_INCONSISTENCIES
+            .with_label_values(&[inconsistency.kind.get_label()])
+            .inc();
+    }
+
+    if !report.is_consistent() {
+        error!(
+            version = report.version,
+            root_hash = %report.root_hash,
+            mismatched_ledger_root_hash = ?report.mismatched_ledger_root_hash,
+            num_inconsistencies = report.inconsistencies.len(),
+            "State store inconsistencies detected: {:?}",
+            report.inconsistencies
+        );
+    }
+}
@@ -26,11 +26,16 @@ use crate::{
         StateSnapshotProgress, StateSnapshotRestore, StateSnapshotRestoreMode, StateValueWriter,
     },
     state_store::buffered_state::BufferedState,
+    state_value_cache::StateValueCache,
     transaction_store::TransactionStore,
     utils::{
+        get_progress,
         iterators::PrefixedStateValueIterator,
         new_sharded_kv_schema_batch,
-        truncation_helper::{truncate_ledger_db, truncate_state_kv_db},
+        truncation_helper::{
+            get_ledger_commit_progress, get_overall_commit_progress, get_state_kv_commit_progress,
+            get_state_merkle_commit_progress, truncate_ledger_db, truncate_state_kv_db,
+        },
         ShardedStateKvSchemaBatch,
     },
 };
@@ -49,6 +54,7 @@ use aptos_scratchpad::{SmtAncestors, SparseMerkleTree};
 use aptos_storage_interface::{
     async_proof_fetcher::AsyncProofFetcher,
     cached_state_view::{CachedStateView, ShardedStateCache},
+    commit_progress::{CommitProgressReport, TruncationInfo},
     db_ensure as ensure,
     state_delta::StateDelta,
     AptosDbError, DbReader, Result, StateSnapshotReceiver,
@@ -67,13 +73,18 @@ use aptos_types::{
     write_set::{TransactionWrite, WriteSet},
 };
 use claims::{assert_ge, assert_le};
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use std::{collections::HashSet, ops::Deref, sync::Arc};
 
 pub(crate) mod buffered_state;
+pub(crate) mod consistency_checker;
+pub(crate) mod snapshot_file;
 mod state_merkle_batch_committer;
 mod state_snapshot_committer;
 
+#[cfg(test)]
+mod buffered_state_test;
 #[cfg(test)]
 mod state_store_test;
 
@@ -94,6 +105,8 @@ pub(crate) struct StateDb {
     pub epoch_snapshot_pruner: StateMerklePrunerManager<StaleNodeIndexCrossEpochSchema>,
     pub state_kv_pruner: StateKvPrunerManager,
     pub skip_usage: bool,
+    // Optional cache of the latest values of hot state keys, set by `enable_state_value_cache()`.
+    pub state_value_cache: OnceCell<StateValueCache>,
 }
 
 pub(crate) struct StateStore {
@@ -104,6 +117,8 @@ pub(crate) struct StateStore {
     buffered_state: Mutex<BufferedState>,
     buffered_state_target_items: usize,
     smt_ancestors: Mutex<SmtAncestors<StateValue>>,
+    // Truncations done by `sync_commit_progress` when the store was opened.
+    startup_truncations: Vec<TruncationInfo>,
 }
 
 impl Deref for StateStore {
@@ -143,6 +158,35 @@ impl DbReader for StateDb {
             .map(|(_, value)| value))
     }
 
+    /// Gets the latest state values of the given keys up to the given version. The keys are
+    /// grouped by shard, and the shards are read in parallel (see `get_state_values_in_shard()`).
+    fn get_state_values_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<Option<StateValue>>> {
+        let mut key_indices_by_shard = vec![Vec::new(); NUM_STATE_SHARDS];
+        for (index, state_key) in state_keys.iter().enumerate() {
+            key_indices_by_shard[state_key.get_shard_id() as usize].push(index);
+        }
+
+        let sharded_values = THREAD_MANAGER.get_io_pool().install(|| {
+            key_indices_by_shard
+                .into_par_iter()
+                .enumerate()
+                .map(|(shard_id, key_indices)| {
+                    self.get_state_values_in_shard(shard_id as u8, state_keys, key_indices, version)
+                })
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        let mut state_values = vec![None; state_keys.len()];
+        for (index, state_value) in sharded_values.into_iter().flatten() {
+            state_values[index] = state_value;
+        }
+        Ok(state_values)
+    }
+
     /// Gets the latest state value and its corresponding version when it's of the given key up
     /// to the given version.
     fn get_state_value_with_version_by_version(
@@ -150,6 +194,17 @@ impl DbReader for StateDb {
         state_key: &StateKey,
         version: Version,
     ) -> Result<Option<(Version, StateValue)>> {
+        let cache = self.state_value_cache.get();
+        let committed_version = match cache {
+            Some(cache) => {
+                if let Some(value) = cache.get(state_key, version) {
+                    return Ok(Some(value));
+                }
+                cache.committed_version(state_key)
+            },
+            None => None,
+        };
+
         let mut read_opts = ReadOptions::default();
         // We want `None` if the state_key changes in iteration.
         read_opts.set_prefix_same_as_start(true);
@@ -158,10 +213,14 @@ impl DbReader for StateDb {
             .db_shard(state_key.get_shard_id())
             .iter::<StateValueSchema>(read_opts)?;
         iter.seek(&(state_key.clone(), version))?;
-        Ok(iter
+        let ret = iter
             .next()
             .transpose()?
-            .and_then(|((_, version), value_opt)| value_opt.map(|value| (version, value))))
+            .and_then(|((_, version), value_opt)| value_opt.map(|value| (version, value)));
+        if let (Some(cache), Some(value)) = (cache, &ret) {
+            cache.insert(state_key, version, committed_version, value.clone());
+        }
+        Ok(ret)
     }
 
     /// Returns the proof of the given state key and version.
@@ -230,6 +289,57 @@ impl StateDb {
         iter.seek_for_prev(&prev_version)?;
         iter.next().transpose().map_err(Into::into)
     }
+
+    /// Enables caching the latest values of hot state keys in memory, with up to
+    /// `max_bytes_per_shard` bytes of keys and values cached for each of the state shards.
+    pub fn enable_state_value_cache(&self, max_bytes_per_shard: usize) -> Result<()> {
+        ensure!(
+            max_bytes_per_shard > 0,
+            "The state value cache must hold at least one byte per shard."
+        );
+        let committed_version = get_state_kv_commit_progress(&self.state_kv_db)?;
+        ensure!(
+            self.state_value_cache
+                .set(StateValueCache::new(max_bytes_per_shard, committed_version))
+                .is_ok(),
+            "The state value cache is already enabled."
+        );
+        Ok(())
+    }
+
+    /// Evicts the cached values of the keys updated by `value_state_sets`. Must be called once
+    /// the updates up to `version` are written to the state kv db.
+    pub fn invalidate_state_value_cache(
+        &self,
+        version: Version,
+        value_state_sets: &[&ShardedStateUpdates],
+    ) {
+        if let Some(cache) = self.state_value_cache.get() {
+            cache.invalidate(
+                version,
+                value_state_sets
+                    .iter()
+                    .flat_map(|shards| shards.iter().flat_map(|kvs| kvs.keys())),
+            );
+        }
+    }
+
+    /// Evicts the cached values of the keys written by `write_sets`. Must be called once the
+    /// write sets up to `version` are written to the state kv db, e.g. when replayed on restore.
+    pub fn invalidate_state_value_cache_by_write_sets(
+        &self,
+        version: Version,
+        write_sets: &[WriteSet],
+    ) {
+        if let Some(cache) = self.state_value_cache.get() {
+            cache.invalidate(
+                version,
+                write_sets
+                    .iter()
+                    .flat_map(|write_set| write_set.iter().map(|(key, _)| key)),
+            );
+        }
+    }
 }
 
 impl DbReader for StateStore {
@@ -256,6 +366,16 @@ impl DbReader for StateStore {
         self.deref().get_state_value_by_version(state_key, version)
     }
 
+    /// Gets the latest state values of the given keys up to the given version.
+    fn get_state_values_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<Option<StateValue>>> {
+        self.deref()
+            .get_state_values_by_version(state_keys, version)
+    }
+
     /// Gets the latest state value and the its corresponding version when its of the given key up
     /// to the given version.
     fn get_state_value_with_version_by_version(
@@ -289,6 +409,43 @@ impl DbReader for StateStore {
 }
 
 impl StateDb {
+    /// Gets the latest state values (up to the given version) of the keys at the given
+    /// indices, which must all belong to the given shard. Values are stored under versioned
+    /// keys, so the lookups can't be expressed as point reads (i.e., a RocksDB multi-get).
+    /// Instead, a single iterator is created for the shard and reused to seek each key.
+    fn get_state_values_in_shard(
+        &self,
+        shard_id: u8,
+        state_keys: &[StateKey],
+        key_indices: Vec<usize>,
+        version: Version,
+    ) -> Result<Vec<(usize, Option<StateValue>)>> {
+        if key_indices.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut read_opts = ReadOptions::default();
+        // We want `None` if the state_key changes in iteration. Note: the prefix is taken
+        // from the seek key on every seek, so the iterator can be reused across keys.
+        read_opts.set_prefix_same_as_start(true);
+        let mut iter = self
+            .state_kv_db
+            .db_shard(shard_id)
+            .iter::<StateValueSchema>(read_opts)?;
+
+        key_indices
+            .into_iter()
+            .map(|index| {
+                iter.seek(&(state_keys[index].clone(), version))?;
+                let state_value = iter
+                    .next()
+                    .transpose()?
+                    .and_then(|(_, value_opt)| value_opt);
+                Ok((index, state_value))
+            })
+            .collect()
+    }
+
     fn expect_value_by_version(
         &self,
         state_key: &StateKey,
@@ -319,13 +476,15 @@ impl StateStore {
         empty_buffered_state_for_restore: bool,
         skip_usage: bool,
     ) -> Self {
-        if !hack_for_tests && !empty_buffered_state_for_restore {
+        let startup_truncations = if !hack_for_tests && !empty_buffered_state_for_restore {
             Self::sync_commit_progress(
                 Arc::clone(&ledger_db),
                 Arc::clone(&state_kv_db),
                 /*crash_if_difference_is_too_large=*/ true,
-            );
-        }
+            )
+        } else {
+            Vec::new()
+        };
         let state_db = Arc::new(StateDb {
             ledger_db,
             state_merkle_db,
@@ -334,6 +493,7 @@ impl StateStore {
             epoch_snapshot_pruner,
             state_kv_pruner,
             skip_usage,
+            state_value_cache: OnceCell::new(),
         });
         let (buffered_state, smt_ancestors) = if empty_buffered_state_for_restore {
             BufferedState::new(
@@ -356,16 +516,18 @@ impl StateStore {
             buffered_state: Mutex::new(buffered_state),
             buffered_state_target_items,
             smt_ancestors: Mutex::new(smt_ancestors),
+            startup_truncations,
         }
     }
 
     // We commit the overall commit progress at the last, and use it as the source of truth of the
-    // commit progress.
+    // commit progress. Returns the truncations that were done.
     pub fn sync_commit_progress(
         ledger_db: Arc<LedgerDb>,
         state_kv_db: Arc<StateKvDb>,
         crash_if_difference_is_too_large: bool,
-    ) {
+    ) -> Vec<TruncationInfo> {
+        let mut truncations = Vec::new();
         let ledger_metadata_db = ledger_db.metadata_db();
         if let Some(DbMetadataValue::Version(overall_commit_progress)) = ledger_metadata_db
             .get::<DbMetadataSchema>(&DbMetadataKey::OverallCommitProgress)
@@ -402,6 +564,13 @@ impl StateStore {
             }
             truncate_ledger_db(ledger_db, overall_commit_progress)
                 .expect("Failed to truncate ledger db.");
+            if difference > 0 {
+                truncations.push(TruncationInfo::new(
+                    "ledger_db",
+                    ledger_commit_progress,
+                    overall_commit_progress,
+                ));
+            }
 
             if state_kv_commit_progress != overall_commit_progress {
                 info!(
@@ -419,10 +588,46 @@ impl StateStore {
                     difference as usize,
                 )
                 .expect("Failed to truncate state K/V db.");
+                truncations.push(TruncationInfo::new(
+                    "state_kv_db",
+                    state_kv_commit_progress,
+                    overall_commit_progress,
+                ));
             }
         } else {
             info!("No overall commit progress was found!");
         }
+        truncations
+    }
+
+    pub fn get_commit_progress(&self) -> Result<CommitProgressReport> {
+        let ledger_metadata_db = self.ledger_db.metadata_db();
+        let state_kv_shard_commit_progress = (0..NUM_STATE_SHARDS)
+            .map(|shard_id| {
+                get_progress(
+                    self.state_kv_db.db_shard(shard_id as u8),
+                    &DbMetadataKey::StateKvShardCommitProgress(shard_id),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let state_merkle_shard_commit_progress = (0..NUM_STATE_SHARDS)
+            .map(|shard_id| {
+                get_progress(
+                    self.state_merkle_db.db_shard(shard_id as u8),
+                    &DbMetadataKey::StateMerkleShardCommitProgress(shard_id),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(CommitProgressReport {
+            overall_commit_progress: get_overall_commit_progress(ledger_metadata_db)?,
+            ledger_commit_progress: get_ledger_commit_progress(ledger_metadata_db)?,
+            state_kv_commit_progress: get_state_kv_commit_progress(&self.state_kv_db)?,
+            state_kv_shard_commit_progress,
+            state_merkle_commit_progress: get_state_merkle_commit_progress(&self.state_merkle_db)?,
+            state_merkle_shard_commit_progress,
+            startup_truncations: self.startup_truncations.clone(),
+        })
     }
 
     #[cfg(feature = "db-debugger")]
@@ -453,6 +658,7 @@ impl StateStore {
             epoch_snapshot_pruner,
             state_kv_pruner,
             skip_usage: false,
+            state_value_cache: OnceCell::new(),
         });
         let (buffered_state, _) = Self::create_buffered_state_from_latest_snapshot(
             &state_db, 0, /*hack_for_tests=*/ false,
@@ -1066,6 +1272,24 @@ impl StateStore {
         })
     }
 
+    pub fn get_value_chunk_by_key_range_with_proof(
+        self: &Arc<Self>,
+        version: Version,
+        start_key_hash: HashValue,
+        chunk_size: usize,
+    ) -> Result<StateValueChunkWithProof> {
+        let first_index = self
+            .state_merkle_db
+            .get_leaf_index(start_key_hash, version)?;
+        if first_index >= self.state_merkle_db.get_leaf_count(version)? {
+            return Err(AptosDbError::RangeStartsPastEnd(format!(
+                "no state key hash >= {:x} at version {}",
+                start_key_hash, version
+            )));
+        }
+        self.get_value_chunk_with_proof(version, first_index, chunk_size)
+    }
+
     // state sync doesn't query for the progress, but keeps its record by itself.
     // TODO: change to async comment once it does like https://github.com/aptos-labs/aptos-core/blob/159b00f3d53e4327523052c1b99dd9889bf13b03/storage/backup/backup-cli/src/backup_types/state_snapshot/restore.rs#L147 or overlap at least two chunks.
     pub fn get_snapshot_receiver(
@@ -1184,7 +1408,11 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
         self.shard_state_value_batch(&batch, &sharded_schema_batch, node_batch)?;
 
         self.state_kv_db
-            .commit(version, batch, sharded_schema_batch)
+            .commit(version, batch, sharded_schema_batch)?;
+        if let Some(cache) = self.state_value_cache.get() {
+            cache.invalidate(version, node_batch.keys().map(|(key, _)| key));
+        }
+        Ok(())
     }
 
     fn write_usage(&self, version: Version, usage: StateStorageUsage) -> Result<()> {
@@ -0,0 +1,159 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A portable file format for state snapshots, independent of the backup service and CLI, so
+//! tooling can export the full key/value set at a snapshot version (e.g. for analytics or
+//! local replay) and import it back into an empty DB.
+//!
+//! The file is a sequence of records, each being the length of the BCS encoded payload (u32,
+//! little endian), the payload itself, and its SHA3-256 checksum. The first record is the
+//! [`StateSnapshotFileHeader`], followed by `num_chunks` chunks of state values, ordered by
+//! hashed key, each with the range proof of its rightmost key, so the import is verified
+//! against the root hash in the header.
+
+use crate::state_store::StateStore;
+use aptos_crypto::HashValue;
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
+use aptos_types::{
+    proof::SparseMerkleRangeProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    sync::Arc,
+};
+
+pub const STATE_SNAPSHOT_FILE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshotFileHeader {
+    pub format_version: u32,
+    pub version: Version,
+    pub root_hash: HashValue,
+    pub num_values: u64,
+    pub num_chunks: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+struct StateSnapshotChunk {
+    raw_values: Vec<(StateKey, StateValue)>,
+    proof: SparseMerkleRangeProof,
+}
+
+fn write_record<T: Serialize>(writer: &mut impl Write, record: &T) -> Result<()> {
+    let bytes = bcs::to_bytes(record)?;
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| AptosDbError::Other(format!("Record too large: {} bytes", bytes.len())))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.write_all(HashValue::sha3_256_of(&bytes).as_ref())?;
+    Ok(())
+}
+
+fn read_record<T: DeserializeOwned>(reader: &mut impl Read) -> Result<T> {
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes)?;
+    let len = u32::from_le_bytes(len_bytes) as u64;
+
+    let mut bytes = Vec::new();
+    reader.by_ref().take(len).read_to_end(&mut bytes)?;
+    ensure!(
+        bytes.len() as u64 == len,
+        "Truncated record, expected {} bytes, got {}.",
+        len,
+        bytes.len(),
+    );
+
+    let mut checksum = [0u8; HashValue::LENGTH];
+    reader.read_exact(&mut checksum)?;
+    ensure!(
+        HashValue::new(checksum) == HashValue::sha3_256_of(&bytes),
+        "Checksum mismatch for a record of {} bytes.",
+        len,
+    );
+    Ok(bcs::from_bytes(&bytes)?)
+}
+
+impl StateStore {
+    /// Writes all the state values at the given snapshot version into the writer, in chunks
+    /// of (at most) `chunk_size` values.
+    pub fn export_state_snapshot(
+        self: &Arc<Self>,
+        version: Version,
+        chunk_size: usize,
+        writer: &mut impl Write,
+    ) -> Result<StateSnapshotFileHeader> {
+        ensure!(chunk_size > 0, "Chunk size must be positive.");
+        let num_values = self.get_value_count(version)?;
+        ensure!(
+            num_values > 0,
+            "Empty state snapshot at version {}.",
+            version
+        );
+
+        let header = StateSnapshotFileHeader {
+            format_version: STATE_SNAPSHOT_FILE_FORMAT_VERSION,
+            version,
+            root_hash: self.get_root_hash(version)?,
+            num_values: num_values as u64,
+            num_chunks: num_values.div_ceil(chunk_size) as u64,
+        };
+        write_record(writer, &header)?;
+
+        for first_index in (0..num_values).step_by(chunk_size) {
+            let chunk = self.get_value_chunk_with_proof(version, first_index, chunk_size)?;
+            ensure!(
+                chunk.root_hash == header.root_hash,
+                "Root hash changed while exporting the state snapshot at version {}.",
+                version,
+            );
+            write_record(writer, &StateSnapshotChunk {
+                raw_values: chunk.raw_values,
+                proof: chunk.proof,
+            })?;
+        }
+        writer.flush()?;
+
+        Ok(header)
+    }
+
+    /// Restores a state snapshot written by `export_state_snapshot` into this (empty) store,
+    /// verifying every chunk against the root hash in the header.
+    pub fn import_state_snapshot(
+        self: &Arc<Self>,
+        reader: &mut impl Read,
+    ) -> Result<StateSnapshotFileHeader> {
+        ensure!(
+            self.state_merkle_db
+                .get_state_snapshot_version_before(Version::MAX)?
+                .is_none(),
+            "State snapshots can only be imported into an empty DB.",
+        );
+
+        let header: StateSnapshotFileHeader = read_record(reader)?;
+        ensure!(
+            header.format_version == STATE_SNAPSHOT_FILE_FORMAT_VERSION,
+            "Unsupported state snapshot file format version {}.",
+            header.format_version,
+        );
+
+        let mut receiver = self.get_snapshot_receiver(header.version, header.root_hash)?;
+        let mut num_values = 0;
+        for _ in 0..header.num_chunks {
+            let chunk: StateSnapshotChunk = read_record(reader)?;
+            num_values += chunk.raw_values.len() as u64;
+            receiver.add_chunk(chunk.raw_values, chunk.proof)?;
+        }
+        ensure!(
+            num_values == header.num_values,
+            "Expected {} state values, got {}.",
+            header.num_values,
+            num_values,
+        );
+        receiver.finish_box()?;
+
+        Ok(header)
+    }
+}
@@ -11,13 +11,17 @@ use crate::{
 };
 use anyhow::{anyhow, ensure, Result};
 use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
 use aptos_jellyfish_merkle::node_type::NodeKey;
 use aptos_logger::{info, trace};
 use aptos_metrics_core::TimerHelper;
 use aptos_schemadb::SchemaBatch;
 use aptos_scratchpad::SmtAncestors;
 use aptos_storage_interface::state_delta::StateDelta;
-use aptos_types::state_store::{state_storage_usage::StateStorageUsage, state_value::StateValue};
+use aptos_types::{
+    state_store::{state_storage_usage::StateStorageUsage, state_value::StateValue},
+    transaction::Version,
+};
 use std::sync::{mpsc::Receiver, Arc};
 
 pub struct StateMerkleBatch {
@@ -31,6 +35,7 @@ pub(crate) struct StateMerkleBatchCommitter {
     state_db: Arc<StateDb>,
     state_merkle_batch_receiver: Receiver<CommitMessage<StateMerkleBatch>>,
     smt_ancestors: SmtAncestors<StateValue>,
+    persisted_snapshot_version: Arc<Mutex<Option<Version>>>,
 }
 
 impl StateMerkleBatchCommitter {
@@ -38,11 +43,13 @@ impl StateMerkleBatchCommitter {
         state_db: Arc<StateDb>,
         state_merkle_batch_receiver: Receiver<CommitMessage<StateMerkleBatch>>,
         smt_ancestors: SmtAncestors<StateValue>,
+        persisted_snapshot_version: Arc<Mutex<Option<Version>>>,
     ) -> Self {
         Self {
             state_db,
             state_merkle_batch_receiver,
             smt_ancestors,
+            persisted_snapshot_version,
         }
     }
 
@@ -86,6 +93,7 @@ impl StateMerkleBatchCommitter {
                         "State snapshot committed."
                     );
                     LATEST_SNAPSHOT_VERSION.set(current_version as i64);
+                    *self.persisted_snapshot_version.lock() = Some(current_version);
                     self.state_db
                         .state_merkle_pruner
                         .maybe_set_pruner_target_db_version(current_version);
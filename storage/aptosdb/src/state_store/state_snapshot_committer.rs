@@ -13,10 +13,11 @@ use crate::{
     versioned_node_cache::VersionedNodeCache,
 };
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
+use aptos_infallible::Mutex;
 use aptos_logger::trace;
 use aptos_scratchpad::SmtAncestors;
 use aptos_storage_interface::{jmt_update_refs, jmt_updates, state_delta::StateDelta, Result};
-use aptos_types::state_store::state_value::StateValue;
+use aptos_types::{state_store::state_value::StateValue, transaction::Version};
 use rayon::prelude::*;
 use static_assertions::const_assert;
 use std::{
@@ -42,6 +43,7 @@ impl StateSnapshotCommitter {
         state_db: Arc<StateDb>,
         state_snapshot_commit_receiver: Receiver<CommitMessage<Arc<StateDelta>>>,
         smt_ancestors: SmtAncestors<StateValue>,
+        persisted_snapshot_version: Arc<Mutex<Option<Version>>>,
     ) -> Self {
         // Note: This is to ensure we cache nodes in memory from previous batches before they get committed to DB.
         const_assert!(
@@ -58,6 +60,7 @@ impl StateSnapshotCommitter {
                     arc_state_db,
                     state_merkle_batch_commit_receiver,
                     smt_ancestors,
+                    persisted_snapshot_version,
                 );
                 committer.run();
             })
@@ -12,10 +12,12 @@ use crate::{
 };
 use aptos_jellyfish_merkle::{
     node_type::{Node, NodeKey},
+    test_helper::plus_one,
     TreeReader,
 };
 use aptos_storage_interface::{
-    jmt_update_refs, jmt_updates, DbReader, DbWriter, StateSnapshotReceiver,
+    consistency_check::{StateInconsistency, StateInconsistencyKind},
+    jmt_update_refs, jmt_updates, AptosDbError, DbReader, DbWriter, StateSnapshotReceiver,
 };
 use aptos_temppath::TempPath;
 use aptos_types::{
@@ -71,6 +73,7 @@ fn put_value_set(
         .state_kv_db
         .commit(version, state_kv_metadata_batch, sharded_state_kv_batches)
         .unwrap();
+    state_store.invalidate_state_value_cache(version, &[&sharded_value_set]);
     root
 }
 
@@ -165,6 +168,141 @@ fn test_state_store_reader_writer() {
     verify_value_and_proof(store, key3, Some(&value3), 1, root);
 }
 
+#[test]
+fn test_get_state_values_by_version() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+
+    // Write values for a set of keys (spread across shards) over several versions
+    let keys: Vec<_> = (0..64)
+        .map(|i| StateKey::raw(format!("test_key{}", i).into_bytes()))
+        .collect();
+    let mut base_version = None;
+    for version in 0..4 {
+        let value_set = keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % 4 >= version as usize)
+            .map(|(i, key)| {
+                let value = format!("test_val{}_{}", i, version);
+                (key.clone(), StateValue::from(value.into_bytes()))
+            })
+            .collect();
+        put_value_set(store, value_set, version, base_version);
+        base_version = Some(version);
+    }
+
+    // Include keys that don't exist, and duplicate keys
+    let mut state_keys = keys.clone();
+    state_keys.push(StateKey::raw(String::from("missing_key").into_bytes()));
+    state_keys.push(keys[0].clone());
+    state_keys.push(keys[63].clone());
+
+    // Verify the batched reads are equivalent to the single key reads
+    for version in 0..4 {
+        let expected_values: Vec<_> = state_keys
+            .iter()
+            .map(|key| store.get_state_value_by_version(key, version).unwrap())
+            .collect();
+        let state_values = store
+            .get_state_values_by_version(&state_keys, version)
+            .unwrap();
+        assert_eq!(state_values, expected_values);
+    }
+    assert!(store
+        .get_state_values_by_version(&[], 3)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_state_value_cache() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    store.enable_state_value_cache(1 << 10).unwrap();
+    assert!(store.enable_state_value_cache(1 << 10).is_err());
+    let cache = store.state_value_cache.get().unwrap();
+
+    let key1 = StateKey::raw(String::from("test_key1").into_bytes());
+    let key2 = StateKey::raw(String::from("test_key2").into_bytes());
+    let value1 = StateValue::from(String::from("test_val1").into_bytes());
+    let value1_update = StateValue::from(String::from("test_val1_update").into_bytes());
+    let value2 = StateValue::from(String::from("test_val2").into_bytes());
+
+    // Values are cached once read at the latest version
+    put_value_set(
+        store,
+        vec![
+            (key1.clone(), value1.clone()),
+            (key2.clone(), value2.clone()),
+        ],
+        0, /* version */
+        None,
+    );
+    assert!(cache.get(&key1, 0).is_none());
+    verify_value_index_in_store(store, key1.clone(), Some(&value1), 0);
+    verify_value_index_in_store(store, key2.clone(), Some(&value2), 0);
+    assert_eq!(cache.get(&key1, 0), Some((0, value1.clone())));
+    assert_eq!(cache.get(&key2, 0), Some((0, value2.clone())));
+
+    // Committing a new value evicts the key, while other keys stay cached
+    put_value_set(
+        store,
+        vec![(key1.clone(), value1_update.clone())],
+        1, /* version */
+        Some(0),
+    );
+    assert!(cache.get(&key1, 1).is_none());
+    assert_eq!(cache.get(&key2, 1), Some((0, value2.clone())));
+
+    // Reads at old versions are served by the DB and not cached
+    verify_value_index_in_store(store, key1.clone(), Some(&value1), 0);
+    assert!(cache.get(&key1, 1).is_none());
+    verify_value_index_in_store(store, key1.clone(), Some(&value1_update), 1);
+    assert_eq!(cache.get(&key1, 1), Some((1, value1_update.clone())));
+    assert!(cache.get(&key1, 0).is_none());
+    verify_value_index_in_store(store, key1, Some(&value1), 0);
+}
+
+#[test]
+fn test_state_value_cache_byte_limit() {
+    // Find three keys of the same shard
+    let keys: Vec<_> = (0..)
+        .map(|i| StateKey::raw(format!("key{:04}", i).into_bytes()))
+        .filter(|key| key.get_shard_id() == 0)
+        .take(3)
+        .collect();
+    let value = StateValue::from(vec![0; 8]);
+    let large_value = StateValue::from(vec![0; 32]);
+    // Each key takes 7 bytes, so the shard fits two entries with the small value
+    let cache = StateValueCache::new(32, None);
+
+    cache.insert(&keys[0], 0, None, (0, value.clone()));
+    cache.insert(&keys[1], 0, None, (0, value.clone()));
+    assert!(cache.get(&keys[0], 0).is_some());
+    assert!(cache.get(&keys[1], 0).is_some());
+
+    // The least recently used key is evicted to make room
+    cache.insert(&keys[2], 0, None, (0, value.clone()));
+    assert!(cache.get(&keys[0], 0).is_none());
+    assert!(cache.get(&keys[1], 0).is_some());
+    assert!(cache.get(&keys[2], 0).is_some());
+
+    // Values that don't fit in the shard are not cached, and drop the stale value of the key
+    cache.insert(&keys[1], 0, None, (0, large_value));
+    assert!(cache.get(&keys[1], 0).is_none());
+    assert!(cache.get(&keys[2], 0).is_some());
+
+    // Invalidated keys free their bytes
+    cache.invalidate(1, std::iter::once(&keys[2]));
+    cache.insert(&keys[0], 1, Some(1), (1, value.clone()));
+    cache.insert(&keys[1], 1, Some(1), (1, value));
+    assert!(cache.get(&keys[0], 1).is_some());
+    assert!(cache.get(&keys[1], 1).is_some());
+}
+
 fn traverse_values(
     store: &StateStore,
     prefix: &StateKeyPrefix,
@@ -186,6 +324,87 @@ fn traverse_values(
     }
 }
 
+#[test]
+fn test_consistency_check() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+
+    // Insert a single key, so that every sample hits it
+    let key = StateKey::raw(String::from("test_key").into_bytes());
+    let value = StateValue::from(String::from("test_val").into_bytes());
+    let root = put_value_set(store, vec![(key.clone(), value.clone())], 0, None);
+
+    // Verify a sample past the last leaf wraps around to the first leaf
+    let last_key_hash = HashValue::new([u8::MAX; HashValue::LENGTH]);
+    assert!(key.hash() < last_key_hash);
+    assert_eq!(
+        consistency_checker::sample_state_key(store, 0, last_key_hash).unwrap(),
+        Some(key.clone())
+    );
+    assert_eq!(
+        consistency_checker::sample_state_key(store, 0, HashValue::zero()).unwrap(),
+        Some(key.clone())
+    );
+
+    // Verify the state kv db and the state merkle db are consistent
+    let report = consistency_checker::check_state_consistency(store, 0, 10).unwrap();
+    assert_eq!(report.version, 0);
+    assert_eq!(report.root_hash, root);
+    assert_eq!(report.num_keys_checked, 10);
+    assert!(report.is_consistent());
+
+    // Overwrite the value in the state kv db, so that it diverges from the merkle leaf
+    let corrupted_value = StateValue::from(String::from("corrupted_val").into_bytes());
+    store
+        .state_kv_db
+        .db_shard(key.get_shard_id())
+        .put::<StateValueSchema>(&(key.clone(), 0), &Some(corrupted_value.clone()))
+        .unwrap();
+
+    // Verify the divergence is detected
+    let report = consistency_checker::check_state_consistency(store, 0, 10).unwrap();
+    assert!(!report.is_consistent());
+    assert_eq!(report.inconsistencies.len(), 10);
+    assert_eq!(report.inconsistencies[0], StateInconsistency {
+        state_key: key,
+        kind: StateInconsistencyKind::ValueHashMismatch {
+            leaf_value_hash: value.hash(),
+            kv_value_hash: corrupted_value.hash(),
+        },
+    });
+}
+
+#[test]
+fn test_import_corrupted_state_snapshot() {
+    let tmp_dir1 = TempPath::new();
+    let db1 = AptosDB::new_for_test(&tmp_dir1);
+    let store1 = &db1.state_store;
+
+    let key = StateKey::raw(String::from("test_key").into_bytes());
+    let value = StateValue::from(String::from("test_val").into_bytes());
+    put_value_set(store1, vec![(key, value)], 0, None);
+
+    let mut file = Vec::new();
+    let header = store1.export_state_snapshot(0, 10, &mut file).unwrap();
+    assert_eq!(header.num_values, 1);
+    assert_eq!(header.num_chunks, 1);
+
+    // The source DB is not empty, so the snapshot can't be imported into it
+    assert!(store1.import_state_snapshot(&mut file.as_slice()).is_err());
+
+    // Flip a byte in the payload of the last record, so that its checksum doesn't match
+    let index = file.len() - HashValue::LENGTH - 1;
+    file[index] ^= 1;
+
+    let tmp_dir2 = TempPath::new();
+    let db2 = AptosDB::new_for_test(&tmp_dir2);
+    assert!(db2
+        .state_store
+        .import_state_snapshot(&mut file.as_slice())
+        .is_err());
+}
+
 #[test]
 fn test_get_values_by_key_prefix() {
     let tmp_dir = TempPath::new();
@@ -470,6 +689,90 @@ proptest! {
         );
     }
 
+    #[test]
+    fn test_restore_by_key_range(
+        (input, batch_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
+            .prop_flat_map(|input| {
+                let len = input.len();
+                (Just(input), 1..len*2)
+            })
+    ) {
+        let tmp_dir1 = TempPath::new();
+        let db1 = AptosDB::new_for_test(&tmp_dir1);
+        let store1 = &db1.state_store;
+        init_store(store1, input.clone().into_iter());
+
+        let version = (input.len() - 1) as Version;
+        let expected_root_hash = store1.get_root_hash(version).unwrap();
+
+        let tmp_dir2 = TempPath::new();
+        let db2 = AptosDB::new_for_test(&tmp_dir2);
+        let store2 = &db2.state_store;
+
+        // Resume each chunk right after the last key of the previous chunk
+        let mut restore = store2.get_snapshot_receiver(version, expected_root_hash).unwrap();
+        let mut start_key_hash = HashValue::zero();
+        let mut current_idx = 0;
+        while current_idx < input.len() {
+            let chunk = store1
+                .get_value_chunk_by_key_range_with_proof(version, start_key_hash, batch_size)
+                .unwrap();
+            prop_assert_eq!(
+                &chunk,
+                &store1.get_value_chunk_with_proof(version, current_idx, batch_size).unwrap()
+            );
+            start_key_hash = plus_one(chunk.last_key);
+            current_idx = chunk.last_index as usize + 1;
+            restore.add_chunk(chunk.raw_values, chunk.proof).unwrap();
+        }
+
+        // Resuming after the last key is a typed error, as there is nothing left to return
+        let result =
+            store1.get_value_chunk_by_key_range_with_proof(version, start_key_hash, batch_size);
+        prop_assert!(matches!(result, Err(AptosDbError::RangeStartsPastEnd(_))));
+
+        restore.finish_box().unwrap();
+        let actual_root_hash = store2.get_root_hash(version).unwrap();
+        prop_assert_eq!(actual_root_hash, expected_root_hash);
+    }
+
+    #[test]
+    fn test_export_import_state_snapshot(
+        (input, chunk_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 1..1000)
+            .prop_flat_map(|input| {
+                let len = input.len();
+                (Just(input), 1..len*2)
+            })
+    ) {
+        let tmp_dir1 = TempPath::new();
+        let db1 = AptosDB::new_for_test(&tmp_dir1);
+        let store1 = &db1.state_store;
+        init_store(store1, input.clone().into_iter());
+
+        let version = (input.len() - 1) as Version;
+        let mut file = Vec::new();
+        let header = store1.export_state_snapshot(version, chunk_size, &mut file).unwrap();
+        prop_assert_eq!(header.num_values, input.len() as u64);
+
+        let tmp_dir2 = TempPath::new();
+        let db2 = AptosDB::new_for_test(&tmp_dir2);
+        let store2 = &db2.state_store;
+        prop_assert_eq!(store2.import_state_snapshot(&mut file.as_slice()).unwrap(), header.clone());
+
+        prop_assert_eq!(store2.get_root_hash(version).unwrap(), header.root_hash);
+        let actual_values = store2
+            .get_state_key_and_value_iter(version, HashValue::zero())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let expected_values = store1
+            .get_state_key_and_value_iter(version, HashValue::zero())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        prop_assert_eq!(actual_values, expected_values);
+    }
+
     #[test]
     fn test_get_rightmost_leaf(
         (input, batch1_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
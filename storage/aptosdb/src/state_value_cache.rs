@@ -0,0 +1,137 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{common::NUM_STATE_SHARDS, metrics::STATE_VALUE_CACHE};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use lru::LruCache;
+use std::fmt;
+
+/// Caches the latest committed value of hot state keys, together with the version it was
+/// written at. Every shard tracks the version of the last commit it has seen, so a value read
+/// from the DB is only cached if no commit happened while it was being read.
+pub(crate) struct StateValueCache {
+    shards: [Mutex<StateValueCacheShard>; NUM_STATE_SHARDS],
+}
+
+struct StateValueCacheShard {
+    values: LruCache<StateKey, (Version, StateValue)>,
+    committed_version: Option<Version>,
+    /// The total size of the cached keys and values, kept within `max_bytes`.
+    num_bytes: usize,
+    max_bytes: usize,
+}
+
+impl StateValueCacheShard {
+    fn entry_size(state_key: &StateKey, value: &StateValue) -> usize {
+        state_key.size() + value.size()
+    }
+
+    fn put(&mut self, state_key: StateKey, value: (Version, StateValue)) {
+        let size = Self::entry_size(&state_key, &value.1);
+        if size > self.max_bytes {
+            self.pop(&state_key);
+            return;
+        }
+        self.num_bytes += size;
+        if let Some((_, old_value)) = self.values.put(state_key.clone(), value) {
+            self.num_bytes -= Self::entry_size(&state_key, &old_value);
+        }
+        while self.num_bytes > self.max_bytes {
+            let (evicted_key, (_, evicted_value)) = self
+                .values
+                .pop_lru()
+                .expect("A non-empty cache must have a LRU entry.");
+            self.num_bytes -= Self::entry_size(&evicted_key, &evicted_value);
+        }
+    }
+
+    fn pop(&mut self, state_key: &StateKey) {
+        if let Some((_, value)) = self.values.pop(state_key) {
+            self.num_bytes -= Self::entry_size(state_key, &value);
+        }
+    }
+}
+
+impl fmt::Debug for StateValueCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "StateValueCache with {NUM_STATE_SHARDS} shards.")
+    }
+}
+
+impl StateValueCache {
+    pub fn new(max_bytes_per_shard: usize, committed_version: Option<Version>) -> Self {
+        Self {
+            // `arr!()` doesn't allow a const in place of the integer literal
+            shards: arr_macro::arr![Mutex::new(StateValueCacheShard {
+                values: LruCache::unbounded(),
+                committed_version,
+                num_bytes: 0,
+                max_bytes: max_bytes_per_shard,
+            }); 16],
+        }
+    }
+
+    fn shard(&self, state_key: &StateKey) -> &Mutex<StateValueCacheShard> {
+        &self.shards[state_key.get_shard_id() as usize]
+    }
+
+    /// Returns the version of the last commit seen by the shard of the given key. It has to be
+    /// passed back to `insert()` once the value is read from the DB.
+    pub fn committed_version(&self, state_key: &StateKey) -> Option<Version> {
+        self.shard(state_key).lock().committed_version
+    }
+
+    /// Returns the cached value of the key if it is the latest value as of the given version.
+    pub fn get(&self, state_key: &StateKey, version: Version) -> Option<(Version, StateValue)> {
+        let ret = self
+            .shard(state_key)
+            .lock()
+            .values
+            .get(state_key)
+            .filter(|(value_version, _)| *value_version <= version)
+            .cloned();
+        STATE_VALUE_CACHE
+            .with_label_values(&[if ret.is_some() { "hit" } else { "miss" }])
+            .inc();
+        ret
+    }
+
+    /// Caches a value read from the DB at `version`. The value is dropped if it might not be the
+    /// latest one, i.e. if it was read at a version older than the last commit, or if a commit
+    /// happened since `committed_version` was taken.
+    pub fn insert(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+        committed_version: Option<Version>,
+        value: (Version, StateValue),
+    ) {
+        if committed_version.map_or(false, |committed| version < committed) {
+            return;
+        }
+        let mut shard = self.shard(state_key).lock();
+        if shard.committed_version == committed_version {
+            shard.put(state_key.clone(), value);
+        }
+    }
+
+    /// Evicts the given keys after their new values are committed at or before `version`. Must
+    /// be called after the values are written to the DB.
+    pub fn invalidate<'a>(&self, version: Version, state_keys: impl Iterator<Item = &'a StateKey>) {
+        let mut keys_by_shard = vec![Vec::new(); NUM_STATE_SHARDS];
+        for state_key in state_keys {
+            keys_by_shard[state_key.get_shard_id() as usize].push(state_key);
+        }
+        for (shard, keys) in self.shards.iter().zip(keys_by_shard) {
+            let mut shard = shard.lock();
+            for state_key in keys {
+                shard.pop(state_key);
+            }
+            shard.committed_version = shard.committed_version.max(Some(version));
+        }
+    }
+}
@@ -8,19 +8,21 @@
 use crate::{
     metadata::{MetadataKey, MetadataValue},
     schema::{
-        column_families, indexer_metadata::IndexerMetadataSchema, table_info::TableInfoSchema,
+        column_families, event_by_type::EventByTypeSchema, indexer_metadata::IndexerMetadataSchema,
+        table_info::TableInfoSchema,
     },
 };
 use aptos_config::config::RocksdbConfig;
 use aptos_logger::info;
 use aptos_rocksdb_options::gen_rocksdb_options;
-use aptos_schemadb::{SchemaBatch, DB};
+use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_storage_interface::{
-    db_other_bail as bail, state_view::DbStateView, AptosDbError, DbReader, Result,
+    db_ensure, db_other_bail as bail, state_view::DbStateView, AptosDbError, DbReader, Result,
 };
 use aptos_types::{
     access_path::Path,
     account_address::AccountAddress,
+    contract_event::ContractEvent,
     state_store::{
         state_key::{StateKey, StateKeyInner},
         table::{TableHandle, TableInfo},
@@ -62,6 +64,8 @@ pub struct IndexerAsyncV2 {
     // is obscure and will be stored as bytes with parent table's handle, once parent table's parsed with instructions,
     // child table handle will be parsed accordingly.
     pending_on: DashMap<TableHandle, DashSet<Bytes>>,
+    // First version from which events are indexed by type
+    event_by_type_start_version: Version,
 }
 
 impl IndexerAsyncV2 {
@@ -84,10 +88,25 @@ impl IndexerAsyncV2 {
             .get::<IndexerMetadataSchema>(&MetadataKey::LatestVersion)?
             .map_or(0, |v| v.expect_version());
 
+        // Events are only indexed by type from the version the index was introduced at, since
+        // earlier versions were indexed before it existed.
+        let event_by_type_start_version =
+            match db.get::<IndexerMetadataSchema>(&MetadataKey::EventByTypeStartVersion)? {
+                Some(version) => version.expect_version(),
+                None => {
+                    db.put::<IndexerMetadataSchema>(
+                        &MetadataKey::EventByTypeStartVersion,
+                        &MetadataValue::Version(next_version),
+                    )?;
+                    next_version
+                },
+            };
+
         Ok(Self {
             db,
             next_version: AtomicU64::new(next_version),
             pending_on,
+            event_by_type_start_version,
         })
     }
 
@@ -96,6 +115,7 @@ impl IndexerAsyncV2 {
         db_reader: Arc<dyn DbReader>,
         first_version: Version,
         write_sets: &[&WriteSet],
+        events: &[&[ContractEvent]],
         end_early_if_pending_on_empty: bool,
     ) -> Result<()> {
         let last_version = first_version + write_sets.len() as Version;
@@ -109,19 +129,28 @@ impl IndexerAsyncV2 {
             &annotator,
             first_version,
             write_sets,
+            events,
             end_early_if_pending_on_empty,
         )
     }
 
     /// Index write sets with the move annotator to parse obscure table handle and key value types
     /// After the current batch's parsed, write the mapping to the rocksdb, also update the next version to be processed
+    /// The events of the transactions are indexed by type in the same write
     pub fn index_with_annotator<R: ModuleResolver>(
         &self,
         annotator: &MoveValueAnnotator<R>,
         first_version: Version,
         write_sets: &[&WriteSet],
+        events: &[&[ContractEvent]],
         end_early_if_pending_on_empty: bool,
     ) -> Result<()> {
+        db_ensure!(
+            write_sets.len() == events.len(),
+            "Indexer expects write sets and events of the same transactions. Got {} write sets and {} event lists.",
+            write_sets.len(),
+            events.len(),
+        );
         let end_version = first_version + write_sets.len() as Version;
         let mut table_info_parser = TableInfoParser::new(self, annotator, &self.pending_on);
         'outer_loop: for write_set in write_sets {
@@ -146,6 +175,15 @@ impl IndexerAsyncV2 {
                 bail!("{}", err);
             },
         };
+        // Events don't depend on the table info, so they are all indexed even when ending early
+        for (version, txn_events) in (first_version..).zip(events) {
+            for (index, event) in txn_events.iter().enumerate() {
+                batch.put::<EventByTypeSchema>(
+                    &(event.type_tag().clone(), version, index as u64),
+                    &(),
+                )?;
+            }
+        }
         self.db.write_schemas(batch)?;
         Ok(())
     }
@@ -227,6 +265,61 @@ impl IndexerAsyncV2 {
     pub fn is_indexer_async_v2_pending_on_empty(&self) -> bool {
         self.pending_on.is_empty()
     }
+
+    /// Returns the first version from which events are indexed by type
+    pub fn event_by_type_start_version(&self) -> Version {
+        self.event_by_type_start_version
+    }
+
+    /// Returns the (version, index) of up to `limit` events of type `type_tag`, emitted by
+    /// transactions in `[start_version, end_version)`, in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        end_version: Version,
+        limit: u64,
+    ) -> Result<Vec<(Version, u64)>> {
+        db_ensure!(
+            start_version >= self.event_by_type_start_version,
+            "Events are indexed by type starting from version {}, requested from version {}.",
+            self.event_by_type_start_version,
+            start_version,
+        );
+
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(type_tag.clone(), start_version, 0))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((event_type_tag, version, index), ()) = res?;
+            if &event_type_tag != type_tag || version >= end_version {
+                break;
+            }
+            result.push((version, index));
+        }
+        Ok(result)
+    }
+
+    /// Removes the type index entries of the events emitted by transactions starting at
+    /// `first_version`, called when those events are pruned from the ledger.
+    pub fn prune_events_by_type(
+        &self,
+        first_version: Version,
+        events: &[&[ContractEvent]],
+    ) -> Result<()> {
+        let batch = SchemaBatch::new();
+        for (version, txn_events) in (first_version..).zip(events) {
+            for (index, event) in txn_events.iter().enumerate() {
+                batch.delete::<EventByTypeSchema>(&(
+                    event.type_tag().clone(),
+                    version,
+                    index as u64,
+                ))?;
+            }
+        }
+        self.db.write_schemas(batch)
+    }
 }
 
 struct TableInfoParser<'a, R> {
@@ -11,19 +11,21 @@ use crate::{
     db::INDEX_DB_NAME,
     metadata::{MetadataKey, MetadataValue},
     schema::{
-        column_families, indexer_metadata::IndexerMetadataSchema, table_info::TableInfoSchema,
+        column_families, event_by_type::EventByTypeSchema, indexer_metadata::IndexerMetadataSchema,
+        table_info::TableInfoSchema,
     },
 };
 use aptos_config::config::RocksdbConfig;
 use aptos_logger::warn;
 use aptos_rocksdb_options::gen_rocksdb_options;
-use aptos_schemadb::{SchemaBatch, DB};
+use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_storage_interface::{
     db_ensure, db_other_bail, state_view::DbStateView, AptosDbError, DbReader, Result,
 };
 use aptos_types::{
     access_path::Path,
     account_address::AccountAddress,
+    contract_event::ContractEvent,
     state_store::{
         state_key::{StateKey, StateKeyInner},
         table::{TableHandle, TableInfo},
@@ -49,6 +51,7 @@ use std::{
 pub struct Indexer {
     db: DB,
     next_version: AtomicVersion,
+    event_by_type_start_version: Version,
 }
 
 impl Indexer {
@@ -69,9 +72,24 @@ impl Indexer {
             .get::<IndexerMetadataSchema>(&MetadataKey::LatestVersion)?
             .map_or(0, |v| v.expect_version());
 
+        // Events are only indexed by type from the version the index was introduced at, since
+        // earlier versions were indexed before it existed.
+        let event_by_type_start_version =
+            match db.get::<IndexerMetadataSchema>(&MetadataKey::EventByTypeStartVersion)? {
+                Some(version) => version.expect_version(),
+                None => {
+                    db.put::<IndexerMetadataSchema>(
+                        &MetadataKey::EventByTypeStartVersion,
+                        &MetadataValue::Version(next_version),
+                    )?;
+                    next_version
+                },
+            };
+
         Ok(Self {
             db,
             next_version: AtomicVersion::new(next_version),
+            event_by_type_start_version,
         })
     }
 
@@ -80,6 +98,7 @@ impl Indexer {
         db_reader: Arc<dyn DbReader>,
         first_version: Version,
         write_sets: &[&WriteSet],
+        events: &[&[ContractEvent]],
     ) -> Result<()> {
         let last_version = first_version + write_sets.len() as Version;
         let state_view = DbStateView {
@@ -88,7 +107,7 @@ impl Indexer {
         };
         let resolver = state_view.as_move_resolver();
         let annotator = MoveValueAnnotator::new(&resolver);
-        self.index_with_annotator(&annotator, first_version, write_sets)
+        self.index_with_annotator(&annotator, first_version, write_sets, events)
     }
 
     pub fn index_with_annotator<R: ModuleResolver>(
@@ -96,7 +115,14 @@ impl Indexer {
         annotator: &MoveValueAnnotator<R>,
         first_version: Version,
         write_sets: &[&WriteSet],
+        events: &[&[ContractEvent]],
     ) -> Result<()> {
+        db_ensure!(
+            write_sets.len() == events.len(),
+            "Indexer expects write sets and events of the same transactions. Got {} write sets and {} event lists.",
+            write_sets.len(),
+            events.len(),
+        );
         let next_version = self.next_version();
         db_ensure!(
             first_version <= next_version,
@@ -136,6 +162,14 @@ impl Indexer {
                 db_other_bail!("Failed to parse table info: {:?}", err);
             },
         };
+        for (version, txn_events) in (first_version..).zip(events) {
+            for (index, event) in txn_events.iter().enumerate() {
+                batch.put::<EventByTypeSchema>(
+                    &(event.type_tag().clone(), version, index as u64),
+                    &(),
+                )?;
+            }
+        }
         batch.put::<IndexerMetadataSchema>(
             &MetadataKey::LatestVersion,
             &MetadataValue::Version(end_version - 1),
@@ -150,11 +184,69 @@ impl Indexer {
         self.next_version.load(Ordering::Relaxed)
     }
 
+    /// Returns the first version from which events are indexed by type
+    pub fn event_by_type_start_version(&self) -> Version {
+        self.event_by_type_start_version
+    }
+
     pub fn get_table_info(&self, handle: TableHandle) -> Result<Option<TableInfo>> {
         self.db.get::<TableInfoSchema>(&handle)
     }
+
+    /// Returns the (version, index) of up to `limit` events of type `type_tag`, emitted by
+    /// transactions in `[start_version, end_version)`, in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        end_version: Version,
+        limit: u64,
+    ) -> Result<Vec<(Version, u64)>> {
+        db_ensure!(
+            start_version >= self.event_by_type_start_version,
+            "Events are indexed by type starting from version {}, requested from version {}.",
+            self.event_by_type_start_version,
+            start_version,
+        );
+
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(type_tag.clone(), start_version, 0))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((event_type_tag, version, index), ()) = res?;
+            if &event_type_tag != type_tag || version >= end_version {
+                break;
+            }
+            result.push((version, index));
+        }
+        Ok(result)
+    }
+
+    /// Removes the type index entries of the events emitted by transactions starting at
+    /// `first_version`, called when those events are pruned from the ledger.
+    pub fn prune_events_by_type(
+        &self,
+        first_version: Version,
+        events: &[&[ContractEvent]],
+    ) -> Result<()> {
+        let batch = SchemaBatch::new();
+        for (version, txn_events) in (first_version..).zip(events) {
+            for (index, event) in txn_events.iter().enumerate() {
+                batch.delete::<EventByTypeSchema>(&(
+                    event.type_tag().clone(),
+                    version,
+                    index as u64,
+                ))?;
+            }
+        }
+        self.db.write_schemas(batch)
+    }
 }
 
+#[cfg(test)]
+mod tests;
+
 struct TableInfoParser<'a, R> {
     indexer: &'a Indexer,
     annotator: &'a MoveValueAnnotator<'a, R>,
@@ -22,4 +22,7 @@ impl MetadataValue {
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 pub(crate) enum MetadataKey {
     LatestVersion,
+    /// The first version from which events are indexed by type, i.e. the next version of the
+    /// indexer when the index was introduced.
+    EventByTypeStartVersion,
 }
@@ -0,0 +1,155 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_storage_interface::mock::MockDbReaderWriter;
+use aptos_temppath::TempPath;
+
+/// Indexes the events of transactions starting at version 0 (with empty write sets)
+fn index_events(indexer: &Indexer, events: &[Vec<ContractEvent>]) {
+    let write_sets = vec![WriteSet::default(); events.len()];
+    let write_sets_ref: Vec<_> = write_sets.iter().collect();
+    let events_ref: Vec<_> = events.iter().map(Vec::as_slice).collect();
+    indexer
+        .index(
+            Arc::new(MockDbReaderWriter),
+            0,
+            &write_sets_ref,
+            &events_ref,
+        )
+        .unwrap();
+}
+
+fn event(type_tag: TypeTag) -> ContractEvent {
+    ContractEvent::new_v2(type_tag, vec![])
+}
+
+/// Returns the events of 5 transactions, where the u8 events (at (0, 0), (2, 0), (2, 1) and
+/// (4, 1)) are directly followed by the u64 events in the index.
+fn create_events() -> Vec<Vec<ContractEvent>> {
+    vec![
+        vec![event(TypeTag::U8), event(TypeTag::U64)],
+        vec![event(TypeTag::U64)],
+        vec![event(TypeTag::U8), event(TypeTag::U8)],
+        vec![],
+        vec![event(TypeTag::U64), event(TypeTag::U8)],
+    ]
+}
+
+#[test]
+fn test_lookup_events_by_type() {
+    let tmp_dir = TempPath::new();
+    let indexer = Indexer::open(&tmp_dir, RocksdbConfig::default()).unwrap();
+    index_events(&indexer, &create_events());
+
+    // The lookup stops at the end of the type, even though the u64 events follow
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U8, 0, 5, 100)
+            .unwrap(),
+        vec![(0, 0), (2, 0), (2, 1), (4, 1)]
+    );
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U64, 0, 5, 100)
+            .unwrap(),
+        vec![(0, 1), (1, 0), (4, 0)]
+    );
+    assert!(indexer
+        .lookup_events_by_type(&TypeTag::Bool, 0, 5, 100)
+        .unwrap()
+        .is_empty());
+
+    // The lookup seeks to the start version
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U8, 1, 5, 100)
+            .unwrap(),
+        vec![(2, 0), (2, 1), (4, 1)]
+    );
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U8, 3, 5, 100)
+            .unwrap(),
+        vec![(4, 1)]
+    );
+
+    // The lookup stops at the limit and before the end version
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U8, 0, 5, 2)
+            .unwrap(),
+        vec![(0, 0), (2, 0)]
+    );
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U8, 0, 4, 100)
+            .unwrap(),
+        vec![(0, 0), (2, 0), (2, 1)]
+    );
+    assert!(indexer
+        .lookup_events_by_type(&TypeTag::U8, 0, 5, 0)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_prune_events_by_type() {
+    let tmp_dir = TempPath::new();
+    let indexer = Indexer::open(&tmp_dir, RocksdbConfig::default()).unwrap();
+    let events = create_events();
+    index_events(&indexer, &events);
+
+    // Prune the events of the first 3 transactions
+    let events_ref: Vec<_> = events[..3].iter().map(Vec::as_slice).collect();
+    indexer.prune_events_by_type(0, &events_ref).unwrap();
+
+    // Verify only the entries of the later transactions are left
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U8, 0, 5, 100)
+            .unwrap(),
+        vec![(4, 1)]
+    );
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U64, 0, 5, 100)
+            .unwrap(),
+        vec![(4, 0)]
+    );
+}
+
+#[test]
+fn test_event_by_type_start_version() {
+    let tmp_dir = TempPath::new();
+    let indexer = Indexer::open(&tmp_dir, RocksdbConfig::default()).unwrap();
+    assert_eq!(indexer.event_by_type_start_version(), 0);
+    index_events(&indexer, &create_events());
+
+    // Reopen the indexer as if it was created before events were indexed by type
+    let batch = SchemaBatch::new();
+    batch
+        .delete::<IndexerMetadataSchema>(&MetadataKey::EventByTypeStartVersion)
+        .unwrap();
+    indexer.db.write_schemas(batch).unwrap();
+    drop(indexer);
+    let indexer = Indexer::open(&tmp_dir, RocksdbConfig::default()).unwrap();
+    let start_version = indexer.next_version();
+    assert_eq!(indexer.event_by_type_start_version(), start_version);
+
+    // Verify lookups below the start version fail, while the ones above it succeed
+    assert!(indexer
+        .lookup_events_by_type(&TypeTag::U8, start_version - 1, 5, 100)
+        .is_err());
+    assert_eq!(
+        indexer
+            .lookup_events_by_type(&TypeTag::U8, start_version, 5, 100)
+            .unwrap(),
+        vec![(4, 1)]
+    );
+
+    // Verify the start version is kept across restarts
+    drop(indexer);
+    let indexer = Indexer::open(&tmp_dir, RocksdbConfig::default()).unwrap();
+    assert_eq!(indexer.event_by_type_start_version(), start_version);
+}
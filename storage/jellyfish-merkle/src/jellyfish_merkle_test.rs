@@ -7,8 +7,9 @@ use crate::{
     node_type::NodeType,
     test_helper::{
         arb_existent_kvs_and_nonexistent_keys, arb_kv_pair_with_distinct_last_nibble,
-        arb_tree_with_index, gen_value, test_get_leaf_count, test_get_range_proof,
-        test_get_with_proof, test_get_with_proof_with_distinct_last_nibble, ValueBlob,
+        arb_tree_with_index, gen_value, test_get_leaf_count, test_get_leaf_index,
+        test_get_range_proof, test_get_with_proof, test_get_with_proof_with_distinct_last_nibble,
+        ValueBlob,
     },
 };
 use aptos_crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
@@ -805,4 +806,9 @@ proptest! {
     fn proptest_get_leaf_count(keys in hash_set(any::<HashValue>(), 3..2000)) {
         test_get_leaf_count(keys)
     }
+
+    #[test]
+    fn proptest_get_leaf_index(keys in hash_set(any::<HashValue>(), 1..1000)) {
+        test_get_leaf_index(keys)
+    }
 }
@@ -838,6 +838,46 @@ where
         self.get_root_node(version).map(|n| n.leaf_count())
     }
 
+    /// Returns the number of leaves whose keys are smaller than `key` at `version`, i.e. the
+    /// index of the smallest leaf whose key is greater or equal to `key`. If all the leaves have
+    /// smaller keys, the leaf count of the tree is returned.
+    pub fn get_leaf_index(&self, key: HashValue, version: Version) -> Result<usize> {
+        let mut leaves_skipped = 0;
+        let mut next_node_key = NodeKey::new_empty_path(version);
+        let nibble_path = NibblePath::new_even(key.to_vec());
+        let mut nibble_iter = nibble_path.nibbles();
+
+        for _ in 0..=ROOT_NIBBLE_HEIGHT {
+            match self.reader.get_node(&next_node_key)? {
+                Node::Internal(internal_node) => {
+                    let queried_child_index = nibble_iter
+                        .next()
+                        .ok_or_else(|| AptosDbError::Other("ran out of nibbles".to_string()))?;
+                    leaves_skipped += internal_node
+                        .children_sorted()
+                        .take_while(|(nibble, _)| **nibble < queried_child_index)
+                        .map(|(_, child)| child.leaf_count())
+                        .sum::<usize>();
+                    match internal_node.child(queried_child_index) {
+                        Some(child) => {
+                            next_node_key = next_node_key
+                                .gen_child_node_key(child.version, queried_child_index);
+                        },
+                        None => return Ok(leaves_skipped),
+                    }
+                },
+                Node::Leaf(leaf_node) => {
+                    if leaf_node.account_key() < key {
+                        leaves_skipped += 1;
+                    }
+                    return Ok(leaves_skipped);
+                },
+                Node::Null => return Ok(leaves_skipped),
+            }
+        }
+        db_other_bail!("Jellyfish Merkle tree has cyclic graph inside.");
+    }
+
     pub fn get_all_nodes_referenced(&self, version: Version) -> Result<Vec<NodeKey>> {
         let mut out_keys = vec![];
         self.get_all_nodes_referenced_impl(NodeKey::new_empty_path(version), &mut out_keys)?;
@@ -20,7 +20,7 @@ use proptest::{
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ops::Bound,
 };
 
@@ -404,3 +404,27 @@ pub fn test_get_leaf_count(keys: HashSet<HashValue>) {
         keys.len() - (idx2 - idx1)
     )
 }
+
+pub fn test_get_leaf_index(keys: HashSet<HashValue>) {
+    let kvs = keys.iter().map(|k| (*k, gen_value())).collect();
+    let (db, version) = init_mock_db(&kvs);
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let sorted_keys = keys.into_iter().collect::<BTreeSet<_>>();
+    assert_eq!(tree.get_leaf_index(HashValue::zero(), version).unwrap(), 0);
+    for (index, key) in sorted_keys.iter().enumerate() {
+        // Existing keys are found at their position in the sorted key list
+        assert_eq!(tree.get_leaf_index(*key, version).unwrap(), index);
+
+        // Missing keys are found at the position of the next larger leaf
+        let mut missing_key = key.to_vec();
+        missing_key[HashValue::LENGTH - 1] |= 1;
+        let missing_key = HashValue::from_slice(&missing_key).unwrap();
+        if !sorted_keys.contains(&missing_key) {
+            assert_eq!(
+                tree.get_leaf_index(missing_key, version).unwrap(),
+                index + 1
+            );
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+
+/// A snapshot of the commit progress recorded by each of the underlying databases.
+///
+/// The overall commit progress is written last on every commit and is the source of truth; the
+/// individual databases are allowed to be ahead of it (they get truncated back on startup), but
+/// never behind, except for the state merkle db which is committed asynchronously.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CommitProgressReport {
+    pub overall_commit_progress: Option<Version>,
+    pub ledger_commit_progress: Option<Version>,
+    pub state_kv_commit_progress: Option<Version>,
+    pub state_kv_shard_commit_progress: Vec<Option<Version>>,
+    pub state_merkle_commit_progress: Option<Version>,
+    pub state_merkle_shard_commit_progress: Vec<Option<Version>>,
+    /// Truncations performed when the databases were synced on startup.
+    pub startup_truncations: Vec<TruncationInfo>,
+}
+
+impl CommitProgressReport {
+    /// Returns true if any database that must be synced with the overall commit progress is
+    /// behind it. The state merkle db is excluded since it legitimately lags behind.
+    pub fn is_diverged(&self) -> bool {
+        let overall = match self.overall_commit_progress {
+            Some(version) => version,
+            None => return false,
+        };
+        let is_behind = |progress: &Option<Version>| progress.map_or(true, |v| v < overall);
+
+        is_behind(&self.ledger_commit_progress)
+            || is_behind(&self.state_kv_commit_progress)
+            || self.state_kv_shard_commit_progress.iter().any(is_behind)
+    }
+
+    /// Returns how far the state merkle db lags behind the overall commit progress.
+    pub fn state_merkle_lag(&self) -> Option<u64> {
+        let overall = self.overall_commit_progress?;
+        Some(overall.saturating_sub(self.state_merkle_commit_progress.unwrap_or(0)))
+    }
+}
+
+/// A truncation of a database back to the overall commit progress.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TruncationInfo {
+    pub db_name: String,
+    /// The commit progress of the database before the truncation.
+    pub from_version: Version,
+    /// The version the database was truncated to (inclusive).
+    pub to_version: Version,
+}
+
+impl TruncationInfo {
+    pub fn new(db_name: &str, from_version: Version, to_version: Version) -> Self {
+        Self {
+            db_name: db_name.to_string(),
+            from_version,
+            to_version,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_diverged() {
+        let mut report = CommitProgressReport {
+            overall_commit_progress: Some(10),
+            ledger_commit_progress: Some(10),
+            state_kv_commit_progress: Some(12),
+            state_kv_shard_commit_progress: vec![Some(10), Some(12)],
+            state_merkle_commit_progress: Some(5),
+            state_merkle_shard_commit_progress: vec![Some(5), Some(5)],
+            startup_truncations: vec![],
+        };
+        assert!(!report.is_diverged());
+        assert_eq!(report.state_merkle_lag(), Some(5));
+
+        report.state_kv_shard_commit_progress[1] = None;
+        assert!(report.is_diverged());
+
+        assert!(!CommitProgressReport::default().is_diverged());
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_types::{state_store::state_key::StateKey, transaction::Version};
+use serde::{Deserialize, Serialize};
+
+/// The result of checking a random sample of state keys against a single state snapshot.
+///
+/// For every sampled key, the JMT proof is recomputed against the root hash of the snapshot,
+/// and the value stored in the state kv db is cross-checked against the merkle leaf.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ConsistencyCheckReport {
+    /// The version of the state snapshot that was checked.
+    pub version: Version,
+    /// The root hash of the state merkle tree at the version.
+    pub root_hash: HashValue,
+    /// The state checkpoint hash committed in the ledger, if it doesn't match the root hash.
+    pub mismatched_ledger_root_hash: Option<HashValue>,
+    pub num_keys_checked: usize,
+    pub inconsistencies: Vec<StateInconsistency>,
+}
+
+impl ConsistencyCheckReport {
+    pub fn new(version: Version, root_hash: HashValue) -> Self {
+        Self {
+            version,
+            root_hash,
+            mismatched_ledger_root_hash: None,
+            num_keys_checked: 0,
+            inconsistencies: vec![],
+        }
+    }
+
+    /// Returns true if no inconsistency was found.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatched_ledger_root_hash.is_none() && self.inconsistencies.is_empty()
+    }
+}
+
+/// An inconsistency found for a single state key.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateInconsistency {
+    pub state_key: StateKey,
+    pub kind: StateInconsistencyKind,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum StateInconsistencyKind {
+    /// The JMT proof of the leaf doesn't verify against the root hash.
+    InvalidProof,
+    /// The state kv db has no value for the key at the leaf version.
+    MissingValue { leaf_version: Version },
+    /// The latest value in the state kv db was written at a different version than the leaf.
+    VersionMismatch {
+        leaf_version: Version,
+        kv_version: Version,
+    },
+    /// The hash of the value in the state kv db doesn't match the leaf.
+    ValueHashMismatch {
+        leaf_value_hash: HashValue,
+        kv_value_hash: HashValue,
+    },
+}
+
+impl StateInconsistencyKind {
+    /// Returns a short label for the kind of inconsistency (e.g., for metrics).
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            StateInconsistencyKind::InvalidProof => "invalid_proof",
+            StateInconsistencyKind::MissingValue { .. } => "missing_value",
+            StateInconsistencyKind::VersionMismatch { .. } => "version_mismatch",
+            StateInconsistencyKind::ValueHashMismatch { .. } => "value_hash_mismatch",
+        }
+    }
+}
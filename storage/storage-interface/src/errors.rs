@@ -18,6 +18,9 @@ pub enum AptosDbError {
     TooManyRequested(u64, u64),
     #[error("Missing state root node at version {0}, probably pruned.")]
     MissingRootError(u64),
+    /// A requested range starts past the last item, so there is nothing to return.
+    #[error("Requested range starts past the end: {0}")]
+    RangeStartsPastEnd(String),
     /// Other non-classified error.
     #[error("AptosDB Other Error: {0}")]
     Other(String),
@@ -2,7 +2,10 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::cached_state_view::ShardedStateCache;
+use crate::{
+    cached_state_view::ShardedStateCache, commit_progress::CommitProgressReport,
+    consistency_check::ConsistencyCheckReport,
+};
 use anyhow::anyhow;
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_types::{
@@ -32,9 +35,11 @@ use aptos_types::{
     transaction::{
         AccountTransactionsWithProof, Transaction, TransactionInfo, TransactionListWithProof,
         TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
+        WriteSetListWithProof,
     },
     write_set::WriteSet,
 };
+use move_core_types::language_storage::TypeTag;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
@@ -42,6 +47,8 @@ use thiserror::Error;
 pub mod async_proof_fetcher;
 pub mod block_info;
 pub mod cached_state_view;
+pub mod commit_progress;
+pub mod consistency_check;
 pub mod errors;
 mod executed_trees;
 mod metrics;
@@ -194,6 +201,16 @@ pub trait DbReader: Send + Sync {
             ledger_version: Version,
         ) -> Result<TransactionOutputListWithProof>;
 
+        /// See [AptosDB::get_write_sets_with_proof].
+        ///
+        /// [AptosDB::get_write_sets_with_proof]: ../aptosdb/struct.AptosDB.html#method.get_write_sets_with_proof
+        fn get_write_sets_with_proof(
+            &self,
+            start_version: Version,
+            limit: u64,
+            ledger_version: Version,
+        ) -> Result<WriteSetListWithProof>;
+
         /// Returns events by given event key
         fn get_events(
             &self,
@@ -291,6 +308,14 @@ pub trait DbReader: Send + Sync {
         /// Returns the latest state checkpoint version if any.
         fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>>;
 
+        /// Returns the commit progress of each underlying database, together with the
+        /// truncations performed when syncing them on startup.
+        fn get_commit_progress(&self) -> Result<CommitProgressReport>;
+
+        /// Returns the report of the latest background consistency check of the state
+        /// store, if the consistency checker is enabled and has completed a check.
+        fn get_latest_consistency_check_report(&self) -> Result<Option<ConsistencyCheckReport>>;
+
         /// Returns the latest state snapshot strictly before `next_version` if any.
         fn get_state_snapshot_before(
             &self,
@@ -342,6 +367,18 @@ pub trait DbReader: Send + Sync {
             version: Version,
         ) -> Result<Option<StateValue>>;
 
+        /// Get the latest state values of the given keys up to the given version, in the order
+        /// of the keys.
+        /// See [AptosDB::get_state_values_by_version].
+        ///
+        /// [AptosDB::get_state_values_by_version]:
+        /// ../aptosdb/struct.AptosDB.html#method.get_state_values_by_version
+        fn get_state_values_by_version(
+            &self,
+            state_keys: &[StateKey],
+            version: Version,
+        ) -> Result<Vec<Option<StateValue>>>;
+
         /// Get the latest state value and its corresponding version when it's of the given key up
         /// to the given version.
         /// See [AptosDB::get_state_value_with_version_by_version].
@@ -432,6 +469,18 @@ pub trait DbReader: Send + Sync {
             chunk_size: usize,
         ) -> Result<StateValueChunkWithProof>;
 
+        /// Get a chunk of state store value, addressed by the hash of the first state key in the
+        /// chunk. The chunk starts at the smallest key hash that is greater or equal to
+        /// `start_key_hash`, so it is stable across versions even if leaves are added or removed
+        /// before it. If all the keys are smaller than `start_key_hash`, a
+        /// `RangeStartsPastEnd` error is returned.
+        fn get_state_value_chunk_by_key_range_with_proof(
+            &self,
+            version: Version,
+            start_key_hash: HashValue,
+            chunk_size: usize,
+        ) -> Result<StateValueChunkWithProof>;
+
         /// Returns if the state store pruner is enabled.
         fn is_state_merkle_pruner_enabled(&self) -> Result<bool>;
 
@@ -447,9 +496,28 @@ pub trait DbReader: Send + Sync {
         /// Get table info from the internal indexer.
         fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo>;
 
+        /// Returns up to `limit` events of type `type_tag`, emitted by transactions with versions
+        /// in `[start_version, ledger_version]`, in ascending order. Served from the internal
+        /// indexer.
+        fn get_events_by_type(
+            &self,
+            type_tag: &TypeTag,
+            start_version: Version,
+            limit: u64,
+            ledger_version: Version,
+        ) -> Result<Vec<EventWithVersion>>;
+
         /// Returns whether the internal indexer DB has been enabled or not
         fn indexer_enabled(&self) -> bool;
 
+        /// Returns the next version which the internal indexer DB should index, or None
+        /// if the internal indexer DB has not been enabled
+        fn get_indexer_next_version(&self) -> Result<Option<Version>>;
+
+        /// Returns the first version from which the internal indexer DB indexes events
+        /// by type, or None if the internal indexer DB has not been enabled
+        fn get_event_by_type_index_start_version(&self) -> Result<Option<Version>>;
+
         /// Returns whether the internal indexer async v2 DB has been enabled or not
         fn indexer_async_v2_enabled(&self) -> bool;
 
@@ -578,13 +646,14 @@ pub trait DbWriter: Send + Sync {
         unimplemented!()
     }
 
-    /// Index table info mapping for the indexer async v2 rocksdb.
+    /// Index table info mapping (and events by type) for the indexer async v2 rocksdb.
     /// Called by the table info service when its constantly parsing the table info.
     fn index_table_info(
         &self,
         db_reader: Arc<dyn DbReader>,
         first_version: Version,
         write_sets: &[&WriteSet],
+        events: &[&[ContractEvent]],
         end_early_if_pending_on_empty: bool,
     ) -> Result<()> {
         unimplemented!()
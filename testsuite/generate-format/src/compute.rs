@@ -2,9 +2,11 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use generate_format::Corpus;
-use std::{fs::File, io::Write};
+use serde_generate::{self as serdegen, SourceInstaller};
+use serde_reflection::Registry;
+use std::{fs::File, io::Write, path::PathBuf};
 
 #[derive(Debug, Parser)]
 #[clap(
@@ -17,11 +19,51 @@ struct Options {
 
     #[clap(long)]
     record: bool,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generate (de)serializers in the given language from the recorded formats
+    Codegen(CodegenOptions),
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Language {
+    Typescript,
+    Python,
+}
+
+#[derive(Debug, Parser)]
+struct CodegenOptions {
+    /// Corpus whose recorded formats are used (only API and Aptos are supported).
+    #[clap(long, value_enum, default_value_t = Corpus::Aptos, ignore_case = true)]
+    corpus: Corpus,
+
+    /// Language for code generation.
+    #[clap(long, value_enum, ignore_case = true)]
+    language: Language,
+
+    /// Directory where to install the generated module, along with the Serde and BCS
+    /// runtimes (otherwise print code on stdout).
+    #[clap(long)]
+    target_source_dir: Option<PathBuf>,
+
+    /// Optional package name of the Serde and BCS runtime dependencies (Python only).
+    #[clap(long)]
+    serde_package_name: Option<String>,
 }
 
 fn main() {
     let options = Options::parse();
 
+    if let Some(Command::Codegen(codegen_options)) = options.command {
+        codegen(codegen_options);
+        return;
+    }
+
     let registry = options.corpus.get_registry();
     let output_file = options.corpus.output_file();
 
@@ -39,6 +81,57 @@ fn main() {
     }
 }
 
+fn codegen(options: CodegenOptions) {
+    let (module_name, output_file) = match (
+        options.corpus.codegen_module_name(),
+        options.corpus.output_file(),
+    ) {
+        (Some(module_name), Some(output_file)) => (module_name, output_file),
+        _ => panic!(
+            "Corpus {:?} doesn't support code generation",
+            options.corpus
+        ),
+    };
+
+    // Generate the code from the recorded formats, i.e. the canonical source, rather
+    // than from whatever the current codebase traces to.
+    let content = std::fs::read_to_string("testsuite/generate-format/".to_string() + output_file)
+        .expect("recorded formats must be readable");
+    let registry = serde_yaml::from_str::<Registry>(content.as_str()).unwrap();
+    let config = serdegen::CodeGeneratorConfig::new(module_name.to_string())
+        .with_encodings(vec![serdegen::Encoding::Bcs]);
+
+    let install_dir = match options.target_source_dir {
+        None => {
+            // Nothing to install. Just print to stdout.
+            let stdout = std::io::stdout();
+            let mut out = stdout.lock();
+            match options.language {
+                Language::Typescript => serdegen::typescript::CodeGenerator::new(&config)
+                    .output(&mut out, &registry)
+                    .unwrap(),
+                Language::Python => serdegen::python3::CodeGenerator::new(&config)
+                    .output(&mut out, &registry)
+                    .unwrap(),
+            }
+            return;
+        },
+        Some(dir) => dir,
+    };
+
+    let installer: Box<dyn SourceInstaller<Error = Box<dyn std::error::Error>>> =
+        match options.language {
+            Language::Typescript => Box::new(serdegen::typescript::Installer::new(install_dir)),
+            Language::Python => Box::new(serdegen::python3::Installer::new(
+                install_dir,
+                options.serde_package_name,
+            )),
+        };
+    installer.install_serde_runtime().unwrap();
+    installer.install_bcs_runtime().unwrap();
+    installer.install_module(&config, &registry).unwrap();
+}
+
 #[test]
 fn verify_tool() {
     use clap::CommandFactory;
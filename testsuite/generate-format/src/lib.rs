@@ -21,6 +21,8 @@ mod linter;
 mod move_abi;
 /// Network messages.
 mod network;
+/// Storage service requests.
+mod storage_service;
 
 pub use linter::lint_bcs_format;
 
@@ -32,6 +34,7 @@ pub enum Corpus {
     Consensus,
     Network,
     MoveABI,
+    StorageService,
 }
 
 impl Corpus {
@@ -43,6 +46,7 @@ impl Corpus {
             Corpus::Consensus => consensus::get_registry(),
             Corpus::Network => network::get_registry(),
             Corpus::MoveABI => move_abi::get_registry(),
+            Corpus::StorageService => storage_service::get_registry(),
         };
         match result {
             Ok(registry) => registry,
@@ -60,6 +64,17 @@ impl Corpus {
             Corpus::Consensus => consensus::output_file(),
             Corpus::Network => network::output_file(),
             Corpus::MoveABI => move_abi::output_file(),
+            Corpus::StorageService => storage_service::output_file(),
+        }
+    }
+
+    /// Name of the module generated from the recorded formats, if this corpus
+    /// supports code generation.
+    pub fn codegen_module_name(self) -> Option<&'static str> {
+        match self {
+            Corpus::API => Some("api_types"),
+            Corpus::Aptos => Some("aptos_types"),
+            Corpus::Consensus | Corpus::Network | Corpus::MoveABI | Corpus::StorageService => None,
         }
     }
 }
@@ -72,6 +87,7 @@ impl Display for Corpus {
             Corpus::Consensus => "Consensus",
             Corpus::Network => "Network",
             Corpus::MoveABI => "MoveABI",
+            Corpus::StorageService => "StorageService",
         })
     }
 }
@@ -0,0 +1,32 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_storage_service_types::{
+    requests::{DataRequest, StorageServiceRequest, StorageServiceRequestEnvelope},
+    responses::{BlockVersionRange, InternalIndexerSummary, PruneWindowForecast},
+};
+use serde_reflection::{Registry, Result, Samples, Tracer, TracerConfig};
+
+/// Return a relative path to start tracking changes in commits.
+pub fn output_file() -> Option<&'static str> {
+    Some("tests/staged/storage_service.yaml")
+}
+
+/// Create a registry of storage service request (and response payload) formats.
+pub fn get_registry() -> Result<Registry> {
+    let mut tracer =
+        Tracer::new(TracerConfig::default().is_human_readable(bcs::is_human_readable()));
+    let samples = Samples::new();
+
+    // Trace the request entry points (both the legacy and enveloped formats)
+    tracer.trace_type::<StorageServiceRequest>(&samples)?;
+    tracer.trace_type::<StorageServiceRequestEnvelope>(&samples)?;
+    tracer.trace_type::<DataRequest>(&samples)?;
+
+    // Trace the response payloads that don't embed ledger data (e.g., proofs)
+    tracer.trace_type::<BlockVersionRange>(&samples)?;
+    tracer.trace_type::<PruneWindowForecast>(&samples)?;
+    tracer.trace_type::<InternalIndexerSummary>(&samples)?;
+
+    tracer.registry()
+}
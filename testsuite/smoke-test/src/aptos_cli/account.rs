@@ -2,9 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::smoke_test_environment::SwarmBuilder;
-use aptos::{account::create::DEFAULT_FUNDED_COINS, common::types::GasOptions};
+use aptos::{
+    account::create::DEFAULT_FUNDED_COINS, common::types::GasOptions, test::assert_txn_aborts_with,
+};
 use aptos_crypto::{PrivateKey, ValidCryptoMaterialStringExt};
 use aptos_keygen::KeyGen;
+use aptos_types::move_utils::MemberId;
+use serde_json::json;
+use std::str::FromStr;
 
 #[tokio::test]
 async fn test_account_flow() {
@@ -26,6 +31,17 @@ async fn test_account_flow() {
     let expected_sender_amount = DEFAULT_FUNDED_COINS - (response.octa_spent()) - transfer_amount;
     let expected_receiver_amount = DEFAULT_FUNDED_COINS + transfer_amount;
 
+    // Verify the transfer emitted the expected coin events
+    let events = cli
+        .transaction_events(response.transaction_hash)
+        .await
+        .unwrap();
+    events.assert_event_count("0x1::coin::WithdrawEvent", 1);
+    events.assert_event_count("0x1::coin::DepositEvent", 1);
+    let amount = json!(transfer_amount.to_string());
+    events.assert_event_field("0x1::coin::WithdrawEvent", "amount", amount.clone());
+    events.assert_event_field("0x1::coin::DepositEvent", "amount", amount);
+
     // transfer_coins already waits for transaction to be committed
     cli.assert_account_balance_now(0, expected_sender_amount)
         .await;
@@ -89,6 +105,20 @@ async fn test_account_flow() {
     // should be lower than it was before (<), unless the gas price is zero, in which
     // case it will be the same (hence <=).
     assert!(cli.account_balance_now(2).await.unwrap() <= new_expected_balance);
+
+    // Transferring more coins than the account holds aborts on-chain
+    // with 0x1::coin::EINSUFFICIENT_BALANCE (an invalid argument)
+    let receiver = format!("address:{}", cli.account_id(0));
+    let transaction = cli
+        .run_function_expecting_failure(
+            1,
+            MemberId::from_str("0x1::coin::transfer").unwrap(),
+            vec![&receiver, &format!("u64:{}", DEFAULT_FUNDED_COINS * 10)],
+            vec!["0x1::aptos_coin::AptosCoin"],
+        )
+        .await
+        .unwrap();
+    assert_txn_aborts_with(&transaction, 0x10006);
 }
 
 #[tokio::test]
@@ -0,0 +1,165 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::smoke_test_environment::SwarmBuilder;
+use aptos::test::{PROPOSAL_STATE_FAILED, PROPOSAL_STATE_SUCCEEDED};
+use aptos_forge::{NodeExt, Swarm};
+use aptos_temppath::TempPath;
+use aptos_types::account_config::CORE_CODE_ADDRESS;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+const NEW_EPOCH_INTERVAL_MICROSECS: u64 = 7_200_000_000;
+
+const PROPOSAL_METADATA: &str = r#"{
+    "title": "Update the epoch interval",
+    "description": "Sets the epoch interval to two hours",
+    "source_code_url": "https://github.com/cedra-network/cedra-core",
+    "discussion_url": "https://github.com/cedra-network/cedra-core"
+}"#;
+
+/// Serves the proposal metadata over HTTP on a local port and returns its URL, as the CLI
+/// fetches the metadata from the URL when submitting a proposal
+async fn serve_proposal_metadata() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/metadata.json", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request).await;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                PROPOSAL_METADATA.len(),
+                PROPOSAL_METADATA
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn test_governance_proposal_flow() {
+    let (swarm, mut cli, _faucet) = SwarmBuilder::new_local(2)
+        .with_aptos()
+        .with_init_genesis_config(Arc::new(|genesis_config| {
+            genesis_config.voting_duration_secs = 10;
+        }))
+        .build_with_cli(0)
+        .await;
+
+    let mut validators: Vec<_> = swarm.validators().collect();
+    validators.sort_by_key(|v| v.name());
+    // Genesis validators are their own owners, operators and delegated voters.
+    let voters = validators
+        .iter()
+        .map(|validator| {
+            let index = cli.add_account_to_cli(
+                validator
+                    .account_private_key()
+                    .as_ref()
+                    .unwrap()
+                    .private_key(),
+            );
+            (index, validator.peer_id())
+        })
+        .collect::<Vec<_>>();
+    let (proposer_index, proposer_pool) = voters[0];
+
+    let script_dir = TempPath::new();
+    script_dir.create_as_dir().unwrap();
+    let script_path = script_dir.path().join("proposal.move");
+    std::fs::write(
+        &script_path,
+        format!(
+            r#"
+    script {{
+        use aptos_framework::aptos_governance;
+        use aptos_framework::block;
+        fun main(proposal_id: u64) {{
+            let framework_signer = aptos_governance::resolve(proposal_id, @aptos_framework);
+            block::update_epoch_interval_microsecs(&framework_signer, {});
+        }}
+    }}
+    "#,
+            NEW_EPOCH_INTERVAL_MICROSECS
+        ),
+    )
+    .unwrap();
+
+    let metadata_url = serve_proposal_metadata().await;
+    let approved_proposal_id = cli
+        .create_proposal(
+            proposer_index,
+            &metadata_url,
+            script_path.clone(),
+            proposer_pool,
+            false,
+        )
+        .await
+        .unwrap()
+        .proposal_id
+        .unwrap();
+    let rejected_proposal_id = cli
+        .create_proposal(
+            proposer_index,
+            &metadata_url,
+            script_path.clone(),
+            proposer_pool,
+            false,
+        )
+        .await
+        .unwrap()
+        .proposal_id
+        .unwrap();
+
+    // The rejected proposal can't be executed, and closes as failed.
+    cli.vote_with_stake_pools(rejected_proposal_id, false, &voters)
+        .await
+        .unwrap();
+    cli.execute_proposal(proposer_index, rejected_proposal_id, script_path.clone())
+        .await
+        .unwrap_err();
+
+    cli.approve_and_execute_proposal(
+        proposer_index,
+        approved_proposal_id,
+        script_path.clone(),
+        &voters,
+        Duration::from_secs(60),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        cli.proposal_state(approved_proposal_id).await.unwrap(),
+        PROPOSAL_STATE_SUCCEEDED
+    );
+
+    assert_eq!(
+        cli.wait_for_proposal_voting_closed(rejected_proposal_id, Duration::from_secs(60))
+            .await
+            .unwrap(),
+        PROPOSAL_STATE_FAILED
+    );
+    assert!(!cli
+        .is_proposal_resolved(rejected_proposal_id)
+        .await
+        .unwrap());
+
+    // The approved proposal script ran with the framework signer.
+    let block_resource = validators[0]
+        .rest_client()
+        .get_account_resource(CORE_CODE_ADDRESS, "0x1::block::BlockResource")
+        .await
+        .unwrap()
+        .into_inner()
+        .unwrap();
+    assert_eq!(
+        block_resource.data["epoch_interval"],
+        NEW_EPOCH_INTERVAL_MICROSECS.to_string()
+    );
+}
@@ -84,6 +84,48 @@ async fn test_move_compile_flow() {
     }
 }
 
+#[tokio::test]
+async fn test_move_coverage_and_prove_flow() {
+    let mut cli = CliTestFramework::local_new(1);
+
+    cli.init_move_dir();
+    let account = cli.account_id(0).to_hex_literal();
+
+    let mut package_addresses = BTreeMap::new();
+    package_addresses.insert(HELLO_BLOCKCHAIN, "_");
+    cli.init_package(
+        PACKAGE_NAME.to_string(),
+        package_addresses,
+        Some(aptos_framework_dir()),
+    )
+    .await
+    .expect("Should succeed");
+    cli.add_move_files();
+
+    let mut named_addresses = BTreeMap::new();
+    named_addresses.insert(HELLO_BLOCKCHAIN, account.as_str());
+
+    // The unit tests of hello_blockchain exercise the message module
+    let coverage = cli
+        .coverage_package(named_addresses.clone(), None)
+        .await
+        .expect("Coverage should succeed");
+    let message_coverage = coverage
+        .module_coverage("message")
+        .expect("Expected coverage of the message module");
+    assert!(message_coverage > 0.0, "coverage: {:?}", coverage);
+
+    // The prover can only run if its tools are installed
+    if !CliTestFramework::prover_tools_configured() {
+        info!("Skipping the prover, as its tools are not configured");
+        return;
+    }
+    match cli.prove_package(named_addresses, None).await {
+        Ok(result) => assert_eq!("Success", result),
+        Err(err) => panic!("Error proving: {:?}", err),
+    }
+}
+
 #[tokio::test]
 async fn test_move_publish_flow() {
     let (_swarm, mut cli, _faucet) = SwarmBuilder::new_local(1)
@@ -12,7 +12,7 @@ use aptos::{
         analyze_validators::{AnalyzeValidators, EpochStats},
         fetch_metadata::FetchMetadata,
     },
-    test::{CliTestFramework, ValidatorPerformance},
+    test::{AccountEventStream, CliTestFramework, ValidatorPerformance},
 };
 use aptos_bitvec::BitVec;
 use aptos_cached_packages::aptos_stdlib;
@@ -1093,11 +1093,26 @@ async fn test_join_and_leave_validator() {
         .await;
 
     let stake_coins = 7;
-    gas_used += get_gas(
-        cli.add_stake(validator_cli_index, stake_coins)
-            .await
-            .unwrap()[0]
-            .clone(),
+    let add_stake_summary = cli
+        .add_stake(validator_cli_index, stake_coins)
+        .await
+        .unwrap()[0]
+        .clone();
+    gas_used += get_gas(add_stake_summary.clone());
+
+    // Verify the stake pool emitted the add stake event
+    let add_stake_event = cli
+        .wait_for_event(
+            cli.account_id(validator_cli_index),
+            &AccountEventStream::handle("0x1::stake::StakePool", "add_stake_events"),
+            add_stake_summary.transaction_hash,
+            "0x1::stake::AddStakeEvent",
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        add_stake_event.data["amount_added"],
+        stake_coins.to_string()
     );
 
     cli.assert_account_balance_now(
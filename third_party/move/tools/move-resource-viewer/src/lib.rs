@@ -24,6 +24,7 @@ use move_core_types::{
 };
 use serde::ser::{SerializeMap, SerializeSeq};
 use std::{
+    collections::{btree_map::Entry, BTreeMap},
     convert::{TryFrom, TryInto},
     fmt::{Display, Formatter},
     rc::Rc,
@@ -185,6 +186,23 @@ impl<'a, T: ModuleResolver + ?Sized> MoveValueAnnotator<'a, T> {
         self.view_value_by_fat_type(&ty, blob)
     }
 
+    /// Annotates a batch of values, resolving each distinct type only once.
+    pub fn view_values<'b>(
+        &self,
+        values: impl IntoIterator<Item = (&'b TypeTag, &'b [u8])>,
+    ) -> Result<Vec<AnnotatedMoveValue>> {
+        let mut resolved_types: BTreeMap<&TypeTag, FatType> = BTreeMap::new();
+        let mut output = vec![];
+        for (ty_tag, blob) in values {
+            let ty = match resolved_types.entry(ty_tag) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(self.cache.resolve_type(ty_tag)?),
+            };
+            output.push(self.view_value_by_fat_type(ty, blob)?);
+        }
+        Ok(output)
+    }
+
     fn view_value_by_fat_type(&self, ty: &FatType, blob: &[u8]) -> Result<AnnotatedMoveValue> {
         let layout = ty.try_into().map_err(into_vm_status)?;
         let move_value = MoveValue::simple_deserialize(blob, &layout)?;
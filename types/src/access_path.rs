@@ -148,6 +148,12 @@ impl AccessPath {
         matches!(self.get_path(), Path::Code(_))
     }
 
+    /// Same as `is_code`, but only looks at the variant tag of the serialized
+    /// path (`Path::Code` is the first variant), without deserializing it.
+    pub fn is_code_path(&self) -> bool {
+        self.path.first() == Some(&0)
+    }
+
     pub fn is_resource_group(&self) -> bool {
         matches!(self.get_path(), Path::ResourceGroup(_))
     }
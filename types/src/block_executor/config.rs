@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug)]
 pub struct BlockExecutorLocalConfig {
     pub concurrency_level: usize,
+    /// Whether to record per-key contention stats during parallel execution, and export
+    /// them as metrics after each block.
+    pub record_contention_stats: bool,
 }
 
 /// Configuration from on-chain configuration, that is
@@ -62,7 +65,10 @@ pub struct BlockExecutorConfig {
 impl BlockExecutorConfig {
     pub fn new_no_block_limit(concurrency_level: usize) -> Self {
         Self {
-            local: BlockExecutorLocalConfig { concurrency_level },
+            local: BlockExecutorLocalConfig {
+                concurrency_level,
+                record_contention_stats: false,
+            },
             onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
         }
     }
@@ -72,7 +78,10 @@ impl BlockExecutorConfig {
         maybe_block_gas_limit: Option<u64>,
     ) -> Self {
         Self {
-            local: BlockExecutorLocalConfig { concurrency_level },
+            local: BlockExecutorLocalConfig {
+                concurrency_level,
+                record_contention_stats: false,
+            },
             onchain: BlockExecutorConfigFromOnchain::new_maybe_block_limit(maybe_block_gas_limit),
         }
     }
@@ -19,6 +19,12 @@ pub enum ExecutableDescriptor {
 
 pub trait ModulePath {
     fn module_path(&self) -> Option<AccessPath>;
+
+    /// Whether the key is a module path. Implementations should make this
+    /// cheap, as it is checked on every write to the multi-version data structures.
+    fn is_module_path(&self) -> bool {
+        self.module_path().is_some()
+    }
 }
 
 impl ModulePath for StateKey {
@@ -30,6 +36,10 @@ impl ModulePath for StateKey {
         }
         None
     }
+
+    fn is_module_path(&self) -> bool {
+        matches!(self.inner(), StateKeyInner::AccessPath(ap) if ap.is_code_path())
+    }
 }
 
 /// For now we will handle the VM code cache / arena memory consumption on the
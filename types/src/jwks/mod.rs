@@ -1,6 +1,11 @@
 // Copyright © Aptos Foundation
 
 use jwk::JWKMoveStruct;
+use move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    move_resource::{MoveResource, MoveStructType},
+};
 use serde::{Deserialize, Serialize};
 
 pub mod jwk;
@@ -14,16 +19,25 @@ pub fn issuer_from_str(s: &str) -> Issuer {
 }
 
 /// Move type `0x1::jwks::OIDCProvider` in rust.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct OIDCProvider {
     pub name: Issuer,
     pub config_url: Vec<u8>,
 }
 
 /// Move type `0x1::jwks::SupportedOIDCProviders` in rust.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct SupportedOIDCProviders {
     pub providers: Vec<OIDCProvider>,
 }
 
+impl MoveStructType for SupportedOIDCProviders {
+    const MODULE_NAME: &'static IdentStr = ident_str!("jwks");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("SupportedOIDCProviders");
+}
+
+impl MoveResource for SupportedOIDCProviders {}
+
 /// Move type `0x1::jwks::ProviderJWKs` in rust.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct ProviderJWKs {
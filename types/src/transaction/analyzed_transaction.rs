@@ -69,6 +69,16 @@ impl StorageLocation {
 impl AnalyzedTransaction {
     pub fn new(transaction: SignatureVerifiedTransaction) -> Self {
         let (read_hints, write_hints) = transaction.get_read_write_hints();
+        Self::new_with_hints(transaction, read_hints, write_hints)
+    }
+
+    /// Creates an analyzed transaction with the given read and write hints (instead of
+    /// analyzing the transaction), e.g., for transactions that can't be analyzed yet.
+    pub fn new_with_hints(
+        transaction: SignatureVerifiedTransaction,
+        read_hints: Vec<StorageLocation>,
+        write_hints: Vec<StorageLocation>,
+    ) -> Self {
         let hints_contain_wildcard = read_hints
             .iter()
             .chain(write_hints.iter())
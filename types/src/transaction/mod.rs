@@ -1731,6 +1731,88 @@ impl TransactionOutputListWithProof {
     }
 }
 
+/// A list of write sets (one per transaction, starting at `first_write_set_version`)
+/// with a proof of the corresponding transaction infos. This allows the write sets
+/// to be verified independently of the transactions that produced them.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct WriteSetListWithProof {
+    pub write_sets: Vec<WriteSet>,
+    pub first_write_set_version: Option<Version>,
+    pub proof: TransactionInfoListWithProof,
+}
+
+impl WriteSetListWithProof {
+    pub fn new(
+        write_sets: Vec<WriteSet>,
+        first_write_set_version: Option<Version>,
+        proof: TransactionInfoListWithProof,
+    ) -> Self {
+        Self {
+            write_sets,
+            first_write_set_version,
+            proof,
+        }
+    }
+
+    /// A convenience function to create an empty proof. Mostly used for tests.
+    pub fn new_empty() -> Self {
+        Self::new(vec![], None, TransactionInfoListWithProof::new_empty())
+    }
+
+    /// Verifies the write set list with proof using the given `ledger_info`.
+    /// This method will ensure:
+    /// 1. All transaction infos exist on the given `ledger_info`.
+    /// 2. If `first_write_set_version` is None, the write set list is empty.
+    ///    Otherwise, the list starts at `first_write_set_version`.
+    /// 3. The hash of each write set matches the state change hash in the
+    ///    corresponding transaction info.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        first_write_set_version: Option<Version>,
+    ) -> Result<()> {
+        // Verify the first write set versions match
+        ensure!(
+            self.first_write_set_version == first_write_set_version,
+            "First write set version ({:?}) doesn't match given version ({:?}).",
+            self.first_write_set_version,
+            first_write_set_version,
+        );
+
+        // Verify the lengths of the write sets and transaction infos match
+        ensure!(
+            self.proof.transaction_infos.len() == self.write_sets.len(),
+            "The number of TransactionInfo objects ({}) does not match the number of \
+             write sets ({}).",
+            self.proof.transaction_infos.len(),
+            self.write_sets.len(),
+        );
+
+        // Verify the write sets against the transaction infos
+        self.write_sets
+            .par_iter()
+            .zip_eq(self.proof.transaction_infos.par_iter())
+            .map(|(write_set, txn_info)| {
+                let write_set_hash = CryptoHash::hash(write_set);
+                ensure!(
+                    txn_info.state_change_hash() == write_set_hash,
+                    "The write set does not match the transaction info in proof. \
+                     Hash of write set: {}. Write set hash in txn_info: {}.",
+                    write_set_hash,
+                    txn_info.state_change_hash(),
+                );
+                Ok(())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Verify the transaction infos are proven by the ledger info.
+        self.proof
+            .verify(ledger_info, self.first_write_set_version)?;
+
+        Ok(())
+    }
+}
+
 /// Verifies a list of events against an expected event root hash. This is done
 /// by calculating the hash of the events using an event accumulator hasher.
 fn verify_events_against_root_hash(